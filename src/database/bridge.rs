@@ -30,6 +30,7 @@ use libduckdb_sys::duckdb_get_uint8;
 use libduckdb_sys::duckdb_get_value_type;
 use libduckdb_sys::duckdb_get_varchar;
 use libduckdb_sys::duckdb_interval;
+use libduckdb_sys::duckdb_is_null_value;
 use libduckdb_sys::duckdb_logical_type;
 use libduckdb_sys::duckdb_struct_type_child_count;
 use libduckdb_sys::duckdb_struct_type_child_name;
@@ -52,6 +53,11 @@ pub(crate) trait ValueBridge {
     /// about the internal memory layout of DuckDB values
     fn get_value_ptr(&self) -> duckdb_value;
 
+    /// Checks whether the value is NULL
+    fn is_null(&self) -> bool {
+        unsafe { duckdb_is_null_value(self.get_value_ptr()) }
+    }
+
     /// Converts the value to a boolean
     fn to_bool(&self) -> bool {
         unsafe { duckdb_get_bool(self.get_value_ptr()) }
@@ -1,6 +1,12 @@
+use crate::database::decimal::MAX_DECIMAL_WIDTH;
 use crate::error::RustySheetError;
+use crate::spreadsheet::cell::parse_duration;
 use crate::spreadsheet::cell::CellType;
+use crate::spreadsheet::criteria::Criteria;
+use duckdb::core::LogicalTypeHandle;
 use duckdb::core::LogicalTypeId;
+use std::fmt::Display;
+use std::fmt::Formatter;
 use thiserror::Error;
 
 /// Errors related to column type parsing and validation.
@@ -12,7 +18,7 @@ pub(crate) enum ColumnError {
 
 /// Supported column data types for spreadsheet data.
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub(crate) enum ColumnType {
+pub enum ColumnType {
     /// Boolean values (true/false)
     Boolean,
     /// 64-bit signed integers
@@ -27,6 +33,15 @@ pub(crate) enum ColumnType {
     Date,
     /// Time without date component
     Time,
+    /// Duration in months, days and microseconds
+    Interval,
+    /// Fixed-point numbers with (width, scale)
+    Decimal(u8, u8),
+}
+
+/// Checks if a floating point number is integral and within the BIGINT range.
+pub(crate) fn is_bigint(number: f64) -> bool {
+    number.fract() == 0f64 && (i64::MIN as f64..i64::MAX as f64).contains(&number)
 }
 
 /// Represents a column in a spreadsheet table with name and data type.
@@ -49,13 +64,25 @@ impl ColumnType {
             ColumnType::Timestamp => "timestamp",
             ColumnType::Date => "date",
             ColumnType::Time => "time",
+            ColumnType::Interval => "interval",
+            ColumnType::Decimal(_, _) => "decimal",
         }
     }
 
     /// Parses a column type from a string representation.
     /// Supports various aliases for each type.
+    /// DECIMAL and NUMERIC read as DOUBLE unless a width and scale are given.
     pub(crate) fn parse(name: &str) -> Result<Self, RustySheetError> {
-        match name.to_ascii_uppercase().as_str() {
+        let upper = name.to_ascii_uppercase();
+        if let Some(arguments) = upper.strip_prefix("DECIMAL(").or_else(|| upper.strip_prefix("NUMERIC(")) {
+            let (width, scale) = arguments.strip_suffix(')')
+                .map(|arguments| arguments.split_once(',').unwrap_or((arguments, "0")))
+                .and_then(|(width, scale)| Some((width.trim().parse::<u8>().ok()?, scale.trim().parse::<u8>().ok()?)))
+                .filter(|(width, scale)| (1..=MAX_DECIMAL_WIDTH).contains(width) && scale <= width)
+                .ok_or_else(|| ColumnError::TypeError(name.to_string()))?;
+            return Ok(Self::Decimal(width, scale));
+        }
+        match upper.as_str() {
             "BOOL" | "BOOLEAN" => Ok(Self::Boolean),
             "INT" | "BIGINT" | "INTEGER" => Ok(Self::BigInt),
             "FLOAT" | "DOUBLE" | "DECIMAL" | "NUMERIC" => Ok(Self::Double),
@@ -63,13 +90,20 @@ impl ColumnType {
             "DATETIME" | "TIMESTAMP" => Ok(Self::Timestamp),
             "DATE" => Ok(Self::Date),
             "TIME" => Ok(Self::Time),
+            "INTERVAL" => Ok(Self::Interval),
             _ => Err(ColumnError::TypeError(name.to_string()))?,
         }
     }
 
     /// Infers column type from cell type and value.
     /// Handles various Excel date/time formats and numeric representations.
-    pub(crate) fn from(cell_type: &CellType, value: &str) -> Option<Self> {
+    ///
+    /// ISO date/time values are classified by their declared representation:
+    /// a value with a time part is a timestamp, otherwise a date. The
+    /// `midnight_as_date` and `epoch_date_as_time` criteria opt into the
+    /// looser heuristics for files that encode dates or times as datetimes.
+    /// With `duration_as_interval`, text durations like `2 05:30:00` are intervals.
+    pub(crate) fn from(cell_type: &CellType, value: &str, criteria: &Criteria) -> Option<Self> {
         match cell_type {
             CellType::Boolean => Some(ColumnType::Boolean),
             CellType::Number if Self::is_integer(value) => Some(ColumnType::BigInt),
@@ -77,17 +111,29 @@ impl ColumnType {
             CellType::NumberDateTime1900 | CellType::NumberDateTime1904 => Some(ColumnType::Timestamp),
             CellType::NumberDate1900 | CellType::NumberDate1904 => Some(ColumnType::Date),
             CellType::NumberTime1900 | CellType::NumberTime1904 => Some(ColumnType::Time),
-            CellType::IsoDateTime if value.contains("1900-01-01") => Some(ColumnType::Time),
-            CellType::IsoDateTime if value.contains("1904-01-01") => Some(ColumnType::Time),
-            CellType::IsoDateTime if value.contains("00:00:00") => Some(ColumnType::Date),
-            CellType::IsoDateTime if !value.contains("T") => Some(ColumnType::Date),
-            CellType::IsoDateTime => Some(ColumnType::Timestamp),
+            CellType::IsoDateTime => Some(Self::from_iso_datetime(value, criteria)),
             CellType::IsoDuration => Some(ColumnType::Time),
+            CellType::InlineString if criteria.duration_as_interval && parse_duration(value).is_some() => Some(ColumnType::Interval),
             CellType::InlineString | CellType::SharedString => Some(ColumnType::Varchar),
             _ => None,
         }
     }
 
+    /// Classifies an ISO 8601 date/time value as a timestamp, date or time.
+    fn from_iso_datetime(value: &str, criteria: &Criteria) -> Self {
+        if let Some((date, time)) = value.split_once('T') {
+            if criteria.epoch_date_as_time && (date == "1900-01-01" || date == "1904-01-01") {
+                ColumnType::Time
+            } else if criteria.midnight_as_date && time.chars().all(|char| char == '0' || char == ':' || char == '.') {
+                ColumnType::Date
+            } else {
+                ColumnType::Timestamp
+            }
+        } else {
+            ColumnType::Date
+        }
+    }
+
     /// Converts column type to DuckDB's logical type ID.
     pub(crate) const fn to_logical_type_id(&self) -> LogicalTypeId {
         match self {
@@ -98,21 +144,34 @@ impl ColumnType {
             Self::Timestamp => LogicalTypeId::Timestamp,
             Self::Date => LogicalTypeId::Date,
             Self::Time => LogicalTypeId::Time,
+            Self::Interval => LogicalTypeId::Interval,
+            Self::Decimal(_, _) => LogicalTypeId::Decimal,
+        }
+    }
+
+    /// Converts column type to DuckDB's logical type, including the width and scale of decimals.
+    pub(crate) fn to_logical_type(&self) -> LogicalTypeHandle {
+        match self {
+            Self::Decimal(width, scale) => LogicalTypeHandle::decimal(*width, *scale),
+            _ => LogicalTypeHandle::from(self.to_logical_type_id()),
         }
     }
 
-    /// Checks if a numeric string represents an integer value.
-    /// Returns true if the decimal part contains only zeros or no decimal point.
+    /// Checks if a numeric string represents an integer value fitting in a BIGINT.
+    /// Returns true if the decimal part contains only zeros or no decimal point;
+    /// values in exponent notation (e.g. `1e+05`) are checked on their parsed value.
     fn is_integer(value: &str) -> bool {
-        if let Some(index) = value.find('.') {
+        if value.contains(['e', 'E']) {
+            value.parse::<f64>().is_ok_and(is_bigint)
+        } else if let Some(index) = value.find('.') {
             for char in value[(index+1)..].chars() {
                 if char != '0' {
                     return false;
                 }
             }
-            true
+            value[..index].parse::<i64>().is_ok()
         } else {
-            true
+            value.parse::<i64>().is_ok()
         }
     }
 
@@ -134,6 +193,8 @@ impl ColumnType {
             ColumnType::Time
         } else if types.iter().all(|kind| kind.is_datetime()) {
             ColumnType::Timestamp
+        } else if types.iter().all(|kind| kind.is_interval()) {
+            ColumnType::Interval
         } else {
             ColumnType::Varchar
         }
@@ -184,6 +245,15 @@ impl ColumnType {
         }
     }
 
+    /// Returns true if this column type represents durations.
+    #[inline]
+    pub(crate) fn is_interval(&self) -> bool {
+        match self {
+            ColumnType::Interval => true,
+            _ => false,
+        }
+    }
+
     /// Returns true if this column type represents date/time related values.
     #[inline]
     pub(crate) fn is_datetime(&self) -> bool {
@@ -193,3 +263,85 @@ impl ColumnType {
         }
     }
 }
+
+impl Display for ColumnType {
+    /// Formats the column type as DuckDB SQL, e.g. `double` or `decimal(18,2)`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnType::Decimal(width, scale) => write!(f, "decimal({width},{scale})"),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::column::ColumnType;
+    use crate::spreadsheet::cell::CellType;
+    use crate::spreadsheet::criteria::Criteria;
+
+    #[test]
+    fn iso_datetime_default() {
+        let criteria = Criteria::default();
+        let kind = |value| ColumnType::from(&CellType::IsoDateTime, value, &criteria);
+
+        assert_eq!(kind("2024-03-01"), Some(ColumnType::Date));
+        assert_eq!(kind("2024-03-01T00:00:00"), Some(ColumnType::Timestamp));
+        assert_eq!(kind("2024-03-01T12:30:00"), Some(ColumnType::Timestamp));
+        assert_eq!(kind("1899-12-30T12:30:00"), Some(ColumnType::Timestamp));
+        assert_eq!(kind("1900-01-01T12:30:00"), Some(ColumnType::Timestamp));
+    }
+
+    #[test]
+    fn iso_datetime_heuristics() {
+        let criteria = Criteria { midnight_as_date: true, epoch_date_as_time: true, ..Default::default() };
+        let kind = |value| ColumnType::from(&CellType::IsoDateTime, value, &criteria);
+
+        assert_eq!(kind("2024-03-01"), Some(ColumnType::Date));
+        assert_eq!(kind("2024-03-01T00:00:00"), Some(ColumnType::Date));
+        assert_eq!(kind("2024-03-01T00:00:00.000"), Some(ColumnType::Date));
+        assert_eq!(kind("2024-03-01T12:30:00"), Some(ColumnType::Timestamp));
+        assert_eq!(kind("1900-01-01T12:30:00"), Some(ColumnType::Time));
+        assert_eq!(kind("1904-01-01T00:00:00"), Some(ColumnType::Time));
+    }
+
+    #[test]
+    fn number_integer_detection() {
+        let criteria = Criteria::default();
+        let kind = |value| ColumnType::from(&CellType::Number, value, &criteria);
+
+        assert_eq!(kind("100000"), Some(ColumnType::BigInt));
+        assert_eq!(kind("100000.00"), Some(ColumnType::BigInt));
+        assert_eq!(kind("1e+05"), Some(ColumnType::BigInt));
+        assert_eq!(kind("1.5E5"), Some(ColumnType::BigInt));
+        assert_eq!(kind("1e-05"), Some(ColumnType::Double));
+        assert_eq!(kind("1.25"), Some(ColumnType::Double));
+        assert_eq!(kind("1e+20"), Some(ColumnType::Double));
+        assert_eq!(kind("100000000000000000000"), Some(ColumnType::Double));
+    }
+
+    #[test]
+    fn duration_interval_detection() {
+        let mut criteria = Criteria::default();
+        assert_eq!(ColumnType::from(&CellType::InlineString, "2 05:30:00", &criteria), Some(ColumnType::Varchar));
+        criteria.duration_as_interval = true;
+        let kind = |value| ColumnType::from(&CellType::InlineString, value, &criteria);
+        assert_eq!(kind("2 05:30:00"), Some(ColumnType::Interval));
+        assert_eq!(kind("05:30"), Some(ColumnType::Interval));
+        assert_eq!(kind("2 days"), Some(ColumnType::Varchar));
+        assert_eq!(ColumnType::detect(vec![kind("2 05:30:00"), None, kind("0 00:15:00")]), ColumnType::Interval);
+        assert_eq!(ColumnType::detect(vec![kind("2 05:30:00"), kind("n/a")]), ColumnType::Varchar);
+        assert_eq!(ColumnType::parse("interval").unwrap(), ColumnType::Interval);
+    }
+
+    #[test]
+    fn decimal_type() {
+        assert_eq!(ColumnType::parse("decimal(18, 2)").unwrap(), ColumnType::Decimal(18, 2));
+        assert_eq!(ColumnType::parse("NUMERIC(10)").unwrap(), ColumnType::Decimal(10, 0));
+        assert_eq!(ColumnType::parse("DECIMAL").unwrap(), ColumnType::Double);
+        assert!(ColumnType::parse("DECIMAL(39,2)").is_err());
+        assert!(ColumnType::parse("DECIMAL(4,6)").is_err());
+        assert!(ColumnType::parse("DECIMAL(18,2").is_err());
+        assert_eq!(ColumnType::Decimal(18, 2).to_string(), "decimal(18,2)");
+    }
+}
@@ -0,0 +1,200 @@
+//! Exact conversion of numeric cell text to DuckDB DECIMAL values.
+//!
+//! Spreadsheets store numbers as the shortest decimal text that round-trips the
+//! binary double (e.g. `2.675`), so rounding that text instead of the double
+//! gives the result users see in the sheet (`2.68`, not `2.67`).
+
+use crate::error::RustySheetError;
+use thiserror::Error;
+
+/// Widest DECIMAL supported by DuckDB
+pub(crate) const MAX_DECIMAL_WIDTH: u8 = 38;
+
+/// Errors related to decimal conversion policies.
+#[derive(Error, Debug)]
+pub(crate) enum DecimalError {
+    #[error("Unsupported decimal rounding '{0}', expected 'half_even' or 'half_up'")]
+    RoundingError(String),
+
+    #[error("Unsupported decimal scale overflow '{0}', expected 'round', 'truncate' or 'error'")]
+    ScaleOverflowError(String),
+}
+
+/// Rounding applied to the digits beyond the scale of a DECIMAL column.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(crate) enum DecimalRounding {
+    /// Ties round to the even neighbour (banker's rounding)
+    #[default]
+    HalfEven,
+    /// Ties round away from zero
+    HalfUp,
+}
+
+impl DecimalRounding {
+    /// Parses a rounding mode name ('half_even' or 'half_up').
+    pub(crate) fn parse(name: &str) -> Result<Self, RustySheetError> {
+        match name.to_ascii_lowercase().as_str() {
+            "half_even" => Ok(Self::HalfEven),
+            "half_up" => Ok(Self::HalfUp),
+            _ => Err(DecimalError::RoundingError(name.to_string()))?,
+        }
+    }
+}
+
+/// Handling of values with more fractional digits than the scale of a DECIMAL column.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(crate) enum ScaleOverflow {
+    /// Round the extra digits with the decimal rounding mode
+    #[default]
+    Round,
+    /// Drop the extra digits
+    Truncate,
+    /// Fail the conversion
+    Error,
+}
+
+impl ScaleOverflow {
+    /// Parses a scale overflow policy name ('round', 'truncate' or 'error').
+    pub(crate) fn parse(name: &str) -> Result<Self, RustySheetError> {
+        match name.to_ascii_lowercase().as_str() {
+            "round" => Ok(Self::Round),
+            "truncate" => Ok(Self::Truncate),
+            "error" => Ok(Self::Error),
+            _ => Err(DecimalError::ScaleOverflowError(name.to_string()))?,
+        }
+    }
+}
+
+/// Conversion policy for DECIMAL columns.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(crate) struct DecimalPolicy {
+    pub(crate) rounding: DecimalRounding,
+    pub(crate) scale_overflow: ScaleOverflow,
+}
+
+/// Converts numeric text (optionally signed, with fraction and exponent) to the
+/// unscaled integer of a DECIMAL(width, scale) value.
+pub(crate) fn to_decimal(text: &str, width: u8, scale: u8, policy: &DecimalPolicy) -> Result<i128, String> {
+    let failure = || format!("parse '{text}' to decimal({width},{scale}) failed");
+    let value = text.trim();
+    let (negative, value) = match value.as_bytes().first() {
+        Some(b'-') => (true, &value[1..]),
+        Some(b'+') => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let (mantissa, exponent) = match value.find(['e', 'E']) {
+        Some(index) => (&value[..index], value[index + 1..].parse::<i64>().map_err(|_| failure())?),
+        None => (value, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if integer.is_empty() && fraction.is_empty()
+        || !integer.bytes().chain(fraction.bytes()).all(|byte| byte.is_ascii_digit()) {
+        return Err(failure());
+    }
+
+    // value = digits * 10^(exponent - fraction length); the result is value * 10^scale
+    let digits = integer.bytes().chain(fraction.bytes()).map(|byte| byte - b'0').collect::<Vec<_>>();
+    let shift = exponent.saturating_sub(fraction.len() as i64).saturating_add(scale as i64);
+    let (kept, dropped) = if shift >= 0 {
+        (&digits[..], &[][..])
+    } else {
+        digits.split_at(digits.len().saturating_sub(shift.unsigned_abs() as usize))
+    };
+
+    let overflow = || format!("'{text}' exceeds decimal({width},{scale})");
+    let limit = 10i128.pow(width as u32);
+    let mut unscaled = 0i128;
+    for digit in kept {
+        unscaled = unscaled.checked_mul(10)
+            .and_then(|unscaled| unscaled.checked_add(*digit as i128))
+            .filter(|unscaled| *unscaled < limit)
+            .ok_or_else(overflow)?;
+    }
+    for _ in 0..shift.max(0) {
+        if unscaled == 0 {
+            break;
+        }
+        unscaled = unscaled.checked_mul(10).filter(|unscaled| *unscaled < limit).ok_or_else(overflow)?;
+    }
+
+    if dropped.iter().any(|digit| *digit != 0) {
+        let round_up = match policy.scale_overflow {
+            ScaleOverflow::Error => return Err(format!("'{text}' has more than {scale} fractional digits")),
+            ScaleOverflow::Truncate => false,
+            ScaleOverflow::Round => {
+                // Exponents may shift every digit past the scale behind implied leading zeros
+                let padded = shift.unsigned_abs() as usize > digits.len();
+                let first = if padded { 0 } else { dropped[0] };
+                let tie = !padded && first == 5 && dropped[1..].iter().all(|digit| *digit == 0);
+                match policy.rounding {
+                    DecimalRounding::HalfUp => first >= 5,
+                    DecimalRounding::HalfEven => first > 5 || (first == 5 && !tie) || (tie && unscaled % 2 == 1),
+                }
+            }
+        };
+        if round_up {
+            unscaled += 1;
+            if unscaled >= limit {
+                return Err(overflow());
+            }
+        }
+    }
+
+    Ok(if negative { -unscaled } else { unscaled })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(rounding: DecimalRounding, scale_overflow: ScaleOverflow) -> DecimalPolicy {
+        DecimalPolicy { rounding, scale_overflow }
+    }
+
+    #[test]
+    fn decimal_rounding() {
+        let half_even = policy(DecimalRounding::HalfEven, ScaleOverflow::Round);
+        let half_up = policy(DecimalRounding::HalfUp, ScaleOverflow::Round);
+        assert_eq!(to_decimal("2.675", 10, 2, &half_even), Ok(268));
+        assert_eq!(to_decimal("2.665", 10, 2, &half_even), Ok(266));
+        assert_eq!(to_decimal("2.665", 10, 2, &half_up), Ok(267));
+        assert_eq!(to_decimal("2.6651", 10, 2, &half_even), Ok(267));
+        assert_eq!(to_decimal("-0.125", 10, 2, &half_even), Ok(-12));
+        assert_eq!(to_decimal("-0.125", 10, 2, &half_up), Ok(-13));
+        assert_eq!(to_decimal("0.5", 10, 0, &half_even), Ok(0));
+        assert_eq!(to_decimal("1.5", 10, 0, &half_even), Ok(2));
+    }
+
+    #[test]
+    fn decimal_notation() {
+        let default = DecimalPolicy::default();
+        assert_eq!(to_decimal("12", 10, 2, &default), Ok(1200));
+        assert_eq!(to_decimal("+.5", 10, 2, &default), Ok(50));
+        assert_eq!(to_decimal("1.5E-3", 10, 4, &default), Ok(15));
+        assert_eq!(to_decimal("1.23e+05", 18, 2, &default), Ok(12_300_000));
+        assert_eq!(to_decimal("0", 1, 0, &default), Ok(0));
+        assert!(to_decimal("", 10, 2, &default).is_err());
+        assert!(to_decimal("1.2.3", 10, 2, &default).is_err());
+        assert!(to_decimal("NaN", 10, 2, &default).is_err());
+    }
+
+    #[test]
+    fn decimal_overflow() {
+        let default = DecimalPolicy::default();
+        assert_eq!(to_decimal("999.99", 5, 2, &default), Ok(99999));
+        assert!(to_decimal("1000", 5, 2, &default).is_err());
+        assert!(to_decimal("999.995", 5, 2, &default).is_err());
+        assert!(to_decimal("1e300", 38, 0, &default).is_err());
+        assert_eq!(to_decimal("1e-300", 38, 2, &default), Ok(0));
+        assert_eq!(to_decimal("5e-3", 10, 1, &default), Ok(0));
+        assert_eq!(to_decimal("6e-2", 10, 1, &default), Ok(1));
+
+        let truncate = policy(DecimalRounding::HalfEven, ScaleOverflow::Truncate);
+        assert_eq!(to_decimal("2.679", 10, 2, &truncate), Ok(267));
+        assert_eq!(to_decimal("-2.679", 10, 2, &truncate), Ok(-267));
+
+        let error = policy(DecimalRounding::HalfEven, ScaleOverflow::Error);
+        assert_eq!(to_decimal("2.670", 10, 2, &error), Ok(267));
+        assert!(to_decimal("2.675", 10, 2, &error).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use duckdb::Connection;
+use duckdb::OptionalExt;
+use libduckdb_sys as ffi;
+use std::ops::Deref;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+/// Number of connections to the database that loaded the extension, opened at load time
+const HOST_CONNECTIONS: usize = 16;
+
+/// Idle connections to the database that loaded the extension, set once at load time
+static HOST_CONNECTION_POOL: OnceLock<HostConnectionPool> = OnceLock::new();
+
+/// Connections to the database that loaded the extension, handed out one query at a time
+struct HostConnectionPool {
+    /// Connections not in use
+    idle: Mutex<Vec<Connection>>,
+    /// Signalled whenever a connection is given back
+    released: Condvar,
+}
+
+/// Opens the connections later queries on the database that loaded the extension use,
+/// so that they share its loaded extensions (httpfs), settings and secrets.
+///
+/// The database handle given to the extension is only valid while the extension loads,
+/// so the connections cannot be opened on demand afterwards.
+pub(crate) fn register_host_database(database: ffi::duckdb_database) -> duckdb::Result<()> {
+    let connections = (0..HOST_CONNECTIONS)
+        .map(|_| unsafe { Connection::open_from_raw(database.cast()) })
+        .collect::<duckdb::Result<Vec<_>>>()?;
+    let _ = HOST_CONNECTION_POOL.set(HostConnectionPool {
+        idle: Mutex::new(connections),
+        released: Condvar::new(),
+    });
+    Ok(())
+}
+
+/// Connection to the database that loaded the extension, given back to the pool on drop
+pub(crate) struct HostConnection(Option<Connection>);
+
+impl Deref for HostConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.0.as_ref().expect("Host connection")
+    }
+}
+
+impl Drop for HostConnection {
+    fn drop(&mut self) {
+        if let (Some(pool), Some(connection)) = (HOST_CONNECTION_POOL.get(), self.0.take()) {
+            pool.idle.lock().expect("Host connection pool lock").push(connection);
+            pool.released.notify_one();
+        }
+    }
+}
+
+/// Takes a connection to the database that loaded the extension, waiting for one to be
+/// given back when all are in use, or opens a private in-memory database when the
+/// extension was not loaded by DuckDB (e.g. in unit tests)
+pub(crate) fn connect_host_database() -> duckdb::Result<HostConnection> {
+    match HOST_CONNECTION_POOL.get() {
+        Some(pool) => {
+            let mut idle = pool.idle.lock().expect("Host connection pool lock");
+            loop {
+                match idle.pop() {
+                    Some(connection) => return Ok(HostConnection(Some(connection))),
+                    None => idle = pool.released.wait(idle).expect("Host connection pool lock"),
+                }
+            }
+        }
+        None => Connection::open_in_memory().map(|connection| HostConnection(Some(connection))),
+    }
+}
+
+/// httpfs settings of remote reads: proxy, timeout and CA certificate bundle
+pub(crate) const HTTP_SETTINGS: [&str; 3] = ["http_proxy", "http_timeout", "ca_cert_file"];
+
+/// Returns the httpfs settings of remote reads as a connection to the host database
+/// resolves them, i.e. the global settings of the database; None for unset ones and for
+/// all of them when httpfs is not loaded.
+pub(crate) fn http_settings() -> duckdb::Result<Vec<(&'static str, Option<String>)>> {
+    let connection = connect_host_database()?;
+    HTTP_SETTINGS.iter()
+        .map(|name| {
+            // current_setting fails on settings of extensions that are not loaded, duckdb_settings lists none
+            let value = connection.query_row("SELECT value FROM duckdb_settings() WHERE name = ?", [name], |row| row.get::<_, Option<String>>(0))
+                .optional()?
+                .flatten()
+                .filter(|value| !value.is_empty());
+            Ok((*name, value))
+        })
+        .collect()
+}
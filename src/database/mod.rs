@@ -1,4 +1,6 @@
 pub(crate) mod bridge;
 pub(crate) mod column;
+pub(crate) mod decimal;
+pub(crate) mod host;
 pub(crate) mod range;
 pub(crate) mod table;
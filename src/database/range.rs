@@ -1,6 +1,7 @@
 use crate::error::RustySheetError;
 use crate::spreadsheet::reference::col_to_index;
 use crate::spreadsheet::reference::row_to_index;
+use crate::spreadsheet::reference::unquote_sheet_name;
 use regex::Regex;
 use thiserror::Error;
 
@@ -28,10 +29,14 @@ impl TryFrom<&str> for Range {
     type Error = RustySheetError;
 
     /// Parses an Excel-style range string (e.g., "A1", "B2:C5", "A", "1:10").
-    /// Supports single cells, ranges, and partial ranges (columns or rows only).
+    /// Supports single cells, ranges, and partial ranges (columns or rows only),
+    /// as well as cells and ranges in R1C1 notation (e.g., "R2C1", "R2C1:R100C5").
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         let pattern = Regex::new(r"^([A-Z]*)(\d*)(:([A-Z]*)(\d*))?$").expect("Hardcode regex pattern");
         let value = value.to_ascii_uppercase();
+        if let Some(range) = parse_r1c1(&value)? {
+            return Ok(range);
+        }
         let captures = pattern
             .captures(value.as_str())
             .ok_or(RangeError::FormatError(value.to_owned()))?;
@@ -56,6 +61,71 @@ impl TryFrom<&str> for Range {
     }
 }
 
+impl Range {
+    /// Checks whether a column lies within the column bounds of the range.
+    pub(crate) fn contains_col(&self, col: usize) -> bool {
+        self.col_lower_bound.map_or(true, |col_lower_bound| col_lower_bound <= col)
+            && self.col_upper_bound.map_or(true, |col_upper_bound| col <= col_upper_bound)
+    }
+
+    /// Checks whether a cell lies within the range.
+    pub(crate) fn contains(&self, row: usize, col: usize) -> bool {
+        self.contains_col(col)
+            && self.row_lower_bound.map_or(true, |row_lower_bound| row_lower_bound <= row)
+            && self.row_upper_bound.map_or(true, |row_upper_bound| row <= row_upper_bound)
+    }
+
+    /// Returns the smallest range covering all the ranges, None without ranges.
+    pub(crate) fn cover(ranges: &[Range]) -> Option<Range> {
+        let lower = |bounds: Vec<Option<usize>>| bounds.into_iter().reduce(|a, b| a.zip(b).map(|(a, b)| a.min(b))).flatten();
+        let upper = |bounds: Vec<Option<usize>>| bounds.into_iter().reduce(|a, b| a.zip(b).map(|(a, b)| a.max(b))).flatten();
+        (!ranges.is_empty()).then(|| Range {
+            row_lower_bound: lower(ranges.iter().map(|range| range.row_lower_bound).collect()),
+            row_upper_bound: upper(ranges.iter().map(|range| range.row_upper_bound).collect()),
+            col_lower_bound: lower(ranges.iter().map(|range| range.col_lower_bound).collect()),
+            col_upper_bound: upper(ranges.iter().map(|range| range.col_upper_bound).collect()),
+        })
+    }
+
+    /// Drops the first `count` rows of a range, counted from the first row of the sheet
+    /// when the range is unbounded above. Without rows to skip the range is kept as is.
+    pub(crate) fn skip_rows(range: Option<Range>, count: Option<usize>) -> Option<Range> {
+        match count.filter(|count| *count > 0) {
+            Some(count) => {
+                let range = range.unwrap_or_default();
+                Some(Range {
+                    row_lower_bound: Some(range.row_lower_bound.unwrap_or(0) + count),
+                    ..range
+                })
+            }
+            None => range,
+        }
+    }
+}
+
+/// Parses a range in R1C1 notation, whose rows and columns are both numbered from 1.
+/// Like "B2" in A1 notation, a single cell such as "R2C2" starts a range ending with the sheet.
+///
+/// # Returns
+/// None when the text is not in R1C1 notation
+fn parse_r1c1(value: &str) -> Result<Option<Range>, RustySheetError> {
+    let pattern = Regex::new(r"^R(\d+)C(\d+)(:R(\d+)C(\d+))?$").expect("Hardcode regex pattern");
+    let Some(captures) = pattern.captures(value) else {
+        return Ok(None);
+    };
+    let index = |group: usize| captures.get(group)
+        .map(|matcher| matcher.as_str().parse::<usize>().ok()
+            .and_then(|number| number.checked_sub(1))
+            .ok_or_else(|| RangeError::FormatError(value.to_owned())))
+        .transpose();
+    Ok(Some(Range {
+        row_lower_bound: index(1)?,
+        col_lower_bound: index(2)?,
+        row_upper_bound: index(4)?,
+        col_upper_bound: index(5)?,
+    }))
+}
+
 impl Default for Range {
     /// Creates an unbounded range (selects entire sheet).
     fn default() -> Self {
@@ -68,3 +138,107 @@ impl Default for Range {
     }
 }
 
+
+/// Value of the `range` parameter: cells in A1 or R1C1 notation, possibly qualified by
+/// their worksheet, or a defined name of the workbook.
+#[derive(Clone, Debug)]
+pub(crate) enum RangeSpec {
+    /// Cells in A1 or R1C1 notation
+    Cells(Range),
+    /// Cells of a worksheet (e.g. `Sheet1!A1:C10`), read instead of the matched sheets
+    SheetCells(String, Range),
+    /// Defined name, resolved to its worksheet and range once the workbook is opened
+    Name(String),
+}
+
+impl TryFrom<&str> for RangeSpec {
+    type Error = RustySheetError;
+
+    /// Parses A1 or R1C1 notation, taking text that is not a range but can be a defined name
+    /// (e.g. "Revenue", "Q1.Sales") as a name. Column parts have at most three letters,
+    /// so "Sales2024" is a name while "Tax" is the column TAX. A worksheet name followed by
+    /// `!`, quoted when needed (e.g. `'Q1 Sales'!B2:D5`), qualifies the range.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if let Some((sheet_name, range)) = value.rsplit_once('!') {
+            let sheet_name = unquote_sheet_name(sheet_name.trim())
+                .ok_or_else(|| RangeError::FormatError(value.to_owned()))?;
+            return Ok(RangeSpec::SheetCells(sheet_name, Range::try_from(range.trim())?));
+        }
+        let is_column = |bound: &str| bound.chars().take_while(char::is_ascii_alphabetic).count() <= 3;
+        match Range::try_from(value) {
+            Ok(range) if value.split(':').all(is_column) => Ok(RangeSpec::Cells(range)),
+            _ if is_defined_name(value) => Ok(RangeSpec::Name(value.to_owned())),
+            Ok(_) => Err(RangeError::FormatError(value.to_owned()))?,
+            Err(error) => Err(error),
+        }
+    }
+}
+
+/// Checks whether a text can be a defined name: a letter, `_` or `\` followed by letters,
+/// digits, `_`, `.` or `\`
+fn is_defined_name(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars.next().is_some_and(|char| char.is_alphabetic() || char == '_' || char == '\\')
+        && chars.all(|char| char.is_alphanumeric() || char == '_' || char == '.' || char == '\\')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(range: Range) -> (Option<usize>, Option<usize>, Option<usize>, Option<usize>) {
+        (range.row_lower_bound, range.row_upper_bound, range.col_lower_bound, range.col_upper_bound)
+    }
+
+    #[test]
+    fn r1c1_ranges() {
+        assert_eq!(bounds(Range::try_from("R2C1:R100C5").unwrap()), (Some(1), Some(99), Some(0), Some(4)));
+        assert_eq!(bounds(Range::try_from("r3c2").unwrap()), (Some(2), None, Some(1), None));
+        assert!(Range::try_from("R0C1").is_err());
+        // Column R of A1 notation
+        assert_eq!(bounds(Range::try_from("R2").unwrap()), (Some(1), None, Some(17), None));
+    }
+
+    #[test]
+    fn disjoint_ranges_cover() {
+        let ranges = [Range::try_from("A1:C100").unwrap(), Range::try_from("F3:H50").unwrap()];
+        let cover = Range::cover(&ranges).unwrap();
+        assert_eq!(bounds(cover), (Some(0), Some(99), Some(0), Some(7)));
+        assert!(ranges[1].contains(2, 5));
+        assert!(!ranges[1].contains(1, 5));
+        assert!(!ranges.iter().any(|range| range.contains_col(3)));
+
+        // An unbounded side of one range leaves the cover unbounded
+        let cover = Range::cover(&[Range::try_from("B2:C10").unwrap(), Range::try_from("E:F").unwrap()]).unwrap();
+        assert_eq!(bounds(cover), (None, None, Some(1), Some(5)));
+        assert!(Range::cover(&[]).is_none());
+    }
+
+    #[test]
+    fn skipped_rows() {
+        let skipped = Range::skip_rows(None, Some(3)).unwrap();
+        assert_eq!(bounds(skipped), (Some(3), None, None, None));
+        let skipped = Range::skip_rows(Some(Range::try_from("B2:D10").unwrap()), Some(2)).unwrap();
+        assert_eq!(bounds(skipped), (Some(3), Some(9), Some(1), Some(3)));
+        assert!(Range::skip_rows(None, Some(0)).is_none());
+        assert!(Range::skip_rows(None, None).is_none());
+    }
+
+    #[test]
+    fn sheet_qualified_ranges() {
+        let RangeSpec::SheetCells(sheet_name, range) = RangeSpec::try_from("Sheet1!A1:C10").unwrap() else {
+            panic!("Expected a sheet-qualified range");
+        };
+        assert_eq!(sheet_name, "Sheet1");
+        assert_eq!(bounds(range), (Some(0), Some(9), Some(0), Some(2)));
+
+        let RangeSpec::SheetCells(sheet_name, range) = RangeSpec::try_from("'Q1 ''24'!R2C1:R100C5").unwrap() else {
+            panic!("Expected a sheet-qualified range");
+        };
+        assert_eq!(sheet_name, "Q1 '24");
+        assert_eq!(bounds(range), (Some(1), Some(99), Some(0), Some(4)));
+
+        assert!(RangeSpec::try_from("Q1 Sales!A1").is_err());
+        assert!(matches!(RangeSpec::try_from("Revenue").unwrap(), RangeSpec::Name(_)));
+    }
+}
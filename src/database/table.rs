@@ -1,16 +1,35 @@
 use crate::database::column::Column;
 
+/// Outcome of converting the analyzed rows of a column to its type, as a read would.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Conversion {
+    /// Rows that would read as NULL: missing cells and null literals
+    pub(crate) nulls: usize,
+    /// Cells whose value would fail to convert
+    pub(crate) failures: usize,
+    /// Reference and message of the first failing cell
+    pub(crate) first_failure: Option<String>,
+}
+
 /// Represents a table extracted from a spreadsheet with metadata about data ranges.
 #[derive(Clone, Debug)]
 pub(crate) struct Table {
     /// Table/sheet name
     pub(crate) name: String,
+    /// Position of the sheet in the workbook (0-based)
+    pub(crate) index: usize,
+    /// Color of the sheet tab as `#RRGGBB`, None when the tab is not colored
+    pub(crate) tab_color: Option<String>,
     /// Column definitions
     pub(crate) columns: Vec<Column>,
+    /// Sheet column index and original header text of each column (None without a header cell)
+    pub(crate) headers: Vec<(usize, Option<String>)>,
     /// Data extraction range - row boundaries
     pub(crate) row_lower_bound: Option<usize>,
     /// Data extraction range - column lower bound
     pub(crate) col_lower_bound: usize,
     /// Data extraction range - column upper bound
     pub(crate) col_upper_bound: usize,
+    /// Dry-run conversion of the analyzed rows for each column (empty unless validating)
+    pub(crate) conversions: Vec<Conversion>,
 }
@@ -1,11 +1,16 @@
+use crate::helpers::metrics::ErrorCategory;
 use thiserror::Error;
 
 /// Main error type for the Rusty Sheet extension.
 /// Aggregates errors from various sources including standard library, dependencies, and internal modules.
 #[derive(Error, Debug)]
 pub(crate) enum RustySheetError {
-    #[error("{0}")]
-    WithContextError(String),
+    /// Error wrapped with a context prefix, keeping the original error as its source.
+    #[error("{context}: {source}")]
+    WithContextError {
+        context: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
 
     #[error("{0}")]
     AnyhowError(#[from] anyhow::Error),
@@ -45,6 +50,12 @@ pub(crate) enum RustySheetError {
     #[error("{0}")]
     XmlAttributeError(#[from] quick_xml::events::attributes::AttrError),
 
+    #[error("{0}")]
+    ArrowError(#[from] arrow::error::ArrowError),
+
+    #[error("{0}")]
+    ParquetError(#[from] parquet::errors::ParquetError),
+
     // Helper module errors
     #[error("{0}")]
     CfbHelperError(#[from] crate::helpers::cfb::CfbError),
@@ -58,6 +69,9 @@ pub(crate) enum RustySheetError {
     #[error("{0}")]
     Biff12HelperError(#[from] crate::helpers::biff12::Biff12Error),
 
+    #[error("{0}")]
+    CryptoHelperError(#[from] crate::helpers::crypto::CryptoError),
+
     #[error("{0}")]
     UnifiedReaderError(#[from] crate::helpers::reader::UnifiedReaderError),
 
@@ -71,6 +85,9 @@ pub(crate) enum RustySheetError {
     #[error("{0}")]
     XlsError(#[from] crate::spreadsheet::xls::XlsError),
 
+    #[error("{0}")]
+    OutputError(#[from] crate::spreadsheet::output::OutputError),
+
     // Database module errors
     #[error("{0}")]
     RangeError(#[from] crate::database::range::RangeError),
@@ -78,11 +95,54 @@ pub(crate) enum RustySheetError {
     #[error("{0}")]
     ColumnError(#[from] crate::database::column::ColumnError),
 
+    #[error("{0}")]
+    DecimalError(#[from] crate::database::decimal::DecimalError),
+
     // Extension module errors
     #[error("{0}")]
     ExtensionError(#[from] crate::extension::ExtensionError),
 }
 
+impl RustySheetError {
+    /// Returns the category the metrics count the error under, that of the original
+    /// error for errors wrapped with a context prefix
+    pub(crate) fn category(&self) -> ErrorCategory {
+        match self {
+            RustySheetError::WithContextError { source, .. } => source.downcast_ref::<RustySheetError>()
+                .map(RustySheetError::category)
+                .unwrap_or(ErrorCategory::Other),
+            RustySheetError::SpreadsheetError(crate::spreadsheet::SpreadsheetError::CellValueError(..)) => ErrorCategory::Conversion,
+            RustySheetError::IoError(_) | RustySheetError::UnifiedReaderError(_) => ErrorCategory::Io,
+            RustySheetError::ZipError(_) => ErrorCategory::Zip,
+            RustySheetError::XmlError(_)
+            | RustySheetError::XmlEncodingError(_)
+            | RustySheetError::XmlAttributeError(_)
+            | RustySheetError::XmlHelperError(_) => ErrorCategory::Xml,
+            RustySheetError::StringEncodingError(_)
+            | RustySheetError::CfbHelperError(_)
+            | RustySheetError::Biff8HelperError(_)
+            | RustySheetError::Biff12HelperError(_)
+            | RustySheetError::SpreadsheetError(_)
+            | RustySheetError::OdsError(_)
+            | RustySheetError::XlsError(_) => ErrorCategory::Format,
+            RustySheetError::CryptoHelperError(_) => ErrorCategory::Encryption,
+            RustySheetError::ParseIntError(_)
+            | RustySheetError::ParseFloatError(_)
+            | RustySheetError::ParseDateTimeError(_)
+            | RustySheetError::ColumnError(_)
+            | RustySheetError::DecimalError(_) => ErrorCategory::Conversion,
+            RustySheetError::PatternError(_)
+            | RustySheetError::RangeError(_)
+            | RustySheetError::ExtensionError(_) => ErrorCategory::Parameter,
+            RustySheetError::DuckDBError(_) => ErrorCategory::Database,
+            RustySheetError::AnyhowError(_)
+            | RustySheetError::OutputError(_)
+            | RustySheetError::ArrowError(_)
+            | RustySheetError::ParquetError(_) => ErrorCategory::Other,
+        }
+    }
+}
+
 pub(crate) trait ResultOptionChain {
     fn ok_none_else<F>(self, f: F) -> Self
     where
@@ -107,6 +167,29 @@ pub(crate) trait ResultMessage {
 
 impl<T> ResultMessage for Result<T, RustySheetError> {
     fn with_prefix(self, message: &str) -> Self {
-        self.map_err(|e| RustySheetError::WithContextError(format!("{}: {}", message, e)))
+        self.map_err(|e| RustySheetError::WithContextError {
+            context: message.to_owned(),
+            source: Box::new(e),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spreadsheet::SpreadsheetError;
+    use std::error::Error;
+
+    #[test]
+    fn with_prefix_keeps_source() {
+        let result: Result<(), RustySheetError> = Err(SpreadsheetError::FileError("a.xlsx".to_owned()).into());
+        let error = result.with_prefix("Sheet1").with_prefix("a.xlsx").unwrap_err();
+        assert_eq!(error.to_string(), "a.xlsx: Sheet1: file 'a.xlsx' is missing or corrupted.");
+
+        let source = error.source().and_then(|e| e.source()).expect("Original error");
+        assert!(matches!(
+            source.downcast_ref::<RustySheetError>(),
+            Some(RustySheetError::SpreadsheetError(SpreadsheetError::FileError(_))),
+        ));
     }
 }
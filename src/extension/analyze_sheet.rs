@@ -1,18 +1,51 @@
+use std::collections::HashMap;
 use std::collections::HashSet;
+use crate::database::column::ColumnType;
+use crate::database::range::Range;
+use crate::database::range::RangeSpec;
 use crate::error::ResultMessage;
 use crate::error::RustySheetError;
+use crate::extension::MaxHeaderLengthParam;
+use crate::extension::default_analyze_rows;
+use crate::extension::AllVarcharParam;
 use crate::extension::AnalyzeRowsParam;
+use crate::extension::ColumnsParam;
+use crate::extension::DateLocaleParam;
+use crate::extension::DisplayValuesParam;
+use crate::extension::DurationAsIntervalParam;
+use crate::extension::EndAtEmptyRowParam;
+use crate::extension::EpochDateAsTimeParam;
 use crate::extension::ErrorAsNullParam;
+use crate::extension::FileNameColumnParam;
 use crate::extension::FileParam;
+use crate::extension::FixEncodingParam;
+use crate::extension::FormulasParam;
+use crate::extension::HeaderAliasesParam;
 use crate::extension::HeaderParam;
+use crate::extension::IncludeHiddenColumnsParam;
+use crate::extension::LenientParam;
+use crate::extension::MidnightAsDateParam;
+use crate::extension::MissingHeaderNameParam;
 use crate::extension::NamedParam;
+use crate::extension::NormalizeNamesParam;
 use crate::extension::NullsParam;
+use crate::extension::OriginalValuesParam;
 use crate::extension::Param;
-use crate::extension::Range;
+use crate::extension::PasswordParam;
 use crate::extension::RangeParam;
+use crate::extension::RequireHeaderParam;
+use crate::extension::SalvageParam;
+use crate::extension::SampleParam;
+use crate::extension::SheetNameColumnParam;
 use crate::extension::SheetParam;
+use crate::extension::SkipEmptyRowsParam;
+use crate::extension::SkipParam;
+use crate::extension::SnapshotParam;
+use crate::extension::StableRowsParam;
 use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::locale::DateLocale;
 use crate::spreadsheet::open_spreadsheet;
+use crate::spreadsheet::MAX_HEADER_LENGTH;
 use duckdb::core::DataChunkHandle;
 use duckdb::core::Inserter;
 use duckdb::core::LogicalTypeHandle;
@@ -27,21 +60,75 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 
 /// Parameters for the analyze_sheet table function
-struct AnalyzeSheetParameters {
+pub(super) struct AnalyzeSheetParameters {
     /// Path to the spreadsheet file
-    file_name: String,
+    pub(super) file_name: String,
     /// Optional sheet name pattern to filter sheets
     sheet_name: Option<Pattern>,
-    /// Optional range specification (e.g., "A1:D10")
-    range: Option<Range>,
+    /// Optional range specification (e.g., "A1:D10") or defined name
+    range: Option<RangeSpec>,
+    /// Number of leading rows dropped before header detection (default: 0)
+    skip: Option<usize>,
     /// Whether to treat first row as header (default: true)
     header: Option<bool>,
+    /// Column type specifications with pattern matching
+    columns: Option<Vec<(Pattern, ColumnType)>>,
+    /// Type every column as VARCHAR instead of detecting its type (default: false)
+    all_varchar: Option<bool>,
     /// Number of rows to analyze for type detection (default: 10)
     analyze_rows: Option<usize>,
+    /// Number of rows sampled uniformly from the whole sheet for type detection (default: none)
+    sample: Option<usize>,
+    /// Consecutive values of one type after which a column's type is stable (default: none)
+    stable_rows: Option<usize>,
     /// null literals (default: empty string)
     nulls: Option<HashSet<String>>,
     /// Whether to convert errors to null values (default: false)
     error_as_null: Option<bool>,
+    /// Skip rows with no data (default: false)
+    skip_empty_rows: Option<bool>,
+    /// Stop reading at first empty row (default: false)
+    end_at_empty_row: Option<bool>,
+    /// Classify ISO datetimes at midnight as dates (default: false)
+    midnight_as_date: Option<bool>,
+    /// Classify ISO datetimes on the 1900/1904 epoch date as times (default: false)
+    epoch_date_as_time: Option<bool>,
+    /// Classify text durations like "2 05:30:00" as intervals (default: false)
+    duration_as_interval: Option<bool>,
+    /// Render numeric cells as displayed by their number format (default: false)
+    display_values: Option<bool>,
+    /// Include hidden, zero-width and collapsed columns (default: true)
+    include_hidden_columns: Option<bool>,
+    /// Fail when every header cell is empty or a null literal (default: false)
+    require_header: Option<bool>,
+    /// Locale of month names parsed in text dates (default: none)
+    date_locale: Option<DateLocale>,
+    /// Canonical column names by localized header text (default: none)
+    header_aliases: Option<HashMap<String, String>>,
+    /// Template naming columns without a header, e.g. 'column_{n}' (default: column letters)
+    missing_header_name: Option<String>,
+    /// Maximum number of characters of column names taken from headers (default: 255)
+    max_header_length: Option<usize>,
+    /// Lowercase, de-accent and underscore the column names taken from headers (default: false)
+    normalize_names: Option<bool>,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Keep the cells parsed before a structural error instead of failing (default: false)
+    lenient: Option<bool>,
+    /// Read the values cells held before the tracked changes instead of the latest ones (default: false)
+    original_values: Option<bool>,
+    /// Read the formulas of formula cells instead of their cached values (default: false)
+    formulas: Option<bool>,
+    /// Repair text whose UTF-8 bytes were decoded as Windows-1252 or Latin-1 (default: false)
+    fix_encoding: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+    /// column name for file name of record
+    file_name_column: Option<String>,
+    /// column name for sheet name of record
+    sheet_name_column: Option<String>,
 }
 
 impl TryFrom<&BindInfo> for AnalyzeSheetParameters {
@@ -53,10 +140,37 @@ impl TryFrom<&BindInfo> for AnalyzeSheetParameters {
             file_name: FileParam::read(bind, 0)?,
             sheet_name: SheetParam::read(bind)?,
             range: RangeParam::read(bind)?,
+            skip: SkipParam::read(bind)?,
             header: HeaderParam::read(bind)?,
+            columns: ColumnsParam::read(bind)?,
+            all_varchar: AllVarcharParam::read(bind)?,
             analyze_rows: AnalyzeRowsParam::read(bind)?,
+            sample: SampleParam::read(bind)?,
+            stable_rows: StableRowsParam::read(bind)?,
             nulls: NullsParam::read(bind)?,
             error_as_null: ErrorAsNullParam::read(bind)?,
+            skip_empty_rows: SkipEmptyRowsParam::read(bind)?,
+            end_at_empty_row: EndAtEmptyRowParam::read(bind)?,
+            midnight_as_date: MidnightAsDateParam::read(bind)?,
+            epoch_date_as_time: EpochDateAsTimeParam::read(bind)?,
+            duration_as_interval: DurationAsIntervalParam::read(bind)?,
+            display_values: DisplayValuesParam::read(bind)?,
+            include_hidden_columns: IncludeHiddenColumnsParam::read(bind)?,
+            require_header: RequireHeaderParam::read(bind)?,
+            date_locale: DateLocaleParam::read(bind)?,
+            header_aliases: HeaderAliasesParam::read(bind)?,
+            missing_header_name: MissingHeaderNameParam::read(bind)?,
+            max_header_length: MaxHeaderLengthParam::read(bind)?,
+            normalize_names: NormalizeNamesParam::read(bind)?,
+            salvage: SalvageParam::read(bind)?,
+            lenient: LenientParam::read(bind)?,
+            original_values: OriginalValuesParam::read(bind)?,
+            formulas: FormulasParam::read(bind)?,
+            fix_encoding: FixEncodingParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+            file_name_column: FileNameColumnParam::read(bind)?,
+            sheet_name_column: SheetNameColumnParam::read(bind)?,
         })
     }
 }
@@ -65,37 +179,69 @@ impl TryFrom<&BindInfo> for AnalyzeSheetParameters {
 /// Bind data for the analyze_sheet table function containing column analysis results
 pub(crate) struct AnalyzeSheetBindData {
     /// Vector of (column_name, column_type) pairs from analyzed sheets
-    columns: Vec<(String, String)>,
+    pub(super) columns: Vec<(String, String)>,
 }
 
 impl TryFrom<&AnalyzeSheetParameters> for AnalyzeSheetBindData {
     type Error = RustySheetError;
 
-    /// Analyze spreadsheet and extract column metadata
+    /// Analyze spreadsheet and extract column metadata.
+    /// Uses the same criteria and extra columns as read_sheet so both report identical schemas.
     fn try_from(parameters: &AnalyzeSheetParameters) -> Result<Self, Self::Error> {
         let mut columns = Vec::<(String, String)>::new();
-        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str())?;
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
         let sheet_name_patterns = parameters.sheet_name
             .as_ref()
             .map(|pattern| vec![pattern.to_owned()]);
+        let (sheet_name_patterns, range) = spreadsheet.resolve_range(parameters.range.as_ref(), sheet_name_patterns)?;
+        let range = Range::skip_rows(range, parameters.skip);
         let header = parameters.header.unwrap_or(true);
         let nulls = parameters.nulls.to_owned().unwrap_or(HashSet::from(["".to_string()]));
         for table in spreadsheet.analyze_sheets(header, &Criteria {
             sheet_name_patterns,
             sheet_limit: Some(1),
-            range: parameters.range,
-            rows_limit: parameters.analyze_rows.or(Some(10)),
+            range,
+            ranges: Vec::new(),
+            rows_limit: parameters.analyze_rows.or(Some(default_analyze_rows(parameters.stable_rows))).filter(|_| parameters.sample.is_none()),
+            sample_rows: parameters.sample,
+            stable_rows: parameters.stable_rows,
             nulls: nulls.to_owned(),
             error_as_null: parameters.error_as_null.unwrap_or(false),
-            skip_empty_rows: false,
-            end_at_empty_row: false,
-        }, &Vec::new())? {
+            skip_empty_rows: parameters.skip_empty_rows.unwrap_or(false),
+            end_at_empty_row: parameters.end_at_empty_row.unwrap_or(false),
+            midnight_as_date: parameters.midnight_as_date.unwrap_or(false),
+            epoch_date_as_time: parameters.epoch_date_as_time.unwrap_or(false),
+            duration_as_interval: parameters.duration_as_interval.unwrap_or(false),
+            display_values: parameters.display_values.unwrap_or(false),
+            include_hidden_columns: parameters.include_hidden_columns.unwrap_or(true),
+            require_header: parameters.require_header.unwrap_or(false),
+            date_locale: parameters.date_locale,
+            count_only: false,
+            max_cell_length: None,
+            checksum: false,
+            header_aliases: parameters.header_aliases.to_owned().unwrap_or_default(),
+            missing_header_name: parameters.missing_header_name.to_owned(),
+            max_header_length: parameters.max_header_length.unwrap_or(MAX_HEADER_LENGTH),
+            validate: false,
+            lenient: parameters.lenient.unwrap_or(false),
+            original_values: parameters.original_values.unwrap_or(false),
+            formulas: parameters.formulas.unwrap_or(false),
+            fix_encoding: parameters.fix_encoding.unwrap_or(false),
+            all_varchar: parameters.all_varchar.unwrap_or(false),
+            normalize_names: parameters.normalize_names.unwrap_or(false),
+        }, parameters.columns.as_ref().unwrap_or(&vec![]))? {
             for column in &table.columns {
                 columns.push((
                     column.name.to_owned(),
-                    column.kind.as_str().to_owned(),
+                    column.kind.to_string(),
                 ));
             }
+            if let Some(name) = &parameters.sheet_name_column {
+                columns.push((name.to_owned(), ColumnType::Varchar.as_str().to_owned()));
+            }
+            if let Some(name) = &parameters.file_name_column {
+                columns.push((name.to_owned(), ColumnType::Varchar.as_str().to_owned()));
+            }
         }
         Ok(AnalyzeSheetBindData { columns })
     }
@@ -173,10 +319,37 @@ impl VTab for AnalyzeSheetTableFunction {
         Some(vec![
             SheetParam::definition(),
             RangeParam::definition(),
+            SkipParam::definition(),
             HeaderParam::definition(),
+            ColumnsParam::definition(),
+            AllVarcharParam::definition(),
             AnalyzeRowsParam::definition(),
+            SampleParam::definition(),
+            StableRowsParam::definition(),
             NullsParam::definition(),
             ErrorAsNullParam::definition(),
+            SkipEmptyRowsParam::definition(),
+            EndAtEmptyRowParam::definition(),
+            MidnightAsDateParam::definition(),
+            EpochDateAsTimeParam::definition(),
+            DurationAsIntervalParam::definition(),
+            DisplayValuesParam::definition(),
+            IncludeHiddenColumnsParam::definition(),
+            RequireHeaderParam::definition(),
+            DateLocaleParam::definition(),
+            HeaderAliasesParam::definition(),
+            MissingHeaderNameParam::definition(),
+            MaxHeaderLengthParam::definition(),
+            NormalizeNamesParam::definition(),
+            SalvageParam::definition(),
+            LenientParam::definition(),
+            OriginalValuesParam::definition(),
+            FormulasParam::definition(),
+            FixEncodingParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+            FileNameColumnParam::definition(),
+            SheetNameColumnParam::definition(),
         ])
     }
 }
@@ -0,0 +1,371 @@
+use crate::database::column::ColumnType;
+use crate::database::range::Range;
+use crate::database::range::RangeSpec;
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::MaxHeaderLengthParam;
+use crate::extension::default_analyze_rows;
+use crate::extension::AnalyzeRowsParam;
+use crate::extension::ColumnsParam;
+use crate::extension::DateLocaleParam;
+use crate::extension::DisplayValuesParam;
+use crate::extension::DurationAsIntervalParam;
+use crate::extension::EndAtEmptyRowParam;
+use crate::extension::EpochDateAsTimeParam;
+use crate::extension::ErrorAsNullParam;
+use crate::extension::FilesParam;
+use crate::extension::FixEncodingParam;
+use crate::extension::FormulasParam;
+use crate::extension::HeaderAliasesParam;
+use crate::extension::HeaderParam;
+use crate::extension::IncludeHiddenColumnsParam;
+use crate::extension::LenientParam;
+use crate::extension::MidnightAsDateParam;
+use crate::extension::MissingHeaderNameParam;
+use crate::extension::NamedParam;
+use crate::extension::NormalizeNamesParam;
+use crate::extension::NullsParam;
+use crate::extension::OriginalValuesParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::RangeParam;
+use crate::extension::RequireHeaderParam;
+use crate::extension::SalvageParam;
+use crate::extension::SampleParam;
+use crate::extension::SheetsParam;
+use crate::extension::SkipEmptyRowsParam;
+use crate::extension::SkipParam;
+use crate::extension::SnapshotParam;
+use crate::extension::StableRowsParam;
+use crate::extension::UnionByNameParam;
+use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::locale::DateLocale;
+use crate::spreadsheet::open_spreadsheets;
+use crate::spreadsheet::MAX_HEADER_LENGTH;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use either::Either;
+use glob::Pattern;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for diagnosing the column types of a read_sheets union
+struct DiagnoseUnionParameters {
+    /// List of file paths to analyze
+    files: Vec<String>,
+    /// Optional sheet name patterns with optional file name filters
+    sheets: Option<Vec<(Option<Pattern>, Pattern)>>,
+    /// Optional cell range or defined name to analyze
+    range: Option<RangeSpec>,
+    /// Number of leading rows dropped before header detection (default: 0)
+    skip: Option<usize>,
+    /// Whether the first row contains headers (default: true)
+    header: Option<bool>,
+    /// Union sheets data by name (true) or position (false) (default: false)
+    union_by_name: Option<bool>,
+    /// Column type specifications with pattern matching
+    columns: Option<Vec<(Pattern, ColumnType)>>,
+    /// Number of rows to analyze for type detection (default: 10)
+    analyze_rows: Option<usize>,
+    /// Number of rows sampled uniformly from the whole sheet for type detection (default: none)
+    sample: Option<usize>,
+    /// Consecutive values of one type after which a column's type is stable (default: none)
+    stable_rows: Option<usize>,
+    /// null literals (default: empty string)
+    nulls: Option<HashSet<String>>,
+    /// Whether to convert errors to null values (default: false)
+    error_as_null: Option<bool>,
+    /// Skip rows with no data (default: false)
+    skip_empty_rows: Option<bool>,
+    /// Stop reading at first empty row (default: false)
+    end_at_empty_row: Option<bool>,
+    /// Classify ISO datetimes at midnight as dates (default: false)
+    midnight_as_date: Option<bool>,
+    /// Classify ISO datetimes on the 1900/1904 epoch date as times (default: false)
+    epoch_date_as_time: Option<bool>,
+    /// Classify text durations like "2 05:30:00" as intervals (default: false)
+    duration_as_interval: Option<bool>,
+    /// Render numeric cells as displayed by their number format (default: false)
+    display_values: Option<bool>,
+    /// Include hidden, zero-width and collapsed columns (default: true)
+    include_hidden_columns: Option<bool>,
+    /// Fail when every header cell is empty or a null literal (default: false)
+    require_header: Option<bool>,
+    /// Locale of month names parsed in text dates (default: none)
+    date_locale: Option<DateLocale>,
+    /// Canonical column names by localized header text (default: none)
+    header_aliases: Option<HashMap<String, String>>,
+    /// Template naming columns without a header, e.g. 'column_{n}' (default: column letters)
+    missing_header_name: Option<String>,
+    /// Maximum number of characters of column names taken from headers (default: 255)
+    max_header_length: Option<usize>,
+    /// Lowercase, de-accent and underscore the column names taken from headers (default: false)
+    normalize_names: Option<bool>,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Keep the cells parsed before a structural error instead of failing (default: false)
+    lenient: Option<bool>,
+    /// Read the values cells held before the tracked changes instead of the latest ones (default: false)
+    original_values: Option<bool>,
+    /// Read the formulas of formula cells instead of their cached values (default: false)
+    formulas: Option<bool>,
+    /// Repair text whose UTF-8 bytes were decoded as Windows-1252 or Latin-1 (default: false)
+    fix_encoding: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for DiagnoseUnionParameters {
+    type Error = RustySheetError;
+
+    /// Constructs parameters from DuckDB bind information
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(DiagnoseUnionParameters {
+            files: FilesParam::read(bind, 0)?,
+            sheets: SheetsParam::read(bind)?,
+            range: RangeParam::read(bind)?,
+            skip: SkipParam::read(bind)?,
+            header: HeaderParam::read(bind)?,
+            union_by_name: UnionByNameParam::read(bind)?,
+            columns: ColumnsParam::read(bind)?,
+            analyze_rows: AnalyzeRowsParam::read(bind)?,
+            sample: SampleParam::read(bind)?,
+            stable_rows: StableRowsParam::read(bind)?,
+            nulls: NullsParam::read(bind)?,
+            error_as_null: ErrorAsNullParam::read(bind)?,
+            skip_empty_rows: SkipEmptyRowsParam::read(bind)?,
+            end_at_empty_row: EndAtEmptyRowParam::read(bind)?,
+            midnight_as_date: MidnightAsDateParam::read(bind)?,
+            epoch_date_as_time: EpochDateAsTimeParam::read(bind)?,
+            duration_as_interval: DurationAsIntervalParam::read(bind)?,
+            display_values: DisplayValuesParam::read(bind)?,
+            include_hidden_columns: IncludeHiddenColumnsParam::read(bind)?,
+            require_header: RequireHeaderParam::read(bind)?,
+            date_locale: DateLocaleParam::read(bind)?,
+            header_aliases: HeaderAliasesParam::read(bind)?,
+            missing_header_name: MissingHeaderNameParam::read(bind)?,
+            max_header_length: MaxHeaderLengthParam::read(bind)?,
+            normalize_names: NormalizeNamesParam::read(bind)?,
+            salvage: SalvageParam::read(bind)?,
+            lenient: LenientParam::read(bind)?,
+            original_values: OriginalValuesParam::read(bind)?,
+            formulas: FormulasParam::read(bind)?,
+            fix_encoding: FixEncodingParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Binding data containing the conflicting columns of the union
+pub(crate) struct DiagnoseUnionBindData {
+    /// Vector of tuples containing (column_name, file_name, sheet_name, detected_type)
+    conflicts: Vec<(String, String, String, String)>,
+}
+
+impl TryFrom<&DiagnoseUnionParameters> for DiagnoseUnionBindData {
+    type Error = RustySheetError;
+
+    /// Analyzes every sheet of the union and keeps the columns whose detected types disagree.
+    ///
+    /// Columns are matched by name with `union_by_name`, by position otherwise,
+    /// so the conflicts are the ones read_sheets would fail on or silently cast.
+    fn try_from(parameters: &DiagnoseUnionParameters) -> Result<Self, Self::Error> {
+        let union_by_name = parameters.union_by_name.unwrap_or(false);
+        let nulls = parameters.nulls.to_owned().unwrap_or(HashSet::from(["".to_string()]));
+        let default_preset_columns = vec![];
+        let preset = parameters.columns.as_ref().unwrap_or(&default_preset_columns);
+
+        // Union columns in order of first appearance, each with the (column_name, file_name, sheet_name, type) of every sheet
+        let mut keys = Vec::<Either<String, usize>>::new();
+        let mut columns = HashMap::<Either<String, usize>, Vec<(String, String, String, ColumnType)>>::new();
+        for (mut spreadsheet, sheet_name_patterns) in open_spreadsheets(&parameters.files, &parameters.sheets, parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())? {
+            let (sheet_name_patterns, range) = spreadsheet.resolve_range(parameters.range.as_ref(), sheet_name_patterns)?;
+            let range = Range::skip_rows(range, parameters.skip);
+            for table in spreadsheet.analyze_sheets(parameters.header.unwrap_or(true), &Criteria {
+                sheet_name_patterns,
+                range,
+                rows_limit: parameters.analyze_rows.or(Some(default_analyze_rows(parameters.stable_rows))).filter(|_| parameters.sample.is_none()),
+                sample_rows: parameters.sample,
+                stable_rows: parameters.stable_rows,
+                nulls: nulls.to_owned(),
+                error_as_null: parameters.error_as_null.unwrap_or(false),
+                skip_empty_rows: parameters.skip_empty_rows.unwrap_or(false),
+                end_at_empty_row: parameters.end_at_empty_row.unwrap_or(false),
+                midnight_as_date: parameters.midnight_as_date.unwrap_or(false),
+                epoch_date_as_time: parameters.epoch_date_as_time.unwrap_or(false),
+                duration_as_interval: parameters.duration_as_interval.unwrap_or(false),
+                display_values: parameters.display_values.unwrap_or(false),
+                include_hidden_columns: parameters.include_hidden_columns.unwrap_or(true),
+                require_header: parameters.require_header.unwrap_or(false),
+                date_locale: parameters.date_locale,
+                header_aliases: parameters.header_aliases.to_owned().unwrap_or_default(),
+                missing_header_name: parameters.missing_header_name.to_owned(),
+                max_header_length: parameters.max_header_length.unwrap_or(MAX_HEADER_LENGTH),
+                lenient: parameters.lenient.unwrap_or(false),
+                original_values: parameters.original_values.unwrap_or(false),
+                formulas: parameters.formulas.unwrap_or(false),
+                fix_encoding: parameters.fix_encoding.unwrap_or(false),
+                normalize_names: parameters.normalize_names.unwrap_or(false),
+                ..Default::default()
+            }, preset).with_prefix(spreadsheet.name().as_str())? {
+                for (index, column) in table.columns.iter().enumerate() {
+                    let key = if union_by_name {
+                        Either::Left(column.name.to_owned())
+                    } else {
+                        Either::Right(index)
+                    };
+                    if !columns.contains_key(&key) {
+                        keys.push(key.clone());
+                    }
+                    columns.entry(key).or_default().push((
+                        column.name.to_owned(),
+                        spreadsheet.name(),
+                        table.name.to_owned(),
+                        column.kind,
+                    ));
+                }
+            }
+        }
+
+        let mut conflicts = Vec::<(String, String, String, String)>::new();
+        for key in keys {
+            let sheets = columns.remove(&key).unwrap_or_default();
+            if sheets.iter().any(|(_, _, _, kind)| *kind != sheets[0].3) {
+                for (column_name, file_name, sheet_name, kind) in sheets {
+                    conflicts.push((column_name, file_name, sheet_name, kind.to_string()));
+                }
+            }
+        }
+        Ok(DiagnoseUnionBindData { conflicts })
+    }
+}
+
+#[repr(C)]
+/// Initialization data for tracking iteration state across function calls
+pub(crate) struct DiagnoseUnionInitData {
+    /// Atomic counter tracking the current position in the conflict vector
+    index: AtomicUsize,
+}
+
+/// DuckDB table function listing the column type conflicts of a read_sheets union at once
+pub(crate) struct DiagnoseUnionTableFunction;
+
+impl VTab for DiagnoseUnionTableFunction {
+    type InitData = DiagnoseUnionInitData;
+    type BindData = DiagnoseUnionBindData;
+
+    /// Binds the table function by parsing parameters and analyzing the union
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = DiagnoseUnionParameters::try_from(bind)?;
+        let data = DiagnoseUnionBindData::try_from(&parameters)?;
+        bind.add_result_column(
+            "column_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "file_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "sheet_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "detected_type",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        Ok(data)
+    }
+
+    /// Initializes the table function with iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(DiagnoseUnionInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Executes the table function to produce output data chunks
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.conflicts.len().min(lower + 2048);
+        if lower < upper {
+            let columns = output.flat_vector(0);
+            let files = output.flat_vector(1);
+            let sheets = output.flat_vector(2);
+            let kinds = output.flat_vector(3);
+            for index in lower..upper {
+                let (column_name, file_name, sheet_name, kind_name) = &bind.conflicts[index];
+                columns.insert(index - lower, column_name);
+                files.insert(index - lower, file_name);
+                sheets.insert(index - lower, sheet_name);
+                kinds.insert(index - lower, kind_name);
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Returns the required parameter types for the table function
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FilesParam::kind()
+        ])
+    }
+
+    /// Returns the named parameter definitions for the table function
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SheetsParam::definition(),
+            RangeParam::definition(),
+            SkipParam::definition(),
+            HeaderParam::definition(),
+            UnionByNameParam::definition(),
+            ColumnsParam::definition(),
+            AnalyzeRowsParam::definition(),
+            SampleParam::definition(),
+            StableRowsParam::definition(),
+            NullsParam::definition(),
+            ErrorAsNullParam::definition(),
+            SkipEmptyRowsParam::definition(),
+            EndAtEmptyRowParam::definition(),
+            MidnightAsDateParam::definition(),
+            EpochDateAsTimeParam::definition(),
+            DurationAsIntervalParam::definition(),
+            DisplayValuesParam::definition(),
+            IncludeHiddenColumnsParam::definition(),
+            RequireHeaderParam::definition(),
+            DateLocaleParam::definition(),
+            HeaderAliasesParam::definition(),
+            MissingHeaderNameParam::definition(),
+            MaxHeaderLengthParam::definition(),
+            NormalizeNamesParam::definition(),
+            SalvageParam::definition(),
+            LenientParam::definition(),
+            OriginalValuesParam::definition(),
+            FormulasParam::definition(),
+            FixEncodingParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
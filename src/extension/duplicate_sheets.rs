@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use crate::database::range::RangeSpec;
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::FilesParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::RangeParam;
+use crate::extension::SalvageParam;
+use crate::extension::SheetsParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::open_spreadsheets;
+use crate::spreadsheet::shared_strings::SharedStrings;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use glob::Pattern;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the duplicate_sheets table function
+struct DuplicateSheetsParameters {
+    /// List of file paths to compare
+    files: Vec<String>,
+    /// Optional sheet name patterns with optional file name filters
+    sheets: Option<Vec<(Option<Pattern>, Pattern)>>,
+    /// Optional cell range or defined name compared in each sheet
+    range: Option<RangeSpec>,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for DuplicateSheetsParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(DuplicateSheetsParameters {
+            files: FilesParam::read(bind, 0)?,
+            sheets: SheetsParam::read(bind)?,
+            range: RangeParam::read(bind)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Bind data for the duplicate_sheets table function
+pub(crate) struct DuplicateSheetsBindData {
+    /// Vector of (file_name, sheet_name, original file_name, original sheet_name) for each duplicate
+    duplicates: Vec<(String, String, String, String)>,
+}
+
+impl TryFrom<&DuplicateSheetsParameters> for DuplicateSheetsBindData {
+    type Error = RustySheetError;
+
+    /// Hash the content of every matched sheet and pair each duplicate with
+    /// the first sheet holding the same content, in file and sheet order.
+    fn try_from(parameters: &DuplicateSheetsParameters) -> Result<Self, Self::Error> {
+        let mut duplicates = Vec::<(String, String, String, String)>::new();
+        let mut originals = HashMap::<u64, (String, String)>::new();
+        for (mut spreadsheet, sheet_name_patterns) in open_spreadsheets(&parameters.files, &parameters.sheets, parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())? {
+            let (sheet_name_patterns, range) = spreadsheet.resolve_range(parameters.range.as_ref(), sheet_name_patterns)?;
+            let file_name = spreadsheet.name();
+            let sheets = spreadsheet.read_sheets(&Criteria {
+                sheet_name_patterns,
+                range,
+                ..Default::default()
+            }).with_prefix(file_name.as_str())?;
+            let shared_strings = spreadsheet.load_shared_strings(None)
+                .map(|(shared_strings, _)| SharedStrings::Memory(shared_strings.into_iter().map(Some).collect()))
+                .with_prefix(file_name.as_str())?;
+            for sheet in sheets {
+                let hash = sheet.content_hash(&shared_strings)?;
+                match originals.get(&hash) {
+                    Some((original_file_name, original_sheet_name)) => duplicates.push((
+                        file_name.to_owned(),
+                        sheet.name.to_owned(),
+                        original_file_name.to_owned(),
+                        original_sheet_name.to_owned(),
+                    )),
+                    None => {
+                        originals.insert(hash, (file_name.to_owned(), sheet.name.to_owned()));
+                    }
+                }
+            }
+        }
+        Ok(DuplicateSheetsBindData { duplicates })
+    }
+}
+
+#[repr(C)]
+/// Init data for the duplicate_sheets table function tracking iteration state
+pub(crate) struct DuplicateSheetsInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function listing the sheets identical to an earlier sheet, such as the
+/// template copies skipped by `read_sheets(..., dedupe_sheets=true)`
+pub(crate) struct DuplicateSheetsTableFunction;
+
+impl VTab for DuplicateSheetsTableFunction {
+    type InitData = DuplicateSheetsInitData;
+    type BindData = DuplicateSheetsBindData;
+
+    /// Bind phase: parse parameters, hash the sheets, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = DuplicateSheetsParameters::try_from(bind)?;
+        let data = DuplicateSheetsBindData::try_from(&parameters)?;
+        bind.add_result_column(
+            "file_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "sheet_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "duplicate_of_file",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "duplicate_of_sheet",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(DuplicateSheetsInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the duplicates to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.duplicates.len().min(lower + 2048);
+        if lower < upper {
+            let files = output.flat_vector(0);
+            let sheets = output.flat_vector(1);
+            let original_files = output.flat_vector(2);
+            let original_sheets = output.flat_vector(3);
+            for index in lower..upper {
+                let (file_name, sheet_name, original_file_name, original_sheet_name) = &bind.duplicates[index];
+                files.insert(index - lower, file_name);
+                sheets.insert(index - lower, sheet_name);
+                original_files.insert(index - lower, original_file_name);
+                original_sheets.insert(index - lower, original_sheet_name);
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file paths)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FilesParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SheetsParam::definition(),
+            RangeParam::definition(),
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
@@ -0,0 +1,505 @@
+use crate::database::column::Column;
+use crate::database::column::ColumnType;
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::read_sheets::ReadSheetsBindData;
+use crate::extension::read_sheets::ReadSheetsInitData;
+use crate::extension::read_sheets::ReadSheetsParameters;
+use crate::extension::read_sheets::ReadSheetsTableFunction;
+use crate::extension::DestParam;
+use crate::extension::ExtensionError;
+use crate::extension::FilesParam;
+use crate::extension::FormatParam;
+use crate::extension::NamedParam;
+use crate::extension::OverwriteParam;
+use crate::extension::Param;
+use crate::extension::PartitionByParam;
+use crate::spreadsheet::sheet::Sheet;
+use arrow::array::ArrayRef;
+use arrow::array::BooleanArray;
+use arrow::array::Date32Array;
+use arrow::array::Decimal128Array;
+use arrow::array::Float64Array;
+use arrow::array::Int64Array;
+use arrow::array::StringArray;
+use arrow::array::Time64MicrosecondArray;
+use arrow::array::TimestampMicrosecondArray;
+use arrow::csv;
+use arrow::datatypes::DataType;
+use arrow::datatypes::Field;
+use arrow::datatypes::Schema;
+use arrow::datatypes::SchemaRef;
+use arrow::datatypes::TimeUnit;
+use arrow::record_batch::RecordBatch;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::FlatVector;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::types::DuckString;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use libduckdb_sys::duckdb_date;
+use libduckdb_sys::duckdb_hugeint;
+use libduckdb_sys::duckdb_interval;
+use libduckdb_sys::duckdb_string_t;
+use libduckdb_sys::duckdb_time;
+use libduckdb_sys::duckdb_timestamp;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// Source of a Hive partition key, naming the directory level of each sheet
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Partition {
+    /// Name of the file the sheet was read from
+    FileName,
+    /// Name of the sheet
+    SheetName,
+}
+
+impl Partition {
+    /// Parses a partition column name ('file_name' or 'sheet_name').
+    fn parse(name: &str) -> Result<Self, RustySheetError> {
+        match name {
+            "file_name" => Ok(Self::FileName),
+            "sheet_name" => Ok(Self::SheetName),
+            _ => Err(ExtensionError::PartitionByError(name.to_owned()))?,
+        }
+    }
+
+    /// Returns the key of the partition directories, e.g. `sheet_name` in `sheet_name=Sales`.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::FileName => "file_name",
+            Self::SheetName => "sheet_name",
+        }
+    }
+
+    /// Returns the value of the partition key for the rows of a sheet.
+    fn value<'a>(&self, sheet: &'a Sheet) -> &'a str {
+        match self {
+            Self::FileName => sheet.file_name.as_str(),
+            Self::SheetName => sheet.name.as_str(),
+        }
+    }
+}
+
+/// Format of the exported files
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+enum ExportFormat {
+    /// Snappy-compressed Parquet files
+    #[default]
+    Parquet,
+    /// CSV files with a header row
+    Csv,
+}
+
+impl ExportFormat {
+    /// Parses an export format name ('parquet' or 'csv').
+    fn parse(name: &str) -> Result<Self, RustySheetError> {
+        match name {
+            "parquet" => Ok(Self::Parquet),
+            "csv" => Ok(Self::Csv),
+            _ => Err(ExtensionError::ExportFormatError(name.to_owned()))?,
+        }
+    }
+
+    /// Returns the extension of the exported files.
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// Writer of the file of one partition
+enum PartitionWriter {
+    Parquet(ArrowWriter<File>),
+    Csv(csv::Writer<BufWriter<File>>),
+}
+
+impl PartitionWriter {
+    /// Creates the file of a partition directory, creating the directory as well.
+    fn create(directory: &Path, format: ExportFormat, schema: &SchemaRef) -> Result<Self, RustySheetError> {
+        fs::create_dir_all(directory)?;
+        let file = File::create(directory.join(format!("data_0.{}", format.extension())))?;
+        Ok(match format {
+            ExportFormat::Parquet => {
+                let properties = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                Self::Parquet(ArrowWriter::try_new(file, schema.clone(), Some(properties))?)
+            }
+            ExportFormat::Csv => Self::Csv(csv::WriterBuilder::new()
+                .with_header(true)
+                .build(BufWriter::new(file))),
+        })
+    }
+
+    /// Appends the rows of a batch to the file.
+    fn write(&mut self, batch: &RecordBatch) -> Result<(), RustySheetError> {
+        match self {
+            Self::Parquet(writer) => writer.write(batch)?,
+            Self::Csv(writer) => writer.write(batch)?,
+        }
+        Ok(())
+    }
+
+    /// Writes the Parquet footer or flushes the CSV rows.
+    fn close(self) -> Result<(), RustySheetError> {
+        match self {
+            Self::Parquet(writer) => {
+                writer.close()?;
+            }
+            Self::Csv(writer) => writer.into_inner().flush()?,
+        }
+        Ok(())
+    }
+}
+
+#[repr(C)]
+/// Binding data for the export_sheets_partitioned table function
+pub(crate) struct ExportSheetsPartitionedBindData {
+    /// Analyzed sheets, read with every read_sheets option
+    sheets: ReadSheetsBindData,
+    /// Destination directory of the partitioned output
+    dest: String,
+    /// Partition keys, one directory level each
+    partition_by: Vec<Partition>,
+    /// Format of the exported files
+    format: ExportFormat,
+    /// Whether existing files of the destination are removed instead of failing
+    overwrite: bool,
+}
+
+impl ExportSheetsPartitionedBindData {
+    /// Returns the directory of the rows of a sheet, e.g. `dest/file_name=a.xlsx/sheet_name=Sales`.
+    fn partition_directory(&self, sheet: &Sheet) -> PathBuf {
+        let mut directory = PathBuf::from(&self.dest);
+        for partition in &self.partition_by {
+            directory.push(format!("{}={}", partition.name(), escape_partition_value(partition.value(sheet))));
+        }
+        directory
+    }
+
+    /// Writes every scanned chunk into the file of its partition
+    ///
+    /// # Arguments
+    /// * `sheets` - Loaded sheets of the scan
+    ///
+    /// # Returns
+    /// * `Result<i64, Box<dyn Error>>` - Number of exported rows or export error
+    fn export(&self, sheets: &ReadSheetsInitData) -> Result<i64, Box<dyn Error>> {
+        prepare_destination(&self.dest, self.overwrite).with_prefix(self.dest.as_str())?;
+        let columns = self.sheets.output_columns();
+        let schema = Arc::new(Schema::new(columns.iter()
+            .map(|column| Field::new(column.name.as_str(), arrow_type(column.kind), true))
+            .collect::<Vec<_>>()));
+        let logical_types = columns.iter()
+            .map(|column| column.kind.to_logical_type())
+            .collect::<Vec<_>>();
+
+        // Chunks of a sheet follow one another, unless the files are interleaved
+        let mut writers = HashMap::<PathBuf, PartitionWriter>::new();
+        let mut count = 0;
+        loop {
+            // A new chunk for each scan, as the scan expects the valid rows of a fresh one
+            let mut chunk = DataChunkHandle::new(&logical_types);
+            let Some(sheet) = sheets.scan(&self.sheets, &mut chunk)? else {
+                break;
+            };
+            if chunk.is_empty() {
+                continue;
+            }
+            let batch = to_record_batch(&schema, &columns, &chunk)?;
+            let writer = match writers.entry(self.partition_directory(sheet)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let writer = PartitionWriter::create(entry.key(), self.format, &schema)
+                        .with_prefix(entry.key().to_string_lossy().as_ref())?;
+                    entry.insert(writer)
+                }
+            };
+            writer.write(&batch)?;
+            count += chunk.len() as i64;
+        }
+        for writer in writers.into_values() {
+            writer.close()?;
+        }
+        Ok(count)
+    }
+}
+
+#[repr(C)]
+/// Initialization data for the export_sheets_partitioned table function
+pub(crate) struct ExportSheetsPartitionedInitData {
+    /// Loaded sheets, scanned by the export
+    sheets: ReadSheetsInitData,
+    /// Whether the sheets have been exported and the result row emitted
+    done: AtomicBool,
+}
+
+/// DuckDB table function exporting sheets as Hive-partitioned files, writing the rows of
+/// the scan straight into the files of their partition
+pub(crate) struct ExportSheetsPartitionedTableFunction;
+
+impl VTab for ExportSheetsPartitionedTableFunction {
+    type InitData = ExportSheetsPartitionedInitData;
+    type BindData = ExportSheetsPartitionedBindData;
+
+    /// Binds the table function by parsing parameters and analyzing the sheets as read_sheets does
+    ///
+    /// # Arguments
+    /// * `bind` - DuckDB bind information containing function parameters
+    ///
+    /// # Returns
+    /// * `Result<Self::BindData, Box<dyn Error>>` - Binding data with the analyzed sheets
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let format = FormatParam::read(bind)?
+            .map(|format| ExportFormat::parse(&format))
+            .transpose()?
+            .unwrap_or_default();
+        let partition_by = PartitionByParam::read(bind)?
+            .unwrap_or_else(|| vec!["file_name".to_owned(), "sheet_name".to_owned()])
+            .iter()
+            .map(|name| Partition::parse(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let dest = DestParam::read(bind, 1)?;
+        let overwrite = OverwriteParam::read(bind)?.unwrap_or(false);
+        let parameters = ReadSheetsParameters::read(bind, false)?;
+        let sheets = ReadSheetsBindData::try_from(&parameters)?;
+        bind.add_result_column(
+            "count",
+            LogicalTypeHandle::from(LogicalTypeId::Bigint),
+        );
+        Ok(ExportSheetsPartitionedBindData { sheets, dest, partition_by, format, overwrite })
+    }
+
+    /// Initializes the table function by reading the analyzed sheets with every column
+    ///
+    /// # Arguments
+    /// * `init` - DuckDB initialization information
+    ///
+    /// # Returns
+    /// * `Result<Self::InitData, Box<dyn Error>>` - Initialization data
+    fn init(init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        let bind = unsafe { &*init.get_bind_data::<Self::BindData>() };
+        let projections = (0..bind.sheets.output_columns().len()).collect();
+        Ok(ExportSheetsPartitionedInitData {
+            sheets: ReadSheetsInitData::read(&bind.sheets, projections)?,
+            done: AtomicBool::new(false),
+        })
+    }
+
+    /// Exports the sheets on the first call and emits a single row holding the number of exported rows
+    ///
+    /// # Arguments
+    /// * `func` - Table function information containing bind and init data
+    /// * `output` - Data chunk handle to populate with results
+    ///
+    /// # Returns
+    /// * `Result<(), Box<dyn Error>>` - Success or execution error
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        if init.done.swap(true, Ordering::Relaxed) {
+            output.set_len(0);
+        } else {
+            let count = bind.export(&init.sheets)?;
+            output.flat_vector(0).as_mut_slice::<i64>()[0] = count;
+            output.set_len(1);
+        }
+        Ok(())
+    }
+
+    /// Returns the required parameter types for the table function
+    ///
+    /// # Returns
+    /// * `Option<Vec<LogicalTypeHandle>>` - Required parameter types (files, destination)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FilesParam::kind(),
+            DestParam::kind(),
+        ])
+    }
+
+    /// Returns the named parameter definitions for the table function: those of read_sheets
+    /// and the partitioning options
+    ///
+    /// # Returns
+    /// * `Option<Vec<(String, LogicalTypeHandle)>>` - Named parameter definitions
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        let mut parameters = ReadSheetsTableFunction::named_parameters()?;
+        parameters.extend([
+            PartitionByParam::definition(),
+            FormatParam::definition(),
+            OverwriteParam::definition(),
+        ]);
+        Some(parameters)
+    }
+}
+
+/// Creates the destination directory; a destination holding files is emptied first with
+/// `overwrite`, and refused otherwise.
+fn prepare_destination(dest: &str, overwrite: bool) -> Result<(), RustySheetError> {
+    let path = Path::new(dest);
+    if path.is_dir() && fs::read_dir(path)?.next().is_some() {
+        if !overwrite {
+            Err(ExtensionError::ExportDestinationError(dest.to_owned()))?
+        }
+        fs::remove_dir_all(path)?;
+    }
+    fs::create_dir_all(path)?;
+    Ok(())
+}
+
+/// Percent-encodes the characters of a partition value that Hive-partitioned readers take
+/// as path or key separators, e.g. `Q1/Q2=x` as `Q1%2FQ2%3Dx`.
+fn escape_partition_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for char in value.chars() {
+        if char.is_control() || matches!(char, '"' | '#' | '%' | '\'' | '*' | '/' | ':' | '=' | '?' | '\\' | '[' | ']' | '^' | '{') {
+            let mut bytes = [0u8; 4];
+            for byte in char.encode_utf8(&mut bytes).bytes() {
+                let _ = write!(escaped, "%{byte:02X}");
+            }
+        } else {
+            escaped.push(char);
+        }
+    }
+    escaped
+}
+
+/// Returns the Arrow type a column of the given type is exported as; intervals are
+/// exported as text, since Parquet intervals cannot hold months, days and microseconds.
+fn arrow_type(kind: ColumnType) -> DataType {
+    match kind {
+        ColumnType::Boolean => DataType::Boolean,
+        ColumnType::BigInt => DataType::Int64,
+        ColumnType::Double => DataType::Float64,
+        ColumnType::Varchar | ColumnType::Interval => DataType::Utf8,
+        ColumnType::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+        ColumnType::Date => DataType::Date32,
+        ColumnType::Time => DataType::Time64(TimeUnit::Microsecond),
+        ColumnType::Decimal(width, scale) => DataType::Decimal128(width, scale as i8),
+    }
+}
+
+/// Converts the rows of a scanned chunk to a record batch of the exported schema.
+fn to_record_batch(schema: &SchemaRef, columns: &[Column], chunk: &DataChunkHandle) -> Result<RecordBatch, RustySheetError> {
+    let len = chunk.len();
+    let arrays = columns.iter()
+        .enumerate()
+        .map(|(index, column)| to_array(column.kind, &chunk.flat_vector(index), len))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(RecordBatch::try_new(schema.clone(), arrays)?)
+}
+
+/// Converts the first `len` values of a vector to an Arrow array, NULL rows included.
+fn to_array(kind: ColumnType, vector: &FlatVector, len: usize) -> Result<ArrayRef, RustySheetError> {
+    Ok(match kind {
+        ColumnType::Boolean => Arc::new(values(vector, len, |value: bool| value).collect::<BooleanArray>()),
+        ColumnType::BigInt => Arc::new(values(vector, len, |value: i64| value).collect::<Int64Array>()),
+        ColumnType::Double => Arc::new(values(vector, len, |value: f64| value).collect::<Float64Array>()),
+        ColumnType::Varchar => Arc::new(values(vector, len, |mut value: duckdb_string_t| DuckString::new(&mut value).as_str().into_owned())
+            .collect::<StringArray>()),
+        ColumnType::Timestamp => Arc::new(values(vector, len, |value: duckdb_timestamp| value.micros).collect::<TimestampMicrosecondArray>()),
+        ColumnType::Date => Arc::new(values(vector, len, |value: duckdb_date| value.days).collect::<Date32Array>()),
+        ColumnType::Time => Arc::new(values(vector, len, |value: duckdb_time| value.micros).collect::<Time64MicrosecondArray>()),
+        ColumnType::Interval => Arc::new(values(vector, len, |value: duckdb_interval| format_interval(&value)).collect::<StringArray>()),
+        ColumnType::Decimal(width, scale) => {
+            // Unscaled values, stored in the physical type DuckDB picks for the width
+            let array = match width {
+                1..=4 => values(vector, len, |value: i16| value as i128).collect::<Decimal128Array>(),
+                5..=9 => values(vector, len, |value: i32| value as i128).collect::<Decimal128Array>(),
+                10..=18 => values(vector, len, |value: i64| value as i128).collect::<Decimal128Array>(),
+                _ => values(vector, len, |value: duckdb_hugeint| ((value.upper as i128) << 64) | value.lower as i128)
+                    .collect::<Decimal128Array>(),
+            };
+            Arc::new(array.with_precision_and_scale(width, scale as i8)?)
+        }
+    })
+}
+
+/// Maps the first `len` values of a vector, None for NULL rows.
+fn values<'a, T: Copy + 'a, U>(vector: &'a FlatVector, len: usize, map: impl Fn(T) -> U + 'a) -> impl Iterator<Item = Option<U>> + 'a {
+    vector.as_slice_with_len::<T>(len)
+        .iter()
+        .enumerate()
+        .map(move |(row, value)| (!vector.row_is_null(row as u64)).then(|| map(*value)))
+}
+
+/// Formats an interval as text DuckDB casts back to an INTERVAL, e.g. `0 months 2 days 05:30:00`.
+fn format_interval(interval: &duckdb_interval) -> String {
+    let sign = if interval.micros < 0 { "-" } else { "" };
+    let micros = interval.micros.unsigned_abs();
+    let seconds = micros / 1_000_000;
+    let mut text = format!(
+        "{} months {} days {}{:02}:{:02}:{:02}",
+        interval.months,
+        interval.days,
+        sign,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+    );
+    if micros % 1_000_000 != 0 {
+        let _ = write!(text, ".{:06}", micros % 1_000_000);
+    }
+    text
+}
+
+/// Quotes a string as a SQL identifier.
+pub(super) fn quote_identifier(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partition_value_escape() {
+        assert_eq!(escape_partition_value("Sales 2024"), "Sales 2024");
+        assert_eq!(escape_partition_value("Q1/Q2=x"), "Q1%2FQ2%3Dx");
+        assert_eq!(escape_partition_value("100%"), "100%25");
+        assert_eq!(escape_partition_value("销售"), "销售");
+    }
+
+    #[test]
+    fn partition_names() {
+        assert_eq!(Partition::parse("file_name").unwrap(), Partition::FileName);
+        assert_eq!(Partition::parse("sheet_name").unwrap().name(), "sheet_name");
+        assert!(matches!(
+            Partition::parse("region"),
+            Err(RustySheetError::ExtensionError(ExtensionError::PartitionByError(name))) if name == "region",
+        ));
+    }
+
+    #[test]
+    fn interval_text() {
+        let interval = |months, days, micros| duckdb_interval { months, days, micros };
+        assert_eq!(format_interval(&interval(0, 2, 19_800_000_000)), "0 months 2 days 05:30:00");
+        assert_eq!(format_interval(&interval(14, 0, 1_500_000)), "14 months 0 days 00:00:01.500000");
+        assert_eq!(format_interval(&interval(0, 0, -90_000_000)), "0 months 0 days -00:01:30");
+    }
+}
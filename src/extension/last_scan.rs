@@ -0,0 +1,200 @@
+use crate::error::RustySheetError;
+use crate::extension::NamedParam;
+use crate::extension::ScanIdParam;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Number of finished read_sheets scans kept for rusty_sheet_last_scan
+const SCAN_HISTORY_LENGTH: usize = 16;
+
+/// Identifier of the next finished scan, starting at 1
+static NEXT_SCAN_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Finished scans as (scan_id, per-sheet statistics) pairs, oldest first
+type ScanHistory = VecDeque<(u64, Vec<SheetScanStats>)>;
+
+/// Statistics of the most recently finished read_sheets scans
+static SCAN_HISTORY: LazyLock<Mutex<ScanHistory>> = LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// Statistics of one sheet read by a read_sheets scan
+pub(super) struct SheetScanStats {
+    /// Display name of the spreadsheet file
+    pub(super) file_name: String,
+    /// Name of the sheet
+    pub(super) sheet_name: String,
+    /// Number of rows emitted to DuckDB so far
+    pub(super) rows: AtomicUsize,
+    /// Number of cells parsed from the sheet
+    pub(super) cells: usize,
+    /// Number of null values emitted in projected data columns so far
+    pub(super) nulls: AtomicUsize,
+    /// Time spent parsing the sheet
+    pub(super) parse_time: Duration,
+}
+
+impl SheetScanStats {
+    /// Creates the statistics of a parsed sheet, before any row is emitted.
+    pub(super) fn new(file_name: String, sheet_name: String, cells: usize, parse_time: Duration) -> Self {
+        SheetScanStats {
+            file_name,
+            sheet_name,
+            rows: AtomicUsize::new(0),
+            cells,
+            nulls: AtomicUsize::new(0),
+            parse_time,
+        }
+    }
+}
+
+/// Records the statistics of a finished scan, dropping the oldest scan beyond the history length.
+pub(super) fn record_scan(stats: Vec<SheetScanStats>) {
+    let scan_id = NEXT_SCAN_ID.fetch_add(1, Ordering::Relaxed);
+    let mut history = SCAN_HISTORY.lock().expect("Scan history lock");
+    history.push_back((scan_id, stats));
+    while history.len() > SCAN_HISTORY_LENGTH {
+        history.pop_front();
+    }
+}
+
+/// Parameters for listing the statistics of a finished scan
+struct LastScanParameters {
+    /// Identifier of the scan to list (default: the most recent one)
+    scan_id: Option<usize>,
+}
+
+impl TryFrom<&BindInfo> for LastScanParameters {
+    type Error = RustySheetError;
+
+    /// Constructs parameters from DuckDB bind information
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(LastScanParameters {
+            scan_id: ScanIdParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Bind data containing the statistics of the selected scan
+pub(crate) struct LastScanBindData {
+    /// Vector of tuples containing (scan_id, file_name, sheet_name, rows, cells, nulls, parse_ms)
+    stats: Vec<(u64, String, String, u64, u64, u64, f64)>,
+}
+
+impl From<&LastScanParameters> for LastScanBindData {
+    /// Copies the statistics of the selected scan, or none when it is no longer kept.
+    fn from(parameters: &LastScanParameters) -> Self {
+        let history = SCAN_HISTORY.lock().expect("Scan history lock");
+        let scan = match parameters.scan_id {
+            Some(scan_id) => history.iter().find(|(id, _)| *id == scan_id as u64),
+            None => history.back(),
+        };
+        let stats = scan
+            .map(|(scan_id, stats)| {
+                stats.iter()
+                    .map(|sheet| (
+                        *scan_id,
+                        sheet.file_name.to_owned(),
+                        sheet.sheet_name.to_owned(),
+                        sheet.rows.load(Ordering::Relaxed) as u64,
+                        sheet.cells as u64,
+                        sheet.nulls.load(Ordering::Relaxed) as u64,
+                        sheet.parse_time.as_secs_f64() * 1000.0,
+                    ))
+                    .collect()
+            })
+            .unwrap_or_default();
+        LastScanBindData { stats }
+    }
+}
+
+#[repr(C)]
+/// Initialization data for tracking iteration state across function calls
+pub(crate) struct LastScanInitData {
+    /// Atomic counter tracking the current position in the statistics vector
+    index: AtomicUsize,
+}
+
+/// DuckDB table function listing the per-sheet statistics of a finished read_sheets scan
+pub(crate) struct LastScanTableFunction;
+
+impl VTab for LastScanTableFunction {
+    type InitData = LastScanInitData;
+    type BindData = LastScanBindData;
+
+    /// Binds the table function by copying the statistics of the selected scan
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = LastScanParameters::try_from(bind)?;
+        let data = LastScanBindData::from(&parameters);
+        bind.add_result_column("scan_id", LogicalTypeHandle::from(LogicalTypeId::UBigint));
+        bind.add_result_column("file_name", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("sheet_name", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("rows", LogicalTypeHandle::from(LogicalTypeId::UBigint));
+        bind.add_result_column("cells", LogicalTypeHandle::from(LogicalTypeId::UBigint));
+        bind.add_result_column("nulls", LogicalTypeHandle::from(LogicalTypeId::UBigint));
+        bind.add_result_column("parse_ms", LogicalTypeHandle::from(LogicalTypeId::Double));
+        Ok(data)
+    }
+
+    /// Initializes the table function with iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(LastScanInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Executes the table function to produce output data chunks
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.stats.len().min(lower + 2048);
+        if lower < upper {
+            let mut scan_ids = output.flat_vector(0);
+            let files = output.flat_vector(1);
+            let sheets = output.flat_vector(2);
+            let mut rows = output.flat_vector(3);
+            let mut cells = output.flat_vector(4);
+            let mut nulls = output.flat_vector(5);
+            let mut parse_times = output.flat_vector(6);
+            for index in lower..upper {
+                let row = index - lower;
+                let (scan_id, file_name, sheet_name, row_count, cell_count, null_count, parse_ms) = &bind.stats[index];
+                scan_ids.as_mut_slice::<u64>()[row] = *scan_id;
+                files.insert(row, file_name.as_str());
+                sheets.insert(row, sheet_name.as_str());
+                rows.as_mut_slice::<u64>()[row] = *row_count;
+                cells.as_mut_slice::<u64>()[row] = *cell_count;
+                nulls.as_mut_slice::<u64>()[row] = *null_count;
+                parse_times.as_mut_slice::<f64>()[row] = *parse_ms;
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Returns the named parameter definitions for the table function
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            ScanIdParam::definition(),
+        ])
+    }
+}
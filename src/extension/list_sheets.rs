@@ -0,0 +1,158 @@
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::FileParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::SalvageParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::open_spreadsheet;
+use crate::spreadsheet::sheet::SheetInfo;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the list_sheets table function
+struct ListSheetsParameters {
+    /// Path to the spreadsheet file
+    file_name: String,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for ListSheetsParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(ListSheetsParameters {
+            file_name: FileParam::read(bind, 0)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Bind data for the list_sheets table function
+pub(crate) struct ListSheetsBindData {
+    /// Worksheets of the workbook in tab order
+    sheets: Vec<SheetInfo>,
+}
+
+impl TryFrom<&ListSheetsParameters> for ListSheetsBindData {
+    type Error = RustySheetError;
+
+    /// Read the worksheet list of the workbook, without reading any cell
+    fn try_from(parameters: &ListSheetsParameters) -> Result<Self, Self::Error> {
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        let sheets = spreadsheet.list_sheets()?;
+        Ok(ListSheetsBindData { sheets })
+    }
+}
+
+#[repr(C)]
+/// Init data for the list_sheets table function tracking iteration state
+pub(crate) struct ListSheetsInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function listing the worksheets of a workbook, for discovering sheet names cheaply
+pub(crate) struct ListSheetsTableFunction;
+
+impl VTab for ListSheetsTableFunction {
+    type InitData = ListSheetsInitData;
+    type BindData = ListSheetsBindData;
+
+    /// Bind phase: parse parameters, list the worksheets, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = ListSheetsParameters::try_from(bind)?;
+        let data = ListSheetsBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column(
+            "sheet_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "sheet_index",
+            LogicalTypeHandle::from(LogicalTypeId::Bigint),
+        );
+        bind.add_result_column(
+            "visibility",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "dimension",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(ListSheetsInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the worksheets to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.sheets.len().min(lower + 2048);
+        if lower < upper {
+            let names = output.flat_vector(0);
+            let mut indexes = output.flat_vector(1);
+            let visibilities = output.flat_vector(2);
+            let mut dimensions = output.flat_vector(3);
+            for index in lower..upper {
+                let sheet = &bind.sheets[index];
+                let row = index - lower;
+                names.insert(row, sheet.name.as_str());
+                indexes.as_mut_slice::<i64>()[row] = sheet.index as i64;
+                visibilities.insert(row, sheet.visibility.name());
+                match &sheet.dimension {
+                    Some(dimension) => dimensions.insert(row, dimension.as_str()),
+                    None => dimensions.set_null(row),
+                }
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
@@ -0,0 +1,73 @@
+use crate::helpers::metrics::metrics;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+#[repr(C)]
+/// Bind data containing the counters read when the query was bound
+pub(crate) struct MetricsBindData {
+    /// Vector of tuples containing (metric, value)
+    metrics: Vec<(String, u64)>,
+}
+
+#[repr(C)]
+/// Initialization data for tracking iteration state across function calls
+pub(crate) struct MetricsInitData {
+    /// Atomic counter tracking the current position in the metrics vector
+    index: AtomicUsize,
+}
+
+/// DuckDB table function listing the cumulative parse counters since the extension was loaded
+pub(crate) struct MetricsTableFunction;
+
+impl VTab for MetricsTableFunction {
+    type InitData = MetricsInitData;
+    type BindData = MetricsBindData;
+
+    /// Binds the table function by reading the current counters
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        bind.add_result_column("metric", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("value", LogicalTypeHandle::from(LogicalTypeId::UBigint));
+        Ok(MetricsBindData { metrics: metrics() })
+    }
+
+    /// Initializes the table function with iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(MetricsInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Executes the table function to produce output data chunks
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.metrics.len().min(lower + 2048);
+        if lower < upper {
+            let names = output.flat_vector(0);
+            let mut values = output.flat_vector(1);
+            for index in lower..upper {
+                let row = index - lower;
+                let (name, value) = &bind.metrics[index];
+                names.insert(row, name.as_str());
+                values.as_mut_slice::<u64>()[row] = *value;
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+}
@@ -3,23 +3,58 @@
 
 pub(crate) mod analyze_sheet;
 pub(crate) mod analyze_sheets;
+pub(crate) mod diagnose_union;
+pub(crate) mod duplicate_sheets;
+pub(crate) mod export_sheets_partitioned;
+pub(crate) mod last_scan;
+pub(crate) mod list_sheets;
+pub(crate) mod metrics;
+pub(crate) mod read_cell_protection;
+pub(crate) mod read_cells;
+pub(crate) mod read_comments;
+pub(crate) mod read_defined_names;
+pub(crate) mod read_formula_graph;
+pub(crate) mod read_hyperlinks;
+pub(crate) mod read_manifest;
 pub(crate) mod read_sheet;
 pub(crate) mod read_sheets;
+pub(crate) mod rusty_sheet_functions;
+pub(crate) mod selftest;
+pub(crate) mod settings;
+pub(crate) mod sheet_anomalies;
+pub(crate) mod sheet_checksum;
+pub(crate) mod sheet_ddl;
+pub(crate) mod sheet_headers;
+pub(crate) mod workbook_metadata;
+pub(crate) mod write_sheet;
 mod writer;
 
 use crate::database::bridge::ValueBridge;
 use crate::database::column::ColumnType;
+use crate::database::decimal::DecimalRounding;
+use crate::database::decimal::ScaleOverflow;
 use crate::database::range::Range;
+use crate::database::range::RangeSpec;
 use crate::error::RustySheetError;
+use crate::extension::read_manifest::ManifestEntry;
+use crate::extension::read_sheets::Expectation;
+use crate::extension::read_sheets::SchemaLock;
+use crate::extension::read_sheets::SheetPolicy;
+use crate::extension::writer::VarcharCase;
 use crate::helpers::reader::UnifiedReader;
+use crate::spreadsheet::locale::DateLocale;
+use crate::spreadsheet::reference::col_to_index;
+use crate::spreadsheet::reference::row_to_index;
 use duckdb::core::LogicalTypeHandle;
 use duckdb::core::LogicalTypeId;
 use duckdb::vtab::BindInfo;
 use duckdb::vtab::Value;
 use glob::glob;
 use glob::Pattern;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use thiserror::Error;
+use regex::Regex;
 
 /// Errors specific to extension parameter processing and validation.
 #[derive(Error, Debug)]
@@ -27,14 +62,92 @@ pub(crate) enum ExtensionError {
     #[error("No files matched wildcard '{0}'")]
     FileWildcardError(String),
 
-    #[error("No worksheets matched the wildcard pattern in any of the files")]
-    SheetNotFoundError,
+    #[error("Manifest entry {0} has no path")]
+    ManifestPathError(usize),
+
+    #[error("No worksheets matched the wildcard pattern in any of the files: {0}")]
+    SheetNotFoundError(String),
+
+    #[error("Spreadsheet '{0}': matched sheets {1} are empty")]
+    SheetEmptyError(String, String),
+
+    #[error("Unsupported {0} '{1}', expected 'skip' or 'error'")]
+    SheetPolicyError(&'static str, String),
 
     #[error("Spreadsheet '{0}': no sheets matched wildcard '{1}'")]
     SheetWildcardError(String, String),
 
+    #[error("Unsupported schema_lock '{0}', expected 'first', 'union' or 'file:<pattern>'")]
+    SchemaLockError(String),
+
+    #[error("schema_lock requires union_by_name=true")]
+    SchemaLockUnionError,
+
+    #[error("No file with sheets matched the schema_lock pattern '{0}'")]
+    SchemaLockFileError(String),
+
+    #[error("Invalid {0} '{1}', expected a count such as '1000' or bounds such as '900:1100', '900:' or ':1100'")]
+    ExpectationError(&'static str, String),
+
+    #[error("Spreadsheet '{0}': sheet '{1}' has {2} {3}, expected {4}")]
+    ExpectationFailedError(String, String, usize, &'static str, String),
+
+    #[error("Spreadsheet '{0}': no table named '{1}'")]
+    TableNotFoundError(String, String),
+
+    #[error("The table parameter cannot be combined with sheet, range or ranges")]
+    TableRangeError,
+
+    #[error("The range and ranges parameters cannot be combined")]
+    RangesError,
+
     #[error("[{0}]{1}!{2}: expected {3:?}, actual {4:?}")]
     ColumnTypeError(String, String, String, ColumnType, ColumnType),
+
+    #[error("Unsupported export format '{0}', expected 'parquet' or 'csv'")]
+    ExportFormatError(String),
+
+    #[error("Unsupported partition column '{0}', expected 'file_name' or 'sheet_name'")]
+    PartitionByError(String),
+
+    #[error("Destination '{0}' is not empty, set overwrite=true to replace its files")]
+    ExportDestinationError(String),
+
+    #[error("Invalid sample '{0}', expected 'reservoir:<rows>'")]
+    SampleError(String),
+
+    #[error("Unsupported date locale '{0}', expected one of {1}")]
+    DateLocaleError(String, String),
+
+    #[error("Unsupported varchar case '{0}', expected 'lower', 'upper' or 'preserve'")]
+    VarcharCaseError(String),
+
+    #[error("Column '{0}': unsupported type for a spreadsheet cell, cast it to VARCHAR")]
+    CellTypeError(String),
+
+    #[error("Unsupported header style '{0}', expected 'bold' or 'none'")]
+    HeaderStyleError(String),
+
+    #[error("Invalid cell reference '{0}', expected a cell such as 'A2'")]
+    CellReferenceError(String),
+
+    #[error("Unsupported workbook format '{0}', expected 'xlsx' or 'ods'")]
+    WorkbookFormatError(String),
+
+    #[error("Appending a worksheet is not supported for the '{0}' format")]
+    AppendFormatError(String),
+
+    #[error("auto_detect=false requires the columns parameter")]
+    AutoDetectColumnsError,
+
+    #[error("No column named '{0}' for its default value")]
+    DefaultColumnError(String),
+
+    #[error("Invalid default '{1}' of column '{0}': {2}")]
+    DefaultValueError(String, String, String),
+
+    #[error("Invalid missing_header_name '{0}', expected a template containing '{{n}}' or '{{col}}'")]
+    MissingHeaderNameError(String),
 }
 
 /// Trait for reading positional parameters from DuckDB bind info.
@@ -54,6 +167,12 @@ pub(crate) trait NamedParam<T> {
     /// Returns the DuckDB logical type for this parameter.
     fn kind() -> LogicalTypeHandle;
 
+    /// Returns the default value as a SQL literal, or None when there is none or
+    /// it differs between functions.
+    fn default_value() -> Option<&'static str> {
+        None
+    }
+
     /// Returns the parameter definition tuple (name, type) for DuckDB registration.
     fn definition() -> (String, LogicalTypeHandle) {
         (Self::name().to_string(), Self::kind())
@@ -75,19 +194,76 @@ pub(crate) trait NamedParam<T> {
 
 struct FileParam;
 struct FilesParam;
+struct ManifestParam;
+struct QueryParam;
 struct SheetParam;
+struct SheetNameParam;
 struct SheetsParam;
 struct RangeParam;
+struct RangesParam;
+struct SkipParam;
 struct HeaderParam;
+struct TableParam;
+struct TableNameParam;
 struct UnionByNameParam;
+struct SchemaLockParam;
+struct DedupeSheetsParam;
+struct InterleaveFilesParam;
+struct OnSheetMissingParam;
+struct OnSheetEmptyParam;
+struct ExpectRowsParam;
+struct ExpectColumnsParam;
 struct ColumnsParam;
+struct DefaultsParam;
 struct AnalyzeRowsParam;
+struct SampleParam;
+struct StableRowsParam;
+struct ValidateParam;
 struct NullsParam;
 struct ErrorAsNullParam;
+struct IgnoreErrorsParam;
 struct SkipEmptyRowsParam;
 struct EndAtEmptyRowParam;
+struct MidnightAsDateParam;
+struct EpochDateAsTimeParam;
+struct DurationAsIntervalParam;
+struct DisplayValuesParam;
+struct IncludeHiddenColumnsParam;
+struct RequireHeaderParam;
+struct EmptyOkParam;
+struct AutoDetectParam;
+struct AllVarcharParam;
+struct HeaderAliasesParam;
+struct MissingHeaderNameParam;
+struct MaxHeaderLengthParam;
+struct NormalizeNamesParam;
+struct DateLocaleParam;
+struct SalvageParam;
+struct LenientParam;
+struct OriginalValuesParam;
+struct FormulasParam;
+struct FixEncodingParam;
+struct SnapshotParam;
+struct PasswordParam;
+struct SpillThresholdParam;
+struct MaxCellLengthParam;
+struct TruncationSuffixParam;
+struct DecimalRoundingParam;
+struct DecimalScaleOverflowParam;
+struct VarcharCaseParam;
+struct DatesAsVarcharParam;
+struct PhoneticSuffixParam;
 struct FileNameColumnParam;
 struct SheetNameColumnParam;
+struct DestParam;
+struct PartitionByParam;
+struct FormatParam;
+struct AppendParam;
+struct OverwriteParam;
+struct HeaderStyleParam;
+struct FreezePanesParam;
+struct AutofilterParam;
+struct ScanIdParam;
 
 /// Parameter handler for file name (positional parameter).
 impl Param<String> for FileParam {
@@ -101,6 +277,67 @@ impl Param<String> for FileParam {
     }
 }
 
+/// Parameter handler for export destination directory (positional parameter).
+impl Param<String> for DestParam {
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn read(bind: &BindInfo, index: u64) -> Result<String, RustySheetError> {
+        let value = bind.get_parameter(index);
+        Ok(value.to_string())
+    }
+}
+
+/// Parameter handler for a manifest listing the files to read, each with its own sheet and range.
+impl Param<Vec<ManifestEntry>> for ManifestParam {
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+            ("path", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ("sheet", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ("range", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+        ]))
+    }
+
+    fn read(bind: &BindInfo, index: u64) -> Result<Vec<ManifestEntry>, RustySheetError> {
+        let mut entries = Vec::<ManifestEntry>::new();
+        for (position, entry) in bind.get_parameter(index).to_list().iter().enumerate() {
+            let mut file_name = None::<String>;
+            let mut sheet_name = None::<Pattern>;
+            let mut range = None::<RangeSpec>;
+            for (field, value) in entry.to_struct_properties() {
+                if value.is_null() {
+                    continue;
+                }
+                match field.as_str() {
+                    "path" => file_name = Some(value.to_string()),
+                    "sheet" => sheet_name = Some(Pattern::new(&value.to_string())?),
+                    "range" => range = Some(RangeSpec::try_from(value.to_varchar().as_str())?),
+                    _ => (),
+                }
+            }
+            entries.push(ManifestEntry {
+                file_name: file_name.ok_or(ExtensionError::ManifestPathError(position + 1))?,
+                sheet_name,
+                range,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+/// Parameter handler for the SQL query to export (positional parameter).
+impl Param<String> for QueryParam {
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn read(bind: &BindInfo, index: u64) -> Result<String, RustySheetError> {
+        let value = bind.get_parameter(index);
+        Ok(value.to_string())
+    }
+}
+
 /// Parameter handler for file patterns with glob expansion.
 impl Param<Vec<String>> for FilesParam {
     fn kind() -> LogicalTypeHandle {
@@ -118,7 +355,11 @@ impl Param<Vec<String>> for FilesParam {
         let mut files = Vec::<String>::new();
         for wildcard in &wildcards {
             if UnifiedReader::is_remote_url(&wildcard) {
-                files.push(wildcard.to_owned());
+                if wildcard.contains(['*', '?', '[']) {
+                    files.extend(UnifiedReader::glob_remote(wildcard)?);
+                } else {
+                    files.push(wildcard.to_owned());
+                }
             } else if let Ok(paths) = glob(&wildcard) {
                 for path in paths.filter_map(Result::ok) {
                     files.push(path.to_str().unwrap().to_string());
@@ -149,6 +390,21 @@ impl NamedParam<Pattern> for SheetParam {
     }
 }
 
+/// Parameter handler for the literal name of a sheet to write.
+impl NamedParam<String> for SheetNameParam {
+    fn name() -> &'static str {
+        "sheet"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        Ok(value.to_string())
+    }
+}
+
 /// Parameter handler for multiple sheet specifications with file filtering.
 impl NamedParam<Vec<(Option<Pattern>, Pattern)>> for SheetsParam {
     fn name() -> &'static str {
@@ -169,8 +425,8 @@ impl NamedParam<Vec<(Option<Pattern>, Pattern)>> for SheetsParam {
     }
 }
 
-/// Parameter handler for Excel-style range specifications.
-impl NamedParam<Range> for RangeParam {
+/// Parameter handler for Excel-style range specifications or defined names.
+impl NamedParam<RangeSpec> for RangeParam {
     fn name() -> &'static str {
         "range"
     }
@@ -179,8 +435,75 @@ impl NamedParam<Range> for RangeParam {
         LogicalTypeHandle::from(LogicalTypeId::Varchar)
     }
 
-    fn cast(value: Value) -> Result<Range, RustySheetError> {
-        Range::try_from(value.to_varchar().as_str())
+    fn cast(value: Value) -> Result<RangeSpec, RustySheetError> {
+        RangeSpec::try_from(value.to_varchar().as_str())
+    }
+}
+
+/// Parameter handler for disjoint blocks of cells read as one table (['A1:C100', 'F1:H100']).
+impl NamedParam<Vec<Range>> for RangesParam {
+    fn name() -> &'static str {
+        "ranges"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar))
+    }
+
+    fn cast(value: Value) -> Result<Vec<Range>, RustySheetError> {
+        value.to_list()
+            .iter()
+            .map(|range| Range::try_from(range.to_varchar().as_str()))
+            .collect()
+    }
+}
+
+/// Parameter handler for the number of leading rows dropped before header detection.
+impl NamedParam<usize> for SkipParam {
+    fn name() -> &'static str {
+        "skip"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::UInteger)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("0")
+    }
+
+    fn cast(value: Value) -> Result<usize, RustySheetError> {
+        Ok(value.to_usize())
+    }
+}
+
+/// Parameter handler for the name of an Excel Table to read.
+impl NamedParam<String> for TableParam {
+    fn name() -> &'static str {
+        "table"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        Ok(value.to_string())
+    }
+}
+
+/// Parameter handler for the name of the table created by a generated CREATE TABLE statement.
+impl NamedParam<String> for TableNameParam {
+    fn name() -> &'static str {
+        "table_name"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        Ok(value.to_string())
     }
 }
 
@@ -194,6 +517,10 @@ impl NamedParam<bool> for HeaderParam {
         LogicalTypeHandle::from(LogicalTypeId::Boolean)
     }
 
+    fn default_value() -> Option<&'static str> {
+        Some("true")
+    }
+
     fn cast(value: Value) -> Result<bool, RustySheetError> {
         Ok(value.to_bool())
     }
@@ -209,139 +536,1154 @@ impl NamedParam<bool> for UnionByNameParam {
         LogicalTypeHandle::from(LogicalTypeId::Boolean)
     }
 
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
     fn cast(value: Value) -> Result<bool, RustySheetError> {
         Ok(value.to_bool())
     }
 }
 
-/// Parameter handler for column type overrides.
-impl NamedParam<Vec<(Pattern, ColumnType)>> for ColumnsParam {
+/// Parameter handler for the schema of a union by name ('union', 'first' or 'file:<pattern>').
+impl NamedParam<SchemaLock> for SchemaLockParam {
     fn name() -> &'static str {
-        "columns"
+        "schema_lock"
     }
 
     fn kind() -> LogicalTypeHandle {
-        LogicalTypeHandle::map(
-            &LogicalTypeHandle::from(LogicalTypeId::Varchar),
-            &LogicalTypeHandle::from(LogicalTypeId::Varchar),
-        )
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
     }
 
-    fn cast(value: Value) -> Result<Vec<(Pattern, ColumnType)>, RustySheetError> {
-        let columns = value
-            .to_map_entries()
-            .iter()
-            .map(|(key, value)| parse_column(key, value))
-            .collect::<Result<Vec<(Pattern, ColumnType)>, RustySheetError>>()?;
-        Ok(columns)
+    fn default_value() -> Option<&'static str> {
+        Some("'union'")
+    }
+
+    fn cast(value: Value) -> Result<SchemaLock, RustySheetError> {
+        SchemaLock::parse(&value.to_string())
     }
 }
 
-/// Parameter handler for number of rows to analyze for type detection.
-impl NamedParam<usize> for AnalyzeRowsParam {
+/// Parameter handler for skipping sheets identical to an earlier one in a union.
+impl NamedParam<bool> for DedupeSheetsParam {
     fn name() -> &'static str {
-        "analyze_rows"
+        "dedupe_sheets"
     }
 
     fn kind() -> LogicalTypeHandle {
-        LogicalTypeHandle::from(LogicalTypeId::UInteger)
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
     }
 
-    fn cast(value: Value) -> Result<usize, RustySheetError> {
-        Ok(value.to_usize())
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
     }
 }
 
-/// Parameter handler for null literals
-impl NamedParam<HashSet<String>> for NullsParam {
+/// Parameter handler for alternating the chunks of the files instead of reading files one after another.
+impl NamedParam<bool> for InterleaveFilesParam {
     fn name() -> &'static str {
-        "nulls"
+        "interleave_files"
     }
 
     fn kind() -> LogicalTypeHandle {
-        LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar))
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
     }
 
-    fn cast(value: Value) -> Result<HashSet<String>, RustySheetError> {
-        Ok(value
-            .to_list()
-            .iter()
-            .map(|parameter| parameter.to_string())
-            .collect::<HashSet<_>>())
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
     }
 }
 
-/// Parameter handler for error handling behavior (fail-fast vs null conversion).
-impl NamedParam<bool> for ErrorAsNullParam {
+/// Parameter handler for files none of whose sheets match the sheet patterns ('skip' or 'error').
+impl NamedParam<SheetPolicy> for OnSheetMissingParam {
     fn name() -> &'static str {
-        "error_as_null"
+        "on_sheet_missing"
     }
 
     fn kind() -> LogicalTypeHandle {
-        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
     }
 
-    fn cast(value: Value) -> Result<bool, RustySheetError> {
-        Ok(value.to_bool())
+    fn default_value() -> Option<&'static str> {
+        Some("'skip'")
+    }
+
+    fn cast(value: Value) -> Result<SheetPolicy, RustySheetError> {
+        SheetPolicy::parse(Self::name(), &value.to_string())
     }
 }
 
-/// Parameter handler for skipping empty rows during processing.
-impl NamedParam<bool> for SkipEmptyRowsParam {
+/// Parameter handler for matched sheets without any data ('skip' or 'error').
+impl NamedParam<SheetPolicy> for OnSheetEmptyParam {
     fn name() -> &'static str {
-        "skip_empty_rows"
+        "on_sheet_empty"
     }
 
     fn kind() -> LogicalTypeHandle {
-        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
     }
 
-    fn cast(value: Value) -> Result<bool, RustySheetError> {
-        Ok(value.to_bool())
+    fn default_value() -> Option<&'static str> {
+        Some("'skip'")
+    }
+
+    fn cast(value: Value) -> Result<SheetPolicy, RustySheetError> {
+        SheetPolicy::parse(Self::name(), &value.to_string())
     }
 }
 
-/// Parameter handler for stopping data extraction at first empty row.
-impl NamedParam<bool> for EndAtEmptyRowParam {
+/// Parameter handler for the number of data rows each sheet must have ('1000', '900:1100', '900:' or ':1100').
+impl NamedParam<Expectation> for ExpectRowsParam {
     fn name() -> &'static str {
-        "end_at_empty_row"
+        "expect_rows"
     }
 
     fn kind() -> LogicalTypeHandle {
-        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
     }
 
-    fn cast(value: Value) -> Result<bool, RustySheetError> {
-        Ok(value.to_bool())
+    fn cast(value: Value) -> Result<Expectation, RustySheetError> {
+        Expectation::parse(Self::name(), &value.to_string())
     }
 }
 
-impl NamedParam<String> for FileNameColumnParam {
+/// Parameter handler for the number of data columns each sheet must have ('12', '10:14', '10:' or ':14').
+impl NamedParam<Expectation> for ExpectColumnsParam {
     fn name() -> &'static str {
-        "file_name_column"
+        "expect_columns"
     }
 
     fn kind() -> LogicalTypeHandle {
         LogicalTypeHandle::from(LogicalTypeId::Varchar)
     }
 
-    fn cast(value: Value) -> Result<String, RustySheetError> {
-        Ok(value.to_string())
+    fn cast(value: Value) -> Result<Expectation, RustySheetError> {
+        Expectation::parse(Self::name(), &value.to_string())
     }
 }
 
-impl NamedParam<String> for SheetNameColumnParam {
+/// Parameter handler for column type overrides.
+impl NamedParam<Vec<(Pattern, ColumnType)>> for ColumnsParam {
     fn name() -> &'static str {
-        "sheet_name_column"
+        "columns"
     }
 
     fn kind() -> LogicalTypeHandle {
-        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+        LogicalTypeHandle::map(
+            &LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            &LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )
     }
 
-    fn cast(value: Value) -> Result<String, RustySheetError> {
-        Ok(value.to_string())
+    fn cast(value: Value) -> Result<Vec<(Pattern, ColumnType)>, RustySheetError> {
+        let columns = value
+            .to_map_entries()
+            .iter()
+            .map(|(key, value)| parse_column(key, value))
+            .collect::<Result<Vec<(Pattern, ColumnType)>, RustySheetError>>()?;
+        Ok(columns)
+    }
+}
+
+/// Parameter handler for the values written in place of NULL cells, by column name ({'qty': 0}).
+impl NamedParam<HashMap<String, String>> for DefaultsParam {
+    fn name() -> &'static str {
+        "defaults"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::map(
+            &LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            &LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )
+    }
+
+    fn cast(value: Value) -> Result<HashMap<String, String>, RustySheetError> {
+        Ok(value.to_map_entries()
+            .into_iter()
+            .map(|(name, default)| (name.to_string(), default.to_string()))
+            .collect())
+    }
+}
+
+/// Parameter handler for number of rows to analyze for type detection.
+impl NamedParam<usize> for AnalyzeRowsParam {
+    fn name() -> &'static str {
+        "analyze_rows"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::UInteger)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("10")
+    }
+
+    fn cast(value: Value) -> Result<usize, RustySheetError> {
+        Ok(value.to_usize())
+    }
+}
+
+/// Rows analyzed for type detection when `analyze_rows` is not given: 10, or up to 1000
+/// when `stable_rows` ends the analysis as soon as the column types are stable.
+pub(crate) fn default_analyze_rows(stable_rows: Option<usize>) -> usize {
+    if stable_rows.is_some() { 1000 } else { 10 }
+}
+
+/// Parameter handler for the consecutive values of one type after which a column's type
+/// is stable, ending the type detection early.
+impl NamedParam<usize> for StableRowsParam {
+    fn name() -> &'static str {
+        "stable_rows"
     }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::UInteger)
+    }
+
+    fn cast(value: Value) -> Result<usize, RustySheetError> {
+        Ok(value.to_usize())
+    }
+}
+
+/// Parameter handler for reservoir sampling ('reservoir:<rows>') during type detection.
+impl NamedParam<usize> for SampleParam {
+    fn name() -> &'static str {
+        "sample"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<usize, RustySheetError> {
+        let sample = value.to_string();
+        sample.split_once(':')
+            .filter(|(method, _)| method.trim().eq_ignore_ascii_case("reservoir"))
+            .and_then(|(_, rows)| rows.trim().parse::<usize>().ok())
+            .filter(|rows| *rows > 0)
+            .ok_or_else(|| ExtensionError::SampleError(sample.to_owned()).into())
+    }
+}
+
+/// Parameter handler for dry-running the conversion of the analyzed rows to the detected types.
+impl NamedParam<bool> for ValidateParam {
+    fn name() -> &'static str {
+        "validate"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for null literals
+impl NamedParam<HashSet<String>> for NullsParam {
+    fn name() -> &'static str {
+        "nulls"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar))
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("['']")
+    }
+
+    fn cast(value: Value) -> Result<HashSet<String>, RustySheetError> {
+        Ok(value
+            .to_list()
+            .iter()
+            .map(|parameter| parameter.to_string())
+            .collect::<HashSet<_>>())
+    }
+}
+
+/// Parameter handler for error handling behavior (fail-fast vs null conversion).
+impl NamedParam<bool> for ErrorAsNullParam {
+    fn name() -> &'static str {
+        "error_as_null"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for dropping the rows with a cell that cannot be converted to its column type.
+impl NamedParam<bool> for IgnoreErrorsParam {
+    fn name() -> &'static str {
+        "ignore_errors"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for skipping empty rows during processing.
+impl NamedParam<bool> for SkipEmptyRowsParam {
+    fn name() -> &'static str {
+        "skip_empty_rows"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for stopping data extraction at first empty row.
+impl NamedParam<bool> for EndAtEmptyRowParam {
+    fn name() -> &'static str {
+        "end_at_empty_row"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for classifying midnight ISO datetimes as dates.
+impl NamedParam<bool> for MidnightAsDateParam {
+    fn name() -> &'static str {
+        "midnight_as_date"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for classifying ISO datetimes on the epoch date as times.
+impl NamedParam<bool> for EpochDateAsTimeParam {
+    fn name() -> &'static str {
+        "epoch_date_as_time"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for classifying text durations as intervals.
+impl NamedParam<bool> for DurationAsIntervalParam {
+    fn name() -> &'static str {
+        "duration_as_interval"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for rendering numeric cells through their number format.
+impl NamedParam<bool> for DisplayValuesParam {
+    fn name() -> &'static str {
+        "display_values"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for including hidden columns.
+impl NamedParam<bool> for IncludeHiddenColumnsParam {
+    fn name() -> &'static str {
+        "include_hidden_columns"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("true")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for rejecting sheets whose header row holds no names.
+impl NamedParam<bool> for RequireHeaderParam {
+    fn name() -> &'static str {
+        "require_header"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for inferring the columns instead of taking them from `columns` alone.
+impl NamedParam<bool> for AutoDetectParam {
+    fn name() -> &'static str {
+        "auto_detect"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("true")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for typing every column as VARCHAR instead of detecting its type.
+impl NamedParam<bool> for AllVarcharParam {
+    fn name() -> &'static str {
+        "all_varchar"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for returning zero rows from an empty sheet instead of failing.
+impl NamedParam<bool> for EmptyOkParam {
+    fn name() -> &'static str {
+        "empty_ok"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for canonical column names of localized headers ({'Amount': ['Montant', 'Betrag']}).
+impl NamedParam<HashMap<String, String>> for HeaderAliasesParam {
+    fn name() -> &'static str {
+        "header_aliases"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::map(
+            &LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            &LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+        )
+    }
+
+    fn cast(value: Value) -> Result<HashMap<String, String>, RustySheetError> {
+        let mut aliases = HashMap::<String, String>::new();
+        for (name, headers) in value.to_map_entries() {
+            let name = name.to_string();
+            for header in headers.to_list() {
+                aliases.insert(header.to_string(), name.to_owned());
+            }
+        }
+        Ok(aliases)
+    }
+}
+
+/// Parameter handler for the template naming columns without a header ('column_{n}').
+impl NamedParam<String> for MissingHeaderNameParam {
+    fn name() -> &'static str {
+        "missing_header_name"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        let template = value.to_string();
+        // Without a placeholder, every column without a header would get the same name
+        if !template.contains("{n}") && !template.contains("{col}") {
+            Err(ExtensionError::MissingHeaderNameError(template.to_owned()))?
+        }
+        Ok(template)
+    }
+}
+
+/// Parameter handler for the maximum number of characters of column names taken from headers.
+impl NamedParam<usize> for MaxHeaderLengthParam {
+    fn name() -> &'static str {
+        "max_header_length"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::UInteger)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("255")
+    }
+
+    fn cast(value: Value) -> Result<usize, RustySheetError> {
+        Ok(value.to_usize())
+    }
+}
+
+/// Parameter handler for making the column names taken from headers usable without quoting.
+impl NamedParam<bool> for NormalizeNamesParam {
+    fn name() -> &'static str {
+        "normalize_names"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for the locale of month names in text dates ('de', 'fr', 'cs', ...).
+impl NamedParam<DateLocale> for DateLocaleParam {
+    fn name() -> &'static str {
+        "date_locale"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<DateLocale, RustySheetError> {
+        let code = value.to_string();
+        DateLocale::from_code(&code)
+            .ok_or_else(|| ExtensionError::DateLocaleError(code.to_owned(), DateLocale::CODES.join(", ")).into())
+    }
+}
+
+/// Parameter handler for recovering archives with a damaged central directory.
+impl NamedParam<bool> for SalvageParam {
+    fn name() -> &'static str {
+        "salvage"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for keeping the cells parsed before a structural error.
+impl NamedParam<bool> for LenientParam {
+    fn name() -> &'static str {
+        "lenient"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for reading the values cells held before the tracked changes.
+impl NamedParam<bool> for OriginalValuesParam {
+    fn name() -> &'static str {
+        "original_values"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for reading the formulas of formula cells instead of their cached values.
+impl NamedParam<bool> for FormulasParam {
+    fn name() -> &'static str {
+        "formulas"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for repairing text whose UTF-8 bytes were decoded as Windows-1252.
+impl NamedParam<bool> for FixEncodingParam {
+    fn name() -> &'static str {
+        "fix_encoding"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for reading a consistent copy of local files that may change meanwhile.
+impl NamedParam<bool> for SnapshotParam {
+    fn name() -> &'static str {
+        "snapshot"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for the password of encrypted workbooks.
+impl NamedParam<String> for PasswordParam {
+    fn name() -> &'static str {
+        "password"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        Ok(value.to_string())
+    }
+}
+
+/// Parameter handler for the number of shared strings above which the shared string table is spilled to disk.
+impl NamedParam<usize> for SpillThresholdParam {
+    fn name() -> &'static str {
+        "spill_threshold"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::UInteger)
+    }
+
+    fn cast(value: Value) -> Result<usize, RustySheetError> {
+        Ok(value.to_usize())
+    }
+}
+
+/// Parameter handler for the maximum number of characters kept in text cells.
+impl NamedParam<usize> for MaxCellLengthParam {
+    fn name() -> &'static str {
+        "max_cell_length"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::UInteger)
+    }
+
+    fn cast(value: Value) -> Result<usize, RustySheetError> {
+        Ok(value.to_usize())
+    }
+}
+
+/// Parameter handler for the suffix appended to truncated text cells (e.g. '…').
+impl NamedParam<String> for TruncationSuffixParam {
+    fn name() -> &'static str {
+        "truncation_suffix"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        Ok(value.to_string())
+    }
+}
+
+/// Parameter handler for the rounding of digits beyond the scale of DECIMAL columns ('half_even' or 'half_up').
+impl NamedParam<DecimalRounding> for DecimalRoundingParam {
+    fn name() -> &'static str {
+        "decimal_rounding"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("'half_even'")
+    }
+
+    fn cast(value: Value) -> Result<DecimalRounding, RustySheetError> {
+        DecimalRounding::parse(&value.to_string())
+    }
+}
+
+/// Parameter handler for values exceeding the scale of DECIMAL columns ('round', 'truncate' or 'error').
+impl NamedParam<ScaleOverflow> for DecimalScaleOverflowParam {
+    fn name() -> &'static str {
+        "decimal_scale_overflow"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("'round'")
+    }
+
+    fn cast(value: Value) -> Result<ScaleOverflow, RustySheetError> {
+        ScaleOverflow::parse(&value.to_string())
+    }
+}
+
+/// Parameter handler for case folding of VARCHAR values ('lower', 'upper' or 'preserve').
+impl NamedParam<VarcharCase> for VarcharCaseParam {
+    fn name() -> &'static str {
+        "varchar_case"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("'preserve'")
+    }
+
+    fn cast(value: Value) -> Result<VarcharCase, RustySheetError> {
+        VarcharCase::parse(&value.to_string())
+    }
+}
+
+/// Parameter handler for writing date, time and timestamp columns as ISO 8601 strings.
+impl NamedParam<bool> for DatesAsVarcharParam {
+    fn name() -> &'static str {
+        "dates_as_varchar"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for the suffix naming the phonetic reading column of each VARCHAR column.
+impl NamedParam<String> for PhoneticSuffixParam {
+    fn name() -> &'static str {
+        "phonetic_suffix"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        Ok(value.to_string())
+    }
+}
+
+impl NamedParam<String> for FileNameColumnParam {
+    fn name() -> &'static str {
+        "file_name_column"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        Ok(value.to_string())
+    }
+}
+
+impl NamedParam<String> for SheetNameColumnParam {
+    fn name() -> &'static str {
+        "sheet_name_column"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        Ok(value.to_string())
+    }
+}
+
+/// Parameter handler for export partition columns.
+impl NamedParam<Vec<String>> for PartitionByParam {
+    fn name() -> &'static str {
+        "partition_by"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar))
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("['file_name', 'sheet_name']")
+    }
+
+    fn cast(value: Value) -> Result<Vec<String>, RustySheetError> {
+        Ok(value
+            .to_list()
+            .iter()
+            .map(|parameter| parameter.to_string())
+            .collect::<Vec<_>>())
+    }
+}
+
+/// Parameter handler for output file format, lowercased; each function checks the
+/// formats it supports (parquet or csv for exports, xlsx or ods for worksheets).
+impl NamedParam<String> for FormatParam {
+    fn name() -> &'static str {
+        "format"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        Ok(value.to_string().to_ascii_lowercase())
+    }
+}
+
+/// Parameter handler for adding a worksheet to an existing workbook instead of replacing it.
+impl NamedParam<bool> for AppendParam {
+    fn name() -> &'static str {
+        "append"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for removing the existing files of an export destination instead of failing.
+impl NamedParam<bool> for OverwriteParam {
+    fn name() -> &'static str {
+        "overwrite"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for the style of the header row of a written worksheet ('bold' or 'none').
+impl NamedParam<String> for HeaderStyleParam {
+    fn name() -> &'static str {
+        "header_style"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("'none'")
+    }
+
+    fn cast(value: Value) -> Result<String, RustySheetError> {
+        let style = value.to_string().to_ascii_lowercase();
+        match style.as_str() {
+            "bold" | "none" => Ok(style),
+            _ => Err(ExtensionError::HeaderStyleError(value.to_string()))?,
+        }
+    }
+}
+
+/// Parameter handler for the top-left cell of the scrolling pane of a written worksheet (e.g. 'A2').
+impl NamedParam<(usize, usize)> for FreezePanesParam {
+    fn name() -> &'static str {
+        "freeze_panes"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Varchar)
+    }
+
+    fn cast(value: Value) -> Result<(usize, usize), RustySheetError> {
+        let reference = value.to_string().to_ascii_uppercase();
+        let pattern = Regex::new(r"^([A-Z]{1,3})([1-9][0-9]{0,6})$").expect("Hardcode regex pattern");
+        pattern.captures(&reference)
+            .and_then(|captures| Some((row_to_index(&captures[2])?, col_to_index(&captures[1])?)))
+            .filter(|&(row, col)| row < 1 << 20 && col < 1 << 14)
+            .ok_or_else(|| ExtensionError::CellReferenceError(value.to_string()).into())
+    }
+}
+
+/// Parameter handler for adding an autofilter over the cells of a written worksheet.
+impl NamedParam<bool> for AutofilterParam {
+    fn name() -> &'static str {
+        "autofilter"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::Boolean)
+    }
+
+    fn default_value() -> Option<&'static str> {
+        Some("false")
+    }
+
+    fn cast(value: Value) -> Result<bool, RustySheetError> {
+        Ok(value.to_bool())
+    }
+}
+
+/// Parameter handler for the identifier of the read_sheets scan listed by rusty_sheet_last_scan.
+impl NamedParam<usize> for ScanIdParam {
+    fn name() -> &'static str {
+        "scan_id"
+    }
+
+    fn kind() -> LogicalTypeHandle {
+        LogicalTypeHandle::from(LogicalTypeId::UInteger)
+    }
+
+    fn cast(value: Value) -> Result<usize, RustySheetError> {
+        Ok(value.to_usize())
+    }
+}
+
+/// Looks up the default value of a named parameter by its SQL name.
+pub(crate) fn named_param_default(name: &str) -> Option<&'static str> {
+    [
+        (SheetParam::name(), SheetParam::default_value()),
+        (SheetsParam::name(), SheetsParam::default_value()),
+        (RangeParam::name(), RangeParam::default_value()),
+        (RangesParam::name(), RangesParam::default_value()),
+        (SkipParam::name(), SkipParam::default_value()),
+        (TableParam::name(), TableParam::default_value()),
+        (TableNameParam::name(), TableNameParam::default_value()),
+        (HeaderParam::name(), HeaderParam::default_value()),
+        (UnionByNameParam::name(), UnionByNameParam::default_value()),
+        (SchemaLockParam::name(), SchemaLockParam::default_value()),
+        (DedupeSheetsParam::name(), DedupeSheetsParam::default_value()),
+        (InterleaveFilesParam::name(), InterleaveFilesParam::default_value()),
+        (OnSheetMissingParam::name(), OnSheetMissingParam::default_value()),
+        (OnSheetEmptyParam::name(), OnSheetEmptyParam::default_value()),
+        (ExpectRowsParam::name(), ExpectRowsParam::default_value()),
+        (ExpectColumnsParam::name(), ExpectColumnsParam::default_value()),
+        (ColumnsParam::name(), ColumnsParam::default_value()),
+        (DefaultsParam::name(), DefaultsParam::default_value()),
+        (AnalyzeRowsParam::name(), AnalyzeRowsParam::default_value()),
+        (SampleParam::name(), SampleParam::default_value()),
+        (StableRowsParam::name(), StableRowsParam::default_value()),
+        (ValidateParam::name(), ValidateParam::default_value()),
+        (NullsParam::name(), NullsParam::default_value()),
+        (ErrorAsNullParam::name(), ErrorAsNullParam::default_value()),
+        (IgnoreErrorsParam::name(), IgnoreErrorsParam::default_value()),
+        (SkipEmptyRowsParam::name(), SkipEmptyRowsParam::default_value()),
+        (EndAtEmptyRowParam::name(), EndAtEmptyRowParam::default_value()),
+        (MidnightAsDateParam::name(), MidnightAsDateParam::default_value()),
+        (EpochDateAsTimeParam::name(), EpochDateAsTimeParam::default_value()),
+        (DurationAsIntervalParam::name(), DurationAsIntervalParam::default_value()),
+        (DisplayValuesParam::name(), DisplayValuesParam::default_value()),
+        (IncludeHiddenColumnsParam::name(), IncludeHiddenColumnsParam::default_value()),
+        (RequireHeaderParam::name(), RequireHeaderParam::default_value()),
+        (EmptyOkParam::name(), EmptyOkParam::default_value()),
+        (AutoDetectParam::name(), AutoDetectParam::default_value()),
+        (AllVarcharParam::name(), AllVarcharParam::default_value()),
+        (HeaderAliasesParam::name(), HeaderAliasesParam::default_value()),
+        (MissingHeaderNameParam::name(), MissingHeaderNameParam::default_value()),
+        (MaxHeaderLengthParam::name(), MaxHeaderLengthParam::default_value()),
+        (NormalizeNamesParam::name(), NormalizeNamesParam::default_value()),
+        (DateLocaleParam::name(), DateLocaleParam::default_value()),
+        (SalvageParam::name(), SalvageParam::default_value()),
+        (LenientParam::name(), LenientParam::default_value()),
+        (OriginalValuesParam::name(), OriginalValuesParam::default_value()),
+        (FormulasParam::name(), FormulasParam::default_value()),
+        (FixEncodingParam::name(), FixEncodingParam::default_value()),
+        (SnapshotParam::name(), SnapshotParam::default_value()),
+        (PasswordParam::name(), PasswordParam::default_value()),
+        (SpillThresholdParam::name(), SpillThresholdParam::default_value()),
+        (MaxCellLengthParam::name(), MaxCellLengthParam::default_value()),
+        (TruncationSuffixParam::name(), TruncationSuffixParam::default_value()),
+        (DecimalRoundingParam::name(), DecimalRoundingParam::default_value()),
+        (DecimalScaleOverflowParam::name(), DecimalScaleOverflowParam::default_value()),
+        (VarcharCaseParam::name(), VarcharCaseParam::default_value()),
+        (DatesAsVarcharParam::name(), DatesAsVarcharParam::default_value()),
+        (PhoneticSuffixParam::name(), PhoneticSuffixParam::default_value()),
+        (FileNameColumnParam::name(), FileNameColumnParam::default_value()),
+        (SheetNameColumnParam::name(), SheetNameColumnParam::default_value()),
+        (PartitionByParam::name(), PartitionByParam::default_value()),
+        (FormatParam::name(), FormatParam::default_value()),
+        (AppendParam::name(), AppendParam::default_value()),
+        (OverwriteParam::name(), OverwriteParam::default_value()),
+        (HeaderStyleParam::name(), HeaderStyleParam::default_value()),
+        (FreezePanesParam::name(), FreezePanesParam::default_value()),
+        (AutofilterParam::name(), AutofilterParam::default_value()),
+        (ScanIdParam::name(), ScanIdParam::default_value()),
+    ]
+    .into_iter()
+    .find(|(param_name, _)| *param_name == name)
+    .and_then(|(_, default_value)| default_value)
 }
 
 /// Parses a sheet specification string in format "filename_pattern=sheet_pattern" or "sheet_pattern".
@@ -0,0 +1,161 @@
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::FileParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::SalvageParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::open_spreadsheet;
+use crate::spreadsheet::sheet::CellProtection;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the read_cell_protection table function
+struct ReadCellProtectionParameters {
+    /// Path to the spreadsheet file
+    file_name: String,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for ReadCellProtectionParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(ReadCellProtectionParameters {
+            file_name: FileParam::read(bind, 0)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Bind data for the read_cell_protection table function
+pub(crate) struct ReadCellProtectionBindData {
+    /// Protection attributes of the cells of the workbook
+    protections: Vec<CellProtection>,
+}
+
+impl TryFrom<&ReadCellProtectionParameters> for ReadCellProtectionBindData {
+    type Error = RustySheetError;
+
+    /// Read the protection attributes of the cells, without reading the values of the worksheets
+    fn try_from(parameters: &ReadCellProtectionParameters) -> Result<Self, Self::Error> {
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        let protections = spreadsheet.cell_protection()?;
+        Ok(ReadCellProtectionBindData { protections })
+    }
+}
+
+#[repr(C)]
+/// Init data for the read_cell_protection table function tracking iteration state
+pub(crate) struct ReadCellProtectionInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function listing which cells of a workbook are locked or hide their formulas
+pub(crate) struct ReadCellProtectionTableFunction;
+
+impl VTab for ReadCellProtectionTableFunction {
+    type InitData = ReadCellProtectionInitData;
+    type BindData = ReadCellProtectionBindData;
+
+    /// Bind phase: parse parameters, read the protection attributes, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = ReadCellProtectionParameters::try_from(bind)?;
+        let data = ReadCellProtectionBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column(
+            "sheet_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "reference",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "locked",
+            LogicalTypeHandle::from(LogicalTypeId::Boolean),
+        );
+        bind.add_result_column(
+            "formula_hidden",
+            LogicalTypeHandle::from(LogicalTypeId::Boolean),
+        );
+        bind.add_result_column(
+            "sheet_protected",
+            LogicalTypeHandle::from(LogicalTypeId::Boolean),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(ReadCellProtectionInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the protection attributes to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.protections.len().min(lower + 2048);
+        if lower < upper {
+            let sheet_names = output.flat_vector(0);
+            let references = output.flat_vector(1);
+            let mut lockeds = output.flat_vector(2);
+            let mut formula_hiddens = output.flat_vector(3);
+            let mut sheet_protecteds = output.flat_vector(4);
+            for index in lower..upper {
+                let protection = &bind.protections[index];
+                let row = index - lower;
+                sheet_names.insert(row, protection.sheet_name.as_str());
+                references.insert(row, protection.reference.as_str());
+                lockeds.as_mut_slice::<bool>()[row] = protection.locked;
+                formula_hiddens.as_mut_slice::<bool>()[row] = protection.formula_hidden;
+                sheet_protecteds.as_mut_slice::<bool>()[row] = protection.sheet_protected;
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
@@ -0,0 +1,302 @@
+use crate::database::range::RangeSpec;
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::writer::write_timestamp;
+use crate::extension::FileParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::RangeParam;
+use crate::extension::SalvageParam;
+use crate::extension::SheetParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::cell::Cell;
+use crate::spreadsheet::cell::CellType;
+use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::open_spreadsheet;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use glob::Pattern;
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the read_cells table function
+struct ReadCellsParameters {
+    /// Path to the spreadsheet file
+    file_name: String,
+    /// Optional sheet name pattern to filter sheets (default: all sheets)
+    sheet_name: Option<Pattern>,
+    /// Optional range specification (e.g., "A1:D10") or defined name
+    range: Option<RangeSpec>,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for ReadCellsParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(ReadCellsParameters {
+            file_name: FileParam::read(bind, 0)?,
+            sheet_name: SheetParam::read(bind)?,
+            range: RangeParam::read(bind)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+/// A populated cell as returned by read_cells
+struct CellRecord {
+    /// Name of the sheet holding the cell
+    sheet_name: String,
+    /// Row number (1-based)
+    row: i64,
+    /// Column number (1-based)
+    col: i64,
+    /// Excel-style reference (e.g. "B2")
+    reference: String,
+    /// Type of the stored value, see `describe`
+    kind: &'static str,
+    /// Value as text
+    value_varchar: Option<String>,
+    /// Value of numeric cells, including the serial number of dates and times
+    value_double: Option<f64>,
+    /// Value of date and time cells in microseconds since 1970-01-01
+    value_timestamp: Option<i64>,
+}
+
+/// Describes a cell value as its type name, text, number and timestamp.
+/// `shared_string` is the resolved text of shared-string cells.
+pub(super) fn describe(cell: &Cell, shared_string: Option<&str>) -> (&'static str, Option<String>, Option<f64>, Option<i64>) {
+    match cell.kind {
+        CellType::Boolean => ("boolean", Some(cell.to_string()), None, None),
+        CellType::Number => ("number", Some(cell.value.to_owned()), cell.to_double().ok(), None),
+        CellType::NumberDateTime1900 | CellType::NumberDateTime1904 |
+        CellType::NumberDate1900 | CellType::NumberDate1904 |
+        CellType::NumberTime1900 | CellType::NumberTime1904 => {
+            let kind = match cell.kind {
+                CellType::NumberDateTime1900 | CellType::NumberDateTime1904 => "datetime",
+                CellType::NumberDate1900 | CellType::NumberDate1904 => "date",
+                _ => "time",
+            };
+            // Display panics on values that are not numbers, so only format valid dates
+            let datetime = cell.to_datetime().ok();
+            let text = datetime.map(|_| cell.to_string()).unwrap_or_else(|| cell.value.to_owned());
+            (kind, Some(text), cell.to_double().ok(), datetime)
+        }
+        CellType::IsoDateTime if cell.value.contains('T') => ("datetime", Some(cell.to_string()), None, cell.to_datetime().ok()),
+        CellType::IsoDateTime => ("date", Some(cell.to_string()), None, cell.to_datetime().ok()),
+        CellType::IsoDuration => ("duration", Some(cell.to_string()), None, None),
+        CellType::InlineString => ("string", Some(cell.value.to_owned()), None, None),
+        CellType::SharedString => ("string", shared_string.map(str::to_owned), None, None),
+        CellType::Error => ("error", Some(cell.value.to_owned()), None, None),
+        CellType::Empty => ("empty", None, None, None),
+    }
+}
+
+/// Returns whether a cell holds a value. Styled blank cells (e.g. `<c r="B3" s="1"/>`)
+/// are parsed as numbers without a value; empty text is kept.
+pub(super) fn is_populated(cell: &Cell) -> bool {
+    match cell.kind {
+        CellType::Empty => false,
+        CellType::InlineString | CellType::SharedString => true,
+        _ => !cell.value.is_empty(),
+    }
+}
+
+#[repr(C)]
+/// Bind data for the read_cells table function
+pub(crate) struct ReadCellsBindData {
+    /// Every populated cell of the matched sheets, in sheet order
+    cells: Vec<CellRecord>,
+}
+
+impl TryFrom<&ReadCellsParameters> for ReadCellsBindData {
+    type Error = RustySheetError;
+
+    /// Read every matched sheet without assuming a table layout and keep its populated
+    /// cells; error cells are skipped rather than failing the read. Referenced shared
+    /// strings are loaded afterwards to resolve their text.
+    fn try_from(parameters: &ReadCellsParameters) -> Result<Self, Self::Error> {
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        let sheet_name_patterns = parameters.sheet_name.as_ref().map(|pattern| vec![pattern.to_owned()]);
+        let (sheet_name_patterns, range) = spreadsheet.resolve_range(parameters.range.as_ref(), sheet_name_patterns)?;
+        let sheets = spreadsheet.read_sheets(&Criteria {
+            sheet_name_patterns,
+            range,
+            error_as_null: true,
+            ..Default::default()
+        })?;
+
+        let indexes = sheets.iter()
+            .flat_map(|sheet| sheet.cells.iter())
+            .filter(|cell| cell.kind == CellType::SharedString)
+            .map(|cell| cell.value.parse::<usize>())
+            .collect::<Result<HashSet<usize>, _>>()?;
+        let (shared_strings, mappings) = spreadsheet.load_shared_strings(Some(indexes))?;
+
+        let mut cells = Vec::new();
+        for sheet in &sheets {
+            for cell in sheet.cells.iter().filter(|cell| is_populated(cell)) {
+                let shared_string = match cell.kind {
+                    CellType::SharedString => mappings.get(&cell.value.parse::<usize>()?)
+                        .and_then(|position| shared_strings.get(*position))
+                        .map(String::as_str),
+                    _ => None,
+                };
+                let (kind, value_varchar, value_double, value_timestamp) = describe(cell, shared_string);
+                cells.push(CellRecord {
+                    sheet_name: sheet.name.to_owned(),
+                    row: cell.row as i64 + 1,
+                    col: cell.col as i64 + 1,
+                    reference: cell.reference(),
+                    kind,
+                    value_varchar,
+                    value_double,
+                    value_timestamp,
+                });
+            }
+        }
+        Ok(ReadCellsBindData { cells })
+    }
+}
+
+#[repr(C)]
+/// Init data for the read_cells table function tracking iteration state
+pub(crate) struct ReadCellsInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function returning one row per populated cell, for workbooks that are not rectangular tables
+pub(crate) struct ReadCellsTableFunction;
+
+impl VTab for ReadCellsTableFunction {
+    type InitData = ReadCellsInitData;
+    type BindData = ReadCellsBindData;
+
+    /// Bind phase: parse parameters, read the cells, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = ReadCellsParameters::try_from(bind)?;
+        let data = ReadCellsBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column(
+            "sheet",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "row",
+            LogicalTypeHandle::from(LogicalTypeId::Bigint),
+        );
+        bind.add_result_column(
+            "col",
+            LogicalTypeHandle::from(LogicalTypeId::Bigint),
+        );
+        bind.add_result_column(
+            "reference",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "type",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "value_varchar",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "value_double",
+            LogicalTypeHandle::from(LogicalTypeId::Double),
+        );
+        bind.add_result_column(
+            "value_timestamp",
+            LogicalTypeHandle::from(LogicalTypeId::Timestamp),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(ReadCellsInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the cells to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.cells.len().min(lower + 2048);
+        if lower < upper {
+            let sheets = output.flat_vector(0);
+            let mut rows = output.flat_vector(1);
+            let mut cols = output.flat_vector(2);
+            let references = output.flat_vector(3);
+            let kinds = output.flat_vector(4);
+            let mut varchars = output.flat_vector(5);
+            let mut doubles = output.flat_vector(6);
+            let mut timestamps = output.flat_vector(7);
+            for index in lower..upper {
+                let cell = &bind.cells[index];
+                let row = index - lower;
+                sheets.insert(row, cell.sheet_name.as_str());
+                rows.as_mut_slice::<i64>()[row] = cell.row;
+                cols.as_mut_slice::<i64>()[row] = cell.col;
+                references.insert(row, cell.reference.as_str());
+                kinds.insert(row, cell.kind);
+                match &cell.value_varchar {
+                    Some(value) => varchars.insert(row, value.as_str()),
+                    None => varchars.set_null(row),
+                }
+                match cell.value_double {
+                    Some(value) => doubles.as_mut_slice::<f64>()[row] = value,
+                    None => doubles.set_null(row),
+                }
+                match cell.value_timestamp {
+                    Some(value) => write_timestamp(&mut timestamps, row, value),
+                    None => timestamps.set_null(row),
+                }
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SheetParam::definition(),
+            RangeParam::definition(),
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
@@ -0,0 +1,158 @@
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::FileParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::SalvageParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::open_spreadsheet;
+use crate::spreadsheet::sheet::CellComment;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the read_comments table function
+struct ReadCommentsParameters {
+    /// Path to the spreadsheet file
+    file_name: String,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for ReadCommentsParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(ReadCommentsParameters {
+            file_name: FileParam::read(bind, 0)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Bind data for the read_comments table function
+pub(crate) struct ReadCommentsBindData {
+    /// Comments of the cells of the workbook
+    comments: Vec<CellComment>,
+}
+
+impl TryFrom<&ReadCommentsParameters> for ReadCommentsBindData {
+    type Error = RustySheetError;
+
+    /// Read the comments of the workbook, without reading any cell
+    fn try_from(parameters: &ReadCommentsParameters) -> Result<Self, Self::Error> {
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        let comments = spreadsheet.comments()?;
+        Ok(ReadCommentsBindData { comments })
+    }
+}
+
+#[repr(C)]
+/// Init data for the read_comments table function tracking iteration state
+pub(crate) struct ReadCommentsInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function listing the comments (notes) attached to the cells of a workbook
+pub(crate) struct ReadCommentsTableFunction;
+
+impl VTab for ReadCommentsTableFunction {
+    type InitData = ReadCommentsInitData;
+    type BindData = ReadCommentsBindData;
+
+    /// Bind phase: parse parameters, read the comments, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = ReadCommentsParameters::try_from(bind)?;
+        let data = ReadCommentsBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column(
+            "sheet_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "reference",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "author",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "text",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(ReadCommentsInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the comments to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.comments.len().min(lower + 2048);
+        if lower < upper {
+            let sheet_names = output.flat_vector(0);
+            let references = output.flat_vector(1);
+            let mut authors = output.flat_vector(2);
+            let texts = output.flat_vector(3);
+            for index in lower..upper {
+                let comment = &bind.comments[index];
+                let row = index - lower;
+                sheet_names.insert(row, comment.sheet_name.as_str());
+                references.insert(row, comment.reference.as_str());
+                match &comment.author {
+                    Some(author) => authors.insert(row, author.as_str()),
+                    None => authors.set_null(row),
+                }
+                texts.insert(row, comment.text.as_str());
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
@@ -0,0 +1,178 @@
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::FileParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::SalvageParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::open_spreadsheet;
+use crate::spreadsheet::sheet::DefinedName;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the read_defined_names table function
+struct ReadDefinedNamesParameters {
+    /// Path to the spreadsheet file
+    file_name: String,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for ReadDefinedNamesParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(ReadDefinedNamesParameters {
+            file_name: FileParam::read(bind, 0)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Bind data for the read_defined_names table function
+pub(crate) struct ReadDefinedNamesBindData {
+    /// Defined names of the workbook
+    names: Vec<DefinedName>,
+}
+
+impl TryFrom<&ReadDefinedNamesParameters> for ReadDefinedNamesBindData {
+    type Error = RustySheetError;
+
+    /// Read the defined names of the workbook, without reading any cell
+    fn try_from(parameters: &ReadDefinedNamesParameters) -> Result<Self, Self::Error> {
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        let names = spreadsheet.defined_names()?;
+        Ok(ReadDefinedNamesBindData { names })
+    }
+}
+
+#[repr(C)]
+/// Init data for the read_defined_names table function tracking iteration state
+pub(crate) struct ReadDefinedNamesInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function listing the defined names of a workbook, for discovering named ranges before reading them
+pub(crate) struct ReadDefinedNamesTableFunction;
+
+impl VTab for ReadDefinedNamesTableFunction {
+    type InitData = ReadDefinedNamesInitData;
+    type BindData = ReadDefinedNamesBindData;
+
+    /// Bind phase: parse parameters, read the names, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = ReadDefinedNamesParameters::try_from(bind)?;
+        let data = ReadDefinedNamesBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column(
+            "name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "scope",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "refers_to",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "sheet_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "range",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "hidden",
+            LogicalTypeHandle::from(LogicalTypeId::Boolean),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(ReadDefinedNamesInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the names to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.names.len().min(lower + 2048);
+        if lower < upper {
+            let names = output.flat_vector(0);
+            let mut scopes = output.flat_vector(1);
+            let refers_tos = output.flat_vector(2);
+            let mut sheet_names = output.flat_vector(3);
+            let mut ranges = output.flat_vector(4);
+            let mut hiddens = output.flat_vector(5);
+            for index in lower..upper {
+                let name = &bind.names[index];
+                let row = index - lower;
+                names.insert(row, name.name.as_str());
+                match &name.scope {
+                    Some(scope) => scopes.insert(row, scope.as_str()),
+                    None => scopes.set_null(row),
+                }
+                refers_tos.insert(row, name.refers_to.as_str());
+                match &name.target {
+                    Some((sheet_name, range)) => {
+                        sheet_names.insert(row, sheet_name.as_str());
+                        ranges.insert(row, range.as_str());
+                    }
+                    None => {
+                        sheet_names.set_null(row);
+                        ranges.set_null(row);
+                    }
+                }
+                hiddens.as_mut_slice::<bool>()[row] = name.hidden;
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
@@ -0,0 +1,222 @@
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::FileParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::SalvageParam;
+use crate::extension::SheetParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::formula::formula_references;
+use crate::spreadsheet::open_spreadsheet;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use glob::Pattern;
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the read_formula_graph table function
+struct ReadFormulaGraphParameters {
+    /// Path to the spreadsheet file
+    file_name: String,
+    /// Optional sheet name pattern to filter sheets (default: all sheets)
+    sheet_name: Option<Pattern>,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for ReadFormulaGraphParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(ReadFormulaGraphParameters {
+            file_name: FileParam::read(bind, 0)?,
+            sheet_name: SheetParam::read(bind)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+/// A reference of a formula to a cell or range, an edge of the dependency graph
+struct FormulaEdge {
+    /// Name of the sheet holding the formula
+    sheet_name: String,
+    /// Excel-style reference of the formula cell (e.g. "C2")
+    from_cell: String,
+    /// Name of the sheet referred to
+    to_sheet: String,
+    /// Cell or range referred to, without `$` markers (e.g. "A2" or "A2:B10")
+    to_range: String,
+    /// Whether the referred sheet is the sheet of the formula
+    same_sheet: bool,
+    /// Formula text, starting with `=`
+    formula: String,
+}
+
+#[repr(C)]
+/// Bind data for the read_formula_graph table function
+pub(crate) struct ReadFormulaGraphBindData {
+    /// Every reference of the formulas of the matched sheets, in sheet and cell order
+    edges: Vec<FormulaEdge>,
+}
+
+impl TryFrom<&ReadFormulaGraphParameters> for ReadFormulaGraphBindData {
+    type Error = RustySheetError;
+
+    /// Read the formulas of every matched sheet and list their references. A formula
+    /// referring several times to the same range gives a single edge.
+    fn try_from(parameters: &ReadFormulaGraphParameters) -> Result<Self, Self::Error> {
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        let sheets = spreadsheet.read_sheets(&Criteria {
+            sheet_name_patterns: parameters.sheet_name.as_ref().map(|pattern| vec![pattern.to_owned()]),
+            error_as_null: true,
+            formulas: true,
+            ..Default::default()
+        })?;
+
+        let mut edges = Vec::new();
+        for sheet in &sheets {
+            // Formulas are read as their text, other cells keep their values
+            let formulas = sheet.cells.iter()
+                .filter(|cell| sheet.formula_cells.contains(&(cell.row, cell.col)));
+            for cell in formulas {
+                let mut seen = HashSet::new();
+                for (to_sheet, to_range) in formula_references(&cell.value) {
+                    let to_sheet = to_sheet.unwrap_or_else(|| sheet.name.to_owned());
+                    if !seen.insert((to_sheet.to_lowercase(), to_range.to_owned())) {
+                        continue;
+                    }
+                    edges.push(FormulaEdge {
+                        sheet_name: sheet.name.to_owned(),
+                        from_cell: cell.reference(),
+                        // Worksheet names are case-insensitive
+                        same_sheet: to_sheet.to_lowercase() == sheet.name.to_lowercase(),
+                        to_sheet,
+                        to_range,
+                        formula: cell.value.to_owned(),
+                    });
+                }
+            }
+        }
+        Ok(ReadFormulaGraphBindData { edges })
+    }
+}
+
+#[repr(C)]
+/// Init data for the read_formula_graph table function tracking iteration state
+pub(crate) struct ReadFormulaGraphInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function returning one row per reference of a formula, the edges of the dependency graph of a workbook
+pub(crate) struct ReadFormulaGraphTableFunction;
+
+impl VTab for ReadFormulaGraphTableFunction {
+    type InitData = ReadFormulaGraphInitData;
+    type BindData = ReadFormulaGraphBindData;
+
+    /// Bind phase: parse parameters, read the formulas, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = ReadFormulaGraphParameters::try_from(bind)?;
+        let data = ReadFormulaGraphBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column(
+            "sheet",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "from_cell",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "to_sheet",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "to_range",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "same_sheet",
+            LogicalTypeHandle::from(LogicalTypeId::Boolean),
+        );
+        bind.add_result_column(
+            "formula",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(ReadFormulaGraphInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the edges to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.edges.len().min(lower + 2048);
+        if lower < upper {
+            let sheets = output.flat_vector(0);
+            let from_cells = output.flat_vector(1);
+            let to_sheets = output.flat_vector(2);
+            let to_ranges = output.flat_vector(3);
+            let mut same_sheets = output.flat_vector(4);
+            let formulas = output.flat_vector(5);
+            for index in lower..upper {
+                let edge = &bind.edges[index];
+                let row = index - lower;
+                sheets.insert(row, edge.sheet_name.as_str());
+                from_cells.insert(row, edge.from_cell.as_str());
+                to_sheets.insert(row, edge.to_sheet.as_str());
+                to_ranges.insert(row, edge.to_range.as_str());
+                same_sheets.as_mut_slice::<bool>()[row] = edge.same_sheet;
+                formulas.insert(row, edge.formula.as_str());
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SheetParam::definition(),
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
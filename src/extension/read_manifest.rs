@@ -0,0 +1,72 @@
+use crate::database::range::RangeSpec;
+use crate::extension::read_sheets::ReadSheetsBindData;
+use crate::extension::read_sheets::ReadSheetsInitData;
+use crate::extension::read_sheets::ReadSheetsParameters;
+use crate::extension::read_sheets::ReadSheetsTableFunction;
+use crate::extension::ManifestParam;
+use crate::extension::Param;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use glob::Pattern;
+use std::error::Error;
+
+/// File of a manifest with the sheet and range read from it
+pub(crate) struct ManifestEntry {
+    /// Path to the spreadsheet file, read as is without wildcard expansion
+    pub(crate) file_name: String,
+    /// Sheet name pattern, None to fall back to the `sheets` parameter
+    pub(crate) sheet_name: Option<Pattern>,
+    /// Range of the sheet, None to fall back to the `range` parameter
+    pub(crate) range: Option<RangeSpec>,
+}
+
+/// DuckDB table function reading the files listed by a manifest, such as a table of
+/// pending files turned into a list of structs, as one union like read_sheets
+pub(crate) struct ReadManifestTableFunction;
+
+impl VTab for ReadManifestTableFunction {
+    type InitData = ReadSheetsInitData;
+    type BindData = ReadSheetsBindData;
+
+    /// Bind phase: parse the manifest and parameters, analyze the listed sheets
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = ReadSheetsParameters::read(bind, true)?;
+        ReadSheetsBindData::bind(bind, &parameters)
+    }
+
+    /// Initialize phase: read the analyzed sheets as read_sheets does
+    fn init(init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        ReadSheetsTableFunction::init(init)
+    }
+
+    /// Function phase: stream data chunks to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        func.get_init_data().scan(func.get_bind_data(), output)?;
+        Ok(())
+    }
+
+    /// Indicates whether this table function supports predicate pushdown
+    fn supports_pushdown() -> bool {
+        true
+    }
+
+    /// Defines the required positional parameters (manifest)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            ManifestParam::kind(),
+        ])
+    }
+
+    /// Defines the named parameters, those of read_sheets, `sheets` and `range` being
+    /// the defaults of the entries without a sheet or range
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        ReadSheetsTableFunction::named_parameters()
+    }
+}
@@ -1,31 +1,91 @@
+use std::collections::HashMap;
 use std::collections::HashSet;
 use crate::database::column::Column;
 use crate::database::column::ColumnType;
+use crate::database::decimal::DecimalPolicy;
+use crate::database::decimal::DecimalRounding;
+use crate::database::decimal::ScaleOverflow;
+use crate::database::range::RangeSpec;
 use crate::error::ResultMessage;
 use crate::error::RustySheetError;
+use crate::extension::MaxHeaderLengthParam;
+use crate::extension::default_analyze_rows;
+use crate::extension::read_sheets::Expectation;
+use crate::extension::writer::converts;
+use crate::extension::writer::default_cells;
+use crate::extension::writer::write_string;
 use crate::extension::writer::write_to_vector;
+use crate::extension::writer::ChunkBufferPool;
+use crate::extension::writer::VarcharCase;
+use crate::extension::writer::WriteOptions;
+use crate::extension::AllVarcharParam;
 use crate::extension::AnalyzeRowsParam;
+use crate::extension::AutoDetectParam;
 use crate::extension::ColumnsParam;
+use crate::extension::DateLocaleParam;
+use crate::extension::DatesAsVarcharParam;
+use crate::extension::DefaultsParam;
+use crate::extension::DecimalRoundingParam;
+use crate::extension::DecimalScaleOverflowParam;
+use crate::extension::DisplayValuesParam;
+use crate::extension::DurationAsIntervalParam;
+use crate::extension::EmptyOkParam;
 use crate::extension::EndAtEmptyRowParam;
+use crate::extension::EpochDateAsTimeParam;
 use crate::extension::ErrorAsNullParam;
+use crate::extension::ExpectColumnsParam;
+use crate::extension::ExpectRowsParam;
 use crate::extension::ExtensionError;
 use crate::extension::FileNameColumnParam;
 use crate::extension::FileParam;
+use crate::extension::FixEncodingParam;
+use crate::extension::FormulasParam;
+use crate::extension::HeaderAliasesParam;
 use crate::extension::HeaderParam;
+use crate::extension::IgnoreErrorsParam;
+use crate::extension::IncludeHiddenColumnsParam;
+use crate::extension::LenientParam;
+use crate::extension::MaxCellLengthParam;
+use crate::extension::MidnightAsDateParam;
+use crate::extension::MissingHeaderNameParam;
 use crate::extension::NamedParam;
+use crate::extension::NormalizeNamesParam;
 use crate::extension::NullsParam;
+use crate::extension::OriginalValuesParam;
 use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::PhoneticSuffixParam;
 use crate::extension::Range;
 use crate::extension::RangeParam;
+use crate::extension::RangesParam;
+use crate::extension::RequireHeaderParam;
+use crate::extension::SalvageParam;
+use crate::extension::SampleParam;
 use crate::extension::SheetNameColumnParam;
 use crate::extension::SheetParam;
 use crate::extension::SkipEmptyRowsParam;
+use crate::extension::SkipParam;
+use crate::extension::SnapshotParam;
+use crate::extension::SpillThresholdParam;
+use crate::extension::StableRowsParam;
+use crate::extension::TableParam;
+use crate::extension::TruncationSuffixParam;
+use crate::extension::VarcharCaseParam;
+use crate::spreadsheet::cell::Cell;
+use crate::spreadsheet::cell::CellType;
 use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::locale::DateLocale;
+use crate::spreadsheet::apply_declared_columns;
+use crate::spreadsheet::declared_columns;
+use crate::spreadsheet::fallback_names;
 use crate::spreadsheet::open_spreadsheet;
+use crate::spreadsheet::shared_strings::SharedStrings;
+use crate::spreadsheet::shared_strings::SharedStringsBuilder;
 use crate::spreadsheet::sheet::Sheet;
+use crate::spreadsheet::Spreadsheet;
+use crate::spreadsheet::MAX_HEADER_LENGTH;
 use anyhow::Result;
 use duckdb::core::DataChunkHandle;
-use duckdb::core::Inserter;
 use duckdb::core::LogicalTypeHandle;
 use duckdb::vtab::BindInfo;
 use duckdb::vtab::InitInfo;
@@ -35,6 +95,11 @@ use glob::Pattern;
 use std::error::Error;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+/// Number of rows read ahead of the whole sheet, so that a `LIMIT` query stops after the first chunk
+const PREVIEW_ROWS: usize = 2 * Sheet::CHUNK_SIZE;
 
 /// Parameters for reading a single sheet from a spreadsheet file.
 struct ReadSheetParameters {
@@ -42,22 +107,98 @@ struct ReadSheetParameters {
     file_name: String,
     /// Optional pattern to match sheet names (supports glob patterns)
     sheet_name: Option<Pattern>,
-    /// Optional range specification for data extraction
-    range: Option<Range>,
+    /// Optional range specification or defined name for data extraction
+    range: Option<RangeSpec>,
+    /// Disjoint blocks of cells read as one table instead of a single range (default: none)
+    ranges: Option<Vec<Range>>,
+    /// Number of leading rows dropped before header detection (default: 0)
+    skip: Option<usize>,
+    /// Name of an Excel Table read instead of a sheet and range (default: none)
+    table: Option<String>,
     /// Whether the first row contains column headers (default: true)
     header: Option<bool>,
     /// Column specifications with patterns and types for type detection
     columns: Option<Vec<(Pattern, ColumnType)>>,
+    /// Type every column as VARCHAR instead of detecting its type (default: false)
+    all_varchar: Option<bool>,
+    /// Values written in place of NULL cells, by column name (default: none)
+    defaults: Option<HashMap<String, String>>,
+    /// Infer the columns instead of taking them from `columns` alone (default: true)
+    auto_detect: Option<bool>,
     /// Number of rows to analyze for automatic type detection
     analyze_rows: Option<usize>,
+    /// Number of rows sampled uniformly from the whole sheet for type detection (default: none)
+    sample: Option<usize>,
+    /// Consecutive values of one type after which a column's type is stable (default: none)
+    stable_rows: Option<usize>,
     /// null literals (default: empty string)
     nulls: Option<HashSet<String>>,
     /// Convert parsing errors to NULL values instead of failing
     error_as_null: Option<bool>,
+    /// Drop the rows with a cell that cannot be converted to its column type instead of failing (default: false)
+    ignore_errors: Option<bool>,
     /// Skip rows that contain no data
     skip_empty_rows: Option<bool>,
     /// Stop reading when encountering an empty row
     end_at_empty_row: Option<bool>,
+    /// Classify ISO datetimes at midnight as dates (default: false)
+    midnight_as_date: Option<bool>,
+    /// Classify ISO datetimes on the 1900/1904 epoch date as times (default: false)
+    epoch_date_as_time: Option<bool>,
+    /// Classify text durations like "2 05:30:00" as intervals (default: false)
+    duration_as_interval: Option<bool>,
+    /// Render numeric cells as displayed by their number format (default: false)
+    display_values: Option<bool>,
+    /// Include hidden, zero-width and collapsed columns (default: true)
+    include_hidden_columns: Option<bool>,
+    /// Fail when every header cell is empty or a null literal (default: false)
+    require_header: Option<bool>,
+    /// Return zero rows from an empty sheet instead of failing (default: false)
+    empty_ok: Option<bool>,
+    /// Number of data rows the sheet must have (default: any)
+    expect_rows: Option<Expectation>,
+    /// Number of data columns the sheet must have (default: any)
+    expect_columns: Option<Expectation>,
+    /// Locale of month names parsed in text dates (default: none)
+    date_locale: Option<DateLocale>,
+    /// Canonical column names by localized header text (default: none)
+    header_aliases: Option<HashMap<String, String>>,
+    /// Template naming columns without a header, e.g. 'column_{n}' (default: column letters)
+    missing_header_name: Option<String>,
+    /// Maximum number of characters of column names taken from headers (default: 255)
+    max_header_length: Option<usize>,
+    /// Lowercase, de-accent and underscore the column names taken from headers (default: false)
+    normalize_names: Option<bool>,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Keep the cells parsed before a structural error instead of failing (default: false)
+    lenient: Option<bool>,
+    /// Read the values cells held before the tracked changes instead of the latest ones (default: false)
+    original_values: Option<bool>,
+    /// Read the formulas of formula cells instead of their cached values (default: false)
+    formulas: Option<bool>,
+    /// Repair text whose UTF-8 bytes were decoded as Windows-1252 or Latin-1 (default: false)
+    fix_encoding: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+    /// Number of shared strings above which the shared string table is spilled to disk (default: never)
+    spill_threshold: Option<usize>,
+    /// Maximum number of characters kept in text cells (default: unlimited)
+    max_cell_length: Option<usize>,
+    /// Suffix appended to truncated text cells (default: none)
+    truncation_suffix: Option<String>,
+    /// Rounding of digits beyond the scale of DECIMAL columns (default: half_even)
+    decimal_rounding: Option<DecimalRounding>,
+    /// Handling of values exceeding the scale of DECIMAL columns (default: round)
+    decimal_scale_overflow: Option<ScaleOverflow>,
+    /// Case folding of VARCHAR values: 'lower', 'upper' or 'preserve' (default: 'preserve')
+    varchar_case: Option<VarcharCase>,
+    /// Write DATE, TIME and TIMESTAMP columns as ISO 8601 strings (default: false)
+    dates_as_varchar: Option<bool>,
+    /// Suffix of the columns holding the phonetic readings of VARCHAR columns (default: none)
+    phonetic_suffix: Option<String>,
     /// column name for file name of record
     file_name_column: Option<String>,
     /// column name for sheet name of record
@@ -74,13 +215,51 @@ impl TryFrom<&BindInfo> for ReadSheetParameters {
             file_name: FileParam::read(bind, 0)?,
             sheet_name: SheetParam::read(bind)?,
             range: RangeParam::read(bind)?,
+            ranges: RangesParam::read(bind)?,
+            skip: SkipParam::read(bind)?,
+            table: TableParam::read(bind)?,
             header: HeaderParam::read(bind)?,
             columns: ColumnsParam::read(bind)?,
+            all_varchar: AllVarcharParam::read(bind)?,
+            defaults: DefaultsParam::read(bind)?,
+            auto_detect: AutoDetectParam::read(bind)?,
             analyze_rows: AnalyzeRowsParam::read(bind)?,
+            sample: SampleParam::read(bind)?,
+            stable_rows: StableRowsParam::read(bind)?,
             nulls: NullsParam::read(bind)?,
             error_as_null: ErrorAsNullParam::read(bind)?,
+            ignore_errors: IgnoreErrorsParam::read(bind)?,
             skip_empty_rows: SkipEmptyRowsParam::read(bind)?,
             end_at_empty_row: EndAtEmptyRowParam::read(bind)?,
+            midnight_as_date: MidnightAsDateParam::read(bind)?,
+            epoch_date_as_time: EpochDateAsTimeParam::read(bind)?,
+            duration_as_interval: DurationAsIntervalParam::read(bind)?,
+            display_values: DisplayValuesParam::read(bind)?,
+            include_hidden_columns: IncludeHiddenColumnsParam::read(bind)?,
+            require_header: RequireHeaderParam::read(bind)?,
+            empty_ok: EmptyOkParam::read(bind)?,
+            expect_rows: ExpectRowsParam::read(bind)?,
+            expect_columns: ExpectColumnsParam::read(bind)?,
+            date_locale: DateLocaleParam::read(bind)?,
+            header_aliases: HeaderAliasesParam::read(bind)?,
+            missing_header_name: MissingHeaderNameParam::read(bind)?,
+            max_header_length: MaxHeaderLengthParam::read(bind)?,
+            normalize_names: NormalizeNamesParam::read(bind)?,
+            salvage: SalvageParam::read(bind)?,
+            lenient: LenientParam::read(bind)?,
+            original_values: OriginalValuesParam::read(bind)?,
+            formulas: FormulasParam::read(bind)?,
+            fix_encoding: FixEncodingParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+            spill_threshold: SpillThresholdParam::read(bind)?,
+            max_cell_length: MaxCellLengthParam::read(bind)?,
+            truncation_suffix: TruncationSuffixParam::read(bind)?,
+            decimal_rounding: DecimalRoundingParam::read(bind)?,
+            decimal_scale_overflow: DecimalScaleOverflowParam::read(bind)?,
+            varchar_case: VarcharCaseParam::read(bind)?,
+            dates_as_varchar: DatesAsVarcharParam::read(bind)?,
+            phonetic_suffix: PhoneticSuffixParam::read(bind)?,
             file_name_column: FileNameColumnParam::read(bind)?,
             sheet_name_column: SheetNameColumnParam::read(bind)?,
         })
@@ -91,56 +270,207 @@ impl TryFrom<&BindInfo> for ReadSheetParameters {
 /// Data structure that holds the binding information for a single sheet read operation.
 /// This data is shared between the bind, init, and function execution phases.
 pub(crate) struct ReadSheetBindData {
+    /// Path to the spreadsheet file
+    file_name: String,
     /// Column definitions including names, types, and metadata
     columns: Vec<Column>,
     /// file name column index
     file_name_column: Option<usize>,
     /// sheet name column index
     sheet_name_column: Option<usize>,
-    /// Loaded sheet data organized in chunks for efficient processing
-    sheets: Vec<Sheet>,
-    /// Shared string table for efficient string storage (XLSX/XLSB format)
-    shared_strings: Vec<Option<String>>,
+    /// Phonetic reading column indexes mapped to the index of their VARCHAR column
+    phonetic_columns: HashMap<usize, usize>,
+    /// Cells written in place of the missing and NULL cells of each data column
+    defaults: Vec<Option<Cell>>,
+    /// Opened spreadsheet, read once the projected columns are known
+    spreadsheet: Mutex<Box<dyn Spreadsheet + Send + Sync>>,
+    /// Criteria for reading the analyzed sheet
+    criteria: Criteria,
+    /// Name of the analyzed sheet
+    sheet_name: String,
+    /// Whether the sheet is empty and read as zero rows (empty_ok)
+    empty: bool,
+    /// Number of data rows the sheet must have, checked once the sheet is read
+    expect_rows: Option<Expectation>,
+    /// Conversion options for DECIMAL and VARCHAR columns
+    options: WriteOptions,
+    /// Whether rows with a cell that cannot be converted to its column type are dropped
+    ignore_errors: bool,
+    /// Number of shared strings above which the shared string table is spilled to disk
+    spill_threshold: Option<usize>,
+}
+
+impl ReadSheetBindData {
+    /// Checks if a column index refers to a sheet data column (not file name, sheet name or row id).
+    fn is_data_column(&self, col: usize) -> bool {
+        col < self.columns.len()
+            && self.file_name_column != Some(col)
+            && self.sheet_name_column != Some(col)
+    }
 }
 
 impl TryFrom<&ReadSheetParameters> for ReadSheetBindData {
     type Error = RustySheetError;
 
-    /// Converts read parameters into bind data by analyzing and loading the spreadsheet.
-    /// This performs the actual file parsing and prepares data for DuckDB consumption.
+    /// Converts read parameters into bind data by analyzing the spreadsheet.
+    /// The data itself is read in the init phase, once the projected columns are known.
     fn try_from(parameters: &ReadSheetParameters) -> Result<Self, Self::Error> {
-        // Prepare sheet name pattern for matching
-        let sheet_name_pattern = parameters.sheet_name.as_ref().map(|pattern| vec![pattern.to_owned()]);
+        let auto_detect = parameters.auto_detect.unwrap_or(true);
+        if !auto_detect && parameters.columns.is_none() {
+            Err(ExtensionError::AutoDetectColumnsError)?
+        }
+
+        if parameters.table.is_some() && (parameters.sheet_name.is_some() || parameters.range.is_some() || parameters.ranges.is_some()) {
+            Err(ExtensionError::TableRangeError)?
+        }
+        if parameters.range.is_some() && parameters.ranges.is_some() {
+            Err(ExtensionError::RangesError)?
+        }
 
-        // Open the spreadsheet file and load shared strings (for XLSX/XLSB formats)
-        let mut spreadsheet = open_spreadsheet(&parameters.file_name)?;
-        let (shared_strings, _) = spreadsheet.load_shared_strings(None)?;
+        // Open the spreadsheet file
+        let mut spreadsheet = open_spreadsheet(&parameters.file_name, parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+
+        // Resolve an Excel Table to its worksheet and range, its totals rows left out
+        let list_object = match &parameters.table {
+            Some(name) => Some(spreadsheet.list_objects()?
+                .into_iter()
+                .find(|list_object| list_object.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| ExtensionError::TableNotFoundError(spreadsheet.name(), name.to_owned()))?),
+            None => None,
+        };
+
+        // Prepare sheet name pattern and range for matching, a defined name pointing at its own worksheet
+        let (sheet_name_pattern, range) = match &list_object {
+            Some(list_object) => {
+                let mut range = Range::try_from(list_object.reference.as_str())?;
+                range.row_upper_bound = range.row_upper_bound.map(|row| row.saturating_sub(list_object.totals_rows));
+                (Some(vec![Pattern::new(&Pattern::escape(&list_object.sheet_name))?]), Some(range))
+            }
+            None => {
+                let sheet_name_pattern = parameters.sheet_name.as_ref().map(|pattern| vec![pattern.to_owned()]);
+                spreadsheet.resolve_range(parameters.range.as_ref(), sheet_name_pattern)?
+            }
+        };
+        // Disjoint ranges are read within the range covering them all
+        let ranges = parameters.ranges.to_owned().unwrap_or_default();
+        let range = range.or(Range::cover(&ranges));
+        let range = Range::skip_rows(range, parameters.skip);
 
         // Set default values for optional parameters
-        let header = parameters.header.unwrap_or(true);
+        let header = match &list_object {
+            Some(list_object) => list_object.has_header,
+            None => parameters.header.unwrap_or(true),
+        };
         let nulls = parameters.nulls.to_owned().unwrap_or(HashSet::from(["".to_string()]));
         let error_as_null = parameters.error_as_null.unwrap_or(false);
         let skip_empty_rows = parameters.skip_empty_rows.unwrap_or(false);
         let end_at_empty_row = parameters.end_at_empty_row.unwrap_or(false);
+        let midnight_as_date = parameters.midnight_as_date.unwrap_or(false);
+        let epoch_date_as_time = parameters.epoch_date_as_time.unwrap_or(false);
+        let duration_as_interval = parameters.duration_as_interval.unwrap_or(false);
+        let lenient = parameters.lenient.unwrap_or(false);
+        let original_values = parameters.original_values.unwrap_or(false);
+        let formulas = parameters.formulas.unwrap_or(false);
+        let fix_encoding = parameters.fix_encoding.unwrap_or(false);
+        let all_varchar = parameters.all_varchar.unwrap_or(false);
+        let display_values = parameters.display_values.unwrap_or(false);
+        let include_hidden_columns = parameters.include_hidden_columns.unwrap_or(true);
+        let require_header = parameters.require_header.unwrap_or(false);
 
         // Analyze the sheet structure to determine column types and bounds
-        let tables = spreadsheet.analyze_sheets(header, &Criteria {
+        let analyze_criteria = Criteria {
             sheet_name_patterns: sheet_name_pattern.to_owned(),
             sheet_limit: Some(1),
-            range: parameters.range,
-            rows_limit: parameters.analyze_rows.or(Some(10)),
+            range,
+            ranges: ranges.to_owned(),
+            rows_limit: parameters.analyze_rows.or(Some(default_analyze_rows(parameters.stable_rows))).filter(|_| parameters.sample.is_none()),
+            sample_rows: parameters.sample,
+            stable_rows: parameters.stable_rows,
             nulls: nulls.to_owned(),
             error_as_null,
             skip_empty_rows,
             end_at_empty_row,
-        }, parameters.columns.as_ref().unwrap_or(&vec![]))?;
+            midnight_as_date,
+            epoch_date_as_time,
+            duration_as_interval,
+            display_values,
+            include_hidden_columns,
+            require_header,
+            date_locale: parameters.date_locale,
+            header_aliases: parameters.header_aliases.to_owned().unwrap_or_default(),
+            missing_header_name: parameters.missing_header_name.to_owned(),
+            max_header_length: parameters.max_header_length.unwrap_or(MAX_HEADER_LENGTH),
+            lenient,
+            original_values,
+            formulas,
+            fix_encoding,
+            all_varchar,
+            normalize_names: parameters.normalize_names.unwrap_or(false),
+            ..Default::default()
+        };
+        let presets = parameters.columns.to_owned().unwrap_or_default();
+        let mut tables = spreadsheet.analyze_sheets(header, &analyze_criteria, &presets)?;
+        // Tables without a header row still name their columns
+        if let (Some(list_object), Some(range)) = (list_object.as_ref().filter(|it| !it.has_header), range) {
+            for table in &mut tables {
+                for (column, (col, _)) in table.columns.iter_mut().zip(&table.headers) {
+                    let index = col - range.col_lower_bound.unwrap_or_default();
+                    if let Some(name) = list_object.column_names.get(index) {
+                        column.name = name.to_owned();
+                    }
+                }
+            }
+        }
+        if !auto_detect {
+            for table in &mut tables {
+                apply_declared_columns(table, &spreadsheet.name(), &presets)?;
+            }
+        }
 
-        // Extract the first matching sheet or return error if no match found
-        let table = tables.get(0).ok_or_else(|| ExtensionError::SheetWildcardError(
-            spreadsheet.name().to_owned(),
-            parameters.sheet_name.as_ref().map(|it| it.to_string()).unwrap_or(String::new()),
-        ))?;
-        let mut columns = table.columns.to_owned();
+        // Extract the first matching sheet or return error if no match found.
+        // Empty sheets are not analyzed; with empty_ok they read as zero rows.
+        let table = tables.first();
+        let empty_sheet_name = match table {
+            None if parameters.empty_ok.unwrap_or(false) => spreadsheet.read_sheets(&analyze_criteria)?
+                .into_iter()
+                .next()
+                .map(|sheet| sheet.name),
+            _ => None,
+        };
+        if table.is_none() && empty_sheet_name.is_none() {
+            Err(ExtensionError::SheetWildcardError(
+                spreadsheet.name().to_owned(),
+                sheet_name_pattern.iter().flatten().map(Pattern::as_str).collect::<Vec<_>>().join(", "),
+            ))?
+        }
+        let sheet_name = table.map(|table| table.name.to_owned()).or(empty_sheet_name).unwrap_or_default();
+        if let Some(expect_columns) = &parameters.expect_columns {
+            expect_columns.check(&spreadsheet.name(), &sheet_name, "columns", table.map(|table| table.columns.len()).unwrap_or(0))?;
+        }
+        let data_columns = match table {
+            Some(table) => table.columns.to_owned(),
+            None => match &parameters.columns {
+                Some(presets) => declared_columns(presets),
+                None => vec![Column {
+                    name: fallback_names(&[0], vec![None], parameters.missing_header_name.as_deref()).remove(0),
+                    kind: ColumnType::Varchar,
+                }],
+            },
+        };
+        let defaults = default_cells(&data_columns, &parameters.defaults.to_owned().unwrap_or_default())?;
+        let mut columns = data_columns.to_owned();
+        let mut phonetic_columns = HashMap::<usize, usize>::new();
+        if let Some(suffix) = &parameters.phonetic_suffix {
+            for (index, column) in data_columns.iter().enumerate() {
+                if column.kind == ColumnType::Varchar {
+                    phonetic_columns.insert(columns.len(), index);
+                    columns.push(Column {
+                        name: format!("{}{}", column.name, suffix),
+                        kind: ColumnType::Varchar,
+                    });
+                }
+            }
+        }
         let sheet_name_column = parameters.sheet_name_column.as_ref().map(|_| columns.len());
         if let Some(name) = &parameters.sheet_name_column {
             columns.push(Column {
@@ -156,39 +486,59 @@ impl TryFrom<&ReadSheetParameters> for ReadSheetBindData {
             });
         }
 
-        // Read the actual data from the spreadsheet using the analyzed structure
-        let sheets = spreadsheet.read_sheets(&Criteria {
+        // Criteria for reading the actual data using the analyzed structure
+        let criteria = Criteria {
             sheet_name_patterns: sheet_name_pattern.to_owned(),
             sheet_limit: Some(1),
-            range: Some(Range {
+            range: table.map(|table| Range {
                 row_lower_bound: table.row_lower_bound,
-                row_upper_bound: parameters.range.and_then(|it| it.row_upper_bound),
+                row_upper_bound: range.and_then(|it| it.row_upper_bound),
                 col_lower_bound: Some(table.col_lower_bound),
                 col_upper_bound: Some(table.col_upper_bound),
             }),
-            rows_limit: None,
-            nulls: nulls.to_owned(),
+            ranges,
+            nulls,
             error_as_null,
             skip_empty_rows,
             end_at_empty_row,
-        })?;
+            midnight_as_date,
+            epoch_date_as_time,
+            duration_as_interval,
+            display_values,
+            include_hidden_columns,
+            require_header,
+            date_locale: parameters.date_locale,
+            max_cell_length: parameters.max_cell_length.map(|length| (length, parameters.truncation_suffix.to_owned().unwrap_or_default())),
+            lenient,
+            original_values,
+            formulas,
+            fix_encoding,
+            all_varchar,
+            ..Default::default()
+        };
 
-        let shared_strings = shared_strings
-            .into_iter()
-            .map(|shared_string| {
-                if !nulls.contains(&shared_string) {
-                    Some(shared_string)
-                } else {
-                    None
-                }
-            })
-            .collect();
         Ok(ReadSheetBindData {
+            file_name: parameters.file_name.to_owned(),
             columns,
             file_name_column,
             sheet_name_column,
-            sheets,
-            shared_strings,
+            phonetic_columns,
+            defaults,
+            spreadsheet: Mutex::new(spreadsheet),
+            criteria,
+            sheet_name,
+            empty: table.is_none(),
+            expect_rows: parameters.expect_rows,
+            options: WriteOptions {
+                decimal: DecimalPolicy {
+                    rounding: parameters.decimal_rounding.unwrap_or_default(),
+                    scale_overflow: parameters.decimal_scale_overflow.unwrap_or_default(),
+                },
+                varchar_case: parameters.varchar_case.unwrap_or_default(),
+                dates_as_varchar: parameters.dates_as_varchar.unwrap_or(false),
+            },
+            ignore_errors: parameters.ignore_errors.unwrap_or(false),
+            spill_threshold: parameters.spill_threshold,
         })
     }
 }
@@ -201,6 +551,105 @@ pub(crate) struct ReadSheetInitData {
     index: AtomicUsize,
     /// Column indices that should be projected (output) from the source data
     projections: Vec<usize>,
+    /// Whether no data column is projected, so only rows are counted (e.g. `count(*)`)
+    count_only: bool,
+    /// Data columns left out of the projection, still converted to drop bad rows with ignore_errors
+    unprojected: Vec<usize>,
+    /// Loaded sheet data organized in chunks for efficient processing
+    sheets: Vec<Sheet>,
+    /// Number of chunks of `sheets` emitted before the whole sheet is read, when `sheets` is a preview
+    preview_chunks: Option<usize>,
+    /// Rest of the sheet past the settled chunks of the preview, read once they are used up
+    remaining_sheets: OnceLock<Vec<Sheet>>,
+    /// Shared string table for efficient string storage (XLSX/XLSB format)
+    shared_strings: SharedStrings,
+    /// Phonetic readings of the shared strings, loaded when a phonetic column is projected
+    phonetic_strings: Vec<Option<String>>,
+    /// Chunk buffers reused across func() calls
+    buffers: ChunkBufferPool,
+}
+
+impl ReadSheetInitData {
+    /// Reads the analyzed sheet, skipping cell values and shared strings when only rows are counted.
+    ///
+    /// DuckDB does not pass `LIMIT` to table functions, so only the first rows are read here
+    /// unless the row count is checked; the rest of the sheet is read by `sheet` once a query
+    /// asks for more chunks than the preview settled, starting after them.
+    fn read(bind: &ReadSheetBindData, projections: Vec<usize>) -> Result<Self, RustySheetError> {
+        // Rows dropped by ignore_errors are only known once every data column is converted
+        let count_only = !bind.ignore_errors && !projections.iter().any(|col| bind.is_data_column(*col));
+        let unprojected = (0..bind.columns.len())
+            .filter(|col| bind.ignore_errors && bind.is_data_column(*col) && !bind.phonetic_columns.contains_key(col) && !projections.contains(col))
+            .collect();
+        let preview = !count_only && bind.expect_rows.is_none();
+        let mut spreadsheet = bind.spreadsheet.lock().expect("Spreadsheet lock");
+        let criteria = Criteria {
+            count_only,
+            rows_limit: Some(PREVIEW_ROWS).filter(|_| preview),
+            ..bind.criteria.clone()
+        };
+        let (mut sheets, shared_strings) = if bind.empty {
+            (Vec::new(), SharedStrings::default())
+        } else if count_only {
+            (spreadsheet.read_sheets(&criteria)?, SharedStrings::default())
+        } else {
+            let strings = SharedStringsBuilder::new(&bind.criteria.nulls, &bind.criteria.max_cell_length, bind.criteria.fix_encoding, bind.spill_threshold);
+            spreadsheet.read_sheets_and_shared_strings(&criteria, strings)?
+        };
+        spreadsheet.localize_dates(&mut sheets, &criteria, false)?;
+        if let Some(expect_rows) = &bind.expect_rows {
+            expect_rows.check(&spreadsheet.name(), &bind.sheet_name, "rows", sheets.first().map(Sheet::row_count).unwrap_or(0))?;
+        }
+        let phonetic_strings = if projections.iter().any(|col| bind.phonetic_columns.contains_key(col)) {
+            spreadsheet.load_phonetic_strings()?
+        } else {
+            Vec::new()
+        };
+        let preview_chunks = Some(sheets.first().map(Sheet::settled_chunks).unwrap_or(0)).filter(|_| preview);
+        Ok(ReadSheetInitData {
+            index: AtomicUsize::new(0),
+            projections,
+            count_only,
+            unprojected,
+            sheets,
+            preview_chunks,
+            remaining_sheets: OnceLock::new(),
+            shared_strings,
+            phonetic_strings,
+            buffers: ChunkBufferPool::default(),
+        })
+    }
+
+    /// Returns the sheet holding a chunk with the index of the chunk within it: the preview
+    /// for its settled chunks, otherwise the rest of the sheet, read from the row after them
+    /// on the first chunk past them. The shared strings of the preview are complete and kept.
+    fn sheet(&self, bind: &ReadSheetBindData, index: usize) -> Result<Option<(&Sheet, usize)>, RustySheetError> {
+        match self.preview_chunks {
+            Some(chunks) if index >= chunks => {
+                if self.remaining_sheets.get().is_none() {
+                    // The spreadsheet lock lets a single thread read the rest of the sheet
+                    let mut spreadsheet = bind.spreadsheet.lock().expect("Spreadsheet lock");
+                    if self.remaining_sheets.get().is_none() {
+                        let settled_row = chunks.checked_sub(1)
+                            .and_then(|chunk| self.sheets.first()?.chunks.get(chunk))
+                            .map(|(_, row_upper, _, _)| *row_upper);
+                        let criteria = Criteria {
+                            range: bind.criteria.range.map(|range| Range {
+                                row_lower_bound: settled_row.map(|row| row + 1).or(range.row_lower_bound),
+                                ..range
+                            }),
+                            ..bind.criteria.clone()
+                        };
+                        let mut sheets = spreadsheet.read_sheets(&criteria)?;
+                        spreadsheet.localize_dates(&mut sheets, &criteria, false)?;
+                        let _ = self.remaining_sheets.set(sheets);
+                    }
+                }
+                Ok(self.remaining_sheets.get().and_then(|sheets| sheets.first()).map(|sheet| (sheet, index - chunks)))
+            }
+            _ => Ok(self.sheets.first().map(|sheet| (sheet, index))),
+        }
+    }
 }
 
 /// Main table function implementation for reading single sheets from spreadsheets.
@@ -218,7 +667,7 @@ impl VTab for ReadSheetTableFunction {
         let data = ReadSheetBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
         // Register output columns with DuckDB
         for column in &data.columns {
-            bind.add_result_column(column.name.as_str(), LogicalTypeHandle::from(column.kind.to_logical_type_id()));
+            bind.add_result_column(column.name.as_str(), data.options.logical_type(column.kind));
         }
         Ok(data)
     }
@@ -226,14 +675,13 @@ impl VTab for ReadSheetTableFunction {
     /// Initializes the table function for execution.
     /// This sets up the processing state and column projections for the current query.
     fn init(init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        let bind = unsafe { &*init.get_bind_data::<Self::BindData>() };
         let projections = init.get_column_indices()
             .into_iter()
             .map(|index| index as usize)
             .collect::<Vec<_>>();
-        Ok(ReadSheetInitData {
-            index: AtomicUsize::new(0),
-            projections,
-        })
+        let data = ReadSheetInitData::read(bind, projections).with_prefix(bind.file_name.as_str())?;
+        Ok(data)
     }
 
     /// Executes the table function to produce data chunks.
@@ -244,28 +692,83 @@ impl VTab for ReadSheetTableFunction {
     ) -> Result<(), Box<dyn Error>> {
         let bind = func.get_bind_data();
         let init = func.get_init_data();
-        let sheet = &bind.sheets[0];
-        let shared_strings = &bind.shared_strings;
         let index = init.index.fetch_add(1, Ordering::Relaxed);
-        if index < sheet.chunks.len() {
+        let Some((sheet, index)) = init.sheet(bind, index).with_prefix(bind.file_name.as_str())? else {
+            output.set_len(0);
+            return Ok(());
+        };
+        let shared_strings = &init.shared_strings;
+        if init.count_only {
+            // Only rows are counted: emit chunk lengths without building the cell tables
+            let len = sheet.chunk_len(index).unwrap_or(0);
+            output.set_len(len);
+            for (index, col) in init.projections.iter().enumerate() {
+                let mut vector = output.flat_vector(index);
+                for row in 0..len {
+                    if bind.file_name_column.map(|column| column == *col).unwrap_or(false) {
+                        write_string(&mut vector, row, sheet.file_name.as_str());
+                    } else if bind.sheet_name_column.map(|column| column == *col).unwrap_or(false) {
+                        write_string(&mut vector, row, sheet.name.as_str());
+                    } else {
+                        vector.set_null(row);
+                    }
+                }
+            }
+        } else if index < sheet.chunks.len() {
             let mut vectors: Vec<_> = (0..init.projections.len()).map(|index| output.flat_vector(index)).collect();
-            if let Some(table) = sheet.chunk(index) {
-                output.set_len(table.len());
-                for (row, record) in table.iter().enumerate() {
+            let mut buffer = init.buffers.take();
+            if sheet.layout_chunk(index, &mut buffer) {
+                // NULLs are set once the whole row is written, so that a row dropped by
+                // ignore_errors leaves no NULL behind in the slot the next row takes
+                let mut len = 0;
+                let mut nulls = Vec::new();
+                'rows: for row in 0..buffer.rows() {
+                    let is_valid = |col: &usize| buffer.cell(sheet, row, *col)
+                        .is_none_or(|cell| converts(&bind.columns[*col], cell, shared_strings, &bind.options));
+                    if !init.unprojected.iter().all(is_valid) {
+                        continue;
+                    }
+                    nulls.clear();
                     for (index, col) in init.projections.iter().enumerate() {
                         let vector = &mut vectors[index];
                         if bind.file_name_column.map(|column| column == *col).unwrap_or(false) {
-                            vector.insert(row, sheet.file_name.as_str());
+                            write_string(vector, len, sheet.file_name.as_str());
                         } else if bind.sheet_name_column.map(|column| column == *col).unwrap_or(false) {
-                            vector.insert(row, sheet.name.as_str());
-                        } else if let Some(cell) = record[*col] {
-                            let column = &bind.columns[*col];
-                            write_to_vector(sheet, column, cell, vector, row, shared_strings)?;
+                            write_string(vector, len, sheet.name.as_str());
+                        } else if let Some(source) = bind.phonetic_columns.get(col) {
+                            let phonetic = buffer.cell(sheet, row, *source)
+                                .filter(|cell| cell.kind == CellType::SharedString)
+                                .and_then(|cell| cell.value.parse::<usize>().ok())
+                                .and_then(|index| init.phonetic_strings.get(index))
+                                .and_then(|phonetic| phonetic.as_ref());
+                            match phonetic {
+                                Some(phonetic) => write_string(vector, len, phonetic),
+                                None => nulls.push(index),
+                            }
                         } else {
-                            vector.set_null(row);
+                            // Missing cells and cells reading as NULL take the default of the column
+                            let null = match buffer.cell(sheet, row, *col).map(|cell| write_to_vector(sheet, &bind.columns[*col], cell, vector, len, shared_strings, &bind.options)) {
+                                Some(Err(_)) if bind.ignore_errors => continue 'rows,
+                                Some(written) => written?,
+                                None => true,
+                            };
+                            if null {
+                                match bind.defaults.get(*col).and_then(Option::as_ref) {
+                                    Some(cell) => {
+                                        write_to_vector(sheet, &bind.columns[*col], cell, vector, len, shared_strings, &bind.options)?;
+                                    }
+                                    None => nulls.push(index),
+                                }
+                            }
                         }
                     }
+                    for &index in &nulls {
+                        vectors[index].set_null(len);
+                    }
+                    len += 1;
                 }
+                output.set_len(len);
+                init.buffers.give_back(buffer);
             } else {
                 output.set_len(0);
             }
@@ -296,13 +799,51 @@ impl VTab for ReadSheetTableFunction {
         Some(vec![
             SheetParam::definition(),
             RangeParam::definition(),
+            RangesParam::definition(),
+            SkipParam::definition(),
+            TableParam::definition(),
             HeaderParam::definition(),
             ColumnsParam::definition(),
+            AllVarcharParam::definition(),
+            DefaultsParam::definition(),
+            AutoDetectParam::definition(),
             AnalyzeRowsParam::definition(),
+            SampleParam::definition(),
+            StableRowsParam::definition(),
             NullsParam::definition(),
             ErrorAsNullParam::definition(),
+            IgnoreErrorsParam::definition(),
             SkipEmptyRowsParam::definition(),
             EndAtEmptyRowParam::definition(),
+            MidnightAsDateParam::definition(),
+            EpochDateAsTimeParam::definition(),
+            DurationAsIntervalParam::definition(),
+            DisplayValuesParam::definition(),
+            IncludeHiddenColumnsParam::definition(),
+            RequireHeaderParam::definition(),
+            EmptyOkParam::definition(),
+            ExpectRowsParam::definition(),
+            ExpectColumnsParam::definition(),
+            DateLocaleParam::definition(),
+            HeaderAliasesParam::definition(),
+            MissingHeaderNameParam::definition(),
+            MaxHeaderLengthParam::definition(),
+            NormalizeNamesParam::definition(),
+            SalvageParam::definition(),
+            LenientParam::definition(),
+            OriginalValuesParam::definition(),
+            FormulasParam::definition(),
+            FixEncodingParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+            SpillThresholdParam::definition(),
+            MaxCellLengthParam::definition(),
+            TruncationSuffixParam::definition(),
+            DecimalRoundingParam::definition(),
+            DecimalScaleOverflowParam::definition(),
+            VarcharCaseParam::definition(),
+            DatesAsVarcharParam::definition(),
+            PhoneticSuffixParam::definition(),
             FileNameColumnParam::definition(),
             SheetNameColumnParam::definition(),
         ])
@@ -1,32 +1,93 @@
 use crate::database::column::Column;
 use crate::database::column::ColumnType;
+use crate::database::decimal::DecimalPolicy;
+use crate::database::decimal::DecimalRounding;
+use crate::database::decimal::ScaleOverflow;
+use crate::database::range::RangeSpec;
 use crate::database::table::Table;
 use crate::error::ResultMessage;
 use crate::error::RustySheetError;
+use crate::extension::MaxHeaderLengthParam;
+use crate::extension::default_analyze_rows;
+use crate::extension::last_scan::record_scan;
+use crate::extension::last_scan::SheetScanStats;
+use crate::extension::read_manifest::ManifestEntry;
+use crate::extension::writer::converts;
+use crate::extension::writer::default_cells;
+use crate::extension::writer::write_string;
 use crate::extension::writer::write_to_vector;
+use crate::extension::writer::ChunkBufferPool;
+use crate::extension::writer::VarcharCase;
+use crate::extension::writer::WriteOptions;
+use crate::extension::AllVarcharParam;
 use crate::extension::AnalyzeRowsParam;
+use crate::extension::AutoDetectParam;
 use crate::extension::ColumnsParam;
+use crate::extension::DateLocaleParam;
+use crate::extension::DatesAsVarcharParam;
+use crate::extension::DefaultsParam;
+use crate::extension::DecimalRoundingParam;
+use crate::extension::DecimalScaleOverflowParam;
+use crate::extension::DedupeSheetsParam;
+use crate::extension::DisplayValuesParam;
+use crate::extension::DurationAsIntervalParam;
 use crate::extension::EndAtEmptyRowParam;
+use crate::extension::EpochDateAsTimeParam;
 use crate::extension::ErrorAsNullParam;
+use crate::extension::ExpectColumnsParam;
+use crate::extension::ExpectRowsParam;
 use crate::extension::ExtensionError;
 use crate::extension::FileNameColumnParam;
 use crate::extension::FilesParam;
+use crate::extension::FixEncodingParam;
+use crate::extension::FormulasParam;
+use crate::extension::HeaderAliasesParam;
 use crate::extension::HeaderParam;
+use crate::extension::IgnoreErrorsParam;
+use crate::extension::IncludeHiddenColumnsParam;
+use crate::extension::InterleaveFilesParam;
+use crate::extension::LenientParam;
+use crate::extension::ManifestParam;
+use crate::extension::MaxCellLengthParam;
+use crate::extension::MidnightAsDateParam;
+use crate::extension::MissingHeaderNameParam;
 use crate::extension::NamedParam;
+use crate::extension::NormalizeNamesParam;
 use crate::extension::NullsParam;
+use crate::extension::OnSheetEmptyParam;
+use crate::extension::OnSheetMissingParam;
+use crate::extension::OriginalValuesParam;
 use crate::extension::Param;
+use crate::extension::PasswordParam;
 use crate::extension::Range;
 use crate::extension::RangeParam;
+use crate::extension::RangesParam;
+use crate::extension::RequireHeaderParam;
+use crate::extension::SalvageParam;
+use crate::extension::SampleParam;
+use crate::extension::SchemaLockParam;
 use crate::extension::SheetNameColumnParam;
 use crate::extension::SheetsParam;
 use crate::extension::SkipEmptyRowsParam;
+use crate::extension::SkipParam;
+use crate::extension::SnapshotParam;
+use crate::extension::SpillThresholdParam;
+use crate::extension::StableRowsParam;
+use crate::extension::TruncationSuffixParam;
 use crate::extension::UnionByNameParam;
+use crate::extension::VarcharCaseParam;
+use crate::spreadsheet::cell::Cell;
 use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::locale::DateLocale;
+use crate::spreadsheet::apply_declared_columns;
 use crate::spreadsheet::open_spreadsheets;
+use crate::spreadsheet::shared_strings::SharedStrings;
+use crate::spreadsheet::shared_strings::SharedStringsBuilder;
 use crate::spreadsheet::sheet::Sheet;
+use crate::spreadsheet::Spreadsheet;
+use crate::spreadsheet::MAX_HEADER_LENGTH;
 use anyhow::Result;
 use duckdb::core::DataChunkHandle;
-use duckdb::core::Inserter;
 use duckdb::core::LogicalTypeHandle;
 use duckdb::vtab::BindInfo;
 use duckdb::vtab::InitInfo;
@@ -36,62 +97,300 @@ use glob::Pattern;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::Instant;
 
-/// Parameters for the read_sheets table function
-struct ReadSheetsParameters {
+/// Handling of a file without matching sheets, or of a matched sheet without data
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(super) enum SheetPolicy {
+    /// Leave it out of the union
+    #[default]
+    Skip,
+    /// Fail the read
+    Error,
+}
+
+impl SheetPolicy {
+    /// Parses a policy name ('skip' or 'error') given to the named parameter `param`.
+    pub(super) fn parse(param: &'static str, name: &str) -> Result<Self, RustySheetError> {
+        match name.to_ascii_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "error" => Ok(Self::Error),
+            _ => Err(ExtensionError::SheetPolicyError(param, name.to_string()))?,
+        }
+    }
+}
+
+/// Schema of a union by name
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) enum SchemaLock {
+    /// Every column of every sheet, in order of appearance
+    #[default]
+    Union,
+    /// The columns of the first file with sheets
+    First,
+    /// The columns of the first file with sheets whose name matches the pattern
+    File(Pattern),
+}
+
+impl SchemaLock {
+    /// Parses a schema lock ('union', 'first' or 'file:<pattern>').
+    pub(super) fn parse(value: &str) -> Result<Self, RustySheetError> {
+        match value.to_ascii_lowercase().as_str() {
+            "union" => return Ok(Self::Union),
+            "first" => return Ok(Self::First),
+            _ => {}
+        }
+        match value.split_once(':') {
+            Some((kind, pattern)) if kind.eq_ignore_ascii_case("file") && !pattern.is_empty() => Ok(Self::File(Pattern::new(pattern)?)),
+            _ => Err(ExtensionError::SchemaLockError(value.to_string()))?,
+        }
+    }
+
+    /// Checks whether the schema is taken from the given file, files being tried in the order they are read.
+    fn is_reference(&self, file_name: &str) -> bool {
+        match self {
+            Self::Union => false,
+            Self::First => true,
+            Self::File(pattern) => pattern.matches(file_name),
+        }
+    }
+}
+
+/// Number of rows or columns a sheet is expected to have, exactly or within bounds
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(super) struct Expectation {
+    /// Smallest accepted count, None when unbounded
+    min: Option<usize>,
+    /// Largest accepted count, None when unbounded
+    max: Option<usize>,
+}
+
+impl Expectation {
+    /// Parses a count ('1000') or bounds ('900:1100', '900:' or ':1100') given to the named parameter `param`.
+    pub(super) fn parse(param: &'static str, value: &str) -> Result<Self, RustySheetError> {
+        let parse_bound = |bound: &str| match bound.trim() {
+            "" => Ok(None),
+            bound => bound.parse::<usize>().map(Some),
+        };
+        let expectation = match value.split_once(':') {
+            Some((min, max)) => parse_bound(min).and_then(|min| parse_bound(max).map(|max| Expectation { min, max })),
+            None => parse_bound(value).map(|count| Expectation { min: count, max: count }),
+        };
+        match expectation {
+            Ok(expectation) if expectation.min.is_some() || expectation.max.is_some() => Ok(expectation),
+            _ => Err(ExtensionError::ExpectationError(param, value.to_string()))?,
+        }
+    }
+
+    /// Checks the actual count of the `unit` (rows or columns) of a sheet against the expectation.
+    pub(super) fn check(&self, file_name: &str, sheet_name: &str, unit: &'static str, count: usize) -> Result<(), RustySheetError> {
+        if self.min.is_some_and(|min| count < min) || self.max.is_some_and(|max| count > max) {
+            Err(ExtensionError::ExpectationFailedError(file_name.to_string(), sheet_name.to_string(), count, unit, self.to_string()))?
+        }
+        Ok(())
+    }
+}
+
+impl Display for Expectation {
+    /// Formats the expectation as shown in error messages, e.g. `at least 900`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min == max => write!(f, "{min}"),
+            (Some(min), Some(max)) => write!(f, "{min} to {max}"),
+            (Some(min), None) => write!(f, "at least {min}"),
+            (None, Some(max)) => write!(f, "at most {max}"),
+            (None, None) => write!(f, "any number"),
+        }
+    }
+}
+
+/// Parameters for the read_sheets and read_manifest table functions
+pub(super) struct ReadSheetsParameters {
     /// List of spreadsheet file paths to read
     files: Vec<String>,
+    /// Sheet and range of each file, overriding `sheets` and `range` (read_manifest only)
+    manifest: Option<Vec<ManifestEntry>>,
     /// Optional sheet name patterns to filter which sheets to read
     sheets: Option<Vec<(Option<Pattern>, Pattern)>>,
-    /// Optional range specification for data extraction
-    range: Option<Range>,
+    /// Optional range specification or defined name for data extraction
+    range: Option<RangeSpec>,
+    /// Disjoint blocks of cells read as one table instead of a single range (default: none)
+    ranges: Option<Vec<Range>>,
+    /// Number of leading rows dropped before header detection (default: 0)
+    skip: Option<usize>,
     /// Whether to treat first row as header (default: true)
     header: Option<bool>,
     /// Union sheets data by name (true) or position (false) (default: false)
     union_by_name: Option<bool>,
+    /// Columns kept by a union by name: every column, or those of a reference file (default: union)
+    schema_lock: Option<SchemaLock>,
+    /// Skip sheets whose data is identical to an earlier sheet of the union (default: false)
+    dedupe_sheets: Option<bool>,
+    /// Alternate the chunks of the files instead of emitting files one after another (default: false)
+    interleave_files: Option<bool>,
+    /// Handling of files none of whose sheets match the sheet patterns (default: skip)
+    on_sheet_missing: Option<SheetPolicy>,
+    /// Handling of matched sheets without any data (default: skip)
+    on_sheet_empty: Option<SheetPolicy>,
+    /// Number of data rows each sheet must have (default: any)
+    expect_rows: Option<Expectation>,
+    /// Number of data columns each sheet must have (default: any)
+    expect_columns: Option<Expectation>,
     /// Column type specifications with pattern matching
     columns: Option<Vec<(Pattern, ColumnType)>>,
+    /// Type every column as VARCHAR instead of detecting its type (default: false)
+    all_varchar: Option<bool>,
+    /// Values written in place of NULL cells, by column name (default: none)
+    defaults: Option<HashMap<String, String>>,
+    /// Infer the columns instead of taking them from `columns` alone (default: true)
+    auto_detect: Option<bool>,
     /// Number of rows to analyze for type detection
     analyze_rows: Option<usize>,
+    /// Number of rows sampled uniformly from the whole sheet for type detection (default: none)
+    sample: Option<usize>,
+    /// Consecutive values of one type after which a column's type is stable (default: none)
+    stable_rows: Option<usize>,
     /// null literals (default: empty string)
     nulls: Option<HashSet<String>>,
     /// Convert parsing errors to NULL values (default: false)
     error_as_null: Option<bool>,
+    /// Drop the rows with a cell that cannot be converted to its column type instead of failing (default: false)
+    ignore_errors: Option<bool>,
     /// Skip rows with no data (default: false)
     skip_empty_rows: Option<bool>,
     /// Stop reading at first empty row (default: false)
     end_at_empty_row: Option<bool>,
+    /// Classify ISO datetimes at midnight as dates (default: false)
+    midnight_as_date: Option<bool>,
+    /// Classify ISO datetimes on the 1900/1904 epoch date as times (default: false)
+    epoch_date_as_time: Option<bool>,
+    /// Classify text durations like "2 05:30:00" as intervals (default: false)
+    duration_as_interval: Option<bool>,
+    /// Render numeric cells as displayed by their number format (default: false)
+    display_values: Option<bool>,
+    /// Include hidden, zero-width and collapsed columns (default: true)
+    include_hidden_columns: Option<bool>,
+    /// Fail when every header cell is empty or a null literal (default: false)
+    require_header: Option<bool>,
+    /// Locale of month names parsed in text dates (default: none)
+    date_locale: Option<DateLocale>,
+    /// Canonical column names by localized header text (default: none)
+    header_aliases: Option<HashMap<String, String>>,
+    /// Template naming columns without a header, e.g. 'column_{n}' (default: column letters)
+    missing_header_name: Option<String>,
+    /// Maximum number of characters of column names taken from headers (default: 255)
+    max_header_length: Option<usize>,
+    /// Lowercase, de-accent and underscore the column names taken from headers (default: false)
+    normalize_names: Option<bool>,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Keep the cells parsed before a structural error instead of failing (default: false)
+    lenient: Option<bool>,
+    /// Read the values cells held before the tracked changes instead of the latest ones (default: false)
+    original_values: Option<bool>,
+    /// Read the formulas of formula cells instead of their cached values (default: false)
+    formulas: Option<bool>,
+    /// Repair text whose UTF-8 bytes were decoded as Windows-1252 or Latin-1 (default: false)
+    fix_encoding: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+    /// Number of shared strings above which the shared string table is spilled to disk (default: never)
+    spill_threshold: Option<usize>,
+    /// Maximum number of characters kept in text cells (default: unlimited)
+    max_cell_length: Option<usize>,
+    /// Suffix appended to truncated text cells (default: none)
+    truncation_suffix: Option<String>,
+    /// Rounding of digits beyond the scale of DECIMAL columns (default: half_even)
+    decimal_rounding: Option<DecimalRounding>,
+    /// Handling of values exceeding the scale of DECIMAL columns (default: round)
+    decimal_scale_overflow: Option<ScaleOverflow>,
+    /// Case folding of VARCHAR values: 'lower', 'upper' or 'preserve' (default: 'preserve')
+    varchar_case: Option<VarcharCase>,
+    /// Write DATE, TIME and TIMESTAMP columns as ISO 8601 strings (default: false)
+    dates_as_varchar: Option<bool>,
     /// column name for file name of record
     file_name_column: Option<String>,
     /// column name for sheet name of record
     sheet_name_column: Option<String>,
 }
 
-impl TryFrom<&BindInfo> for ReadSheetsParameters {
-    type Error = RustySheetError;
-
+impl ReadSheetsParameters {
     /// Construct ReadSheetsParameters from DuckDB bind information
     ///
     /// # Arguments
     /// * `bind` - DuckDB bind information containing function parameters
+    /// * `manifest` - Whether the positional parameter is a manifest of files (read_manifest)
+    ///   rather than file patterns (read_sheets)
     ///
     /// # Returns
-    /// * `Result<Self, Self::Error>` - Parameters or error if parsing fails
-    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+    /// * `Result<Self, RustySheetError>` - Parameters or error if parsing fails
+    pub(super) fn read(bind: &BindInfo, manifest: bool) -> Result<Self, RustySheetError> {
+        let (files, manifest) = if manifest {
+            let entries = ManifestParam::read(bind, 0)?;
+            (entries.iter().map(|entry| entry.file_name.to_owned()).collect(), Some(entries))
+        } else {
+            (FilesParam::read(bind, 0)?, None)
+        };
         Ok(ReadSheetsParameters {
-            files: FilesParam::read(bind, 0)?,
+            files,
+            manifest,
             sheets: SheetsParam::read(bind)?,
             range: RangeParam::read(bind)?,
+            ranges: RangesParam::read(bind)?,
+            skip: SkipParam::read(bind)?,
             header: HeaderParam::read(bind)?,
             union_by_name: UnionByNameParam::read(bind)?,
+            schema_lock: SchemaLockParam::read(bind)?,
+            dedupe_sheets: DedupeSheetsParam::read(bind)?,
+            interleave_files: InterleaveFilesParam::read(bind)?,
+            on_sheet_missing: OnSheetMissingParam::read(bind)?,
+            on_sheet_empty: OnSheetEmptyParam::read(bind)?,
+            expect_rows: ExpectRowsParam::read(bind)?,
+            expect_columns: ExpectColumnsParam::read(bind)?,
             columns: ColumnsParam::read(bind)?,
+            all_varchar: AllVarcharParam::read(bind)?,
+            defaults: DefaultsParam::read(bind)?,
+            auto_detect: AutoDetectParam::read(bind)?,
             analyze_rows: AnalyzeRowsParam::read(bind)?,
+            sample: SampleParam::read(bind)?,
+            stable_rows: StableRowsParam::read(bind)?,
             nulls: NullsParam::read(bind)?,
             error_as_null: ErrorAsNullParam::read(bind)?,
+            ignore_errors: IgnoreErrorsParam::read(bind)?,
             skip_empty_rows: SkipEmptyRowsParam::read(bind)?,
             end_at_empty_row: EndAtEmptyRowParam::read(bind)?,
+            midnight_as_date: MidnightAsDateParam::read(bind)?,
+            epoch_date_as_time: EpochDateAsTimeParam::read(bind)?,
+            duration_as_interval: DurationAsIntervalParam::read(bind)?,
+            display_values: DisplayValuesParam::read(bind)?,
+            include_hidden_columns: IncludeHiddenColumnsParam::read(bind)?,
+            require_header: RequireHeaderParam::read(bind)?,
+            date_locale: DateLocaleParam::read(bind)?,
+            header_aliases: HeaderAliasesParam::read(bind)?,
+            missing_header_name: MissingHeaderNameParam::read(bind)?,
+            max_header_length: MaxHeaderLengthParam::read(bind)?,
+            normalize_names: NormalizeNamesParam::read(bind)?,
+            salvage: SalvageParam::read(bind)?,
+            lenient: LenientParam::read(bind)?,
+            original_values: OriginalValuesParam::read(bind)?,
+            formulas: FormulasParam::read(bind)?,
+            fix_encoding: FixEncodingParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+            spill_threshold: SpillThresholdParam::read(bind)?,
+            max_cell_length: MaxCellLengthParam::read(bind)?,
+            truncation_suffix: TruncationSuffixParam::read(bind)?,
+            decimal_rounding: DecimalRoundingParam::read(bind)?,
+            decimal_scale_overflow: DecimalScaleOverflowParam::read(bind)?,
+            varchar_case: VarcharCaseParam::read(bind)?,
+            dates_as_varchar: DatesAsVarcharParam::read(bind)?,
             file_name_column: FileNameColumnParam::read(bind)?,
             sheet_name_column: SheetNameColumnParam::read(bind)?,
         })
@@ -101,14 +400,63 @@ impl TryFrom<&BindInfo> for ReadSheetsParameters {
 #[repr(C)]
 /// Data structure for the bind phase of the read_sheets table function
 pub(crate) struct ReadSheetsBindData {
-    /// Shared string tables for string reference resolution & loaded sheet data from each spreadsheet
-    spreadsheets: Vec<(Vec<Option<String>>, Vec<Sheet>, Vec<HashMap<usize, usize>>)>,
+    /// Opened spreadsheets with the name and read criteria of each analyzed sheet and its column mappings,
+    /// read once the projected columns are known
+    spreadsheets: Vec<(Mutex<Box<dyn Spreadsheet + Send + Sync>>, Vec<(String, Criteria)>, Vec<HashMap<usize, usize>>)>,
+    /// null literals (default: empty string)
+    nulls: HashSet<String>,
     /// Column definitions with names and types
     columns: Vec<Column>,
+    /// Cells written in place of the missing and NULL cells of each data column
+    defaults: Vec<Option<Cell>>,
     /// file name column index
     file_name_column: Option<usize>,
     /// sheet name column index
     sheet_name_column: Option<usize>,
+    /// Whether sheets identical to an earlier sheet are skipped
+    dedupe_sheets: bool,
+    /// Whether the chunks of the files are emitted round-robin
+    interleave_files: bool,
+    /// Number of data rows each sheet must have, checked once the sheets are read
+    expect_rows: Option<Expectation>,
+    /// Maximum number of characters kept in text cells and the suffix marking truncated values
+    max_cell_length: Option<(usize, String)>,
+    /// Whether shared strings whose UTF-8 bytes were decoded as Windows-1252 are repaired
+    fix_encoding: bool,
+    /// Number of shared strings above which the shared string table is spilled to disk
+    spill_threshold: Option<usize>,
+    /// Conversion options for DECIMAL and VARCHAR columns
+    options: WriteOptions,
+    /// Whether rows with a cell that cannot be converted to its column type are dropped
+    ignore_errors: bool,
+}
+
+impl ReadSheetsBindData {
+    /// Analyzes the spreadsheets and registers the output columns with DuckDB
+    pub(super) fn bind(bind: &BindInfo, parameters: &ReadSheetsParameters) -> Result<Self, Box<dyn Error>> {
+        let data = ReadSheetsBindData::try_from(parameters)?;
+        for column in &data.columns {
+            bind.add_result_column(column.name.as_str(), data.options.logical_type(column.kind));
+        }
+        Ok(data)
+    }
+
+    /// Returns the output columns, each with the type it is written as.
+    pub(super) fn output_columns(&self) -> Vec<Column> {
+        self.columns.iter()
+            .map(|column| Column {
+                name: column.name.to_owned(),
+                kind: self.options.column_type(column.kind),
+            })
+            .collect()
+    }
+
+    /// Checks if a column index refers to a sheet data column (not file name, sheet name or row id).
+    fn is_data_column(&self, col: usize) -> bool {
+        col < self.columns.len()
+            && self.file_name_column != Some(col)
+            && self.sheet_name_column != Some(col)
+    }
 }
 
 impl TryFrom<&ReadSheetsParameters> for ReadSheetsBindData {
@@ -118,9 +466,8 @@ impl TryFrom<&ReadSheetsParameters> for ReadSheetsBindData {
     ///
     /// This method performs the heavy lifting of:
     /// - Opening and analyzing spreadsheets
-    /// - Loading shared strings
     /// - Setting up column definitions
-    /// - Preparing sheet data for iteration
+    /// - Preparing the read criteria of each sheet, read in the init phase
     ///
     /// # Arguments
     /// * `parameters` - Function parameters from user input
@@ -134,7 +481,32 @@ impl TryFrom<&ReadSheetsParameters> for ReadSheetsBindData {
         let error_as_null = parameters.error_as_null.unwrap_or(false);
         let skip_empty_rows = parameters.skip_empty_rows.unwrap_or(false);
         let end_at_empty_row = parameters.end_at_empty_row.unwrap_or(false);
-        let rows_limit = parameters.analyze_rows.or(Some(10));
+        let midnight_as_date = parameters.midnight_as_date.unwrap_or(false);
+        let epoch_date_as_time = parameters.epoch_date_as_time.unwrap_or(false);
+        let duration_as_interval = parameters.duration_as_interval.unwrap_or(false);
+        let lenient = parameters.lenient.unwrap_or(false);
+        let original_values = parameters.original_values.unwrap_or(false);
+        let formulas = parameters.formulas.unwrap_or(false);
+        let fix_encoding = parameters.fix_encoding.unwrap_or(false);
+        let all_varchar = parameters.all_varchar.unwrap_or(false);
+        let display_values = parameters.display_values.unwrap_or(false);
+        let include_hidden_columns = parameters.include_hidden_columns.unwrap_or(true);
+        let require_header = parameters.require_header.unwrap_or(false);
+        let max_cell_length = parameters.max_cell_length
+            .map(|length| (length, parameters.truncation_suffix.to_owned().unwrap_or_default()));
+        let sample_rows = parameters.sample;
+        let rows_limit = parameters.analyze_rows.or(Some(default_analyze_rows(parameters.stable_rows))).filter(|_| sample_rows.is_none());
+        let auto_detect = parameters.auto_detect.unwrap_or(true);
+        if !auto_detect && parameters.columns.is_none() {
+            Err(ExtensionError::AutoDetectColumnsError)?
+        }
+        if parameters.range.is_some() && parameters.ranges.is_some() {
+            Err(ExtensionError::RangesError)?
+        }
+        let schema_lock = parameters.schema_lock.to_owned().unwrap_or_default();
+        if schema_lock != SchemaLock::Union && !union_by_name {
+            Err(ExtensionError::SchemaLockUnionError)?
+        }
         let default_preset_columns = vec![];
         let preset = parameters.columns.as_ref().unwrap_or(&default_preset_columns);
 
@@ -142,26 +514,116 @@ impl TryFrom<&ReadSheetsParameters> for ReadSheetsBindData {
         let mut shared_tables = None::<Vec<Table>>;
         let mut columns = Vec::<Column>::new();
         let mut columns_indexes = HashMap::<String, usize>::new();
-        for (spreadsheet, sheet_name_patterns) in open_spreadsheets(&parameters.files, &parameters.sheets)?.iter_mut() {
-            let tables = spreadsheet.analyze_sheets(header, &Criteria {
+        let on_sheet_missing = parameters.on_sheet_missing.unwrap_or_default();
+        let on_sheet_empty = parameters.on_sheet_empty.unwrap_or_default();
+        // Why each skipped file contributed no sheet, reported when no file does
+        let mut diagnostics = Vec::<String>::new();
+        // Files with sheets, analyzed before any is mapped so that a later file can lock the schema
+        let mut analyzed = Vec::new();
+        let spreadsheets_and_patterns = open_spreadsheets(&parameters.files, &parameters.sheets, parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        for (index, (mut spreadsheet, sheet_name_patterns)) in spreadsheets_and_patterns.into_iter().enumerate() {
+            // Manifest entries name the sheet and range of their file, falling back to `sheets` and `range`
+            let entry = parameters.manifest.as_ref().map(|entries| &entries[index]);
+            let sheet_name_patterns = match entry.and_then(|entry| entry.sheet_name.as_ref()) {
+                Some(pattern) => Some(vec![pattern.to_owned()]),
+                None => sheet_name_patterns,
+            };
+            let entry_range = entry.and_then(|entry| entry.range.as_ref());
+            let (sheet_name_patterns, range) = spreadsheet.resolve_range(entry_range.or(parameters.range.as_ref()), sheet_name_patterns)?;
+            // Disjoint ranges are read within the range covering them all, unless the entry has a range of its own
+            let ranges = parameters.ranges.to_owned().filter(|_| entry_range.is_none()).unwrap_or_default();
+            let range = range.or(Range::cover(&ranges));
+            let range = Range::skip_rows(range, parameters.skip);
+            let analyze_criteria = Criteria {
                 sheet_name_patterns: sheet_name_patterns.to_owned(),
-                sheet_limit: None,
-                range: parameters.range,
+                range,
+                ranges: ranges.to_owned(),
                 rows_limit,
+                sample_rows,
+                stable_rows: parameters.stable_rows,
                 nulls: nulls.to_owned(),
                 error_as_null,
                 skip_empty_rows,
                 end_at_empty_row,
-            }, preset)?;
-            if tables.is_empty() {
-                continue
-            } else if !union_by_name && shared_tables.is_none() {
+                midnight_as_date,
+                epoch_date_as_time,
+                duration_as_interval,
+                display_values,
+                include_hidden_columns,
+                require_header,
+                date_locale: parameters.date_locale,
+                header_aliases: parameters.header_aliases.to_owned().unwrap_or_default(),
+                missing_header_name: parameters.missing_header_name.to_owned(),
+                max_header_length: parameters.max_header_length.unwrap_or(MAX_HEADER_LENGTH),
+                lenient,
+                original_values,
+                formulas,
+                fix_encoding,
+                all_varchar,
+                normalize_names: parameters.normalize_names.unwrap_or(false),
+                ..Default::default()
+            };
+            let mut tables = spreadsheet.analyze_sheets(header, &analyze_criteria, preset)?;
+            if !auto_detect {
+                for table in &mut tables {
+                    apply_declared_columns(table, &spreadsheet.name(), preset)?;
+                }
+            }
+
+            // Empty sheets are not analyzed, so the sheet list tells them from sheets the patterns missed
+            if tables.is_empty() || on_sheet_empty == SheetPolicy::Error {
+                let matched_sheets = spreadsheet.list_sheets()?.into_iter()
+                    .filter(|sheet| analyze_criteria.accept(&sheet.name))
+                    .map(|sheet| sheet.name)
+                    .collect::<Vec<_>>();
+                if matched_sheets.is_empty() {
+                    let wildcard = sheet_name_patterns.iter().flatten().map(Pattern::as_str).collect::<Vec<_>>().join(", ");
+                    if on_sheet_missing == SheetPolicy::Error {
+                        Err(ExtensionError::SheetWildcardError(spreadsheet.name(), wildcard.to_owned()))?
+                    }
+                    diagnostics.push(format!("'{}' has no sheets matching '{}'", spreadsheet.name(), wildcard));
+                    continue;
+                }
+                let empty_sheets = matched_sheets.iter()
+                    .filter(|name| !tables.iter().any(|table| &table.name == *name))
+                    .map(|name| format!("'{name}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if on_sheet_empty == SheetPolicy::Error && !empty_sheets.is_empty() {
+                    Err(ExtensionError::SheetEmptyError(spreadsheet.name(), empty_sheets.to_owned()))?
+                }
+                if tables.is_empty() {
+                    diagnostics.push(format!("'{}' has only empty sheets {}", spreadsheet.name(), empty_sheets));
+                    continue;
+                }
+            }
+            analyzed.push((spreadsheet, tables, entry, range, ranges));
+        }
+
+        // A locked schema takes the columns of the reference file, the columns of other files not among them being dropped
+        let reference = analyzed.iter().find(|(spreadsheet, ..)| schema_lock.is_reference(&spreadsheet.name()));
+        if let (SchemaLock::File(pattern), None, false) = (&schema_lock, reference, analyzed.is_empty()) {
+            Err(ExtensionError::SchemaLockFileError(pattern.to_string()))?
+        }
+        let locked = reference.is_some();
+        for column in reference.iter().flat_map(|(_, tables, ..)| tables).flat_map(|table| &table.columns) {
+            if !columns_indexes.contains_key(&column.name) {
+                columns_indexes.insert(column.name.to_owned(), columns.len());
+                columns.push(column.clone());
+            }
+        }
+
+        for (spreadsheet, tables, entry, range, ranges) in analyzed {
+            if !union_by_name && shared_tables.is_none() {
                 shared_tables = Some(tables.clone());
             }
 
             let mut sheets = Vec::new();
             let mut sheets_columns_mappings = Vec::new();
             for actual_table in &tables {
+                if let Some(expect_columns) = &parameters.expect_columns {
+                    expect_columns.check(&spreadsheet.name(), &actual_table.name, "columns", actual_table.columns.len())?;
+                }
                 let table = if union_by_name {
                     actual_table
                 } else {
@@ -175,7 +637,8 @@ impl TryFrom<&ReadSheetsParameters> for ReadSheetsBindData {
                     let column_index = if let Some(column_index) = columns_indexes.get(&column.name) {
                         let column_index = *column_index;
                         let expected_column = &columns[column_index];
-                        if expected_column.kind != column.kind {
+                        // The type of a locked column wins, the cells being converted as they are read
+                        if expected_column.kind != column.kind && !locked {
                             Err(ExtensionError::ColumnTypeError(
                                 spreadsheet.name().to_owned(),
                                 table.name.to_owned(),
@@ -185,6 +648,8 @@ impl TryFrom<&ReadSheetsParameters> for ReadSheetsBindData {
                             ))?
                         }
                         column_index
+                    } else if locked {
+                        continue;
                     } else {
                         let column_index = columns.len();
                         columns_indexes.insert(column.name.to_owned(), column_index);
@@ -195,41 +660,41 @@ impl TryFrom<&ReadSheetsParameters> for ReadSheetsBindData {
                 }
                 sheets_columns_mappings.push(columns_mappings);
 
-                let actual_sheets = spreadsheet.read_sheets(&Criteria {
+                // A range of its own keeps the bounds of the sheet, even when unioned by position
+                let bounds = if entry.is_some_and(|entry| entry.range.is_some()) { actual_table } else { table };
+                sheets.push((actual_table.name.to_owned(), Criteria {
                     sheet_name_patterns: Some(vec![Pattern::new(&actual_table.name)?]), // 用实际的工作表名称精准匹配目标工作表
                     sheet_limit: Some(1),
                     range: Some(Range {
-                        row_lower_bound: table.row_lower_bound,
-                        row_upper_bound: parameters.range.and_then(|it| it.row_upper_bound),
-                        col_lower_bound: Some(table.col_lower_bound),
-                        col_upper_bound: Some(table.col_upper_bound),
+                        row_lower_bound: bounds.row_lower_bound,
+                        row_upper_bound: range.and_then(|it| it.row_upper_bound),
+                        col_lower_bound: Some(bounds.col_lower_bound),
+                        col_upper_bound: Some(bounds.col_upper_bound),
                     }),
-                    rows_limit: None,
+                    ranges: ranges.to_owned(),
                     nulls: nulls.to_owned(),
                     error_as_null,
                     skip_empty_rows,
                     end_at_empty_row,
-                }).with_prefix(table.name.as_str()).with_prefix(spreadsheet.name().as_str())?;
-                assert_eq!(actual_sheets.len(), 1);
-                sheets.extend(actual_sheets);
+                    midnight_as_date,
+                    epoch_date_as_time,
+                    duration_as_interval,
+                    display_values,
+                    include_hidden_columns,
+                    require_header,
+                    date_locale: parameters.date_locale,
+                    max_cell_length: max_cell_length.to_owned(),
+                    lenient,
+                    original_values,
+                    formulas,
+                    fix_encoding,
+                    all_varchar,
+                    ..Default::default()
+                }));
             }
-
-            let shared_strings = spreadsheet.load_shared_strings(None)
-                .map(|(shared_strings, _)| {
-                    shared_strings
-                        .into_iter()
-                        .map(|shared_string| {
-                            if !nulls.contains(&shared_string) {
-                                Some(shared_string)
-                            } else {
-                                None
-                            }
-                        })
-                        .collect()
-                })
-                .with_prefix(spreadsheet.name().as_str())?;
-            spreadsheets.push((shared_strings, sheets, sheets_columns_mappings));
+            spreadsheets.push((Mutex::new(spreadsheet), sheets, sheets_columns_mappings));
         }
+        let defaults = default_cells(&columns, &parameters.defaults.to_owned().unwrap_or_default())?;
         let sheet_name_column = parameters.sheet_name_column.as_ref().map(|_| columns.len());
         if let Some(name) = &parameters.sheet_name_column {
             columns.push(Column {
@@ -246,14 +711,31 @@ impl TryFrom<&ReadSheetsParameters> for ReadSheetsBindData {
         }
 
         if spreadsheets.is_empty() {
-            Err(ExtensionError::SheetNotFoundError)?;
+            Err(ExtensionError::SheetNotFoundError(diagnostics.join("; ")))?;
         }
 
         Ok(ReadSheetsBindData {
             spreadsheets,
+            nulls,
             columns,
+            defaults,
             file_name_column,
             sheet_name_column,
+            dedupe_sheets: parameters.dedupe_sheets.unwrap_or(false),
+            interleave_files: parameters.interleave_files.unwrap_or(false),
+            expect_rows: parameters.expect_rows,
+            max_cell_length,
+            fix_encoding,
+            spill_threshold: parameters.spill_threshold,
+            options: WriteOptions {
+                decimal: DecimalPolicy {
+                    rounding: parameters.decimal_rounding.unwrap_or_default(),
+                    scale_overflow: parameters.decimal_scale_overflow.unwrap_or_default(),
+                },
+                varchar_case: parameters.varchar_case.unwrap_or_default(),
+                dates_as_varchar: parameters.dates_as_varchar.unwrap_or(false),
+            },
+            ignore_errors: parameters.ignore_errors.unwrap_or(false),
         })
     }
 }
@@ -261,12 +743,224 @@ impl TryFrom<&ReadSheetsParameters> for ReadSheetsBindData {
 #[repr(C)]
 /// Data structure for the initialization phase of the read_sheets table function
 pub(crate) struct ReadSheetsInitData {
-    /// List of (sheet_index, chunk_index) pairs for iteration
+    /// List of (spreadsheet_index, sheet_index, chunk_index) triples for iteration,
+    /// ordered by file, then sheet, then row, or alternating between files when interleaved
     indexes: Vec<(usize, usize, usize)>,
     /// Atomic counter tracking current iteration position
     index: AtomicUsize,
     /// Column projection indices for selective column reading
     projections: Vec<usize>,
+    /// Whether no data column is projected, so only rows are counted (e.g. `count(*)`)
+    count_only: bool,
+    /// Data columns left out of the projection, still converted to drop bad rows with ignore_errors
+    unprojected: Vec<usize>,
+    /// Shared string tables for string reference resolution & loaded sheet data from each spreadsheet
+    spreadsheets: Vec<(SharedStrings, Vec<Sheet>)>,
+    /// Scan statistics of each loaded sheet, published to rusty_sheet_last_scan once the scan ends
+    stats: Vec<Vec<SheetScanStats>>,
+    /// Chunk buffers reused across func() calls
+    buffers: ChunkBufferPool,
+}
+
+impl ReadSheetsInitData {
+    /// Reads the analyzed sheets, skipping cell values and shared strings when only rows are counted.
+    ///
+    /// # Arguments
+    /// * `bind` - Bind data holding the opened spreadsheets and read criteria
+    /// * `projections` - Projected column indices
+    ///
+    /// # Returns
+    /// * `Result<Self, RustySheetError>` - Loaded sheets and iteration state or read error
+    pub(super) fn read(bind: &ReadSheetsBindData, projections: Vec<usize>) -> Result<Self, RustySheetError> {
+        // Values are always decoded when deduplicating, as duplicates are told apart by them, and
+        // with ignore_errors, as dropped rows are only known once every data column is converted
+        let count_only = !bind.dedupe_sheets && !bind.ignore_errors && !projections.iter().any(|col| bind.is_data_column(*col));
+        let unprojected = (0..bind.columns.len())
+            .filter(|col| bind.ignore_errors && bind.is_data_column(*col) && !projections.contains(col))
+            .collect::<Vec<_>>();
+        let mut spreadsheets = Vec::new();
+        let mut stats = Vec::new();
+        for (spreadsheet, sheets_criteria, _) in &bind.spreadsheets {
+            let mut spreadsheet = spreadsheet.lock().expect("Spreadsheet lock");
+            let mut sheets = Vec::new();
+            let mut sheets_stats = Vec::new();
+            for (name, criteria) in sheets_criteria {
+                let criteria = Criteria {
+                    count_only,
+                    ..criteria.clone()
+                };
+                let started = Instant::now();
+                let actual_sheets = spreadsheet.read_sheets(&criteria)
+                    .and_then(|mut sheets| spreadsheet.localize_dates(&mut sheets, &criteria, false).map(|_| sheets))
+                    .with_prefix(name.as_str()).with_prefix(spreadsheet.name().as_str())?;
+                assert_eq!(actual_sheets.len(), 1);
+                let sheet = &actual_sheets[0];
+                if let Some(expect_rows) = &bind.expect_rows {
+                    expect_rows.check(&spreadsheet.name(), &sheet.name, "rows", sheet.row_count())?;
+                }
+                sheets_stats.push(SheetScanStats::new(sheet.file_name.to_owned(), sheet.name.to_owned(), sheet.cells.len(), started.elapsed()));
+                sheets.extend(actual_sheets);
+            }
+            stats.push(sheets_stats);
+
+            let shared_strings = if count_only {
+                SharedStrings::default()
+            } else {
+                let mut strings = SharedStringsBuilder::new(&bind.nulls, &bind.max_cell_length, bind.fix_encoding, bind.spill_threshold);
+                spreadsheet.stream_shared_strings(&mut strings)
+                    .and_then(|_| strings.finish())
+                    .with_prefix(spreadsheet.name().as_str())?
+            };
+            spreadsheets.push((shared_strings, sheets));
+        }
+
+        let mut files_indexes = Vec::<Vec<(usize, usize, usize)>>::new();
+        let mut hashes = HashSet::<(u64, Vec<(usize, usize)>)>::new();
+        for (spreadsheet_index, (shared_strings, sheets)) in spreadsheets.iter().enumerate() {
+            let (_, _, sheets_columns_mappings) = &bind.spreadsheets[spreadsheet_index];
+            let mut indexes = Vec::<(usize, usize, usize)>::new();
+            for (sheet_index, sheet) in sheets.iter().enumerate() {
+                if bind.dedupe_sheets {
+                    // Identical cells only yield identical rows when mapped to the same columns
+                    let mut columns_mappings = sheets_columns_mappings[sheet_index].iter()
+                        .map(|(column_index, index)| (*column_index, *index))
+                        .collect::<Vec<_>>();
+                    columns_mappings.sort();
+                    if !hashes.insert((sheet.content_hash(shared_strings)?, columns_mappings)) {
+                        continue;
+                    }
+                }
+                for chunk_index in 0..sheet.chunks.len() {
+                    indexes.push((spreadsheet_index, sheet_index, chunk_index));
+                }
+            }
+            files_indexes.push(indexes);
+        }
+        let indexes = if bind.interleave_files {
+            // One chunk of each file in turn, so the first rows come from several files
+            let rounds = files_indexes.iter().map(Vec::len).max().unwrap_or(0);
+            (0..rounds)
+                .flat_map(|round| files_indexes.iter().filter_map(move |indexes| indexes.get(round).copied()))
+                .collect()
+        } else {
+            files_indexes.concat()
+        };
+        Ok(ReadSheetsInitData {
+            indexes,
+            index: AtomicUsize::new(0),
+            projections,
+            count_only,
+            unprojected,
+            spreadsheets,
+            stats,
+            buffers: ChunkBufferPool::default(),
+        })
+    }
+
+    /// Emits the next chunk of the scan, an empty chunk once every chunk has been emitted
+    ///
+    /// # Arguments
+    /// * `bind` - Bind data holding the columns and their mappings
+    /// * `output` - Output data chunk to populate
+    ///
+    /// # Returns
+    /// * `Result<Option<&Sheet>, Box<dyn Error>>` - Sheet the chunk was read from, None once every chunk has been emitted
+    pub(super) fn scan(&self, bind: &ReadSheetsBindData, output: &mut DataChunkHandle) -> Result<Option<&Sheet>, Box<dyn Error>> {
+        let index = self.index.fetch_add(1, Ordering::Relaxed);
+        if index < self.indexes.len() {
+            let mut vectors: Vec<_> = (0..self.projections.len()).map(|index| output.flat_vector(index)).collect();
+            let (spreadsheet_index, sheet_index, chunk_index) = self.indexes[index];
+            let (shared_strings, sheets) = &self.spreadsheets[spreadsheet_index];
+            let (_, _, sheets_columns_mappings) = &bind.spreadsheets[spreadsheet_index];
+            let sheet = &sheets[sheet_index];
+            let columns_mappings = &sheets_columns_mappings[sheet_index];
+            let stats = &self.stats[spreadsheet_index][sheet_index];
+            let mut buffer = self.buffers.take();
+            if self.count_only {
+                // Only rows are counted: emit chunk lengths without building the cell tables
+                let len = sheet.chunk_len(chunk_index).unwrap_or(0);
+                output.set_len(len);
+                stats.rows.fetch_add(len, Ordering::Relaxed);
+                for (index, col) in self.projections.iter().enumerate() {
+                    let vector = &mut vectors[index];
+                    for row in 0..len {
+                        if bind.file_name_column.map(|column| column == *col).unwrap_or(false) {
+                            write_string(vector, row, sheet.file_name.as_str());
+                        } else if bind.sheet_name_column.map(|column| column == *col).unwrap_or(false) {
+                            write_string(vector, row, sheet.name.as_str());
+                        } else {
+                            vector.set_null(row);
+                        }
+                    }
+                }
+            } else if sheet.layout_chunk(chunk_index, &mut buffer) {
+                // NULLs are set once the whole row is written, so that a row dropped by
+                // ignore_errors leaves no NULL behind in the slot the next row takes
+                let mut len = 0;
+                let mut nulls = 0;
+                let mut row_nulls = Vec::new();
+                'rows: for row in 0..buffer.rows() {
+                    let is_valid = |col: &usize| columns_mappings.get(col)
+                        .and_then(|column_index| buffer.cell(sheet, row, *column_index))
+                        .is_none_or(|cell| converts(&bind.columns[*col], cell, shared_strings, &bind.options));
+                    if !self.unprojected.iter().all(is_valid) {
+                        continue;
+                    }
+                    row_nulls.clear();
+                    for (index, col) in self.projections.iter().enumerate() {
+                        let vector = &mut vectors[index];
+                        if bind.file_name_column.map(|column| column == *col).unwrap_or(false) {
+                            write_string(vector, len, sheet.file_name.as_str());
+                        } else if bind.sheet_name_column.map(|column| column == *col).unwrap_or(false) {
+                            write_string(vector, len, sheet.name.as_str());
+                        } else {
+                            // Missing cells, cells reading as NULL and columns absent from the sheet
+                            // take the default of the column
+                            let null = match columns_mappings.get(col)
+                                .and_then(|column_index| buffer.cell(sheet, row, *column_index))
+                                .map(|cell| write_to_vector(sheet, &bind.columns[*col], cell, vector, len, shared_strings, &bind.options))
+                            {
+                                Some(Err(_)) if bind.ignore_errors => continue 'rows,
+                                Some(written) => written?,
+                                None => true,
+                            };
+                            if null {
+                                match bind.defaults.get(*col).and_then(Option::as_ref) {
+                                    Some(cell) => {
+                                        write_to_vector(sheet, &bind.columns[*col], cell, vector, len, shared_strings, &bind.options)?;
+                                    }
+                                    None => row_nulls.push(index),
+                                }
+                            }
+                        }
+                    }
+                    for &index in &row_nulls {
+                        vectors[index].set_null(len);
+                    }
+                    nulls += row_nulls.len();
+                    len += 1;
+                }
+                output.set_len(len);
+                stats.rows.fetch_add(len, Ordering::Relaxed);
+                stats.nulls.fetch_add(nulls, Ordering::Relaxed);
+            } else {
+                output.set_len(0);
+            }
+            self.buffers.give_back(buffer);
+            Ok(Some(sheet))
+        } else {
+            // No more data to process
+            output.set_len(0);
+            Ok(None)
+        }
+    }
+}
+
+impl Drop for ReadSheetsInitData {
+    /// Publishes the scan statistics, also when the query stopped early (e.g. `LIMIT`).
+    fn drop(&mut self) {
+        record_scan(std::mem::take(&mut self.stats).into_iter().flatten().collect());
+    }
 }
 
 /// DuckDB table function for reading multiple sheets from spreadsheet files
@@ -284,13 +978,8 @@ impl VTab for ReadSheetsTableFunction {
     /// # Returns
     /// * `Result<Self::BindData, Box<dyn Error>>` - Prepared bind data
     fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
-        let parameters = ReadSheetsParameters::try_from(bind)?;
-        let data = ReadSheetsBindData::try_from(&parameters)?;
-        // Register output columns with DuckDB
-        for column in &data.columns {
-            bind.add_result_column(column.name.as_str(), LogicalTypeHandle::from(column.kind.to_logical_type_id()));
-        }
-        Ok(data)
+        let parameters = ReadSheetsParameters::read(bind, false)?;
+        ReadSheetsBindData::bind(bind, &parameters)
     }
 
     /// Initialize phase: prepare iteration state and projections
@@ -301,26 +990,15 @@ impl VTab for ReadSheetsTableFunction {
     /// # Returns
     /// * `Result<Self::InitData, Box<dyn Error>>` - Initialized iteration state
     fn init(init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
-        let bind: *const Self::BindData = init.get_bind_data();
-        let mut indexes = Vec::<(usize, usize, usize)>::new();
-        unsafe {
-            for (spreadsheet_index, (_, sheets, _)) in (*bind).spreadsheets.iter().enumerate() {
-                for (sheet_index, sheet) in sheets.iter().enumerate() {
-                    for chunk_index in 0..sheet.chunks.len() {
-                        indexes.push((spreadsheet_index, sheet_index, chunk_index));
-                    }
-                }
-            }
-        };
+        let bind = unsafe { &*init.get_bind_data::<Self::BindData>() };
         let projections = init.get_column_indices()
             .into_iter()
             .map(|index| index as usize)
             .collect::<Vec<_>>();
-        Ok(ReadSheetsInitData {
-            indexes,
-            index: AtomicUsize::new(0),
-            projections,
-        })
+        // Chunks are produced in sheet row order, but the C API has no batch index to
+        // report it, so DuckDB cannot elide an ORDER BY on the output
+        let data = ReadSheetsInitData::read(bind, projections)?;
+        Ok(data)
     }
 
     /// Function phase: stream data chunks to DuckDB
@@ -338,43 +1016,7 @@ impl VTab for ReadSheetsTableFunction {
         func: &TableFunctionInfo<Self>,
         output: &mut DataChunkHandle,
     ) -> Result<(), Box<dyn Error>> {
-        let bind = func.get_bind_data();
-        let init = func.get_init_data();
-        let index = init.index.fetch_add(1, Ordering::Relaxed);
-        if index < init.indexes.len() {
-            let mut vectors: Vec<_> = (0..init.projections.len()).map(|index| output.flat_vector(index)).collect();
-            let (spreadsheet_index, sheet_index, chunk_index) = init.indexes[index];
-            let (shared_strings, sheets, sheets_columns_mappings) = &bind.spreadsheets[spreadsheet_index];
-            let sheet = &sheets[sheet_index];
-            let columns_mappings = &sheets_columns_mappings[sheet_index];
-            if let Some(table) = sheet.chunk(chunk_index) {
-                output.set_len(table.len());
-                for (row, record) in table.iter().enumerate() {
-                    for (index, col) in init.projections.iter().enumerate() {
-                        let vector = &mut vectors[index];
-                        if bind.file_name_column.map(|column| column == *col).unwrap_or(false) {
-                            vector.insert(row, sheet.file_name.as_str());
-                        } else if bind.sheet_name_column.map(|column| column == *col).unwrap_or(false) {
-                            vector.insert(row, sheet.name.as_str());
-                        } else if let Some(column_index) = columns_mappings.get(col) {
-                            if let Some(cell) = record[*column_index] {
-                                let column = &bind.columns[*col];
-                                write_to_vector(sheet, column, cell, vector, row, shared_strings)?;
-                            } else {
-                                vector.set_null(row);
-                            }
-                        } else {
-                            vector.set_null(row);
-                        }
-                    }
-                }
-            } else {
-                output.set_len(0);
-            }
-        } else {
-            // No more data to process
-            output.set_len(0);
-        }
+        func.get_init_data().scan(func.get_bind_data(), output)?;
         Ok(())
     }
 
@@ -395,14 +1037,54 @@ impl VTab for ReadSheetsTableFunction {
         Some(vec![
             SheetsParam::definition(),
             RangeParam::definition(),
+            RangesParam::definition(),
+            SkipParam::definition(),
             HeaderParam::definition(),
             UnionByNameParam::definition(),
+            SchemaLockParam::definition(),
+            DedupeSheetsParam::definition(),
+            InterleaveFilesParam::definition(),
+            OnSheetMissingParam::definition(),
+            OnSheetEmptyParam::definition(),
+            ExpectRowsParam::definition(),
+            ExpectColumnsParam::definition(),
             ColumnsParam::definition(),
+            AllVarcharParam::definition(),
+            DefaultsParam::definition(),
+            AutoDetectParam::definition(),
             AnalyzeRowsParam::definition(),
+            SampleParam::definition(),
+            StableRowsParam::definition(),
             NullsParam::definition(),
             ErrorAsNullParam::definition(),
+            IgnoreErrorsParam::definition(),
             SkipEmptyRowsParam::definition(),
             EndAtEmptyRowParam::definition(),
+            MidnightAsDateParam::definition(),
+            EpochDateAsTimeParam::definition(),
+            DurationAsIntervalParam::definition(),
+            DisplayValuesParam::definition(),
+            IncludeHiddenColumnsParam::definition(),
+            RequireHeaderParam::definition(),
+            DateLocaleParam::definition(),
+            HeaderAliasesParam::definition(),
+            MissingHeaderNameParam::definition(),
+            MaxHeaderLengthParam::definition(),
+            NormalizeNamesParam::definition(),
+            SalvageParam::definition(),
+            LenientParam::definition(),
+            OriginalValuesParam::definition(),
+            FormulasParam::definition(),
+            FixEncodingParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+            SpillThresholdParam::definition(),
+            MaxCellLengthParam::definition(),
+            TruncationSuffixParam::definition(),
+            DecimalRoundingParam::definition(),
+            DecimalScaleOverflowParam::definition(),
+            VarcharCaseParam::definition(),
+            DatesAsVarcharParam::definition(),
             FileNameColumnParam::definition(),
             SheetNameColumnParam::definition(),
         ])
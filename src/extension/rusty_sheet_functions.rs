@@ -0,0 +1,192 @@
+use crate::extension::analyze_sheet::AnalyzeSheetTableFunction;
+use crate::extension::analyze_sheets::AnalyzeSheetsTableFunction;
+use crate::extension::diagnose_union::DiagnoseUnionTableFunction;
+use crate::extension::duplicate_sheets::DuplicateSheetsTableFunction;
+use crate::extension::export_sheets_partitioned::ExportSheetsPartitionedTableFunction;
+use crate::extension::last_scan::LastScanTableFunction;
+use crate::extension::list_sheets::ListSheetsTableFunction;
+use crate::extension::metrics::MetricsTableFunction;
+use crate::extension::named_param_default;
+use crate::extension::read_cell_protection::ReadCellProtectionTableFunction;
+use crate::extension::read_cells::ReadCellsTableFunction;
+use crate::extension::read_comments::ReadCommentsTableFunction;
+use crate::extension::read_hyperlinks::ReadHyperlinksTableFunction;
+use crate::extension::read_manifest::ReadManifestTableFunction;
+use crate::extension::read_defined_names::ReadDefinedNamesTableFunction;
+use crate::extension::read_formula_graph::ReadFormulaGraphTableFunction;
+use crate::extension::read_sheet::ReadSheetTableFunction;
+use crate::extension::read_sheets::ReadSheetsTableFunction;
+use crate::extension::settings::SettingsTableFunction;
+use crate::extension::sheet_anomalies::SheetAnomaliesTableFunction;
+use crate::extension::sheet_checksum::SheetChecksumTableFunction;
+use crate::extension::sheet_ddl::SheetDdlTableFunction;
+use crate::extension::sheet_headers::SheetHeadersTableFunction;
+use crate::extension::workbook_metadata::WorkbookMetadataTableFunction;
+use crate::extension::write_sheet::WriteSheetTableFunction;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameter of a registered table function, as listed by rusty_sheet_functions
+struct ParameterInfo {
+    /// Name of the table function
+    function_name: &'static str,
+    /// Name of a named parameter, None for positional parameters
+    parameter_name: Option<String>,
+    /// Position of a positional parameter, None for named parameters
+    parameter_index: Option<u32>,
+    /// SQL type name of the parameter
+    parameter_type: &'static str,
+    /// Default value as a SQL literal, None when there is none or it differs between functions
+    default_value: Option<&'static str>,
+}
+
+/// Lists the positional then named parameters of a table function from its registration.
+fn describe<T: VTab>(function_name: &'static str) -> Vec<ParameterInfo> {
+    let positional = T::parameters().unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(index, kind)| ParameterInfo {
+            function_name,
+            parameter_name: None,
+            parameter_index: Some(index as u32),
+            parameter_type: type_name(&kind),
+            default_value: None,
+        });
+    let named = T::named_parameters().unwrap_or_default()
+        .into_iter()
+        .map(|(name, kind)| ParameterInfo {
+            function_name,
+            default_value: named_param_default(&name),
+            parameter_name: Some(name),
+            parameter_index: None,
+            parameter_type: type_name(&kind),
+        });
+    positional.chain(named).collect()
+}
+
+/// Returns the SQL name of a parameter type; nested types are named by their kind only.
+fn type_name(kind: &LogicalTypeHandle) -> &'static str {
+    match kind.id() {
+        LogicalTypeId::Boolean => "BOOLEAN",
+        LogicalTypeId::UInteger => "UINTEGER",
+        LogicalTypeId::Varchar => "VARCHAR",
+        LogicalTypeId::List => "LIST",
+        LogicalTypeId::Map => "MAP",
+        LogicalTypeId::Struct => "STRUCT",
+        _ => "ANY",
+    }
+}
+
+#[repr(C)]
+/// Bind data for the rusty_sheet_functions table function
+pub(crate) struct RustySheetFunctionsBindData {
+    /// Parameters of every registered table function
+    parameters: Vec<ParameterInfo>,
+}
+
+#[repr(C)]
+/// Init data for the rusty_sheet_functions table function tracking iteration state
+pub(crate) struct RustySheetFunctionsInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function listing the parameters of the registered table functions for introspection
+pub(crate) struct RustySheetFunctionsTableFunction;
+
+impl VTab for RustySheetFunctionsTableFunction {
+    type InitData = RustySheetFunctionsInitData;
+    type BindData = RustySheetFunctionsBindData;
+
+    /// Bind phase: collect the parameter definitions and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = [
+            describe::<AnalyzeSheetTableFunction>("analyze_sheet"),
+            describe::<AnalyzeSheetsTableFunction>("analyze_sheets"),
+            describe::<SheetDdlTableFunction>("sheet_ddl"),
+            describe::<ReadSheetTableFunction>("read_sheet"),
+            describe::<ReadSheetsTableFunction>("read_sheets"),
+            describe::<ReadManifestTableFunction>("read_manifest"),
+            describe::<ExportSheetsPartitionedTableFunction>("export_sheets_partitioned"),
+            describe::<SheetHeadersTableFunction>("sheet_headers"),
+            describe::<DuplicateSheetsTableFunction>("duplicate_sheets"),
+            describe::<DiagnoseUnionTableFunction>("diagnose_union"),
+            describe::<SheetChecksumTableFunction>("sheet_checksum"),
+            describe::<SheetAnomaliesTableFunction>("sheet_anomalies"),
+            describe::<ReadCellsTableFunction>("read_cells"),
+            describe::<ListSheetsTableFunction>("list_sheets"),
+            describe::<WorkbookMetadataTableFunction>("workbook_metadata"),
+            describe::<ReadDefinedNamesTableFunction>("read_defined_names"),
+            describe::<ReadCommentsTableFunction>("read_comments"),
+            describe::<ReadHyperlinksTableFunction>("read_hyperlinks"),
+            describe::<ReadCellProtectionTableFunction>("read_cell_protection"),
+            describe::<ReadFormulaGraphTableFunction>("read_formula_graph"),
+            describe::<WriteSheetTableFunction>("write_sheet"),
+            describe::<LastScanTableFunction>("rusty_sheet_last_scan"),
+            describe::<MetricsTableFunction>("rusty_sheet_metrics"),
+            describe::<SettingsTableFunction>("rusty_sheet_settings"),
+        ].into_iter().flatten().collect();
+        bind.add_result_column("function_name", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("parameter_name", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("parameter_index", LogicalTypeHandle::from(LogicalTypeId::UInteger));
+        bind.add_result_column("parameter_type", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("default_value", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        Ok(RustySheetFunctionsBindData { parameters })
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(RustySheetFunctionsInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the parameter definitions to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.parameters.len().min(lower + 2048);
+        if lower < upper {
+            let functions = output.flat_vector(0);
+            let mut names = output.flat_vector(1);
+            let mut indexes = output.flat_vector(2);
+            let types = output.flat_vector(3);
+            let mut defaults = output.flat_vector(4);
+            for index in lower..upper {
+                let row = index - lower;
+                let parameter = &bind.parameters[index];
+                functions.insert(row, parameter.function_name);
+                match &parameter.parameter_name {
+                    Some(name) => names.insert(row, name),
+                    None => names.set_null(row),
+                }
+                match parameter.parameter_index {
+                    Some(parameter_index) => indexes.as_mut_slice::<u32>()[row] = parameter_index,
+                    None => indexes.set_null(row),
+                }
+                types.insert(row, parameter.parameter_type);
+                match parameter.default_value {
+                    Some(default_value) => defaults.insert(row, default_value),
+                    None => defaults.set_null(row),
+                }
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+}
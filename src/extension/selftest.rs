@@ -0,0 +1,230 @@
+//! Conformance corpus run by rusty_sheet_selftest
+//!
+//! Tiny workbooks of every supported format are compiled into the extension, each holding
+//! the same cells: a shared string, a number, a boolean, a date, a datetime, an error, a time,
+//! a custom date format, an inline string and a merged range. Workbooks in the 1904 date
+//! system hold the same date. Every fixture is parsed the way `read_cells` parses a file,
+//! and each feature passes when its cells read back as expected.
+
+use crate::error::RustySheetError;
+use crate::extension::read_cells::describe;
+use crate::extension::read_cells::is_populated;
+use crate::spreadsheet::cell::CellType;
+use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::open_spreadsheet;
+use chrono::DateTime;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::process;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Sequence number distinguishing the fixture files written by concurrent runs
+static FIXTURE_SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Expected reading of a cell as `type:text`, followed by ` @ timestamp` for dates and
+/// datetimes; empty when the cell must not be read
+type Check = (&'static str, &'static str, &'static str);
+
+/// Checks of the workbooks in the 1900 date system as (feature, reference, expected reading)
+const EXCEL_CHECKS: &[Check] = &[
+    ("shared strings", "A1", "string:東京"),
+    ("numbers", "B1", "number:42.5"),
+    ("booleans", "C1", "boolean:true"),
+    ("dates", "D1", "date:2024-02-29 @ 2024-02-29 00:00:00"),
+    ("datetimes", "E1", "datetime:2024-02-29 13:45:30 @ 2024-02-29 13:45:30"),
+    ("error values", "F1", ""),
+    ("times", "G1", "time:13:45:30"),
+    ("custom date formats", "A2", "date:2024-02-29 @ 2024-02-29 00:00:00"),
+    ("inline strings", "C2", "string:inline"),
+    ("merged cells", "A3", "string:merged"),
+    ("merged cells", "B3", ""),
+];
+
+/// Checks of the workbooks in the 1904 date system
+const EXCEL_1904_CHECKS: &[Check] = &[
+    ("1904 date system", "A1", "date:2024-02-29 @ 2024-02-29 00:00:00"),
+];
+
+/// Checks of the OpenDocument spreadsheet, which stores ISO dates and durations
+const ODS_CHECKS: &[Check] = &[
+    ("strings", "A1", "string:東京"),
+    ("numbers", "B1", "number:42.5"),
+    ("booleans", "C1", "boolean:true"),
+    ("dates", "D1", "date:2024-02-29 @ 2024-02-29 00:00:00"),
+    ("datetimes", "E1", "datetime:2024-02-29 13:45:30 @ 2024-02-29 13:45:30"),
+    ("error values", "F1", ""),
+    ("times", "G1", "duration:13:45:30"),
+    ("merged cells", "A3", "string:merged"),
+    ("merged cells", "B3", ""),
+    ("repeated cells", "A4", "number:7"),
+    ("repeated cells", "B4", "number:7"),
+];
+
+/// Fixtures as (format, file name, content, checks)
+const FIXTURES: &[(&str, &str, &[u8], &[Check])] = &[
+    ("xlsx", "conformance.xlsx", include_bytes!("conformance/conformance.xlsx"), EXCEL_CHECKS),
+    ("xlsx", "conformance_1904.xlsx", include_bytes!("conformance/conformance_1904.xlsx"), EXCEL_1904_CHECKS),
+    ("xlsb", "conformance.xlsb", include_bytes!("conformance/conformance.xlsb"), EXCEL_CHECKS),
+    ("xlsb", "conformance_1904.xlsb", include_bytes!("conformance/conformance_1904.xlsb"), EXCEL_1904_CHECKS),
+    ("xls", "conformance.xls", include_bytes!("conformance/conformance.xls"), EXCEL_CHECKS),
+    ("xls", "conformance_1904.xls", include_bytes!("conformance/conformance_1904.xls"), EXCEL_1904_CHECKS),
+    ("ods", "conformance.ods", include_bytes!("conformance/conformance.ods"), ODS_CHECKS),
+];
+
+/// Result of a feature: (format, feature, passed, detail of the first failed check)
+type Outcome = (&'static str, &'static str, bool, Option<String>);
+
+/// Reads every populated cell of a fixture as `type:text[ @ timestamp]` by reference
+fn read_fixture(file_name: &str, content: &[u8]) -> Result<HashMap<String, String>, RustySheetError> {
+    // Spreadsheets are opened by path, so the fixture is written to a temporary file
+    let sequence = FIXTURE_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("rusty_sheet_selftest_{}_{}_{}", process::id(), sequence, file_name));
+    fs::write(&path, content)?;
+    let result = (|| {
+        let mut spreadsheet = open_spreadsheet(&path.to_string_lossy(), false, false, None)?;
+        let sheets = spreadsheet.read_sheets(&Criteria {
+            error_as_null: true,
+            ..Default::default()
+        })?;
+        let (shared_strings, _) = spreadsheet.load_shared_strings(None)?;
+        let mut cells = HashMap::new();
+        for cell in sheets.iter().flat_map(|sheet| sheet.cells.iter()).filter(|cell| is_populated(cell)) {
+            let shared_string = match cell.kind {
+                CellType::SharedString => shared_strings.get(cell.value.parse::<usize>()?).map(String::as_str),
+                _ => None,
+            };
+            let (kind, text, _, timestamp) = describe(cell, shared_string);
+            let mut reading = format!("{kind}:{}", text.unwrap_or_default());
+            if let Some(timestamp) = timestamp.filter(|_| kind == "date" || kind == "datetime") {
+                let timestamp = DateTime::from_timestamp_micros(timestamp)
+                    .map(|datetime| datetime.naive_utc().format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_else(|| timestamp.to_string());
+                reading.push_str(&format!(" @ {timestamp}"));
+            }
+            cells.insert(cell.reference(), reading);
+        }
+        Ok(cells)
+    })();
+    let _ = fs::remove_file(&path);
+    result
+}
+
+/// Runs the conformance corpus, returning one outcome per format and feature.
+/// A fixture that fails to parse fails all of its features with the error message.
+pub(super) fn run_conformance() -> Vec<Outcome> {
+    let mut outcomes = Vec::<Outcome>::new();
+    for (format, file_name, content, checks) in FIXTURES {
+        let cells = read_fixture(file_name, content);
+        for (feature, reference, expected) in checks.iter() {
+            let failure = match &cells {
+                Ok(cells) => {
+                    let actual = cells.get(*reference).map(String::as_str).unwrap_or_default();
+                    (actual != *expected).then(|| format!("{reference}: expected '{expected}', found '{actual}'"))
+                }
+                Err(error) => Some(error.to_string()),
+            };
+            match outcomes.iter_mut().find(|(kind, name, _, _)| kind == format && name == feature) {
+                Some((_, _, passed, detail)) => if *passed && failure.is_some() {
+                    *passed = false;
+                    *detail = failure;
+                },
+                None => outcomes.push((format, feature, failure.is_none(), failure)),
+            }
+        }
+    }
+    outcomes
+}
+
+#[repr(C)]
+/// Bind data for the rusty_sheet_selftest table function
+pub(crate) struct SelftestBindData {
+    /// Outcome of each feature of each format
+    outcomes: Vec<Outcome>,
+}
+
+#[repr(C)]
+/// Init data for the rusty_sheet_selftest table function tracking iteration state
+pub(crate) struct SelftestInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function checking that the installed build reads the embedded conformance corpus
+pub(crate) struct SelftestTableFunction;
+
+impl VTab for SelftestTableFunction {
+    type InitData = SelftestInitData;
+    type BindData = SelftestBindData;
+
+    /// Bind phase: run the corpus and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let outcomes = run_conformance();
+        bind.add_result_column("format", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("feature", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("passed", LogicalTypeHandle::from(LogicalTypeId::Boolean));
+        bind.add_result_column("detail", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        Ok(SelftestBindData { outcomes })
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(SelftestInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the outcomes to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.outcomes.len().min(lower + 2048);
+        if lower < upper {
+            let formats = output.flat_vector(0);
+            let features = output.flat_vector(1);
+            let mut passes = output.flat_vector(2);
+            let mut details = output.flat_vector(3);
+            for index in lower..upper {
+                let row = index - lower;
+                let (format, feature, passed, detail) = &bind.outcomes[index];
+                formats.insert(row, *format);
+                features.insert(row, *feature);
+                passes.as_mut_slice::<bool>()[row] = *passed;
+                match detail {
+                    Some(detail) => details.insert(row, detail.as_str()),
+                    None => details.set_null(row),
+                }
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conformance_corpus_passes() {
+        let failures = run_conformance().into_iter()
+            .filter(|(_, _, passed, _)| !passed)
+            .collect::<Vec<_>>();
+        assert!(failures.is_empty(), "{failures:?}");
+    }
+}
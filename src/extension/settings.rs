@@ -0,0 +1,80 @@
+use crate::database::host::http_settings;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+#[repr(C)]
+/// Bind data for the rusty_sheet_settings table function
+pub(crate) struct SettingsBindData {
+    /// Settings of remote reads as (name, value) pairs, None for unset ones
+    settings: Vec<(&'static str, Option<String>)>,
+}
+
+#[repr(C)]
+/// Init data for the rusty_sheet_settings table function tracking iteration state
+pub(crate) struct SettingsInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function listing the settings that apply to remote reads, i.e. the HTTP proxy,
+/// timeout and CA certificates of httpfs as the host database resolves them for each read
+pub(crate) struct SettingsTableFunction;
+
+impl VTab for SettingsTableFunction {
+    type InitData = SettingsInitData;
+    type BindData = SettingsBindData;
+
+    /// Bind phase: read the current settings and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let data = SettingsBindData {
+            settings: http_settings()?,
+        };
+        bind.add_result_column("name", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("value", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(SettingsInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the settings to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.settings.len().min(lower + 2048);
+        if lower < upper {
+            let names = output.flat_vector(0);
+            let mut values = output.flat_vector(1);
+            for index in lower..upper {
+                let row = index - lower;
+                let (name, value) = &bind.settings[index];
+                names.insert(row, *name);
+                match value {
+                    Some(value) => values.insert(row, value.as_str()),
+                    None => values.set_null(row),
+                }
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use crate::database::range::RangeSpec;
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::FileParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::RangeParam;
+use crate::extension::SalvageParam;
+use crate::extension::SheetParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::open_spreadsheet;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use glob::Pattern;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the sheet_anomalies table function
+struct SheetAnomaliesParameters {
+    /// Path to the spreadsheet file
+    file_name: String,
+    /// Optional sheet name pattern to filter sheets (default: all sheets)
+    sheet_name: Option<Pattern>,
+    /// Optional range specification (e.g., "A1:D10") or defined name
+    range: Option<RangeSpec>,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for SheetAnomaliesParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(SheetAnomaliesParameters {
+            file_name: FileParam::read(bind, 0)?,
+            sheet_name: SheetParam::read(bind)?,
+            range: RangeParam::read(bind)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Bind data for the sheet_anomalies table function
+pub(crate) struct SheetAnomaliesBindData {
+    /// Vector of (sheet_name, last row read, anomaly) for each structural error met
+    anomalies: Vec<(String, Option<i64>, String)>,
+}
+
+impl TryFrom<&SheetAnomaliesParameters> for SheetAnomaliesBindData {
+    type Error = RustySheetError;
+
+    /// Parse every matched sheet leniently, keeping only the positions of the cells,
+    /// and collect the structural errors that ended the parsing of a sheet early.
+    fn try_from(parameters: &SheetAnomaliesParameters) -> Result<Self, Self::Error> {
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        let sheet_name_patterns = parameters.sheet_name.as_ref().map(|pattern| vec![pattern.to_owned()]);
+        let (sheet_name_patterns, range) = spreadsheet.resolve_range(parameters.range.as_ref(), sheet_name_patterns)?;
+        let sheets = spreadsheet.read_sheets(&Criteria {
+            sheet_name_patterns,
+            range,
+            error_as_null: true,
+            count_only: true,
+            lenient: true,
+            ..Default::default()
+        })?;
+        let anomalies = sheets.into_iter()
+            .flat_map(|sheet| {
+                let last_row = sheet.row_upper_bound.map(|row| row as i64 + 1);
+                sheet.anomalies.into_iter().map(move |anomaly| (sheet.name.to_owned(), last_row, anomaly))
+            })
+            .collect();
+        Ok(SheetAnomaliesBindData { anomalies })
+    }
+}
+
+#[repr(C)]
+/// Init data for the sheet_anomalies table function tracking iteration state
+pub(crate) struct SheetAnomaliesInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function listing the structural errors that a lenient read of each sheet skips
+pub(crate) struct SheetAnomaliesTableFunction;
+
+impl VTab for SheetAnomaliesTableFunction {
+    type InitData = SheetAnomaliesInitData;
+    type BindData = SheetAnomaliesBindData;
+
+    /// Bind phase: parse parameters, scan the sheets, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = SheetAnomaliesParameters::try_from(bind)?;
+        let data = SheetAnomaliesBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column(
+            "sheet_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "last_row",
+            LogicalTypeHandle::from(LogicalTypeId::Bigint),
+        );
+        bind.add_result_column(
+            "anomaly",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(SheetAnomaliesInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the anomalies to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.anomalies.len().min(lower + 2048);
+        if lower < upper {
+            let sheets = output.flat_vector(0);
+            let mut last_rows = output.flat_vector(1);
+            let anomalies = output.flat_vector(2);
+            for index in lower..upper {
+                let (sheet_name, last_row, anomaly) = &bind.anomalies[index];
+                sheets.insert(index - lower, sheet_name);
+                match last_row {
+                    Some(last_row) => last_rows.as_mut_slice::<i64>()[index - lower] = *last_row,
+                    None => last_rows.set_null(index - lower),
+                }
+                anomalies.insert(index - lower, anomaly);
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SheetParam::definition(),
+            RangeParam::definition(),
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
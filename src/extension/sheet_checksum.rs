@@ -0,0 +1,169 @@
+use crate::database::range::RangeSpec;
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::FileParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::RangeParam;
+use crate::extension::SalvageParam;
+use crate::extension::SheetParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::open_spreadsheet;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use glob::Pattern;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the sheet_checksum table function
+struct SheetChecksumParameters {
+    /// Path to the spreadsheet file
+    file_name: String,
+    /// Optional sheet name pattern to filter sheets (default: all sheets)
+    sheet_name: Option<Pattern>,
+    /// Optional range specification (e.g., "A1:D10") or defined name
+    range: Option<RangeSpec>,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for SheetChecksumParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(SheetChecksumParameters {
+            file_name: FileParam::read(bind, 0)?,
+            sheet_name: SheetParam::read(bind)?,
+            range: RangeParam::read(bind)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Bind data for the sheet_checksum table function
+pub(crate) struct SheetChecksumBindData {
+    /// Vector of (sheet_name, checksum) for each matched sheet
+    checksums: Vec<(String, String)>,
+}
+
+impl TryFrom<&SheetChecksumParameters> for SheetChecksumBindData {
+    type Error = RustySheetError;
+
+    /// Hash every matched sheet while parsing it, without materializing its cells.
+    /// Referenced shared strings are loaded afterwards to complete the checksums.
+    fn try_from(parameters: &SheetChecksumParameters) -> Result<Self, Self::Error> {
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        let sheet_name_patterns = parameters.sheet_name.as_ref().map(|pattern| vec![pattern.to_owned()]);
+        let (sheet_name_patterns, range) = spreadsheet.resolve_range(parameters.range.as_ref(), sheet_name_patterns)?;
+        let sheets = spreadsheet.read_sheets(&Criteria {
+            sheet_name_patterns,
+            range,
+            checksum: true,
+            ..Default::default()
+        })?;
+        let (shared_strings, mappings) = spreadsheet.load_shared_strings(None)?;
+        let checksums = sheets.iter()
+            .filter_map(|sheet| {
+                sheet.checksum(&shared_strings, &mappings)
+                    .map(|checksum| (sheet.name.to_owned(), format!("{checksum:016x}")))
+            })
+            .collect();
+        Ok(SheetChecksumBindData { checksums })
+    }
+}
+
+#[repr(C)]
+/// Init data for the sheet_checksum table function tracking iteration state
+pub(crate) struct SheetChecksumInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function computing a stable content checksum of each sheet for change detection
+pub(crate) struct SheetChecksumTableFunction;
+
+impl VTab for SheetChecksumTableFunction {
+    type InitData = SheetChecksumInitData;
+    type BindData = SheetChecksumBindData;
+
+    /// Bind phase: parse parameters, hash the sheets, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = SheetChecksumParameters::try_from(bind)?;
+        let data = SheetChecksumBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column(
+            "sheet_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "checksum",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(SheetChecksumInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the checksums to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.checksums.len().min(lower + 2048);
+        if lower < upper {
+            let sheets = output.flat_vector(0);
+            let checksums = output.flat_vector(1);
+            for index in lower..upper {
+                let (sheet_name, checksum) = &bind.checksums[index];
+                sheets.insert(index - lower, sheet_name);
+                checksums.insert(index - lower, checksum);
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SheetParam::definition(),
+            RangeParam::definition(),
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
@@ -0,0 +1,111 @@
+use crate::error::ResultMessage;
+use crate::extension::analyze_sheet::AnalyzeSheetBindData;
+use crate::extension::analyze_sheet::AnalyzeSheetParameters;
+use crate::extension::analyze_sheet::AnalyzeSheetTableFunction;
+use crate::extension::export_sheets_partitioned::quote_identifier;
+use crate::extension::FileParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::TableNameParam;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::error::Error;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+#[repr(C)]
+/// Bind data for the sheet_ddl table function holding the generated statement
+pub(crate) struct SheetDdlBindData {
+    /// CREATE TABLE statement of the analyzed sheet
+    ddl: String,
+}
+
+#[repr(C)]
+/// Init data for the sheet_ddl table function tracking whether the statement was emitted
+pub(crate) struct SheetDdlInitData {
+    /// Whether the single row has been emitted
+    done: AtomicBool,
+}
+
+/// Table function generating the CREATE TABLE statement of a sheet from its analysis,
+/// with the columns named and typed as analyze_sheet reports them
+pub(crate) struct SheetDdlTableFunction;
+
+impl VTab for SheetDdlTableFunction {
+    type InitData = SheetDdlInitData;
+    type BindData = SheetDdlBindData;
+
+    /// Bind phase: analyze the sheet like analyze_sheet and build the statement
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = AnalyzeSheetParameters::try_from(bind)?;
+        let table_name = TableNameParam::read(bind)?.unwrap_or_else(|| {
+            Path::new(&parameters.file_name)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        let data = AnalyzeSheetBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column("ddl", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        Ok(SheetDdlBindData {
+            ddl: create_table_statement(&table_name, &data.columns),
+        })
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(SheetDdlInitData {
+            done: AtomicBool::new(false),
+        })
+    }
+
+    /// Function phase: emit the statement as a single row
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        if init.done.swap(true, Ordering::Relaxed) {
+            output.set_len(0);
+        } else {
+            output.flat_vector(0).insert(0, bind.ddl.as_str());
+            output.set_len(1);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define the named parameters, those of analyze_sheet and `table_name`
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        let mut parameters = AnalyzeSheetTableFunction::named_parameters()?;
+        parameters.push(TableNameParam::definition());
+        Some(parameters)
+    }
+}
+
+/// Builds a CREATE TABLE statement with one line per column.
+/// Every part of a qualified table name (`schema.table`) and every column name is quoted.
+fn create_table_statement(table_name: &str, columns: &[(String, String)]) -> String {
+    let table_name = table_name.split('.')
+        .map(quote_identifier)
+        .collect::<Vec<_>>()
+        .join(".");
+    let columns = columns.iter()
+        .map(|(name, kind)| format!("    {} {}", quote_identifier(name), kind.to_uppercase()))
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!("CREATE TABLE {table_name} (\n{columns}\n);")
+}
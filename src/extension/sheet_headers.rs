@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use crate::database::range::Range;
+use crate::database::range::RangeSpec;
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::AnalyzeRowsParam;
+use crate::extension::FileParam;
+use crate::extension::HeaderAliasesParam;
+use crate::extension::IncludeHiddenColumnsParam;
+use crate::extension::MaxHeaderLengthParam;
+use crate::extension::MissingHeaderNameParam;
+use crate::extension::NamedParam;
+use crate::extension::NormalizeNamesParam;
+use crate::extension::NullsParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::RangeParam;
+use crate::extension::SalvageParam;
+use crate::extension::SheetParam;
+use crate::extension::SkipParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::open_spreadsheet;
+use crate::spreadsheet::reference::index_to_reference;
+use crate::spreadsheet::MAX_HEADER_LENGTH;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use glob::Pattern;
+use std::error::Error;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the sheet_headers table function
+struct SheetHeadersParameters {
+    /// Path to the spreadsheet file
+    file_name: String,
+    /// Optional sheet name pattern to filter sheets
+    sheet_name: Option<Pattern>,
+    /// Optional range specification (e.g., "A1:D10") or defined name
+    range: Option<RangeSpec>,
+    /// Number of leading rows dropped before header detection (default: 0)
+    skip: Option<usize>,
+    /// Number of rows analyzed for the column bounds (default: 10)
+    analyze_rows: Option<usize>,
+    /// null literals (default: empty string)
+    nulls: Option<HashSet<String>>,
+    /// Include hidden, zero-width and collapsed columns (default: true)
+    include_hidden_columns: Option<bool>,
+    /// Canonical column names by localized header text (default: none)
+    header_aliases: Option<HashMap<String, String>>,
+    /// Template naming columns without a header, e.g. 'column_{n}' (default: column letters)
+    missing_header_name: Option<String>,
+    /// Maximum number of characters of column names taken from headers (default: 255)
+    max_header_length: Option<usize>,
+    /// Lowercase, de-accent and underscore the column names taken from headers (default: false)
+    normalize_names: Option<bool>,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for SheetHeadersParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(SheetHeadersParameters {
+            file_name: FileParam::read(bind, 0)?,
+            sheet_name: SheetParam::read(bind)?,
+            range: RangeParam::read(bind)?,
+            skip: SkipParam::read(bind)?,
+            analyze_rows: AnalyzeRowsParam::read(bind)?,
+            nulls: NullsParam::read(bind)?,
+            include_hidden_columns: IncludeHiddenColumnsParam::read(bind)?,
+            header_aliases: HeaderAliasesParam::read(bind)?,
+            missing_header_name: MissingHeaderNameParam::read(bind)?,
+            max_header_length: MaxHeaderLengthParam::read(bind)?,
+            normalize_names: NormalizeNamesParam::read(bind)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Bind data for the sheet_headers table function
+pub(crate) struct SheetHeadersBindData {
+    /// Vector of (column_name, header text, header cell reference) from the analyzed sheet
+    headers: Vec<(String, Option<String>, String)>,
+}
+
+impl TryFrom<&SheetHeadersParameters> for SheetHeadersBindData {
+    type Error = RustySheetError;
+
+    /// Analyze the header row and pair each column name with its original header text.
+    /// Uses the same criteria as read_sheet so the column names match its output.
+    fn try_from(parameters: &SheetHeadersParameters) -> Result<Self, Self::Error> {
+        let mut headers = Vec::<(String, Option<String>, String)>::new();
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        let sheet_name_patterns = parameters.sheet_name
+            .as_ref()
+            .map(|pattern| vec![pattern.to_owned()]);
+        let (sheet_name_patterns, range) = spreadsheet.resolve_range(parameters.range.as_ref(), sheet_name_patterns)?;
+        let range = Range::skip_rows(range, parameters.skip);
+        let nulls = parameters.nulls.to_owned().unwrap_or(HashSet::from(["".to_string()]));
+        for table in spreadsheet.analyze_sheets(true, &Criteria {
+            sheet_name_patterns,
+            sheet_limit: Some(1),
+            range,
+            rows_limit: parameters.analyze_rows.or(Some(10)),
+            nulls,
+            error_as_null: true,
+            include_hidden_columns: parameters.include_hidden_columns.unwrap_or(true),
+            header_aliases: parameters.header_aliases.to_owned().unwrap_or_default(),
+            missing_header_name: parameters.missing_header_name.to_owned(),
+            max_header_length: parameters.max_header_length.unwrap_or(MAX_HEADER_LENGTH),
+            normalize_names: parameters.normalize_names.unwrap_or(false),
+            ..Default::default()
+        }, &vec![])? {
+            let header_row = table.row_lower_bound.unwrap_or(1).saturating_sub(1);
+            for (column, (col, text)) in table.columns.iter().zip(&table.headers) {
+                headers.push((
+                    column.name.to_owned(),
+                    text.to_owned(),
+                    index_to_reference(header_row, *col),
+                ));
+            }
+        }
+        Ok(SheetHeadersBindData { headers })
+    }
+}
+
+#[repr(C)]
+/// Init data for the sheet_headers table function tracking iteration state
+pub(crate) struct SheetHeadersInitData {
+    /// Atomic counter tracking the current processing index
+    index: AtomicUsize,
+}
+
+/// Table function listing the original header text behind each column name
+pub(crate) struct SheetHeadersTableFunction;
+
+impl VTab for SheetHeadersTableFunction {
+    type InitData = SheetHeadersInitData;
+    type BindData = SheetHeadersBindData;
+
+    /// Bind phase: parse parameters, analyze the header row, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = SheetHeadersParameters::try_from(bind)?;
+        let data = SheetHeadersBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column(
+            "column_index",
+            LogicalTypeHandle::from(LogicalTypeId::Bigint),
+        );
+        bind.add_result_column(
+            "column_name",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "header",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "cell",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(SheetHeadersInitData {
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    /// Function phase: stream the header mapping to DuckDB
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        let lower = init.index.fetch_add(2048, Ordering::Relaxed);
+        let upper = bind.headers.len().min(lower + 2048);
+        if lower < upper {
+            let mut indexes = output.flat_vector(0);
+            let columns = output.flat_vector(1);
+            let mut texts = output.flat_vector(2);
+            let cells = output.flat_vector(3);
+            for index in lower..upper {
+                let (column_name, text, cell) = &bind.headers[index];
+                indexes.as_mut_slice::<i64>()[index - lower] = index as i64;
+                columns.insert(index - lower, column_name);
+                match text {
+                    Some(text) => texts.insert(index - lower, text),
+                    None => texts.set_null(index - lower),
+                }
+                cells.insert(index - lower, cell);
+            }
+            output.set_len(upper - lower);
+        } else {
+            output.set_len(0);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SheetParam::definition(),
+            RangeParam::definition(),
+            SkipParam::definition(),
+            AnalyzeRowsParam::definition(),
+            NullsParam::definition(),
+            IncludeHiddenColumnsParam::definition(),
+            HeaderAliasesParam::definition(),
+            MissingHeaderNameParam::definition(),
+            MaxHeaderLengthParam::definition(),
+            NormalizeNamesParam::definition(),
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
@@ -0,0 +1,171 @@
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::writer::write_timestamp;
+use crate::extension::FileParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::PasswordParam;
+use crate::extension::SalvageParam;
+use crate::extension::SnapshotParam;
+use crate::spreadsheet::metadata::WorkbookMetadata;
+use crate::spreadsheet::open_spreadsheet;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::Inserter;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use std::error::Error;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+/// Parameters for the workbook_metadata table function
+struct WorkbookMetadataParameters {
+    /// Path to the spreadsheet file
+    file_name: String,
+    /// Recover ZIP-based files whose central directory is unreadable (default: false)
+    salvage: Option<bool>,
+    /// Copy local files before parsing so that changes made meanwhile (e.g. by a sync client) are not seen (default: false)
+    snapshot: Option<bool>,
+    /// Password of encrypted workbooks
+    password: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for WorkbookMetadataParameters {
+    type Error = RustySheetError;
+
+    /// Parse parameters from DuckDB bind info
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        Ok(WorkbookMetadataParameters {
+            file_name: FileParam::read(bind, 0)?,
+            salvage: SalvageParam::read(bind)?,
+            snapshot: SnapshotParam::read(bind)?,
+            password: PasswordParam::read(bind)?,
+        })
+    }
+}
+
+#[repr(C)]
+/// Bind data for the workbook_metadata table function
+pub(crate) struct WorkbookMetadataBindData {
+    /// Document properties of the workbook
+    metadata: WorkbookMetadata,
+}
+
+impl TryFrom<&WorkbookMetadataParameters> for WorkbookMetadataBindData {
+    type Error = RustySheetError;
+
+    /// Read the document properties of the workbook, without reading any worksheet
+    fn try_from(parameters: &WorkbookMetadataParameters) -> Result<Self, Self::Error> {
+        let mut spreadsheet = open_spreadsheet(parameters.file_name.as_str(), parameters.salvage.unwrap_or(false), parameters.snapshot.unwrap_or(false), parameters.password.as_deref())?;
+        let metadata = spreadsheet.metadata()?;
+        Ok(WorkbookMetadataBindData { metadata })
+    }
+}
+
+#[repr(C)]
+/// Init data for the workbook_metadata table function tracking iteration state
+pub(crate) struct WorkbookMetadataInitData {
+    /// Whether the result row has been emitted
+    done: AtomicBool,
+}
+
+/// Table function returning the document properties of a workbook as a single row
+pub(crate) struct WorkbookMetadataTableFunction;
+
+impl VTab for WorkbookMetadataTableFunction {
+    type InitData = WorkbookMetadataInitData;
+    type BindData = WorkbookMetadataBindData;
+
+    /// Bind phase: parse parameters, read the properties, and define result columns
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = WorkbookMetadataParameters::try_from(bind)?;
+        let data = WorkbookMetadataBindData::try_from(&parameters).with_prefix(parameters.file_name.as_str())?;
+        bind.add_result_column(
+            "title",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "author",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "created",
+            LogicalTypeHandle::from(LogicalTypeId::Timestamp),
+        );
+        bind.add_result_column(
+            "modified",
+            LogicalTypeHandle::from(LogicalTypeId::Timestamp),
+        );
+        bind.add_result_column(
+            "application",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        bind.add_result_column(
+            "sheet_count",
+            LogicalTypeHandle::from(LogicalTypeId::Bigint),
+        );
+        bind.add_result_column(
+            "date_system",
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        );
+        Ok(data)
+    }
+
+    /// Init phase: initialize iteration state
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(WorkbookMetadataInitData {
+            done: AtomicBool::new(false),
+        })
+    }
+
+    /// Function phase: emit the properties as one row
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        if init.done.swap(true, Ordering::Relaxed) {
+            output.set_len(0);
+        } else {
+            let metadata = &bind.metadata;
+            for (index, text) in [(0, &metadata.title), (1, &metadata.author), (4, &metadata.application)] {
+                let mut vector = output.flat_vector(index);
+                match text {
+                    Some(text) => vector.insert(0, text.as_str()),
+                    None => vector.set_null(0),
+                }
+            }
+            for (index, timestamp) in [(2, metadata.created), (3, metadata.modified)] {
+                let mut vector = output.flat_vector(index);
+                match timestamp {
+                    Some(timestamp) => write_timestamp(&mut vector, 0, timestamp),
+                    None => vector.set_null(0),
+                }
+            }
+            output.flat_vector(5).as_mut_slice::<i64>()[0] = metadata.sheet_count as i64;
+            output.flat_vector(6).insert(0, if metadata.is_1904 { "1904" } else { "1900" });
+            output.set_len(1);
+        }
+        Ok(())
+    }
+
+    /// Define required positional parameters (file path)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+        ])
+    }
+
+    /// Define optional named parameters
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SalvageParam::definition(),
+            SnapshotParam::definition(),
+            PasswordParam::definition(),
+        ])
+    }
+}
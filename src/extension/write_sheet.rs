@@ -0,0 +1,342 @@
+use crate::database::host::connect_host_database;
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::AppendParam;
+use crate::extension::AutofilterParam;
+use crate::extension::ExtensionError;
+use crate::extension::FileParam;
+use crate::extension::FormatParam;
+use crate::extension::FreezePanesParam;
+use crate::extension::HeaderParam;
+use crate::extension::HeaderStyleParam;
+use crate::extension::NamedParam;
+use crate::extension::Param;
+use crate::extension::QueryParam;
+use crate::extension::SheetNameParam;
+use crate::spreadsheet::cell::Cell;
+use crate::spreadsheet::cell::CellType;
+use crate::spreadsheet::output::append_xlsx;
+use crate::spreadsheet::output::to_serial_date;
+use crate::spreadsheet::output::write_ods;
+use crate::spreadsheet::output::write_xlsx;
+use crate::spreadsheet::output::SheetLayout;
+use chrono::DateTime;
+use duckdb::core::DataChunkHandle;
+use duckdb::core::LogicalTypeHandle;
+use duckdb::core::LogicalTypeId;
+use duckdb::types::ValueRef;
+use duckdb::vtab::BindInfo;
+use duckdb::vtab::InitInfo;
+use duckdb::vtab::TableFunctionInfo;
+use duckdb::vtab::VTab;
+use duckdb::Connection;
+use duckdb::Rows;
+use std::error::Error;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+/// Microseconds in a day
+const DAY_MICROS: i64 = 86_400_000_000;
+
+/// Parameters for writing a query result into a worksheet
+struct WriteSheetParameters {
+    /// Path of the file to create, or of the xlsx file to add the worksheet to in append mode
+    file_name: String,
+    /// SQL query producing the rows to write
+    query: String,
+    /// Name of the worksheet (default: 'Sheet1')
+    sheet_name: Option<String>,
+    /// Whether the column names are written as the first row (default: true)
+    header: Option<bool>,
+    /// Whether the worksheet is added to the existing file instead of replacing it (default: false)
+    append: Option<bool>,
+    /// Style of the column names row, 'bold' or 'none' (default: 'none')
+    header_style: Option<String>,
+    /// Top-left cell of the scrolling pane, the rows above and columns before it stay in view
+    freeze_panes: Option<(usize, usize)>,
+    /// Whether the written cells get an autofilter (default: false)
+    autofilter: Option<bool>,
+    /// Workbook format, 'xlsx' or 'ods' (default: 'xlsx')
+    format: Option<String>,
+}
+
+impl TryFrom<&BindInfo> for WriteSheetParameters {
+    type Error = RustySheetError;
+
+    /// Constructs parameters from DuckDB bind information
+    ///
+    /// # Arguments
+    /// * `bind` - DuckDB bind information containing function parameters
+    ///
+    /// # Returns
+    /// * `Result<Self, RustySheetError>` - Parameters or error if parsing fails
+    fn try_from(bind: &BindInfo) -> Result<Self, Self::Error> {
+        let format = FormatParam::read(bind)?;
+        if let Some(format) = format.as_deref().filter(|format| !matches!(*format, "xlsx" | "ods")) {
+            Err(ExtensionError::WorkbookFormatError(format.to_owned()))?
+        }
+        let append = AppendParam::read(bind)?;
+        if let Some(format) = format.as_deref().filter(|format| append == Some(true) && *format != "xlsx") {
+            Err(ExtensionError::AppendFormatError(format.to_owned()))?
+        }
+        Ok(WriteSheetParameters {
+            file_name: FileParam::read(bind, 0)?,
+            query: QueryParam::read(bind, 1)?,
+            sheet_name: SheetNameParam::read(bind)?,
+            header: HeaderParam::read(bind)?,
+            append,
+            header_style: HeaderStyleParam::read(bind)?,
+            freeze_panes: FreezePanesParam::read(bind)?,
+            autofilter: AutofilterParam::read(bind)?,
+            format,
+        })
+    }
+}
+
+#[repr(C)]
+/// Binding data for the write_sheet table function
+pub(crate) struct WriteSheetBindData {
+    /// Write parameters, applied by the first func() call so that binding the query
+    /// (DESCRIBE, EXPLAIN, prepared statements) writes nothing
+    parameters: WriteSheetParameters,
+}
+
+impl WriteSheetParameters {
+    /// Runs the query on the host database and writes its rows into a worksheet of a new
+    /// xlsx or ods file, or of the existing xlsx file in append mode
+    ///
+    /// # Returns
+    /// * `Result<i64, RustySheetError>` - Number of written rows or write error
+    fn write(&self) -> Result<i64, RustySheetError> {
+        // The host database sees the tables, attached databases, extensions and secrets of the caller
+        let connection = connect_host_database()?;
+        self.write_query(&connection)
+    }
+
+    /// Streams the rows of the query into the worksheet as the writer asks for them
+    fn write_query(&self, connection: &Connection) -> Result<i64, RustySheetError> {
+        let header = self.header.unwrap_or(true);
+        let mut statement = connection.prepare(&self.query)?;
+        let mut cells = QueryCells::new(statement.query([])?, header);
+        let columns = cells.names.len();
+        let sheet_name = self.sheet_name.as_deref().unwrap_or("Sheet1");
+        let layout = SheetLayout {
+            bold_header: header && self.header_style.as_deref() == Some("bold"),
+            freeze_panes: self.freeze_panes,
+            autofilter: self.autofilter.unwrap_or(false),
+        };
+        match self.format.as_deref().unwrap_or("xlsx") {
+            "ods" => write_ods(&self.file_name, sheet_name, columns, &mut cells, &layout)?,
+            _ if self.append.unwrap_or(false) => append_xlsx(&self.file_name, sheet_name, &mut cells, &layout)?,
+            _ => write_xlsx(&self.file_name, sheet_name, &mut cells, &layout)?,
+        }
+        Ok(cells.count)
+    }
+}
+
+/// Cells of a query result, below the column names when `header` is set, converted one
+/// row at a time as the writer consumes them so that the result is never held in memory
+struct QueryCells<'stmt> {
+    /// Rows of the query result
+    rows: Rows<'stmt>,
+    /// Column names of the query result
+    names: Vec<String>,
+    /// Cells of the current row not handed out yet, in reverse order
+    pending: Vec<Cell>,
+    /// Index of the next worksheet row
+    row: usize,
+    /// Number of query rows read
+    count: i64,
+}
+
+impl<'stmt> QueryCells<'stmt> {
+    /// Starts with the column names as the first row when `header` is set
+    fn new(rows: Rows<'stmt>, header: bool) -> Self {
+        let names = rows.as_ref().map(|statement| statement.column_names()).unwrap_or_default();
+        let pending = match header {
+            true => names.iter().enumerate().rev().map(|(col, name)| Cell {
+                row: 0,
+                col,
+                kind: CellType::InlineString,
+                value: name.to_owned(),
+            }).collect(),
+            false => Vec::new(),
+        };
+        QueryCells { rows, names, pending, row: usize::from(header), count: 0 }
+    }
+}
+
+impl Iterator for QueryCells<'_> {
+    type Item = Result<Cell, RustySheetError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            let row = match self.rows.next() {
+                Ok(Some(row)) => row,
+                Ok(None) => return None,
+                Err(error) => return Some(Err(error.into())),
+            };
+            for (col, name) in self.names.iter().enumerate().rev() {
+                let cell_value = row.get_ref(col).map_err(RustySheetError::from).and_then(|value| {
+                    to_cell_value(value).ok_or_else(|| ExtensionError::CellTypeError(name.to_owned()).into())
+                });
+                match cell_value {
+                    Ok((kind, value)) => self.pending.push(Cell { row: self.row, col, kind, value }),
+                    Err(error) => return Some(Err(error)),
+                }
+            }
+            self.row += 1;
+            self.count += 1;
+        }
+        self.pending.pop().map(Ok)
+    }
+}
+
+/// Converts a query value to the kind and text of a cell.
+///
+/// Dates, timestamps and times become 1900 date serials, except dates before 1900
+/// which are kept as ISO text. Returns None for types without a cell representation
+/// (blobs, intervals, nested types).
+fn to_cell_value(value: ValueRef<'_>) -> Option<(CellType, String)> {
+    let cell_value = match value {
+        ValueRef::Null => (CellType::Empty, String::new()),
+        ValueRef::Boolean(value) => (CellType::Boolean, if value { "1" } else { "0" }.to_owned()),
+        ValueRef::TinyInt(value) => (CellType::Number, value.to_string()),
+        ValueRef::SmallInt(value) => (CellType::Number, value.to_string()),
+        ValueRef::Int(value) => (CellType::Number, value.to_string()),
+        ValueRef::BigInt(value) => (CellType::Number, value.to_string()),
+        ValueRef::HugeInt(value) => (CellType::Number, value.to_string()),
+        ValueRef::UTinyInt(value) => (CellType::Number, value.to_string()),
+        ValueRef::USmallInt(value) => (CellType::Number, value.to_string()),
+        ValueRef::UInt(value) => (CellType::Number, value.to_string()),
+        ValueRef::UBigInt(value) => (CellType::Number, value.to_string()),
+        ValueRef::Float(value) => to_number(value as f64),
+        ValueRef::Double(value) => to_number(value),
+        ValueRef::Decimal(value) => (CellType::Number, value.to_string()),
+        ValueRef::Text(value) => (CellType::InlineString, String::from_utf8_lossy(value).into_owned()),
+        ValueRef::Date32(days) => match to_serial_date(days) {
+            Some(serial) => (CellType::NumberDate1900, serial.to_string()),
+            None => (CellType::InlineString, DateTime::from_timestamp(days as i64 * 86_400, 0)?.format("%Y-%m-%d").to_string()),
+        },
+        ValueRef::Timestamp(unit, value) => {
+            let micros = unit.to_micros(value);
+            match to_serial_date(micros.div_euclid(DAY_MICROS) as i32) {
+                Some(serial) => {
+                    let fraction = micros.rem_euclid(DAY_MICROS) as f64 / DAY_MICROS as f64;
+                    (CellType::NumberDateTime1900, (serial as f64 + fraction).to_string())
+                }
+                None => (CellType::InlineString, DateTime::from_timestamp_micros(micros)?.format("%Y-%m-%d %H:%M:%S%.f").to_string()),
+            }
+        }
+        ValueRef::Time64(unit, value) => {
+            let fraction = unit.to_micros(value) as f64 / DAY_MICROS as f64;
+            (CellType::NumberTime1900, fraction.to_string())
+        }
+        _ => None?,
+    };
+    Some(cell_value)
+}
+
+/// Converts a floating point value to a number cell, or to text for NaN and infinities.
+fn to_number(value: f64) -> (CellType, String) {
+    if value.is_finite() {
+        (CellType::Number, value.to_string())
+    } else {
+        (CellType::InlineString, value.to_string())
+    }
+}
+
+#[repr(C)]
+/// Initialization data for the write_sheet table function
+pub(crate) struct WriteSheetInitData {
+    /// Whether the file has been written and the result row emitted
+    done: AtomicBool,
+}
+
+/// DuckDB table function for writing a query result into a worksheet of an xlsx or ods file
+pub(crate) struct WriteSheetTableFunction;
+
+impl VTab for WriteSheetTableFunction {
+    type InitData = WriteSheetInitData;
+    type BindData = WriteSheetBindData;
+
+    /// Binds the table function by parsing parameters, the file being written by func()
+    ///
+    /// # Arguments
+    /// * `bind` - DuckDB bind information containing function parameters
+    ///
+    /// # Returns
+    /// * `Result<Self::BindData, Box<dyn Error>>` - Binding data with the write parameters
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let parameters = WriteSheetParameters::try_from(bind)?;
+        bind.add_result_column(
+            "count",
+            LogicalTypeHandle::from(LogicalTypeId::Bigint),
+        );
+        Ok(WriteSheetBindData { parameters })
+    }
+
+    /// Initializes the table function with emission state
+    ///
+    /// # Arguments
+    /// * `_` - DuckDB initialization information (unused)
+    ///
+    /// # Returns
+    /// * `Result<Self::InitData, Box<dyn Error>>` - Initialization data
+    fn init(_: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(WriteSheetInitData {
+            done: AtomicBool::new(false),
+        })
+    }
+
+    /// Writes the file on the first call and emits a single row holding the number of written rows
+    ///
+    /// # Arguments
+    /// * `func` - Table function information containing bind and init data
+    /// * `output` - Data chunk handle to populate with results
+    ///
+    /// # Returns
+    /// * `Result<(), Box<dyn Error>>` - Success or execution error
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init = func.get_init_data();
+        let bind = func.get_bind_data();
+        if init.done.swap(true, Ordering::Relaxed) {
+            output.set_len(0);
+        } else {
+            let count = bind.parameters.write().with_prefix(bind.parameters.file_name.as_str())?;
+            output.flat_vector(0).as_mut_slice::<i64>()[0] = count;
+            output.set_len(1);
+        }
+        Ok(())
+    }
+
+    /// Returns the required parameter types for the table function
+    ///
+    /// # Returns
+    /// * `Option<Vec<LogicalTypeHandle>>` - Required parameter types (file, query)
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            FileParam::kind(),
+            QueryParam::kind(),
+        ])
+    }
+
+    /// Returns the named parameter definitions for the table function
+    ///
+    /// # Returns
+    /// * `Option<Vec<(String, LogicalTypeHandle)>>` - Named parameter definitions
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            SheetNameParam::definition(),
+            HeaderParam::definition(),
+            AppendParam::definition(),
+            HeaderStyleParam::definition(),
+            FreezePanesParam::definition(),
+            AutofilterParam::definition(),
+            FormatParam::definition(),
+        ])
+    }
+}
@@ -2,21 +2,130 @@
 
 use crate::database::column::Column;
 use crate::database::column::ColumnType;
+use crate::database::decimal::to_decimal;
+use crate::database::decimal::DecimalPolicy;
 use crate::error::RustySheetError;
+use crate::extension::ExtensionError;
+use crate::helpers::metrics;
+use crate::helpers::metrics::ErrorCategory;
+use crate::spreadsheet::cell::parse_duration;
 use crate::spreadsheet::cell::Cell;
 use crate::spreadsheet::cell::CellType;
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use duckdb::core::LogicalTypeHandle;
 use duckdb::core::FlatVector;
 use duckdb::core::Inserter;
 use libduckdb_sys::duckdb_date;
+use libduckdb_sys::duckdb_hugeint;
+use libduckdb_sys::duckdb_interval;
 use libduckdb_sys::duckdb_time;
 use libduckdb_sys::duckdb_timestamp;
+use crate::spreadsheet::sheet::ChunkBuffer;
 use crate::spreadsheet::sheet::Sheet;
+use crate::spreadsheet::shared_strings::SharedStrings;
 use crate::spreadsheet::SpreadsheetError;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Longest string DuckDB stores inline in the 16-byte string_t slot itself
+const INLINE_STRING_LENGTH: usize = 12;
+
+/// Inlined variant of DuckDB's string_t: the length followed by the zero-padded bytes
+#[repr(C)]
+struct InlinedString {
+    length: u32,
+    inlined: [u8; INLINE_STRING_LENGTH],
+}
+
+/// Case folding applied to the values written to VARCHAR columns.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(super) enum VarcharCase {
+    /// Keep values as they are
+    #[default]
+    Preserve,
+    /// Fold values to lower case
+    Lower,
+    /// Fold values to upper case
+    Upper,
+}
+
+impl VarcharCase {
+    /// Parses a case folding name ('lower', 'upper' or 'preserve').
+    pub(super) fn parse(name: &str) -> Result<Self, RustySheetError> {
+        match name.to_ascii_lowercase().as_str() {
+            "preserve" => Ok(Self::Preserve),
+            "lower" => Ok(Self::Lower),
+            "upper" => Ok(Self::Upper),
+            _ => Err(ExtensionError::VarcharCaseError(name.to_string()))?,
+        }
+    }
+
+    /// Folds a value, borrowing it when it is kept as is.
+    fn apply<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::Preserve => Cow::Borrowed(value),
+            Self::Lower => Cow::Owned(value.to_lowercase()),
+            Self::Upper => Cow::Owned(value.to_uppercase()),
+        }
+    }
+}
+
+/// Conversion options applied to every written cell.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(super) struct WriteOptions {
+    /// Conversion policy for DECIMAL columns
+    pub(super) decimal: DecimalPolicy,
+    /// Case folding of VARCHAR values
+    pub(super) varchar_case: VarcharCase,
+    /// Whether DATE, TIME and TIMESTAMP columns are written as ISO 8601 strings
+    pub(super) dates_as_varchar: bool,
+}
+
+impl WriteOptions {
+    /// Returns the type a column of the given type is written as.
+    pub(super) fn column_type(&self, kind: ColumnType) -> ColumnType {
+        if self.dates_as_varchar && kind.is_datetime() {
+            ColumnType::Varchar
+        } else {
+            kind
+        }
+    }
+
+    /// Returns the DuckDB type a column of the given type is written as.
+    pub(super) fn logical_type(&self, kind: ColumnType) -> LogicalTypeHandle {
+        self.column_type(kind).to_logical_type()
+    }
+}
+
+/// Chunk buffers reused by the func() calls of a scan.
+///
+/// A call takes a buffer for itself and gives it back once its chunk is written,
+/// so calls running on several threads never share a buffer.
+#[derive(Debug, Default)]
+pub(super) struct ChunkBufferPool(Mutex<Vec<ChunkBuffer>>);
+
+impl ChunkBufferPool {
+    /// Takes a free buffer, or a new one when every buffer is in use.
+    pub(super) fn take(&self) -> ChunkBuffer {
+        self.0.lock().expect("Chunk buffer pool lock").pop().unwrap_or_default()
+    }
+
+    /// Gives a buffer back to the following calls.
+    pub(super) fn give_back(&self, buffer: ChunkBuffer) {
+        self.0.lock().expect("Chunk buffer pool lock").push(buffer);
+    }
+}
 
 /// Writes a cell value to a DuckDB vector based on column type.
 /// Handles type conversion and error mapping for different data types.
-pub(super) fn write_to_vector(sheet: &Sheet, column: &Column, cell: &Cell, vector: &mut FlatVector, row: usize, shared_strings: &Vec<Option<String>>) -> Result<(), RustySheetError> {
+/// Returns true without writing when the cell reads as NULL, i.e. a shared string matching
+/// a null literal, leaving the caller to write NULL or the default of the column.
+pub(super) fn write_to_vector(sheet: &Sheet, column: &Column, cell: &Cell, vector: &mut FlatVector, row: usize, shared_strings: &SharedStrings, options: &WriteOptions) -> Result<bool, RustySheetError> {
     let mapper = |message: String| {
+        metrics::record_error(ErrorCategory::Conversion);
         SpreadsheetError::CellValueError(
             sheet.file_name.to_owned(),
             sheet.name.to_owned(),
@@ -24,24 +133,163 @@ pub(super) fn write_to_vector(sheet: &Sheet, column: &Column, cell: &Cell, vecto
             message,
         )
     };
+    if options.dates_as_varchar && column.kind.is_datetime() {
+        write_string(vector, row, &to_iso_string(column.kind, cell).map_err(mapper)?);
+        return Ok(false);
+    }
     match (column.kind, cell.kind) {
         (ColumnType::Varchar, CellType::SharedString) => {
             let index = cell.value.parse::<usize>()?;
-            if let Some(shared_string) = &shared_strings[index] {
-                vector.insert(row, shared_string);
+            if let Some(shared_string) = shared_strings.get(index)? {
+                write_string(vector, row, &options.varchar_case.apply(&shared_string));
             } else {
-                vector.set_null(row);
+                return Ok(true);
             }
         }
-        (ColumnType::Varchar, _) => vector.insert(row, &cell.to_string()),
+        (ColumnType::Varchar, _) => write_string(vector, row, &options.varchar_case.apply(&cell.text())),
         (ColumnType::Boolean, _) => write_primitive(vector, row, cell.to_boolean()),
         (ColumnType::BigInt, _) => write_primitive(vector, row, cell.to_bigint().map_err(mapper)?),
         (ColumnType::Double, _) => write_primitive(vector, row, cell.to_double().map_err(mapper)?),
         (ColumnType::Timestamp, _) => write_timestamp(vector, row, cell.to_datetime().map_err(mapper)?),
         (ColumnType::Date, _) => write_date(vector, row, cell.to_date().map_err(mapper)?),
         (ColumnType::Time, _) => write_time(vector, row, cell.to_time().map_err(mapper)?),
+        (ColumnType::Interval, CellType::SharedString) => {
+            let index = cell.value.parse::<usize>()?;
+            if let Some(shared_string) = shared_strings.get(index)? {
+                let (days, micros) = parse_duration(&shared_string)
+                    .ok_or_else(|| mapper(format!("parse '{shared_string}' to interval failed")))?;
+                write_interval(vector, row, (0, days, micros));
+            } else {
+                return Ok(true);
+            }
+        }
+        (ColumnType::Interval, _) => write_interval(vector, row, cell.to_interval().map_err(mapper)?),
+        (ColumnType::Decimal(width, scale), CellType::SharedString) => {
+            let index = cell.value.parse::<usize>()?;
+            if let Some(shared_string) = shared_strings.get(index)? {
+                write_decimal(vector, row, width, to_decimal(&shared_string, width, scale, &options.decimal).map_err(mapper)?);
+            } else {
+                return Ok(true);
+            }
+        }
+        (ColumnType::Decimal(width, scale), _) => write_decimal(vector, row, width, to_decimal(&cell.value, width, scale, &options.decimal).map_err(mapper)?),
+    }
+    Ok(false)
+}
+
+/// Checks whether a cell converts to the type of its column, as `write_to_vector` would,
+/// without writing it. ignore_errors uses it to drop the rows holding a bad cell in a
+/// column left out of the projection.
+pub(super) fn converts(column: &Column, cell: &Cell, shared_strings: &SharedStrings, options: &WriteOptions) -> bool {
+    let shared_string = || shared_strings.get(cell.value.parse::<usize>()?);
+    let converts = match (column.kind, cell.kind) {
+        _ if options.dates_as_varchar && column.kind.is_datetime() => to_iso_string(column.kind, cell).is_ok(),
+        (ColumnType::Varchar, CellType::SharedString) => shared_string().is_ok(),
+        (ColumnType::Varchar, _) | (ColumnType::Boolean, _) => true,
+        (ColumnType::BigInt, _) => cell.to_bigint().is_ok(),
+        (ColumnType::Double, _) => cell.to_double().is_ok(),
+        (ColumnType::Timestamp, _) => cell.to_datetime().is_ok(),
+        (ColumnType::Date, _) => cell.to_date().is_ok(),
+        (ColumnType::Time, _) => cell.to_time().is_ok(),
+        (ColumnType::Interval, CellType::SharedString) => match shared_string() {
+            Ok(shared_string) => shared_string.is_none_or(|shared_string| parse_duration(&shared_string).is_some()),
+            Err(_) => false,
+        },
+        (ColumnType::Interval, _) => cell.to_interval().is_ok(),
+        (ColumnType::Decimal(width, scale), CellType::SharedString) => match shared_string() {
+            Ok(shared_string) => shared_string.is_none_or(|shared_string| to_decimal(&shared_string, width, scale, &options.decimal).is_ok()),
+            Err(_) => false,
+        },
+        (ColumnType::Decimal(width, scale), _) => to_decimal(&cell.value, width, scale, &options.decimal).is_ok(),
+    };
+    if !converts {
+        metrics::record_error(ErrorCategory::Conversion);
+    }
+    converts
+}
+
+/// Formats a date, time or timestamp cell as an ISO 8601 string, e.g. `2024-05-01`,
+/// `08:30:00.250` or `2024-05-01 08:30:00`, with a space between date and time. The value is
+/// converted as for the typed column, so it reads the same whatever the number format or
+/// locale of the cell, and values out of the supported range fail instead of panicking.
+fn to_iso_string(kind: ColumnType, cell: &Cell) -> Result<String, String> {
+    let out_of_range = || format!("'{}' is out of the date range", cell.value);
+    match kind {
+        ColumnType::Date => {
+            let date = cell.to_date()?
+                .checked_add(719_163) // Days from 0001-01-01 to 1970-01-01
+                .and_then(NaiveDate::from_num_days_from_ce_opt)
+                .ok_or_else(out_of_range)?;
+            Ok(date.format("%Y-%m-%d").to_string())
+        }
+        ColumnType::Time => {
+            let micros = cell.to_time()?.rem_euclid(86_400_000_000);
+            let time = NaiveTime::from_num_seconds_from_midnight_opt((micros / 1_000_000) as u32, (micros % 1_000_000) as u32 * 1_000)
+                .ok_or_else(out_of_range)?;
+            Ok(time.format("%H:%M:%S%.f").to_string())
+        }
+        _ => {
+            let datetime = DateTime::from_timestamp_micros(cell.to_datetime()?).ok_or_else(out_of_range)?;
+            Ok(datetime.naive_utc().format("%Y-%m-%d %H:%M:%S%.f").to_string())
+        }
+    }
+}
+
+/// Builds the cells written in place of the missing and NULL cells of each column from the
+/// text of the defaults given by column name; columns without a default get None.
+pub(super) fn default_cells(columns: &[Column], defaults: &HashMap<String, String>) -> Result<Vec<Option<Cell>>, RustySheetError> {
+    if let Some(name) = defaults.keys().find(|name| !columns.iter().any(|column| &column.name == *name)) {
+        Err(ExtensionError::DefaultColumnError(name.to_owned()))?
+    }
+    columns.iter()
+        .map(|column| defaults.get(&column.name).map(|text| default_cell(column, text)).transpose())
+        .collect()
+}
+
+/// Converts the text of a default to a cell of the column type: booleans as `true` or `false`,
+/// dates and timestamps in ISO 8601, times as `hh:mm:ss` and intervals as `d hh:mm:ss`.
+fn default_cell(column: &Column, text: &str) -> Result<Cell, RustySheetError> {
+    let error = |message: String| ExtensionError::DefaultValueError(column.name.to_owned(), text.to_owned(), message);
+    let (kind, value) = match column.kind {
+        ColumnType::Varchar | ColumnType::Interval => (CellType::InlineString, text.to_owned()),
+        ColumnType::Boolean => match text.to_ascii_lowercase().as_str() {
+            "true" | "1" => (CellType::Boolean, "1".to_owned()),
+            "false" | "0" => (CellType::Boolean, "0".to_owned()),
+            _ => Err(error("expected true or false".to_owned()))?,
+        },
+        ColumnType::Timestamp | ColumnType::Date => (CellType::IsoDateTime, text.replacen(' ', "T", 1)),
+        ColumnType::Time => (CellType::IsoDateTime, format!("1970-01-01T{text}")),
+        ColumnType::BigInt | ColumnType::Double | ColumnType::Decimal(_, _) => (CellType::Number, text.to_owned()),
+    };
+    let cell = Cell { row: 0, col: 0, kind, value };
+    let converted = match column.kind {
+        ColumnType::Varchar | ColumnType::Boolean => Ok(()),
+        ColumnType::BigInt => cell.to_bigint().map(|_| ()),
+        ColumnType::Double => cell.to_double().map(|_| ()),
+        ColumnType::Timestamp => cell.to_datetime().map(|_| ()),
+        ColumnType::Date => cell.to_date().map(|_| ()),
+        ColumnType::Time => cell.to_time().map(|_| ()),
+        ColumnType::Interval => cell.to_interval().map(|_| ()),
+        ColumnType::Decimal(width, scale) => to_decimal(&cell.value, width, scale, &DecimalPolicy::default()).map(|_| ()),
+    };
+    converted.map_err(error)?;
+    Ok(cell)
+}
+
+/// Writes a string to a VARCHAR vector.
+/// Short strings are written inline into the string_t slot, skipping the per-row FFI call;
+/// longer ones are copied into the vector's string heap by DuckDB.
+pub(super) fn write_string(vector: &mut FlatVector, index: usize, value: &str) {
+    if value.len() <= INLINE_STRING_LENGTH {
+        let mut inlined = [0u8; INLINE_STRING_LENGTH];
+        inlined[..value.len()].copy_from_slice(value.as_bytes());
+        write_primitive(vector, index, InlinedString {
+            length: value.len() as u32,
+            inlined,
+        });
+    } else {
+        vector.insert(index, value);
     }
-    Ok(())
 }
 
 /// Writes a primitive value directly to a vector using pointer arithmetic.
@@ -52,8 +300,21 @@ fn write_primitive<T>(vector: &mut FlatVector, index: usize, value: T) {
     }
 }
 
+/// Writes an unscaled decimal value using the physical type DuckDB picks for the width.
+fn write_decimal(vector: &mut FlatVector, index: usize, width: u8, value: i128) {
+    match width {
+        1..=4 => write_primitive(vector, index, value as i16),
+        5..=9 => write_primitive(vector, index, value as i32),
+        10..=18 => write_primitive(vector, index, value as i64),
+        _ => write_primitive(vector, index, duckdb_hugeint {
+            lower: value as u64,
+            upper: (value >> 64) as i64,
+        }),
+    }
+}
+
 /// Writes a timestamp value (microseconds since epoch) to a DuckDB timestamp vector.
-fn write_timestamp(vector: &mut FlatVector, index: usize, value: i64) {
+pub(super) fn write_timestamp(vector: &mut FlatVector, index: usize, value: i64) {
     let pointer: *mut duckdb_timestamp = vector.as_mut_ptr();
     unsafe {
         let pointer = pointer.add(index);
@@ -70,14 +331,16 @@ fn write_date(vector: &mut FlatVector, index: usize, value: i32) {
     }
 }
 
-// fn write_interval(vector: &mut FlatVector, index: usize, value: Duration) {
-//     let pointer: *mut duckdb_interval = vector.as_mut_ptr();
-//     unsafe {
-//         let pointer = pointer.add(index);
-//         (*pointer).days = value.num_days() as i32;
-//         (*pointer).micros = value.subsec_micros() as i64;
-//     }
-// }
+/// Writes an interval value (months, days, microseconds) to a DuckDB interval vector.
+fn write_interval(vector: &mut FlatVector, index: usize, (months, days, micros): (i32, i32, i64)) {
+    let pointer: *mut duckdb_interval = vector.as_mut_ptr();
+    unsafe {
+        let pointer = pointer.add(index);
+        (*pointer).months = months;
+        (*pointer).days = days;
+        (*pointer).micros = micros;
+    }
+}
 
 /// Writes a time value (microseconds since midnight) to a DuckDB time vector.
 fn write_time(vector: &mut FlatVector, index: usize, value: i64) {
@@ -43,7 +43,10 @@ impl<R: BufRead> Biff12Reader<R> {
         at: usize,
     ) -> Result<(Cow<'_, str>, usize), RustySheetError> {
         let lower_bound = at + 4usize;
-        let size = to_usize(&self.buffer[at..at + lower_bound]);
+        if self.buffer.len() < lower_bound {
+            Err(Biff12Error::NoEnoughData(lower_bound, self.buffer.len()))?
+        }
+        let size = to_usize(&self.buffer[at..lower_bound]);
         let upper_bound = lower_bound + size * 2;
         if self.buffer.len() >= upper_bound {
             let (value, _, _) = UTF_16LE.decode(&self.buffer[lower_bound..upper_bound]);
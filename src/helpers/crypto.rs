@@ -0,0 +1,1011 @@
+//! Decryption of password protected Office documents
+//! Implements the Standard and Agile encryption of OOXML packages (MS-OFFCRYPTO), whose
+//! encrypted package is stored in a Compound File Binary container, and the XOR obfuscation
+//! and RC4 encryption of the records of BIFF8 workbook streams, as well as the encryption of the
+//! parts of OpenDocument packages declared in their manifest
+
+use crate::error::RustySheetError;
+use crate::helpers::cfb::Cfb;
+use crate::helpers::string::to_u16;
+use crate::helpers::string::to_u64;
+use crate::helpers::string::to_usize;
+use crate::helpers::xml::XmlNodeHelper;
+use crate::helpers::xml::XmlReader;
+use crate::match_xml_events;
+use aes::cipher::block_padding::NoPadding;
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::AsyncStreamCipher;
+use aes::cipher::BlockCipher;
+use aes::cipher::BlockDecrypt;
+use aes::cipher::BlockDecryptMut;
+use aes::cipher::InnerIvInit;
+use aes::cipher::KeyInit;
+use aes::Aes128;
+use aes::Aes192;
+use aes::Aes256;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use blowfish::Blowfish;
+use flate2::read::DeflateDecoder;
+use md5::Md5;
+use quick_xml::events::Event;
+use rc4::consts::U5;
+use rc4::consts::U6;
+use rc4::consts::U7;
+use rc4::consts::U8;
+use rc4::consts::U9;
+use rc4::consts::U10;
+use rc4::consts::U11;
+use rc4::consts::U12;
+use rc4::consts::U13;
+use rc4::consts::U14;
+use rc4::consts::U15;
+use rc4::consts::U16;
+use rc4::Rc4;
+use rc4::StreamCipher;
+use sha1::Digest;
+use sha1::Sha1;
+use sha2::Sha256;
+use sha2::Sha384;
+use sha2::Sha512;
+use std::io::Read;
+use thiserror::Error;
+
+/// Stream holding the encryption parameters
+const ENCRYPTION_INFO: &str = "EncryptionInfo";
+/// Stream holding the size and the encrypted bytes of the package
+const ENCRYPTED_PACKAGE: &str = "EncryptedPackage";
+
+/// Hash iterations of the Standard encryption key derivation
+const STANDARD_SPIN_COUNT: u32 = 50000;
+/// Bytes of the package encrypted with one initialization vector in Agile encryption
+const AGILE_SEGMENT_SIZE: usize = 4096;
+/// Block keys of the Agile key derivation (MS-OFFCRYPTO 2.3.4.13)
+const VERIFIER_HASH_INPUT_BLOCK: [u8; 8] = [0xfe, 0xa7, 0xd2, 0x76, 0x3b, 0x4b, 0x9e, 0x79];
+const VERIFIER_HASH_VALUE_BLOCK: [u8; 8] = [0xd7, 0xaa, 0x0f, 0x6d, 0x30, 0x61, 0x34, 0x4e];
+const ENCRYPTED_KEY_VALUE_BLOCK: [u8; 8] = [0x14, 0x6e, 0x0b, 0xe7, 0xab, 0xac, 0xd0, 0xd6];
+
+/// Algorithm identifiers of the Standard encryption header
+const ALG_ID_AES_128: usize = 0x660E;
+const ALG_ID_AES_192: usize = 0x660F;
+const ALG_ID_AES_256: usize = 0x6610;
+
+/// Most PBKDF2 iterations accepted from an OpenDocument manifest; producers use 1024 to 100000
+const MAX_ODF_ITERATION_COUNT: u32 = 1_000_000;
+
+/// Bytes of a BIFF8 workbook stream encrypted with one RC4 key
+const RC4_BLOCK_SIZE: usize = 1024;
+/// Bytes appended to short passwords by the XOR obfuscation (MS-OFFCRYPTO 2.3.7.2)
+const XOR_PAD: [u8; 16] = [0xbb, 0xff, 0xff, 0xba, 0xff, 0xff, 0xb9, 0x80, 0x00, 0xbe, 0x0f, 0x00, 0xbf, 0x0f, 0x00, 0x00];
+
+/// Password Excel encrypts workbooks with when they are only protected against modification
+pub(crate) const DEFAULT_PASSWORD: &str = "VelvetSweatshop";
+
+/// Errors specific to the decryption of protected documents
+#[derive(Error, Debug)]
+pub(crate) enum CryptoError {
+    #[error("The encryption information is missing or corrupted")]
+    EncryptionInfoError,
+
+    #[error("Unsupported encryption version {0}.{1}")]
+    EncryptionVersionError(u16, u16),
+
+    #[error("Unsupported encryption algorithm '{0}'")]
+    EncryptionAlgorithmError(String),
+
+    #[error("The password is incorrect")]
+    PasswordError,
+}
+
+/// Decrypts the package of a password protected OOXML document (xlsx, xlsb)
+///
+/// # Arguments
+/// * `cfb` - Compound file holding the `EncryptionInfo` and `EncryptedPackage` streams
+/// * `password` - Password the document was encrypted with
+///
+/// # Returns
+/// * `Result<Vec<u8>, RustySheetError>` - Bytes of the decrypted ZIP package
+pub(crate) fn decrypt_package(cfb: &Cfb, password: &str) -> Result<Vec<u8>, RustySheetError> {
+    let info = cfb.read(ENCRYPTION_INFO)?.ok_or(CryptoError::EncryptionInfoError)?;
+    let package = cfb.read(ENCRYPTED_PACKAGE)?.ok_or(CryptoError::EncryptionInfoError)?;
+    let key = match parse_version(&info)? {
+        (2..=4, 2) => StandardEncryption::parse(&info)?.key(password)?,
+        (4, 4) => AgileEncryption::parse(&info)?.key(password)?,
+        (major, minor) => Err(CryptoError::EncryptionVersionError(major, minor))?,
+    };
+    Ok(key.decrypt(&package)?)
+}
+
+/// Reads the major and minor version at the start of the encryption information
+fn parse_version(info: &[u8]) -> Result<(u16, u16), CryptoError> {
+    if info.len() < 8 {
+        Err(CryptoError::EncryptionInfoError)?
+    }
+    Ok((to_u16(&info[0..2]), to_u16(&info[2..4])))
+}
+
+/// Returns the password as the UTF-16LE bytes hashed by the key derivations
+fn password_bytes(password: &str) -> Vec<u8> {
+    password.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+/// Hash algorithms of the key derivations
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum HashAlgorithm {
+    /// Only used by the RC4 encryption of legacy workbooks
+    Md5,
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    fn parse(name: &str) -> Result<HashAlgorithm, CryptoError> {
+        match name.to_ascii_uppercase().as_str() {
+            "SHA1" | "SHA-1" => Ok(HashAlgorithm::Sha1),
+            "SHA256" | "SHA-256" => Ok(HashAlgorithm::Sha256),
+            "SHA384" | "SHA-384" => Ok(HashAlgorithm::Sha384),
+            "SHA512" | "SHA-512" => Ok(HashAlgorithm::Sha512),
+            _ => Err(CryptoError::EncryptionAlgorithmError(name.to_owned())),
+        }
+    }
+
+    /// Hashes the concatenation of `parts`
+    fn digest(&self, parts: &[&[u8]]) -> Vec<u8> {
+        fn digest<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            parts.iter().for_each(|part| hasher.update(part));
+            hasher.finalize().to_vec()
+        }
+        match self {
+            HashAlgorithm::Md5 => digest::<Md5>(parts),
+            HashAlgorithm::Sha1 => digest::<Sha1>(parts),
+            HashAlgorithm::Sha256 => digest::<Sha256>(parts),
+            HashAlgorithm::Sha384 => digest::<Sha384>(parts),
+            HashAlgorithm::Sha512 => digest::<Sha512>(parts),
+        }
+    }
+
+    /// Hashes the salted password, then rehashes it `spin_count` times with the iteration number
+    fn spin(&self, salt: &[u8], password: &str, spin_count: u32) -> Vec<u8> {
+        let mut hash = self.digest(&[salt, &password_bytes(password)]);
+        for iteration in 0..spin_count {
+            hash = self.digest(&[&iteration.to_le_bytes(), &hash]);
+        }
+        hash
+    }
+}
+
+/// AES key of one of the three key sizes
+enum AesKey {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256),
+}
+
+impl AesKey {
+    fn new(key: &[u8]) -> Result<AesKey, CryptoError> {
+        match key.len() {
+            16 => Ok(AesKey::Aes128(Aes128::new(GenericArray::from_slice(key)))),
+            24 => Ok(AesKey::Aes192(Aes192::new(GenericArray::from_slice(key)))),
+            32 => Ok(AesKey::Aes256(Aes256::new(GenericArray::from_slice(key)))),
+            size => Err(CryptoError::EncryptionAlgorithmError(format!("AES-{}", size * 8))),
+        }
+    }
+
+    /// Decrypts the 16-byte blocks of `data` independently; trailing bytes are left as is
+    fn decrypt_ecb(&self, data: &mut [u8]) {
+        for block in data.chunks_exact_mut(16) {
+            let block = GenericArray::from_mut_slice(block);
+            match self {
+                AesKey::Aes128(cipher) => cipher.decrypt_block(block),
+                AesKey::Aes192(cipher) => cipher.decrypt_block(block),
+                AesKey::Aes256(cipher) => cipher.decrypt_block(block),
+            }
+        }
+    }
+
+    /// Decrypts the 16-byte blocks of `data` chained from `iv`; trailing bytes are left as is
+    fn decrypt_cbc(&self, iv: &[u8], data: &mut [u8]) {
+        match self {
+            AesKey::Aes128(cipher) => decrypt_cbc(cipher, iv, data),
+            AesKey::Aes192(cipher) => decrypt_cbc(cipher, iv, data),
+            AesKey::Aes256(cipher) => decrypt_cbc(cipher, iv, data),
+        }
+    }
+}
+
+/// Decrypts the whole blocks of `data` with a block cipher in CBC mode chained from `iv`;
+/// trailing bytes are left as is
+fn decrypt_cbc<C: BlockDecryptMut + BlockCipher + Clone>(cipher: &C, iv: &[u8], data: &mut [u8]) {
+    let size = data.len() - data.len() % C::block_size();
+    if let Ok(decryptor) = cbc::Decryptor::inner_iv_slice_init(cipher.clone(), &iv[..C::block_size()]) {
+        // Whole blocks never fail to decrypt without padding
+        let _ = decryptor.decrypt_padded_mut::<NoPadding>(&mut data[..size]);
+    }
+}
+
+/// Key decrypting the package once the password is verified
+enum PackageKey {
+    /// Standard encryption: AES in ECB mode over the whole package
+    Standard(AesKey),
+    /// Agile encryption: AES in CBC mode over segments with their own initialization vector
+    Agile {
+        key: AesKey,
+        hash: HashAlgorithm,
+        salt: Vec<u8>,
+        block_size: usize,
+    },
+}
+
+impl PackageKey {
+    /// Decrypts the `EncryptedPackage` stream: its size followed by the encrypted bytes
+    fn decrypt(&self, package: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if package.len() < 8 {
+            Err(CryptoError::EncryptionInfoError)?
+        }
+        let size = to_u64(&package[0..8]) as usize;
+        let mut data = package[8..].to_vec();
+        match self {
+            PackageKey::Standard(key) => key.decrypt_ecb(&mut data),
+            PackageKey::Agile { key, hash, salt, block_size } => {
+                for (index, segment) in data.chunks_mut(AGILE_SEGMENT_SIZE).enumerate() {
+                    let iv = fit(&hash.digest(&[salt, &(index as u32).to_le_bytes()]), *block_size);
+                    key.decrypt_cbc(&iv, segment);
+                }
+            }
+        }
+        if size > data.len() {
+            Err(CryptoError::EncryptionInfoError)?
+        }
+        data.truncate(size);
+        Ok(data)
+    }
+}
+
+/// Truncates `bytes` to `size`, or pads them with 0x36 up to `size`
+fn fit(bytes: &[u8], size: usize) -> Vec<u8> {
+    let mut bytes = bytes[..size.min(bytes.len())].to_vec();
+    bytes.resize(size, 0x36);
+    bytes
+}
+
+/// Parameters of the Standard encryption (MS-OFFCRYPTO 2.3.4.5)
+#[derive(Debug)]
+struct StandardEncryption {
+    key_size: usize,
+    salt: Vec<u8>,
+    encrypted_verifier: Vec<u8>,
+    encrypted_verifier_hash: Vec<u8>,
+}
+
+impl StandardEncryption {
+    fn parse(info: &[u8]) -> Result<StandardEncryption, CryptoError> {
+        let field = |offset: usize| info.get(offset..offset + 4).map(to_usize).ok_or(CryptoError::EncryptionInfoError);
+        let header_size = field(8)?;
+        let algorithm = field(20)?;
+        let key_size = match (algorithm, field(28)?) {
+            (ALG_ID_AES_128 | ALG_ID_AES_192 | ALG_ID_AES_256, bits) => bits / 8,
+            (algorithm, _) => Err(CryptoError::EncryptionAlgorithmError(format!("0x{algorithm:04X}")))?,
+        };
+        let verifier = 12 + header_size;
+        let salt_size = field(verifier)?;
+        let salt = verifier + 4;
+        let encrypted_verifier = salt + salt_size;
+        let encrypted_verifier_hash = encrypted_verifier + 16 + 4;
+        let bytes = |offset: usize, size: usize| info.get(offset..offset + size).map(<[u8]>::to_vec).ok_or(CryptoError::EncryptionInfoError);
+        Ok(StandardEncryption {
+            key_size,
+            salt: bytes(salt, salt_size)?,
+            encrypted_verifier: bytes(encrypted_verifier, 16)?,
+            encrypted_verifier_hash: bytes(encrypted_verifier_hash, 32)?,
+        })
+    }
+
+    /// Derives the key from the password and checks it against the verifier
+    fn key(&self, password: &str) -> Result<PackageKey, CryptoError> {
+        if self.key_size > 40 {
+            Err(CryptoError::EncryptionInfoError)?
+        }
+        let key = AesKey::new(&standard_key(&self.salt, password, self.key_size))?;
+
+        let hash = HashAlgorithm::Sha1;
+        let mut verifier = self.encrypted_verifier.to_owned();
+        let mut verifier_hash = self.encrypted_verifier_hash.to_owned();
+        key.decrypt_ecb(&mut verifier);
+        key.decrypt_ecb(&mut verifier_hash);
+        if hash.digest(&[&verifier]) != verifier_hash[..20] {
+            Err(CryptoError::PasswordError)?
+        }
+        Ok(PackageKey::Standard(key))
+    }
+}
+
+/// Derives the `key_size` bytes of a Standard encryption key from the password (MS-OFFCRYPTO 2.3.4.7)
+fn standard_key(salt: &[u8], password: &str, key_size: usize) -> Vec<u8> {
+    let hash = HashAlgorithm::Sha1;
+    let spun = hash.spin(salt, password, STANDARD_SPIN_COUNT);
+    let derived = hash.digest(&[&spun, &0u32.to_le_bytes()]);
+    let pad = |byte: u8| {
+        let mut buffer = [byte; 64];
+        buffer.iter_mut().zip(&derived).for_each(|(value, byte)| *value ^= byte);
+        hash.digest(&[&buffer])
+    };
+    let mut key = [pad(0x36), pad(0x5c)].concat();
+    key.truncate(key_size);
+    key
+}
+
+/// Parameters of the Agile encryption (MS-OFFCRYPTO 2.3.4.10)
+#[derive(Debug, Default)]
+struct AgileEncryption {
+    /// Parameters of the package encryption
+    data: AgileParameters,
+    /// Parameters of the password key encryptor
+    password: AgileParameters,
+    spin_count: u32,
+    encrypted_verifier_hash_input: Vec<u8>,
+    encrypted_verifier_hash_value: Vec<u8>,
+    encrypted_key_value: Vec<u8>,
+}
+
+/// Cipher parameters shared by the `keyData` and `encryptedKey` elements
+#[derive(Debug, Default)]
+struct AgileParameters {
+    salt: Vec<u8>,
+    hash: Option<HashAlgorithm>,
+    key_bits: usize,
+    block_size: usize,
+}
+
+impl AgileParameters {
+    fn parse(event: &quick_xml::events::BytesStart) -> Result<AgileParameters, RustySheetError> {
+        let cipher = event.get_attribute_value("cipherAlgorithm")?.unwrap_or_default();
+        let chaining = event.get_attribute_value("cipherChaining")?.unwrap_or_default();
+        if cipher != "AES" || chaining != "ChainingModeCBC" {
+            Err(CryptoError::EncryptionAlgorithmError(format!("{cipher} {chaining}")))?
+        }
+        Ok(AgileParameters {
+            salt: decode_base64(event.get_attribute_value("saltValue")?.as_deref())?,
+            hash: Some(HashAlgorithm::parse(&event.get_attribute_value("hashAlgorithm")?.unwrap_or_default())?),
+            key_bits: event.parse_attribute_value("keyBits")?.ok_or(CryptoError::EncryptionInfoError)?,
+            block_size: event.parse_attribute_value("blockSize")?.ok_or(CryptoError::EncryptionInfoError)?,
+        })
+    }
+}
+
+/// Decodes a base64 attribute value of the encryption descriptor
+fn decode_base64(value: Option<&str>) -> Result<Vec<u8>, CryptoError> {
+    value.and_then(|value| BASE64.decode(value.trim()).ok())
+        .ok_or(CryptoError::EncryptionInfoError)
+}
+
+impl AgileEncryption {
+    /// Parses the XML descriptor following the version and reserved fields
+    fn parse(info: &[u8]) -> Result<AgileEncryption, RustySheetError> {
+        let mut encryption = AgileEncryption::default();
+        let mut reader = XmlReader::new(&info[8..]);
+        match_xml_events!(reader => {
+            Event::Start(event) if event.local_name().as_ref() == b"keyData" => {
+                encryption.data = AgileParameters::parse(&event)?;
+            }
+            Event::Start(event) if event.local_name().as_ref() == b"encryptedKey" && event.get_attribute_value("spinCount")?.is_some() => {
+                encryption.password = AgileParameters::parse(&event)?;
+                encryption.spin_count = event.parse_attribute_value("spinCount")?.unwrap_or_default();
+                encryption.encrypted_verifier_hash_input = decode_base64(event.get_attribute_value("encryptedVerifierHashInput")?.as_deref())?;
+                encryption.encrypted_verifier_hash_value = decode_base64(event.get_attribute_value("encryptedVerifierHashValue")?.as_deref())?;
+                encryption.encrypted_key_value = decode_base64(event.get_attribute_value("encryptedKeyValue")?.as_deref())?;
+            }
+        });
+        if encryption.data.hash.is_none() || encryption.password.hash.is_none() {
+            Err(CryptoError::EncryptionInfoError)?
+        }
+        Ok(encryption)
+    }
+
+    /// Derives the password key (MS-OFFCRYPTO 2.3.4.11), checks it against the verifier
+    /// and decrypts the key of the package with it (2.3.4.13)
+    fn key(&self, password: &str) -> Result<PackageKey, CryptoError> {
+        let AgileParameters { salt, hash, key_bits, block_size } = &self.password;
+        let hash = hash.ok_or(CryptoError::EncryptionInfoError)?;
+        if *block_size < 16 {
+            Err(CryptoError::EncryptionInfoError)?
+        }
+        let spun = hash.spin(salt, password, self.spin_count);
+        let iv = fit(salt, *block_size);
+        let decrypt = |block: &[u8], encrypted: &[u8]| -> Result<Vec<u8>, CryptoError> {
+            let key = AesKey::new(&fit(&hash.digest(&[&spun, block]), key_bits / 8))?;
+            let mut bytes = encrypted.to_vec();
+            key.decrypt_cbc(&iv, &mut bytes);
+            Ok(bytes)
+        };
+
+        let verifier = decrypt(&VERIFIER_HASH_INPUT_BLOCK, &self.encrypted_verifier_hash_input)?;
+        let verifier = &verifier[..salt.len().min(verifier.len())];
+        let expected = decrypt(&VERIFIER_HASH_VALUE_BLOCK, &self.encrypted_verifier_hash_value)?;
+        let actual = hash.digest(&[verifier]);
+        if expected.get(..actual.len()) != Some(actual.as_slice()) {
+            Err(CryptoError::PasswordError)?
+        }
+
+        let key = decrypt(&ENCRYPTED_KEY_VALUE_BLOCK, &self.encrypted_key_value)?;
+        let key = key.get(..self.data.key_bits / 8).ok_or(CryptoError::EncryptionInfoError)?;
+        if self.data.block_size < 16 {
+            Err(CryptoError::EncryptionInfoError)?
+        }
+        Ok(PackageKey::Agile {
+            key: AesKey::new(key)?,
+            hash: self.data.hash.ok_or(CryptoError::EncryptionInfoError)?,
+            salt: self.data.salt.to_owned(),
+            block_size: self.data.block_size,
+        })
+    }
+}
+
+/// Encryption of a part of an OpenDocument package, declared by the `encryption-data`
+/// element of its manifest entry (ODF 1.2 part 3, section 3.8)
+#[derive(Clone, Debug, Default)]
+pub(crate) struct OdfEncryption {
+    checksum_type: String,
+    checksum: Vec<u8>,
+    algorithm: String,
+    iv: Vec<u8>,
+    start_key_generation: String,
+    key_derivation: String,
+    key_size: Option<usize>,
+    iteration_count: u32,
+    salt: Vec<u8>,
+}
+
+impl OdfEncryption {
+    /// Reads the attributes of `encryption-data` or one of its child elements
+    pub(crate) fn parse(&mut self, event: &quick_xml::events::BytesStart) -> Result<(), RustySheetError> {
+        match event.local_name().as_ref() {
+            b"encryption-data" => {
+                self.checksum_type = event.get_attribute_value("manifest:checksum-type")?.unwrap_or_default().into_owned();
+                self.checksum = decode_base64(event.get_attribute_value("manifest:checksum")?.as_deref()).unwrap_or_default();
+            }
+            b"algorithm" => {
+                self.algorithm = event.get_attribute_value("manifest:algorithm-name")?.unwrap_or_default().into_owned();
+                self.iv = decode_base64(event.get_attribute_value("manifest:initialisation-vector")?.as_deref())?;
+            }
+            b"start-key-generation" => {
+                self.start_key_generation = event.get_attribute_value("manifest:start-key-generation-name")?.unwrap_or_default().into_owned();
+            }
+            b"key-derivation" => {
+                self.key_derivation = event.get_attribute_value("manifest:key-derivation-name")?.unwrap_or_default().into_owned();
+                self.key_size = event.parse_attribute_value("manifest:key-size")?;
+                self.iteration_count = event.parse_attribute_value("manifest:iteration-count")?.unwrap_or_default();
+                self.salt = decode_base64(event.get_attribute_value("manifest:salt")?.as_deref())?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Decrypts and inflates the part: the start key is the SHA-1 or SHA-256 hash of the
+    /// UTF-8 password, the key is derived from it with PBKDF2, and the deflated part is
+    /// encrypted with Blowfish in CFB mode or AES-256 in CBC mode
+    ///
+    /// # Arguments
+    /// * `data` - Encrypted bytes of the part, as stored in the package
+    /// * `password` - Password the document was encrypted with
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>, RustySheetError>` - Decrypted and inflated bytes of the part
+    pub(crate) fn decrypt(&self, data: &[u8], password: &str) -> Result<Vec<u8>, RustySheetError> {
+        let start_key = match odf_name(&self.start_key_generation) {
+            "" | "SHA1" | "sha1" => HashAlgorithm::Sha1.digest(&[password.as_bytes()]),
+            "sha256" | "SHA256" => HashAlgorithm::Sha256.digest(&[password.as_bytes()]),
+            _ => Err(CryptoError::EncryptionAlgorithmError(self.start_key_generation.to_owned()))?,
+        };
+        if self.key_derivation != "PBKDF2" {
+            Err(CryptoError::EncryptionAlgorithmError(self.key_derivation.to_owned()))?
+        }
+        if !(1..=MAX_ODF_ITERATION_COUNT).contains(&self.iteration_count) {
+            Err(CryptoError::EncryptionInfoError)?
+        }
+
+        let mut decrypted = data.to_vec();
+        match odf_name(&self.algorithm) {
+            "Blowfish CFB" => {
+                // Blowfish keys are 32 to 448 bits long
+                let key_size = self.key_size.unwrap_or(16);
+                if !(4..=56).contains(&key_size) {
+                    Err(CryptoError::EncryptionInfoError)?
+                }
+                let mut key = vec![0u8; key_size];
+                pbkdf2::pbkdf2_hmac::<Sha1>(&start_key, &self.salt, self.iteration_count, &mut key);
+                let cipher: Blowfish = Blowfish::new_from_slice(&key).map_err(|_| CryptoError::EncryptionInfoError)?;
+                let iv = self.iv.get(..8).ok_or(CryptoError::EncryptionInfoError)?;
+                decrypt_cfb(&cipher, iv, &mut decrypted);
+            }
+            "aes256-cbc" => {
+                if self.key_size.is_some_and(|key_size| key_size != 32) {
+                    Err(CryptoError::EncryptionInfoError)?
+                }
+                let mut key = [0u8; 32];
+                pbkdf2::pbkdf2_hmac::<Sha1>(&start_key, &self.salt, self.iteration_count, &mut key);
+                if self.iv.len() < 16 || !decrypted.len().is_multiple_of(16) {
+                    Err(CryptoError::EncryptionInfoError)?
+                }
+                AesKey::new(&key)?.decrypt_cbc(&self.iv, &mut decrypted);
+                // Padding bytes all hold the padding length (PKCS #7)
+                let padding = decrypted.last().copied().unwrap_or_default() as usize;
+                if !(1..=16).contains(&padding) || padding > decrypted.len() {
+                    Err(CryptoError::PasswordError)?
+                }
+                decrypted.truncate(decrypted.len() - padding);
+            }
+            _ => Err(CryptoError::EncryptionAlgorithmError(self.algorithm.to_owned()))?,
+        }
+
+        let checksum = match odf_name(&self.checksum_type) {
+            "" => None,
+            "SHA1/1K" | "sha1-1k" => Some(HashAlgorithm::Sha1.digest(&[&decrypted[..decrypted.len().min(1024)]])),
+            "sha256-1k" => Some(HashAlgorithm::Sha256.digest(&[&decrypted[..decrypted.len().min(1024)]])),
+            "SHA1" => Some(HashAlgorithm::Sha1.digest(&[&decrypted])),
+            _ => Err(CryptoError::EncryptionAlgorithmError(self.checksum_type.to_owned()))?,
+        };
+        if checksum.is_some_and(|checksum| checksum != self.checksum) {
+            Err(CryptoError::PasswordError)?
+        }
+
+        let mut inflated = Vec::new();
+        DeflateDecoder::new(decrypted.as_slice()).read_to_end(&mut inflated)?;
+        Ok(inflated)
+    }
+}
+
+/// Strips the namespace URI of an algorithm name, such as
+/// `http://www.w3.org/2001/04/xmlenc#aes256-cbc`
+fn odf_name(name: &str) -> &str {
+    name.rsplit('#').next().unwrap_or(name)
+}
+
+/// Decrypts `data` with a 64-bit block cipher in CFB mode chained from `iv`
+fn decrypt_cfb(cipher: &Blowfish, iv: &[u8], data: &mut [u8]) {
+    if let Ok(decryptor) = cfb_mode::Decryptor::inner_iv_slice_init(cipher.clone(), &iv[..8]) {
+        decryptor.decrypt(data);
+    }
+}
+
+/// Cipher of the records following the FILE_PASS record of a BIFF8 workbook stream
+/// (MS-XLS 2.2.10, MS-OFFCRYPTO 2.3.6 and 2.3.7)
+pub(crate) enum RecordCipher {
+    /// XOR obfuscation with the 16-byte obfuscation array
+    Xor([u8; 16]),
+    /// RC4 encryption, rekeyed every 1024 bytes of the stream
+    Rc4 {
+        key: Rc4Key,
+        /// Block whose key stream is cached
+        block: Option<usize>,
+        key_stream: Vec<u8>,
+    },
+}
+
+impl RecordCipher {
+    /// Verifies the password against a FILE_PASS record
+    ///
+    /// # Arguments
+    /// * `file_pass` - Data of the FILE_PASS record
+    /// * `password` - Password the workbook was encrypted with
+    ///
+    /// # Returns
+    /// The cipher decrypting the following records, or `PasswordError` for a wrong password
+    pub(crate) fn new(file_pass: &[u8], password: &str) -> Result<RecordCipher, CryptoError> {
+        let field = |offset: usize| file_pass.get(offset..offset + 2).map(to_u16).ok_or(CryptoError::EncryptionInfoError);
+        match field(0)? {
+            0 => {
+                let password = xor_password_bytes(password);
+                if xor_verifier(&password) != field(4)? {
+                    Err(CryptoError::PasswordError)?
+                }
+                Ok(RecordCipher::Xor(xor_array(&password, field(2)?)))
+            }
+            1 => {
+                let key = match (field(2)?, field(4)?) {
+                    (1, 1) => Rc4Key::parse_md5(file_pass, password)?,
+                    (2..=4, 2) => Rc4Key::parse_crypto_api(file_pass, password)?,
+                    (major, minor) => Err(CryptoError::EncryptionVersionError(major, minor))?,
+                };
+                Ok(RecordCipher::Rc4 { key, block: None, key_stream: Vec::new() })
+            }
+            kind => Err(CryptoError::EncryptionAlgorithmError(format!("FilePass type {kind}"))),
+        }
+    }
+
+    /// Decrypts record data in place
+    ///
+    /// # Arguments
+    /// * `data` - Encrypted bytes of a record
+    /// * `position` - Position of the first byte in the workbook stream
+    /// * `record_size` - Size of the whole record data, which offsets the XOR obfuscation array
+    pub(crate) fn decrypt(&mut self, data: &mut [u8], position: usize, record_size: usize) {
+        match self {
+            RecordCipher::Xor(array) => {
+                for (index, byte) in data.iter_mut().enumerate() {
+                    // XORed with the obfuscation array first, then rotated back (MS-OFFCRYPTO 2.3.7.4)
+                    *byte = (*byte ^ array[(position + index + record_size) % 16]).rotate_right(5);
+                }
+            }
+            RecordCipher::Rc4 { key, block, key_stream } => {
+                for (index, byte) in data.iter_mut().enumerate() {
+                    let position = position + index;
+                    if *block != Some(position / RC4_BLOCK_SIZE) {
+                        *block = Some(position / RC4_BLOCK_SIZE);
+                        *key_stream = vec![0; RC4_BLOCK_SIZE];
+                        apply_rc4(&key.block_key(position / RC4_BLOCK_SIZE), key_stream);
+                    }
+                    *byte ^= key_stream[position % RC4_BLOCK_SIZE];
+                }
+            }
+        }
+    }
+}
+
+/// Returns the password as the single bytes the XOR obfuscation works on: the low byte
+/// of each of the first 15 characters, or the high byte when the low byte is zero
+fn xor_password_bytes(password: &str) -> Vec<u8> {
+    password.encode_utf16()
+        .take(15)
+        .map(|char| match char.to_le_bytes() {
+            [0, high] => high,
+            [low, _] => low,
+        })
+        .collect()
+}
+
+/// Computes the password verifier of the XOR obfuscation (MS-OFFCRYPTO 2.3.7.1)
+fn xor_verifier(password: &[u8]) -> u16 {
+    let length = password.len() as u8;
+    let verifier = password.iter().rev().chain([&length]).fold(0u16, |verifier, &byte| {
+        (((verifier >> 14) & 0x01) | ((verifier << 1) & 0x7fff)) ^ byte as u16
+    });
+    verifier ^ 0xce4b
+}
+
+/// Builds the obfuscation array from the password padded to 16 bytes and the obfuscation key
+/// (MS-OFFCRYPTO 2.3.7.2)
+fn xor_array(password: &[u8], key: u16) -> [u8; 16] {
+    let key = key.to_le_bytes();
+    let mut array = [0u8; 16];
+    for (index, byte) in array.iter_mut().enumerate() {
+        let source = password.get(index).copied().unwrap_or_else(|| XOR_PAD[index - password.len()]);
+        *byte = (source ^ key[index % 2]).rotate_right(1);
+    }
+    array
+}
+
+/// Key derivation of the RC4 encryption of BIFF8 workbook streams
+pub(crate) enum Rc4Key {
+    /// RC4 encryption (MS-OFFCRYPTO 2.3.6.2): MD5 of the truncated password hash
+    Md5([u8; 5]),
+    /// RC4 CryptoAPI encryption (MS-OFFCRYPTO 2.3.5.2): SHA-1 of the salted password
+    CryptoApi { hash: Vec<u8>, key_size: usize },
+}
+
+impl Rc4Key {
+    /// Parses the salt and password verifier of the RC4 encryption, then verifies the password
+    fn parse_md5(file_pass: &[u8], password: &str) -> Result<Rc4Key, CryptoError> {
+        if file_pass.len() < 54 {
+            Err(CryptoError::EncryptionInfoError)?
+        }
+        let salt = &file_pass[6..22];
+        let hash = HashAlgorithm::Md5.digest(&[&password_bytes(password)]);
+        let intermediate = [&hash[..5], salt].concat().repeat(16);
+        let hash = HashAlgorithm::Md5.digest(&[&intermediate]);
+        let key = Rc4Key::Md5(hash[..5].try_into().expect("[u8; 5]"));
+        let mut verifier = file_pass[22..54].to_vec();
+        apply_rc4(&key.block_key(0), &mut verifier);
+        if HashAlgorithm::Md5.digest(&[&verifier[..16]])[..] != verifier[16..] {
+            Err(CryptoError::PasswordError)?
+        }
+        Ok(key)
+    }
+
+    /// Parses the encryption header and password verifier of the RC4 CryptoAPI encryption,
+    /// then verifies the password
+    fn parse_crypto_api(file_pass: &[u8], password: &str) -> Result<Rc4Key, CryptoError> {
+        let field = |offset: usize| file_pass.get(offset..offset + 4).map(to_usize).ok_or(CryptoError::EncryptionInfoError);
+        let header_size = field(10)?;
+        // Key sizes the block key derivation supports, from 40 to 128 bits in steps of 8 bits
+        let key_size = match field(14 + 16)? {
+            0 => 40,
+            bits if (40..=128).contains(&bits) && bits % 8 == 0 => bits,
+            _ => Err(CryptoError::EncryptionInfoError)?,
+        };
+        let verifier = 14 + header_size;
+        let salt = file_pass.get(verifier + 4..verifier + 20).ok_or(CryptoError::EncryptionInfoError)?;
+        let mut encrypted = file_pass.get(verifier + 20..verifier + 60).ok_or(CryptoError::EncryptionInfoError)?.to_vec();
+        encrypted.drain(16..20); // Skip the verifier hash size between the verifier and its hash
+        let key = Rc4Key::CryptoApi {
+            hash: HashAlgorithm::Sha1.digest(&[salt, &password_bytes(password)]),
+            key_size,
+        };
+        apply_rc4(&key.block_key(0), &mut encrypted);
+        if HashAlgorithm::Sha1.digest(&[&encrypted[..16]])[..] != encrypted[16..] {
+            Err(CryptoError::PasswordError)?
+        }
+        Ok(key)
+    }
+
+    /// Derives the RC4 key of a block of the stream
+    fn block_key(&self, block: usize) -> Vec<u8> {
+        let block = (block as u32).to_le_bytes();
+        match self {
+            Rc4Key::Md5(hash) => HashAlgorithm::Md5.digest(&[&hash[..], &block]),
+            Rc4Key::CryptoApi { hash, key_size } => {
+                let hash = HashAlgorithm::Sha1.digest(&[hash, &block]);
+                // 40-bit keys are padded with zeros to 128 bits
+                match key_size / 8 {
+                    5 => [&hash[..5], &[0; 11]].concat(),
+                    size => hash[..size].to_vec(),
+                }
+            }
+        }
+    }
+}
+
+/// XORs `data` with the key stream of the RC4 `key`, whose size is 5 to 16 bytes
+fn apply_rc4(key: &[u8], data: &mut [u8]) {
+    fn apply<N>(key: &[u8], data: &mut [u8]) where Rc4<N>: KeyInit + StreamCipher {
+        Rc4::<N>::new_from_slice(key).expect("RC4 key size").apply_keystream(data);
+    }
+    match key.len() {
+        5 => apply::<U5>(key, data),
+        6 => apply::<U6>(key, data),
+        7 => apply::<U7>(key, data),
+        8 => apply::<U8>(key, data),
+        9 => apply::<U9>(key, data),
+        10 => apply::<U10>(key, data),
+        11 => apply::<U11>(key, data),
+        12 => apply::<U12>(key, data),
+        13 => apply::<U13>(key, data),
+        14 => apply::<U14>(key, data),
+        15 => apply::<U15>(key, data),
+        _ => apply::<U16>(key, data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::cipher::BlockEncrypt;
+    use aes::cipher::BlockEncryptMut;
+    use aes::cipher::KeyIvInit;
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn encrypt_cbc(key: &[u8], iv: &[u8], data: &mut [u8]) {
+        let size = data.len();
+        cbc::Encryptor::<Aes256>::new_from_slices(key, &iv[..16]).unwrap()
+            .encrypt_padded_mut::<NoPadding>(data, size).unwrap();
+    }
+
+    #[test]
+    fn aes_decrypt_known_answer() {
+        // FIPS-197 appendix C.3
+        let key = (0u8..32).collect::<Vec<_>>();
+        let mut block = [0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49, 0x60, 0x89];
+        AesKey::new(&key).unwrap().decrypt_ecb(&mut block);
+        assert_eq!(block, [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff]);
+    }
+
+    #[test]
+    fn standard_round_trip() {
+        let salt = [1u8; 16];
+        let header = [
+            &0x24u32.to_le_bytes()[..], &0u32.to_le_bytes(), &(ALG_ID_AES_128 as u32).to_le_bytes(),
+            &0x8004u32.to_le_bytes(), &128u32.to_le_bytes(), &0x18u32.to_le_bytes(), &[0; 8], &[0; 4],
+        ].concat();
+        let verifier = [2u8; 16];
+        let mut encrypted_verifier = verifier.to_vec();
+        let mut encrypted_verifier_hash = fit(&HashAlgorithm::Sha1.digest(&[&verifier]), 32);
+        let plain = b"PK\x03\x04 decrypted package".to_vec();
+        let mut encrypted = fit(&plain, plain.len().div_ceil(16) * 16);
+
+        let mut info = [&[4, 0, 2, 0][..], &0x24u32.to_le_bytes(), &(header.len() as u32).to_le_bytes(), &header].concat();
+        let key = standard_key(&salt, "secret", 16);
+        let cipher = Aes128::new(GenericArray::from_slice(&key));
+        for data in [&mut encrypted_verifier, &mut encrypted_verifier_hash, &mut encrypted] {
+            data.chunks_exact_mut(16).for_each(|block| cipher.encrypt_block(GenericArray::from_mut_slice(block)));
+        }
+        info.extend([&16u32.to_le_bytes()[..], &salt, &encrypted_verifier, &20u32.to_le_bytes(), &encrypted_verifier_hash].concat());
+        let package = [&(plain.len() as u64).to_le_bytes()[..], &encrypted].concat();
+
+        let encryption = StandardEncryption::parse(&info).unwrap();
+        assert!(matches!(encryption.key("wrong"), Err(CryptoError::PasswordError)));
+        assert_eq!(encryption.key("secret").unwrap().decrypt(&package).unwrap(), plain);
+    }
+
+    #[test]
+    fn agile_round_trip() {
+        let password = "secret";
+        let hash = HashAlgorithm::Sha512;
+        let password_salt = [7u8; 16];
+        let data_salt = [9u8; 16];
+        let package_key = [3u8; 32];
+        let spun = hash.spin(&password_salt, password, 1000);
+        let encrypt = |block: &[u8], plain: &[u8]| {
+            let mut bytes = fit(plain, plain.len().div_ceil(16) * 16);
+            encrypt_cbc(&fit(&hash.digest(&[&spun, block]), 32), &password_salt, &mut bytes);
+            BASE64.encode(bytes)
+        };
+        let verifier = [5u8; 16];
+        let descriptor = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<encryption xmlns="http://schemas.microsoft.com/office/2006/encryption" xmlns:p="http://schemas.microsoft.com/office/2006/keyEncryptor/password">"#,
+                r#"<keyData saltSize="16" blockSize="16" keyBits="256" hashSize="64" cipherAlgorithm="AES" cipherChaining="ChainingModeCBC" hashAlgorithm="SHA512" saltValue="{}"/>"#,
+                r#"<keyEncryptors><keyEncryptor uri="http://schemas.microsoft.com/office/2006/keyEncryptor/password">"#,
+                r#"<p:encryptedKey spinCount="1000" saltSize="16" blockSize="16" keyBits="256" hashSize="64" cipherAlgorithm="AES" cipherChaining="ChainingModeCBC" hashAlgorithm="SHA512" saltValue="{}" encryptedVerifierHashInput="{}" encryptedVerifierHashValue="{}" encryptedKeyValue="{}"/>"#,
+                r#"</keyEncryptor></keyEncryptors></encryption>"#,
+            ),
+            BASE64.encode(data_salt),
+            BASE64.encode(password_salt),
+            encrypt(&VERIFIER_HASH_INPUT_BLOCK, &verifier),
+            encrypt(&VERIFIER_HASH_VALUE_BLOCK, &hash.digest(&[&verifier])),
+            encrypt(&ENCRYPTED_KEY_VALUE_BLOCK, &package_key),
+        );
+        let info = [&[4, 0, 4, 0, 0x40, 0, 0, 0][..], descriptor.as_bytes()].concat();
+
+        let plain = (0..5000).map(|index| (index % 251) as u8).collect::<Vec<_>>();
+        let mut encrypted = fit(&plain, plain.len().div_ceil(16) * 16);
+        for (index, segment) in encrypted.chunks_mut(AGILE_SEGMENT_SIZE).enumerate() {
+            let iv = hash.digest(&[&data_salt, &(index as u32).to_le_bytes()]);
+            encrypt_cbc(&package_key, &iv, segment);
+        }
+        let package = [&(plain.len() as u64).to_le_bytes()[..], &encrypted].concat();
+
+        let encryption = AgileEncryption::parse(&info).unwrap();
+        assert!(matches!(encryption.key("wrong"), Err(CryptoError::PasswordError)));
+        let decrypted = encryption.key(password).unwrap().decrypt(&package).unwrap();
+        assert_eq!(decrypted, plain);
+    }
+
+    #[test]
+    fn md5_and_rc4_known_answers() {
+        // RFC 1321 appendix A.5 and the key stream of the 40-bit RC4 key of RFC 6229
+        assert_eq!(HashAlgorithm::Md5.digest(&[b""]), [0xd4, 0x1d, 0x8c, 0xd9, 0x8f, 0x00, 0xb2, 0x04, 0xe9, 0x80, 0x09, 0x98, 0xec, 0xf8, 0x42, 0x7e]);
+        assert_eq!(HashAlgorithm::Md5.digest(&[b"ab", b"c"]), [0x90, 0x01, 0x50, 0x98, 0x3c, 0xd2, 0x4f, 0xb0, 0xd6, 0x96, 0x3f, 0x7d, 0x28, 0xe1, 0x7f, 0x72]);
+        let mut data = [0u8; 8];
+        apply_rc4(&[1, 2, 3, 4, 5], &mut data);
+        assert_eq!(data, [0xb2, 0x39, 0x63, 0x05, 0xf0, 0x3d, 0xc0, 0x27]);
+    }
+
+    #[test]
+    fn rc4_record_round_trip() {
+        let salt = [4u8; 16];
+        let hash = HashAlgorithm::Md5.digest(&[&password_bytes("secret")]);
+        let key = Rc4Key::Md5(HashAlgorithm::Md5.digest(&[&[&hash[..5], &salt].concat().repeat(16)])[..5].try_into().unwrap());
+        let verifier = [6u8; 16];
+        let mut encrypted_verifier = [&verifier[..], &HashAlgorithm::Md5.digest(&[&verifier])].concat();
+        apply_rc4(&key.block_key(0), &mut encrypted_verifier);
+        let file_pass = [&[1, 0, 1, 0, 1, 0][..], &salt, &encrypted_verifier].concat();
+
+        let plain = (0..3000).map(|index| (index % 253) as u8).collect::<Vec<_>>();
+        let mut encrypted = plain.clone();
+        for (block, chunk) in encrypted.chunks_mut(RC4_BLOCK_SIZE).enumerate() {
+            apply_rc4(&key.block_key(block), chunk);
+        }
+
+        assert!(matches!(RecordCipher::new(&file_pass, "wrong"), Err(CryptoError::PasswordError)));
+        let mut cipher = RecordCipher::new(&file_pass, "secret").unwrap();
+        // Records straddling block boundaries, decrypted out of order
+        let (head, tail) = encrypted.split_at_mut(1500);
+        cipher.decrypt(&mut tail[..], 1500, 1500);
+        cipher.decrypt(&mut head[..], 0, 1500);
+        assert_eq!(encrypted, plain);
+    }
+
+    #[test]
+    fn rc4_crypto_api_key_size() {
+        // FILE_PASS of the RC4 CryptoAPI encryption with a 32-byte header declaring a 1-bit key
+        let mut file_pass = [&[1, 0, 2, 0, 2, 0, 0, 0, 0, 0][..], &32u32.to_le_bytes(), &[0; 32 + 60]].concat();
+        file_pass[30..34].copy_from_slice(&1u32.to_le_bytes());
+        assert!(matches!(RecordCipher::new(&file_pass, "secret"), Err(CryptoError::EncryptionInfoError)));
+        file_pass[30..34].copy_from_slice(&136u32.to_le_bytes());
+        assert!(matches!(RecordCipher::new(&file_pass, "secret"), Err(CryptoError::EncryptionInfoError)));
+        file_pass[30..34].copy_from_slice(&128u32.to_le_bytes());
+        assert!(matches!(RecordCipher::new(&file_pass, "secret"), Err(CryptoError::PasswordError)));
+    }
+
+    #[test]
+    fn xor_obfuscation_password() {
+        let password = xor_password_bytes("abc");
+        let file_pass = [&[0, 0, 0x34, 0x12][..], &xor_verifier(&password).to_le_bytes()].concat();
+        assert!(matches!(RecordCipher::new(&file_pass, "abd"), Err(CryptoError::PasswordError)));
+        let Ok(RecordCipher::Xor(array)) = RecordCipher::new(&file_pass, "abc") else { panic!("XOR cipher expected") };
+        assert_eq!(array[0], (b'a' ^ 0x34).rotate_right(1));
+        assert_eq!(array[3], (XOR_PAD[0] ^ 0x12).rotate_right(1));
+    }
+
+    #[test]
+    fn xor_obfuscation_record() {
+        let password = xor_password_bytes("abc");
+        let file_pass = [&[0, 0, 0x34, 0x12][..], &xor_verifier(&password).to_le_bytes()].concat();
+        let array = xor_array(&password, 0x1234);
+        // LABELSST record data (row 1, column 2, format 15, string 7) at offset 0x2a0 of the stream
+        let plain = [0x01u8, 0x00, 0x02, 0x00, 0x0f, 0x00, 0x07, 0x00, 0x00, 0x00];
+        let position = 0x2a0;
+        // EncryptData_Method1: each byte is rotated left by 5 bits, then XORed with the array
+        let mut data = plain.iter().enumerate()
+            .map(|(index, byte)| byte.rotate_left(5) ^ array[(position + index + plain.len()) % 16])
+            .collect::<Vec<_>>();
+        assert_ne!(data, plain);
+        RecordCipher::new(&file_pass, "abc").unwrap().decrypt(&mut data, position, plain.len());
+        assert_eq!(data, plain);
+    }
+
+    #[test]
+    fn odf_round_trip() {
+        let plain = "<office:document-content/>".repeat(100).into_bytes();
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&plain).unwrap();
+        let deflated = encoder.finish().unwrap();
+        let salt = [4u8; 16];
+        let iv = [6u8; 16];
+        for (algorithm, start_key, key_size) in [
+            ("http://www.w3.org/2001/04/xmlenc#aes256-cbc", "http://www.w3.org/2000/09/xmldsig#sha256", 32),
+            ("Blowfish CFB", "SHA1", 16),
+        ] {
+            let start = match key_size {
+                32 => HashAlgorithm::Sha256.digest(&[b"secret"]),
+                _ => HashAlgorithm::Sha1.digest(&[b"secret"]),
+            };
+            let mut key = vec![0u8; key_size];
+            pbkdf2::pbkdf2_hmac::<Sha1>(&start, &salt, 1024, &mut key);
+            let mut encrypted = deflated.clone();
+            if key_size == 32 {
+                let padding = 16 - encrypted.len() % 16;
+                encrypted.extend(std::iter::repeat_n(padding as u8, padding));
+                encrypt_cbc(&key, &iv, &mut encrypted);
+            } else {
+                cfb_mode::Encryptor::<Blowfish>::new_from_slices(&key, &iv[..8]).unwrap().encrypt(&mut encrypted);
+            }
+            let manifest = format!(
+                concat!(
+                    r#"<manifest:encryption-data manifest:checksum-type="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0#sha256-1k" manifest:checksum="{}">"#,
+                    r#"<manifest:algorithm manifest:algorithm-name="{}" manifest:initialisation-vector="{}"/>"#,
+                    r#"<manifest:start-key-generation manifest:start-key-generation-name="{}" manifest:key-size="{}"/>"#,
+                    r#"<manifest:key-derivation manifest:key-derivation-name="PBKDF2" manifest:key-size="{}" manifest:iteration-count="1024" manifest:salt="{}"/>"#,
+                    r#"</manifest:encryption-data>"#,
+                ),
+                BASE64.encode(HashAlgorithm::Sha256.digest(&[&deflated[..deflated.len().min(1024)]])),
+                algorithm,
+                BASE64.encode(&iv[..key_size / 2]),
+                start_key,
+                key_size,
+                key_size,
+                BASE64.encode(salt),
+            );
+
+            let mut encryption = OdfEncryption::default();
+            let mut reader = XmlReader::new(manifest.as_bytes());
+            while let Some(event) = reader.next().unwrap() {
+                match event {
+                    Event::Eof => break,
+                    Event::Start(event) => encryption.parse(&event).unwrap(),
+                    _ => (),
+                }
+            }
+            assert!(matches!(
+                encryption.decrypt(&encrypted, "wrong").unwrap_err(),
+                RustySheetError::CryptoHelperError(CryptoError::PasswordError),
+            ));
+            assert_eq!(encryption.decrypt(&encrypted, "secret").unwrap(), plain);
+
+            // Iteration counts and key sizes out of bounds are rejected before deriving the key
+            for (iteration_count, key_size) in [(0, key_size), (u32::MAX, key_size), (1024, 1 << 40)] {
+                let encryption = OdfEncryption { iteration_count, key_size: Some(key_size), ..encryption.clone() };
+                assert!(matches!(
+                    encryption.decrypt(&encrypted, "secret").unwrap_err(),
+                    RustySheetError::CryptoHelperError(CryptoError::EncryptionInfoError),
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Cumulative parse counters of the process, kept since the extension was loaded
+//! and listed by rusty_sheet_metrics for monitoring agents
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+/// Number of spreadsheet files opened successfully
+static FILES_OPENED: AtomicU64 = AtomicU64::new(0);
+/// Number of bytes decompressed from the parts of ZIP packages (XLSX, XLSB, ODS)
+static BYTES_DECOMPRESSED: AtomicU64 = AtomicU64::new(0);
+/// Number of cells parsed from worksheets
+static CELLS_PARSED: AtomicU64 = AtomicU64::new(0);
+/// Number of errors raised while opening files or parsing worksheets, by category
+static ERRORS: [AtomicU64; ErrorCategory::ALL.len()] = [const { AtomicU64::new(0) }; ErrorCategory::ALL.len()];
+
+/// Category of an error counted by the metrics, declared in the order of `ALL`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorCategory {
+    /// File system and remote read errors
+    Io,
+    /// Corrupted or unsupported ZIP packages
+    Zip,
+    /// Malformed XML parts
+    Xml,
+    /// Structural errors of the workbook formats (BIFF, CFB, OpenDocument)
+    Format,
+    /// Encrypted workbooks that could not be decrypted
+    Encryption,
+    /// Cell values that could not be converted to their column type
+    Conversion,
+    /// Invalid parameters, ranges and patterns
+    Parameter,
+    /// Errors raised by DuckDB
+    Database,
+    /// Any other error
+    Other,
+}
+
+impl ErrorCategory {
+    /// Every category, in the order the metrics list them
+    pub(crate) const ALL: [ErrorCategory; 9] = [
+        ErrorCategory::Io,
+        ErrorCategory::Zip,
+        ErrorCategory::Xml,
+        ErrorCategory::Format,
+        ErrorCategory::Encryption,
+        ErrorCategory::Conversion,
+        ErrorCategory::Parameter,
+        ErrorCategory::Database,
+        ErrorCategory::Other,
+    ];
+
+    /// Returns the name of the category as listed by the metrics
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            ErrorCategory::Io => "io",
+            ErrorCategory::Zip => "zip",
+            ErrorCategory::Xml => "xml",
+            ErrorCategory::Format => "format",
+            ErrorCategory::Encryption => "encryption",
+            ErrorCategory::Conversion => "conversion",
+            ErrorCategory::Parameter => "parameter",
+            ErrorCategory::Database => "database",
+            ErrorCategory::Other => "other",
+        }
+    }
+}
+
+/// Counts a spreadsheet file opened successfully.
+pub(crate) fn record_file_opened() {
+    FILES_OPENED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts bytes decompressed from a ZIP package.
+pub(crate) fn record_bytes_decompressed(bytes: usize) {
+    BYTES_DECOMPRESSED.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+/// Counts the cells parsed from a worksheet.
+pub(crate) fn record_cells_parsed(cells: usize) {
+    CELLS_PARSED.fetch_add(cells as u64, Ordering::Relaxed);
+}
+
+/// Counts an error of the given category.
+pub(crate) fn record_error(category: ErrorCategory) {
+    ERRORS[category as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns the current value of every counter as (metric, value) pairs.
+/// Every error category is listed, zero or not, so that scrapers see stable series.
+pub(crate) fn metrics() -> Vec<(String, u64)> {
+    let mut metrics = vec![
+        ("files_opened".to_owned(), FILES_OPENED.load(Ordering::Relaxed)),
+        ("bytes_decompressed".to_owned(), BYTES_DECOMPRESSED.load(Ordering::Relaxed)),
+        ("cells_parsed".to_owned(), CELLS_PARSED.load(Ordering::Relaxed)),
+    ];
+    metrics.extend(ErrorCategory::ALL.iter().zip(ERRORS.iter())
+        .map(|(category, count)| (format!("errors_{}", category.name()), count.load(Ordering::Relaxed))));
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::RustySheetError;
+    use crate::spreadsheet::SpreadsheetError;
+
+    fn metric(name: &str) -> u64 {
+        metrics().into_iter().find(|(metric, _)| metric == name).map(|(_, value)| value).unwrap()
+    }
+
+    #[test]
+    fn metrics_list_every_error_category() {
+        let names = metrics().into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names.len(), 3 + ErrorCategory::ALL.len());
+        assert!(names.contains(&"errors_io".to_owned()));
+        assert!(names.contains(&"errors_other".to_owned()));
+    }
+
+    #[test]
+    fn wrapped_errors_count_under_the_category_of_the_source() {
+        let before = metric("errors_format");
+        let error: RustySheetError = SpreadsheetError::FileError("a.xlsx".to_owned()).into();
+        let error = RustySheetError::WithContextError { context: "a.xlsx".to_owned(), source: Box::new(error) };
+        record_error(error.category());
+        assert!(metric("errors_format") > before);
+    }
+}
@@ -1,6 +1,8 @@
 pub(crate) mod biff12;
 pub(crate) mod biff8;
 pub(crate) mod cfb;
+pub(crate) mod crypto;
+pub(crate) mod metrics;
 pub(crate) mod reader;
 pub(crate) mod string;
 pub(crate) mod xml;
@@ -1,27 +1,241 @@
+use crate::database::host::connect_host_database;
 use crate::error::RustySheetError;
+use std::env;
+use std::fs;
 use std::fs::File;
+use std::fs::Metadata;
+use std::io;
 use std::io::BufReader;
 use std::io::Cursor;
 use std::io::Read;
 use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::Weak;
+use std::time::SystemTime;
 use thiserror::Error;
 use url::Url;
 
+/// Largest local file a snapshot copies into memory; larger files are copied to a temporary file
+const SNAPSHOT_MEMORY_LIMIT: u64 = 256 * 1024 * 1024;
+/// Number of copies attempted before giving up on a file that keeps changing
+const SNAPSHOT_ATTEMPTS: usize = 3;
+
+/// Local files kept open at once by default, well below the usual limits on open file
+/// descriptors (256 on macOS, 1024 on Linux) so that DuckDB keeps room for its own files
+const DEFAULT_MAX_OPEN_FILES: usize = 128;
+/// Environment variable overriding `DEFAULT_MAX_OPEN_FILES`
+const MAX_OPEN_FILES_VARIABLE: &str = "RUSTY_SHEET_MAX_OPEN_FILES";
+
+/// Sequence number keeping the temporary snapshot files of one process apart
+static SNAPSHOT_SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Open local files of the process, shared by every scan
+static FILE_POOL: LazyLock<FilePool> = LazyLock::new(|| {
+    let budget = env::var(MAX_OPEN_FILES_VARIABLE).ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|&budget| budget > 0)
+        .unwrap_or(DEFAULT_MAX_OPEN_FILES);
+    FilePool::new(budget)
+});
+
 #[derive(Error, Debug)]
 pub(crate) enum UnifiedReaderError {
     #[error("No data from remote file: '{0}'")]
     RemoteFileNoDataError(String),
+
+    #[error("File '{0}' kept changing while taking a snapshot, retry once it is synced")]
+    SnapshotChangedError(String),
+
+    #[error("File '{0}' changed while it was closed to stay within the open file limit, retry once it is synced or read it with snapshot=true")]
+    ReopenChangedError(String),
 }
 
 /// A unified reader that can handle both local files and remote URLs
 pub(crate) enum UnifiedReader {
     /// Local file reader
-    Local(BufReader<File>),
-    /// Remote URL reader (in-memory buffer)
+    Local(PooledReader),
+    /// Remote URL reader, or snapshot of a local file (in-memory buffer)
     Remote(Cursor<Vec<u8>>),
+    /// Snapshot of a local file too large to keep in memory
+    Snapshot(SnapshotFile),
+}
+
+/// Temporary copy of a local file, removed once the reader is dropped
+pub(crate) struct SnapshotFile {
+    /// Reader over the copy, closed before the copy is removed
+    reader: Option<PooledReader>,
+    /// Path of the copy
+    path: PathBuf,
+}
+
+impl SnapshotFile {
+    fn reader(&mut self) -> &mut PooledReader {
+        self.reader.as_mut().expect("Snapshot reader")
+    }
+}
+
+/// Open local files, closing the least recently used ones once more than `budget`
+/// are open, so that scans over many files stay within the limit on open file
+/// descriptors. A closed file is reopened at the same position on its next read.
+pub(crate) struct FilePool {
+    /// Largest number of files kept open
+    budget: usize,
+    /// Files currently open
+    files: Mutex<Vec<Weak<Mutex<PooledFile>>>>,
+    /// Clock ordering the uses of the files
+    clock: AtomicU64,
+}
+
+/// Local file of a [`FilePool`], whose descriptor the pool may close between reads
+struct PooledFile {
+    path: PathBuf,
+    /// Reader over the file, None while closed
+    reader: Option<BufReader<File>>,
+    /// Position to restore once reopened
+    position: u64,
+    /// Size and modification time at open, to detect a file rewritten while closed
+    fingerprint: (u64, Option<SystemTime>),
+    /// Tick of the pool clock at the last use
+    last_used: u64,
+}
+
+impl PooledFile {
+    /// Closes the descriptor, keeping the position. Returns false when the position
+    /// is unknown and the file stays open.
+    fn close(&mut self) -> bool {
+        let Some(mut reader) = self.reader.take() else { return true };
+        match reader.stream_position() {
+            Ok(position) => {
+                self.position = position;
+                true
+            }
+            Err(_) => {
+                self.reader = Some(reader);
+                false
+            }
+        }
+    }
+}
+
+/// Returns the size and modification time identifying a version of a file
+fn fingerprint(metadata: &Metadata) -> (u64, Option<SystemTime>) {
+    (metadata.len(), metadata.modified().ok())
+}
+
+impl FilePool {
+    pub(crate) fn new(budget: usize) -> Self {
+        FilePool {
+            budget,
+            files: Mutex::new(Vec::new()),
+            clock: AtomicU64::new(0),
+        }
+    }
+
+    /// Opens a local file, closing the least recently used files if the budget is spent
+    pub(crate) fn open(&'static self, path: &Path) -> io::Result<PooledReader> {
+        let file = PooledFile {
+            path: path.to_owned(),
+            reader: None,
+            position: 0,
+            fingerprint: fingerprint(&fs::metadata(path)?),
+            last_used: 0,
+        };
+        let mut reader = PooledReader { file: Arc::new(Mutex::new(file)), pool: self };
+        reader.with_reader(|_| Ok(()))?;
+        Ok(reader)
+    }
+
+    /// Reopens a closed file at its last position
+    ///
+    /// Files in use by other threads are not waited for: they are skipped when making
+    /// room, so the budget is exceeded while all the open files are busy.
+    fn reopen(&self, shared: &Arc<Mutex<PooledFile>>, file: &mut PooledFile) -> io::Result<()> {
+        let mut files = self.files.lock().expect("File pool lock");
+        files.retain(|file| file.strong_count() > 0);
+        if files.len() >= self.budget {
+            let open_files = files.iter().filter_map(Weak::upgrade).collect::<Vec<_>>();
+            let mut idle_files = open_files.iter()
+                .filter_map(|file| Some((file, file.try_lock().ok()?)))
+                .collect::<Vec<_>>();
+            idle_files.sort_by_key(|(_, file)| file.last_used);
+            let closed = idle_files.into_iter()
+                .take(files.len() + 1 - self.budget)
+                .filter_map(|(shared, mut file)| file.close().then_some(Arc::as_ptr(shared)))
+                .collect::<Vec<_>>();
+            files.retain(|file| !closed.contains(&file.as_ptr()));
+        }
+
+        let mut reader = BufReader::new(File::open(&file.path)?);
+        if fingerprint(&reader.get_ref().metadata()?) != file.fingerprint {
+            Err(io::Error::other(UnifiedReaderError::ReopenChangedError(file.path.display().to_string())))?
+        }
+        reader.seek(SeekFrom::Start(file.position))?;
+        file.reader = Some(reader);
+        files.push(Arc::downgrade(shared));
+        Ok(())
+    }
+}
+
+/// Reader over a local file of a [`FilePool`]
+pub(crate) struct PooledReader {
+    file: Arc<Mutex<PooledFile>>,
+    pool: &'static FilePool,
+}
+
+impl PooledReader {
+    /// Runs `action` on the reader of the file, reopening it first if the pool closed it
+    fn with_reader<T>(&mut self, action: impl FnOnce(&mut BufReader<File>) -> io::Result<T>) -> io::Result<T> {
+        let mut file = self.file.lock().expect("Pooled file lock");
+        if file.reader.is_none() {
+            self.pool.reopen(&self.file, &mut file)?;
+        }
+        file.last_used = self.pool.clock.fetch_add(1, Ordering::Relaxed);
+        action(file.reader.as_mut().expect("Reopened reader"))
+    }
+}
+
+impl Read for PooledReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.with_reader(|reader| reader.read(buf))
+    }
+}
+
+impl Seek for PooledReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.with_reader(|reader| reader.seek(pos))
+    }
+}
+
+impl Drop for SnapshotFile {
+    fn drop(&mut self) {
+        self.reader.take();
+        let _ = fs::remove_file(&self.path);
+    }
 }
 
 impl UnifiedReader {
+    /// Opens a file, first taking a snapshot of local files when `snapshot` is set
+    ///
+    /// # Arguments
+    /// * `file_name` - Path or URL to the file
+    /// * `snapshot` - Whether to read a consistent copy of a local file instead of the file itself
+    pub(crate) fn open(file_name: &str, snapshot: bool) -> Result<UnifiedReader, RustySheetError> {
+        if snapshot && !Self::is_remote_url(file_name) {
+            Self::snapshot(file_name)
+        } else {
+            Self::new(file_name)
+        }
+    }
+
     /// Opens a file from either a local path or remote URL
     /// For remote URLs, uses DuckDB's read_blob with proper credential handling
     ///
@@ -37,9 +251,8 @@ impl UnifiedReader {
             // DuckDB handles credentials and protocols automatically
             Self::read_blob_with_duckdb(file_name)
         } else {
-            // Local file
-            let file = File::open(file_name)?;
-            Ok(UnifiedReader::Local(BufReader::new(file)))
+            // Local file, closed and reopened by the pool while many files are open
+            Ok(UnifiedReader::Local(FILE_POOL.open(Path::new(file_name))?))
         }
     }
 
@@ -52,15 +265,55 @@ impl UnifiedReader {
         }
     }
 
+    /// Copies a local file that may be rewritten meanwhile (e.g. by a sync client)
+    ///
+    /// Files up to `SNAPSHOT_MEMORY_LIMIT` are read into memory, larger ones are
+    /// copied to a temporary file. The copy is kept only if the size and the
+    /// modification time of the file are the same before and after copying.
+    fn snapshot(file_name: &str) -> Result<UnifiedReader, RustySheetError> {
+        for _ in 0..SNAPSHOT_ATTEMPTS {
+            let mut file = File::open(file_name)?;
+            let before = file.metadata()?;
+            let reader = if before.len() <= SNAPSHOT_MEMORY_LIMIT {
+                let mut buffer = Vec::with_capacity(before.len() as usize);
+                file.read_to_end(&mut buffer)?;
+                UnifiedReader::Remote(Cursor::new(buffer))
+            } else {
+                let sequence = SNAPSHOT_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+                let path = std::env::temp_dir().join(format!("rusty_sheet_snapshot_{}_{}", process::id(), sequence));
+                let mut snapshot = SnapshotFile { reader: None, path };
+                io::copy(&mut file, &mut File::create(&snapshot.path)?)?;
+                snapshot.reader = Some(FILE_POOL.open(&snapshot.path)?);
+                UnifiedReader::Snapshot(snapshot)
+            };
+            let after = fs::metadata(file_name)?;
+            if before.len() == after.len() && before.modified().ok() == after.modified().ok() {
+                return Ok(reader);
+            }
+        }
+        Err(UnifiedReaderError::SnapshotChangedError(file_name.to_owned()))?
+    }
+
+    /// Lists the remote files matching a wildcard URL (e.g. 's3://bucket/*.xlsx') using
+    /// DuckDB's glob, so that the file systems of loaded extensions such as httpfs apply
+    pub(crate) fn glob_remote(pattern: &str) -> Result<Vec<String>, RustySheetError> {
+        let connection = connect_host_database()?;
+        let result = connection.prepare("SELECT file FROM glob(?) ORDER BY file")
+            .and_then(|mut statement| statement.query_map([pattern], |row| row.get(0))?.collect());
+        Ok(result?)
+    }
+
     /// Reads a remote file using DuckDB's read_blob functionality
     /// This handles all protocols (http, https, s3, gs, hf, etc.) with proper credential management
     fn read_blob_with_duckdb(file_name: &str) -> Result<UnifiedReader, RustySheetError> {
-        // Create an in-memory DuckDB connection and read the blob directly
-        let connection = duckdb::Connection::open_in_memory()?;
+        // Take a connection to the database that loaded the extension, whose virtual file system
+        // knows the protocols of the loaded extensions (httpfs, azure, ...), with the
+        // proxy, timeout and CA settings of the database resolved for this read
+        let connection = connect_host_database()?;
         // Read the blob directly using query_row - DuckDB handles all URL types and credentials
         let result: Result<Vec<u8>, _> = connection.query_row("SELECT content FROM read_blob(?)", [file_name], |row| row.get(0));
-        // Close connection
-        connection.close().map_err(|(_, e)| e)?;
+        // Give the connection back to the pool
+        drop(connection);
 
         let bytes = result?;
         if bytes.is_empty() {
@@ -77,6 +330,7 @@ impl Read for UnifiedReader {
         match self {
             UnifiedReader::Local(reader) => reader.read(buf),
             UnifiedReader::Remote(reader) => reader.read(buf),
+            UnifiedReader::Snapshot(snapshot) => snapshot.reader().read(buf),
         }
     }
 }
@@ -86,13 +340,21 @@ impl Seek for UnifiedReader {
         match self {
             UnifiedReader::Local(reader) => reader.seek(pos),
             UnifiedReader::Remote(reader) => reader.seek(pos),
+            UnifiedReader::Snapshot(snapshot) => snapshot.reader().seek(pos),
         }
     }
 }
 
+impl From<Cursor<Vec<u8>>> for UnifiedReader {
+    fn from(reader: Cursor<Vec<u8>>) -> Self {
+        UnifiedReader::Remote(reader)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::helpers::zip::ZipHelper;
 
     #[test]
     fn test_is_remote_url() {
@@ -121,4 +383,97 @@ mod tests {
         let result = UnifiedReader::new("non_existent_file.xlsx");
         assert!(result.is_err(), "Should fail to open non-existent file");
     }
+
+    #[test]
+    fn test_snapshot_local_file() {
+        // A snapshot keeps the content read at open time
+        let path = std::env::temp_dir().join(format!("rusty_sheet_snapshot_test_{}", process::id()));
+        fs::write(&path, b"before").unwrap();
+        let mut reader = UnifiedReader::open(path.to_str().unwrap(), true).unwrap();
+        fs::write(&path, b"after, longer").unwrap();
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(reader, UnifiedReader::Remote(_)));
+        assert_eq!(content, "before");
+    }
+
+    /// Writes `count` zip archives holding a `data.txt` part each and returns their paths
+    fn write_archives(name: &str, count: usize) -> Vec<PathBuf> {
+        (0..count).map(|index| {
+            let path = std::env::temp_dir().join(format!("rusty_sheet_{}_{}_{}.zip", name, process::id(), index));
+            let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+            zip.start_file("data.txt", zip::write::SimpleFileOptions::default()).unwrap();
+            io::Write::write_all(&mut zip, format!("archive {index} ").repeat(1000).as_bytes()).unwrap();
+            zip.finish().unwrap();
+            path
+        }).collect()
+    }
+
+    fn open_files(pool: &FilePool) -> usize {
+        pool.files.lock().unwrap().iter().filter(|file| file.strong_count() > 0).count()
+    }
+
+    #[test]
+    fn pooled_archives_stay_within_budget() {
+        let pool = Box::leak(Box::new(FilePool::new(2)));
+        let paths = write_archives("pool_budget", 5);
+        let mut archives = paths.iter()
+            .map(|path| zip::ZipArchive::new(UnifiedReader::Local(pool.open(path).unwrap())).unwrap())
+            .collect::<Vec<_>>();
+        // Each pass reopens the archives closed by the previous ones
+        for _ in 0..2 {
+            for (index, archive) in archives.iter_mut().enumerate() {
+                let mut content = String::new();
+                archive.file("DATA.TXT").unwrap().unwrap().read_to_string(&mut content).unwrap();
+                assert_eq!(content, format!("archive {index} ").repeat(1000));
+                assert!(open_files(pool) <= 2);
+            }
+        }
+        drop(archives);
+        paths.iter().for_each(|path| fs::remove_file(path).unwrap());
+
+        assert_eq!(open_files(pool), 0);
+    }
+
+    #[test]
+    fn pooled_archives_read_from_threads() {
+        let pool = Box::leak(Box::new(FilePool::new(3)));
+        let paths = write_archives("pool_threads", 8);
+        let archives = paths.iter()
+            .map(|path| Mutex::new(zip::ZipArchive::new(UnifiedReader::Local(pool.open(path).unwrap())).unwrap()))
+            .collect::<Vec<_>>();
+        std::thread::scope(|scope| {
+            for (index, archive) in archives.iter().enumerate() {
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        let mut archive = archive.lock().unwrap();
+                        let mut part = archive.file("data.txt").unwrap().unwrap();
+                        let expected = format!("archive {index} ");
+                        let mut content = vec![0; expected.len()];
+                        part.read_exact(&mut content).unwrap();
+                        assert_eq!(content, expected.as_bytes());
+                    }
+                });
+            }
+        });
+        drop(archives);
+        paths.iter().for_each(|path| fs::remove_file(path).unwrap());
+
+        assert_eq!(open_files(pool), 0);
+    }
+
+    #[test]
+    fn pooled_file_rewritten_while_closed() {
+        let pool = Box::leak(Box::new(FilePool::new(1)));
+        let paths = write_archives("pool_rewritten", 2);
+        let mut first = pool.open(&paths[0]).unwrap();
+        let _second = pool.open(&paths[1]).unwrap();
+        fs::write(&paths[0], b"rewritten").unwrap();
+        let error = first.read(&mut [0; 4]).unwrap_err();
+        paths.iter().for_each(|path| fs::remove_file(path).unwrap());
+
+        assert!(error.to_string().contains("changed while it was closed"), "{error}");
+    }
 }
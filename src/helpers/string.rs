@@ -1,8 +1,125 @@
 //! Binary data conversion utilities for reading various numeric types from byte slices.
-//! Provides efficient little-endian conversion functions optimized for spreadsheet parsing.
+//! Provides efficient little-endian conversion functions optimized for spreadsheet parsing,
+//! the handling of text buffers reused across cells, the repair of mis-decoded text and the
+//! stripping of accents.
 
 use std::usize;
 
+/// Capacity kept by a text buffer reused across cells. Formats without a cell length
+/// limit (ODS) may hold megabytes in one cell; the buffer grown for it is shrunk back
+/// to this size instead of staying allocated for the rest of the scan.
+pub(crate) const RETAINED_BUFFER_CAPACITY: usize = 64 * 1024;
+
+/// Buffer reused across cells or XML events, cleared with `clear_buffer`
+pub(crate) trait ReusableBuffer {
+    fn clear(&mut self);
+    fn capacity(&self) -> usize;
+    fn shrink_to(&mut self, min_capacity: usize);
+}
+
+impl ReusableBuffer for String {
+    fn clear(&mut self) {
+        String::clear(self)
+    }
+
+    fn capacity(&self) -> usize {
+        String::capacity(self)
+    }
+
+    fn shrink_to(&mut self, min_capacity: usize) {
+        String::shrink_to(self, min_capacity)
+    }
+}
+
+impl ReusableBuffer for Vec<u8> {
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+
+    fn shrink_to(&mut self, min_capacity: usize) {
+        Vec::shrink_to(self, min_capacity)
+    }
+}
+
+/// Clears a buffer reused across cells, releasing the memory grown by an abnormally long value
+#[inline]
+pub(crate) fn clear_buffer<B: ReusableBuffer>(buffer: &mut B) {
+    buffer.clear();
+    if buffer.capacity() > RETAINED_BUFFER_CAPACITY {
+        buffer.shrink_to(RETAINED_BUFFER_CAPACITY);
+    }
+}
+
+/// Characters of the Windows-1252 code page for the bytes 0x80 to 0x9F, in byte order.
+/// The bytes it leaves undefined keep their Latin-1 control character.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// Repairs text whose UTF-8 bytes were decoded as Windows-1252 or Latin-1 (e.g. "CafÃ©" for "Café"),
+/// undoing as many layers of such decoding as the text went through.
+///
+/// The text is re-encoded as Windows-1252 and kept only if the bytes are valid UTF-8, which text
+/// that was decoded correctly almost never gives: its accented letters are not followed by the
+/// characters of UTF-8 continuation bytes. Returns true if the text was repaired.
+pub(crate) fn repair_encoding(text: &mut String) -> bool {
+    let mut repaired = false;
+    while !text.is_ascii() {
+        let bytes = text.chars()
+            .map(|char| match char as u32 {
+                code @ 0..=0xFF => Some(code as u8),
+                _ => WINDOWS_1252_HIGH.iter().position(|&high| high == char).map(|index| 0x80 + index as u8),
+            })
+            .collect::<Option<Vec<u8>>>();
+        match bytes.map(String::from_utf8) {
+            Some(Ok(decoded)) => {
+                *text = decoded;
+                repaired = true;
+            }
+            _ => break,
+        }
+    }
+    repaired
+}
+
+/// Returns the letters of a lowercase Latin letter without its accent (e.g. "e" for 'é',
+/// "ss" for 'ß'), or None when the character has no accent to strip.
+pub(crate) fn strip_accent(char: char) -> Option<&'static str> {
+    let stripped = match char {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĳ' => "ij",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' | 'ŉ' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+    Some(stripped)
+}
+
 /// Converts a byte slice into an iterator of 32-bit unsigned integers.
 /// Processes bytes in 4-byte chunks using little-endian byte order.
 pub(crate) fn to_u32_iter(bytes: &[u8]) -> impl ExactSizeIterator<Item = u32> + '_ {
@@ -58,3 +175,56 @@ pub(crate) fn to_u16(s: &[u8]) -> u16 {
 pub(crate) fn to_usize(s: &[u8]) -> usize {
     to_u32(s).try_into().expect("usize")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_buffer_releases_long_values() {
+        let mut buffer = String::with_capacity(16);
+        buffer.push_str("short");
+        clear_buffer(&mut buffer);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.capacity(), 16);
+
+        buffer.push_str(&"x".repeat(4 * RETAINED_BUFFER_CAPACITY));
+        clear_buffer(&mut buffer);
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() <= RETAINED_BUFFER_CAPACITY);
+
+        let mut bytes = vec![0u8; 4 * RETAINED_BUFFER_CAPACITY];
+        clear_buffer(&mut bytes);
+        assert!(bytes.is_empty());
+        assert!(bytes.capacity() <= RETAINED_BUFFER_CAPACITY);
+    }
+
+    #[test]
+    fn repair_mis_decoded_text() {
+        let mut text = "CafÃ© â€“ NaÃ¯ve".to_owned();
+        assert!(repair_encoding(&mut text));
+        assert_eq!(text, "Café – Naïve");
+
+        // Decoded twice over
+        let mut text = "MÃƒÂ¼ller".to_owned();
+        assert!(repair_encoding(&mut text));
+        assert_eq!(text, "Müller");
+
+        // Correctly decoded text is kept
+        for value in ["Café", "Müller & Söhne", "北京", "ASCII only", "Ã"] {
+            let mut text = value.to_owned();
+            assert!(!repair_encoding(&mut text));
+            assert_eq!(text, value);
+        }
+    }
+
+    #[test]
+    fn strip_accents_of_latin_letters() {
+        let stripped = "montant échéance ßøœ".chars()
+            .map(|char| strip_accent(char).map(str::to_owned).unwrap_or_else(|| char.to_string()))
+            .collect::<String>();
+        assert_eq!(stripped, "montant echeance ssooe");
+        assert_eq!(strip_accent('€'), None);
+        assert_eq!(strip_accent('北'), None);
+    }
+}
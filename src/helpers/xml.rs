@@ -2,6 +2,7 @@
 //! Provides XML reader wrapper and helper traits for attribute and text processing
 
 use crate::error::RustySheetError;
+use crate::helpers::string::clear_buffer;
 use quick_xml::escape::resolve_xml_entity;
 use quick_xml::events::attributes::Attribute;
 use quick_xml::events::BytesRef;
@@ -46,7 +47,7 @@ impl<R: BufRead> XmlReader<R> {
 
     /// Reads the next XML event from the reader
     pub(crate) fn next(&'_ mut self) -> Result<Option<Event<'_>>, RustySheetError> {
-        self.buffer.clear();
+        clear_buffer(&mut self.buffer);
         match self.reader.read_event_into(&mut self.buffer) {
             Ok(Event::Eof) => Ok(None),
             Ok(event) => Ok(Some(event)),
@@ -3,30 +3,41 @@
 
 use crate::error::RustySheetError;
 use crate::helpers::biff12::Biff12Reader;
+use crate::helpers::metrics;
 use crate::helpers::xml::XmlReader;
+use std::collections::HashMap;
 use std::io::BufReader;
+use std::io::Cursor;
 use std::io::Read;
 use std::io::Seek;
+use std::io::SeekFrom;
+use std::sync::Arc;
 use zip::read::ZipFile;
 use zip::result::ZipError;
 use zip::ZipArchive;
 
-/// Helper trait for ZIP archive operations with specialized reader creation
+/// Largest uncompressed part kept in memory for another pass
+const PART_CACHE_LIMIT: u64 = 16 * 1024 * 1024;
+/// Largest total size of the cached parts of one package
+const CACHE_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Signature of a local file header
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+/// Signature of an optional data descriptor following the file data
+const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x08074b50;
+/// Signature of a central directory file header
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x02014b50;
+/// Signature of the end of central directory record
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+/// Size of the fixed part of a local file header
+const LOCAL_HEADER_SIZE: usize = 30;
+/// General purpose flag: sizes and CRC follow the file data in a data descriptor
+const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+
+/// Helper trait for ZIP archive operations
 pub(crate) trait ZipHelper<RS: Read + Seek> {
     /// Gets a file from the ZIP archive by name (case-insensitive, path separator agnostic)
     fn file(&'_ mut self, name: &str) -> Result<Option<ZipFile<'_, RS>>, RustySheetError>;
-
-    /// Creates an XML reader for a file within the ZIP archive
-    fn xml_reader(
-        &'_ mut self,
-        name: &str,
-    ) -> Result<Option<XmlReader<BufReader<ZipFile<'_, RS>>>>, RustySheetError>;
-
-    /// Creates a BIFF12 reader for a file within the ZIP archive
-    fn biff_reader(
-        &'_ mut self,
-        name: &str,
-    ) -> Result<Option<Biff12Reader<BufReader<ZipFile<'_, RS>>>>, RustySheetError>;
 }
 
 impl<RS: Read + Seek> ZipHelper<RS> for ZipArchive<RS> {
@@ -43,26 +54,270 @@ impl<RS: Read + Seek> ZipHelper<RS> for ZipArchive<RS> {
             Err(error) => Err(error)?,
         }
     }
+}
+
+/// Reader over a package part, either decompressed in memory or streamed from the archive.
+/// Streamed bytes are counted by the metrics as they are read, cached ones once when cached.
+pub(crate) enum ZipPart<'a, RS: Read> {
+    /// Part decompressed in memory
+    Cached(Cursor<Arc<[u8]>>),
+    /// Part streamed from the archive (too large to cache)
+    Stream(ZipFile<'a, RS>),
+}
+
+impl<RS: Read> Read for ZipPart<'_, RS> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            ZipPart::Cached(reader) => reader.read(buf),
+            ZipPart::Stream(reader) => {
+                let read = reader.read(buf)?;
+                metrics::record_bytes_decompressed(read);
+                Ok(read)
+            }
+        }
+    }
+}
+
+/// ZIP package of a spreadsheet caching decompressed parts between passes.
+///
+/// Readers such as `read_sheet` open each worksheet part twice: once to analyze the
+/// column types and once to read the data. Parts up to `PART_CACHE_LIMIT` are
+/// decompressed once into memory; the next pass takes the cached copy, so memory is
+/// released after the second use.
+pub(crate) struct ZipPackage<RS: Read + Seek> {
+    /// Underlying ZIP archive
+    zip: ZipArchive<RS>,
+    /// Decompressed parts waiting for their next pass, keyed by requested name
+    cache: HashMap<String, Arc<[u8]>>,
+    /// Total size of the cached parts
+    cache_size: usize,
+}
+
+impl<RS: Read + Seek> ZipPackage<RS> {
+    /// Opens a ZIP package from a seekable reader
+    pub(crate) fn new(reader: RS) -> Result<Self, RustySheetError> {
+        Ok(ZipPackage {
+            zip: ZipArchive::new(reader)?,
+            cache: HashMap::new(),
+            cache_size: 0,
+        })
+    }
+
+    /// Opens a ZIP package, rebuilding the central directory from the local file
+    /// headers when it is unreadable and `salvage` is set
+    pub(crate) fn open(mut reader: RS, salvage: bool) -> Result<Self, RustySheetError>
+    where
+        RS: From<Cursor<Vec<u8>>>,
+    {
+        if salvage {
+            if let Err(error) = ZipArchive::new(&mut reader).map(|_| ()) {
+                let buffer = rebuild_central_directory(&mut reader)?.ok_or(error)?;
+                return Self::new(RS::from(Cursor::new(buffer)));
+            }
+        }
+        Self::new(reader)
+    }
+
+    /// Takes the decompressed part a previous pass left in the cache, if any
+    pub(crate) fn take_cached(&mut self, name: &str) -> Option<Arc<[u8]>> {
+        let data = self.cache.remove(name)?;
+        self.cache_size -= data.len();
+        Some(data)
+    }
+
+    /// Gets a file from the ZIP archive by name, bypassing the cache
+    pub(crate) fn file(&'_ mut self, name: &str) -> Result<Option<ZipFile<'_, RS>>, RustySheetError> {
+        self.zip.file(name)
+    }
+
+    /// Opens a part, from the cache if a previous pass decompressed it
+    fn part(&'_ mut self, name: &str) -> Result<Option<ZipPart<'_, RS>>, RustySheetError> {
+        if let Some(data) = self.take_cached(name) {
+            return Ok(Some(ZipPart::Cached(Cursor::new(data))));
+        }
+
+        let mut file = match self.zip.file(name)? {
+            Some(file) => file,
+            None => return Ok(None),
+        };
+        let size = file.size();
+        if size <= PART_CACHE_LIMIT && self.cache_size + size as usize <= CACHE_LIMIT {
+            let mut data = Vec::with_capacity(size as usize);
+            file.read_to_end(&mut data)?;
+            metrics::record_bytes_decompressed(data.len());
+            let data = Arc::<[u8]>::from(data);
+            self.cache_size += data.len();
+            self.cache.insert(name.to_owned(), data.clone());
+            Ok(Some(ZipPart::Cached(Cursor::new(data))))
+        } else {
+            Ok(Some(ZipPart::Stream(file)))
+        }
+    }
 
     /// Creates an XML reader for a file within the ZIP archive
-    fn xml_reader(
+    pub(crate) fn xml_reader(
         &'_ mut self,
         name: &str,
-    ) -> Result<Option<XmlReader<BufReader<ZipFile<'_, RS>>>>, RustySheetError> {
+    ) -> Result<Option<XmlReader<BufReader<ZipPart<'_, RS>>>>, RustySheetError> {
         let reader = self
-            .file(name)?
-            .map(|file| XmlReader::new(BufReader::new(file)));
+            .part(name)?
+            .map(|part| XmlReader::new(BufReader::new(part)));
         Ok(reader)
     }
 
     /// Creates a BIFF12 reader for a file within the ZIP archive
-    fn biff_reader(
+    pub(crate) fn biff_reader(
         &'_ mut self,
         name: &str,
-    ) -> Result<Option<Biff12Reader<BufReader<ZipFile<'_, RS>>>>, RustySheetError> {
+    ) -> Result<Option<Biff12Reader<BufReader<ZipPart<'_, RS>>>>, RustySheetError> {
         let reader = self
-            .file(name)?
-            .map(|file| Biff12Reader::new(BufReader::new(file)));
+            .part(name)?
+            .map(|part| Biff12Reader::new(BufReader::new(part)));
         Ok(reader)
     }
 }
+
+/// Local file entry recovered from a damaged archive
+struct LocalEntry {
+    /// Offset of the local file header
+    offset: usize,
+    /// Fields shared with the central directory header, from "version needed" to the extra field length
+    fields: [u8; 26],
+    /// Raw file name
+    name: Vec<u8>,
+}
+
+/// Rebuilds a ZIP archive whose central directory is missing or truncated.
+///
+/// Scans the local file headers from the start of the archive and keeps every entry
+/// whose data is complete, then appends a fresh central directory for them. Returns
+/// `None` when no complete entry is found.
+pub(crate) fn rebuild_central_directory<R: Read + Seek>(reader: &mut R) -> Result<Option<Vec<u8>>, RustySheetError> {
+    let mut buffer = Vec::new();
+    reader.seek(SeekFrom::Start(0))?;
+    reader.read_to_end(&mut buffer)?;
+
+    let mut entries = Vec::<LocalEntry>::new();
+    let mut offset = 0;
+    while let Some((entry, next)) = read_local_entry(&buffer, offset) {
+        entries.push(entry);
+        offset = next;
+    }
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    // Drop whatever follows the last complete entry and append the central directory
+    buffer.truncate(offset);
+    let directory_offset = buffer.len();
+    for entry in &entries {
+        buffer.extend_from_slice(&CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+        buffer.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        buffer.extend_from_slice(&entry.fields);
+        buffer.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        buffer.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        buffer.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        buffer.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        buffer.extend_from_slice(&(entry.offset as u32).to_le_bytes());
+        buffer.extend_from_slice(&entry.name);
+    }
+    let directory_size = buffer.len() - directory_offset;
+    buffer.extend_from_slice(&END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+    buffer.extend_from_slice(&0u16.to_le_bytes()); // disk number
+    buffer.extend_from_slice(&0u16.to_le_bytes()); // disk of the central directory
+    buffer.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    buffer.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    buffer.extend_from_slice(&(directory_size as u32).to_le_bytes());
+    buffer.extend_from_slice(&(directory_offset as u32).to_le_bytes());
+    buffer.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    Ok(Some(buffer))
+}
+
+/// Reads the local file entry at `offset`, returning it with the offset of the next entry,
+/// or `None` if there is no complete entry there
+fn read_local_entry(buffer: &[u8], offset: usize) -> Option<(LocalEntry, usize)> {
+    let header = buffer.get(offset..offset + LOCAL_HEADER_SIZE)?;
+    if read_u32(header, 0) != LOCAL_HEADER_SIGNATURE || offset > u32::MAX as usize {
+        return None;
+    }
+    let flags = read_u16(header, 6);
+    let name_length = read_u16(header, 26) as usize;
+    let extra_length = read_u16(header, 28) as usize;
+    let data_offset = offset + LOCAL_HEADER_SIZE + name_length + extra_length;
+    let name = buffer.get(offset + LOCAL_HEADER_SIZE..offset + LOCAL_HEADER_SIZE + name_length)?.to_vec();
+
+    let mut fields = [0u8; 26];
+    fields.copy_from_slice(&header[4..LOCAL_HEADER_SIZE]);
+    // The central directory carries no extra field
+    fields[24..26].copy_from_slice(&0u16.to_le_bytes());
+
+    let next = if flags & FLAG_DATA_DESCRIPTOR == 0 {
+        data_offset + read_u32(header, 18) as usize
+    } else {
+        // Sizes are only known from the data descriptor: find the one matching its position
+        let (descriptor, next) = (data_offset..=buffer.len().saturating_sub(16)).find_map(|position| {
+            let descriptor = &buffer[position..position + 16];
+            let size = read_u32(descriptor, 8) as usize;
+            (read_u32(descriptor, 0) == DATA_DESCRIPTOR_SIGNATURE && size == position - data_offset)
+                .then_some((descriptor, position + 16))
+        })?;
+        fields[10..22].copy_from_slice(&descriptor[4..16]);
+        next
+    };
+    // Zip64 entries and truncated data cannot be recovered
+    if read_u32(&fields, 14) == u32::MAX || next > buffer.len() {
+        return None;
+    }
+    Some((LocalEntry { offset, fields, name }, next))
+}
+
+fn read_u16(buffer: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([buffer[offset], buffer[offset + 1]])
+}
+
+fn read_u32(buffer: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([buffer[offset], buffer[offset + 1], buffer[offset + 2], buffer[offset + 3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn cached_part_is_taken_on_second_pass() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file("xl/worksheets/sheet1.xml", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"<worksheet/>").unwrap();
+        let buffer = writer.finish().unwrap();
+
+        let mut package = ZipPackage::new(buffer).unwrap();
+        for _ in 0..2 {
+            let mut content = String::new();
+            package.part("xl/worksheets/sheet1.xml").unwrap().unwrap().read_to_string(&mut content).unwrap();
+            assert_eq!(content, "<worksheet/>");
+        }
+        assert!(package.cache.is_empty());
+        assert_eq!(package.cache_size, 0);
+        assert!(package.part("xl/worksheets/sheet2.xml").unwrap().is_none());
+    }
+
+    #[test]
+    fn salvage_truncated_archive() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file("xl/workbook.xml", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"<workbook/>").unwrap();
+        writer.start_file("xl/worksheets/sheet1.xml", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"<worksheet/>").unwrap();
+        let mut buffer = writer.finish().unwrap().into_inner();
+        buffer.truncate(buffer.len() - 40);
+
+        assert!(ZipPackage::open(Cursor::new(buffer.to_owned()), false).is_err());
+        let mut package = ZipPackage::open(Cursor::new(buffer), true).unwrap();
+        let mut content = String::new();
+        package.part("xl/worksheets/sheet1.xml").unwrap().unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "<worksheet/>");
+    }
+}
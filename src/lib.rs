@@ -7,12 +7,36 @@ pub(crate) mod database;
 pub(crate) mod error;
 pub(crate) mod extension;
 pub(crate) mod helpers;
+pub mod reader;
 pub(crate) mod spreadsheet;
 
+use crate::database::host::register_host_database;
 use crate::extension::analyze_sheet::AnalyzeSheetTableFunction;
 use crate::extension::analyze_sheets::AnalyzeSheetsTableFunction;
+use crate::extension::diagnose_union::DiagnoseUnionTableFunction;
+use crate::extension::duplicate_sheets::DuplicateSheetsTableFunction;
+use crate::extension::export_sheets_partitioned::ExportSheetsPartitionedTableFunction;
+use crate::extension::last_scan::LastScanTableFunction;
+use crate::extension::list_sheets::ListSheetsTableFunction;
+use crate::extension::metrics::MetricsTableFunction;
+use crate::extension::read_cell_protection::ReadCellProtectionTableFunction;
+use crate::extension::read_cells::ReadCellsTableFunction;
+use crate::extension::read_comments::ReadCommentsTableFunction;
+use crate::extension::read_hyperlinks::ReadHyperlinksTableFunction;
+use crate::extension::read_defined_names::ReadDefinedNamesTableFunction;
+use crate::extension::read_formula_graph::ReadFormulaGraphTableFunction;
+use crate::extension::read_manifest::ReadManifestTableFunction;
 use crate::extension::read_sheet::ReadSheetTableFunction;
 use crate::extension::read_sheets::ReadSheetsTableFunction;
+use crate::extension::rusty_sheet_functions::RustySheetFunctionsTableFunction;
+use crate::extension::selftest::SelftestTableFunction;
+use crate::extension::settings::SettingsTableFunction;
+use crate::extension::sheet_anomalies::SheetAnomaliesTableFunction;
+use crate::extension::sheet_checksum::SheetChecksumTableFunction;
+use crate::extension::sheet_ddl::SheetDdlTableFunction;
+use crate::extension::sheet_headers::SheetHeadersTableFunction;
+use crate::extension::workbook_metadata::WorkbookMetadataTableFunction;
+use crate::extension::write_sheet::WriteSheetTableFunction;
 use anyhow::Context;
 use anyhow::Result;
 use duckdb::Connection;
@@ -28,6 +52,7 @@ pub fn rusty_sheet_init_c_api_internal(
         return Ok(false);
     }
     let db: ffi::duckdb_database = unsafe { *(*access).get_database.unwrap()(info) };
+    register_host_database(db)?;
     let connection = unsafe { Connection::open_from_raw(db.cast())? };
     extension_entrypoint(connection)?;
     Ok(true)
@@ -67,11 +92,77 @@ pub fn extension_entrypoint(connection: Connection) -> Result<()> {
     connection
         .register_table_function::<AnalyzeSheetsTableFunction>("analyze_sheets")
         .context("Failed to register analyze_sheets table function")?;
+    connection
+        .register_table_function::<SheetDdlTableFunction>("sheet_ddl")
+        .context("Failed to register sheet_ddl table function")?;
     connection
         .register_table_function::<ReadSheetTableFunction>("read_sheet")
         .context("Failed to register read_sheet table function")?;
     connection
         .register_table_function::<ReadSheetsTableFunction>("read_sheets")
         .context("Failed to register read_sheets table function")?;
+    connection
+        .register_table_function::<ReadManifestTableFunction>("read_manifest")
+        .context("Failed to register read_manifest table function")?;
+    connection
+        .register_table_function::<ExportSheetsPartitionedTableFunction>("export_sheets_partitioned")
+        .context("Failed to register export_sheets_partitioned table function")?;
+    connection
+        .register_table_function::<SheetHeadersTableFunction>("sheet_headers")
+        .context("Failed to register sheet_headers table function")?;
+    connection
+        .register_table_function::<DuplicateSheetsTableFunction>("duplicate_sheets")
+        .context("Failed to register duplicate_sheets table function")?;
+    connection
+        .register_table_function::<DiagnoseUnionTableFunction>("diagnose_union")
+        .context("Failed to register diagnose_union table function")?;
+    connection
+        .register_table_function::<SheetChecksumTableFunction>("sheet_checksum")
+        .context("Failed to register sheet_checksum table function")?;
+    connection
+        .register_table_function::<SheetAnomaliesTableFunction>("sheet_anomalies")
+        .context("Failed to register sheet_anomalies table function")?;
+    connection
+        .register_table_function::<ReadCellsTableFunction>("read_cells")
+        .context("Failed to register read_cells table function")?;
+    connection
+        .register_table_function::<ListSheetsTableFunction>("list_sheets")
+        .context("Failed to register list_sheets table function")?;
+    connection
+        .register_table_function::<WorkbookMetadataTableFunction>("workbook_metadata")
+        .context("Failed to register workbook_metadata table function")?;
+    connection
+        .register_table_function::<ReadDefinedNamesTableFunction>("read_defined_names")
+        .context("Failed to register read_defined_names table function")?;
+    connection
+        .register_table_function::<ReadCommentsTableFunction>("read_comments")
+        .context("Failed to register read_comments table function")?;
+    connection
+        .register_table_function::<ReadHyperlinksTableFunction>("read_hyperlinks")
+        .context("Failed to register read_hyperlinks table function")?;
+    connection
+        .register_table_function::<ReadCellProtectionTableFunction>("read_cell_protection")
+        .context("Failed to register read_cell_protection table function")?;
+    connection
+        .register_table_function::<ReadFormulaGraphTableFunction>("read_formula_graph")
+        .context("Failed to register read_formula_graph table function")?;
+    connection
+        .register_table_function::<WriteSheetTableFunction>("write_sheet")
+        .context("Failed to register write_sheet table function")?;
+    connection
+        .register_table_function::<LastScanTableFunction>("rusty_sheet_last_scan")
+        .context("Failed to register rusty_sheet_last_scan table function")?;
+    connection
+        .register_table_function::<MetricsTableFunction>("rusty_sheet_metrics")
+        .context("Failed to register rusty_sheet_metrics table function")?;
+    connection
+        .register_table_function::<SettingsTableFunction>("rusty_sheet_settings")
+        .context("Failed to register rusty_sheet_settings table function")?;
+    connection
+        .register_table_function::<RustySheetFunctionsTableFunction>("rusty_sheet_functions")
+        .context("Failed to register rusty_sheet_functions table function")?;
+    connection
+        .register_table_function::<SelftestTableFunction>("rusty_sheet_selftest")
+        .context("Failed to register rusty_sheet_selftest table function")?;
     Ok(())
 }
@@ -0,0 +1,497 @@
+//! Rust API streaming the rows of a worksheet, for embedding the readers without DuckDB.
+//!
+//! `SheetReader` takes the options of `read_sheet` and returns the rows of the first
+//! matching worksheet as typed values, converted chunk by chunk while iterating.
+
+use crate::database::column::Column;
+use crate::database::decimal::to_decimal;
+use crate::database::decimal::DecimalPolicy;
+use crate::database::range::Range;
+use crate::error::ResultMessage;
+use crate::error::RustySheetError;
+use crate::extension::default_analyze_rows;
+use crate::extension::ExtensionError;
+use crate::spreadsheet::cell::parse_duration;
+use crate::spreadsheet::cell::Cell;
+use crate::spreadsheet::cell::CellType;
+use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::open_spreadsheet;
+use crate::spreadsheet::shared_strings::SharedStrings;
+use crate::spreadsheet::shared_strings::SharedStringsBuilder;
+use crate::spreadsheet::sheet::Sheet;
+use crate::spreadsheet::SpreadsheetError;
+use glob::Pattern;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+pub use crate::database::column::ColumnType;
+
+/// Error reported by `SheetReader`, with the message the extension would report
+#[derive(Debug)]
+pub struct ReadError(String);
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+impl From<RustySheetError> for ReadError {
+    fn from(error: RustySheetError) -> Self {
+        ReadError(error.to_string())
+    }
+}
+
+/// Value of a cell converted to the type of its column, in the representation DuckDB uses
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypedValue {
+    Boolean(bool),
+    BigInt(i64),
+    Double(f64),
+    Varchar(String),
+    /// Microseconds since 1970-01-01 00:00:00
+    Timestamp(i64),
+    /// Days since 1970-01-01
+    Date(i32),
+    /// Microseconds since midnight
+    Time(i64),
+    Interval { months: i32, days: i32, micros: i64 },
+    /// Unscaled value of a DECIMAL(width, scale)
+    Decimal { value: i128, width: u8, scale: u8 },
+}
+
+/// Name and type of a column of the rows
+#[derive(Clone, Debug, PartialEq)]
+pub struct SheetColumn {
+    pub name: String,
+    pub kind: ColumnType,
+}
+
+/// Reader of the rows of one worksheet, configured like `read_sheet`
+///
+/// ```ignore
+/// let rows = SheetReader::new("sales.xlsx").sheet("Q*").column("Amount", "DOUBLE").rows()?;
+/// for row in rows {
+///     let row: Vec<Option<TypedValue>> = row?;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SheetReader {
+    file_name: String,
+    sheet: Option<String>,
+    range: Option<String>,
+    header: bool,
+    columns: Vec<(String, String)>,
+    analyze_rows: Option<usize>,
+    nulls: HashSet<String>,
+    error_as_null: bool,
+    skip_empty_rows: bool,
+    end_at_empty_row: bool,
+    midnight_as_date: bool,
+    epoch_date_as_time: bool,
+    duration_as_interval: bool,
+    display_values: bool,
+    include_hidden_columns: bool,
+    max_cell_length: Option<(usize, String)>,
+    fix_encoding: bool,
+    all_varchar: bool,
+    normalize_names: bool,
+    salvage: bool,
+    snapshot: bool,
+    password: Option<String>,
+    spill_threshold: Option<usize>,
+}
+
+impl SheetReader {
+    /// Creates a reader of a local file with the defaults of `read_sheet`
+    pub fn new(file_name: &str) -> SheetReader {
+        SheetReader {
+            file_name: file_name.to_owned(),
+            sheet: None,
+            range: None,
+            header: true,
+            columns: Vec::new(),
+            analyze_rows: None,
+            nulls: HashSet::from([String::new()]),
+            error_as_null: false,
+            skip_empty_rows: false,
+            end_at_empty_row: false,
+            midnight_as_date: false,
+            epoch_date_as_time: false,
+            duration_as_interval: false,
+            display_values: false,
+            include_hidden_columns: true,
+            max_cell_length: None,
+            fix_encoding: false,
+            all_varchar: false,
+            normalize_names: false,
+            salvage: false,
+            snapshot: false,
+            password: None,
+            spill_threshold: None,
+        }
+    }
+
+    /// Reads the first worksheet matching a wildcard pattern instead of the first worksheet
+    pub fn sheet(mut self, pattern: &str) -> SheetReader {
+        self.sheet = Some(pattern.to_owned());
+        self
+    }
+
+    /// Reads an Excel-style range such as `'A2:E100'`
+    pub fn range(mut self, range: &str) -> SheetReader {
+        self.range = Some(range.to_owned());
+        self
+    }
+
+    /// Whether the first row holds the column names
+    pub fn header(mut self, header: bool) -> SheetReader {
+        self.header = header;
+        self
+    }
+
+    /// Sets the type of the columns matching a name pattern or a `'#n'` position, e.g. `'DOUBLE'`
+    pub fn column(mut self, pattern: &str, kind: &str) -> SheetReader {
+        self.columns.push((pattern.to_owned(), kind.to_owned()));
+        self
+    }
+
+    /// Number of rows analyzed to detect the column types
+    pub fn analyze_rows(mut self, rows: usize) -> SheetReader {
+        self.analyze_rows = Some(rows);
+        self
+    }
+
+    /// Texts read as NULL, instead of the empty string
+    pub fn nulls(mut self, nulls: &[&str]) -> SheetReader {
+        self.nulls = nulls.iter().map(|null| null.to_string()).collect();
+        self
+    }
+
+    /// Reads error cells as NULL instead of failing
+    pub fn error_as_null(mut self, error_as_null: bool) -> SheetReader {
+        self.error_as_null = error_as_null;
+        self
+    }
+
+    /// Skips rows without data
+    pub fn skip_empty_rows(mut self, skip_empty_rows: bool) -> SheetReader {
+        self.skip_empty_rows = skip_empty_rows;
+        self
+    }
+
+    /// Stops at the first row without data
+    pub fn end_at_empty_row(mut self, end_at_empty_row: bool) -> SheetReader {
+        self.end_at_empty_row = end_at_empty_row;
+        self
+    }
+
+    /// Classifies ISO datetimes at midnight as dates
+    pub fn midnight_as_date(mut self, midnight_as_date: bool) -> SheetReader {
+        self.midnight_as_date = midnight_as_date;
+        self
+    }
+
+    /// Classifies ISO datetimes on the 1900/1904 epoch date as times
+    pub fn epoch_date_as_time(mut self, epoch_date_as_time: bool) -> SheetReader {
+        self.epoch_date_as_time = epoch_date_as_time;
+        self
+    }
+
+    /// Classifies text durations like "2 05:30:00" as intervals
+    pub fn duration_as_interval(mut self, duration_as_interval: bool) -> SheetReader {
+        self.duration_as_interval = duration_as_interval;
+        self
+    }
+
+    /// Reads numeric cells as displayed by their number format
+    pub fn display_values(mut self, display_values: bool) -> SheetReader {
+        self.display_values = display_values;
+        self
+    }
+
+    /// Whether hidden, zero-width and collapsed columns are read
+    pub fn include_hidden_columns(mut self, include_hidden_columns: bool) -> SheetReader {
+        self.include_hidden_columns = include_hidden_columns;
+        self
+    }
+
+    /// Truncates text cells to `length` characters, appending `suffix` to truncated values
+    pub fn max_cell_length(mut self, length: usize, suffix: &str) -> SheetReader {
+        self.max_cell_length = Some((length, suffix.to_owned()));
+        self
+    }
+
+    /// Repairs text whose UTF-8 bytes were decoded as Windows-1252 or Latin-1 (e.g. "CafÃ©")
+    pub fn fix_encoding(mut self, fix_encoding: bool) -> SheetReader {
+        self.fix_encoding = fix_encoding;
+        self
+    }
+
+    /// Types every column as VARCHAR instead of detecting its type, except those given a type
+    pub fn all_varchar(mut self, all_varchar: bool) -> SheetReader {
+        self.all_varchar = all_varchar;
+        self
+    }
+
+    /// Lowercases the column names taken from headers, strips their accents and replaces
+    /// anything but letters and digits with underscores
+    pub fn normalize_names(mut self, normalize_names: bool) -> SheetReader {
+        self.normalize_names = normalize_names;
+        self
+    }
+
+    /// Recovers ZIP-based files whose central directory is damaged
+    pub fn salvage(mut self, salvage: bool) -> SheetReader {
+        self.salvage = salvage;
+        self
+    }
+
+    /// Copies the file before parsing so that changes made meanwhile are not seen
+    pub fn snapshot(mut self, snapshot: bool) -> SheetReader {
+        self.snapshot = snapshot;
+        self
+    }
+
+    /// Password of an encrypted workbook or ODS file
+    pub fn password(mut self, password: &str) -> SheetReader {
+        self.password = Some(password.to_owned());
+        self
+    }
+
+    /// Keeps the shared string table in temporary files once it holds more than `threshold` strings
+    pub fn spill_threshold(mut self, threshold: usize) -> SheetReader {
+        self.spill_threshold = Some(threshold);
+        self
+    }
+
+    /// Analyzes the worksheet and returns an iterator over its rows
+    ///
+    /// The worksheet is parsed up front as by `read_sheet`; rows are converted one chunk
+    /// at a time while iterating, and the text of a chunk is released once it is converted.
+    pub fn rows(&self) -> Result<Rows, ReadError> {
+        Ok(self.open().with_prefix(&self.file_name)?)
+    }
+
+    fn open(&self) -> Result<Rows, RustySheetError> {
+        let sheet_name_patterns = self.sheet.as_deref().map(Pattern::new).transpose()?.map(|pattern| vec![pattern]);
+        let range = self.range.as_deref().map(Range::try_from).transpose()?;
+        let presets = self.columns.iter()
+            .map(|(pattern, kind)| Ok((Pattern::new(pattern)?, ColumnType::parse(kind)?)))
+            .collect::<Result<Vec<_>, RustySheetError>>()?;
+
+        let mut spreadsheet = open_spreadsheet(&self.file_name, self.salvage, self.snapshot, self.password.as_deref())?;
+        let analyze_criteria = self.criteria(sheet_name_patterns.to_owned(), range, Some(self.analyze_rows.unwrap_or(default_analyze_rows(None))));
+        let tables = spreadsheet.analyze_sheets(self.header, &analyze_criteria, &presets)?;
+        let table = tables.first()
+            .ok_or_else(|| ExtensionError::SheetWildcardError(spreadsheet.name(), self.sheet.to_owned().unwrap_or_default()))?;
+
+        let criteria = Criteria {
+            max_cell_length: self.max_cell_length.to_owned(),
+            ..self.criteria(sheet_name_patterns, Some(Range {
+                row_lower_bound: table.row_lower_bound,
+                row_upper_bound: range.and_then(|it| it.row_upper_bound),
+                col_lower_bound: Some(table.col_lower_bound),
+                col_upper_bound: Some(table.col_upper_bound),
+            }), None)
+        };
+        let strings = SharedStringsBuilder::new(&self.nulls, &self.max_cell_length, self.fix_encoding, self.spill_threshold);
+        let (mut sheets, shared_strings) = spreadsheet.read_sheets_and_shared_strings(&criteria, strings)?;
+        spreadsheet.localize_dates(&mut sheets, &criteria, false)?;
+        Ok(Rows {
+            columns: table.columns.to_owned(),
+            sheet: sheets.into_iter().next(),
+            shared_strings,
+            chunk: 0,
+            pending: Vec::new().into_iter(),
+            failed: false,
+        })
+    }
+
+    /// Builds the criteria of the analysis and of the read of the worksheet
+    fn criteria(&self, sheet_name_patterns: Option<Vec<Pattern>>, range: Option<Range>, rows_limit: Option<usize>) -> Criteria {
+        Criteria {
+            sheet_name_patterns,
+            sheet_limit: Some(1),
+            range,
+            rows_limit,
+            nulls: self.nulls.to_owned(),
+            error_as_null: self.error_as_null,
+            skip_empty_rows: self.skip_empty_rows,
+            end_at_empty_row: self.end_at_empty_row,
+            midnight_as_date: self.midnight_as_date,
+            epoch_date_as_time: self.epoch_date_as_time,
+            duration_as_interval: self.duration_as_interval,
+            display_values: self.display_values,
+            include_hidden_columns: self.include_hidden_columns,
+            fix_encoding: self.fix_encoding,
+            all_varchar: self.all_varchar,
+            normalize_names: self.normalize_names,
+            ..Default::default()
+        }
+    }
+}
+
+/// Iterator over the rows of a worksheet, each holding one value per column
+///
+/// Iteration ends after the first error.
+pub struct Rows {
+    columns: Vec<Column>,
+    sheet: Option<Sheet>,
+    shared_strings: SharedStrings,
+    /// Next chunk of the sheet to convert
+    chunk: usize,
+    /// Converted rows of the current chunk
+    pending: std::vec::IntoIter<Vec<Option<TypedValue>>>,
+    failed: bool,
+}
+
+impl Rows {
+    /// Returns the names and types of the columns
+    pub fn columns(&self) -> Vec<SheetColumn> {
+        self.columns.iter()
+            .map(|column| SheetColumn { name: column.name.to_owned(), kind: column.kind })
+            .collect()
+    }
+
+    /// Converts the next chunk of the sheet, returning false once the sheet is exhausted
+    fn convert_chunk(&mut self) -> Result<bool, RustySheetError> {
+        let Some(sheet) = &mut self.sheet else {
+            return Ok(false);
+        };
+        let Some(table) = sheet.chunk(self.chunk) else {
+            return Ok(false);
+        };
+        let policy = DecimalPolicy::default();
+        let rows = table.iter()
+            .map(|record| {
+                record.iter().zip(&self.columns)
+                    .map(|(cell, column)| match cell {
+                        Some(cell) => to_typed_value(sheet, column, cell, &self.shared_strings, &policy),
+                        None => Ok(None),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(table);
+        sheet.release_chunk(self.chunk);
+        self.chunk += 1;
+        self.pending = rows.into_iter();
+        Ok(true)
+    }
+}
+
+impl Iterator for Rows {
+    type Item = Result<Vec<Option<TypedValue>>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            if let Some(row) = self.pending.next() {
+                return Some(Ok(row));
+            }
+            match self.convert_chunk() {
+                Ok(true) => (),
+                Ok(false) => return None,
+                Err(error) => {
+                    self.failed = true;
+                    return Some(Err(error.into()));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Converts a cell to the type of its column, as the extension writes it to a vector.
+/// Shared strings matching a null literal are None.
+fn to_typed_value(sheet: &Sheet, column: &Column, cell: &Cell, shared_strings: &SharedStrings, policy: &DecimalPolicy) -> Result<Option<TypedValue>, RustySheetError> {
+    let mapper = |message: String| {
+        SpreadsheetError::CellValueError(
+            sheet.file_name.to_owned(),
+            sheet.name.to_owned(),
+            cell.reference(),
+            message,
+        )
+    };
+    let value = match (column.kind, cell.kind) {
+        (ColumnType::Varchar | ColumnType::Interval | ColumnType::Decimal(_, _), CellType::SharedString) => {
+            let Some(text) = shared_strings.get(cell.value.parse::<usize>()?)? else {
+                return Ok(None);
+            };
+            match column.kind {
+                ColumnType::Interval => {
+                    let (days, micros) = parse_duration(&text)
+                        .ok_or_else(|| mapper(format!("parse '{text}' to interval failed")))?;
+                    TypedValue::Interval { months: 0, days, micros }
+                }
+                ColumnType::Decimal(width, scale) => TypedValue::Decimal {
+                    value: to_decimal(&text, width, scale, policy).map_err(mapper)?,
+                    width,
+                    scale,
+                },
+                _ => TypedValue::Varchar(text.into_owned()),
+            }
+        }
+        (ColumnType::Varchar, _) => TypedValue::Varchar(cell.to_string()),
+        (ColumnType::Boolean, _) => TypedValue::Boolean(cell.to_boolean()),
+        (ColumnType::BigInt, _) => TypedValue::BigInt(cell.to_bigint().map_err(mapper)?),
+        (ColumnType::Double, _) => TypedValue::Double(cell.to_double().map_err(mapper)?),
+        (ColumnType::Timestamp, _) => TypedValue::Timestamp(cell.to_datetime().map_err(mapper)?),
+        (ColumnType::Date, _) => TypedValue::Date(cell.to_date().map_err(mapper)?),
+        (ColumnType::Time, _) => TypedValue::Time(cell.to_time().map_err(mapper)?),
+        (ColumnType::Interval, _) => {
+            let (months, days, micros) = cell.to_interval().map_err(mapper)?;
+            TypedValue::Interval { months, days, micros }
+        }
+        (ColumnType::Decimal(width, scale), _) => TypedValue::Decimal {
+            value: to_decimal(&cell.value, width, scale, policy).map_err(mapper)?,
+            width,
+            scale,
+        },
+    };
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spreadsheet::output::write_xlsx;
+    use crate::spreadsheet::output::SheetLayout;
+
+    fn cell(row: usize, col: usize, kind: CellType, value: &str) -> Cell {
+        Cell { row, col, kind, value: value.to_owned() }
+    }
+
+    #[test]
+    fn rows_are_typed_by_column() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_reader_{}.xlsx", std::process::id()));
+        let file_name = path.to_str().unwrap();
+        write_xlsx(file_name, "Report", [
+            cell(0, 0, CellType::InlineString, "name"),
+            cell(0, 1, CellType::InlineString, "amount"),
+            cell(1, 0, CellType::InlineString, "tea"),
+            cell(1, 1, CellType::Number, "3"),
+            cell(2, 0, CellType::InlineString, "n/a"),
+            cell(2, 1, CellType::Number, "4.5"),
+        ].map(Ok), &SheetLayout::default()).unwrap();
+
+        let rows = SheetReader::new(file_name).nulls(&["", "n/a"]).column("amount", "DOUBLE").rows().unwrap();
+        let columns = rows.columns();
+        let rows = rows.collect::<Result<Vec<_>, _>>().unwrap();
+        let missing = SheetReader::new(file_name).sheet("Other").rows().err().map(|error| error.to_string());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(columns, vec![
+            SheetColumn { name: "name".to_owned(), kind: ColumnType::Varchar },
+            SheetColumn { name: "amount".to_owned(), kind: ColumnType::Double },
+        ]);
+        assert_eq!(rows, vec![
+            vec![Some(TypedValue::Varchar("tea".to_owned())), Some(TypedValue::Double(3.0))],
+            vec![None, Some(TypedValue::Double(4.5))],
+        ]);
+        assert!(missing.is_some_and(|error| error.contains("Other")));
+    }
+}
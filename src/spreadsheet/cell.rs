@@ -1,10 +1,13 @@
+use crate::database::column::is_bigint;
 use crate::error::RustySheetError;
 use crate::spreadsheet::reference::index_to_reference;
+use chrono::DateTime;
 use chrono::Duration;
 use chrono::NaiveDate;
 use chrono::NaiveDateTime;
 use chrono::Timelike;
 use iso8601_duration::Duration as IsoDuration;
+use std::borrow::Cow;
 use std::fmt::Display;
 
 /// Types of cell data in spreadsheet files.
@@ -135,13 +138,36 @@ impl Cell {
         index_to_reference(self.row, self.col)
     }
 
+    /// Returns the same text as `to_string`, borrowing the value when it is shown as is.
+    pub(crate) fn text(&self) -> Cow<'_, str> {
+        match self.kind {
+            CellType::Boolean => Cow::Borrowed(if self.value == "1" { "true" } else { "false" }),
+            CellType::NumberDateTime1900
+            | CellType::NumberDate1900
+            | CellType::NumberTime1900
+            | CellType::NumberDateTime1904
+            | CellType::NumberDate1904
+            | CellType::NumberTime1904
+            | CellType::IsoDateTime
+            | CellType::IsoDuration => Cow::Owned(self.to_string()),
+            _ => Cow::Borrowed(&self.value),
+        }
+    }
+
     /// Converts cell value to boolean (1 = true, other = false).
     pub(crate) fn to_boolean(&self) -> bool {
         self.value == "1"
     }
 
     /// Converts cell value to 64-bit integer, parsing only leading numeric characters.
+    /// Values in exponent notation (e.g. `1e+05`) are converted when integral.
     pub(crate) fn to_bigint(&self) -> Result<i64, String> {
+        if self.value.contains(['e', 'E']) {
+            return self.value.parse::<f64>().ok()
+                .filter(|number| is_bigint(*number))
+                .map(|number| number as i64)
+                .ok_or_else(|| format!("parse '{}' to bigint failed", self.value));
+        }
         let mut integer = self.value.as_str();
         for (index, char) in self.value.char_indices() {
             if !char.is_ascii_digit() && char != '-' {
@@ -170,14 +196,10 @@ impl Cell {
                 Ok(days - 25_568 + if days >= 60 { -1 } else { 0 }) // Convert from 1900 to 1970 epoch
             }
             CellType::NumberDateTime1904 | CellType::NumberDate1904 | CellType::NumberTime1904 => {
-                let days = self.to_double()?.trunc() as i32; // Handle Lotus 1-2-3 leap year bug
-                Ok(days - 25_568 + 1_460) // Convert from 1904 to 1970 epoch
-            }
-            CellType::IsoDateTime => {
-                NaiveDate::parse_from_str(&self.value, "%Y-%m-%d")
-                    .map_err(|_| format!("parse '{}' to NaiveDate failed", self.value))
-                    .map(|date| date.to_epoch_days())
+                let days = self.to_double()?.trunc() as i32;
+                Ok(days - 25_569 + 1_462) // Convert from 1904 to 1970 epoch
             }
+            CellType::IsoDateTime => self.to_iso_datetime().map(|datetime| datetime.date().to_epoch_days()),
             CellType::IsoDuration => Ok(0), // Duration only used for ods time
             _ => Err(format!("parse '{}' to date failed", self.value))?
         }
@@ -193,16 +215,13 @@ impl Cell {
                 let fraction = self.to_double()?;
                 Ok((fraction * 86_400_000_000f64).round() as i64)
             }
-            CellType::IsoDateTime => {
-                NaiveDateTime::parse_from_str(&self.value, "%Y-%m-%dT%H:%M:%S%.f")
-                    .map_err(|_| format!("parse '{}' to NaiveDateTime failed", self.value))
-                    .map(|datetime| {
-                        let time = datetime.time();
-                        let seconds = time.num_seconds_from_midnight() as i64;
-                        let nanoseconds = time.nanosecond() as i64;
-                        (seconds * 1_000_000) + (nanoseconds / 1_000)
-                    })
-            }
+            CellType::IsoDateTime if !self.value.contains('T') => Ok(0), // Date only, time is midnight
+            CellType::IsoDateTime => self.to_iso_datetime().map(|datetime| {
+                let time = datetime.time();
+                let seconds = time.num_seconds_from_midnight() as i64;
+                let nanoseconds = time.nanosecond() as i64;
+                (seconds * 1_000_000) + (nanoseconds / 1_000)
+            }),
             CellType::IsoDuration => {
                 if let Ok(duration) = self.value.parse::<IsoDuration>() {
                     let hour = duration.hour as i64;
@@ -228,21 +247,59 @@ impl Cell {
                 let time = self.to_double()?;
                 Ok(((days + time.fract()) * 86_400_000_000f64).round() as i64)
             }
-            CellType::IsoDateTime => {
-                let datetime = if self.value.contains('T') {
-                    NaiveDateTime::parse_from_str(&self.value, "%Y-%m-%dT%H:%M:%S%.f")
-                        .map_err(|_| format!("parse '{}' to NaiveDateTime failed", self.value))
-                } else {
-                    NaiveDate::parse_from_str(&self.value, "%Y-%m-%d")
-                        .map_err(|_| format!("parse '{}' to NaiveDate failed", self.value))
-                        .map(|date| date.and_hms_opt(0, 0, 0).expect("Append 00:00:00"))
-                };
-                datetime.map(|datetime| datetime.and_utc().timestamp_micros())
-            }
+            CellType::IsoDateTime => self.to_iso_datetime().map(|datetime| datetime.and_utc().timestamp_micros()),
             CellType::IsoDuration => self.to_time(),
             _ => Err(format!("parse '{}' to datetime failed", self.value))?,
         }
     }
+
+    /// Converts cell value to an interval of (months, days, microseconds).
+    /// Numeric values count days, as in Excel `[h]:mm:ss` durations; text is
+    /// parsed as a `d hh:mm:ss` duration, see [`parse_duration`].
+    pub(crate) fn to_interval(&self) -> Result<(i32, i32, i64), String> {
+        match self.kind {
+            CellType::Number |
+            CellType::NumberDateTime1900 | CellType::NumberDateTime1904 |
+            CellType::NumberDate1900 | CellType::NumberDate1904 |
+            CellType::NumberTime1900 | CellType::NumberTime1904 => {
+                let micros = (self.to_double()? * 86_400_000_000f64).round() as i64;
+                Ok((0, (micros / 86_400_000_000) as i32, micros % 86_400_000_000))
+            }
+            CellType::IsoDuration => {
+                if let Ok(duration) = self.value.parse::<IsoDuration>() {
+                    let months = (duration.year * 12f32 + duration.month) as i32;
+                    let seconds = duration.hour as f64 * 3600f64 + duration.minute as f64 * 60f64 + duration.second as f64;
+                    Ok((months, duration.day as i32, (seconds * 1_000_000f64).round() as i64))
+                } else {
+                    Err(format!("parse '{}' to iso8601 duration failed", self.value))?
+                }
+            }
+            CellType::InlineString => parse_duration(&self.value)
+                .map(|(days, micros)| (0, days, micros))
+                .ok_or_else(|| format!("parse '{}' to interval failed", self.value)),
+            _ => Err(format!("parse '{}' to interval failed", self.value))?,
+        }
+    }
+
+    /// Parses an ISO 8601 date or datetime value.
+    /// Datetimes with a UTC offset (`+02:00`, `+0200`) or `Z` suffix are converted to UTC.
+    fn to_iso_datetime(&self) -> Result<NaiveDateTime, String> {
+        const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+        let value = self.value.as_str();
+        if !value.contains('T') {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| format!("parse '{}' to NaiveDate failed", value))
+                .map(|date| date.and_hms_opt(0, 0, 0).expect("Append 00:00:00"))
+        } else if let Some(value) = value.strip_suffix('Z') {
+            NaiveDateTime::parse_from_str(value, FORMAT)
+                .map_err(|_| format!("parse '{}' to NaiveDateTime failed", self.value))
+        } else {
+            NaiveDateTime::parse_from_str(value, FORMAT)
+                .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%:z").map(|datetime| datetime.naive_utc()))
+                .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z").map(|datetime| datetime.naive_utc()))
+                .map_err(|_| format!("parse '{}' to NaiveDateTime failed", value))
+        }
+    }
 }
 
 impl Display for Cell {
@@ -317,6 +374,40 @@ impl Display for Cell {
     }
 }
 
+/// Parses a duration written as `[-][d ]h:mm[:ss[.ffffff]]` (e.g. `2 05:30:00`)
+/// into days and microseconds. Hours are not limited to a day.
+pub(crate) fn parse_duration(text: &str) -> Option<(i32, i64)> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text),
+    };
+    let (days, time) = match text.split_once(char::is_whitespace) {
+        Some((days, time)) => (days.parse::<u32>().ok()?, time.trim_start()),
+        None => (0, text),
+    };
+    let digits = |value: &str, length: usize| value.len() == length && value.bytes().all(|byte| byte.is_ascii_digit());
+    let mut parts = time.split(':');
+    let hours = parts.next().filter(|hours| (1..=6).contains(&hours.len()) && digits(hours, hours.len()))?;
+    let minutes = parts.next().filter(|minutes| digits(minutes, 2))?;
+    let (seconds, fraction) = match parts.next() {
+        Some(seconds) => seconds.split_once('.').unwrap_or((seconds, "")),
+        None => ("00", ""),
+    };
+    if parts.next().is_some() || !digits(seconds, 2) || fraction.len() > 6 || !digits(fraction, fraction.len()) {
+        return None;
+    }
+    let (minutes, seconds) = (minutes.parse::<i64>().ok()?, seconds.parse::<i64>().ok()?);
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+    let fraction = format!("{fraction:0<6}").parse::<i64>().ok()?;
+    let micros = (hours.parse::<i64>().ok()? * 3600 + minutes * 60 + seconds) * 1_000_000 + fraction;
+    let (days, micros) = (days as i64 + micros / 86_400_000_000, micros % 86_400_000_000);
+    let days = i32::try_from(days).ok()?;
+    Some(if negative { (-days, -micros) } else { (days, micros) })
+}
+
 /// Converts Excel numeric date to ISO date string.
 /// Handles Lotus 1-2-3 leap year bug for 1900 epoch.
 fn to_date_string(value: &str, is_1904: bool) -> Result<String, RustySheetError> {
@@ -360,3 +451,84 @@ pub(crate) fn to_datetime_string(value: &str, is_1904: bool) -> Result<String, R
         Ok(format!("{date} 00:00:00"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iso(value: &str) -> Cell {
+        Cell {
+            row: 0,
+            col: 0,
+            kind: CellType::IsoDateTime,
+            value: value.to_owned(),
+        }
+    }
+
+    #[test]
+    fn iso_datetime_with_offset() {
+        let expected = iso("2024-05-01T08:00:00").to_datetime().unwrap();
+        assert_eq!(iso("2024-05-01T10:00:00+02:00").to_datetime(), Ok(expected));
+        assert_eq!(iso("2024-05-01T10:00:00+0200").to_datetime(), Ok(expected));
+        assert_eq!(iso("2024-05-01T08:00:00Z").to_datetime(), Ok(expected));
+        assert_eq!(iso("2024-05-01T08:00:00.000Z").to_datetime(), Ok(expected));
+
+        assert_eq!(iso("2024-05-01T01:30:00+02:00").to_date(), iso("2024-04-30").to_date());
+        assert_eq!(iso("2024-05-01T01:30:00+02:00").to_time(), iso("1970-01-01T23:30:00").to_time());
+        assert!(iso("2024-05-01T10:00:00+25:00").to_datetime().is_err());
+    }
+
+    #[test]
+    fn date_1904() {
+        let cell = |kind: CellType, value: &str| Cell {
+            row: 0,
+            col: 0,
+            kind,
+            value: value.to_owned(),
+        };
+        // 2024-02-29 is serial 45351 in the 1900 date system and 43889 in the 1904 one
+        assert_eq!(cell(CellType::NumberDate1904, "43889").to_date(), Ok(19_782));
+        assert_eq!(cell(CellType::NumberDate1904, "43889").to_date(), cell(CellType::NumberDate1900, "45351").to_date());
+        assert_eq!(cell(CellType::NumberDateTime1904, "43889.5").to_datetime(), cell(CellType::NumberDateTime1900, "45351.5").to_datetime());
+        assert_eq!(cell(CellType::NumberDate1904, "0").to_date(), Ok(-24_107)); // 1904-01-01
+    }
+
+    #[test]
+    fn bigint_with_exponent() {
+        let number = |value: &str| Cell {
+            row: 0,
+            col: 0,
+            kind: CellType::Number,
+            value: value.to_owned(),
+        };
+        assert_eq!(number("100000").to_bigint(), Ok(100000));
+        assert_eq!(number("1e+05").to_bigint(), Ok(100000));
+        assert_eq!(number("1.5E5").to_bigint(), Ok(150000));
+        assert!(number("1e-05").to_bigint().is_err());
+    }
+
+    #[test]
+    fn composite_duration() {
+        const HOUR: i64 = 3_600_000_000;
+        assert_eq!(parse_duration("2 05:30:00"), Some((2, 5 * HOUR + 30 * 60_000_000)));
+        assert_eq!(parse_duration(" 0 00:00:01.5 "), Some((0, 1_500_000)));
+        assert_eq!(parse_duration("12:30"), Some((0, 12 * HOUR + 30 * 60_000_000)));
+        assert_eq!(parse_duration("49:00:00"), Some((2, HOUR)));
+        assert_eq!(parse_duration("-1 02:00:00"), Some((-1, -2 * HOUR)));
+        assert_eq!(parse_duration("2 5:30:00"), Some((2, 5 * HOUR + 30 * 60_000_000)));
+        assert!(parse_duration("2 05:60:00").is_none());
+        assert!(parse_duration("2 days").is_none());
+        assert!(parse_duration("05:30:00:00").is_none());
+        assert!(parse_duration("2024-05-01 10:00:00").is_none());
+
+        let cell = |kind: CellType, value: &str| Cell {
+            row: 0,
+            col: 0,
+            kind,
+            value: value.to_owned(),
+        };
+        assert_eq!(cell(CellType::InlineString, "2 05:30:00").to_interval(), Ok((0, 2, 5 * HOUR + 30 * 60_000_000)));
+        assert_eq!(cell(CellType::NumberTime1900, "2.25").to_interval(), Ok((0, 2, 6 * HOUR)));
+        assert!(cell(CellType::InlineString, "soon").to_interval().is_err());
+    }
+}
@@ -1,5 +1,8 @@
 use crate::database::range::Range;
+use crate::spreadsheet::locale::DateLocale;
+use crate::spreadsheet::MAX_HEADER_LENGTH;
 use glob::Pattern;
+use std::collections::HashMap;
 use std::collections::HashSet;
 
 /// Criteria for filtering and selecting data from spreadsheets.
@@ -14,9 +17,20 @@ pub(crate) struct Criteria {
     /// Data range within sheets to extract.
     pub(crate) range: Option<Range>,
 
+    /// Disjoint blocks of cells within `range`, read as one table without the columns
+    /// and cells outside every block; empty to read the whole of `range`.
+    pub(crate) ranges: Vec<Range>,
+
     /// Maximum number of rows to read per sheet.
     pub(crate) rows_limit: Option<usize>,
 
+    /// Number of rows sampled uniformly per sheet besides the first row, instead of `rows_limit`.
+    pub(crate) sample_rows: Option<usize>,
+
+    /// Consecutive values of the same type after which a column is stable; the rows are
+    /// read up to `rows_limit` only while some column is not.
+    pub(crate) stable_rows: Option<usize>,
+
     /// null literals (default: empty string)
     pub(crate) nulls: HashSet<String>,
 
@@ -28,6 +42,108 @@ pub(crate) struct Criteria {
 
     /// Stop reading when encountering a completely empty row.
     pub(crate) end_at_empty_row: bool,
+
+    /// Classify ISO datetimes whose time part is midnight as dates.
+    pub(crate) midnight_as_date: bool,
+
+    /// Classify ISO datetimes on the 1900/1904 epoch date as times.
+    pub(crate) epoch_date_as_time: bool,
+
+    /// Classify text durations like "2 05:30:00" as intervals.
+    pub(crate) duration_as_interval: bool,
+
+    /// Render numeric cells as text using their number format.
+    pub(crate) display_values: bool,
+
+    /// Include hidden, zero-width and collapsed outline columns.
+    pub(crate) include_hidden_columns: bool,
+
+    /// Fail when every header cell is empty or a null literal.
+    pub(crate) require_header: bool,
+
+    /// Locale whose month names are parsed in text dates (e.g. "Dez 2024").
+    pub(crate) date_locale: Option<DateLocale>,
+
+    /// Only track which rows hold cells, dropping cell values (row counting).
+    pub(crate) count_only: bool,
+
+    /// Maximum number of characters kept in text cells and the suffix marking truncated values.
+    pub(crate) max_cell_length: Option<(usize, String)>,
+
+    /// Hash cells while parsing instead of keeping them (sheet checksums).
+    pub(crate) checksum: bool,
+
+    /// Canonical column names by header text, applied before column presets.
+    pub(crate) header_aliases: HashMap<String, String>,
+
+    /// Template naming the columns without a header, with `{n}` (1-based position) and
+    /// `{col}` (column letter) placeholders; when unset, such columns are named by their letter.
+    pub(crate) missing_header_name: Option<String>,
+
+    /// Maximum number of characters of the column names taken from headers.
+    pub(crate) max_header_length: usize,
+
+    /// Lowercase the column names taken from headers, strip their accents and replace
+    /// anything but letters and digits with underscores, so they need no quoting.
+    pub(crate) normalize_names: bool,
+
+    /// Convert the analyzed rows to the detected column types and count the NULLs and failures.
+    pub(crate) validate: bool,
+
+    /// Keep the cells parsed before a structural error, recording it as an anomaly of the sheet.
+    pub(crate) lenient: bool,
+
+    /// Read the values cells held before the tracked changes of the workbook instead of the latest ones.
+    pub(crate) original_values: bool,
+
+    /// Read the formulas of formula cells, as text starting with `=`, instead of their cached values.
+    pub(crate) formulas: bool,
+
+    /// Repair text whose UTF-8 bytes were decoded as Windows-1252 or Latin-1 (mojibake).
+    pub(crate) fix_encoding: bool,
+
+    /// Type every column as VARCHAR instead of detecting its type, columns given a type still keep it.
+    pub(crate) all_varchar: bool,
+}
+
+impl Default for Criteria {
+    /// Reads every sheet in full with no null literal, including hidden columns and keeping
+    /// header names up to the default length; every other option is off.
+    fn default() -> Criteria {
+        Criteria {
+            sheet_name_patterns: None,
+            sheet_limit: None,
+            range: None,
+            ranges: Vec::new(),
+            rows_limit: None,
+            sample_rows: None,
+            stable_rows: None,
+            nulls: HashSet::new(),
+            error_as_null: false,
+            skip_empty_rows: false,
+            end_at_empty_row: false,
+            midnight_as_date: false,
+            epoch_date_as_time: false,
+            duration_as_interval: false,
+            display_values: false,
+            include_hidden_columns: true,
+            require_header: false,
+            date_locale: None,
+            count_only: false,
+            max_cell_length: None,
+            checksum: false,
+            header_aliases: HashMap::new(),
+            missing_header_name: None,
+            max_header_length: MAX_HEADER_LENGTH,
+            normalize_names: false,
+            validate: false,
+            lenient: false,
+            original_values: false,
+            formulas: false,
+            fix_encoding: false,
+            all_varchar: false,
+        }
+    }
 }
 
 impl Criteria {
@@ -1,124 +1,454 @@
 //! Microsoft Office Excel Helpers
 use crate::error::RustySheetError;
 use crate::helpers::cfb::Cfb;
+use crate::helpers::crypto::decrypt_package;
 use crate::helpers::reader::UnifiedReader;
 use crate::helpers::xml::XmlNodeHelper;
-use crate::helpers::zip::ZipHelper;
+use crate::helpers::zip::ZipPackage;
 use crate::match_xml_events;
 use crate::spreadsheet::cell::CellType;
+use crate::spreadsheet::format::builtin_number_format_code;
+use crate::spreadsheet::format::NumberFormat;
+use crate::spreadsheet::metadata::parse_timestamp;
+use crate::spreadsheet::metadata::read_properties;
+use crate::spreadsheet::metadata::WorkbookMetadata;
 use crate::spreadsheet::SpreadsheetError;
 use quick_xml::events::Event;
 use std::borrow::Cow;
 use std::collections::HashMap;
-use zip::ZipArchive;
+use std::io::Cursor;
 
+/// Path of the core properties part: title, creator and timestamps
+const CORE_PROPERTIES_PART: &str = "docProps/core.xml";
+/// Path of the extended properties part: application name
+const APP_PROPERTIES_PART: &str = "docProps/app.xml";
 /// XML tag name for relationship elements in Excel files
 const TAG_RELATIONSHIP: &[u8] = b"Relationship";
+/// XML tag name for RGB colors in the theme
+const TAG_SRGB_COLOR: &[u8] = b"srgbClr";
+/// XML tag name for system colors in the theme
+const TAG_SYSTEM_COLOR: &[u8] = b"sysClr";
+
+/// Color scheme elements of the theme, in the order theme color indexes refer to them
+const THEME_COLOR_SLOTS: [&[u8]; 12] = [
+    b"lt1", b"dk1", b"lt2", b"dk2",
+    b"accent1", b"accent2", b"accent3", b"accent4", b"accent5", b"accent6",
+    b"hlink", b"folHlink",
+];
+
+/// Default color palette of indexed colors 8 to 63
+const DEFAULT_PALETTE: [u32; 56] = [
+    0x000000, 0xFFFFFF, 0xFF0000, 0x00FF00, 0x0000FF, 0xFFFF00, 0xFF00FF, 0x00FFFF,
+    0x800000, 0x008000, 0x000080, 0x808000, 0x800080, 0x008080, 0xC0C0C0, 0x808080,
+    0x9999FF, 0x993366, 0xFFFFCC, 0xCCFFFF, 0x660066, 0xFF8080, 0x0066CC, 0xCCCCFF,
+    0x000080, 0xFF00FF, 0xFFFF00, 0x00FFFF, 0x800080, 0x800000, 0x008080, 0x0000FF,
+    0x00CCFF, 0xCCFFFF, 0xCCFFCC, 0xFFFF99, 0x99CCFF, 0xFF99CC, 0xCC99FF, 0xFFCC99,
+    0x3366FF, 0x33CCCC, 0x99CC00, 0xFFCC00, 0xFF9900, 0xFF6600, 0x666699, 0x969696,
+    0x003366, 0x339966, 0x003300, 0x333300, 0x993300, 0x993366, 0x333399, 0x333333,
+];
+
+/// Color reference of a workbook element, such as a sheet tab
+pub(super) enum Color {
+    /// ARGB value
+    Rgb(u32),
+    /// Index into the color palette
+    Indexed(usize),
+    /// Index into the theme colors
+    Theme(usize),
+}
+
+/// Contents of an opened Excel file
+pub(super) struct Workbook {
+    /// Zip archive handle
+    pub(super) zip: ZipPackage<UnifiedReader>,
+    /// Number format mappings
+    pub(super) number_formats: Vec<NumberFormat>,
+    /// List of sheet names and their paths
+    pub(super) sheets: Vec<(String, String)>,
+    /// Path of the shared string table, if the workbook declares one
+    pub(super) shared_strings: Option<String>,
+    /// Theme colors resolving theme color references, see `load_theme_colors`
+    pub(super) theme_colors: Vec<u32>,
+    /// Whether serial dates count from 1904-01-01
+    pub(super) is_1904: bool,
+}
+
+/// Parts of an Excel workbook found through its relationships
+pub(super) struct Relationships {
+    /// Mapping of relationship IDs to worksheet paths
+    pub(super) worksheets: HashMap<String, String>,
+    /// Path of the shared string table, if the workbook declares one
+    pub(super) shared_strings: Option<String>,
+    /// Path of the styles part, if the workbook declares one
+    pub(super) styles: Option<String>,
+    /// Path of the theme part, if the workbook declares one
+    pub(super) theme: Option<String>,
+}
 
 /// Opens an Excel file and loads its contents
 ///
 /// # Arguments
 /// * `file_name` - Path to the Excel file
+/// * `salvage` - Whether to recover the entries of an archive with a damaged central directory
+/// * `snapshot` - Whether to read a consistent copy of a local file instead of the file itself
+/// * `password` - Password decrypting an encrypted package
 /// * `load_workbook` - Function to load workbook metadata and sheets
 /// * `load_number_formats` - Function to load number formatting information
 ///
 /// # Returns
-/// Tuple containing:
-/// - Zip archive handle
-/// - Number format mappings
-/// - List of sheet names and their paths
-pub(super) fn open<W, F>(file_name: &str, load_workbook: W, load_number_formats: F) -> Result<(
-    ZipArchive<UnifiedReader>,
-    Vec<CellType>,
-    Vec<(String, String)>
-), RustySheetError>
+/// The package with its number formats, sheets, shared string table path, theme colors and date system
+pub(super) fn open<W, F>(file_name: &str, salvage: bool, snapshot: bool, password: Option<&str>, load_workbook: W, load_number_formats: F) -> Result<Workbook, RustySheetError>
 where
-    W: Fn(&mut ZipArchive<UnifiedReader>) -> Result<(Vec<(String, String)>, bool), RustySheetError>,
-    F: Fn(&mut ZipArchive<UnifiedReader>, bool) -> Result<Vec<CellType>, RustySheetError>,
+    W: Fn(&mut ZipPackage<UnifiedReader>) -> Result<(Vec<(String, String)>, bool, Relationships), RustySheetError>,
+    F: Fn(&mut ZipPackage<UnifiedReader>, Option<&str>, bool) -> Result<Vec<NumberFormat>, RustySheetError>,
 {
     // Open file from local path or remote URL
-    let mut reader = UnifiedReader::new(file_name)?;
+    let mut reader = UnifiedReader::open(file_name, snapshot)?;
     
-    // Check if password protected
-    if is_password_protected(&mut reader) {
-        Err(SpreadsheetError::SpreadsheetPasswordProtectedError(file_name.to_owned()))?;
+    // Decrypt password protected packages in memory
+    if let Some(cfb) = encrypted_package(&mut reader) {
+        let password = password.ok_or_else(|| SpreadsheetError::SpreadsheetPasswordProtectedError(file_name.to_owned()))?;
+        reader = UnifiedReader::from(Cursor::new(decrypt_package(&cfb, password)?));
     }
 
-    let mut zip = ZipArchive::new(reader)?;
-    let (sheets, is_1904) = load_workbook(&mut zip)?;
+    let mut zip = ZipPackage::open(reader, salvage)?;
+    let (sheets, is_1904, relationships) = load_workbook(&mut zip)?;
     if sheets.is_empty() {
         Err(SpreadsheetError::SpreadsheetEmptyError(file_name.to_owned()))?
     }
 
-    let number_formats = load_number_formats(&mut zip, is_1904)?;
-    Ok((zip, number_formats, sheets))
+    // Report every worksheet whose part is missing at once, rather than failing mid-scan on the first
+    let mut missing_parts = Vec::<String>::new();
+    for (sheet_name, zip_path) in &sheets {
+        if zip.file(zip_path)?.is_none() {
+            missing_parts.push(format!("'{sheet_name}' ({zip_path})"));
+        }
+    }
+    if !missing_parts.is_empty() {
+        Err(SpreadsheetError::MissingPartsError(file_name.to_owned(), missing_parts.join(", ")))?
+    }
+
+    let number_formats = load_number_formats(&mut zip, relationships.styles.as_deref(), is_1904)?;
+    let theme_colors = load_theme_colors(&mut zip, relationships.theme.as_deref())?;
+    Ok(Workbook {
+        zip,
+        number_formats,
+        sheets,
+        shared_strings: relationships.shared_strings,
+        theme_colors,
+        is_1904,
+    })
 }
 
-/// Loads worksheet relationships from an Excel file
+/// Opens a second, independent package on a local Excel file
+///
+/// Lets one part be read on another thread while the original package keeps
+/// parsing. Remote files and archives that only open with salvage return None.
+pub(super) fn reopen(file_name: &str) -> Option<ZipPackage<UnifiedReader>> {
+    if UnifiedReader::is_remote_url(file_name) {
+        return None;
+    }
+    UnifiedReader::new(file_name).ok()
+        .and_then(|reader| ZipPackage::new(reader).ok())
+}
+
+/// Loads the workbook relationships from an Excel file
+///
+/// Shared strings and styles are located by relationship type rather than by
+/// their usual paths, since some producers store them elsewhere.
 ///
 /// # Arguments
 /// * `zip` - Zip archive handle
 /// * `path` - Path to the relationships XML file within the archive
 ///
 /// # Returns
-/// Mapping of relationship IDs to worksheet paths
-pub(super) fn load_relationships(zip: &mut ZipArchive<UnifiedReader>, path: &str) -> Result<HashMap<String, String>, RustySheetError> {
+/// Worksheet paths by relationship ID, and the shared strings, styles and theme paths
+pub(super) fn load_relationships(zip: &mut ZipPackage<UnifiedReader>, path: &str) -> Result<Relationships, RustySheetError> {
     let mut reader = zip.xml_reader(path)?
         .ok_or_else(|| SpreadsheetError::FileError(path.to_string()))?;
-    let mut relationships: HashMap<String, String> = HashMap::new();
+    let mut relationships = Relationships {
+        worksheets: HashMap::new(),
+        shared_strings: None,
+        styles: None,
+        theme: None,
+    };
     match_xml_events!(reader => {
         Event::Start(event) if event.local_name().as_ref() == TAG_RELATIONSHIP => {
             let id = event.get_attribute_value("Id")?;
             let kind = event.get_attribute_value("Type")?;
             let target = event.get_attribute_value("Target")?;
-            // Only process worksheet relationships
-            if kind.map(|it| it.ends_with("/worksheet")).unwrap_or(true) {
-                if let Some((id, target)) = id.zip(target) {
-                    relationships.insert(id.to_string(), to_zip_path(target));
+            match (kind, target) {
+                (Some(kind), Some(target)) if kind.ends_with("/sharedStrings") => {
+                    relationships.shared_strings.get_or_insert_with(|| to_zip_path(target));
+                }
+                (Some(kind), Some(target)) if kind.ends_with("/styles") => {
+                    relationships.styles.get_or_insert_with(|| to_zip_path(target));
                 }
+                (Some(kind), Some(target)) if kind.ends_with("/theme") => {
+                    relationships.theme.get_or_insert_with(|| to_zip_path(target));
+                }
+                // Untyped relationships are taken as worksheets
+                (kind, Some(target)) if kind.as_ref().map(|it| it.ends_with("/worksheet")).unwrap_or(true) => {
+                    if let Some(id) = id {
+                        relationships.worksheets.insert(id.to_string(), to_zip_path(target));
+                    }
+                }
+                _ => (),
+            }
+        }
+    });
+    Ok(relationships)
+}
+
+/// Loads the relationships of a package part other than the workbook, such as the
+/// comments of a worksheet
+///
+/// # Arguments
+/// * `zip` - Zip archive handle
+/// * `part` - Path of the part within the archive, e.g. `xl/worksheets/sheet1.xml`
+///
+/// # Returns
+/// (type, path) pairs in document order, the type being the last segment of the
+/// relationship type (e.g. `comments`); empty when the part has no relationships
+pub(super) fn load_part_relationships(zip: &mut ZipPackage<UnifiedReader>, part: &str) -> Result<Vec<(String, String)>, RustySheetError> {
+    let (folder, file_name) = part.rsplit_once('/').unwrap_or(("", part));
+    let Some(mut reader) = zip.xml_reader(&format!("{folder}/_rels/{file_name}.rels"))? else {
+        return Ok(Vec::new());
+    };
+    let mut relationships = Vec::<(String, String)>::new();
+    match_xml_events!(reader => {
+        Event::Start(event) if event.local_name().as_ref() == TAG_RELATIONSHIP => {
+            let external = event.get_attribute_value("TargetMode")?.is_some_and(|mode| mode == "External");
+            let kind = event.get_attribute_value("Type")?;
+            let target = event.get_attribute_value("Target")?;
+            if let (false, Some(kind), Some(target)) = (external, kind, target) {
+                // Targets are relative to the folder of the part, unless absolute
+                let path = if target.starts_with('/') { target } else { Cow::Owned(format!("{folder}/{target}")) };
+                relationships.push((kind.rsplit('/').next().unwrap_or_default().to_owned(), to_zip_path(path)));
             }
         }
     });
     Ok(relationships)
 }
 
-/// Maps format indexes to cell types using custom and built-in formats
+/// Loads the targets of the hyperlinks of a worksheet part by relationship ID
+///
+/// # Arguments
+/// * `zip` - Zip archive handle
+/// * `part` - Path of the worksheet part within the archive
+///
+/// # Returns
+/// Targets as written in the relationships, usually external URLs
+pub(super) fn load_hyperlink_targets(zip: &mut ZipPackage<UnifiedReader>, part: &str) -> Result<HashMap<String, String>, RustySheetError> {
+    let (folder, file_name) = part.rsplit_once('/').unwrap_or(("", part));
+    let Some(mut reader) = zip.xml_reader(&format!("{folder}/_rels/{file_name}.rels"))? else {
+        return Ok(HashMap::new());
+    };
+    let mut targets = HashMap::<String, String>::new();
+    match_xml_events!(reader => {
+        Event::Start(event) if event.local_name().as_ref() == TAG_RELATIONSHIP => {
+            let id = event.get_attribute_value("Id")?;
+            let kind = event.get_attribute_value("Type")?;
+            let target = event.get_attribute_value("Target")?;
+            if let (Some(id), Some(kind), Some(target)) = (id, kind, target) {
+                if kind.ends_with("/hyperlink") {
+                    targets.insert(id.to_string(), target.to_string());
+                }
+            }
+        }
+    });
+    Ok(targets)
+}
+
+/// Maps format indexes to number formats using custom and built-in formats
 ///
 /// # Arguments
 /// * `format_indexes` - List of format identifiers
-/// * `custom_formats` - Custom format mappings defined in the workbook
+/// * `custom_formats` - Custom format codes defined in the workbook
 /// * `is_1904` - Whether the workbook uses the 1904 date system
 ///
 /// # Returns
-/// Vector of cell types corresponding to each format index
-pub(super) fn load_number_formats(format_indexes: Vec<String>, custom_formats: HashMap<String, CellType>, is_1904: bool) -> Vec<CellType> {
+/// Vector of number formats corresponding to each format index
+pub(super) fn load_number_formats(format_indexes: Vec<String>, custom_formats: HashMap<String, String>, is_1904: bool) -> Vec<NumberFormat> {
     format_indexes
         .iter()
         .map(|id| {
-            custom_formats
-                .get(id)
-                .map(Clone::clone)
-                .or_else(|| CellType::parse_builtin_number_format_id(id, is_1904))
-                .unwrap_or(CellType::Number)
+            if let Some(code) = custom_formats.get(id) {
+                NumberFormat {
+                    kind: CellType::parse_custom_number_format(code, is_1904),
+                    code: code.to_owned(),
+                }
+            } else {
+                NumberFormat {
+                    kind: CellType::parse_builtin_number_format_id(id, is_1904).unwrap_or(CellType::Number),
+                    code: builtin_number_format_code(id).to_owned(),
+                }
+            }
         })
         .collect()
 }
 
+/// Loads the color scheme of the workbook theme
+///
+/// # Arguments
+/// * `zip` - Zip archive handle
+/// * `path` - Path of the theme part, if the workbook declares one
+///
+/// # Returns
+/// Theme colors as `0xRRGGBB`, in the order theme color indexes refer to them
+/// (light 1, dark 1, light 2, dark 2, accents, hyperlinks); empty without a theme
+pub(super) fn load_theme_colors(zip: &mut ZipPackage<UnifiedReader>, path: Option<&str>) -> Result<Vec<u32>, RustySheetError> {
+    let Some(mut reader) = path.map(|path| zip.xml_reader(path)).transpose()?.flatten() else {
+        return Ok(Vec::new());
+    };
+    let mut colors = HashMap::<String, u32>::new();
+    let mut slot = None::<String>;
+    match_xml_events!(reader => {
+        Event::Start(event) if THEME_COLOR_SLOTS.contains(&event.local_name().as_ref()) => {
+            slot = Some(String::from_utf8_lossy(event.local_name().as_ref()).into_owned());
+        }
+        Event::Start(event) if slot.is_some() && matches!(event.local_name().as_ref(), TAG_SRGB_COLOR | TAG_SYSTEM_COLOR) => {
+            // System colors carry the color they last resolved to
+            let value = if event.local_name().as_ref() == TAG_SRGB_COLOR {
+                event.get_attribute_value("val")?
+            } else {
+                event.get_attribute_value("lastClr")?
+            };
+            if let (Some(slot), Some(rgb)) = (slot.take(), value.and_then(|it| u32::from_str_radix(&it, 16).ok())) {
+                colors.insert(slot, rgb);
+            }
+        }
+        Event::End(event) if THEME_COLOR_SLOTS.contains(&event.local_name().as_ref()) => slot = None,
+    });
+    Ok(THEME_COLOR_SLOTS.iter()
+        .map_while(|name| colors.get(String::from_utf8_lossy(name).as_ref()).copied())
+        .collect())
+}
+
+/// Loads the document properties of a package from `docProps/core.xml` and `docProps/app.xml`
+///
+/// # Arguments
+/// * `zip` - Zip archive handle
+/// * `sheet_count` - Number of worksheets of the workbook
+/// * `is_1904` - Whether the workbook uses the 1904 date system
+///
+/// # Returns
+/// The properties; those of a missing part are None
+pub(super) fn load_document_properties(zip: &mut ZipPackage<UnifiedReader>, sheet_count: usize, is_1904: bool) -> Result<WorkbookMetadata, RustySheetError> {
+    let core = match zip.xml_reader(CORE_PROPERTIES_PART)? {
+        Some(mut reader) => read_properties(&mut reader, &["title", "creator", "created", "modified"])?,
+        None => HashMap::new(),
+    };
+    let app = match zip.xml_reader(APP_PROPERTIES_PART)? {
+        Some(mut reader) => read_properties(&mut reader, &["Application"])?,
+        None => HashMap::new(),
+    };
+    Ok(WorkbookMetadata {
+        title: core.get("title").cloned(),
+        author: core.get("creator").cloned(),
+        created: core.get("created").map(String::as_str).and_then(parse_timestamp),
+        modified: core.get("modified").map(String::as_str).and_then(parse_timestamp),
+        application: app.get("Application").cloned(),
+        sheet_count,
+        is_1904,
+    })
+}
+
+/// Resolves a color reference to its `#RRGGBB` text
+///
+/// # Arguments
+/// * `color` - Color reference as stored in the workbook
+/// * `tint` - Lightening (positive) or darkening (negative) factor between -1 and 1
+/// * `theme_colors` - Theme colors, see `load_theme_colors`
+///
+/// # Returns
+/// The color, or None for automatic, system and unresolved colors
+pub(super) fn to_hex_color(color: Color, tint: f64, theme_colors: &[u32]) -> Option<String> {
+    let rgb = match color {
+        Color::Rgb(argb) => argb & 0xFFFFFF,
+        // Indexes 0 to 7 repeat the first eight palette colors
+        Color::Indexed(index) => *DEFAULT_PALETTE.get(if index < 8 { index } else { index - 8 })?,
+        Color::Theme(index) => *theme_colors.get(index)?,
+    };
+    let rgb = if tint == 0.0 { rgb } else { apply_tint(rgb, tint) };
+    Some(format!("#{rgb:06X}"))
+}
+
+/// Lightens or darkens a color by changing its HSL luminance, as Excel applies tints
+fn apply_tint(rgb: u32, tint: f64) -> u32 {
+    let [_, red, green, blue] = rgb.to_be_bytes().map(|it| it as f64 / 255.0);
+    let max = red.max(green).max(blue);
+    let min = red.min(green).min(blue);
+    let luminance = (max + min) / 2.0;
+    let (hue, saturation) = if max == min {
+        (0.0, 0.0)
+    } else {
+        let delta = max - min;
+        let saturation = if luminance > 0.5 { delta / (2.0 - max - min) } else { delta / (max + min) };
+        let hue = if max == red {
+            (green - blue) / delta + if green < blue { 6.0 } else { 0.0 }
+        } else if max == green {
+            (blue - red) / delta + 2.0
+        } else {
+            (red - green) / delta + 4.0
+        };
+        (hue / 6.0, saturation)
+    };
+
+    let luminance = if tint < 0.0 {
+        luminance * (1.0 + tint)
+    } else {
+        luminance * (1.0 - tint) + tint
+    };
+    let upper = if luminance < 0.5 { luminance * (1.0 + saturation) } else { luminance + saturation - luminance * saturation };
+    let lower = 2.0 * luminance - upper;
+    let channel = |offset: f64| {
+        let hue = (hue + offset).rem_euclid(1.0);
+        let value = if saturation == 0.0 {
+            luminance
+        } else if hue < 1.0 / 6.0 {
+            lower + (upper - lower) * 6.0 * hue
+        } else if hue < 1.0 / 2.0 {
+            upper
+        } else if hue < 2.0 / 3.0 {
+            lower + (upper - lower) * (2.0 / 3.0 - hue) * 6.0
+        } else {
+            lower
+        };
+        (value.clamp(0.0, 1.0) * 255.0).round() as u32
+    };
+    channel(1.0 / 3.0) << 16 | channel(0.0) << 8 | channel(-1.0 / 3.0)
+}
+
 /// Normalizes a path to ensure it points to the correct location within the Excel zip archive
 ///
+/// Absolute targets are resolved from the package root, relative targets from `xl/`,
+/// and `.` / `..` segments are collapsed.
+///
 /// # Arguments
 /// * `path` - Original path from relationship or reference
 ///
 /// # Returns
 /// Normalized path suitable for accessing files within the zip archive
 pub(crate) fn to_zip_path(path: Cow<'_, str>) -> String {
-    if path.starts_with("/xl/") {
-        path[1..].to_string()
+    let path = if let Some(path) = path.strip_prefix('/') {
+        path.to_string()
     } else if path.starts_with("xl/") {
         path.to_string()
     } else {
         format!("xl/{path}")
+    };
+    let mut segments = Vec::<&str>::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
     }
+    segments.join("/")
 }
 
 /// Checks if an Excel file is password protected
@@ -127,11 +457,8 @@ pub(crate) fn to_zip_path(path: Cow<'_, str>) -> String {
 /// * `reader` - File reader positioned at the beginning of the file
 ///
 /// # Returns
-/// `true` if the file contains an encrypted package, `false` otherwise
-fn is_password_protected(reader: &mut UnifiedReader) -> bool {
-    if let Ok(cfb) = Cfb::new(reader) {
-        cfb.exists("EncryptedPackage")
-    } else {
-        false
-    }
+/// The compound file holding the encrypted package, `None` for a plain package
+fn encrypted_package(reader: &mut UnifiedReader) -> Option<Cfb> {
+    Cfb::new(reader).ok()
+        .filter(|cfb| cfb.exists("EncryptedPackage"))
 }
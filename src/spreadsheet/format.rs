@@ -0,0 +1,561 @@
+//! Excel number format codes and a renderer producing the text Excel displays.
+
+use crate::spreadsheet::cell::CellType;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+
+/// Month names used by `mmm`, `mmmm` and `mmmmm` date tokens
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// Day names used by `ddd` and `dddd` date tokens
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+/// Number format of a cell style: the detected cell type and the original format code.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct NumberFormat {
+    /// Cell type derived from the format code
+    pub(crate) kind: CellType,
+    /// Excel format code, e.g. `0.00%` or `#,##0`
+    pub(crate) code: String,
+}
+
+impl NumberFormat {
+    /// Renders a raw numeric cell value as Excel would display it.
+    /// Values that are not finite numbers, such as `NaN` or `inf`, are returned unchanged.
+    pub(crate) fn render(&self, value: &str) -> String {
+        match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => render(&self.code, number, is_1904(self.kind)),
+            _ => value.to_owned(),
+        }
+    }
+}
+
+/// Returns true if the cell type holds a number displayed through a number format.
+pub(crate) fn is_formatted(kind: CellType) -> bool {
+    matches!(
+        kind,
+        CellType::Number
+            | CellType::NumberDateTime1900 | CellType::NumberDateTime1904
+            | CellType::NumberDate1900 | CellType::NumberDate1904
+            | CellType::NumberTime1900 | CellType::NumberTime1904
+    )
+}
+
+/// Renders a raw numeric cell value with its number format, or `General` if it has none.
+pub(crate) fn render_value(format: Option<&NumberFormat>, value: &str) -> String {
+    match (format, value.parse::<f64>()) {
+        (Some(format), _) => format.render(value),
+        (None, Ok(number)) if number.is_finite() => render_general(number),
+        (None, _) => value.to_owned(),
+    }
+}
+
+/// Returns the format code of a built-in Excel number format ID.
+pub(crate) fn builtin_number_format_code(id: &str) -> &'static str {
+    match id {
+        "1" => "0",
+        "2" => "0.00",
+        "3" => "#,##0",
+        "4" => "#,##0.00",
+        "9" => "0%",
+        "10" => "0.00%",
+        "11" => "0.00E+00",
+        "14" => "m/d/yyyy",
+        "15" => "d-mmm-yy",
+        "16" => "d-mmm",
+        "17" => "mmm-yy",
+        "18" => "h:mm AM/PM",
+        "19" => "h:mm:ss AM/PM",
+        "20" => "h:mm",
+        "21" => "h:mm:ss",
+        "22" => "m/d/yyyy h:mm",
+        "37" => "#,##0 ;(#,##0)",
+        "38" => "#,##0 ;[Red](#,##0)",
+        "39" => "#,##0.00;(#,##0.00)",
+        "40" => "#,##0.00;[Red](#,##0.00)",
+        "45" => "mm:ss",
+        "46" => "[h]:mm:ss",
+        "47" => "mm:ss.0",
+        "48" => "##0.0E+0",
+        "49" => "@",
+        _ => "General",
+    }
+}
+
+/// Returns true if the cell type uses the 1904 date system.
+fn is_1904(kind: CellType) -> bool {
+    matches!(kind, CellType::NumberDateTime1904 | CellType::NumberDate1904 | CellType::NumberTime1904)
+}
+
+/// Token of a format code section
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// Literal text copied to the output
+    Literal(String),
+    /// Digit placeholder: `0`, `#` or `?`
+    Digit(char),
+    /// Decimal point
+    Point,
+    /// Thousands separator or scaling comma
+    Comma,
+    /// Percent sign, multiplies the value by 100
+    Percent,
+    /// Scientific notation marker with its sign (`+` or `-`)
+    Exponent(char),
+    /// Text placeholder `@`
+    Text,
+    /// Date or time token such as `yyyy`, `mm`, `ss`, `[h]` or `AM/PM`
+    DateTime(String),
+    /// `General` keyword
+    General,
+}
+
+/// Renders a number with an Excel format code.
+pub(crate) fn render(code: &str, number: f64, is_1904: bool) -> String {
+    let sections = split_sections(code);
+    let (section, number, signed) = if sections.len() >= 3 && number == 0f64 {
+        (sections[2].as_str(), number, false)
+    } else if sections.len() >= 2 && number < 0f64 {
+        (sections[1].as_str(), -number, false)
+    } else {
+        (sections[0].as_str(), number, true)
+    };
+
+    let tokens = tokenize(section);
+    if tokens.iter().any(|token| matches!(token, Token::DateTime(_))) {
+        render_datetime(&tokens, number, is_1904)
+    } else if tokens.iter().any(|token| matches!(token, Token::Digit(_))) {
+        let text = render_number(&tokens, number.abs());
+        if signed && number < 0f64 && text.chars().any(|char| char.is_ascii_digit() && char != '0') {
+            format!("-{text}")
+        } else {
+            text
+        }
+    } else {
+        let general = render_general(if signed { number } else { number.abs() });
+        tokens.iter().map(|token| match token {
+            Token::Literal(text) => text.to_owned(),
+            Token::General | Token::Text => general.to_owned(),
+            _ => String::new(),
+        }).collect()
+    }
+}
+
+/// Splits a format code into its `;` separated sections, honoring quotes and escapes.
+fn split_sections(code: &str) -> Vec<String> {
+    let mut sections = vec![String::new()];
+    let mut is_escaped = false;
+    let mut is_literal = false;
+    for char in code.chars() {
+        match char {
+            _ if is_escaped => is_escaped = false,
+            '\\' if !is_literal => is_escaped = true,
+            '"' => is_literal = !is_literal,
+            ';' if !is_literal => {
+                sections.push(String::new());
+                continue;
+            }
+            _ => (),
+        }
+        sections.last_mut().expect("section").push(char);
+    }
+    sections
+}
+
+/// Splits a format code section into tokens.
+fn tokenize(section: &str) -> Vec<Token> {
+    let chars = section.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::<Token>::new();
+    let mut index = 0usize;
+    while index < chars.len() {
+        let char = chars[index];
+        let rest = chars[index..].iter().collect::<String>();
+        let upper = rest.to_ascii_uppercase();
+        index += 1;
+        match char {
+            '"' => {
+                let mut text = String::new();
+                while index < chars.len() && chars[index] != '"' {
+                    text.push(chars[index]);
+                    index += 1;
+                }
+                index += 1;
+                tokens.push(Token::Literal(text));
+            }
+            '\\' => {
+                if index < chars.len() {
+                    tokens.push(Token::Literal(chars[index].to_string()));
+                    index += 1;
+                }
+            }
+            '_' => {
+                // Padding as wide as the next character
+                index += 1;
+                tokens.push(Token::Literal(" ".to_owned()));
+            }
+            '*' => index += 1, // Repeat-to-fill characters are dropped
+            '[' => {
+                let mut text = String::new();
+                while index < chars.len() && chars[index] != ']' {
+                    text.push(chars[index]);
+                    index += 1;
+                }
+                index += 1;
+                // Keep elapsed time tokens, drop colors, conditions and locales
+                let lower = text.to_ascii_lowercase();
+                if !lower.is_empty() && lower.chars().all(|char| char == 'h' || char == 'm' || char == 's') {
+                    tokens.push(Token::DateTime(format!("[{}]", &lower[..1])));
+                }
+            }
+            '0' | '#' | '?' => tokens.push(Token::Digit(char)),
+            '.' => tokens.push(Token::Point),
+            ',' => tokens.push(Token::Comma),
+            '%' => tokens.push(Token::Percent),
+            '@' => tokens.push(Token::Text),
+            'E' | 'e' if rest.len() > 1 && (chars[index] == '+' || chars[index] == '-') => {
+                tokens.push(Token::Exponent(chars[index]));
+                index += 1;
+            }
+            _ if upper.starts_with("GENERAL") => {
+                index += 6;
+                tokens.push(Token::General);
+            }
+            _ if upper.starts_with("AM/PM") => {
+                index += 4;
+                tokens.push(Token::DateTime("AM/PM".to_owned()));
+            }
+            _ if upper.starts_with("A/P") => {
+                index += 2;
+                tokens.push(Token::DateTime("A/P".to_owned()));
+            }
+            'y' | 'Y' | 'm' | 'M' | 'd' | 'D' | 'h' | 'H' | 's' | 'S' => {
+                let lower = char.to_ascii_lowercase();
+                let mut text = lower.to_string();
+                while index < chars.len() && chars[index].to_ascii_lowercase() == lower {
+                    text.push(lower);
+                    index += 1;
+                }
+                tokens.push(Token::DateTime(text));
+            }
+            _ => tokens.push(Token::Literal(char.to_string())),
+        }
+    }
+
+    // Decimal points directly after seconds introduce fractional seconds
+    let mut index = 0usize;
+    while index + 1 < tokens.len() {
+        if matches!(&tokens[index], Token::DateTime(text) if text.starts_with('s') || text == "[s]") && tokens[index + 1] == Token::Point {
+            let mut fraction = String::from(".");
+            let mut next = index + 2;
+            while next < tokens.len() && tokens[next] == Token::Digit('0') {
+                fraction.push('0');
+                next += 1;
+            }
+            tokens.splice(index + 1..next, [Token::DateTime(fraction)]);
+        }
+        index += 1;
+    }
+    tokens
+}
+
+/// Renders a non-negative number with digit placeholder tokens.
+fn render_number(tokens: &[Token], mut number: f64) -> String {
+    let exponent_index = tokens.iter().position(|token| matches!(token, Token::Exponent(_)));
+    let number_end = exponent_index.unwrap_or(tokens.len());
+    let point_index = tokens[..number_end].iter().position(|token| *token == Token::Point);
+    let integer_end = point_index.unwrap_or(number_end);
+
+    let placeholders = |tokens: &[Token]| tokens.iter().filter_map(|token| match token {
+        Token::Digit(char) => Some(*char),
+        _ => None,
+    }).collect::<Vec<_>>();
+    let integer_placeholders = placeholders(&tokens[..integer_end]);
+    let fraction_placeholders = point_index.map(|index| placeholders(&tokens[index + 1..number_end])).unwrap_or_default();
+    let exponent_placeholders = exponent_index.map(|index| placeholders(&tokens[index + 1..])).unwrap_or_default();
+
+    // Commas between integer placeholders group thousands, trailing ones scale by 1000
+    let last_digit = tokens[..integer_end].iter().rposition(|token| matches!(token, Token::Digit(_)));
+    let first_digit = tokens[..integer_end].iter().position(|token| matches!(token, Token::Digit(_)));
+    let mut grouping = false;
+    for (index, token) in tokens[..integer_end].iter().enumerate() {
+        if *token == Token::Comma {
+            if first_digit.map(|first| first < index).unwrap_or(false) && last_digit.map(|last| index < last).unwrap_or(false) {
+                grouping = true;
+            } else if last_digit.map(|last| index > last).unwrap_or(false) {
+                number /= 1000f64;
+            }
+        }
+    }
+    for token in tokens {
+        if *token == Token::Percent {
+            number *= 100f64;
+        }
+    }
+
+    // Scientific notation splits the number into mantissa and exponent
+    let mut exponent = 0i32;
+    if exponent_index.is_some() && number != 0f64 {
+        let width = integer_placeholders.len().max(1) as i32;
+        let magnitude = number.log10().floor() as i32;
+        exponent = if width > 1 && integer_placeholders[0] == '#' {
+            magnitude.div_euclid(width) * width
+        } else {
+            magnitude - (integer_placeholders.iter().filter(|char| **char == '0').count().max(1) as i32 - 1)
+        };
+        number /= 10f64.powi(exponent);
+    }
+
+    // Excel rounds half away from zero
+    let factor = 10f64.powi(fraction_placeholders.len() as i32);
+    let text = format!("{:.*}", fraction_placeholders.len(), (number * factor).round() / factor);
+    let (integer, fraction) = text.split_once('.').unwrap_or((text.as_str(), ""));
+    let integer = if integer == "0" { "" } else { integer };
+
+    // Fill integer placeholders from the right; the leftmost one takes the overflow digits
+    let digits = integer.chars().collect::<Vec<_>>();
+    let mut remaining = digits.len();
+    let mut position = 0usize;
+    let mut integer_outputs = vec![String::new(); integer_placeholders.len()];
+    for (index, placeholder) in integer_placeholders.iter().enumerate().rev() {
+        let chars = if remaining > 0 {
+            let lower = if index == 0 { 0 } else { remaining - 1 };
+            let chars = digits[lower..remaining].to_vec();
+            remaining = lower;
+            chars
+        } else {
+            match placeholder {
+                '0' => vec!['0'],
+                '?' => vec![' '],
+                _ => vec![],
+            }
+        };
+        let mut output = String::new();
+        for char in chars.iter().rev() {
+            if grouping && position > 0 && position % 3 == 0 && char.is_ascii_digit() {
+                output.insert(0, ',');
+            }
+            output.insert(0, *char);
+            if char.is_ascii_digit() {
+                position += 1;
+            }
+        }
+        integer_outputs[index] = output;
+    }
+
+    // Fill fraction placeholders from the left, trimming optional trailing zeros
+    let fraction_digits = fraction.chars().collect::<Vec<_>>();
+    let mut fraction_outputs = fraction_digits.iter().map(char::to_string).collect::<Vec<_>>();
+    for (index, placeholder) in fraction_placeholders.iter().enumerate().rev() {
+        if *placeholder == '0' || fraction_digits[index] != '0' {
+            break;
+        }
+        fraction_outputs[index] = if *placeholder == '?' { " ".to_owned() } else { String::new() };
+    }
+
+    let exponent_digits = format!("{:0width$}", exponent.abs(), width = exponent_placeholders.iter().filter(|char| **char == '0').count());
+
+    let mut output = String::new();
+    let mut integer_index = 0usize;
+    let mut fraction_index = 0usize;
+    let mut exponent_written = false;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Literal(text) => output.push_str(text),
+            Token::Digit(_) if index < integer_end => {
+                output.push_str(&integer_outputs[integer_index]);
+                integer_index += 1;
+            }
+            Token::Digit(_) if index < number_end => {
+                output.push_str(&fraction_outputs[fraction_index]);
+                fraction_index += 1;
+            }
+            Token::Digit(_) => if !exponent_written {
+                output.push_str(&exponent_digits);
+                exponent_written = true;
+            },
+            Token::Point if index < number_end => output.push('.'),
+            Token::Percent => output.push('%'),
+            Token::Exponent(sign) => {
+                output.push('E');
+                if exponent < 0 {
+                    output.push('-');
+                } else if *sign == '+' {
+                    output.push('+');
+                }
+            }
+            Token::Text | Token::General => output.push_str(&render_general(number)),
+            _ => (),
+        }
+    }
+    output
+}
+
+/// Renders a serial date/time number with date and time tokens.
+fn render_datetime(tokens: &[Token], number: f64, is_1904: bool) -> String {
+    // Round to the precision of the fractional seconds shown
+    let precision = tokens.iter().filter_map(|token| match token {
+        Token::DateTime(text) if text.starts_with('.') => Some(text.len() as u32 - 1),
+        _ => None,
+    }).max().unwrap_or(0);
+    let unit = 10i64.pow(precision);
+    let ticks = (number * 86_400f64 * unit as f64).round() as i64;
+    let days = ticks.div_euclid(86_400 * unit);
+    let seconds_of_day = ticks.rem_euclid(86_400 * unit);
+    let fraction = seconds_of_day % unit;
+    let seconds_of_day = seconds_of_day / unit;
+    let (hour, minute, second) = (seconds_of_day / 3600, seconds_of_day / 60 % 60, seconds_of_day % 60);
+    let total_seconds = ticks / unit;
+
+    let offset = if is_1904 { 1462 } else if days < 60 { 1 } else { 0 };
+    // Serials beyond the dates chrono can represent are shown as Excel shows invalid dates
+    let Some(date) = Duration::try_days(days + offset)
+        .and_then(|days| NaiveDate::from_ymd_opt(1899, 12, 30).expect("NaiveDate Literal").checked_add_signed(days))
+    else {
+        return "#####".to_owned();
+    };
+    let has_meridiem = tokens.iter().any(|token| matches!(token, Token::DateTime(text) if text.contains('/')));
+
+    // `m` means minutes right after an hour token or right before a second token
+    let kinds = tokens.iter().filter_map(|token| match token {
+        Token::DateTime(text) => Some(text.as_str()),
+        _ => None,
+    }).collect::<Vec<_>>();
+    let mut minute_flags = Vec::<bool>::new();
+    for (index, kind) in kinds.iter().enumerate() {
+        let after_hour = index > 0 && (kinds[index - 1].starts_with('h') || kinds[index - 1] == "[h]");
+        let before_second = kinds.get(index + 1).map(|next| next.starts_with('s') || *next == "[s]").unwrap_or(false);
+        minute_flags.push(kind.starts_with('m') && kind.len() <= 2 && (after_hour || before_second));
+    }
+
+    let mut output = String::new();
+    let mut kind_index = 0usize;
+    for token in tokens {
+        match token {
+            Token::Literal(text) => output.push_str(text),
+            Token::Digit(char) => output.push(*char),
+            Token::Point => output.push('.'),
+            Token::Comma => output.push(','),
+            Token::Percent => output.push('%'),
+            Token::Text | Token::General => output.push_str(&render_general(number)),
+            Token::Exponent(_) => (),
+            Token::DateTime(text) => {
+                let is_minute = minute_flags[kind_index];
+                kind_index += 1;
+                let value = match text.as_str() {
+                    "yy" | "y" => format!("{:02}", date.year() % 100),
+                    _ if text.starts_with('y') => format!("{:04}", date.year()),
+                    "m" if is_minute => minute.to_string(),
+                    "mm" if is_minute => format!("{minute:02}"),
+                    "m" => date.month().to_string(),
+                    "mm" => format!("{:02}", date.month()),
+                    "mmm" => MONTHS[date.month0() as usize][..3].to_owned(),
+                    "mmmmm" => MONTHS[date.month0() as usize][..1].to_owned(),
+                    _ if text.starts_with('m') => MONTHS[date.month0() as usize].to_owned(),
+                    "d" => date.day().to_string(),
+                    "dd" => format!("{:02}", date.day()),
+                    "ddd" => WEEKDAYS[date.weekday().num_days_from_monday() as usize][..3].to_owned(),
+                    _ if text.starts_with('d') => WEEKDAYS[date.weekday().num_days_from_monday() as usize].to_owned(),
+                    _ if text.starts_with('h') => {
+                        let hour = if has_meridiem { (hour + 11) % 12 + 1 } else { hour };
+                        if text.len() > 1 { format!("{hour:02}") } else { hour.to_string() }
+                    }
+                    "s" => second.to_string(),
+                    _ if text.starts_with('s') => format!("{second:02}"),
+                    "[h]" => (total_seconds / 3600).to_string(),
+                    "[m]" => (total_seconds / 60).to_string(),
+                    "[s]" => total_seconds.to_string(),
+                    "AM/PM" => if hour < 12 { "AM" } else { "PM" }.to_owned(),
+                    "A/P" => if hour < 12 { "A" } else { "P" }.to_owned(),
+                    _ if text.starts_with('.') => format!(".{:0width$}", fraction, width = text.len() - 1),
+                    _ => String::new(),
+                };
+                output.push_str(&value);
+            }
+        }
+    }
+    output
+}
+
+/// Renders a number with the `General` format: up to 11 significant digits.
+fn render_general(number: f64) -> String {
+    if number == 0f64 {
+        return "0".to_owned();
+    }
+    let magnitude = number.abs().log10().floor() as i32;
+    if !(-10..11).contains(&magnitude) {
+        let text = format!("{:.5E}", number);
+        let (mantissa, exponent) = text.split_once('E').expect("Scientific notation");
+        let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+        let exponent = exponent.parse::<i32>().expect("Exponent");
+        format!("{mantissa}E{}{:02}", if exponent < 0 { '-' } else { '+' }, exponent.abs())
+    } else {
+        let decimals = (10 - magnitude).max(0) as usize;
+        let text = format!("{:.*}", decimals, number);
+        if text.contains('.') {
+            text.trim_end_matches('0').trim_end_matches('.').to_owned()
+        } else {
+            text
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spreadsheet::cell::CellType;
+    use crate::spreadsheet::format::render;
+    use crate::spreadsheet::format::render_value;
+    use crate::spreadsheet::format::NumberFormat;
+
+    #[test]
+    fn render_numbers() {
+        assert_eq!(render("General", 1234.5, false), "1234.5");
+        assert_eq!(render("General", 0.1 + 0.2, false), "0.3");
+        assert_eq!(render("0", 2.5, false), "3");
+        assert_eq!(render("0.00", 3.14159, false), "3.14");
+        assert_eq!(render("#,##0", 1234567.0, false), "1,234,567");
+        assert_eq!(render("#,##0.00", -1234.5, false), "-1,234.50");
+        assert_eq!(render("0.00%", 0.1234, false), "12.34%");
+        assert_eq!(render("0%", 0.5, false), "50%");
+        assert_eq!(render("#.##", 1.5, false), "1.5");
+        assert_eq!(render("0.00E+00", 12345.0, false), "1.23E+04");
+        assert_eq!(render("#,##0,\"K\"", 12345.0, false), "12K");
+        assert_eq!(render("000-00-0000", 123456789.0, false), "123-45-6789");
+        assert_eq!(render("\"$\"#,##0.00;(\"$\"#,##0.00)", -5.0, false), "($5.00)");
+        assert_eq!(render("0;-0;\"zero\"", 0.0, false), "zero");
+        assert_eq!(render("[Red]0.0", 1.25, false), "1.3");
+    }
+
+    #[test]
+    fn render_dates() {
+        assert_eq!(render("yyyy-mm-dd", 45352.0, false), "2024-03-01");
+        assert_eq!(render("m/d/yyyy", 45352.0, false), "3/1/2024");
+        assert_eq!(render("d-mmm-yy", 45352.0, false), "1-Mar-24");
+        assert_eq!(render("dddd, mmmm d", 45352.0, false), "Friday, March 1");
+        assert_eq!(render("h:mm AM/PM", 0.75, false), "6:00 PM");
+        assert_eq!(render("hh:mm:ss", 0.5 + 1.0 / 86400.0, false), "12:00:01");
+        assert_eq!(render("[h]:mm", 1.5, false), "36:00");
+        assert_eq!(render("mm:ss.0", 61.3 / 86400.0, false), "01:01.3");
+        assert_eq!(render("yyyy-mm-dd", 43890.0, true), "2024-03-01");
+    }
+
+    #[test]
+    fn render_non_finite_numbers() {
+        let format = |code: &str| NumberFormat { kind: CellType::Number, code: code.to_owned() };
+        assert_eq!(format("0.00").render("NaN"), "NaN");
+        assert_eq!(format("#,##0.00").render("inf"), "inf");
+        assert_eq!(format("0.00E+00").render("-inf"), "-inf");
+        assert_eq!(format("General").render("inf"), "inf");
+        assert_eq!(render_value(None, "-inf"), "-inf");
+    }
+
+    #[test]
+    fn render_out_of_range_dates() {
+        assert_eq!(render("yyyy-mm-dd", 1e8, false), "#####");
+        assert_eq!(render("yyyy-mm-dd hh:mm", -1e12, true), "#####");
+        assert_eq!(render("m/d/yyyy", 1e300, false), "#####");
+    }
+}
@@ -0,0 +1,681 @@
+//! Decoder rendering parsed formula tokens (BIFF8 and BIFF12 `Rgce`) as formula text.
+//!
+//! Used to give error messages some context and to read formulas (`formulas=true`);
+//! tokens that need workbook level tables (names, external sheets, shared formulas)
+//! make the whole formula undecodable. Also lists the references of formula text,
+//! for the dependency graph of read_formula_graph.
+
+use crate::spreadsheet::cell::to_error_value;
+use crate::spreadsheet::reference::col_to_index;
+use crate::spreadsheet::reference::index_to_col;
+use crate::spreadsheet::reference::index_to_row;
+use crate::spreadsheet::MAX_COL;
+use crate::spreadsheet::MAX_ROW;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// Binary operators indexed by `ptg - 0x03`
+const OPERATORS: [&str; 15] = ["+", "-", "*", "/", "^", "&", "<", "<=", "=", ">=", ">", "<>", " ", ",", ":"];
+
+/// Appends the formula text to an error value when the formula could be decoded.
+pub(crate) fn with_formula(value: &str, formula: Option<String>) -> String {
+    match formula {
+        Some(formula) => format!("{} in formula ={}", value, formula),
+        None => value.to_owned(),
+    }
+}
+
+/// Decodes a parsed formula into its text without the leading `=`.
+///
+/// # Arguments
+/// * `rgce` - Formula token bytes
+/// * `is_biff12` - Whether the tokens use the BIFF12 (XLSB) layout instead of BIFF8 (XLS)
+///
+/// # Returns
+/// * `Option<String>` - Formula text, or `None` if a token is not supported
+pub(crate) fn decode_formula(rgce: &[u8], is_biff12: bool) -> Option<String> {
+    let mut tokens = Tokens { data: rgce, offset: 0, is_biff12 };
+    let mut stack = Vec::<String>::new();
+    while tokens.offset < rgce.len() {
+        let ptg = tokens.u8()?;
+        // Operand tokens carry their class (reference, value, array) in bits 5-6
+        let ptg = if ptg >= 0x20 { (ptg & 0x1F) | 0x20 } else { ptg };
+        match ptg {
+            0x03..=0x11 => {
+                let right = stack.pop()?;
+                let left = stack.pop()?;
+                stack.push(format!("{}{}{}", left, OPERATORS[(ptg - 0x03) as usize], right));
+            }
+            0x12 => {
+                let operand = stack.pop()?;
+                stack.push(format!("+{}", operand));
+            }
+            0x13 => {
+                let operand = stack.pop()?;
+                stack.push(format!("-{}", operand));
+            }
+            0x14 => {
+                let operand = stack.pop()?;
+                stack.push(format!("{}%", operand));
+            }
+            0x15 => {
+                let operand = stack.pop()?;
+                stack.push(format!("({})", operand));
+            }
+            0x16 => stack.push(String::new()),
+            0x17 => {
+                let value = tokens.string()?;
+                stack.push(format!("\"{}\"", value.replace('"', "\"\"")));
+            }
+            0x19 => {
+                let flags = tokens.u8()?;
+                let count = tokens.u16()? as usize;
+                if flags & 0x04 != 0 {
+                    tokens.skip((count + 1) * 2)?; // Jump table of CHOOSE
+                } else if flags & 0x10 != 0 {
+                    let operand = stack.pop()?;
+                    stack.push(format!("SUM({})", operand));
+                }
+            }
+            0x1C => stack.push(to_error_value(tokens.u8()?).to_owned()),
+            0x1D => stack.push(if tokens.u8()? != 0 { "TRUE" } else { "FALSE" }.to_owned()),
+            0x1E => stack.push(tokens.u16()?.to_string()),
+            0x1F => stack.push(f64::from_le_bytes(tokens.take(8)?.try_into().ok()?).to_string()),
+            0x21 => {
+                let (name, arity) = function(tokens.u16()?)?;
+                call(&mut stack, name, arity?)?;
+            }
+            0x22 => {
+                let arity = tokens.u8()? as usize;
+                let (name, _) = function(tokens.u16()? & 0x7FFF)?;
+                call(&mut stack, name, arity)?;
+            }
+            0x24 => {
+                let reference = tokens.cell()?;
+                stack.push(reference);
+            }
+            0x25 => {
+                let (row_lower, row_upper) = (tokens.row()?, tokens.row()?);
+                let (col_lower, col_upper) = (tokens.u16()?, tokens.u16()?);
+                stack.push(format!(
+                    "{}:{}",
+                    to_reference(row_lower, col_lower),
+                    to_reference(row_upper, col_upper),
+                ));
+            }
+            0x26..=0x28 => tokens.skip(6)?, // Memory area headers, the sub-expression follows
+            0x29 => tokens.skip(2)?,
+            0x2A => {
+                tokens.skip(if is_biff12 { 6 } else { 4 })?;
+                stack.push("#REF!".to_owned());
+            }
+            0x2B => {
+                tokens.skip(if is_biff12 { 12 } else { 8 })?;
+                stack.push("#REF!".to_owned());
+            }
+            _ => return None,
+        }
+    }
+    if stack.len() == 1 {
+        stack.pop()
+    } else {
+        None
+    }
+}
+
+/// Moves the relative references of a formula by a number of rows and columns, as Excel
+/// does when filling a shared formula from its first cell to the other cells of its range.
+///
+/// References are recognized outside of string literals and quoted sheet names; words
+/// followed by `(` are function names and kept as they are. References moved out of the
+/// worksheet become `#REF!`.
+///
+/// # Arguments
+/// * `formula` - Formula text without the leading `=`
+/// * `rows` - Number of rows to move the relative references by
+/// * `cols` - Number of columns to move the relative references by
+pub(crate) fn shift_formula(formula: &str, rows: isize, cols: isize) -> String {
+    let mut shifted = String::with_capacity(formula.len());
+    let mut chars = formula.char_indices().peekable();
+    while let Some((lower, char)) = chars.next() {
+        if char == '"' || char == '\'' {
+            shifted.push(char);
+            while let Some((_, next)) = chars.next() {
+                shifted.push(next);
+                if next == char {
+                    // A doubled quote stands for the quote itself
+                    if chars.next_if(|(_, following)| *following == char).is_some() {
+                        shifted.push(char);
+                    } else {
+                        break;
+                    }
+                }
+            }
+        } else if is_word_char(char) {
+            let mut upper = lower + char.len_utf8();
+            while let Some((index, next)) = chars.next_if(|(_, next)| is_word_char(*next)) {
+                upper = index + next.len_utf8();
+            }
+            let word = &formula[lower..upper];
+            let is_function = chars.peek().is_some_and(|(_, next)| *next == '(');
+            match shift_reference(word, rows, cols).filter(|_| !is_function) {
+                Some(reference) => shifted.push_str(&reference),
+                None => shifted.push_str(word),
+            }
+        } else {
+            shifted.push(char);
+        }
+    }
+    shifted
+}
+
+/// Whether a character belongs to a word of a formula: a name, a number or a reference.
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric() || matches!(char, '$' | '_' | '.' | '\\')
+}
+
+/// Moves an `A1` reference, keeping its absolute (`$`) parts.
+///
+/// # Returns
+/// * `Option<String>` - Moved reference, or `None` if the word is not a reference
+fn shift_reference(word: &str, rows: isize, cols: isize) -> Option<String> {
+    let (is_col_absolute, rest) = match word.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, word),
+    };
+    let (letters, rest) = rest.split_at(rest.find(|char: char| !char.is_ascii_uppercase())?);
+    let (is_row_absolute, digits) = match rest.strip_prefix('$') {
+        Some(digits) => (true, digits),
+        None => (false, rest),
+    };
+    if !(1..=3).contains(&letters.len()) || digits.is_empty() || digits.starts_with('0') || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let col = col_to_index(letters).filter(|col| *col <= MAX_COL)?;
+    let row = digits.parse::<usize>().ok().map(|row| row - 1).filter(|row| *row <= MAX_ROW)?;
+    let col = if is_col_absolute { Some(col) } else { col.checked_add_signed(cols).filter(|col| *col <= MAX_COL) };
+    let row = if is_row_absolute { Some(row) } else { row.checked_add_signed(rows).filter(|row| *row <= MAX_ROW) };
+    match (col, row) {
+        (Some(col), Some(row)) => Some(format!(
+            "{}{}{}{}",
+            if is_col_absolute { "$" } else { "" },
+            index_to_col(col),
+            if is_row_absolute { "$" } else { "" },
+            index_to_row(row),
+        )),
+        _ => Some("#REF!".to_owned()),
+    }
+}
+
+/// Worksheet qualifying the reference that follows it in formula text
+enum Qualifier {
+    /// Worksheet of the formula
+    None,
+    /// Worksheet named before `!`
+    Sheet(String),
+    /// Worksheet of another workbook (`[1]Sheet1!`), left out of the references
+    External,
+}
+
+/// Lists the cells and ranges formula text refers to, in Excel (`'Q1 Sales'!$A$1:$D$10`)
+/// or OpenDocument (`[$'Q1 Sales'.$A$1:.$D$10]`) notation. Whole columns and rows (`A:A`,
+/// `1:3`) are kept; defined names, structured references and references to other
+/// workbooks are not resolved and left out.
+///
+/// # Arguments
+/// * `formula` - Formula text, with or without the leading `=`
+///
+/// # Returns
+/// * `Vec<(Option<String>, String)>` - Worksheet named by each reference (`None` for the
+///   worksheet of the formula) and its range without `$` markers, in order of appearance
+pub(crate) fn formula_references(formula: &str) -> Vec<(Option<String>, String)> {
+    let mut references = Vec::new();
+    let mut qualifier = Qualifier::None;
+    let mut chars = formula.char_indices().peekable();
+    while let Some((lower, char)) = chars.next() {
+        if char == '"' {
+            take_quoted(&mut chars, char);
+            qualifier = Qualifier::None;
+        } else if char == '\'' {
+            let sheet_name = take_quoted(&mut chars, char);
+            qualifier = match chars.next_if(|(_, next)| *next == '!') {
+                Some(_) if sheet_name.starts_with('[') => Qualifier::External,
+                Some(_) => Qualifier::Sheet(sheet_name),
+                None => Qualifier::None,
+            };
+        } else if char == '[' {
+            let mut content = String::new();
+            while let Some((_, next)) = chars.next_if(|(_, next)| *next != ']') {
+                content.push(next);
+            }
+            chars.next();
+            // Brackets hold OpenDocument references, or workbook indexes and table columns in Excel
+            match opendocument_reference(&content) {
+                Some(reference) => {
+                    references.push(reference);
+                    qualifier = Qualifier::None;
+                }
+                None => qualifier = Qualifier::External,
+            }
+        } else if is_word_char(char) {
+            let word = take_word(formula, &mut chars, lower);
+            if chars.next_if(|(_, next)| *next == '!').is_some() {
+                if !matches!(qualifier, Qualifier::External) {
+                    qualifier = Qualifier::Sheet(word.to_owned());
+                }
+                continue;
+            }
+            let is_function = chars.peek().is_some_and(|(_, next)| *next == '(');
+            // A range is two bounds of the same kind around `:`, otherwise only cells are references
+            let mut after = chars.clone();
+            let upper = after.next_if(|(_, next)| *next == ':')
+                .and_then(|_| after.next_if(|(_, next)| is_word_char(*next)))
+                .map(|(upper, _)| take_word(formula, &mut after, upper));
+            let range = match (range_bound(word), upper.and_then(range_bound)) {
+                (Some((lower_bound, lower_kind)), Some((upper_bound, upper_kind))) if lower_kind == upper_kind => {
+                    chars = after;
+                    Some(format!("{}:{}", lower_bound, upper_bound))
+                }
+                (Some((cell, (true, true))), _) => Some(cell),
+                _ => None,
+            };
+            match (range.filter(|_| !is_function), std::mem::replace(&mut qualifier, Qualifier::None)) {
+                (Some(range), Qualifier::None) => references.push((None, range)),
+                (Some(range), Qualifier::Sheet(sheet_name)) => references.push((Some(sheet_name), range)),
+                _ => {}
+            }
+        } else {
+            qualifier = Qualifier::None;
+        }
+    }
+    references
+}
+
+/// Takes the rest of the word starting with the character at byte `lower` of the formula.
+fn take_word<'a>(formula: &'a str, chars: &mut Peekable<CharIndices<'a>>, lower: usize) -> &'a str {
+    let mut upper = lower + formula[lower..].chars().next().map_or(0, char::len_utf8);
+    while let Some((index, next)) = chars.next_if(|(_, next)| is_word_char(*next)) {
+        upper = index + next.len_utf8();
+    }
+    &formula[lower..upper]
+}
+
+/// Takes the text up to the closing `quote`, a doubled quote standing for the quote itself.
+fn take_quoted(chars: &mut Peekable<CharIndices>, quote: char) -> String {
+    let mut text = String::new();
+    while let Some((_, next)) = chars.next() {
+        if next == quote && chars.next_if(|(_, following)| *following == quote).is_none() {
+            break;
+        }
+        text.push(next);
+    }
+    text
+}
+
+/// Normalizes a bound of a range without its `$` markers: a cell (`B2`), a whole column
+/// (`B`) or a whole row (`2`).
+///
+/// # Returns
+/// * `Option<(String, (bool, bool))>` - Bound and whether it has a column and a row, or
+///   `None` if the word is not a bound
+fn range_bound(word: &str) -> Option<(String, (bool, bool))> {
+    let bound = word.replace('$', "");
+    let (letters, digits) = bound.split_at(bound.len() - bound.trim_start_matches(|char: char| char.is_ascii_uppercase()).len());
+    let is_col = letters.is_empty() || (letters.len() <= 3 && col_to_index(letters).is_some_and(|col| col <= MAX_COL));
+    let is_row = digits.is_empty() || (!digits.starts_with('0')
+        && digits.bytes().all(|byte| byte.is_ascii_digit())
+        && digits.parse::<usize>().is_ok_and(|row| row <= MAX_ROW + 1));
+    (is_col && is_row && !bound.is_empty()).then(|| (bound.clone(), (!letters.is_empty(), !digits.is_empty())))
+}
+
+/// Parses the content of an OpenDocument reference, such as `.A1`, `.A1:.B2` or
+/// `$'Q1 Sales'.$A$1`, as the worksheet it names and its range without `$` markers.
+fn opendocument_reference(content: &str) -> Option<(Option<String>, String)> {
+    let mut sheet_name = None;
+    let mut bounds = Vec::new();
+    for part in content.split(':') {
+        let (sheet, bound) = part.rsplit_once('.')?;
+        let sheet = sheet.trim_start_matches('$');
+        if !sheet.is_empty() && sheet_name.is_none() {
+            sheet_name = Some(match sheet.strip_prefix('\'').and_then(|name| name.strip_suffix('\'')) {
+                Some(name) => name.replace("''", "'"),
+                None => sheet.to_owned(),
+            });
+        }
+        bounds.push(range_bound(bound)?);
+    }
+    match bounds.as_slice() {
+        [(cell, (true, true))] => Some((sheet_name, cell.to_owned())),
+        [(lower, lower_kind), (upper, upper_kind)] if lower_kind == upper_kind => Some((sheet_name, format!("{}:{}", lower, upper))),
+        _ => None,
+    }
+}
+
+/// Pops `arity` arguments and pushes the function call.
+fn call(stack: &mut Vec<String>, name: &str, arity: usize) -> Option<()> {
+    let arguments = stack.split_off(stack.len().checked_sub(arity)?);
+    stack.push(format!("{}({})", name, arguments.join(",")));
+    Some(())
+}
+
+/// Formats a row and a column with its relative flags (bit 14 column, bit 15 row) as `A1`.
+fn to_reference(row: usize, col: u16) -> String {
+    format!(
+        "{}{}{}{}",
+        if col & 0x4000 == 0 { "$" } else { "" },
+        index_to_col((col & 0x3FFF) as usize),
+        if col & 0x8000 == 0 { "$" } else { "" },
+        index_to_row(row),
+    )
+}
+
+/// Cursor over formula token bytes
+struct Tokens<'a> {
+    data: &'a [u8],
+    offset: usize,
+    is_biff12: bool,
+}
+
+impl<'a> Tokens<'a> {
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.offset..self.offset + length)?;
+        self.offset += length;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, length: usize) -> Option<()> {
+        self.take(length).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a row index, 16 bits in BIFF8 and 32 bits in BIFF12.
+    fn row(&mut self) -> Option<usize> {
+        if self.is_biff12 {
+            self.u32().map(|row| row as usize)
+        } else {
+            self.u16().map(|row| row as usize)
+        }
+    }
+
+    /// Reads a single cell reference.
+    fn cell(&mut self) -> Option<String> {
+        let row = self.row()?;
+        let col = self.u16()?;
+        Some(to_reference(row, col))
+    }
+
+    /// Reads a string literal: BIFF12 uses UTF-16 with a 16-bit length,
+    /// BIFF8 an 8-bit length and a flag selecting compressed or UTF-16 characters.
+    fn string(&mut self) -> Option<String> {
+        let (length, is_wide) = if self.is_biff12 {
+            (self.u16()? as usize, true)
+        } else {
+            let length = self.u8()? as usize;
+            (length, self.u8()? & 0x01 != 0)
+        };
+        if is_wide {
+            let units = self.take(length * 2)?
+                .chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .collect::<Vec<_>>();
+            String::from_utf16(&units).ok()
+        } else {
+            Some(self.take(length)?.iter().map(|byte| *byte as char).collect())
+        }
+    }
+}
+
+/// Looks up a built-in function by its index, returning its name and fixed arity
+/// (`None` for functions taking a variable number of arguments).
+fn function(id: u16) -> Option<(&'static str, Option<usize>)> {
+    let (name, arity) = match id {
+        0 => ("COUNT", None),
+        1 => ("IF", None),
+        2 => ("ISNA", Some(1)),
+        3 => ("ISERROR", Some(1)),
+        4 => ("SUM", None),
+        5 => ("AVERAGE", None),
+        6 => ("MIN", None),
+        7 => ("MAX", None),
+        8 => ("ROW", None),
+        9 => ("COLUMN", None),
+        10 => ("NA", Some(0)),
+        11 => ("NPV", None),
+        12 => ("STDEV", None),
+        13 => ("DOLLAR", None),
+        14 => ("FIXED", None),
+        15 => ("SIN", Some(1)),
+        16 => ("COS", Some(1)),
+        17 => ("TAN", Some(1)),
+        18 => ("ATAN", Some(1)),
+        19 => ("PI", Some(0)),
+        20 => ("SQRT", Some(1)),
+        21 => ("EXP", Some(1)),
+        22 => ("LN", Some(1)),
+        23 => ("LOG10", Some(1)),
+        24 => ("ABS", Some(1)),
+        25 => ("INT", Some(1)),
+        26 => ("SIGN", Some(1)),
+        27 => ("ROUND", Some(2)),
+        28 => ("LOOKUP", None),
+        29 => ("INDEX", None),
+        30 => ("REPT", Some(2)),
+        31 => ("MID", Some(3)),
+        32 => ("LEN", Some(1)),
+        33 => ("VALUE", Some(1)),
+        34 => ("TRUE", Some(0)),
+        35 => ("FALSE", Some(0)),
+        36 => ("AND", None),
+        37 => ("OR", None),
+        38 => ("NOT", Some(1)),
+        39 => ("MOD", Some(2)),
+        40 => ("DCOUNT", Some(3)),
+        41 => ("DSUM", Some(3)),
+        42 => ("DAVERAGE", Some(3)),
+        43 => ("DMIN", Some(3)),
+        44 => ("DMAX", Some(3)),
+        45 => ("DSTDEV", Some(3)),
+        46 => ("VAR", None),
+        47 => ("DVAR", Some(3)),
+        48 => ("TEXT", Some(2)),
+        56 => ("PV", None),
+        57 => ("FV", None),
+        58 => ("NPER", None),
+        59 => ("PMT", None),
+        60 => ("RATE", None),
+        61 => ("MIRR", Some(3)),
+        62 => ("IRR", None),
+        63 => ("RAND", Some(0)),
+        64 => ("MATCH", None),
+        65 => ("DATE", Some(3)),
+        66 => ("TIME", Some(3)),
+        67 => ("DAY", Some(1)),
+        68 => ("MONTH", Some(1)),
+        69 => ("YEAR", Some(1)),
+        70 => ("WEEKDAY", None),
+        71 => ("HOUR", Some(1)),
+        72 => ("MINUTE", Some(1)),
+        73 => ("SECOND", Some(1)),
+        74 => ("NOW", Some(0)),
+        75 => ("AREAS", Some(1)),
+        76 => ("ROWS", Some(1)),
+        77 => ("COLUMNS", Some(1)),
+        78 => ("OFFSET", None),
+        82 => ("SEARCH", None),
+        83 => ("TRANSPOSE", Some(1)),
+        86 => ("TYPE", Some(1)),
+        97 => ("ATAN2", Some(2)),
+        98 => ("ASIN", Some(1)),
+        99 => ("ACOS", Some(1)),
+        100 => ("CHOOSE", None),
+        101 => ("HLOOKUP", None),
+        102 => ("VLOOKUP", None),
+        105 => ("ISREF", Some(1)),
+        109 => ("LOG", None),
+        111 => ("CHAR", Some(1)),
+        112 => ("LOWER", Some(1)),
+        113 => ("UPPER", Some(1)),
+        114 => ("PROPER", Some(1)),
+        115 => ("LEFT", None),
+        116 => ("RIGHT", None),
+        117 => ("EXACT", Some(2)),
+        118 => ("TRIM", Some(1)),
+        119 => ("REPLACE", Some(4)),
+        120 => ("SUBSTITUTE", None),
+        121 => ("CODE", Some(1)),
+        124 => ("FIND", None),
+        125 => ("CELL", None),
+        126 => ("ISERR", Some(1)),
+        127 => ("ISTEXT", Some(1)),
+        128 => ("ISNUMBER", Some(1)),
+        129 => ("ISBLANK", Some(1)),
+        130 => ("T", Some(1)),
+        131 => ("N", Some(1)),
+        140 => ("DATEVALUE", Some(1)),
+        141 => ("TIMEVALUE", Some(1)),
+        142 => ("SLN", Some(3)),
+        143 => ("SYD", Some(4)),
+        144 => ("DDB", None),
+        148 => ("INDIRECT", None),
+        162 => ("CLEAN", Some(1)),
+        163 => ("MDETERM", Some(1)),
+        164 => ("MINVERSE", Some(1)),
+        165 => ("MMULT", Some(2)),
+        167 => ("IPMT", None),
+        168 => ("PPMT", None),
+        169 => ("COUNTA", None),
+        183 => ("PRODUCT", None),
+        184 => ("FACT", Some(1)),
+        189 => ("DPRODUCT", Some(3)),
+        190 => ("ISNONTEXT", Some(1)),
+        193 => ("STDEVP", None),
+        194 => ("VARP", None),
+        195 => ("DSTDEVP", Some(3)),
+        196 => ("DVARP", Some(3)),
+        197 => ("TRUNC", None),
+        198 => ("ISLOGICAL", Some(1)),
+        199 => ("DCOUNTA", Some(3)),
+        212 => ("ROUNDUP", Some(2)),
+        213 => ("ROUNDDOWN", Some(2)),
+        216 => ("RANK", None),
+        219 => ("ADDRESS", None),
+        220 => ("DAYS360", None),
+        221 => ("TODAY", Some(0)),
+        222 => ("VDB", None),
+        227 => ("MEDIAN", None),
+        228 => ("SUMPRODUCT", None),
+        229 => ("SINH", Some(1)),
+        230 => ("COSH", Some(1)),
+        231 => ("TANH", Some(1)),
+        232 => ("ASINH", Some(1)),
+        233 => ("ACOSH", Some(1)),
+        234 => ("ATANH", Some(1)),
+        235 => ("DGET", Some(3)),
+        244 => ("INFO", Some(1)),
+        247 => ("DB", None),
+        252 => ("FREQUENCY", Some(2)),
+        261 => ("ERROR.TYPE", Some(1)),
+        269 => ("AVEDEV", None),
+        276 => ("COMBIN", Some(2)),
+        279 => ("EVEN", Some(1)),
+        285 => ("FLOOR", Some(2)),
+        288 => ("CEILING", Some(2)),
+        298 => ("ODD", Some(1)),
+        336 => ("CONCATENATE", None),
+        337 => ("POWER", Some(2)),
+        342 => ("RADIANS", Some(1)),
+        343 => ("DEGREES", Some(1)),
+        344 => ("SUBTOTAL", None),
+        345 => ("SUMIF", None),
+        346 => ("COUNTIF", Some(2)),
+        347 => ("COUNTBLANK", Some(1)),
+        354 => ("ROMAN", None),
+        361 => ("AVERAGEA", None),
+        362 => ("MAXA", None),
+        363 => ("MINA", None),
+        366 => ("STDEVA", None),
+        480 => ("IFERROR", Some(2)),
+        481 => ("COUNTIFS", None),
+        482 => ("SUMIFS", None),
+        483 => ("AVERAGEIF", None),
+        484 => ("AVERAGEIFS", None),
+        _ => return None,
+    };
+    Some((name, arity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_biff8_formula() {
+        let rgce = [
+            0x25, 0x00, 0x00, 0x01, 0x00, 0x00, 0xC0, 0x01, 0xC0, // A1:B2
+            0x19, 0x10, 0x00, 0x00,                               // SUM
+            0x24, 0x02, 0x00, 0x02, 0x00,                         // $C$3
+            0x06,                                                 // /
+        ];
+        assert_eq!(decode_formula(&rgce, false), Some("SUM(A1:B2)/$C$3".to_owned()));
+    }
+
+    #[test]
+    fn decode_biff12_formula() {
+        let rgce = [
+            0x24, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0,             // A1
+            0x1E, 0x00, 0x00,                                     // 0
+            0x0D,                                                 // >
+            0x17, 0x03, 0x00, b'y', 0x00, b'e', 0x00, b's', 0x00, // "yes"
+            0x1D, 0x00,                                           // FALSE
+            0x42, 0x03, 0x01, 0x00,                               // IF with 3 arguments
+        ];
+        assert_eq!(decode_formula(&rgce, true), Some("IF(A1>0,\"yes\",FALSE)".to_owned()));
+    }
+
+    #[test]
+    fn shift_shared_formula() {
+        assert_eq!(shift_formula("SUM(A1:B2)*$C$1+C$1+$C1", 2, 1), "SUM(B3:C4)*$C$1+D$1+$C3");
+        // Functions, names, strings and quoted sheet names are kept
+        assert_eq!(shift_formula(r#"LOG10(A1)&"A1"&'Q1 Sales'!A1&Sheet1!A1&TRUE"#, 1, 0), r#"LOG10(A2)&"A1"&'Q1 Sales'!A2&Sheet1!A2&TRUE"#);
+        assert_eq!(shift_formula(r#""say ""A1"""&A1"#, 0, 1), r#""say ""A1"""&B1"#);
+        assert_eq!(shift_formula("A2-A1", -1, 0), "A1-#REF!");
+        assert_eq!(shift_formula("1.5E3*ABCD1", 1, 1), "1.5E3*ABCD1");
+    }
+
+    #[test]
+    fn list_formula_references() {
+        let reference = |sheet_name: Option<&str>, range: &str| (sheet_name.map(str::to_owned), range.to_owned());
+        assert_eq!(formula_references("=SUM(A1:B2)*$C$1+'Q1 Sales'!$A$1:$D$10+Sheet2!B:B+LOG10(2:3)"), vec![
+            reference(None, "A1:B2"),
+            reference(None, "C1"),
+            reference(Some("Q1 Sales"), "A1:D10"),
+            reference(Some("Sheet2"), "B:B"),
+            reference(None, "2:3"),
+        ]);
+        assert_eq!(formula_references("=SUM([.A1:.B2])+[$'Q1 Sales'.$C$3]"), vec![
+            reference(None, "A1:B2"),
+            reference(Some("Q1 Sales"), "C3"),
+        ]);
+        // Strings, functions, names, numbers, table columns and other workbooks are left out
+        assert!(formula_references(r#"="A1"&TRUE&1.5E3&Rate*[1]Sheet1!A1&'[2]Q1'!B2&Table1[Amount]"#).is_empty());
+    }
+
+    #[test]
+    fn decode_unsupported_formula() {
+        // Named range reference
+        assert_eq!(decode_formula(&[0x23, 0x01, 0x00, 0x00, 0x00], false), None);
+        assert_eq!(with_formula("#N/A", None), "#N/A");
+        assert_eq!(with_formula("#N/A", Some("NA()".to_owned())), "#N/A in formula =NA()");
+    }
+}
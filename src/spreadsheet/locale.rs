@@ -0,0 +1,193 @@
+//! Localized month names for parsing text dates such as "31-Dec-24" or "Dez 2024".
+
+use chrono::NaiveDate;
+use chrono::Weekday;
+
+/// English month names, also accepted for every other locale
+const EN: [&str; 12] = [
+    "january|jan", "february|feb", "march|mar", "april|apr", "may", "june|jun",
+    "july|jul", "august|aug", "september|sep|sept", "october|oct", "november|nov", "december|dec",
+];
+const DE: [&str; 12] = [
+    "januar|jänner|jan|jän", "februar|feber|feb", "märz|mär|mrz", "april|apr", "mai", "juni|jun",
+    "juli|jul", "august|aug", "september|sep|sept", "oktober|okt", "november|nov", "dezember|dez",
+];
+const FR: [&str; 12] = [
+    "janvier|janv", "février|févr|fév", "mars", "avril|avr", "mai", "juin",
+    "juillet|juil", "août", "septembre|sept", "octobre|oct", "novembre|nov", "décembre|déc",
+];
+const ES: [&str; 12] = [
+    "enero|ene", "febrero|feb", "marzo|mar", "abril|abr", "mayo|may", "junio|jun",
+    "julio|jul", "agosto|ago", "septiembre|setiembre|sep|sept|set", "octubre|oct", "noviembre|nov", "diciembre|dic",
+];
+const IT: [&str; 12] = [
+    "gennaio|gen", "febbraio|feb", "marzo|mar", "aprile|apr", "maggio|mag", "giugno|giu",
+    "luglio|lug", "agosto|ago", "settembre|set", "ottobre|ott", "novembre|nov", "dicembre|dic",
+];
+const NL: [&str; 12] = [
+    "januari|jan", "februari|feb", "maart|mrt", "april|apr", "mei", "juni|jun",
+    "juli|jul", "augustus|aug", "september|sep|sept", "oktober|okt", "november|nov", "december|dec",
+];
+const PT: [&str; 12] = [
+    "janeiro|jan", "fevereiro|fev", "março|mar", "abril|abr", "maio|mai", "junho|jun",
+    "julho|jul", "agosto|ago", "setembro|set", "outubro|out", "novembro|nov", "dezembro|dez",
+];
+const CS: [&str; 12] = [
+    "leden|ledna|led", "únor|února|úno", "březen|března|bře", "duben|dubna|dub", "květen|května|kvě", "červen|června|čvn",
+    "červenec|července|čvc", "srpen|srpna|srp", "září|zář", "říjen|října|říj", "listopad|listopadu|lis", "prosinec|prosince|pro",
+];
+const PL: [&str; 12] = [
+    "styczeń|stycznia|sty", "luty|lutego|lut", "marzec|marca|mar", "kwiecień|kwietnia|kwi", "maj|maja", "czerwiec|czerwca|cze",
+    "lipiec|lipca|lip", "sierpień|sierpnia|sie", "wrzesień|września|wrz", "październik|października|paź", "listopad|listopada|lis", "grudzień|grudnia|gru",
+];
+
+/// Locale whose month names are recognized in text dates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum DateLocale {
+    En,
+    De,
+    Fr,
+    Es,
+    It,
+    Nl,
+    Pt,
+    Cs,
+    Pl,
+}
+
+impl DateLocale {
+    /// Supported locale codes, in declaration order
+    pub(crate) const CODES: [&'static str; 9] = ["en", "de", "fr", "es", "it", "nl", "pt", "cs", "pl"];
+
+    /// Resolves a locale code such as `de` or `de-AT` (case-insensitive).
+    pub(crate) fn from_code(code: &str) -> Option<Self> {
+        let language = code.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        match language.as_str() {
+            "en" => Some(Self::En),
+            "de" => Some(Self::De),
+            "fr" => Some(Self::Fr),
+            "es" => Some(Self::Es),
+            "it" => Some(Self::It),
+            "nl" => Some(Self::Nl),
+            "pt" => Some(Self::Pt),
+            "cs" => Some(Self::Cs),
+            "pl" => Some(Self::Pl),
+            _ => None,
+        }
+    }
+
+    fn months(&self) -> &'static [&'static str; 12] {
+        match self {
+            Self::En => &EN,
+            Self::De => &DE,
+            Self::Fr => &FR,
+            Self::Es => &ES,
+            Self::It => &IT,
+            Self::Nl => &NL,
+            Self::Pt => &PT,
+            Self::Cs => &CS,
+            Self::Pl => &PL,
+        }
+    }
+
+    /// Returns the month number (1-12) of a month name or abbreviation.
+    fn month(&self, token: &str) -> Option<u32> {
+        let token = token.to_lowercase();
+        [self.months(), &EN].into_iter()
+            .find_map(|months| months.iter().position(|names| names.split('|').any(|name| name == token)))
+            .map(|index| index as u32 + 1)
+    }
+
+    /// Parses a date written with a month name, e.g. "31-Dec-24", "31. Dezember 2024",
+    /// "Dec 31, 2024" or "Dez 2024" (first day of the month), or an ISO week date
+    /// such as "2024-W52" or "2024-W52-3".
+    pub(crate) fn parse_date(&self, text: &str) -> Option<NaiveDate> {
+        let text = text.trim();
+        if let Some(date) = parse_week_date(text) {
+            return Some(date);
+        }
+
+        let tokens = text
+            .split(|char: char| char.is_whitespace() || matches!(char, '-' | '/' | ',' | '.'))
+            .filter(|token| !token.is_empty())
+            .collect::<Vec<_>>();
+        let month_index = tokens.iter().position(|token| !token.chars().all(|char| char.is_ascii_digit()))?;
+        let month = self.month(tokens[month_index])?;
+        let numbers = tokens.iter()
+            .enumerate()
+            .filter(|(index, _)| *index != month_index)
+            .map(|(_, token)| token.parse::<u32>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let (year, day) = match (month_index, numbers.as_slice()) {
+            (0, [year]) if is_year(tokens[1]) => (*year, 1),         // Dez 2024
+            (0, [day, year]) => (*year, *day),                       // Dec 31, 2024
+            (1, [year, day]) if is_year(tokens[0]) => (*year, *day), // 2024-Dec-31
+            (1, [day, year]) => (*year, *day),                       // 31-Dec-24
+            _ => return None,
+        };
+        NaiveDate::from_ymd_opt(to_full_year(year)?, month, day)
+    }
+}
+
+/// Parses an ISO week date, "YYYY-Www" (Monday) or "YYYY-Www-D".
+fn parse_week_date(text: &str) -> Option<NaiveDate> {
+    let (year, week) = text.split_once("-W")?;
+    let (week, day) = week.split_once('-').unwrap_or((week, "1"));
+    let weekday = match day.parse::<u8>().ok()? {
+        1 => Weekday::Mon,
+        2 => Weekday::Tue,
+        3 => Weekday::Wed,
+        4 => Weekday::Thu,
+        5 => Weekday::Fri,
+        6 => Weekday::Sat,
+        7 => Weekday::Sun,
+        _ => return None,
+    };
+    if year.len() != 4 || week.len() != 2 {
+        return None;
+    }
+    NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, weekday)
+}
+
+/// Checks if a token is written as a four-digit year.
+fn is_year(token: &str) -> bool {
+    token.len() == 4
+}
+
+/// Expands two-digit years to 1970-2069, keeping four-digit years.
+fn to_full_year(year: u32) -> Option<i32> {
+    match year {
+        0..=69 => Some(2000 + year as i32),
+        70..=99 => Some(1900 + year as i32),
+        1000..=9999 => Some(year as i32),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    #[test]
+    fn parse_month_names() {
+        assert_eq!(DateLocale::En.parse_date("31-Dec-24"), date(2024, 12, 31));
+        assert_eq!(DateLocale::En.parse_date("Dec 31, 2024"), date(2024, 12, 31));
+        assert_eq!(DateLocale::De.parse_date("Dez 2024"), date(2024, 12, 1));
+        assert_eq!(DateLocale::De.parse_date("31. März 2024"), date(2024, 3, 31));
+        assert_eq!(DateLocale::De.parse_date("31-Dec-24"), date(2024, 12, 31)); // English fallback
+        assert_eq!(DateLocale::Fr.parse_date("1 déc. 2024"), date(2024, 12, 1));
+        assert_eq!(DateLocale::Cs.parse_date("24. prosince 2024"), date(2024, 12, 24));
+        assert_eq!(DateLocale::Cs.parse_date("červenec 2024"), date(2024, 7, 1));
+        assert_eq!(DateLocale::Pl.parse_date("2024-gru-31"), date(2024, 12, 31));
+        assert_eq!(DateLocale::En.parse_date("2024-W01-2"), date(2024, 1, 2));
+        assert_eq!(DateLocale::En.parse_date("31-Dez-24"), None);
+        assert_eq!(DateLocale::De.parse_date("31.12.2024"), None);
+        assert_eq!(DateLocale::De.parse_date("Dezember"), None);
+        assert_eq!(DateLocale::De.parse_date("Mai 2024 Bericht"), None);
+        assert_eq!(DateLocale::from_code("de-AT"), Some(DateLocale::De));
+    }
+}
@@ -0,0 +1,217 @@
+//! Document properties of workbooks
+//!
+//! Office Open XML packages keep them in `docProps/core.xml` and `docProps/app.xml`,
+//! OpenDocument packages in `meta.xml` and BIFF8 workbooks in the `SummaryInformation`
+//! property set stream of the compound file.
+
+use crate::error::RustySheetError;
+use crate::helpers::xml::XmlReader;
+use crate::helpers::xml::XmlTextContextHelper;
+use crate::match_xml_events;
+use chrono::DateTime;
+use chrono::NaiveDateTime;
+use quick_xml::events::Event;
+use std::collections::HashMap;
+use std::io::BufRead;
+
+/// Property identifier of the code page of the property set strings
+const PID_CODEPAGE: u32 = 1;
+/// Property identifier of the document title
+const PID_TITLE: u32 = 2;
+/// Property identifier of the document author
+const PID_AUTHOR: u32 = 4;
+/// Property identifier of the creation time
+const PID_CREATE_DTM: u32 = 12;
+/// Property identifier of the time the document was last saved
+const PID_LAST_SAVE_DTM: u32 = 13;
+/// Property identifier of the application that created the document
+const PID_APP_NAME: u32 = 18;
+
+/// Property type of 16-bit signed integers
+const VT_I2: u16 = 0x02;
+/// Property type of strings in the code page of the property set
+const VT_LPSTR: u16 = 0x1E;
+/// Property type of UTF-16 strings
+const VT_LPWSTR: u16 = 0x1F;
+/// Property type of times in 100-nanosecond intervals since 1601-01-01
+const VT_FILETIME: u16 = 0x40;
+
+/// Microseconds between 1601-01-01 and 1970-01-01
+const FILETIME_EPOCH_MICROS: i64 = 11_644_473_600_000_000;
+
+/// Document properties of a workbook; properties the file does not record are None
+#[derive(Clone, Debug, Default)]
+pub(crate) struct WorkbookMetadata {
+    /// Title of the document
+    pub(crate) title: Option<String>,
+    /// Author who created the document
+    pub(crate) author: Option<String>,
+    /// Creation time in microseconds since 1970-01-01
+    pub(crate) created: Option<i64>,
+    /// Time of the last modification in microseconds since 1970-01-01
+    pub(crate) modified: Option<i64>,
+    /// Application that created the document
+    pub(crate) application: Option<String>,
+    /// Number of worksheets
+    pub(crate) sheet_count: usize,
+    /// Whether serial dates count from 1904-01-01 instead of 1900-01-01
+    pub(crate) is_1904: bool,
+}
+
+/// Parses a W3CDTF or ISO 8601 timestamp, such as `2024-02-29T13:45:30Z`, into microseconds
+/// since 1970-01-01. Timestamps without a zone are taken as UTC.
+pub(crate) fn parse_timestamp(text: &str) -> Option<i64> {
+    let text = text.trim();
+    DateTime::parse_from_rfc3339(text)
+        .map(|datetime| datetime.timestamp_micros())
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f").ok().map(|datetime| datetime.and_utc().timestamp_micros()))
+}
+
+/// Reads the text of the elements with the given local names from an XML part
+///
+/// # Arguments
+/// * `reader` - XML reader of the part
+/// * `names` - Local names of the elements, e.g. `creator` for `dc:creator`
+///
+/// # Returns
+/// The trimmed text of the first non-empty element of each name, by local name
+pub(crate) fn read_properties<R: BufRead>(reader: &mut XmlReader<R>, names: &[&str]) -> Result<HashMap<String, String>, RustySheetError> {
+    let mut properties = HashMap::<String, String>::new();
+    let mut name = None::<String>;
+    let mut text = String::new();
+    match_xml_events!(reader => {
+        Event::Start(event) => {
+            let local_name = String::from_utf8_lossy(event.local_name().as_ref()).into_owned();
+            if names.contains(&local_name.as_str()) && !properties.contains_key(&local_name) {
+                name = Some(local_name);
+                text.clear();
+            }
+        }
+        Event::Text(event) if name.is_some() => text.push_bytes_text(&event)?,
+        Event::GeneralRef(event) if name.is_some() => text.push_bytes_ref(&event)?,
+        Event::End(event) if name.as_deref().is_some_and(|name| name.as_bytes() == event.local_name().as_ref()) => {
+            if let Some(name) = name.take().filter(|_| !text.trim().is_empty()) {
+                properties.insert(name, text.trim().to_owned());
+            }
+        }
+    });
+    Ok(properties)
+}
+
+/// Parses the document properties of a `SummaryInformation` property set stream (MS-OLEPS)
+///
+/// Only the title, author, creation time, last save time and application name are read;
+/// the sheet count and date system are left to the caller. A malformed stream yields
+/// the properties read before the damage.
+pub(crate) fn parse_summary_information(stream: &[u8]) -> WorkbookMetadata {
+    let mut metadata = WorkbookMetadata::default();
+    let u16_at = |offset: usize| stream.get(offset..offset + 2).map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]));
+    let u32_at = |offset: usize| stream.get(offset..offset + 4).map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+    // The first property set follows the 28-byte header and its 16-byte format identifier
+    let Some(start) = u32_at(44).map(|offset| offset as usize) else {
+        return metadata;
+    };
+    let count = u32_at(start + 4).unwrap_or(0) as usize;
+    let mut properties = Vec::<(u32, usize)>::new();
+    for index in 0..count {
+        match (u32_at(start + 8 + index * 8), u32_at(start + 12 + index * 8)) {
+            (Some(id), Some(offset)) => properties.push((id, start + offset as usize)),
+            _ => break,
+        }
+    }
+
+    // Strings of type VT_LPSTR are encoded in the code page of the property set
+    let encoding = properties.iter()
+        .find(|(id, offset)| *id == PID_CODEPAGE && u16_at(*offset) == Some(VT_I2))
+        .and_then(|(_, offset)| u16_at(offset + 4))
+        .and_then(codepage::to_encoding)
+        .unwrap_or(encoding_rs::WINDOWS_1252);
+    let string_at = |offset: usize| -> Option<String> {
+        let kind = u16_at(offset)?;
+        let size = u32_at(offset + 4)? as usize;
+        let text = match kind {
+            VT_LPSTR => encoding.decode_without_bom_handling(stream.get(offset + 8..offset + 8 + size)?).0.into_owned(),
+            VT_LPWSTR => encoding_rs::UTF_16LE.decode_without_bom_handling(stream.get(offset + 8..offset + 8 + size * 2)?).0.into_owned(),
+            _ => return None,
+        };
+        Some(text.trim_end_matches('\0').trim().to_owned()).filter(|text| !text.is_empty())
+    };
+    let time_at = |offset: usize| -> Option<i64> {
+        let ticks = (u32_at(offset + 8)? as u64) << 32 | u32_at(offset + 4)? as u64;
+        // An unset time is stored as zero
+        (u16_at(offset)? == VT_FILETIME && ticks > 0).then(|| (ticks / 10) as i64 - FILETIME_EPOCH_MICROS)
+    };
+
+    for (id, offset) in properties {
+        match id {
+            PID_TITLE => metadata.title = string_at(offset),
+            PID_AUTHOR => metadata.author = string_at(offset),
+            PID_CREATE_DTM => metadata.created = time_at(offset),
+            PID_LAST_SAVE_DTM => metadata.modified = time_at(offset),
+            PID_APP_NAME => metadata.application = string_at(offset),
+            _ => (),
+        }
+    }
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn timestamps_with_and_without_zone() {
+        assert_eq!(parse_timestamp("2024-02-29T13:45:30Z"), Some(1_709_214_330_000_000));
+        assert_eq!(parse_timestamp("2024-02-29T14:45:30+01:00"), Some(1_709_214_330_000_000));
+        assert_eq!(parse_timestamp("2024-02-29T13:45:30.5"), Some(1_709_214_330_500_000));
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+
+    #[test]
+    fn properties_by_local_name() {
+        let xml = r#"<cp:coreProperties xmlns:cp="cp" xmlns:dc="dc" xmlns:dcterms="dcterms">
+            <dc:title>Q1 &amp; Q2</dc:title><dc:creator> Ann </dc:creator><dc:subject/>
+            <dcterms:created>2024-02-29T13:45:30Z</dcterms:created></cp:coreProperties>"#;
+        let mut reader = XmlReader::new(Cursor::new(xml.as_bytes()));
+        let properties = read_properties(&mut reader, &["title", "creator", "subject", "created"]).unwrap();
+        assert_eq!(properties.get("title").map(String::as_str), Some("Q1 & Q2"));
+        assert_eq!(properties.get("creator").map(String::as_str), Some("Ann"));
+        assert_eq!(properties.get("subject"), None);
+        assert_eq!(properties.get("created").map(String::as_str), Some("2024-02-29T13:45:30Z"));
+    }
+
+    #[test]
+    fn summary_information_properties() {
+        // Header, one property set at offset 48 holding a code page, a title, an author and a creation time
+        let mut stream = vec![0u8; 48];
+        stream[44..48].copy_from_slice(&48u32.to_le_bytes());
+        let values: [(u32, Vec<u8>); 4] = [
+            (PID_CODEPAGE, [&VT_I2.to_le_bytes()[..], &[0, 0], &1252u16.to_le_bytes(), &[0, 0]].concat()),
+            (PID_TITLE, [&VT_LPSTR.to_le_bytes()[..], &[0, 0], &4u32.to_le_bytes(), b"Caf\xE9"].concat()),
+            (PID_AUTHOR, [&VT_LPWSTR.to_le_bytes()[..], &[0, 0], &4u32.to_le_bytes(), &[b'A', 0, b'n', 0, b'n', 0, 0, 0]].concat()),
+            (PID_CREATE_DTM, [&VT_FILETIME.to_le_bytes()[..], &[0, 0], &133_536_879_300_000_000u64.to_le_bytes()].concat()),
+        ];
+        let mut set = ((values.len() as u32).to_le_bytes()).to_vec();
+        let mut offset = 8 + values.len() * 8;
+        let mut body = Vec::<u8>::new();
+        for (id, value) in &values {
+            set.extend(id.to_le_bytes());
+            set.extend((offset as u32).to_le_bytes());
+            offset += value.len();
+            body.extend(value);
+        }
+        stream.extend((offset as u32).to_le_bytes());
+        stream.extend(set);
+        stream.extend(body);
+
+        let metadata = parse_summary_information(&stream);
+        assert_eq!(metadata.title.as_deref(), Some("Café"));
+        assert_eq!(metadata.author.as_deref(), Some("Ann"));
+        assert_eq!(metadata.created, Some(1_709_214_330_000_000));
+        assert_eq!(metadata.modified, None);
+        assert_eq!(metadata.application, None);
+        assert!(parse_summary_information(&[]).title.is_none());
+    }
+}
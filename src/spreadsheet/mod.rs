@@ -1,8 +1,17 @@
 use crate::database::column::Column;
 use crate::database::column::ColumnType;
+use crate::database::decimal::to_decimal;
+use crate::database::decimal::DecimalPolicy;
+use crate::database::range::Range;
+use crate::database::range::RangeSpec;
+use crate::database::table::Conversion;
 use crate::database::table::Table;
 use crate::error::ResultMessage;
 use crate::error::RustySheetError;
+use crate::helpers::metrics;
+use crate::helpers::string::repair_encoding;
+use crate::helpers::string::strip_accent;
+use crate::spreadsheet::cell::parse_duration;
 use crate::spreadsheet::cell::Cell;
 use crate::spreadsheet::cell::CellType;
 use crate::spreadsheet::ods::OdsSpreadsheet;
@@ -12,20 +21,43 @@ use crate::spreadsheet::xlsb::XlsbSpreadsheet;
 use crate::spreadsheet::xlsx::XlsxSpreadsheet;
 use criteria::Criteria;
 use glob::Pattern;
+use metadata::WorkbookMetadata;
+use shared_strings::SharedStrings;
+use shared_strings::SharedStringsBuilder;
+use sheet::CellComment;
+use sheet::CellProtection;
+use sheet::DefinedName;
+use sheet::Hyperlink;
+use sheet::ListObject;
 use sheet::Sheet;
+use sheet::truncate_text;
+use sheet::SheetInfo;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use thiserror::Error;
 
 pub(crate) mod cell;
 pub(crate) mod ods;
+pub(crate) mod output;
 pub(crate) mod excel;
+pub(crate) mod format;
+pub(crate) mod formula;
+pub(crate) mod locale;
+pub(crate) mod metadata;
 pub(crate) mod reference;
 pub(crate) mod xls;
 pub(crate) mod xlsb;
 pub(crate) mod xlsx;
 pub(crate) mod criteria;
 pub(crate) mod sheet;
+pub(crate) mod shared_strings;
+
+/// Default maximum number of characters of a column name taken from a header
+pub(crate) const MAX_HEADER_LENGTH: usize = 255;
+/// Last zero-based row index of a worksheet
+pub(crate) const MAX_ROW: usize = 1_048_575;
+/// Last zero-based column index of a worksheet (XFD)
+pub(crate) const MAX_COL: usize = 16_383;
 
 #[derive(Error, Debug)]
 pub(crate) enum SpreadsheetError {
@@ -41,6 +73,10 @@ pub(crate) enum SpreadsheetError {
     #[error("Spreadsheet '{0}': empty")]
     SpreadsheetEmptyError(String),
 
+    /// Error indicating worksheets whose parts are missing from the archive
+    #[error("Spreadsheet '{0}': missing worksheet parts {1}")]
+    MissingPartsError(String, String),
+
     /// Error indicating the file is missing or corrupted
     #[error("file '{0}' is missing or corrupted.")]
     FileError(String),
@@ -48,6 +84,26 @@ pub(crate) enum SpreadsheetError {
     /// Error indicating a specific cell value is invalid
     #[error("Cell '[{0}]{1}!{2}': {3}")]
     CellValueError(String, String, String, String),
+
+    /// Error indicating repeated rows or columns run past the largest addressable position
+    #[error("Sheet '[{0}]{1}': repeated rows or columns exceed the addressable range")]
+    RepeatOverflowError(String, String),
+
+    /// Error indicating every header cell is empty or a null literal
+    #[error("Sheet '[{0}]{1}': header row has no names, check the range and header settings")]
+    HeaderEmptyError(String, String),
+
+    /// Error indicating a sheet has more or fewer columns than declared with auto_detect=false
+    #[error("Sheet '[{0}]{1}': {2} columns found, {3} declared")]
+    DeclaredColumnsError(String, String, usize, usize),
+
+    /// Error indicating the range parameter names no defined name of the workbook
+    #[error("Spreadsheet '{0}': no defined name '{1}'")]
+    DefinedNameError(String, String),
+
+    /// Error indicating a defined name refers to a formula or to several ranges
+    #[error("Spreadsheet '{0}': defined name '{1}' refers to '{2}', not to a single range")]
+    DefinedNameTargetError(String, String, String),
 }
 
 pub(crate) trait Spreadsheet {
@@ -65,14 +121,44 @@ pub(crate) trait Spreadsheet {
         indexes: Option<HashSet<usize>>,
     ) -> Result<(Vec<String>, HashMap<usize, usize>), RustySheetError>;
 
+    /// Loads the phonetic readings (e.g. furigana) of all shared strings
+    ///
+    /// The result is indexed like the full shared string table; strings without
+    /// a reading are None. Formats without phonetic data return an empty vector.
+    fn load_phonetic_strings(&mut self) -> Result<Vec<Option<String>>, RustySheetError> {
+        Ok(Vec::new())
+    }
+
+    /// Streams the full shared string table into `strings`
+    ///
+    /// Formats whose table can be parsed item by item override this, so that a
+    /// table spilled to disk is never loaded into memory as a whole.
+    fn stream_shared_strings(&mut self, strings: &mut SharedStringsBuilder) -> Result<(), RustySheetError> {
+        let (shared_strings, _) = self.load_shared_strings(None)?;
+        shared_strings.into_iter().try_for_each(|shared_string| strings.push(shared_string))
+    }
+
+    /// Reads the worksheets and the full shared string table
+    ///
+    /// Formats that can read the shared strings concurrently override this to
+    /// overlap the two; shared-string cells keep their index either way and are
+    /// resolved when written.
+    fn read_sheets_and_shared_strings(&mut self, criteria: &Criteria, mut strings: SharedStringsBuilder) -> Result<(Vec<Sheet>, SharedStrings), RustySheetError> {
+        let sheets = self.read_sheets(criteria)?;
+        self.stream_shared_strings(&mut strings)?;
+        Ok((sheets, strings.finish()?))
+    }
+
     /// Analyzes data within specified worksheet ranges
     ///
     /// Processes sheets according to criteria and detects column types
     /// automatically. Supports header detection and type presets.
     fn analyze_sheets(&mut self, has_header: bool, criteria: &Criteria, presets: &Vec<(Pattern, ColumnType)>) -> Result<Vec<Table>, RustySheetError> {
         let mut shared_indexes = HashSet::<usize>::new();
-        let mut sheets = Vec::<(String, Vec<Option<Cell>>, Vec<ColumnType>, Option<usize>, usize, usize)>::new();
-        for sheet in self.read_sheets(criteria)? {
+        let mut sheets = Vec::<(String, usize, Option<String>, Vec<Option<Cell>>, Vec<ColumnType>, Option<usize>, usize, usize, Vec<usize>, Vec<Vec<Cell>>, usize)>::new();
+        let mut actual_sheets = self.read_sheets(criteria)?;
+        self.localize_dates(&mut actual_sheets, criteria, has_header)?;
+        for sheet in actual_sheets {
             let row_lower_bound = criteria.range.and_then(|it| it.row_lower_bound).or(sheet.row_lower_bound);
             let col_lower_bound = criteria.range.and_then(|it| it.col_lower_bound).or(sheet.col_lower_bound);
             let col_upper_bound = criteria.range.and_then(|it| it.col_upper_bound).or(sheet.col_upper_bound);
@@ -84,6 +170,7 @@ pub(crate) trait Spreadsheet {
             let col_upper_bound = col_upper_bound.unwrap();
             let mut header = vec![None::<Cell>; col_upper_bound - col_lower_bound + 1];
             let mut data = vec![Vec::<Cell>::new(); col_upper_bound - col_lower_bound + 1];
+            let mut data_rows = HashSet::<usize>::new();
 
             for cell in &sheet.cells {
                 if cell.kind == CellType::SharedString {
@@ -94,73 +181,245 @@ pub(crate) trait Spreadsheet {
                     header[index] = Some(cell.to_owned());
                 } else {
                     data[index].push(cell.to_owned());
+                    data_rows.insert(cell.row);
                 }
             }
 
-            let kinds = (col_lower_bound..=col_upper_bound).map(|col| {
+            let visible_cols = (col_lower_bound..=col_upper_bound)
+                .filter(|col| !sheet.is_hidden_col(*col))
+                .collect::<Vec<_>>();
+            let kinds = visible_cols.iter().map(|col| {
+                if criteria.all_varchar {
+                    return ColumnType::Varchar;
+                }
                 let index = col - col_lower_bound;
                 let types = data[index].iter()
-                    .map(|cell| ColumnType::from(&cell.kind, &cell.value))
+                    .map(|cell| ColumnType::from(&cell.kind, &cell.value, criteria))
                     .collect::<Vec<_>>();
                 ColumnType::detect(types)
             }).collect::<Vec<_>>();
 
             sheets.push((
                 sheet.name.to_owned(),
+                sheet.index,
+                sheet.tab_color.to_owned(),
                 header,
                 kinds,
                 row_lower_bound.map(|row| if has_header { row + 1 } else { row }),
                 col_lower_bound,
                 col_upper_bound,
+                visible_cols,
+                if criteria.validate { data } else { Vec::new() },
+                data_rows.len(),
             ));
         }
         let (shared_strings, mappings) = self.load_shared_strings(Some(shared_indexes))?;
 
         let mut tables = Vec::<Table>::new();
-        for (name, header, kinds, row_lower_bound, col_lower_bound, col_upper_bound) in sheets.into_iter() {
-            let names = (col_lower_bound..=col_upper_bound).map(|col| {
-                let index = col - col_lower_bound;
-                if let Some(cell) = &header[index] {
-                    let value = if cell.kind == CellType::SharedString {
+        for (name, index, tab_color, header, kinds, row_lower_bound, col_lower_bound, col_upper_bound, visible_cols, data, data_rows) in sheets.into_iter() {
+            let headers = visible_cols.iter().map(|&col| {
+                header[col - col_lower_bound].as_ref().map(|cell| {
+                    if cell.kind == CellType::SharedString {
                         let id = cell.value.parse::<usize>().expect("Shared string index");
-                        let index = mappings[&id];
-                        shared_strings[index].to_owned()
+                        let mut text = shared_strings[mappings[&id]].to_owned();
+                        if criteria.fix_encoding {
+                            repair_encoding(&mut text);
+                        }
+                        text
                     } else {
                         cell.to_string()
-                    };
-                    if !criteria.nulls.contains(&value) {
-                        value
-                    } else {
-                        index_to_col(col).to_owned()
                     }
-                } else {
-                    index_to_col(col).to_owned()
-                }
+                })
             }).collect::<Vec<_>>();
+            let names = headers.iter()
+                .map(|text| text.to_owned().filter(|value| !criteria.nulls.contains(value)))
+                .map(|name| name.map(|name| criteria.header_aliases.get(&name).cloned().unwrap_or(name)))
+                .map(|name| name.and_then(|name| sanitize_name(&name, criteria.max_header_length)))
+                .map(|name| if criteria.normalize_names { name.and_then(|name| normalize_name(&name)) } else { name })
+                .collect::<Vec<_>>();
+            if has_header && criteria.require_header && names.iter().all(Option::is_none) {
+                Err(SpreadsheetError::HeaderEmptyError(self.name(), name.to_owned()))?
+            }
+            let names = unique_names(fallback_names(&visible_cols, names, criteria.missing_header_name.as_deref()));
 
-            let columns = names.iter().zip(kinds)
-                .map(|(name, kind)| {
+            let columns = names.iter().zip(kinds).enumerate()
+                .map(|(index, (name, kind))| {
                     Column {
                         name: name.to_owned(),
-                        kind: presets.iter()
-                            .find(|(pattern, _)| pattern.matches(name))
-                            .map(|(_, kind)| kind.to_owned())
-                            .unwrap_or(kind.to_owned()),
+                        kind: find_preset(presets, index + 1, name).unwrap_or(kind),
                     }
                 })
                 .collect::<Vec<_>>();
+
+            // Dry-run the read of the analyzed rows: rows without a cell in a column read as NULL
+            let mut conversions = Vec::<Conversion>::new();
+            if criteria.validate {
+                for (col, column) in visible_cols.iter().zip(&columns) {
+                    let cells = &data[col - col_lower_bound];
+                    let mut conversion = Conversion {
+                        nulls: data_rows - cells.len(),
+                        ..Conversion::default()
+                    };
+                    for cell in cells {
+                        let shared_string = if cell.kind == CellType::SharedString {
+                            let id = cell.value.parse::<usize>()?;
+                            Some(shared_strings[mappings[&id]].as_str()).filter(|text| !criteria.nulls.contains(*text))
+                        } else {
+                            None
+                        };
+                        match convert(column.kind, cell, shared_string) {
+                            Ok(true) => (),
+                            Ok(false) => conversion.nulls += 1,
+                            Err(message) => {
+                                conversion.failures += 1;
+                                conversion.first_failure.get_or_insert_with(|| format!("{}: {message}", cell.reference()));
+                            }
+                        }
+                    }
+                    conversions.push(conversion);
+                }
+            }
+
+            let headers = visible_cols.into_iter().zip(headers).collect::<Vec<_>>();
             tables.push(Table {
                 name,
+                index,
+                tab_color,
                 columns,
+                headers,
                 row_lower_bound,
                 col_lower_bound,
                 col_upper_bound,
+                conversions,
             });
         }
 
         Ok(tables)
     }
 
+    /// Converts string cells holding text-month dates of the criteria's date locale into ISO dates.
+    /// With `duration_as_interval`, shared strings holding durations become inline strings,
+    /// so that their text is seen by the type detection.
+    ///
+    /// Cells of the header row (the first row when `has_header`) keep their text.
+    fn localize_dates(&mut self, sheets: &mut [Sheet], criteria: &Criteria, has_header: bool) -> Result<(), RustySheetError> {
+        if criteria.count_only || (criteria.date_locale.is_none() && !criteria.duration_as_interval) {
+            return Ok(());
+        }
+        let header_row = |sheet: &Sheet| criteria.range
+            .and_then(|it| it.row_lower_bound)
+            .or(sheet.row_lower_bound)
+            .filter(|_| has_header);
+
+        let mut shared_indexes = HashSet::<usize>::new();
+        for sheet in sheets.iter() {
+            let header_row = header_row(sheet);
+            for cell in sheet.cells.iter().filter(|cell| cell.kind == CellType::SharedString && Some(cell.row) != header_row) {
+                shared_indexes.insert(cell.value.parse::<usize>()?);
+            }
+        }
+        let (shared_strings, mappings) = if shared_indexes.is_empty() {
+            (Vec::new(), HashMap::new())
+        } else {
+            self.load_shared_strings(Some(shared_indexes))?
+        };
+
+        for sheet in sheets.iter_mut() {
+            let header_row = header_row(sheet);
+            for cell in sheet.cells.iter_mut().filter(|cell| Some(cell.row) != header_row) {
+                let text = match cell.kind {
+                    CellType::InlineString => cell.value.as_str(),
+                    CellType::SharedString => shared_strings[mappings[&cell.value.parse::<usize>()?]].as_str(),
+                    _ => continue,
+                };
+                if let Some(date) = criteria.date_locale.and_then(|locale| locale.parse_date(text)) {
+                    cell.kind = CellType::IsoDateTime;
+                    cell.value = date.format("%Y-%m-%d").to_string();
+                } else if criteria.duration_as_interval && cell.kind == CellType::SharedString && parse_duration(text).is_some() {
+                    cell.kind = CellType::InlineString;
+                    cell.value = text.to_owned();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists the worksheets in tab order with their visibility and recorded used range,
+    /// without reading their cells
+    fn list_sheets(&mut self) -> Result<Vec<SheetInfo>, RustySheetError>;
+
+    /// Lists the defined names of the workbook with the range or formula they refer to
+    ///
+    /// Formats storing names as parsed formulas (XLS and XLSB) return an empty vector.
+    fn defined_names(&mut self) -> Result<Vec<DefinedName>, RustySheetError> {
+        Ok(Vec::new())
+    }
+
+    /// Resolves the `range` parameter to the range to read and, for a sheet-qualified range
+    /// or a defined name, the worksheet it refers to
+    ///
+    /// Names local to a matched worksheet come first, then names of the whole workbook.
+    ///
+    /// # Arguments
+    /// * `range` - Cells in A1 or R1C1 notation, possibly sheet-qualified, or a defined name
+    /// * `sheet_name_patterns` - Worksheets to read, replaced by the worksheet of a sheet-qualified
+    ///   range or a defined name
+    ///
+    /// # Returns
+    /// The worksheet patterns and range to read
+    fn resolve_range(&mut self, range: Option<&RangeSpec>, sheet_name_patterns: Option<Vec<Pattern>>) -> Result<(Option<Vec<Pattern>>, Option<Range>), RustySheetError> {
+        let name = match range {
+            None => return Ok((sheet_name_patterns, None)),
+            Some(RangeSpec::Cells(range)) => return Ok((sheet_name_patterns, Some(*range))),
+            Some(RangeSpec::SheetCells(sheet_name, range)) => return Ok((Some(vec![Pattern::new(&Pattern::escape(sheet_name))?]), Some(*range))),
+            Some(RangeSpec::Name(name)) => name,
+        };
+        let rank = |defined_name: &DefinedName| match &defined_name.scope {
+            Some(scope) if sheet_name_patterns.iter().flatten().any(|pattern| pattern.matches(scope)) => 0,
+            None => 1,
+            Some(_) => 2,
+        };
+        let defined_name = self.defined_names()?.into_iter()
+            .filter(|defined_name| defined_name.name.eq_ignore_ascii_case(name))
+            .min_by_key(rank)
+            .ok_or_else(|| SpreadsheetError::DefinedNameError(self.name(), name.to_owned()))?;
+        let (sheet_name, range) = defined_name.target
+            .ok_or_else(|| SpreadsheetError::DefinedNameTargetError(self.name(), name.to_owned(), defined_name.refers_to.to_owned()))?;
+        Ok((Some(vec![Pattern::new(&Pattern::escape(&sheet_name))?]), Some(Range::try_from(range.as_str())?)))
+    }
+
+    /// Lists the comments attached to the cells of the worksheets, in worksheet order
+    ///
+    /// Formats storing comments as binary records (XLS and XLSB) return an empty vector.
+    fn comments(&mut self) -> Result<Vec<CellComment>, RustySheetError> {
+        Ok(Vec::new())
+    }
+
+    /// Lists the hyperlinks attached to the cells of the worksheets, in worksheet order
+    ///
+    /// Formats storing hyperlinks as binary records (XLS and XLSB) return an empty vector.
+    fn hyperlinks(&mut self) -> Result<Vec<Hyperlink>, RustySheetError> {
+        Ok(Vec::new())
+    }
+
+    /// Lists the protection attributes of the cells stored in the worksheets, in worksheet order
+    ///
+    /// Formats other than XLSX return an empty vector.
+    fn cell_protection(&mut self) -> Result<Vec<CellProtection>, RustySheetError> {
+        Ok(Vec::new())
+    }
+
+    /// Lists the Excel Tables (list objects) of the worksheets, in worksheet order
+    ///
+    /// Formats without tables, or storing them as binary records (XLSB), return an empty vector.
+    fn list_objects(&mut self) -> Result<Vec<ListObject>, RustySheetError> {
+        Ok(Vec::new())
+    }
+
+    /// Reads the document properties, the number of worksheets and the date system,
+    /// without reading any worksheet
+    fn metadata(&mut self) -> Result<WorkbookMetadata, RustySheetError>;
+
     /// Reads all non-empty cells within specified ranges
     ///
     /// Returns a collection of sheets with their cell data
@@ -171,34 +430,70 @@ pub(crate) trait Spreadsheet {
     ) -> Result<Vec<Sheet>, RustySheetError>;
 }
 
+/// Converts a cell to a column type the way a read writes it, without writing it
+///
+/// `shared_string` is the text of a shared-string cell, or None when it is a null
+/// literal. Decimals use the default rounding policy. Returns false when the cell
+/// reads as NULL.
+fn convert(kind: ColumnType, cell: &Cell, shared_string: Option<&str>) -> Result<bool, String> {
+    let decimal = DecimalPolicy::default();
+    match (kind, cell.kind) {
+        (ColumnType::Varchar | ColumnType::Decimal(_, _) | ColumnType::Interval, CellType::SharedString) if shared_string.is_none() => Ok(false),
+        (ColumnType::Varchar | ColumnType::Boolean, _) => Ok(true),
+        (ColumnType::BigInt, _) => cell.to_bigint().map(|_| true),
+        (ColumnType::Double, _) => cell.to_double().map(|_| true),
+        (ColumnType::Timestamp, _) => cell.to_datetime().map(|_| true),
+        (ColumnType::Date, _) => cell.to_date().map(|_| true),
+        (ColumnType::Time, _) => cell.to_time().map(|_| true),
+        (ColumnType::Interval, CellType::SharedString) => {
+            let text = shared_string.unwrap_or_default();
+            parse_duration(text).map(|_| true).ok_or_else(|| format!("parse '{text}' to interval failed"))
+        }
+        (ColumnType::Interval, _) => cell.to_interval().map(|_| true),
+        (ColumnType::Decimal(width, scale), CellType::SharedString) => to_decimal(shared_string.unwrap_or_default(), width, scale, &decimal).map(|_| true),
+        (ColumnType::Decimal(width, scale), _) => to_decimal(&cell.value, width, scale, &decimal).map(|_| true),
+    }
+}
+
 /// Opens a spreadsheet file based on its format
 ///
 /// Automatically detects the file format from the extension and returns
 /// the appropriate spreadsheet implementation (XLSX, XLS, XLSB, or ODS).
-pub(crate) fn open_spreadsheet(file_name: &str) -> Result<Box<dyn Spreadsheet + Send + Sync>, RustySheetError> {
+/// With `salvage`, ZIP-based files whose central directory is unreadable
+/// (e.g. truncated uploads) are recovered from their local file headers.
+/// With `snapshot`, local files are copied before parsing so that a sync
+/// client rewriting them cannot change the bytes halfway through a scan.
+/// With `password`, encrypted workbooks are decrypted in memory.
+/// Opened files and open errors are counted by the metrics.
+pub(crate) fn open_spreadsheet(file_name: &str, salvage: bool, snapshot: bool, password: Option<&str>) -> Result<Box<dyn Spreadsheet + Send + Sync>, RustySheetError> {
     let uri = file_name.find('?').map(|index| &file_name[0..index]).unwrap_or(file_name);
     let extension = if let Some(index) = uri.rfind('.') {
         &uri.to_ascii_lowercase()[index + 1..]
     } else {
         ""
     };
-    match extension {
-        "xlsx" | "xlsm" | "xlam" => Ok(Box::new(XlsxSpreadsheet::open(file_name)?)),
-        "xlsb" => Ok(Box::new(XlsbSpreadsheet::open(file_name)?)),
-        "xls" | "xla" | "et" | "ett" => Ok(Box::new(XlsSpreadsheet::open(file_name)?)),
-        "ods" => Ok(Box::new(OdsSpreadsheet::open(file_name)?)),
-        _ => Err(SpreadsheetError::SpreadsheetFormatError(file_name.to_owned()))?,
+    let spreadsheet: Result<Box<dyn Spreadsheet + Send + Sync>, RustySheetError> = match extension {
+        "xlsx" | "xlsm" | "xlam" => XlsxSpreadsheet::open(file_name, salvage, snapshot, password).map(|it| Box::new(it) as _),
+        "xlsb" => XlsbSpreadsheet::open(file_name, salvage, snapshot, password).map(|it| Box::new(it) as _),
+        "xls" | "xla" | "et" | "ett" => XlsSpreadsheet::open(file_name, snapshot, password).map(|it| Box::new(it) as _),
+        "ods" => OdsSpreadsheet::open(file_name, salvage, snapshot, password).map(|it| Box::new(it) as _),
+        _ => Err(SpreadsheetError::SpreadsheetFormatError(file_name.to_owned()).into()),
+    };
+    match &spreadsheet {
+        Ok(_) => metrics::record_file_opened(),
+        Err(error) => metrics::record_error(error.category()),
     }
+    spreadsheet
 }
 
 /// Opens multiple spreadsheet files and associates them with sheet name patterns
 ///
 /// Returns a vector of tuples containing the spreadsheet and optional
 /// sheet name patterns that match each file.
-pub(crate) fn open_spreadsheets(files: &Vec<String>, patterns: &Option<Vec<(Option<Pattern>, Pattern)>>) -> Result<Vec<(Box<dyn Spreadsheet + Send + Sync>, Option<Vec<Pattern>>)>, RustySheetError> {
+pub(crate) fn open_spreadsheets(files: &Vec<String>, patterns: &Option<Vec<(Option<Pattern>, Pattern)>>, salvage: bool, snapshot: bool, password: Option<&str>) -> Result<Vec<(Box<dyn Spreadsheet + Send + Sync>, Option<Vec<Pattern>>)>, RustySheetError> {
     let spreadsheets = files
         .iter()
-        .map(|path| open_spreadsheet(path).with_prefix(path))
+        .map(|path| open_spreadsheet(path, salvage, snapshot, password).with_prefix(path))
         .collect::<Result<Vec<_>, _>>()?;
     let spreadsheets = spreadsheets.into_iter().map(|spreadsheet| {
         let sheet_name_patterns = patterns.as_ref().map(|sheets| {
@@ -217,3 +512,178 @@ pub(crate) fn open_spreadsheets(files: &Vec<String>, patterns: &Option<Vec<(Opti
     }).collect::<Vec<_>>();
     Ok(spreadsheets)
 }
+
+/// Names the columns without a header after the `missing_header_name` template, or by
+/// their column letter without a template
+///
+/// Templated names are kept apart from the header names: a name already taken by a
+/// header or an earlier column is suffixed with `_2`, `_3`, ...
+///
+/// # Arguments
+/// * `cols` - Sheet column indexes of the columns, in output order
+/// * `names` - Header names of the columns, None for columns without a header
+/// * `template` - Template with `{n}` (1-based position) and `{col}` (column letter) placeholders
+pub(crate) fn fallback_names(cols: &[usize], names: Vec<Option<String>>, template: Option<&str>) -> Vec<String> {
+    let Some(template) = template else {
+        return cols.iter().zip(names)
+            .map(|(&col, name)| name.unwrap_or_else(|| index_to_col(col).into_owned()))
+            .collect();
+    };
+    let mut taken = names.iter().flatten().cloned().collect::<HashSet<String>>();
+    cols.iter().zip(names).enumerate()
+        .map(|(index, (&col, name))| name.unwrap_or_else(|| {
+            let base = template
+                .replace("{n}", &(index + 1).to_string())
+                .replace("{col}", &index_to_col(col));
+            let mut name = base.to_owned();
+            let mut suffix = 1usize;
+            while taken.contains(&name) {
+                suffix += 1;
+                name = format!("{base}_{suffix}");
+            }
+            taken.insert(name.to_owned());
+            name
+        }))
+        .collect()
+}
+
+/// Makes a header text usable as a column name
+///
+/// Runs of control characters (line breaks, tabs, ...) become a single space, surrounding
+/// spaces are trimmed and the name is cut to `max_length` characters.
+///
+/// # Returns
+/// The column name, or None when nothing is left of the text
+pub(crate) fn sanitize_name(text: &str, max_length: usize) -> Option<String> {
+    let mut name = String::with_capacity(text.len());
+    let mut is_control = false;
+    for char in text.chars() {
+        if char.is_control() {
+            is_control = true;
+        } else {
+            if is_control && !name.is_empty() {
+                name.push(' ');
+            }
+            is_control = false;
+            name.push(char);
+        }
+    }
+    let mut name = name.trim().to_owned();
+    if truncate_text(&mut name, max_length, "") {
+        name.truncate(name.trim_end().len());
+    }
+    Some(name).filter(|name| !name.is_empty())
+}
+
+/// Keywords DuckDB reserves, which cannot be used as column names without quoting
+const RESERVED_KEYWORDS: [&str; 76] = [
+    "all", "analyse", "analyze", "and", "any", "array", "as", "asc", "asymmetric", "both",
+    "case", "cast", "check", "collate", "column", "constraint", "create", "default", "deferrable", "desc",
+    "describe", "distinct", "do", "else", "end", "except", "false", "fetch", "for", "foreign",
+    "from", "grant", "group", "having", "in", "initially", "intersect", "into", "lambda", "lateral",
+    "leading", "limit", "not", "null", "offset", "on", "only", "or", "order", "pivot",
+    "pivot_longer", "pivot_wider", "placing", "primary", "qualify", "references", "returning", "select", "show", "some",
+    "summarize", "symmetric", "table", "then", "to", "trailing", "true", "union", "unique", "unpivot",
+    "using", "variadic", "when", "where", "window", "with",
+];
+
+/// Makes a column name usable in SQL without quoting, the way `normalize_names` of
+/// `read_csv` does (e.g. `Montant (€) 2024` becomes `montant_2024`)
+///
+/// The name is lowercased and its Latin letters lose their accents; runs of anything but
+/// letters and digits become a single underscore, trimmed at both ends. Names starting
+/// with a digit or equal to a reserved keyword are prefixed with an underscore.
+///
+/// # Returns
+/// The normalized name, or None when no letter or digit is left
+pub(crate) fn normalize_name(text: &str) -> Option<String> {
+    let mut name = String::with_capacity(text.len());
+    for char in text.chars().flat_map(char::to_lowercase) {
+        match strip_accent(char) {
+            Some(stripped) => name.push_str(stripped),
+            None if char.is_alphanumeric() => name.push(char),
+            None if ('\u{300}'..='\u{36F}').contains(&char) => {} // Combining accents of decomposed letters
+            None => {
+                if !name.is_empty() && !name.ends_with('_') {
+                    name.push('_');
+                }
+            }
+        }
+    }
+    name.truncate(name.trim_end_matches('_').len());
+    if name.is_empty() {
+        None
+    } else if name.starts_with(|char: char| char.is_ascii_digit()) || RESERVED_KEYWORDS.contains(&name.as_str()) {
+        Some(format!("_{name}"))
+    } else {
+        Some(name)
+    }
+}
+
+/// Suffixes the names already used by an earlier column with `_2`, `_3`, ..., skipping
+/// suffixed names used by other columns
+///
+/// Names are compared case-insensitively, as DuckDB resolves column names.
+pub(crate) fn unique_names(names: Vec<String>) -> Vec<String> {
+    let mut taken = names.iter().map(|name| name.to_lowercase()).collect::<HashSet<_>>();
+    let mut used = HashSet::<String>::new();
+    names.into_iter()
+        .map(|name| {
+            if used.insert(name.to_lowercase()) {
+                return name;
+            }
+            let mut suffix = 1usize;
+            loop {
+                suffix += 1;
+                let candidate = format!("{name}_{suffix}");
+                if taken.insert(candidate.to_lowercase()) {
+                    used.insert(candidate.to_lowercase());
+                    return candidate;
+                }
+            }
+        })
+        .collect()
+}
+
+/// Returns the type preset of a column, looking up its 1-based position ('#3') before
+/// its name, so that positional presets hold whatever the header says.
+fn find_preset(presets: &[(Pattern, ColumnType)], position: usize, name: &str) -> Option<ColumnType> {
+    let positional = presets.iter()
+        .find(|(pattern, _)| preset_position(pattern) == Some(position));
+    let named = || presets.iter()
+        .find(|(pattern, _)| preset_position(pattern).is_none() && pattern.matches(name));
+    positional.or_else(named).map(|(_, kind)| kind.to_owned())
+}
+
+/// Returns the 1-based column position of a '#<n>' preset, None for name patterns
+pub(crate) fn preset_position(pattern: &Pattern) -> Option<usize> {
+    pattern.as_str().strip_prefix('#')
+        .and_then(|position| position.parse::<usize>().ok())
+        .filter(|&position| position > 0)
+}
+
+/// Returns the columns declared by the presets in their order, named by their keys or,
+/// for '#n' keys, by their column letter
+pub(crate) fn declared_columns(presets: &[(Pattern, ColumnType)]) -> Vec<Column> {
+    presets.iter()
+        .map(|(pattern, kind)| Column {
+            name: match preset_position(pattern) {
+                Some(position) => index_to_col(position - 1).into_owned(),
+                None => pattern.as_str().to_owned(),
+            },
+            kind: kind.to_owned(),
+        })
+        .collect()
+}
+
+/// Replaces the inferred columns of an analyzed table with the declared columns (auto_detect=false)
+///
+/// The declared columns name and type the sheet columns by position, so the schema depends
+/// on neither the header nor the data; a sheet with more or fewer columns fails.
+pub(crate) fn apply_declared_columns(table: &mut Table, file_name: &str, presets: &[(Pattern, ColumnType)]) -> Result<(), SpreadsheetError> {
+    if table.columns.len() != presets.len() {
+        Err(SpreadsheetError::DeclaredColumnsError(file_name.to_owned(), table.name.to_owned(), table.columns.len(), presets.len()))?
+    }
+    table.columns = declared_columns(presets);
+    Ok(())
+}
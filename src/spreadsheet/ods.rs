@@ -1,30 +1,62 @@
 use crate::error::RustySheetError;
+use crate::helpers::crypto::OdfEncryption;
 use crate::helpers::reader::UnifiedReader;
+use crate::helpers::string::clear_buffer;
 use crate::helpers::xml::XmlNodeHelper;
+use crate::helpers::xml::XmlReader;
 use crate::helpers::xml::XmlTextContextHelper;
-use crate::helpers::zip::ZipHelper;
+use crate::helpers::zip::ZipPackage;
+use crate::helpers::zip::ZipPart;
 use crate::match_xml_events;
 use crate::spreadsheet::cell::Cell;
 use crate::spreadsheet::cell::CellType;
 use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::metadata::parse_timestamp;
+use crate::spreadsheet::metadata::read_properties;
+use crate::spreadsheet::metadata::WorkbookMetadata;
 use crate::spreadsheet::reference::index_to_reference;
+use crate::spreadsheet::reference::plain_range;
+use crate::spreadsheet::reference::unquote_sheet_name;
+use crate::spreadsheet::sheet::CellComment;
+use crate::spreadsheet::sheet::ColumnInfo;
+use crate::spreadsheet::sheet::DefinedName;
+use crate::spreadsheet::sheet::Hyperlink;
 use crate::spreadsheet::sheet::Sheet;
+use crate::spreadsheet::sheet::SheetInfo;
+use crate::spreadsheet::sheet::Visibility;
 use crate::spreadsheet::Spreadsheet;
 use crate::spreadsheet::SpreadsheetError;
+use crate::spreadsheet::MAX_COL;
+use quick_xml::events::BytesStart;
 use quick_xml::events::Event;
 use quick_xml::name::QName;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::io::BufReader;
+use std::io::Cursor;
 use std::io::Read;
+use std::sync::Arc;
 use thiserror::Error;
-use zip::ZipArchive;
+
+/// Original type, value and formula flag of the changed cells of a table by (row, column)
+type OriginalValues = HashMap<(usize, usize), (CellType, String, bool)>;
 
 /// ODS file MIME type identifier
 const MIME_TYPE: &[u8] = b"application/vnd.oasis.opendocument.spreadsheet";
+/// Longest mimetype entry read; the entry only holds the media type
+const MIME_TYPE_LIMIT: u64 = 256;
 /// XML element name for spreadsheet root
 const SPREADSHEET: QName = QName(b"office:spreadsheet");
+/// XML element name for style definition
+const STYLE: QName = QName(b"style:style");
+/// XML element name for table style properties
+const TABLE_PROPERTIES: QName = QName(b"style:table-properties");
 /// XML element name for table (sheet)
 const TABLE: QName = QName(b"table:table");
+/// XML element name for table column
+const TABLE_COLUMN: QName = QName(b"table:table-column");
+/// XML element name for table column group (outline)
+const TABLE_COLUMN_GROUP: QName = QName(b"table:table-column-group");
 /// XML element name for table row
 const TABLE_ROW: QName = QName(b"table:table-row");
 /// XML element name for table cell
@@ -33,10 +65,36 @@ const TABLE_CELL: QName = QName(b"table:table-cell");
 const TABLE_COVERED_CELL: QName = QName(b"table:covered-table-cell");
 /// XML element name for annotations (comments)
 const ANNOTATION: QName = QName(b"office:annotation");
+/// XML element name for the author of an annotation
+const CREATOR: QName = QName(b"dc:creator");
+/// XML element name for a hyperlink within paragraph text
+const LINK: QName = QName(b"text:a");
 /// XML element name for paragraph text
 const PARAGRAPH: QName = QName(b"text:p");
 /// XML element name for string (space) text
 const STRING: QName = QName(b"text:s");
+/// XML element name for the changes tracked in the document
+const TRACKED_CHANGES: QName = QName(b"table:tracked-changes");
+/// XML element name for a tracked change of cell content
+const CELL_CONTENT_CHANGE: QName = QName(b"table:cell-content-change");
+/// XML element name for the address of a changed cell
+const CELL_ADDRESS: QName = QName(b"table:cell-address");
+/// XML element name for the content of a cell before a change
+const PREVIOUS: QName = QName(b"table:previous");
+/// XML element name for a cell recorded by change tracking
+const CHANGE_TRACK_CELL: QName = QName(b"table:change-track-table-cell");
+/// XML element name for a named cell range
+const NAMED_RANGE: QName = QName(b"table:named-range");
+/// XML element name for a named formula
+const NAMED_EXPRESSION: QName = QName(b"table:named-expression");
+/// XML element name for the null date of the calculation settings
+const NULL_DATE: QName = QName(b"table:null-date");
+/// Path of the metadata part
+const META_PART: &str = "meta.xml";
+/// XML element name for a manifest file entry
+const FILE_ENTRY: QName = QName(b"manifest:file-entry");
+/// XML element name for manifest encryption data
+const ENCRYPTION_DATA: QName = QName(b"manifest:encryption-data");
 
 /// Error types specific to ODS spreadsheet processing
 #[derive(Error, Debug)]
@@ -51,7 +109,11 @@ pub(crate) struct OdsSpreadsheet {
     /// Name of the ODS file
     pub(crate) name: String,
     /// ZIP archive containing the ODS file contents
-    zip: ZipArchive<UnifiedReader>,
+    zip: ZipPackage<UnifiedReader>,
+    /// Path of the document content part, resolved through the manifest
+    content_path: String,
+    /// Decrypted content part of a password protected document
+    content: Option<Arc<[u8]>>,
 }
 
 impl OdsSpreadsheet {
@@ -59,20 +121,40 @@ impl OdsSpreadsheet {
     ///
     /// # Arguments
     /// * `file_name` - Path to the ODS file to open
+    /// * `salvage` - Whether to recover the entries of an archive with a damaged central directory
+    /// * `snapshot` - Whether to read a consistent copy of a local file instead of the file itself
+    /// * `password` - Password decrypting the content of a password protected document
     ///
     /// # Returns
     /// * `Result<Self, RustySheetError>` - ODS spreadsheet instance or error
-    pub(crate) fn open(file_name: &str) -> Result<Self, RustySheetError> {
+    pub(crate) fn open(file_name: &str, salvage: bool, snapshot: bool, password: Option<&str>) -> Result<Self, RustySheetError> {
         // Open file from local path or remote URL
-        let reader = UnifiedReader::new(file_name)?;
-        let mut zip = ZipArchive::new(reader)?;
-        check_mime(&mut zip)?;
-        if is_password_protected(&mut zip)? {
-            Err(SpreadsheetError::SpreadsheetPasswordProtectedError(file_name.to_owned()))?;
+        let reader = UnifiedReader::open(file_name, snapshot)?;
+        let mut zip = ZipPackage::open(reader, salvage)?;
+        let has_mime_type = check_mime(&mut zip)?;
+        let (encryption, content_path, media_type) = load_manifest(&mut zip)?;
+        // Without a mimetype entry, the media type of the package root in the manifest identifies the document
+        if !has_mime_type && media_type.is_some_and(|media_type| media_type.as_bytes() != MIME_TYPE) {
+            Err(OdsError::MimeTypeError)?;
         }
+        let content = match encryption {
+            Some(encryption) => {
+                let password = password
+                    .ok_or_else(|| SpreadsheetError::SpreadsheetPasswordProtectedError(file_name.to_owned()))?;
+                let mut file = zip
+                    .file(&content_path)?
+                    .ok_or_else(|| SpreadsheetError::FileError(file_name.to_owned()))?;
+                let mut data = Vec::new();
+                file.read_to_end(&mut data)?;
+                Some(Arc::from(encryption.decrypt(&data, password)?))
+            }
+            None => None,
+        };
         Ok(OdsSpreadsheet {
             name: file_name.to_owned(),
             zip,
+            content_path,
+            content,
         })
     }
 }
@@ -100,6 +182,278 @@ impl Spreadsheet for OdsSpreadsheet {
         Ok((Vec::new(), HashMap::new()))
     }
 
+    /// Lists the tables with the display property of their table style
+    ///
+    /// The content part holds the tables and their cells together, so the whole part is
+    /// scanned, but no cell is parsed. ODS does not record the used range of a table.
+    fn list_sheets(&mut self) -> Result<Vec<SheetInfo>, RustySheetError> {
+        let mut sheets = Vec::<SheetInfo>::new();
+        let mut style_name = String::new();
+        // Table style names whose tables are hidden, declared in the automatic styles before the tables
+        let mut hidden_styles = HashSet::<String>::new();
+        // Depth of nested tables, e.g. tables embedded in cells
+        let mut depth = 0usize;
+        let mut reader = match &self.content {
+            Some(content) => XmlReader::new(BufReader::new(ZipPart::Cached(Cursor::new(content.clone())))),
+            None => self.zip
+                .xml_reader(&self.content_path)?
+                .ok_or_else(|| SpreadsheetError::FileError(self.name.to_owned()))?,
+        };
+        match_xml_events!(reader => {
+            Event::End(event) if event.name() == SPREADSHEET => break,
+            Event::Start(event) if event.name() == STYLE => {
+                style_name.clear();
+                style_name.push_str(&event.get_attribute_value("style:name")?.unwrap_or_default());
+            }
+            Event::Start(event) if event.name() == TABLE_PROPERTIES => {
+                if event.get_attribute_value("table:display")?.is_some_and(|display| display == "false") {
+                    hidden_styles.insert(style_name.to_owned());
+                }
+            }
+            Event::Start(event) if event.name() == TABLE => {
+                if depth == 0 {
+                    let hidden = event.get_attribute_value("table:style-name")?
+                        .is_some_and(|name| hidden_styles.contains(name.as_ref()));
+                    sheets.push(SheetInfo {
+                        name: event.get_attribute_value("table:name")?.unwrap_or_default().to_string(),
+                        index: sheets.len(),
+                        visibility: if hidden { Visibility::Hidden } else { Visibility::Visible },
+                        dimension: None,
+                    });
+                }
+                depth += 1;
+            }
+            Event::End(event) if event.name() == TABLE => depth = depth.saturating_sub(1),
+        });
+        Ok(sheets)
+    }
+
+    /// Lists the named ranges and named expressions, those declared inside a table
+    /// being local to it
+    fn defined_names(&mut self) -> Result<Vec<DefinedName>, RustySheetError> {
+        let mut names = Vec::<DefinedName>::new();
+        // Names of the enclosing tables, the innermost last
+        let mut tables = Vec::<String>::new();
+        let mut reader = match &self.content {
+            Some(content) => XmlReader::new(BufReader::new(ZipPart::Cached(Cursor::new(content.clone())))),
+            None => self.zip
+                .xml_reader(&self.content_path)?
+                .ok_or_else(|| SpreadsheetError::FileError(self.name.to_owned()))?,
+        };
+        match_xml_events!(reader => {
+            Event::End(event) if event.name() == SPREADSHEET => break,
+            Event::Start(event) if event.name() == TABLE => {
+                tables.push(event.get_attribute_value("table:name")?.unwrap_or_default().to_string());
+            }
+            Event::End(event) if event.name() == TABLE => {
+                tables.pop();
+            }
+            Event::Start(event) if event.name() == NAMED_RANGE || event.name() == NAMED_EXPRESSION => {
+                let (refers_to, target) = if event.name() == NAMED_RANGE {
+                    let address = event.get_attribute_value("table:cell-range-address")?.unwrap_or_default().to_string();
+                    let target = split_range_address(&address);
+                    (address, target)
+                } else {
+                    (event.get_attribute_value("table:expression")?.unwrap_or_default().to_string(), None)
+                };
+                names.push(DefinedName {
+                    name: event.get_attribute_value("table:name")?.unwrap_or_default().to_string(),
+                    scope: tables.first().cloned(),
+                    refers_to,
+                    target,
+                    hidden: false,
+                });
+            }
+        });
+        Ok(names)
+    }
+
+    /// Lists the annotations of the table cells, following the repeated rows and cells
+    /// to find the position of each annotated cell
+    fn comments(&mut self) -> Result<Vec<CellComment>, RustySheetError> {
+        let mut comments = Vec::<CellComment>::new();
+        let mut sheet_name = String::new();
+        // Depth of nested tables, e.g. tables embedded in cells
+        let mut depth = 0usize;
+        let mut row = 0usize;
+        let mut col = 0usize;
+        let mut row_count = 0usize;
+        let mut col_count = 0usize;
+        let mut comment = None::<CellComment>;
+        let mut is_author = false;
+        let mut is_paragraph = false;
+        let mut reader = match &self.content {
+            Some(content) => XmlReader::new(BufReader::new(ZipPart::Cached(Cursor::new(content.clone())))),
+            None => self.zip
+                .xml_reader(&self.content_path)?
+                .ok_or_else(|| SpreadsheetError::FileError(self.name.to_owned()))?,
+        };
+        match_xml_events!(reader => {
+            Event::End(event) if event.name() == SPREADSHEET => break,
+            Event::Start(event) if event.name() == TABLE => {
+                if depth == 0 {
+                    sheet_name = event.get_attribute_value("table:name")?.unwrap_or_default().to_string();
+                    row = 0;
+                }
+                depth += 1;
+            }
+            Event::End(event) if event.name() == TABLE => depth = depth.saturating_sub(1),
+            Event::Start(event) if depth == 1 && event.name() == TABLE_ROW => {
+                row_count = event.parse_attribute_value("table:number-rows-repeated")?.unwrap_or(1);
+                col = 0;
+            }
+            Event::End(event) if depth == 1 && event.name() == TABLE_ROW => row = row.saturating_add(row_count),
+            Event::Start(event) if depth == 1 && (event.name() == TABLE_CELL || event.name() == TABLE_COVERED_CELL) => {
+                col_count = event.parse_attribute_value("table:number-columns-repeated")?.unwrap_or(1);
+            }
+            Event::End(event) if depth == 1 && (event.name() == TABLE_CELL || event.name() == TABLE_COVERED_CELL) => {
+                col = col.saturating_add(col_count);
+            }
+            Event::Start(event) if depth == 1 && event.name() == ANNOTATION => {
+                comment = Some(CellComment {
+                    sheet_name: sheet_name.to_owned(),
+                    reference: index_to_reference(row, col),
+                    author: None,
+                    text: String::new(),
+                });
+            }
+            Event::End(event) if event.name() == ANNOTATION => comments.extend(comment.take()),
+            Event::Start(event) if comment.is_some() && event.name() == CREATOR => is_author = true,
+            Event::End(event) if event.name() == CREATOR => is_author = false,
+            Event::Start(event) if event.name() == PARAGRAPH => if let Some(comment) = &mut comment {
+                if !comment.text.is_empty() {
+                    comment.text.push('\n');
+                }
+                is_paragraph = true;
+            },
+            Event::End(event) if event.name() == PARAGRAPH => is_paragraph = false,
+            Event::Start(event) if is_paragraph && event.name() == STRING => if let Some(comment) = &mut comment {
+                let count = event.parse_attribute_value("text:c")?.unwrap_or(1);
+                for _ in 0..count {
+                    comment.text.push(' ');
+                }
+            },
+            // The date of the annotation is left out
+            Event::Text(event) if is_author || is_paragraph => if let Some(comment) = &mut comment {
+                if is_author {
+                    comment.author.get_or_insert_with(String::new).push_bytes_text(&event)?;
+                } else {
+                    comment.text.push_bytes_text(&event)?;
+                }
+            },
+            Event::GeneralRef(event) if is_author || is_paragraph => if let Some(comment) = &mut comment {
+                if is_author {
+                    comment.author.get_or_insert_with(String::new).push_bytes_ref(&event)?;
+                } else {
+                    comment.text.push_bytes_ref(&event)?;
+                }
+            },
+        });
+        Ok(comments)
+    }
+
+    /// Lists the hyperlinks in the text of the table cells, leaving out those of
+    /// annotations; a cell holds as many links as its text does
+    fn hyperlinks(&mut self) -> Result<Vec<Hyperlink>, RustySheetError> {
+        let mut hyperlinks = Vec::<Hyperlink>::new();
+        let mut sheet_name = String::new();
+        // Depth of nested tables, e.g. tables embedded in cells
+        let mut depth = 0usize;
+        let mut row = 0usize;
+        let mut col = 0usize;
+        let mut row_count = 0usize;
+        let mut col_count = 0usize;
+        let mut hyperlink = None::<Hyperlink>;
+        let mut is_annotation = false;
+        let mut reader = match &self.content {
+            Some(content) => XmlReader::new(BufReader::new(ZipPart::Cached(Cursor::new(content.clone())))),
+            None => self.zip
+                .xml_reader(&self.content_path)?
+                .ok_or_else(|| SpreadsheetError::FileError(self.name.to_owned()))?,
+        };
+        match_xml_events!(reader => {
+            Event::End(event) if event.name() == SPREADSHEET => break,
+            Event::Start(event) if event.name() == TABLE => {
+                if depth == 0 {
+                    sheet_name = event.get_attribute_value("table:name")?.unwrap_or_default().to_string();
+                    row = 0;
+                }
+                depth += 1;
+            }
+            Event::End(event) if event.name() == TABLE => depth = depth.saturating_sub(1),
+            Event::Start(event) if depth == 1 && event.name() == TABLE_ROW => {
+                row_count = event.parse_attribute_value("table:number-rows-repeated")?.unwrap_or(1);
+                col = 0;
+            }
+            Event::End(event) if depth == 1 && event.name() == TABLE_ROW => row = row.saturating_add(row_count),
+            Event::Start(event) if depth == 1 && (event.name() == TABLE_CELL || event.name() == TABLE_COVERED_CELL) => {
+                col_count = event.parse_attribute_value("table:number-columns-repeated")?.unwrap_or(1);
+            }
+            Event::End(event) if depth == 1 && (event.name() == TABLE_CELL || event.name() == TABLE_COVERED_CELL) => {
+                col = col.saturating_add(col_count);
+            }
+            Event::Start(event) if event.name() == ANNOTATION => is_annotation = true,
+            Event::End(event) if event.name() == ANNOTATION => is_annotation = false,
+            Event::Start(event) if depth == 1 && !is_annotation && event.name() == LINK => {
+                hyperlink = Some(Hyperlink {
+                    sheet_name: sheet_name.to_owned(),
+                    reference: index_to_reference(row, col),
+                    display_text: Some(String::new()),
+                    target_url: event.get_attribute_value("xlink:href")?.unwrap_or_default().to_string(),
+                });
+            }
+            Event::End(event) if event.name() == LINK => hyperlinks.extend(hyperlink.take()),
+            Event::Start(event) if event.name() == STRING => if let Some(text) = hyperlink.as_mut().and_then(|hyperlink| hyperlink.display_text.as_mut()) {
+                let count = event.parse_attribute_value("text:c")?.unwrap_or(1);
+                for _ in 0..count {
+                    text.push(' ');
+                }
+            },
+            Event::Text(event) => if let Some(text) = hyperlink.as_mut().and_then(|hyperlink| hyperlink.display_text.as_mut()) {
+                text.push_bytes_text(&event)?;
+            },
+            Event::GeneralRef(event) => if let Some(text) = hyperlink.as_mut().and_then(|hyperlink| hyperlink.display_text.as_mut()) {
+                text.push_bytes_ref(&event)?;
+            },
+        });
+        Ok(hyperlinks)
+    }
+
+    /// Reads the document properties from the metadata part, counting the tables
+    ///
+    /// ODS stores dates as ISO text, so the date system is only the null date of the
+    /// calculation settings, which precede the tables in the content part.
+    fn metadata(&mut self) -> Result<WorkbookMetadata, RustySheetError> {
+        let properties = match self.zip.xml_reader(META_PART)? {
+            Some(mut reader) => read_properties(&mut reader, &["title", "initial-creator", "creator", "creation-date", "date", "generator"])?,
+            None => HashMap::new(),
+        };
+        let sheet_count = self.list_sheets()?.len();
+        let mut is_1904 = false;
+        let mut reader = match &self.content {
+            Some(content) => XmlReader::new(BufReader::new(ZipPart::Cached(Cursor::new(content.clone())))),
+            None => self.zip
+                .xml_reader(&self.content_path)?
+                .ok_or_else(|| SpreadsheetError::FileError(self.name.to_owned()))?,
+        };
+        match_xml_events!(reader => {
+            Event::Start(event) if event.name() == TABLE => break,
+            Event::Start(event) if event.name() == NULL_DATE => {
+                is_1904 = event.get_attribute_value("table:date-value")?.is_some_and(|date| date == "1904-01-01");
+            }
+        });
+        Ok(WorkbookMetadata {
+            title: properties.get("title").cloned(),
+            // dc:creator is the author of the last modification
+            author: properties.get("initial-creator").or_else(|| properties.get("creator")).cloned(),
+            created: properties.get("creation-date").map(String::as_str).and_then(parse_timestamp),
+            modified: properties.get("date").map(String::as_str).and_then(parse_timestamp),
+            application: properties.get("generator").cloned(),
+            sheet_count,
+            is_1904,
+        })
+    }
+
     /// Reads sheets from the ODS file according to specified criteria
     ///
     /// # Arguments
@@ -110,15 +464,40 @@ impl Spreadsheet for OdsSpreadsheet {
     fn read_sheets(&mut self, criteria: &Criteria) -> Result<Vec<Sheet>, RustySheetError> {
         let mut sheets = Vec::<Sheet>::new();
         let mut sheet_count = 0usize;
+        let mut table_count = 0usize;
         let mut sheet_name = String::new();
-        let mut reader = self.zip
-            .xml_reader("content.xml")?
-            .expect("content.xml");
+        let mut style_name = String::new();
+        // Tab colors by table style name, declared in the automatic styles before the tables
+        let mut tab_colors = HashMap::<String, String>::new();
+        let mut tab_color = None::<String>;
+        // Original values of changed cells by table index, declared before the tables
+        let mut originals = HashMap::<usize, OriginalValues>::new();
+        let mut reader = match &self.content {
+            Some(content) => XmlReader::new(BufReader::new(ZipPart::Cached(Cursor::new(content.clone())))),
+            None => self.zip
+                .xml_reader(&self.content_path)?
+                .ok_or_else(|| SpreadsheetError::FileError(self.name.to_owned()))?,
+        };
         'sheets: loop {
             match_xml_events!(reader => {
                 Event::End(event) if event.name() == SPREADSHEET => break 'sheets,
+                Event::Start(event) if criteria.original_values && event.name() == TRACKED_CHANGES => {
+                    originals = read_tracked_changes(&mut reader, criteria)?;
+                }
+                Event::Start(event) if event.name() == STYLE => {
+                    style_name.clear();
+                    style_name.push_str(&event.get_attribute_value("style:name")?.unwrap_or_default());
+                }
+                Event::Start(event) if event.name() == TABLE_PROPERTIES => {
+                    if let Some(color) = event.get_attribute_value("tableooo:tab-color")? {
+                        tab_colors.insert(style_name.to_owned(), color.to_uppercase());
+                    }
+                }
                 Event::Start(event) if event.name() == TABLE => {
                     let table_name = event.get_attribute_value("table:name")?.expect("Sheet name");
+                    tab_color = event.get_attribute_value("table:style-name")?
+                        .and_then(|name| tab_colors.get(name.as_ref()).cloned());
+                    table_count += 1;
                     sheet_name.clear();
                     sheet_name.push_str(&table_name);
                     if criteria.sheet_limit.map(|limit| sheet_count >= limit).unwrap_or(false) {
@@ -131,182 +510,680 @@ impl Spreadsheet for OdsSpreadsheet {
                     }
                 }
             });
-            let mut sheet = Sheet::new(&self.name, &sheet_name, criteria.range, criteria.rows_limit, criteria.skip_empty_rows);
-            let mut last_row = sheet.chunk_row_lower;
-
-            // Cell信息
-            let mut row = 0usize;
-            let mut col = 0usize;
-            let mut row_count = 0usize;
-            let mut col_count = 0usize;
-            let mut kind = CellType::default();
-            let mut value = String::new();
-            // 上下文信息
-            let mut element_context = false; // 是否读取子元素
-            let mut comment_context = false; // 是否为注释内容
-            match_xml_events!(reader => {
-                Event::End(event) if event.name() == TABLE => break,
-                Event::Start(event) if event.name() == TABLE_ROW => {
-                    row_count = event.parse_attribute_value("table:number-rows-repeated")?.unwrap_or(1);
-                    col = 0;
-                }
-                Event::End(event) if event.name() == TABLE_ROW => {
-                    row += row_count;
-                    if sheet.after_row_upper_bound(row) {
+            let mut sheet = Sheet::new(&self.name, &sheet_name, criteria.range, criteria.rows_limit, criteria.sample_rows, criteria.skip_empty_rows, criteria.count_only);
+            sheet.index = table_count - 1;
+            sheet.tab_color = tab_color.take();
+            sheet.max_cell_length = criteria.max_cell_length.to_owned();
+            sheet.fix_encoding = criteria.fix_encoding;
+            sheet.blocks = criteria.ranges.to_owned();
+            sheet.stop_when_stable(criteria);
+            if criteria.checksum {
+                sheet.hash_only();
+            }
+            let parsed = read_table(&mut reader, &mut sheet, criteria, originals.get(&(table_count - 1)));
+            // The content stream cannot be resumed past a structural error
+            let broken = parsed.is_err();
+            sheet.tolerate(parsed, criteria.lenient)?;
+            sheet.finish(criteria.end_at_empty_row);
+            sheets.push(sheet);
+            if broken {
+                break;
+            }
+
+            if criteria.sheet_limit.map(|limit| sheet_count >= limit).unwrap_or(false) {
+                break;
+            }
+        }
+
+        Ok(sheets)
+    }
+}
+
+/// Parses the rows of a table element, up to its end tag, into the sheet
+///
+/// # Arguments
+/// * `reader` - Reader of content.xml positioned after the table start tag
+/// * `sheet` - Sheet receiving the cells
+/// * `criteria` - Selection criteria for which data to extract
+/// * `originals` - Original values of the cells changed since change tracking was turned on
+fn read_table(reader: &mut XmlReader<BufReader<ZipPart<'_, UnifiedReader>>>, sheet: &mut Sheet, criteria: &Criteria, originals: Option<&OriginalValues>) -> Result<(), RustySheetError> {
+    let mut last_row = sheet.chunk_row_lower;
+
+    // Cell信息
+    let mut row = 0usize;
+    let mut col = 0usize;
+    let mut row_count = 0usize;
+    let mut col_count = 0usize;
+    let mut kind = CellType::default();
+    let mut value = String::new();
+    let mut is_formula = false;
+    // 上下文信息
+    let mut element_context = false; // 是否读取子元素
+    let mut comment_context = false; // 是否为注释内容
+    // 列信息
+    let mut column_count = 0usize;
+    let mut column_groups = Vec::<bool>::new(); // 列分组是否折叠
+    match_xml_events!(reader => {
+        Event::End(event) if event.name() == TABLE => break,
+        Event::Start(event) if !criteria.include_hidden_columns && event.name() == TABLE_COLUMN_GROUP => {
+            let display = event.get_attribute_value("table:display")?.map(|cow| cow != "false").unwrap_or(true);
+            column_groups.push(!display);
+        }
+        Event::End(event) if !criteria.include_hidden_columns && event.name() == TABLE_COLUMN_GROUP => {
+            column_groups.pop();
+        }
+        Event::Start(event) if !criteria.include_hidden_columns && event.name() == TABLE_COLUMN => {
+            let repeated = event.parse_attribute_value::<usize>("table:number-columns-repeated")?.unwrap_or(1);
+            let column_end = column_count.checked_add(repeated)
+                .ok_or_else(|| SpreadsheetError::RepeatOverflowError(sheet.file_name.to_owned(), sheet.name.to_owned()))?;
+            let visible = event.get_attribute_value("table:visibility")?.map(|cow| cow == "visible").unwrap_or(true);
+            // Columns repeated zero times, or past the last column of a worksheet, hide nothing
+            if (!visible || column_groups.contains(&true)) && column_count < column_end && column_count <= MAX_COL {
+                sheet.hide_columns(&mut vec![ColumnInfo {
+                    lower: column_count,
+                    upper: (column_end - 1).min(MAX_COL),
+                    hidden: true,
+                    ..Default::default()
+                }]);
+            }
+            column_count = column_end;
+        }
+        Event::Start(event) if event.name() == TABLE_ROW => {
+            // Repeats are not limited to Excel's 1,048,576 rows, so generated
+            // files may address any row a usize holds, but no further
+            row_count = event.parse_attribute_value("table:number-rows-repeated")?.unwrap_or(1);
+            if row.checked_add(row_count).is_none() {
+                Err(SpreadsheetError::RepeatOverflowError(sheet.file_name.to_owned(), sheet.name.to_owned()))?
+            }
+            col = 0;
+        }
+        Event::End(event) if event.name() == TABLE_ROW => {
+            row += row_count;
+            if sheet.after_row_upper_bound(row) {
+                break;
+            }
+        }
+        Event::Start(event) if event.name() == TABLE_CELL || event.name() == TABLE_COVERED_CELL => {
+            clear_buffer(&mut value);
+            col_count = event.parse_attribute_value::<usize>("table:number-columns-repeated")?.unwrap_or(1);
+            if col.checked_add(col_count).is_none() {
+                Err(SpreadsheetError::RepeatOverflowError(sheet.file_name.to_owned(), sheet.name.to_owned()))?
+            }
+            (kind, element_context, is_formula) = read_cell_attributes(&event, criteria, &mut value)?;
+        }
+        Event::End(event) if event.name() == TABLE_CELL || event.name() == TABLE_COVERED_CELL => {
+            let changed = originals.is_some_and(|originals| if row_count == 1 && col_count == 1 {
+                originals.contains_key(&(row, col))
+            } else {
+                originals.keys().any(|&(change_row, change_col)| (row..row + row_count).contains(&change_row) && (col..col + col_count).contains(&change_col))
+            });
+            if kind != CellType::Empty || changed {
+                // Jump over repeated rows before the range instead of visiting each of them
+                let row_lower = criteria.range
+                    .and_then(|range| range.row_lower_bound)
+                    .map_or(row, |row_lower_bound| row_lower_bound.clamp(row, row + row_count));
+                for row_number in row_lower..row + row_count {
+                    if sheet.after_row_upper_bound(row_number) {
                         break;
                     }
-                }
-                Event::Start(event) if event.name() == TABLE_CELL || event.name() == TABLE_COVERED_CELL => {
-                    value.clear();
-                    col_count = event.parse_attribute_value::<usize>("table:number-columns-repeated")?.unwrap_or(1);
-                    kind = if let Some(result_type) = event.get_attribute_value("office:value-type")? {
-                        match result_type.as_ref() {
-                            "boolean" => CellType::Boolean,
-                            "date" => CellType::IsoDateTime,
-                            "time" => CellType::IsoDuration,
-                            "string" => if event.get_attribute_value("calcext:value-type")?.map(|cow| cow == "error").unwrap_or(false) {
-                                if criteria.error_as_null {
-                                    CellType::Empty
-                                } else {
-                                    CellType::Error
+                    for col_offset in 0..col_count {
+                        let col_number = col + col_offset;
+                        // The original value replaces the one written after the change
+                        let (kind, value, is_formula) = match originals.and_then(|originals| originals.get(&(row_number, col_number))) {
+                            Some((original_kind, original_value, original_formula)) => (*original_kind, original_value.as_str(), *original_formula),
+                            None => (kind, value.as_str(), is_formula),
+                        };
+                        if kind != CellType::Empty && sheet.contains(row_number, col_number) {
+                            if let Some(last_row) = last_row {
+                                if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row_number) || (!sheet.is_empty() && last_row + 1 < row_number)) {
+                                    break;
                                 }
-                            } else {
-                                CellType::InlineString
-                            },
-                            _ => CellType::Number,
-                        }
-                    } else {
-                        CellType::Empty
-                    };
-
-                    if let Some(result_type) = event.get_attribute_value("office:value-type")? {
-                        match result_type.as_ref() {
-                            "string" => element_context = kind != CellType::Empty, // error_as_null
-                            "boolean" => if event.get_attribute_value("office:boolean-value")?.map(|cow| cow != "false" && cow != "0").unwrap_or(false) {
-                                value.push_str("1");
-                            } else {
-                                value.push_str("0");
-                            },
-                            "date" => if let Some(data) = event.get_attribute_value("office:date-value")? {
-                                value.push_str(&data);
-                            }
-                            "time" => if let Some(data) = event.get_attribute_value("office:time-value")? {
-                                value.push_str(&data);
-                            }
-                            _ => if let Some(data) = event.get_attribute_value("office:value")? {
-                                value.push_str(&data);
-                            }
-                        }
-                    }
-                }
-                Event::End(event) if event.name() == TABLE_CELL || event.name() == TABLE_COVERED_CELL => {
-                    if kind != CellType::Empty {
-                        for row_offset in 0..row_count {
-                            let row_number = row + row_offset;
-                            if sheet.before_row_lower_bound(row_number) {
-                                continue;
-                            } else if sheet.after_row_upper_bound(row_number) {
-                                break;
                             }
-                            for col_offset in 0..col_count {
-                                let col_number = col + col_offset;
-                                if !sheet.before_col_lower_bound(col_number) && !sheet.after_col_upper_bound(col_number) {
-                                    if let Some(last_row) = last_row {
-                                        if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row) || (!sheet.is_empty() && last_row + 1 < row)) {
-                                            break;
-                                        }
-                                    }
-                                    last_row = Some(row);
-                                    if kind != CellType::Error {
-                                        if !criteria.nulls.contains(&value) {
-                                            sheet.push(Cell {
-                                                row: row_number,
-                                                col: col_number,
-                                                kind,
-                                                value: value.to_owned(),
-                                            });
-                                        }
+                            last_row = Some(row_number);
+                            if kind != CellType::Error {
+                                if !criteria.nulls.contains(value) {
+                                    let cell = Cell {
+                                        row: row_number,
+                                        col: col_number,
+                                        kind,
+                                        value: value.to_owned(),
+                                    };
+                                    if is_formula {
+                                        sheet.push_formula(cell);
                                     } else {
-                                        let reference = index_to_reference(row, col);
-                                        Err(SpreadsheetError::CellValueError(
-                                            sheet.file_name.to_owned(),
-                                            sheet.name.to_owned(),
-                                            reference,
-                                            value.to_owned(),
-                                        ))?
+                                        sheet.push(cell);
                                     }
                                 }
+                            } else {
+                                let reference = index_to_reference(row_number, col_number);
+                                Err(SpreadsheetError::CellValueError(
+                                    sheet.file_name.to_owned(),
+                                    sheet.name.to_owned(),
+                                    reference,
+                                    value.to_owned(),
+                                ))?
                             }
                         }
                     }
-                    col += col_count;
-                    element_context = false;
-                    comment_context = false;
                 }
-                // 读取字符串内容
-                Event::Start(event) if element_context && event.name() == ANNOTATION => comment_context = true,
-                Event::End(event) if element_context && comment_context && event.name() == ANNOTATION => comment_context = false,
-                Event::Start(event) if element_context && !comment_context && event.name() == PARAGRAPH => {
-                    if !value.is_empty() {
-                        value.push('\n');
-                    }
-                }
-                Event::Start(event) if element_context && !comment_context && event.name() == STRING => {
-                    let count = event.parse_attribute_value("text:c")?.unwrap_or(1);
-                    for _ in 0..count {
-                        value.push(' ');
-                    }
-                }
-                Event::Text(event) if element_context && !comment_context => value.push_bytes_text(&event)?,
-                Event::GeneralRef(event) if element_context && !comment_context => value.push_bytes_ref(&event)?,
-            });
-            sheet.finish(criteria.end_at_empty_row);
-            sheets.push(sheet);
+            }
+            col += col_count;
+            element_context = false;
+            comment_context = false;
+        }
+        // 读取字符串内容
+        Event::Start(event) if element_context && event.name() == ANNOTATION => comment_context = true,
+        Event::End(event) if element_context && comment_context && event.name() == ANNOTATION => comment_context = false,
+        Event::Start(event) if element_context && !comment_context && event.name() == PARAGRAPH => {
+            if !value.is_empty() {
+                value.push('\n');
+            }
+        }
+        Event::Start(event) if element_context && !comment_context && event.name() == STRING => {
+            let count = event.parse_attribute_value("text:c")?.unwrap_or(1);
+            for _ in 0..count {
+                value.push(' ');
+            }
+        }
+        Event::Text(event) if element_context && !comment_context => value.push_bytes_text(&event)?,
+        Event::GeneralRef(event) if element_context && !comment_context => value.push_bytes_ref(&event)?,
+    });
+    Ok(())
+}
 
-            if criteria.sheet_limit.map(|limit| sheet_count >= limit).unwrap_or(false) {
-                break;
+/// Reads the original values of the cells whose content was changed while changes were tracked
+///
+/// The document holds the latest values, while each change keeps the value it replaced; the
+/// earliest change of a cell therefore holds its original value. Rejected changes are skipped,
+/// and inserted, deleted or moved rows and columns are not undone.
+///
+/// # Arguments
+/// * `reader` - Reader of content.xml positioned after the tracked changes start tag
+/// * `criteria` - Selection criteria deciding how errors, formatted values and formulas are read
+///
+/// # Returns
+/// Original values of the changed cells by table index
+fn read_tracked_changes(reader: &mut XmlReader<BufReader<ZipPart<'_, UnifiedReader>>>, criteria: &Criteria) -> Result<HashMap<usize, OriginalValues>, RustySheetError> {
+    let mut originals = HashMap::<usize, OriginalValues>::new();
+    let mut address = None::<(usize, usize, usize)>;
+    let mut rejected = false;
+    let mut kind = CellType::default();
+    let mut value = String::new();
+    let mut is_formula = false;
+    // 上下文信息
+    let mut change_context = false; // 是否为单元格内容变更
+    let mut previous_context = false; // 是否为变更前的内容
+    let mut element_context = false; // 是否读取子元素
+    let mut comment_context = false; // 是否为注释内容
+    match_xml_events!(reader => {
+        Event::End(event) if event.name() == TRACKED_CHANGES => break,
+        Event::Start(event) if event.name() == CELL_CONTENT_CHANGE => {
+            change_context = true;
+            address = None;
+            rejected = event.get_attribute_value("table:acceptance-state")?.map(|cow| cow == "rejected").unwrap_or(false);
+        }
+        Event::End(event) if event.name() == CELL_CONTENT_CHANGE => change_context = false,
+        Event::Start(event) if change_context && event.name() == CELL_ADDRESS => {
+            let table = event.parse_attribute_value::<usize>("table:table")?;
+            let row = event.parse_attribute_value::<usize>("table:row")?;
+            let col = event.parse_attribute_value::<usize>("table:column")?;
+            address = table.zip(row).zip(col).map(|((table, row), col)| (table, row, col));
+        }
+        Event::Start(event) if change_context && event.name() == PREVIOUS => previous_context = true,
+        Event::End(event) if change_context && event.name() == PREVIOUS => previous_context = false,
+        Event::Start(event) if previous_context && event.name() == CHANGE_TRACK_CELL => {
+            clear_buffer(&mut value);
+            (kind, element_context, is_formula) = read_cell_attributes(&event, criteria, &mut value)?;
+        }
+        Event::End(event) if previous_context && event.name() == CHANGE_TRACK_CELL => {
+            if let Some((table, row, col)) = address.filter(|_| !rejected) {
+                originals.entry(table)
+                    .or_default()
+                    .entry((row, col))
+                    .or_insert_with(|| (kind, value.to_owned(), is_formula));
+            }
+            element_context = false;
+            comment_context = false;
+        }
+        // 读取字符串内容
+        Event::Start(event) if element_context && event.name() == ANNOTATION => comment_context = true,
+        Event::End(event) if element_context && comment_context && event.name() == ANNOTATION => comment_context = false,
+        Event::Start(event) if element_context && !comment_context && event.name() == PARAGRAPH => {
+            if !value.is_empty() {
+                value.push('\n');
             }
         }
+        Event::Start(event) if element_context && !comment_context && event.name() == STRING => {
+            let count = event.parse_attribute_value("text:c")?.unwrap_or(1);
+            for _ in 0..count {
+                value.push(' ');
+            }
+        }
+        Event::Text(event) if element_context && !comment_context => value.push_bytes_text(&event)?,
+        Event::GeneralRef(event) if element_context && !comment_context => value.push_bytes_ref(&event)?,
+    });
+    Ok(originals)
+}
 
-        Ok(sheets)
+/// Determines the type of a table cell and collects the value held in its attributes
+///
+/// # Arguments
+/// * `event` - Start tag of the cell
+/// * `criteria` - Selection criteria deciding how errors and formatted values are read
+/// * `value` - Buffer receiving the value
+///
+/// # Returns
+/// The cell type, whether the value is the text of the paragraphs of the cell, and whether it is the text of its formula
+fn read_cell_attributes(event: &BytesStart, criteria: &Criteria, value: &mut String) -> Result<(CellType, bool, bool), RustySheetError> {
+    if criteria.formulas {
+        if let Some(formula) = event.get_attribute_value("table:formula")? {
+            // The namespace of the formula syntax prefixes the text, e.g. "of:=SUM([.A1:.A3])"
+            let formula = formula.split_once(':')
+                .filter(|(prefix, _)| !prefix.contains('='))
+                .map_or(formula.as_ref(), |(_, formula)| formula);
+            value.push_str(formula);
+            return Ok((CellType::InlineString, false, true));
+        }
     }
+    let kind = if let Some(result_type) = event.get_attribute_value("office:value-type")? {
+        match result_type.as_ref() {
+            "boolean" => CellType::Boolean,
+            // The paragraph text holds the value as displayed by its number style
+            "float" | "percentage" | "currency" | "date" | "time" if criteria.display_values => CellType::InlineString,
+            "date" => CellType::IsoDateTime,
+            "time" => CellType::IsoDuration,
+            "string" => if event.get_attribute_value("calcext:value-type")?.map(|cow| cow == "error").unwrap_or(false) {
+                if criteria.error_as_null {
+                    CellType::Empty
+                } else {
+                    CellType::Error
+                }
+            } else {
+                CellType::InlineString
+            },
+            _ => CellType::Number,
+        }
+    } else {
+        CellType::Empty
+    };
+
+    if let Some(result_type) = event.get_attribute_value("office:value-type")? {
+        match result_type.as_ref() {
+            "string" => return Ok((kind, kind != CellType::Empty, false)), // error_as_null
+            "float" | "percentage" | "currency" | "date" | "time" if criteria.display_values => return Ok((kind, true, false)),
+            "boolean" => if event.get_attribute_value("office:boolean-value")?.map(|cow| cow != "false" && cow != "0").unwrap_or(false) {
+                value.push_str("1");
+            } else {
+                value.push_str("0");
+            },
+            "date" => if let Some(data) = event.get_attribute_value("office:date-value")? {
+                value.push_str(&data);
+            }
+            "time" => if let Some(data) = event.get_attribute_value("office:time-value")? {
+                value.push_str(&data);
+            }
+            _ => if let Some(data) = event.get_attribute_value("office:value")? {
+                value.push_str(&data);
+            }
+        }
+    }
+    Ok((kind, false, false))
 }
 
 /// Validates that the ZIP archive contains a valid ODS file by checking MIME type
 ///
+/// Surrounding whitespace, such as a trailing newline, is ignored.
+///
 /// # Arguments
 /// * `zip` - ZIP archive to validate
 ///
 /// # Returns
-/// * `Result<(), RustySheetError>` - Success or MIME type error
-fn check_mime(zip: &mut ZipArchive<UnifiedReader>) -> Result<(), RustySheetError> {
-    if let Some(file) = &mut zip.file("mimetype")? {
-        let mut buffer = [0u8; 46];
-        file.read_exact(&mut buffer)?;
-        if &buffer[..] != MIME_TYPE {
-            Err(OdsError::MimeTypeError)?;
-        }
+/// * `Result<bool, RustySheetError>` - Whether the archive has a mimetype entry, or MIME type error
+fn check_mime(zip: &mut ZipPackage<UnifiedReader>) -> Result<bool, RustySheetError> {
+    let Some(file) = zip.file("mimetype")? else {
+        return Ok(false);
+    };
+    let mut buffer = Vec::<u8>::new();
+    file.take(MIME_TYPE_LIMIT).read_to_end(&mut buffer)?;
+    if buffer.trim_ascii() != MIME_TYPE {
+        Err(OdsError::MimeTypeError)?;
     }
-    Ok(())
+    Ok(true)
 }
 
-/// Checks if the ODS file is password protected by examining the manifest
+/// Examines the manifest for encryption and the location of the document content
+///
+/// The content part normally lives at the package root, but some generators place the
+/// spreadsheet in a subdirectory declared by a manifest entry with the spreadsheet media
+/// type. Embedded objects (charts, formulas) have their own `content.xml` and are skipped.
 ///
 /// # Arguments
 /// * `zip` - ZIP archive to check
 ///
 /// # Returns
-/// * `Result<bool, RustySheetError>` - True if password protected, false otherwise
-fn is_password_protected(zip: &mut ZipArchive<UnifiedReader>) -> Result<bool, RustySheetError> {
-    let mut reader = zip
-        .xml_reader("META-INF/manifest.xml")?
-        .expect("META-INF/manifest.xml");
-    let mut in_file_entry = false;
-    match_xml_events!(reader => {
-        Event::Start(event) if event.name() == QName(b"manifest:file-entry") => in_file_entry = true,
-        Event::Start(event) if in_file_entry && event.name() == QName(b"manifest:encryption-data") => {
-            return Ok(true);
+/// * `Result<(Option<OdfEncryption>, String, Option<String>), RustySheetError>` - Tuple containing:
+///   - Encryption of the content part, if the document is password protected
+///   - Path of the content part
+///   - Media type declared for the package root, if any
+fn load_manifest(zip: &mut ZipPackage<UnifiedReader>) -> Result<(Option<OdfEncryption>, String, Option<String>), RustySheetError> {
+    let mut encryptions = HashMap::<String, OdfEncryption>::new();
+    let mut entry_path = String::new();
+    let mut media_type_of_root = None::<String>;
+    let mut document_path = None::<String>;
+    let mut content_paths = Vec::<String>::new();
+    if let Some(mut reader) = zip.xml_reader("META-INF/manifest.xml")? {
+        let mut in_file_entry = false;
+        match_xml_events!(reader => {
+            Event::Start(event) if event.name() == FILE_ENTRY => {
+                in_file_entry = true;
+                let path = event.get_attribute_value("manifest:full-path")?.unwrap_or_default();
+                entry_path = path.trim_start_matches('/').to_owned();
+                let media_type = event.get_attribute_value("manifest:media-type")?.unwrap_or_default();
+                if path == "/" {
+                    media_type_of_root = Some(media_type.trim().to_owned());
+                }
+                if path.ends_with('/') && media_type.as_bytes() == MIME_TYPE {
+                    // Prefer the outermost document, the package root being "/"
+                    if document_path.as_ref().map(|document_path| path.len() < document_path.len()).unwrap_or(true) {
+                        document_path = Some(path.trim_start_matches('/').to_owned());
+                    }
+                } else if path.rsplit('/').next() == Some("content.xml") {
+                    content_paths.push(path.trim_start_matches('/').to_owned());
+                }
+            }
+            Event::Start(event) if in_file_entry && event.name() == ENCRYPTION_DATA => {
+                encryptions.entry(entry_path.to_owned()).or_default().parse(&event)?;
+            }
+            Event::Start(event) if encryptions.contains_key(&entry_path) => {
+                // Algorithm, start key generation and key derivation of the encryption data
+                if let Some(encryption) = encryptions.get_mut(&entry_path) {
+                    encryption.parse(&event)?;
+                }
+            }
+        });
+    }
+
+    let content_path = format!("{}content.xml", document_path.unwrap_or_default());
+    let content_path = if zip.file(&content_path)?.is_some() {
+        content_path
+    } else {
+        content_paths.into_iter().next().unwrap_or(content_path)
+    };
+    Ok((encryptions.remove(&content_path), content_path, media_type_of_root))
+}
+
+/// Splits an ODF cell range address, such as `$'Q1 Sales'.$A$1:.$D$10`, into its table
+/// name and the range as accepted by the `range` parameter
+///
+/// # Returns
+/// None for addresses without a table, spanning several tables or listing several ranges
+fn split_range_address(address: &str) -> Option<(String, String)> {
+    // Colons inside quoted table names do not separate the bounds
+    let mut bounds = Vec::<&str>::new();
+    let mut is_quoted = false;
+    let mut start = 0usize;
+    for (index, char) in address.char_indices() {
+        match char {
+            '\'' => is_quoted = !is_quoted,
+            ':' if !is_quoted => {
+                bounds.push(&address[start..index]);
+                start = index + 1;
+            }
+            _ => (),
         }
-    });
-    Ok(false)
+    }
+    bounds.push(&address[start..]);
+    if bounds.len() > 2 {
+        return None;
+    }
+
+    // The table of the upper bound may be omitted, as in `.$D$10`
+    let mut table = None::<String>;
+    let mut cells = Vec::<&str>::new();
+    for bound in bounds {
+        let (table_name, cell) = bound.trim().rsplit_once('.')?;
+        let table_name = table_name.strip_prefix('$').unwrap_or(table_name);
+        if !table_name.is_empty() {
+            let table_name = unquote_sheet_name(table_name)?;
+            if table.as_ref().is_some_and(|table| *table != table_name) {
+                return None;
+            }
+            table = Some(table_name);
+        }
+        cells.push(cell);
+    }
+    Some((table?, plain_range(&cells.join(":"))?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::range::Range;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    /// Writes an ODS package holding a single table named Data with the given rows.
+    fn write_table(path: &std::path::Path, rows: &str) {
+        write_package(path, &[("mimetype", std::str::from_utf8(MIME_TYPE).unwrap()), ("content.xml", &content(rows))]);
+    }
+
+    /// Returns a content part holding a single table named Data with the given rows.
+    fn content(rows: &str) -> String {
+        format!(
+            r#"<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"><office:body><office:spreadsheet><table:table table:name="Data">{rows}</table:table></office:spreadsheet></office:body></office:document-content>"#,
+        )
+    }
+
+    fn write_package(path: &std::path::Path, parts: &[(&str, &str)]) {
+        let mut zip = ZipWriter::new(std::fs::File::create(path).unwrap());
+        for (name, content) in parts {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    /// Returns a manifest declaring the media type of the package root.
+    fn manifest(media_type: &str) -> String {
+        format!(
+            r#"<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0"><manifest:file-entry manifest:full-path="/" manifest:media-type="{media_type}"/><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/></manifest:manifest>"#,
+        )
+    }
+
+    #[test]
+    fn rows_past_excel_limit() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_repeated_rows_{}.ods", std::process::id()));
+        write_table(&path, concat!(
+            r#"<table:table-row><table:table-cell office:value-type="float" office:value="1"/></table:table-row>"#,
+            r#"<table:table-row table:number-rows-repeated="2000000"><table:table-cell/></table:table-row>"#,
+            r#"<table:table-row><table:table-cell table:number-columns-repeated="16384"/><table:table-cell office:value-type="float" office:value="2"/></table:table-row>"#,
+            r#"<table:table-row table:number-rows-repeated="1000000000000"><table:table-cell office:value-type="float" office:value="3"/></table:table-row>"#,
+        ));
+        let mut spreadsheet = OdsSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let sheets = spreadsheet.read_sheets(&Criteria { range: Some(Range::try_from("A2000001:XFE2000003").unwrap()), skip_empty_rows: true, ..Default::default() }).unwrap();
+        // Only the repeats inside the range are visited
+        let tail = spreadsheet.read_sheets(&Criteria { range: Some(Range::try_from("A1000001999999").unwrap()), skip_empty_rows: true, ..Default::default() }).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let cells = sheets[0].cells.iter()
+            .map(|cell| (cell.row, cell.col, cell.value.as_str(), cell.reference()))
+            .collect::<Vec<_>>();
+        assert_eq!(cells, vec![
+            (2_000_001, 16384, "2", "XFE2000002".to_owned()),
+            (2_000_002, 0, "3", "A2000003".to_owned()),
+        ]);
+        let rows = tail[0].cells.iter().map(|cell| cell.row).collect::<Vec<_>>();
+        assert_eq!(rows, vec![1_000_001_999_998, 1_000_001_999_999, 1_000_002_000_000, 1_000_002_000_001]);
+    }
+
+    #[test]
+    fn hidden_column_repeats() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_hidden_columns_{}.ods", std::process::id()));
+        write_table(&path, concat!(
+            r#"<table:table-column table:number-columns-repeated="0" table:visibility="collapse"/>"#,
+            r#"<table:table-column/><table:table-column table:visibility="collapse"/>"#,
+            r#"<table:table-column table:number-columns-repeated="1000000000" table:visibility="collapse"/>"#,
+            r#"<table:table-row><table:table-cell office:value-type="float" office:value="1"/>"#,
+            r#"<table:table-cell office:value-type="float" office:value="2"/><table:table-cell office:value-type="float" office:value="3"/></table:table-row>"#,
+        ));
+        let mut spreadsheet = OdsSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let sheets = spreadsheet.read_sheets(&Criteria { include_hidden_columns: false, skip_empty_rows: true, ..Default::default() }).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Zero repeats hide nothing, and repeats past the last column of a worksheet stop at it
+        assert_eq!(sheets[0].hidden_columns, vec![(1, 1), (2, MAX_COL)]);
+        let values = sheets[0].cells.iter().map(|cell| cell.value.as_str()).collect::<Vec<_>>();
+        assert_eq!(values, vec!["1"]);
+    }
+
+    #[test]
+    fn formula_texts() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_formulas_{}.ods", std::process::id()));
+        write_table(&path, concat!(
+            r#"<table:table-row><table:table-cell office:value-type="float" office:value="1"/>"#,
+            r#"<table:table-cell table:formula="of:=[.A1]*2" office:value-type="float" office:value="2"/>"#,
+            r#"<table:table-cell table:formula="of:=1/0" office:value-type="string" calcext:value-type="error"><text:p>#DIV/0!</text:p></table:table-cell>"#,
+            r#"<table:table-cell office:value-type="string"><text:p>=text</text:p></table:table-cell></table:table-row>"#,
+        ));
+        let mut spreadsheet = OdsSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let sheets = spreadsheet.read_sheets(&Criteria { formulas: true, skip_empty_rows: true, ..Default::default() }).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let values = sheets[0].cells.iter().map(|cell| cell.value.as_str()).collect::<Vec<_>>();
+        assert_eq!(values, vec!["1", "=[.A1]*2", "=1/0", "=text"]);
+        // Text starting with `=` is not a formula
+        assert_eq!(sheets[0].formula_cells, HashSet::from([(0, 1), (0, 2)]));
+    }
+
+    #[test]
+    fn repeat_overflow() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_repeat_overflow_{}.ods", std::process::id()));
+        write_table(&path, &format!(
+            r#"<table:table-row table:number-rows-repeated="{}"><table:table-cell/></table:table-row><table:table-row table:number-rows-repeated="{}"><table:table-cell/></table:table-row>"#,
+            usize::MAX, usize::MAX,
+        ));
+        let mut spreadsheet = OdsSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let error = spreadsheet.read_sheets(&Criteria { skip_empty_rows: true, ..Default::default() }).err().map(|error| error.to_string());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(error.unwrap().contains("repeated rows or columns exceed the addressable range"));
+    }
+
+    #[test]
+    fn analysis_stops_once_types_are_stable() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_stable_rows_{}.ods", std::process::id()));
+        let row = |id: &str, value: &str| format!(
+            r#"<table:table-row><table:table-cell office:value-type="{}" office:value="{id}"><text:p>{id}</text:p></table:table-cell><table:table-cell office:value-type="{}" office:value="{value}"><text:p>{value}</text:p></table:table-cell></table:table-row>"#,
+            if id == "id" { "string" } else { "float" },
+            if value == "1.5" { "float" } else { "string" },
+        );
+        // The second column changes type on the third row, both are stable from the fourth
+        write_table(&path, &[row("id", "name"), row("1", "1.5"), row("2", "x"), row("3", "y"), row("4", "z"), row("5", "1.5")].concat());
+        let mut spreadsheet = OdsSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let stable = spreadsheet.read_sheets(&Criteria { rows_limit: Some(1000), stable_rows: Some(2), skip_empty_rows: true, ..Default::default() }).unwrap();
+        let limited = spreadsheet.read_sheets(&Criteria { rows_limit: Some(3), stable_rows: Some(2), skip_empty_rows: true, ..Default::default() }).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!((stable[0].cells.len(), stable[0].row_upper_bound), (8, Some(3)));
+        assert_eq!((limited[0].cells.len(), limited[0].row_upper_bound), (6, Some(2)));
+    }
+
+    #[test]
+    fn cell_annotations() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_annotations_{}.ods", std::process::id()));
+        write_table(&path, concat!(
+            r#"<table:table-row table:number-rows-repeated="2"><table:table-cell/></table:table-row>"#,
+            r#"<table:table-row><table:table-cell table:number-columns-repeated="2"/><table:table-cell office:value-type="float" office:value="1">"#,
+            r#"<office:annotation><dc:creator>Ann</dc:creator><dc:date>2024-02-29T13:45:30</dc:date><text:p>Check</text:p><text:p>twice<text:s text:c="2"/>&amp; fix</text:p></office:annotation>"#,
+            r#"<text:p>1</text:p></table:table-cell></table:table-row>"#,
+            r#"<table:table-row><table:table-cell><office:annotation><text:p>No author</text:p></office:annotation></table:table-cell></table:table-row>"#,
+        ));
+        let mut spreadsheet = OdsSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let comments = spreadsheet.comments().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let comments = comments.iter()
+            .map(|comment| (comment.sheet_name.as_str(), comment.reference.as_str(), comment.author.as_deref(), comment.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(comments, vec![
+            ("Data", "C3", Some("Ann"), "Check\ntwice  & fix"),
+            ("Data", "A4", None, "No author"),
+        ]);
+    }
+
+    #[test]
+    fn tracked_changes() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_tracked_changes_{}.ods", std::process::id()));
+        let change = |id: usize, address: &str, state: &str, previous: &str| format!(
+            r#"<table:cell-content-change table:id="ct{id}"{state}><table:cell-address {address}/><office:change-info><dc:creator>Ann</dc:creator><text:p>Reason</text:p></office:change-info><table:previous><table:change-track-table-cell{previous}</table:previous></table:cell-content-change>"#,
+        );
+        let changes = [
+            change(1, r#"table:table="0" table:row="0" table:column="0""#, "", r#" office:value-type="float" office:value="1"><text:p>1</text:p></table:change-track-table-cell>"#),
+            change(2, r#"table:table="0" table:row="0" table:column="0""#, "", r#" office:value-type="float" office:value="1.5"><text:p>1.5</text:p></table:change-track-table-cell>"#),
+            change(3, r#"table:table="0" table:row="0" table:column="1""#, "", "/>"),
+            change(4, r#"table:table="0" table:row="1" table:column="0""#, "", r#" office:value-type="string"><text:p>old<text:s/>value</text:p></table:change-track-table-cell>"#),
+            change(5, r#"table:table="0" table:row="1" table:column="1""#, r#" table:acceptance-state="rejected""#, r#" office:value-type="string"><text:p>x</text:p></table:change-track-table-cell>"#),
+        ].concat();
+        let content = content(concat!(
+            r#"<table:table-row><table:table-cell office:value-type="float" office:value="2"/><table:table-cell office:value-type="string"><text:p>new</text:p></table:table-cell></table:table-row>"#,
+            r#"<table:table-row><table:table-cell/><table:table-cell office:value-type="string"><text:p>kept</text:p></table:table-cell></table:table-row>"#,
+        )).replace("<office:spreadsheet>", &format!("<office:spreadsheet><table:tracked-changes>{changes}</table:tracked-changes>"));
+        write_package(&path, &[("mimetype", std::str::from_utf8(MIME_TYPE).unwrap()), ("content.xml", &content)]);
+        let mut spreadsheet = OdsSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let latest = spreadsheet.read_sheets(&Criteria { skip_empty_rows: true, ..Default::default() }).unwrap();
+        let original = spreadsheet.read_sheets(&Criteria { original_values: true, skip_empty_rows: true, ..Default::default() }).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let values = |sheet: &Sheet| sheet.cells.iter()
+            .map(|cell| (cell.reference(), cell.value.to_owned()))
+            .collect::<Vec<_>>();
+        let cells = |cells: &[(&str, &str)]| cells.iter()
+            .map(|(reference, value)| (reference.to_string(), value.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(values(&latest[0]), cells(&[("A1", "2"), ("B1", "new"), ("B2", "kept")]));
+        assert_eq!(values(&original[0]), cells(&[("A1", "1"), ("A2", "old value"), ("B2", "kept")]));
+    }
+
+    #[test]
+    fn cell_hyperlinks() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_hyperlinks_{}.ods", std::process::id()));
+        write_table(&path, concat!(
+            r#"<table:table-row table:number-rows-repeated="2"><table:table-cell/></table:table-row>"#,
+            r#"<table:table-row><table:table-cell table:number-columns-repeated="2"/><table:table-cell office:value-type="string">"#,
+            r#"<office:annotation><text:p><text:a xlink:href="https://example.com/note">note</text:a></text:p></office:annotation>"#,
+            r##"<text:p>See <text:a xlink:href="https://example.com/items?id=1&amp;v=2">Item<text:s/>1</text:a> and <text:a xlink:href="#Other.A1">back</text:a></text:p></table:table-cell></table:table-row>"##,
+        ));
+        let mut spreadsheet = OdsSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let hyperlinks = spreadsheet.hyperlinks().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let hyperlinks = hyperlinks.iter()
+            .map(|hyperlink| (hyperlink.sheet_name.as_str(), hyperlink.reference.as_str(), hyperlink.display_text.as_deref(), hyperlink.target_url.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(hyperlinks, vec![
+            ("Data", "C3", Some("Item 1"), "https://example.com/items?id=1&v=2"),
+            ("Data", "C3", Some("back"), "#Other.A1"),
+        ]);
+    }
+
+    #[test]
+    fn mime_type_detection() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_mime_type_{}.ods", std::process::id()));
+        let content = content(r#"<table:table-row><table:table-cell office:value-type="float" office:value="1"/></table:table-row>"#);
+        let open = |parts: &[(&str, &str)]| {
+            write_package(&path, parts);
+            OdsSpreadsheet::open(path.to_str().unwrap(), false, false, None).map(|_| ())
+        };
+        let trailing_newline = open(&[("mimetype", "application/vnd.oasis.opendocument.spreadsheet\r\n"), ("content.xml", &content)]);
+        let other_type = open(&[("mimetype", "application/vnd.oasis.opendocument.text"), ("content.xml", &content)]);
+        let manifest_only = open(&[("META-INF/manifest.xml", &manifest("application/vnd.oasis.opendocument.spreadsheet")), ("content.xml", &content)]);
+        let other_manifest = open(&[("META-INF/manifest.xml", &manifest("application/vnd.oasis.opendocument.text")), ("content.xml", &content)]);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(trailing_newline.is_ok());
+        assert!(other_type.is_err());
+        assert!(manifest_only.is_ok());
+        assert!(other_manifest.is_err());
+    }
 }
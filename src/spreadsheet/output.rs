@@ -0,0 +1,1114 @@
+//! Writers producing Office Open XML workbooks and OpenDocument spreadsheets from cells,
+//! the reverse of [`XlsxSpreadsheet`] and [`OdsSpreadsheet`].
+//!
+//! [`XlsxSpreadsheet`]: crate::spreadsheet::xlsx::XlsxSpreadsheet
+//! [`OdsSpreadsheet`]: crate::spreadsheet::ods::OdsSpreadsheet
+
+use crate::error::RustySheetError;
+use crate::helpers::xml::XmlAttributeHelper;
+use crate::spreadsheet::cell::to_datetime_string;
+use crate::spreadsheet::cell::Cell;
+use crate::spreadsheet::cell::CellType;
+use crate::spreadsheet::reference::index_to_reference;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::Regex;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Seek;
+use std::io::Write;
+use thiserror::Error;
+use zip::write::SimpleFileOptions;
+use zip::CompressionMethod;
+use zip::ZipArchive;
+use zip::ZipWriter;
+
+/// Errors raised while writing a workbook.
+#[derive(Error, Debug)]
+pub(crate) enum OutputError {
+    /// Error indicating a sheet name Excel refuses to open
+    #[error("Sheet name '{0}': expected 1 to 31 characters, none of : \\ / ? * [ ], not starting or ending with '")]
+    SheetNameError(String),
+
+    /// Error indicating cells past the last row or column of a worksheet
+    #[error("Sheet '{0}': cell {1} is outside the 1048576 rows and 16384 columns of a worksheet")]
+    SheetSizeError(String, String),
+
+    /// Error indicating an existing sheet already has the name, compared case-insensitively as Excel does
+    #[error("Sheet name '{0}': already used in the workbook")]
+    SheetExistsError(String),
+
+    /// Error indicating a workbook part without the element that must be patched
+    #[error("Part '{0}': missing element '{1}'")]
+    PartElementError(String, String),
+}
+
+const RELATIONSHIPS_NAMESPACE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const WORKSHEET_RELATIONSHIP: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet";
+const STYLES_RELATIONSHIP: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles";
+const WORKSHEET_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml";
+const STYLES_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml";
+
+/// Number formats of the date, date/time and time styles
+const DATE_FORMATS: [&str; 3] = [r"yyyy\-mm\-dd", r"yyyy\-mm\-dd\ hh:mm:ss", "hh:mm:ss"];
+
+/// Indexes in `cellXfs` of the styles formatting date, date/time and time serials and
+/// the header row (None keeps the default style), and whether the workbook counts dates
+/// from 1904.
+struct CellStyles {
+    date: usize,
+    datetime: usize,
+    time: usize,
+    header: Option<usize>,
+    is_1904: bool,
+}
+
+/// Cell styles of the workbooks created from [`STYLES`]
+const NEW_CELL_STYLES: CellStyles = CellStyles { date: 1, datetime: 2, time: 3, header: None, is_1904: false };
+
+/// Index in `cellXfs` of the bold header style of the workbooks created from [`STYLES`]
+const NEW_HEADER_STYLE: usize = 4;
+
+/// Font of the bold header style, added to the fonts of existing workbooks
+const HEADER_FONT: &str = r#"<{prefix}font><{prefix}b/><{prefix}sz val="11"/><{prefix}name val="Calibri"/></{prefix}font>"#;
+
+/// Presentation options of a written worksheet
+#[derive(Default)]
+pub(crate) struct SheetLayout {
+    /// Whether the cells of the first row are set in bold
+    pub(crate) bold_header: bool,
+    /// Top-left cell (row, column) of the scrolling pane; the rows above it and the
+    /// columns before it stay in view
+    pub(crate) freeze_panes: Option<(usize, usize)>,
+    /// Whether the written cells get an autofilter, with the drop-downs on the first row
+    pub(crate) autofilter: bool,
+}
+
+const CONTENT_TYPES: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+    r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+    r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+    r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+    r#"<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+    r#"<Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#,
+    r#"</Types>"#,
+);
+
+const ROOT_RELATIONSHIPS: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+    r#"</Relationships>"#,
+);
+
+const WORKBOOK_RELATIONSHIPS: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>"#,
+    r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>"#,
+    r#"</Relationships>"#,
+);
+
+/// Styles of the written cells: `s="1"` dates, `s="2"` date/times and `s="3"` times,
+/// with ISO-like formats that [`CellType::parse_custom_number_format`] reads back, and
+/// `s="4"` bold header cells.
+const STYLES: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+    r#"<numFmts count="3">"#,
+    r#"<numFmt numFmtId="164" formatCode="yyyy\-mm\-dd"/>"#,
+    r#"<numFmt numFmtId="165" formatCode="yyyy\-mm\-dd\ hh:mm:ss"/>"#,
+    r#"<numFmt numFmtId="166" formatCode="hh:mm:ss"/>"#,
+    r#"</numFmts>"#,
+    r#"<fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts>"#,
+    r#"<fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills>"#,
+    r#"<borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders>"#,
+    r#"<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>"#,
+    r#"<cellXfs count="5">"#,
+    r#"<xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/>"#,
+    r#"<xf numFmtId="164" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+    r#"<xf numFmtId="165" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+    r#"<xf numFmtId="166" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+    r#"<xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/>"#,
+    r#"</cellXfs>"#,
+    r#"<cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles>"#,
+    r#"</styleSheet>"#,
+);
+
+const ODS_MIME_TYPE: &str = "application/vnd.oasis.opendocument.spreadsheet";
+
+/// Styles part of the written spreadsheets, declaring the `Default` cell style the
+/// automatic styles of the content derive from.
+const ODS_STYLES: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+    r#"<office:document-styles xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" office:version="1.2">"#,
+    r#"<office:styles><style:default-style style:family="table-cell"/><style:style style:name="Default" style:family="table-cell"/></office:styles>"#,
+    r#"</office:document-styles>"#,
+);
+
+/// Start of the content part up to the tables: `ce1` dates, `ce2` date/times and `ce3`
+/// times with ISO-like formats, and `ce4` bold header cells.
+const ODS_CONTENT_START: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+    r#"<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:number="urn:oasis:names:tc:opendocument:xmlns:datastyle:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" office:version="1.2">"#,
+    r#"<office:automatic-styles>"#,
+    r#"<number:date-style style:name="N1"><number:year number:style="long"/><number:text>-</number:text><number:month number:style="long"/><number:text>-</number:text><number:day number:style="long"/></number:date-style>"#,
+    r#"<number:date-style style:name="N2"><number:year number:style="long"/><number:text>-</number:text><number:month number:style="long"/><number:text>-</number:text><number:day number:style="long"/><number:text> </number:text>"#,
+    r#"<number:hours number:style="long"/><number:text>:</number:text><number:minutes number:style="long"/><number:text>:</number:text><number:seconds number:style="long"/></number:date-style>"#,
+    r#"<number:time-style style:name="N3"><number:hours number:style="long"/><number:text>:</number:text><number:minutes number:style="long"/><number:text>:</number:text><number:seconds number:style="long"/></number:time-style>"#,
+    r#"<style:style style:name="ce1" style:family="table-cell" style:parent-style-name="Default" style:data-style-name="N1"/>"#,
+    r#"<style:style style:name="ce2" style:family="table-cell" style:parent-style-name="Default" style:data-style-name="N2"/>"#,
+    r#"<style:style style:name="ce3" style:family="table-cell" style:parent-style-name="Default" style:data-style-name="N3"/>"#,
+    r#"<style:style style:name="ce4" style:family="table-cell" style:parent-style-name="Default"><style:text-properties fo:font-weight="bold"/></style:style>"#,
+    r#"</office:automatic-styles>"#,
+    r#"<office:body><office:spreadsheet>"#,
+);
+
+/// Writes the cells, ordered by row then column, as the only worksheet of a new xlsx file,
+/// replacing any existing file once they are all written.
+///
+/// Numbers and booleans keep their type, `NumberDate1900`, `NumberDateTime1900` and
+/// `NumberTime1900` serials are formatted as dates and times, anything else is written
+/// as inline text. Empty cells are left out. The cells are consumed one at a time, so
+/// they can be produced while the file is written; the first error fails the write.
+///
+/// # Arguments
+/// * `file_name` - Path of the xlsx file to create
+/// * `sheet_name` - Name of the worksheet
+/// * `cells` - Cells of the worksheet, ordered by row then column
+/// * `layout` - Header style, frozen panes and autofilter of the worksheet
+pub(crate) fn write_xlsx<I: IntoIterator<Item = Result<Cell, RustySheetError>>>(file_name: &str, sheet_name: &str, cells: I, layout: &SheetLayout) -> Result<(), RustySheetError> {
+    validate_sheet_name(sheet_name)?;
+    replace_file(file_name, |file| write_xlsx_package(file, sheet_name, cells, layout))
+}
+
+/// Writes the parts of a single-worksheet xlsx package into the file.
+fn write_xlsx_package<I: IntoIterator<Item = Result<Cell, RustySheetError>>>(file: File, sheet_name: &str, cells: I, layout: &SheetLayout) -> Result<(), RustySheetError> {
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in [
+        ("[Content_Types].xml", CONTENT_TYPES),
+        ("_rels/.rels", ROOT_RELATIONSHIPS),
+        ("xl/_rels/workbook.xml.rels", WORKBOOK_RELATIONSHIPS),
+        ("xl/styles.xml", STYLES),
+    ] {
+        zip.start_file(name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+
+    zip.start_file("xl/workbook.xml", options)?;
+    write!(
+        zip,
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
+            r#"<sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets>"#,
+            r#"</workbook>"#,
+        ),
+        escape(sheet_name),
+    )?;
+
+    zip.start_file("xl/worksheets/sheet1.xml", options)?;
+    let styles = CellStyles { header: layout.bold_header.then_some(NEW_HEADER_STYLE), ..NEW_CELL_STYLES };
+    write_worksheet(&mut zip, sheet_name, cells, &styles, layout)?;
+
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+/// Adds the cells as a new worksheet after the existing ones of an xlsx file.
+///
+/// The archive is copied entry by entry into a temporary file next to it, with the
+/// workbook, its relationships, the content types and the styles patched to declare
+/// the worksheet and its cell styles, and the temporary file then replaces the original.
+///
+/// # Arguments
+/// * `file_name` - Path of the existing xlsx file
+/// * `sheet_name` - Name of the new worksheet, which no existing worksheet may have
+/// * `cells` - Cells of the worksheet, ordered by row then column
+/// * `layout` - Header style, frozen panes and autofilter of the worksheet
+pub(crate) fn append_xlsx<I: IntoIterator<Item = Result<Cell, RustySheetError>>>(file_name: &str, sheet_name: &str, cells: I, layout: &SheetLayout) -> Result<(), RustySheetError> {
+    validate_sheet_name(sheet_name)?;
+
+    let mut archive = ZipArchive::new(BufReader::new(File::open(file_name)?))?;
+    let workbook = read_part(&mut archive, "xl/workbook.xml")?;
+    let relationships = read_part(&mut archive, "xl/_rels/workbook.xml.rels")?;
+    let content_types = read_part(&mut archive, "[Content_Types].xml")?;
+    let styles = match archive.index_for_name("xl/styles.xml") {
+        Some(_) => Some(read_part(&mut archive, "xl/styles.xml")?),
+        None => None,
+    };
+
+    let names = attribute_values(&workbook, "sheet", "name")?;
+    if names.iter().any(|name| name.to_lowercase() == sheet_name.to_lowercase()) {
+        Err(OutputError::SheetExistsError(sheet_name.to_owned()))?
+    }
+    let sheet_id = attribute_values(&workbook, "sheet", "sheetId")?.iter()
+        .filter_map(|id| id.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0) + 1;
+    let is_1904 = attribute_values(&workbook, "workbookPr", "date1904")?.iter()
+        .any(|value| value == "1" || value == "true");
+    let relationship_ids = attribute_values(&relationships, "Relationship", "Id")?;
+    let mut free_relationship_ids = (1..)
+        .map(|index| format!("rId{index}"))
+        .filter(|id| !relationship_ids.contains(id));
+    let sheet_relationship_id = free_relationship_ids.next().expect("Unbounded relationship ids");
+    let part = (1..)
+        .map(|index| format!("xl/worksheets/sheet{index}.xml"))
+        .find(|part| archive.index_for_name(part).is_none())
+        .expect("Unbounded part names");
+
+    let mut added_relationships = vec![(sheet_relationship_id.to_owned(), WORKSHEET_RELATIONSHIP, part["xl/".len()..].to_owned())];
+    let mut added_content_types = vec![(format!("/{part}"), WORKSHEET_CONTENT_TYPE)];
+    let (styles, cell_styles) = match styles {
+        Some(styles) => patch_styles(&styles, is_1904, layout.bold_header)?,
+        None => {
+            let id = free_relationship_ids.next().expect("Unbounded relationship ids");
+            added_relationships.push((id, STYLES_RELATIONSHIP, "styles.xml".to_owned()));
+            added_content_types.push(("/xl/styles.xml".to_owned(), STYLES_CONTENT_TYPE));
+            let header = layout.bold_header.then_some(NEW_HEADER_STYLE);
+            (STYLES.to_owned(), CellStyles { header, is_1904, ..NEW_CELL_STYLES })
+        }
+    };
+
+    let workbook = append_children(&workbook, "sheets", 1, |prefix| format!(
+        r#"<{prefix}sheet xmlns:r="{RELATIONSHIPS_NAMESPACE}" name="{}" sheetId="{sheet_id}" r:id="{sheet_relationship_id}"/>"#,
+        escape(sheet_name),
+    ))?.ok_or_else(|| OutputError::PartElementError("xl/workbook.xml".to_owned(), "sheets".to_owned()))?;
+    let relationships = append_children(&relationships, "Relationships", added_relationships.len(), |prefix| {
+        added_relationships.iter()
+            .map(|(id, kind, target)| format!(r#"<{prefix}Relationship Id="{id}" Type="{kind}" Target="{target}"/>"#))
+            .collect()
+    })?.ok_or_else(|| OutputError::PartElementError("xl/_rels/workbook.xml.rels".to_owned(), "Relationships".to_owned()))?;
+    let content_types = append_children(&content_types, "Types", added_content_types.len(), |prefix| {
+        added_content_types.iter()
+            .map(|(part, content_type)| format!(r#"<{prefix}Override PartName="{part}" ContentType="{content_type}"/>"#))
+            .collect()
+    })?.ok_or_else(|| OutputError::PartElementError("[Content_Types].xml".to_owned(), "Types".to_owned()))?;
+
+    let temporary = format!("{file_name}.tmp");
+    let parts = [
+        ("xl/workbook.xml", workbook),
+        ("xl/_rels/workbook.xml.rels", relationships),
+        ("[Content_Types].xml", content_types),
+        ("xl/styles.xml", styles),
+    ];
+    let result = rewrite_archive(&mut archive, &temporary, &parts, &part, |writer| {
+        write_worksheet(writer, sheet_name, cells, &cell_styles, layout)
+    });
+    drop(archive);
+    match result {
+        Ok(()) => Ok(std::fs::rename(&temporary, file_name)?),
+        Err(error) => {
+            let _ = std::fs::remove_file(&temporary);
+            Err(error)
+        }
+    }
+}
+
+/// Creates a file through `write` in a temporary file next to it, which then replaces the
+/// file, so that a failed write leaves any existing file untouched.
+fn replace_file<F: FnOnce(File) -> Result<(), RustySheetError>>(file_name: &str, write: F) -> Result<(), RustySheetError> {
+    let temporary = format!("{file_name}.tmp");
+    match File::create(&temporary).map_err(RustySheetError::from).and_then(write) {
+        Ok(()) => Ok(std::fs::rename(&temporary, file_name)?),
+        Err(error) => {
+            let _ = std::fs::remove_file(&temporary);
+            Err(error)
+        }
+    }
+}
+
+/// Copies the entries of the archive into a new xlsx file without recompressing them,
+/// except the replaced parts, and writes the new worksheet part with `write_part`.
+fn rewrite_archive<R: Read + Seek, F: FnOnce(&mut ZipWriter<BufWriter<File>>) -> Result<(), RustySheetError>>(
+    archive: &mut ZipArchive<R>,
+    file_name: &str,
+    parts: &[(&str, String)],
+    part: &str,
+    write_part: F,
+) -> Result<(), RustySheetError> {
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(file_name)?));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for index in 0..archive.len() {
+        let entry = archive.by_index_raw(index)?;
+        if !parts.iter().any(|(name, _)| *name == entry.name()) {
+            zip.raw_copy_file(entry)?;
+        }
+    }
+    for (name, content) in parts {
+        zip.start_file(*name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.start_file(part, options)?;
+    write_part(&mut zip)?;
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+/// Reads a part of the archive as text.
+fn read_part<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<String, RustySheetError> {
+    let mut content = String::new();
+    archive.by_name(name)?.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Adds the date, date/time and time formats and styles after the existing ones of a
+/// styles part, and the bold header font and style when `bold_header`.
+fn patch_styles(styles: &str, is_1904: bool, bold_header: bool) -> Result<(String, CellStyles), RustySheetError> {
+    let format_id = attribute_values(styles, "numFmt", "numFmtId")?.iter()
+        .filter_map(|id| id.parse::<usize>().ok())
+        .fold(163, usize::max) + 1;
+    let formats = |prefix: &str| DATE_FORMATS.iter()
+        .enumerate()
+        .map(|(index, code)| format!(r#"<{prefix}numFmt numFmtId="{}" formatCode="{}"/>"#, format_id + index, escape(*code)))
+        .collect::<String>();
+    let styles = match append_children(styles, "numFmts", DATE_FORMATS.len(), formats)? {
+        Some(styles) => styles,
+        None => prepend_child(styles, "styleSheet", |prefix| format!(
+            r#"<{prefix}numFmts count="{}">{}</{prefix}numFmts>"#,
+            DATE_FORMATS.len(),
+            formats(prefix),
+        ))?.ok_or_else(|| OutputError::PartElementError("xl/styles.xml".to_owned(), "styleSheet".to_owned()))?,
+    };
+
+    let (styles, font_id) = if bold_header {
+        let font_id = child_count(&styles, "fonts", "font")?;
+        let styles = append_children(&styles, "fonts", 1, |prefix| HEADER_FONT.replace("{prefix}", prefix))?
+            .ok_or_else(|| OutputError::PartElementError("xl/styles.xml".to_owned(), "fonts".to_owned()))?;
+        (styles, Some(font_id))
+    } else {
+        (styles, None)
+    };
+
+    let style = child_count(&styles, "cellXfs", "xf")?;
+    let styles = append_children(&styles, "cellXfs", DATE_FORMATS.len() + usize::from(bold_header), |prefix| {
+        let date_styles = (0..DATE_FORMATS.len()).map(|index| format!(
+            r#"<{prefix}xf numFmtId="{}" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+            format_id + index,
+        ));
+        let header_style = font_id.map(|font_id| format!(
+            r#"<{prefix}xf numFmtId="0" fontId="{font_id}" fillId="0" borderId="0" xfId="0" applyFont="1"/>"#,
+        ));
+        date_styles.chain(header_style).collect()
+    })?.ok_or_else(|| OutputError::PartElementError("xl/styles.xml".to_owned(), "cellXfs".to_owned()))?;
+    let header = font_id.map(|_| style + DATE_FORMATS.len());
+    Ok((styles, CellStyles { date: style, datetime: style + 1, time: style + 2, header, is_1904 }))
+}
+
+/// Counts the `child` elements of the `parent` element, e.g. the `xf` elements of
+/// `cellXfs`, which is the index the next cell style gets.
+fn child_count(styles: &str, parent: &str, child: &str) -> Result<usize, RustySheetError> {
+    let mut reader = Reader::from_str(styles);
+    let mut is_parent = false;
+    let mut count = 0;
+    loop {
+        match reader.read_event()? {
+            Event::Start(event) if event.local_name().as_ref() == parent.as_bytes() => is_parent = true,
+            Event::End(event) if event.local_name().as_ref() == parent.as_bytes() => is_parent = false,
+            Event::Start(event) | Event::Empty(event) if is_parent && event.local_name().as_ref() == child.as_bytes() => count += 1,
+            Event::Eof => return Ok(count),
+            _ => (),
+        }
+    }
+}
+
+/// Collects the values of an attribute on every element with the given local name,
+/// both matched by local name so that namespace prefixes do not matter.
+fn attribute_values(xml: &str, element: &str, attribute: &str) -> Result<Vec<String>, RustySheetError> {
+    let mut reader = Reader::from_str(xml);
+    let mut values = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(event) | Event::Empty(event) if event.local_name().as_ref() == element.as_bytes() => {
+                for result in event.attributes() {
+                    let attribute_value = result?;
+                    if attribute_value.key.local_name().as_ref() == attribute.as_bytes() {
+                        values.push(attribute_value.get_value()?.into_owned());
+                    }
+                }
+            }
+            Event::Eof => return Ok(values),
+            _ => (),
+        }
+    }
+}
+
+/// Appends children to the first element with the given local name, raising its
+/// `count` attribute by `added` when it has one. `children` receives the namespace
+/// prefix of the element (e.g. `x:` or an empty string) to write them with.
+/// Returns None when the element is missing.
+fn append_children<F: Fn(&str) -> String>(xml: &str, element: &str, added: usize, children: F) -> Result<Option<String>, RustySheetError> {
+    let mut reader = Reader::from_str(xml);
+    let mut start_tag = None;
+    loop {
+        let position = reader.buffer_position() as usize;
+        match reader.read_event()? {
+            Event::Start(event) if start_tag.is_none() && event.local_name().as_ref() == element.as_bytes() => {
+                start_tag = Some(position..reader.buffer_position() as usize);
+            }
+            Event::End(event) if event.local_name().as_ref() == element.as_bytes() => {
+                let Some(start_tag) = start_tag else { return Ok(None) };
+                let prefix = to_prefix(event.name().as_ref(), element);
+                return Ok(Some(format!(
+                    "{}{}{}{}{}",
+                    &xml[..start_tag.start],
+                    with_count(&xml[start_tag.clone()], added),
+                    &xml[start_tag.end..position],
+                    children(&prefix),
+                    &xml[position..],
+                )));
+            }
+            Event::Empty(event) if start_tag.is_none() && event.local_name().as_ref() == element.as_bytes() => {
+                let end = reader.buffer_position() as usize;
+                let name = String::from_utf8_lossy(event.name().as_ref()).into_owned();
+                let prefix = to_prefix(event.name().as_ref(), element);
+                let tag = with_count(&xml[position..end], added);
+                return Ok(Some(format!(
+                    "{}{}>{}</{}>{}",
+                    &xml[..position],
+                    tag.trim_end_matches("/>"),
+                    children(&prefix),
+                    name,
+                    &xml[end..],
+                )));
+            }
+            Event::Eof => return Ok(None),
+            _ => (),
+        }
+    }
+}
+
+/// Inserts a child before the existing children of the first element with the given
+/// local name, see [`append_children`]. Returns None when the element is missing.
+fn prepend_child<F: Fn(&str) -> String>(xml: &str, element: &str, child: F) -> Result<Option<String>, RustySheetError> {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(event) if event.local_name().as_ref() == element.as_bytes() => {
+                let end = reader.buffer_position() as usize;
+                let prefix = to_prefix(event.name().as_ref(), element);
+                return Ok(Some(format!("{}{}{}", &xml[..end], child(&prefix), &xml[end..])));
+            }
+            Event::Eof => return Ok(None),
+            _ => (),
+        }
+    }
+}
+
+/// Returns the namespace prefix, colon included, of a qualified element name.
+fn to_prefix(name: &[u8], local_name: &str) -> String {
+    let name = String::from_utf8_lossy(name);
+    name.strip_suffix(local_name).unwrap_or_default().to_owned()
+}
+
+/// Raises the `count` attribute of a start tag, if it has one.
+fn with_count(tag: &str, added: usize) -> String {
+    let pattern = Regex::new(r#"(\scount\s*=\s*["'])(\d+)"#).expect("Hardcode regex pattern");
+    pattern.replace(tag, |captures: &regex::Captures| {
+        let count = captures[2].parse::<usize>().unwrap_or(0) + added;
+        format!("{}{}", &captures[1], count)
+    }).into_owned()
+}
+
+/// Checks the rules Excel applies to sheet names.
+fn validate_sheet_name(sheet_name: &str) -> Result<(), OutputError> {
+    let length = sheet_name.chars().count();
+    if (1..=31).contains(&length)
+        && !sheet_name.contains([':', '\\', '/', '?', '*', '[', ']'])
+        && !sheet_name.starts_with('\'')
+        && !sheet_name.ends_with('\'') {
+        Ok(())
+    } else {
+        Err(OutputError::SheetNameError(sheet_name.to_owned()))
+    }
+}
+
+/// Streams the worksheet part, opening a `<row>` element whenever the row index changes.
+fn write_worksheet<W: Write, I: IntoIterator<Item = Result<Cell, RustySheetError>>>(writer: &mut W, sheet_name: &str, cells: I, styles: &CellStyles, layout: &SheetLayout) -> Result<(), RustySheetError> {
+    writer.write_all(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+    ).as_bytes())?;
+    if let Some((row, col)) = layout.freeze_panes.filter(|&panes| panes != (0, 0)) {
+        let active_pane = match (row, col) {
+            (0, _) => "topRight",
+            (_, 0) => "bottomLeft",
+            _ => "bottomRight",
+        };
+        let x_split = if col > 0 { format!(r#" xSplit="{col}""#) } else { String::new() };
+        let y_split = if row > 0 { format!(r#" ySplit="{row}""#) } else { String::new() };
+        write!(
+            writer,
+            r#"<sheetViews><sheetView workbookViewId="0"><pane{}{} topLeftCell="{}" activePane="{}" state="frozen"/></sheetView></sheetViews>"#,
+            x_split,
+            y_split,
+            index_to_reference(row, col),
+            active_pane,
+        )?;
+    }
+    writer.write_all(b"<sheetData>")?;
+    let mut current_row = None;
+    let mut last_col = None;
+    for cell in cells {
+        let cell = cell?;
+        if cell.kind == CellType::Empty {
+            continue;
+        }
+        if cell.row >= 1 << 20 || cell.col >= 1 << 14 {
+            Err(OutputError::SheetSizeError(sheet_name.to_owned(), cell.reference()))?
+        }
+        last_col = last_col.max(Some(cell.col));
+        if current_row != Some(cell.row) {
+            if current_row.is_some() {
+                writer.write_all(b"</row>")?;
+            }
+            write!(writer, r#"<row r="{}">"#, cell.row + 1)?;
+            current_row = Some(cell.row);
+        }
+        let reference = cell.reference();
+        let style = match styles.header {
+            Some(style) if cell.row == 0 => format!(r#" s="{style}""#),
+            _ => String::new(),
+        };
+        match cell.kind {
+            CellType::Boolean => write!(writer, r#"<c r="{}"{} t="b"><v>{}</v></c>"#, reference, style, cell.value)?,
+            CellType::Number => write!(writer, r#"<c r="{}"{}><v>{}</v></c>"#, reference, style, cell.value)?,
+            CellType::NumberDate1900 | CellType::NumberDateTime1900 | CellType::NumberTime1900 => {
+                let date_style = match cell.kind {
+                    CellType::NumberDate1900 => styles.date,
+                    CellType::NumberDateTime1900 => styles.datetime,
+                    _ => styles.time,
+                };
+                match to_date_system(&cell, styles.is_1904)? {
+                    Some(serial) => write!(writer, r#"<c r="{}" s="{}"><v>{}</v></c>"#, reference, date_style, serial)?,
+                    None => {
+                        let text = to_datetime_string(&cell.value, false)?;
+                        let text = if cell.kind == CellType::NumberDate1900 { &text[..10] } else { &text };
+                        write_inline_string(writer, &reference, &style, text)?
+                    }
+                }
+            }
+            _ => write_inline_string(writer, &reference, &style, &cell.value)?,
+        }
+    }
+    if current_row.is_some() {
+        writer.write_all(b"</row>")?;
+    }
+    writer.write_all(b"</sheetData>")?;
+    if let (true, Some(last_row), Some(last_col)) = (layout.autofilter, current_row, last_col) {
+        write!(writer, r#"<autoFilter ref="A1:{}"/>"#, index_to_reference(last_row, last_col))?;
+    }
+    writer.write_all(b"</worksheet>")?;
+    Ok(())
+}
+
+/// Writes a cell holding its text inline, without the shared strings part. `style` is
+/// the cell's style attribute, if any.
+fn write_inline_string<W: Write>(writer: &mut W, reference: &str, style: &str, text: &str) -> Result<(), RustySheetError> {
+    write!(
+        writer,
+        r#"<c r="{}"{} t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+        reference,
+        style,
+        escape(to_xml_text(text)),
+    )?;
+    Ok(())
+}
+
+/// Shifts a 1900 date serial to the 1904 date system of the workbook when needed.
+/// Returns None for dates before 1904-01-01, which such workbooks cannot represent.
+fn to_date_system(cell: &Cell, is_1904: bool) -> Result<Option<String>, RustySheetError> {
+    if !is_1904 || cell.kind == CellType::NumberTime1900 {
+        Ok(Some(cell.value.to_owned()))
+    } else {
+        let serial = cell.value.parse::<f64>()? - 1_462f64;
+        Ok((serial >= 0f64).then(|| serial.to_string()))
+    }
+}
+
+/// Drops the control characters XML 1.0 cannot represent, keeping tabs and line breaks.
+fn to_xml_text(value: &str) -> String {
+    value.chars()
+        .filter(|character| !matches!(character, '\u{0}'..='\u{8}' | '\u{b}' | '\u{c}' | '\u{e}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}'))
+        .collect()
+}
+
+/// Writes the cells, ordered by row then column, as the only table of a new ods file,
+/// replacing any existing file once they are all written.
+///
+/// Cells keep the types of [`write_xlsx`] and are consumed one at a time the same way;
+/// date and time serials become ODF date and duration values. Frozen panes are stored in
+/// the view settings and the autofilter as the anonymous database range of the sheet, as
+/// LibreOffice does.
+///
+/// # Arguments
+/// * `file_name` - Path of the ods file to create
+/// * `sheet_name` - Name of the table
+/// * `columns` - Number of columns of the table, declared before its rows
+/// * `cells` - Cells of the table, ordered by row then column
+/// * `layout` - Header style, frozen panes and autofilter of the table
+pub(crate) fn write_ods<I: IntoIterator<Item = Result<Cell, RustySheetError>>>(file_name: &str, sheet_name: &str, columns: usize, cells: I, layout: &SheetLayout) -> Result<(), RustySheetError> {
+    validate_sheet_name(sheet_name)?;
+    replace_file(file_name, |file| write_ods_package(file, sheet_name, columns, cells, layout))
+}
+
+/// Writes the parts of a single-table ods package into the file.
+fn write_ods_package<I: IntoIterator<Item = Result<Cell, RustySheetError>>>(file: File, sheet_name: &str, columns: usize, cells: I, layout: &SheetLayout) -> Result<(), RustySheetError> {
+    let freeze_panes = layout.freeze_panes.filter(|&panes| panes != (0, 0));
+
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    // The mimetype entry comes first and uncompressed, so the format is recognized from the file header
+    zip.start_file("mimetype", SimpleFileOptions::default().compression_method(CompressionMethod::Stored))?;
+    zip.write_all(ODS_MIME_TYPE.as_bytes())?;
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file("META-INF/manifest.xml", options)?;
+    write!(
+        zip,
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2">"#,
+            r#"<manifest:file-entry manifest:full-path="/" manifest:version="1.2" manifest:media-type="{}"/>"#,
+            r#"<manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>"#,
+            r#"<manifest:file-entry manifest:full-path="styles.xml" manifest:media-type="text/xml"/>"#,
+            r#"{}</manifest:manifest>"#,
+        ),
+        ODS_MIME_TYPE,
+        if freeze_panes.is_some() { r#"<manifest:file-entry manifest:full-path="settings.xml" manifest:media-type="text/xml"/>"# } else { "" },
+    )?;
+    zip.start_file("styles.xml", options)?;
+    zip.write_all(ODS_STYLES.as_bytes())?;
+    if let Some((row, col)) = freeze_panes {
+        zip.start_file("settings.xml", options)?;
+        write_view_settings(&mut zip, sheet_name, row, col)?;
+    }
+
+    zip.start_file("content.xml", options)?;
+    write_table(&mut zip, sheet_name, columns, cells, layout)?;
+
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+/// Writes the settings part freezing the rows above `row` and the columns before `col`.
+fn write_view_settings<W: Write>(writer: &mut W, sheet_name: &str, row: usize, col: usize) -> Result<(), RustySheetError> {
+    // Panes are numbered 0 top-left, 1 top-right, 2 bottom-left and 3 bottom-right
+    let active_pane = usize::from(col > 0) + 2 * usize::from(row > 0);
+    let item = |name: &str, kind: &str, value: usize| format!(
+        r#"<config:config-item config:name="{name}" config:type="{kind}">{value}</config:config-item>"#,
+    );
+    write!(
+        writer,
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<office:document-settings xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:config="urn:oasis:names:tc:opendocument:xmlns:config:1.0" office:version="1.2">"#,
+            r#"<office:settings><config:config-item-set config:name="ooo:view-settings"><config:config-item-map-indexed config:name="Views"><config:config-item-map-entry>"#,
+            r#"<config:config-item config:name="ViewId" config:type="string">view1</config:config-item>"#,
+            r#"<config:config-item-map-named config:name="Tables"><config:config-item-map-entry config:name="{0}">{1}</config:config-item-map-entry></config:config-item-map-named>"#,
+            r#"<config:config-item config:name="ActiveTable" config:type="string">{0}</config:config-item>"#,
+            r#"</config:config-item-map-entry></config:config-item-map-indexed></config:config-item-set></office:settings>"#,
+            r#"</office:document-settings>"#,
+        ),
+        escape(sheet_name),
+        [
+            item("HorizontalSplitMode", "short", if col > 0 { 2 } else { 0 }),
+            item("VerticalSplitMode", "short", if row > 0 { 2 } else { 0 }),
+            item("HorizontalSplitPosition", "int", col),
+            item("VerticalSplitPosition", "int", row),
+            item("ActiveSplitRange", "short", active_pane),
+            item("PositionLeft", "int", 0),
+            item("PositionRight", "int", col),
+            item("PositionTop", "int", 0),
+            item("PositionBottom", "int", row),
+        ].concat(),
+    )?;
+    Ok(())
+}
+
+/// Streams the content part, opening a `<table:table-row>` element whenever the row
+/// index changes and repeating empty rows and cells over the gaps.
+fn write_table<W: Write, I: IntoIterator<Item = Result<Cell, RustySheetError>>>(writer: &mut W, sheet_name: &str, columns: usize, cells: I, layout: &SheetLayout) -> Result<(), RustySheetError> {
+    let columns = columns.max(1);
+    writer.write_all(ODS_CONTENT_START.as_bytes())?;
+    write!(
+        writer,
+        r#"<table:table table:name="{}"><table:table-column table:number-columns-repeated="{}"/>"#,
+        escape(sheet_name),
+        columns,
+    )?;
+    let mut current_row = None;
+    let mut next_col = 0;
+    for cell in cells {
+        let cell = cell?;
+        if cell.kind == CellType::Empty {
+            continue;
+        }
+        if cell.row >= 1 << 20 || cell.col >= 1 << 14 {
+            Err(OutputError::SheetSizeError(sheet_name.to_owned(), cell.reference()))?
+        }
+        if current_row != Some(cell.row) {
+            if current_row.is_some() {
+                writer.write_all(b"</table:table-row>")?;
+            }
+            let empty_rows = cell.row - current_row.map_or(0, |row| row + 1);
+            if empty_rows > 0 {
+                write!(writer, r#"<table:table-row table:number-rows-repeated="{empty_rows}"><table:table-cell/></table:table-row>"#)?;
+            }
+            writer.write_all(b"<table:table-row>")?;
+            current_row = Some(cell.row);
+            next_col = 0;
+        }
+        if cell.col > next_col {
+            write!(writer, r#"<table:table-cell table:number-columns-repeated="{}"/>"#, cell.col - next_col)?;
+        }
+        write_table_cell(writer, &cell, layout.bold_header && cell.row == 0)?;
+        next_col = cell.col + 1;
+    }
+    match current_row {
+        Some(_) => writer.write_all(b"</table:table-row>")?,
+        // A table holds at least one row
+        None => writer.write_all(b"<table:table-row><table:table-cell/></table:table-row>")?,
+    }
+    writer.write_all(b"</table:table>")?;
+    if let (true, Some(last_row)) = (layout.autofilter, current_row) {
+        let table = format!("'{}'", sheet_name.replace('\'', "''"));
+        write!(
+            writer,
+            r#"<table:database-ranges><table:database-range table:name="__Anonymous_Sheet_DB__0" table:target-range-address="{}" table:display-filter-buttons="true"/></table:database-ranges>"#,
+            escape(format!("{table}.A1:{table}.{}", index_to_reference(last_row, columns - 1))),
+        )?;
+    }
+    writer.write_all(b"</office:spreadsheet></office:body></office:document-content>")?;
+    Ok(())
+}
+
+/// Writes a table cell with its typed value and the text displayed for it, in bold
+/// when `bold` unless the cell has a date style.
+fn write_table_cell<W: Write>(writer: &mut W, cell: &Cell, bold: bool) -> Result<(), RustySheetError> {
+    let style = if bold { r#" table:style-name="ce4""# } else { "" };
+    match cell.kind {
+        CellType::Boolean => {
+            let value = cell.value != "0";
+            write!(
+                writer,
+                r#"<table:table-cell{} office:value-type="boolean" office:boolean-value="{}"><text:p>{}</text:p></table:table-cell>"#,
+                style,
+                value,
+                if value { "TRUE" } else { "FALSE" },
+            )?
+        }
+        CellType::Number => write!(
+            writer,
+            r#"<table:table-cell{0} office:value-type="float" office:value="{1}"><text:p>{1}</text:p></table:table-cell>"#,
+            style,
+            cell.value,
+        )?,
+        CellType::NumberDate1900 | CellType::NumberDateTime1900 | CellType::NumberTime1900 => {
+            let datetime = from_serial_date(&cell.value)?;
+            let (style, kind, value, text) = match cell.kind {
+                CellType::NumberDate1900 => ("ce1", "date", datetime.format("%Y-%m-%d"), datetime.format("%Y-%m-%d")),
+                CellType::NumberDateTime1900 => ("ce2", "date", datetime.format("%Y-%m-%dT%H:%M:%S%.f"), datetime.format("%Y-%m-%d %H:%M:%S")),
+                _ => ("ce3", "time", datetime.format("PT%HH%MM%S%.fS"), datetime.format("%H:%M:%S")),
+            };
+            write!(
+                writer,
+                r#"<table:table-cell table:style-name="{style}" office:value-type="{kind}" office:{kind}-value="{value}"><text:p>{text}</text:p></table:table-cell>"#,
+            )?
+        }
+        _ => {
+            write!(writer, r#"<table:table-cell{style} office:value-type="string">"#)?;
+            for line in to_xml_text(&cell.value).split('\n') {
+                write!(writer, "<text:p>{}</text:p>", to_paragraph(line))?;
+            }
+            writer.write_all(b"</table:table-cell>")?;
+        }
+    }
+    Ok(())
+}
+
+/// Escapes a line of text as paragraph content, where XML whitespace collapses: tabs
+/// become `<text:tab/>` and spaces that would be lost, leading, trailing or repeated,
+/// become `<text:s/>`.
+fn to_paragraph(line: &str) -> String {
+    let mut paragraph = String::new();
+    let mut spaces = 0;
+    for (index, character) in line.char_indices() {
+        if character == ' ' {
+            spaces += 1;
+            continue;
+        }
+        push_spaces(&mut paragraph, spaces, index == spaces);
+        spaces = 0;
+        match character {
+            '\t' => paragraph.push_str("<text:tab/>"),
+            '&' => paragraph.push_str("&amp;"),
+            '<' => paragraph.push_str("&lt;"),
+            '>' => paragraph.push_str("&gt;"),
+            _ => paragraph.push(character),
+        }
+    }
+    push_spaces(&mut paragraph, spaces, true);
+    paragraph
+}
+
+/// Appends a run of spaces, keeping a single literal space between words.
+fn push_spaces(paragraph: &mut String, spaces: usize, is_edge: bool) {
+    match (spaces, is_edge) {
+        (0, _) => (),
+        (1, true) => paragraph.push_str("<text:s/>"),
+        (_, true) => paragraph.push_str(&format!(r#"<text:s text:c="{spaces}"/>"#)),
+        (1, false) => paragraph.push(' '),
+        (_, false) => paragraph.push_str(&format!(r#" <text:s text:c="{}"/>"#, spaces - 1)),
+    }
+}
+
+/// Converts an Excel 1900 date serial to a date and time, reproducing the Lotus 1-2-3
+/// leap year bug as [`to_serial_date`] does.
+fn from_serial_date(value: &str) -> Result<NaiveDateTime, RustySheetError> {
+    let serial = value.parse::<f64>()?;
+    let days = serial.trunc() as i64;
+    let micros = (serial.fract() * 86_400_000_000f64).round() as i64;
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).expect("NaiveDate Literal").and_time(NaiveTime::MIN);
+    Ok(epoch + Duration::days(if days < 60 { days + 1 } else { days }) + Duration::microseconds(micros))
+}
+
+/// Converts days since 1970-01-01 to an Excel 1900 date serial, reproducing the
+/// Lotus 1-2-3 leap year bug (serial 60 is the fictitious 1900-02-29).
+/// Returns None before 1900-01-01, which Excel cannot represent.
+pub(crate) fn to_serial_date(days: i32) -> Option<i64> {
+    let serial = days as i64 + 25_569;
+    let serial = if serial < 61 { serial - 1 } else { serial };
+    (serial >= 1).then_some(serial)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spreadsheet::criteria::Criteria;
+    use crate::spreadsheet::ods::OdsSpreadsheet;
+    use crate::spreadsheet::xlsx::XlsxSpreadsheet;
+    use crate::spreadsheet::Spreadsheet;
+
+    fn cell(row: usize, col: usize, kind: CellType, value: &str) -> Cell {
+        Cell { row, col, kind, value: value.to_owned() }
+    }
+
+    #[test]
+    fn written_workbook_reads_back() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_output_{}.xlsx", std::process::id()));
+        let cells = vec![
+            cell(0, 0, CellType::InlineString, "name"),
+            cell(0, 1, CellType::InlineString, "amount"),
+            cell(0, 2, CellType::InlineString, "paid"),
+            cell(0, 3, CellType::InlineString, "due"),
+            cell(1, 0, CellType::InlineString, " <A&B> "),
+            cell(1, 1, CellType::Number, "12.5"),
+            cell(1, 2, CellType::Boolean, "1"),
+            cell(1, 3, CellType::NumberDate1900, &to_serial_date(19_723).unwrap().to_string()),
+            cell(2, 0, CellType::InlineString, "bell\u{7}"),
+            cell(2, 1, CellType::Empty, ""),
+            cell(2, 3, CellType::NumberTime1900, "0.5"),
+        ];
+        write_xlsx(path.to_str().unwrap(), "Report", cells.into_iter().map(Ok), &SheetLayout::default()).unwrap();
+
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let sheets = spreadsheet.read_sheets(&Criteria::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(sheets.len(), 1);
+        assert_eq!(sheets[0].name, "Report");
+        let cells = sheets[0].cells.iter()
+            .map(|cell| (cell.reference(), cell.kind, cell.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(cells, vec![
+            ("A1".to_owned(), CellType::InlineString, "name"),
+            ("B1".to_owned(), CellType::InlineString, "amount"),
+            ("C1".to_owned(), CellType::InlineString, "paid"),
+            ("D1".to_owned(), CellType::InlineString, "due"),
+            ("A2".to_owned(), CellType::InlineString, " <A&B> "),
+            ("B2".to_owned(), CellType::Number, "12.5"),
+            ("C2".to_owned(), CellType::Boolean, "1"),
+            ("D2".to_owned(), CellType::NumberDate1900, "45292"),
+            ("A3".to_owned(), CellType::InlineString, "bell"),
+            ("D3".to_owned(), CellType::NumberTime1900, "0.5"),
+        ]);
+        assert_eq!(sheets[0].cells[7].to_date(), Ok(19_723));
+    }
+
+    #[test]
+    fn appended_sheets_keep_existing_ones() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_append_{}.xlsx", std::process::id()));
+        let file_name = path.to_str().unwrap();
+        let layout = SheetLayout::default();
+        write_xlsx(file_name, "Report", [Ok(cell(0, 0, CellType::Number, "1"))], &layout).unwrap();
+        append_xlsx(file_name, "Q3", [Ok(cell(0, 0, CellType::NumberDateTime1900, "45292.5"))], &layout).unwrap();
+        append_xlsx(file_name, "Q4", [Ok(cell(1, 1, CellType::InlineString, "x"))], &layout).unwrap();
+        let error = append_xlsx(file_name, "q3", [], &layout).unwrap_err();
+
+        let mut spreadsheet = XlsxSpreadsheet::open(file_name, false, false, None).unwrap();
+        let sheets = spreadsheet.read_sheets(&Criteria::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(error, RustySheetError::OutputError(OutputError::SheetExistsError(_))));
+        let cells = sheets.iter()
+            .map(|sheet| (sheet.name.as_str(), sheet.cells[0].reference(), sheet.cells[0].kind, sheet.cells[0].value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(cells, vec![
+            ("Report", "A1".to_owned(), CellType::Number, "1"),
+            ("Q3", "A1".to_owned(), CellType::NumberDateTime1900, "45292.5"),
+            ("Q4", "B2".to_owned(), CellType::InlineString, "x"),
+        ]);
+    }
+
+    #[test]
+    fn failed_writes_keep_existing_file() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_failed_{}.xlsx", std::process::id()));
+        let file_name = path.to_str().unwrap();
+        let layout = SheetLayout::default();
+        write_xlsx(file_name, "Report", [Ok(cell(0, 0, CellType::Number, "1"))], &layout).unwrap();
+        let failing = || [Ok(cell(0, 0, CellType::Number, "2")), Err(RustySheetError::from(OutputError::SheetNameError("Q3".to_owned())))];
+        let xlsx_error = write_xlsx(file_name, "Report", failing(), &layout).err();
+        let ods_error = write_ods(file_name, "Report", 1, failing(), &layout).err();
+
+        let mut spreadsheet = XlsxSpreadsheet::open(file_name, false, false, None).unwrap();
+        let sheets = spreadsheet.read_sheets(&Criteria::default()).unwrap();
+        let temporary = std::path::Path::new(&format!("{file_name}.tmp")).exists();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(xlsx_error.is_some() && ods_error.is_some());
+        assert!(!temporary);
+        assert_eq!(sheets[0].cells[0].value, "1");
+    }
+
+    #[test]
+    fn appended_styles_follow_existing_ones() {
+        // Prefixed elements, as written by the Open XML SDK, in the 1904 date system
+        let styles = concat!(
+            r#"<x:styleSheet xmlns:x="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+            r#"<x:numFmts count="1"><x:numFmt numFmtId="170" formatCode="0.0%"/></x:numFmts>"#,
+            r#"<x:cellXfs count="2"><x:xf numFmtId="0"/><x:xf numFmtId="170"><x:alignment/></x:xf></x:cellXfs>"#,
+            r#"</x:styleSheet>"#,
+        );
+        let (styles, date_styles) = patch_styles(styles, true, false).unwrap();
+        assert_eq!(styles, concat!(
+            r#"<x:styleSheet xmlns:x="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+            r#"<x:numFmts count="4"><x:numFmt numFmtId="170" formatCode="0.0%"/>"#,
+            r#"<x:numFmt numFmtId="171" formatCode="yyyy\-mm\-dd"/><x:numFmt numFmtId="172" formatCode="yyyy\-mm\-dd\ hh:mm:ss"/><x:numFmt numFmtId="173" formatCode="hh:mm:ss"/></x:numFmts>"#,
+            r#"<x:cellXfs count="5"><x:xf numFmtId="0"/><x:xf numFmtId="170"><x:alignment/></x:xf>"#,
+            r#"<x:xf numFmtId="171" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><x:xf numFmtId="172" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><x:xf numFmtId="173" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/></x:cellXfs>"#,
+            r#"</x:styleSheet>"#,
+        ));
+        assert_eq!((date_styles.date, date_styles.datetime, date_styles.time), (2, 3, 4));
+
+        let mut worksheet = Vec::new();
+        write_worksheet(&mut worksheet, "Data", [
+            cell(0, 0, CellType::NumberDate1900, "45292"),
+            cell(0, 1, CellType::NumberDate1900, "1"),
+        ].map(Ok), &date_styles, &SheetLayout::default()).unwrap();
+        let worksheet = String::from_utf8(worksheet).unwrap();
+        assert!(worksheet.contains(r#"<c r="A1" s="2"><v>43830</v></c><c r="B1" t="inlineStr"><is><t xml:space="preserve">1900-01-01</t></is></c>"#), "{worksheet}");
+    }
+
+    #[test]
+    fn header_style_frozen_panes_and_autofilter() {
+        let styles = concat!(
+            r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+            r#"<fonts count="1"><font><sz val="10"/></font></fonts>"#,
+            r#"<cellXfs count="1"><xf numFmtId="0"/></cellXfs>"#,
+            r#"</styleSheet>"#,
+        );
+        let (styles, cell_styles) = patch_styles(styles, false, true).unwrap();
+        assert!(styles.contains(r#"<fonts count="2"><font><sz val="10"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts>"#), "{styles}");
+        assert!(styles.contains(r#"<xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs>"#), "{styles}");
+        assert_eq!(cell_styles.header, Some(4));
+
+        let layout = SheetLayout { bold_header: true, freeze_panes: Some((1, 0)), autofilter: true };
+        let mut worksheet = Vec::new();
+        write_worksheet(&mut worksheet, "Data", [
+            cell(0, 0, CellType::InlineString, "id"),
+            cell(0, 1, CellType::InlineString, "due"),
+            cell(1, 0, CellType::Number, "1"),
+            cell(1, 1, CellType::NumberDate1900, "45292"),
+        ].map(Ok), &cell_styles, &layout).unwrap();
+        let worksheet = String::from_utf8(worksheet).unwrap();
+        assert!(worksheet.contains(r#"<sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews><sheetData>"#), "{worksheet}");
+        assert!(worksheet.contains(r#"<c r="A1" s="4" t="inlineStr">"#), "{worksheet}");
+        assert!(worksheet.contains(r#"<c r="A2"><v>1</v></c><c r="B2" s="1"><v>45292</v></c>"#), "{worksheet}");
+        assert!(worksheet.ends_with(r#"</sheetData><autoFilter ref="A1:B2"/></worksheet>"#), "{worksheet}");
+    }
+
+    #[test]
+    fn written_spreadsheet_reads_back() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_output_{}.ods", std::process::id()));
+        let file_name = path.to_str().unwrap();
+        let cells = vec![
+            cell(0, 0, CellType::InlineString, "name"),
+            cell(0, 2, CellType::InlineString, "due"),
+            cell(2, 0, CellType::InlineString, "  <A&B>   x\ny "),
+            cell(2, 1, CellType::Boolean, "0"),
+            cell(2, 2, CellType::NumberDateTime1900, "45292.5"),
+            cell(3, 1, CellType::Number, "-1.25"),
+            cell(3, 2, CellType::NumberDate1900, "45292"),
+            cell(3, 3, CellType::NumberTime1900, "0.75"),
+        ];
+        let layout = SheetLayout { bold_header: true, freeze_panes: Some((1, 0)), autofilter: true };
+        write_ods(file_name, "Q'3", 4, cells.into_iter().map(Ok), &layout).unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mime_type = archive.by_index(0).map(|entry| (entry.name().to_owned(), entry.compression())).unwrap();
+        let content = read_part(&mut archive, "content.xml").unwrap();
+        let settings = read_part(&mut archive, "settings.xml").unwrap();
+        let mut spreadsheet = OdsSpreadsheet::open(file_name, false, false, None).unwrap();
+        let sheets = spreadsheet.read_sheets(&Criteria::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(mime_type, ("mimetype".to_owned(), CompressionMethod::Stored));
+        assert!(content.contains(r#"<table:table-cell table:style-name="ce4" office:value-type="string"><text:p>name</text:p></table:table-cell><table:table-cell table:number-columns-repeated="1"/>"#), "{content}");
+        assert!(content.contains(r#"<text:p><text:s text:c="2"/>&lt;A&amp;B&gt; <text:s text:c="2"/>x</text:p><text:p>y<text:s/></text:p>"#), "{content}");
+        assert!(content.contains(r#"table:target-range-address="&apos;Q&apos;&apos;3&apos;.A1:&apos;Q&apos;&apos;3&apos;.D4""#), "{content}");
+        assert!(settings.contains(r#"<config:config-item config:name="VerticalSplitPosition" config:type="int">1</config:config-item>"#), "{settings}");
+
+        assert_eq!(sheets[0].name, "Q'3");
+        let cells = sheets[0].cells.iter()
+            .map(|cell| (cell.reference(), cell.kind, cell.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(cells, vec![
+            ("A1".to_owned(), CellType::InlineString, "name"),
+            ("C1".to_owned(), CellType::InlineString, "due"),
+            ("A3".to_owned(), CellType::InlineString, "  <A&B>   x\ny "),
+            ("B3".to_owned(), CellType::Boolean, "0"),
+            ("C3".to_owned(), CellType::IsoDateTime, "2024-01-01T12:00:00"),
+            ("B4".to_owned(), CellType::Number, "-1.25"),
+            ("C4".to_owned(), CellType::IsoDateTime, "2024-01-01"),
+            ("D4".to_owned(), CellType::IsoDuration, "PT18H00M00S"),
+        ]);
+        assert_eq!(sheets[0].cells[6].to_date(), Ok(19_723));
+    }
+
+    #[test]
+    fn serial_dates_around_leap_year_bug() {
+        assert_eq!(to_serial_date(-25_567), Some(1)); // 1900-01-01
+        assert_eq!(to_serial_date(-25_509), Some(59)); // 1900-02-28
+        assert_eq!(to_serial_date(-25_508), Some(61)); // 1900-03-01
+        assert_eq!(to_serial_date(0), Some(25_569));
+        assert_eq!(to_serial_date(-25_568), None);
+    }
+
+    #[test]
+    fn invalid_sheet_names() {
+        assert!(validate_sheet_name("Report 2024").is_ok());
+        for name in ["", "a/b", "[x]", "'quoted'", "abcdefghijklmnopqrstuvwxyz012345"] {
+            assert!(validate_sheet_name(name).is_err(), "{name}");
+        }
+    }
+}
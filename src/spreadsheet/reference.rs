@@ -1,19 +1,32 @@
+use std::borrow::Cow;
+
 /// Converts a zero-based column index to Excel column letter notation
 ///
-/// Uses a precomputed lookup table for optimal performance. Supports
-/// columns from A (0) to XFD (16383), covering the full Excel range.
+/// Uses a precomputed lookup table for optimal performance for columns
+/// from A (0) to XFD (16383), covering the full Excel range. Columns past
+/// XFD, which OpenDocument tables may address, are spelled out on demand.
 ///
 /// # Arguments
 /// * `index` - Zero-based column index (0 = "A", 1 = "B", ..., 16383 = "XFD")
 ///
 /// # Returns
-/// Excel-style column letter(s) as a static string slice
-///
-/// # Panics
-/// Panics if `index` exceeds 16383 (Excel's maximum column limit)
+/// Excel-style column letter(s), borrowed from the lookup table when possible
 #[inline]
-pub(crate) fn index_to_col(index: usize) -> &'static str {
-    INDEXES_TO_COLUMNS[index]
+pub(crate) fn index_to_col(index: usize) -> Cow<'static, str> {
+    match INDEXES_TO_COLUMNS.get(index) {
+        Some(letters) => Cow::Borrowed(letters),
+        None => {
+            let mut letters = Vec::<u8>::new();
+            let mut number = index + 1;
+            while number > 0 {
+                number -= 1;
+                letters.push(b'A' + (number % 26) as u8);
+                number /= 26;
+            }
+            letters.reverse();
+            Cow::Owned(String::from_utf8(letters).expect("Column letters"))
+        }
+    }
 }
 
 /// Converts Excel column letters to a zero-based column index
@@ -102,11 +115,66 @@ pub(crate) fn row_to_index(row: &str) -> Option<usize> {
 /// Excel-style cell reference as a string (e.g., "A1", "B2", "AB100")
 pub(crate) fn index_to_reference(row_index: usize, col_index: usize) -> String {
     let mut reference = String::new();
-    reference.push_str(index_to_col(col_index));
+    reference.push_str(&index_to_col(col_index));
     reference.push_str(&index_to_row(row_index));
     reference
 }
 
+/// Converts zero-based inclusive bounds to an Excel-style range
+///
+/// # Returns
+/// Range such as "A1:D10", or a single reference when the range holds one cell
+pub(crate) fn bounds_to_range(row_lower: usize, col_lower: usize, row_upper: usize, col_upper: usize) -> String {
+    let lower = index_to_reference(row_lower, col_lower);
+    if row_lower == row_upper && col_lower == col_upper {
+        lower
+    } else {
+        format!("{lower}:{}", index_to_reference(row_upper, col_upper))
+    }
+}
+
+/// Splits a reference to a single range, such as `'Q1 Sales'!$A$1:$D$10`, into its
+/// worksheet name and the range as accepted by the `range` parameter (`A1:D10`)
+///
+/// # Returns
+/// None for formulas, references without a worksheet, to several areas or to other workbooks
+pub(crate) fn split_sheet_range(reference: &str) -> Option<(String, String)> {
+    let (sheet_name, range) = reference.trim().rsplit_once('!')?;
+    Some((unquote_sheet_name(sheet_name)?, plain_range(range)?))
+}
+
+/// Unquotes a worksheet name as written in references, e.g. `'It''s'` as `It's`
+///
+/// # Returns
+/// None for empty names and names of other workbooks (`[1]Sheet1`)
+pub(crate) fn unquote_sheet_name(sheet_name: &str) -> Option<String> {
+    let sheet_name = match sheet_name.strip_prefix('\'').and_then(|name| name.strip_suffix('\'')) {
+        // Quotes inside the name are doubled, a lone one ends the name early
+        Some(name) if !name.replace("''", "").contains('\'') => name.replace("''", "'"),
+        Some(_) => return None,
+        // Only names of letters, digits, underscores and dots are written without quotes
+        None if sheet_name.chars().all(|char| char.is_alphanumeric() || char == '_' || char == '.') => sheet_name.to_owned(),
+        None => return None,
+    };
+    Some(sheet_name).filter(|name| !name.is_empty() && !name.starts_with('['))
+}
+
+/// Converts the range part of a reference, with or without `$` markers, to a range as
+/// accepted by the `range` parameter. Single cells become one-cell ranges (`B2:B2`),
+/// since a lone cell would read from that cell to the end of the sheet.
+///
+/// # Returns
+/// None when a bound is not a cell, a column or a row
+pub(crate) fn plain_range(range: &str) -> Option<String> {
+    let range = range.replace('$', "");
+    let (lower, upper) = range.split_once(':').unwrap_or((&range, &range));
+    let is_bound = |bound: &str| {
+        let digits = bound.trim_start_matches(|char: char| char.is_ascii_uppercase());
+        !bound.is_empty() && digits.chars().all(|char| char.is_ascii_digit()) && !digits.starts_with('0')
+    };
+    (is_bound(lower) && is_bound(upper)).then(|| format!("{lower}:{upper}"))
+}
+
 /// Converts an Excel-style cell reference to zero-based row and column indices
 ///
 /// Parses Excel-style cell references (e.g., "A1", "B2", "AB100") into their corresponding
@@ -0,0 +1,273 @@
+//! Shared string tables resolved when cells are written
+//!
+//! The full table of a workbook is kept in memory, or spilled to a pair of temporary
+//! files once it holds more strings than a configurable threshold: the text of the
+//! strings and the offset of each of them. Both files are memory-mapped once complete,
+//! so spilled strings are read back on demand without locking.
+
+use crate::error::RustySheetError;
+use crate::helpers::string::repair_encoding;
+use crate::spreadsheet::sheet::truncate_text;
+use memmap2::Mmap;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Flag of an offset whose string matches a null literal
+const NULL_FLAG: u64 = 1 << 63;
+
+/// Size of an entry of the offsets file
+const OFFSET_SIZE: usize = 8;
+
+/// Sequence number distinguishing the spill files of the process
+static SPILL_SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Full shared string table of a workbook, with null literals and truncation applied
+pub(crate) enum SharedStrings {
+    /// Strings kept in memory; None stands for a null literal
+    Memory(Vec<Option<String>>),
+    /// Strings spilled to temporary files
+    Spilled(SpilledStrings),
+}
+
+impl Default for SharedStrings {
+    fn default() -> Self {
+        SharedStrings::Memory(Vec::new())
+    }
+}
+
+impl SharedStrings {
+    /// Returns the string at `index`, or None for a null literal.
+    /// Indexes past the end of the table read as null literals.
+    pub(crate) fn get(&self, index: usize) -> Result<Option<Cow<'_, str>>, RustySheetError> {
+        match self {
+            SharedStrings::Memory(strings) => Ok(strings.get(index).and_then(Option::as_deref).map(Cow::Borrowed)),
+            SharedStrings::Spilled(strings) => Ok(strings.get(index)?.map(Cow::Owned)),
+        }
+    }
+}
+
+/// Temporary file removed once dropped
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Creates a new temporary file for the spilled strings with the given extension
+    fn create(sequence: usize, extension: &str) -> Result<(TempFile, File), RustySheetError> {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_strings_{}_{}.{}", process::id(), sequence, extension));
+        let file = File::options().read(true).write(true).create_new(true).open(&path)?;
+        Ok((TempFile { path }, file))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Shared strings spilled to disk: the concatenated UTF-8 text of the strings and,
+/// for each of them, its offset in the text followed by the total length.
+pub(crate) struct SpilledStrings {
+    /// Mapped text file
+    text: Mmap,
+    /// Mapped offsets file
+    offsets: Mmap,
+    /// Number of strings
+    len: usize,
+    /// Removes the files once the table is dropped (after they are unmapped)
+    _paths: [TempFile; 2],
+}
+
+impl SpilledStrings {
+    /// Reads the string at `index` back from the mapped files
+    fn get(&self, index: usize) -> Result<Option<String>, RustySheetError> {
+        if index >= self.len {
+            return Ok(None);
+        }
+        let offset = |index: usize| {
+            let entry = &self.offsets[index * OFFSET_SIZE..(index + 1) * OFFSET_SIZE];
+            u64::from_le_bytes(entry.try_into().expect("Offset entry"))
+        };
+        let start = offset(index);
+        if start & NULL_FLAG != 0 {
+            return Ok(None);
+        }
+        // The next entry carries the flag when it belongs to a null literal
+        let end = offset(index + 1) & !NULL_FLAG;
+        Ok(Some(String::from_utf8_lossy(&self.text[start as usize..end as usize]).into_owned()))
+    }
+}
+
+/// Shared strings being written to disk
+struct SpillWriter {
+    text: BufWriter<File>,
+    offsets: BufWriter<File>,
+    /// Length of the text written so far
+    position: u64,
+    len: usize,
+    paths: [TempFile; 2],
+}
+
+impl SpillWriter {
+    fn create() -> Result<SpillWriter, RustySheetError> {
+        let sequence = SPILL_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        let (text_path, text) = TempFile::create(sequence, "txt")?;
+        let (offsets_path, offsets) = TempFile::create(sequence, "idx")?;
+        Ok(SpillWriter {
+            text: BufWriter::new(text),
+            offsets: BufWriter::new(offsets),
+            position: 0,
+            len: 0,
+            paths: [text_path, offsets_path],
+        })
+    }
+
+    fn push(&mut self, string: Option<&str>) -> Result<(), RustySheetError> {
+        match string {
+            Some(string) => {
+                self.offsets.write_all(&self.position.to_le_bytes())?;
+                self.text.write_all(string.as_bytes())?;
+                self.position += string.len() as u64;
+            }
+            None => self.offsets.write_all(&(self.position | NULL_FLAG).to_le_bytes())?,
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<SpilledStrings, RustySheetError> {
+        self.offsets.write_all(&self.position.to_le_bytes())?;
+        let text = self.text.into_inner().map_err(|error| error.into_error())?;
+        let offsets = self.offsets.into_inner().map_err(|error| error.into_error())?;
+        // SAFETY: the files are private to this table and no longer written
+        let (text, offsets) = unsafe { (Mmap::map(&text)?, Mmap::map(&offsets)?) };
+        Ok(SpilledStrings {
+            text,
+            offsets,
+            len: self.len,
+            _paths: self.paths,
+        })
+    }
+}
+
+/// Builds the shared string table of a workbook item by item, so that a table
+/// larger than the spill threshold never sits in memory as a whole.
+pub(crate) struct SharedStringsBuilder {
+    /// Null literals, read as None
+    nulls: HashSet<String>,
+    /// Maximum number of characters kept in a string and the suffix marking truncated strings
+    max_cell_length: Option<(usize, String)>,
+    /// Whether strings whose UTF-8 bytes were decoded as Windows-1252 are repaired
+    fix_encoding: bool,
+    /// Number of strings above which the table is spilled to disk
+    spill_threshold: Option<usize>,
+    /// Strings kept in memory until the threshold is reached
+    strings: Vec<Option<String>>,
+    /// Spill files once the threshold is reached
+    spill: Option<SpillWriter>,
+}
+
+impl SharedStringsBuilder {
+    /// Creates a builder applying the null literals, encoding repair and truncation of a read
+    ///
+    /// # Arguments
+    /// * `nulls` - Strings read as NULL
+    /// * `max_cell_length` - Maximum number of characters kept and the suffix of truncated strings
+    /// * `fix_encoding` - Whether strings whose UTF-8 bytes were decoded as Windows-1252 are repaired
+    /// * `spill_threshold` - Number of strings above which the table is spilled to disk, or None to keep it in memory
+    pub(crate) fn new(nulls: &HashSet<String>, max_cell_length: &Option<(usize, String)>, fix_encoding: bool, spill_threshold: Option<usize>) -> Self {
+        SharedStringsBuilder {
+            nulls: nulls.to_owned(),
+            max_cell_length: max_cell_length.to_owned(),
+            fix_encoding,
+            spill_threshold,
+            strings: Vec::new(),
+            spill: None,
+        }
+    }
+
+    /// Appends the next string of the table
+    pub(crate) fn push(&mut self, mut string: String) -> Result<(), RustySheetError> {
+        let string = if self.nulls.contains(&string) {
+            None
+        } else {
+            if self.fix_encoding {
+                repair_encoding(&mut string);
+            }
+            if let Some((length, suffix)) = &self.max_cell_length {
+                truncate_text(&mut string, *length, suffix);
+            }
+            Some(string)
+        };
+
+        if let Some(spill) = &mut self.spill {
+            return spill.push(string.as_deref());
+        }
+        self.strings.push(string);
+        if self.spill_threshold.is_some_and(|threshold| self.strings.len() > threshold) {
+            let mut spill = SpillWriter::create()?;
+            for string in std::mem::take(&mut self.strings) {
+                spill.push(string.as_deref())?;
+            }
+            self.spill = Some(spill);
+        }
+        Ok(())
+    }
+
+    /// Completes the table
+    pub(crate) fn finish(self) -> Result<SharedStrings, RustySheetError> {
+        match self.spill {
+            Some(spill) => Ok(SharedStrings::Spilled(spill.finish()?)),
+            None => Ok(SharedStrings::Memory(self.strings)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(strings: &[&str], spill_threshold: Option<usize>) -> SharedStrings {
+        let nulls = HashSet::from(["n/a".to_owned()]);
+        let mut builder = SharedStringsBuilder::new(&nulls, &Some((5, "…".to_owned())), false, spill_threshold);
+        for string in strings {
+            builder.push(string.to_string()).unwrap();
+        }
+        builder.finish().unwrap()
+    }
+
+    #[test]
+    fn spilled_strings_read_like_memory() {
+        let strings = ["東京", "", "n/a", "truncated text", "last"];
+        let memory = build(&strings, None);
+        let spilled = build(&strings, Some(2));
+        assert!(matches!(memory, SharedStrings::Memory(_)));
+        assert!(matches!(&spilled, SharedStrings::Spilled(spilled) if spilled.len == strings.len()));
+        for index in 0..=strings.len() {
+            assert_eq!(spilled.get(index).unwrap(), memory.get(index).unwrap());
+        }
+        assert_eq!(spilled.get(2).unwrap(), None);
+        assert_eq!(spilled.get(3).unwrap().as_deref(), Some("trunc…"));
+    }
+
+    #[test]
+    fn spilled_null_literal_bounds_string() {
+        let strings = ["first", "n/a", "last", "n/a"];
+        let spilled = build(&strings, Some(1));
+        assert!(matches!(&spilled, SharedStrings::Spilled(_)));
+        assert_eq!(spilled.get(0).unwrap().as_deref(), Some("first"));
+        assert_eq!(spilled.get(1).unwrap(), None);
+        assert_eq!(spilled.get(2).unwrap().as_deref(), Some("last"));
+        assert_eq!(spilled.get(3).unwrap(), None);
+        assert_eq!(spilled.get(4).unwrap(), None);
+    }
+}
@@ -1,5 +1,332 @@
+use crate::database::column::ColumnType;
 use crate::database::range::Range;
+use crate::error::RustySheetError;
+use crate::helpers::metrics;
+use crate::helpers::string::repair_encoding;
 use crate::spreadsheet::cell::Cell;
+use crate::spreadsheet::cell::CellType;
+use crate::spreadsheet::criteria::Criteria;
+use crate::spreadsheet::shared_strings::SharedStrings;
+use crate::spreadsheet::SpreadsheetError;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// FNV-1a 64-bit offset basis
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// FNV-1a 64-bit prime
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// FNV-1a hash over a fixed byte layout, stable across platforms and releases.
+#[derive(Clone, Copy)]
+struct ContentHasher(u64);
+
+impl ContentHasher {
+    fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.write_u64(value.len() as u64);
+        self.write(value.as_bytes());
+    }
+
+    fn write_cell(&mut self, row: usize, col: usize, kind: CellType, value: &str) {
+        self.write_u64(row as u64);
+        self.write_u64(col as u64);
+        self.write(&[kind as u8]);
+        self.write_str(value);
+    }
+}
+
+/// Running checksum of a sheet whose cells are hashed while parsing instead of being kept.
+struct Digest {
+    /// Hash of the cells in parse order, shared strings by index
+    hasher: ContentHasher,
+    /// Shared string indexes referenced by the cells, hashed with their text once loaded
+    shared_indexes: BTreeSet<usize>,
+}
+
+/// Truncates a text to at most `length` characters, on a char boundary, appending `suffix`.
+/// Returns true if the text was truncated.
+pub(crate) fn truncate_text(text: &mut String, length: usize, suffix: &str) -> bool {
+    match text.char_indices().nth(length) {
+        Some((index, _)) => {
+            text.truncate(index);
+            text.push_str(suffix);
+            text.shrink_to_fit();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Column display properties from `<col>`, COLINFO or BrtColInfo records.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ColumnInfo {
+    /// First column index (0-based)
+    pub(crate) lower: usize,
+    /// Last column index (0-based, inclusive)
+    pub(crate) upper: usize,
+    /// Column width in characters, if specified
+    pub(crate) width: Option<f64>,
+    /// Whether the column is explicitly hidden
+    pub(crate) hidden: bool,
+    /// Outline (grouping) level, 0 if not grouped
+    pub(crate) outline_level: u8,
+    /// Whether the outline group next to this column is collapsed
+    pub(crate) collapsed: bool,
+}
+
+/// Visibility of a worksheet tab
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Visibility {
+    Visible,
+    /// Hidden, and can be unhidden from the user interface
+    Hidden,
+    /// Hidden, and can only be unhidden from VBA
+    VeryHidden,
+}
+
+impl Visibility {
+    /// Converts the hsState field of BrtBundleSh and BOUNDSHEET8 records
+    pub(crate) fn from_state(state: u32) -> Visibility {
+        match state & 0x03 {
+            0 => Visibility::Visible,
+            1 => Visibility::Hidden,
+            _ => Visibility::VeryHidden,
+        }
+    }
+
+    /// Returns the name of the visibility, as spelled by the `state` attribute of SpreadsheetML
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Visibility::Visible => "visible",
+            Visibility::Hidden => "hidden",
+            Visibility::VeryHidden => "veryHidden",
+        }
+    }
+}
+
+/// Worksheet of a workbook, described without reading its cells
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SheetInfo {
+    /// Worksheet name
+    pub(crate) name: String,
+    /// Position of the worksheet in tab order (0-based)
+    pub(crate) index: usize,
+    /// Visibility of the worksheet tab
+    pub(crate) visibility: Visibility,
+    /// Used range recorded by the producer (e.g. "A1:D10"), if the format records one
+    pub(crate) dimension: Option<String>,
+}
+
+/// Defined name of a workbook, naming a range or a formula
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DefinedName {
+    /// Name as used in formulas
+    pub(crate) name: String,
+    /// Worksheet the name is local to, None for names of the whole workbook
+    pub(crate) scope: Option<String>,
+    /// Range or formula the name refers to, as stored in the workbook
+    pub(crate) refers_to: String,
+    /// Worksheet and range (e.g. "A1:D10") of names referring to a single range
+    pub(crate) target: Option<(String, String)>,
+    /// Whether the name is hidden from the user, such as the range of an autofilter
+    pub(crate) hidden: bool,
+}
+
+/// Comment (note) attached to a cell
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CellComment {
+    /// Worksheet of the cell
+    pub(crate) sheet_name: String,
+    /// Reference of the cell (e.g. "B2")
+    pub(crate) reference: String,
+    /// Author of the comment, if the file records one
+    pub(crate) author: Option<String>,
+    /// Text of the comment, with line breaks between its paragraphs
+    pub(crate) text: String,
+}
+
+/// Hyperlink attached to a cell
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Hyperlink {
+    /// Worksheet of the cell
+    pub(crate) sheet_name: String,
+    /// Reference of the cell or range holding the link (e.g. "B2")
+    pub(crate) reference: String,
+    /// Text shown for the link, if the file records one
+    pub(crate) display_text: Option<String>,
+    /// Target of the link; locations within the workbook start with `#`
+    pub(crate) target_url: String,
+}
+
+/// Protection attributes of a cell, from its cell format and the protection of its worksheet
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CellProtection {
+    /// Worksheet of the cell
+    pub(crate) sheet_name: String,
+    /// Reference of the cell (e.g. "B2")
+    pub(crate) reference: String,
+    /// Whether the cell cannot be edited once the worksheet is protected
+    pub(crate) locked: bool,
+    /// Whether the formula of the cell is hidden once the worksheet is protected
+    pub(crate) formula_hidden: bool,
+    /// Whether the worksheet is protected, enforcing the two flags above
+    pub(crate) sheet_protected: bool,
+}
+
+/// Excel Table (list object) defined on a worksheet
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ListObject {
+    /// Name of the table as shown by Excel and used in structured references
+    pub(crate) name: String,
+    /// Worksheet holding the table
+    pub(crate) sheet_name: String,
+    /// Range of the table, header and totals rows included (e.g. "B2:E20")
+    pub(crate) reference: String,
+    /// Whether the first row of the range holds the column names
+    pub(crate) has_header: bool,
+    /// Number of totals rows at the end of the range
+    pub(crate) totals_rows: usize,
+    /// Names of the table columns, from left to right
+    pub(crate) column_names: Vec<String>,
+}
+
+/// Cells of a chunk laid out row after row by `Sheet::layout_chunk`.
+///
+/// The buffer is meant to be reused from chunk to chunk, so that laying out a chunk
+/// allocates nothing once the buffer has grown to the size of a chunk.
+#[derive(Debug, Default)]
+pub(crate) struct ChunkBuffer {
+    /// Position of each cell in the cells of the sheet, row after row
+    cells: Vec<Option<usize>>,
+    /// Number of rows of the chunk
+    rows: usize,
+    /// Number of visible columns of each row
+    width: usize,
+}
+
+impl ChunkBuffer {
+    /// Returns the number of rows of the chunk laid out last
+    pub(crate) fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the cell at a row and visible column of the chunk laid out last
+    ///
+    /// # Arguments
+    /// * `sheet` - Sheet the chunk was laid out from
+    /// * `row` - Row within the chunk
+    /// * `col` - Visible column within the row
+    pub(crate) fn cell<'a>(&self, sheet: &'a Sheet, row: usize, col: usize) -> Option<&'a Cell> {
+        self.cells[row * self.width + col].map(|index| &sheet.cells[index])
+    }
+}
+
+/// Uniform random sample of rows collected in a single pass (reservoir sampling, algorithm R).
+struct Reservoir {
+    /// Maximum number of sampled rows
+    size: usize,
+    /// Number of rows offered so far
+    count: usize,
+    /// Sampled rows, each holding the cells of one row
+    rows: Vec<Vec<Cell>>,
+    /// Cells of the row currently being read
+    pending: Vec<Cell>,
+    /// Xorshift state, seeded with a constant so the analysis is reproducible
+    state: u64,
+}
+
+impl Reservoir {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            count: 0,
+            rows: Vec::with_capacity(size),
+            pending: Vec::new(),
+            state: 0x2545_F491_4F6C_DD1D,
+        }
+    }
+
+    /// Adds a cell, offering the previous row to the sample when the row changes.
+    fn push(&mut self, cell: Cell) {
+        if self.pending.last().is_some_and(|last| last.row != cell.row) {
+            self.offer();
+        }
+        self.pending.push(cell);
+    }
+
+    /// Keeps the pending row with probability `size / count`, replacing a random sampled row.
+    fn offer(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let row = std::mem::take(&mut self.pending);
+        self.count += 1;
+        if self.rows.len() < self.size {
+            self.rows.push(row);
+        } else {
+            let index = (self.next_random() % self.count as u64) as usize;
+            if index < self.size {
+                self.rows[index] = row;
+            }
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Returns the cells of the sampled rows in row order.
+    fn finish(mut self) -> Vec<Cell> {
+        self.offer();
+        self.rows.sort_by_key(|row| row[0].row);
+        self.rows.into_iter().flatten().collect()
+    }
+}
+
+/// Type streaks of the columns, ending the analysis once every column's type is stable.
+struct Convergence {
+    /// Number of consecutive values of the same type making a column stable
+    stable_values: usize,
+    /// Criteria classifying the cell values, as the analysis does
+    criteria: Criteria,
+    /// Type of the last value of each column and the number of consecutive values of that type
+    streaks: HashMap<usize, (ColumnType, usize)>,
+}
+
+impl Convergence {
+    /// Extends or restarts the streak of the cell's column.
+    fn track(&mut self, cell: &Cell) {
+        let Some(kind) = ColumnType::from(&cell.kind, &cell.value, &self.criteria) else { return };
+        let streak = self.streaks.entry(cell.col).or_insert((kind, 0));
+        if streak.0 == kind {
+            streak.1 += 1;
+        } else {
+            *streak = (kind, 1);
+        }
+    }
+
+    /// Returns true once every column seen so far is stable.
+    fn is_stable(&self) -> bool {
+        !self.streaks.is_empty() && self.streaks.values().all(|(_, count)| *count >= self.stable_values)
+    }
+}
 
 /// Represents a sheet from a spreadsheet file with data organized in chunks for efficient processing.
 pub(crate) struct Sheet {
@@ -7,6 +334,8 @@ pub(crate) struct Sheet {
     pub(crate) file_name: String,
     /// Sheet name
     pub(crate) name: String,
+    /// Position of the sheet in the workbook (0-based)
+    pub(crate) index: usize,
     /// All cells in the sheet
     pub(crate) cells: Vec<Cell>,
     /// Data chunks for efficient processing:
@@ -21,10 +350,40 @@ pub(crate) struct Sheet {
     pub(super) chunk_row_lower: Option<usize>,
     /// Expected data range (user-specified)
     pub(super) range: Range,
+    /// Disjoint blocks of cells within `range`, empty when the whole range is read
+    pub(super) blocks: Vec<Range>,
     /// Row limit for data extraction
     pub(super) limit: Option<usize>,
     /// Whether to skip empty rows
     pub(super) skip_empty_rows: bool,
+    /// Color of the sheet tab as `#RRGGBB`, None when the tab is not colored
+    pub(crate) tab_color: Option<String>,
+    /// Ranges of hidden columns excluded from the data (lower and upper indexes, inclusive)
+    pub(crate) hidden_columns: Vec<(usize, usize)>,
+    /// Positions (row and column indexes) of the cells read as the text of their formula, see `push_formula`
+    pub(crate) formula_cells: HashSet<(usize, usize)>,
+    /// Whether cells arrived out of row/column order (or duplicated)
+    is_unordered: bool,
+    /// Whether the chunks were rebuilt from out-of-order cells, see `settled_chunks`
+    is_reordered: bool,
+    /// Whether the rows read reached the row limit, so that rows past it may be missing, see `settled_chunks`
+    reached_limit: bool,
+    /// Row sample replacing the row limit, the first row is always kept
+    reservoir: Option<Reservoir>,
+    /// Whether cell values are dropped, keeping only cell positions
+    count_only: bool,
+    /// Maximum number of characters kept in text cells and the suffix marking truncated values
+    pub(crate) max_cell_length: Option<(usize, String)>,
+    /// Whether text cells whose UTF-8 bytes were decoded as Windows-1252 are repaired
+    pub(crate) fix_encoding: bool,
+    /// Running checksum replacing the cells, see `checksum`
+    digest: Option<Digest>,
+    /// Type streaks lowering the row limit once the column types are stable, see `stop_when_stable`
+    convergence: Option<Convergence>,
+    /// Structural errors that ended the parsing early in lenient mode, see `tolerate`
+    pub(crate) anomalies: Vec<String>,
+    /// Cells handed to the sheet by the parser since it was created, counted by the metrics once it is finished
+    parsed_cells: usize,
     /// Actual data range (determined from cell data)
     pub(crate) row_lower_bound: Option<usize>,
     pub(crate) row_upper_bound: Option<usize>,
@@ -34,14 +393,15 @@ pub(crate) struct Sheet {
 
 impl Sheet {
     /// Size of data chunks for processing efficiency
-    const CHUNK_SIZE: usize = 2048;
+    pub(crate) const CHUNK_SIZE: usize = 2048;
 
     /// Creates a new sheet with specified parameters.
-    pub(super) fn new(file_name: &str, name: &str, range: Option<Range>, limit: Option<usize>, skip_empty_rows: bool) -> Self {
+    pub(super) fn new(file_name: &str, name: &str, range: Option<Range>, limit: Option<usize>, sample_rows: Option<usize>, skip_empty_rows: bool, count_only: bool) -> Self {
         let range = range.unwrap_or_default();
         Self {
             file_name: file_name.to_owned(),
             name: name.to_owned(),
+            index: 0,
             cells: Vec::new(),
             chunks: Vec::new(),
             chunk_index_lower: 0,
@@ -52,11 +412,98 @@ impl Sheet {
             col_lower_bound: None,
             col_upper_bound: None,
             range,
+            blocks: Vec::new(),
             limit,
             skip_empty_rows,
+            tab_color: None,
+            hidden_columns: Vec::new(),
+            formula_cells: HashSet::new(),
+            is_unordered: false,
+            is_reordered: false,
+            reached_limit: false,
+            reservoir: sample_rows.map(Reservoir::new),
+            count_only,
+            max_cell_length: None,
+            fix_encoding: false,
+            digest: None,
+            convergence: None,
+            anomalies: Vec::new(),
+            parsed_cells: 0,
+        }
+    }
+
+    /// Stops reading at the end of the first row where every column has had
+    /// `criteria.stable_rows` consecutive values of the same type, for the analysis.
+    /// The row limit still applies when the types keep changing.
+    pub(super) fn stop_when_stable(&mut self, criteria: &Criteria) {
+        self.convergence = criteria.stable_rows.map(|stable_values| Convergence {
+            stable_values,
+            criteria: criteria.to_owned(),
+            streaks: HashMap::new(),
+        });
+    }
+
+    /// Keeps the cells parsed before a structural error (e.g. a truncated record or a
+    /// bad attribute value) when `lenient`, recording the error as an anomaly.
+    /// Error values of cells are data rather than structure, and still fail.
+    /// Every error is counted by the metrics, tolerated or not.
+    pub(super) fn tolerate(&mut self, result: Result<(), RustySheetError>, lenient: bool) -> Result<(), RustySheetError> {
+        if let Err(error) = &result {
+            metrics::record_error(error.category());
+        }
+        match result {
+            Err(RustySheetError::SpreadsheetError(error @ SpreadsheetError::CellValueError(..))) => Err(error)?,
+            Err(error) if lenient => {
+                self.anomalies.push(error.to_string());
+                Ok(())
+            }
+            result => result,
         }
     }
 
+    /// Hashes the cells while parsing instead of keeping them, for `checksum`.
+    pub(super) fn hash_only(&mut self) {
+        self.digest = Some(Digest {
+            hasher: ContentHasher::new(),
+            shared_indexes: BTreeSet::new(),
+        });
+    }
+
+    /// Marks hidden, zero-width and collapsed outline columns as hidden.
+    ///
+    /// A collapsed column hides the adjacent run of columns with a deeper
+    /// outline level, preferring the run before it (summary column on the right).
+    pub(super) fn hide_columns(&mut self, infos: &mut Vec<ColumnInfo>) {
+        infos.sort_by_key(|info| info.lower);
+        for (index, info) in infos.iter().enumerate() {
+            if info.hidden || info.width == Some(0f64) {
+                self.hidden_columns.push((info.lower, info.upper));
+            }
+            if info.collapsed {
+                let is_detail = |other: &ColumnInfo| other.outline_level > info.outline_level;
+                let mut lower = index;
+                while lower > 0 && is_detail(&infos[lower - 1]) && infos[lower - 1].upper + 1 == infos[lower].lower {
+                    lower -= 1;
+                }
+                let mut upper = index;
+                if lower == index {
+                    while upper + 1 < infos.len() && is_detail(&infos[upper + 1]) && infos[upper].upper + 1 == infos[upper + 1].lower {
+                        upper += 1;
+                    }
+                }
+                for other in infos[lower..=upper].iter().filter(|other| is_detail(other)) {
+                    self.hidden_columns.push((other.lower, other.upper));
+                }
+            }
+        }
+    }
+
+    /// Checks if a column is hidden, or lies outside every block of disjoint ranges.
+    pub(super) fn is_hidden_col(&self, col: usize) -> bool {
+        self.hidden_columns.iter().any(|(lower, upper)| (*lower..=*upper).contains(&col))
+            || (!self.blocks.is_empty() && !self.blocks.iter().any(|block| block.contains_col(col)))
+    }
+
     /// Returns true if the sheet contains no cells.
     pub(super) fn is_empty(&self) -> bool {
         self.cells.is_empty()
@@ -100,15 +547,115 @@ impl Sheet {
             && !self.after_row_upper_bound(row)
             && !self.before_col_lower_bound(col)
             && !self.after_col_upper_bound(col)
+            && !self.is_hidden_col(col)
+            && (self.blocks.is_empty() || self.blocks.iter().any(|block| block.contains(row, col)))
     }
 
     /// Adds a cell to the sheet, updating chunk boundaries and data ranges.
-    pub(super) fn push(&mut self, cell: Cell) {
-        self.update_chunk(cell.row);
-        self.update_bound(cell.row, cell.col);
+    ///
+    /// Cells are expected in row-major order. Once a cell arrives out of order
+    /// (or at an already used position) the remaining cells are buffered and
+    /// the chunks are rebuilt when the sheet is finished.
+    pub(super) fn push(&mut self, mut cell: Cell) {
+        self.parsed_cells += 1;
+        if let Some(digest) = &mut self.digest {
+            if cell.kind == CellType::SharedString {
+                if let Ok(index) = cell.value.parse::<usize>() {
+                    digest.shared_indexes.insert(index);
+                }
+            }
+            digest.hasher.write_cell(cell.row, cell.col, cell.kind, &cell.value);
+            self.update_bound(cell.row, cell.col);
+            return;
+        }
+        if self.count_only {
+            cell.value = String::new();
+        } else if cell.kind == CellType::InlineString {
+            if self.fix_encoding {
+                repair_encoding(&mut cell.value);
+            }
+            if let Some((length, suffix)) = &self.max_cell_length {
+                truncate_text(&mut cell.value, *length, suffix);
+            }
+        }
+        if self.reservoir.is_some() {
+            self.update_bound(cell.row, cell.col);
+            match &mut self.reservoir {
+                Some(reservoir) if self.row_lower_bound != Some(cell.row) => reservoir.push(cell),
+                _ => self.cells.push(cell), // The first (header) row is always kept
+            }
+            return;
+        }
+        if !self.is_unordered {
+            if let Some(last) = self.cells.last() {
+                self.is_unordered = (cell.row, cell.col) <= (last.row, last.col);
+            }
+        }
+        if !self.is_unordered {
+            if self.is_converged(cell.row) {
+                return;
+            }
+            self.update_chunk(cell.row);
+            self.update_bound(cell.row, cell.col);
+            if let Some(convergence) = &mut self.convergence {
+                convergence.track(&cell);
+            }
+        }
         self.cells.push(cell);
     }
 
+    /// Adds a cell holding the text of its formula, read with `formulas=true`,
+    /// recording its position so that it is told apart from text that merely starts with `=`.
+    pub(super) fn push_formula(&mut self, cell: Cell) {
+        self.formula_cells.insert((cell.row, cell.col));
+        self.push(cell);
+    }
+
+    /// Checks, when `row` starts a new row, whether the column types were stable at the
+    /// end of the previous one, and if so lowers the row limit to end the sheet there.
+    fn is_converged(&mut self, row: usize) -> bool {
+        let (Some(convergence), Some(row_lower_bound), Some(row_upper_bound)) = (&self.convergence, self.row_lower_bound, self.row_upper_bound) else {
+            return false;
+        };
+        if row_upper_bound == row || !convergence.is_stable() {
+            return false;
+        }
+        self.limit = Some(row_upper_bound + 1 - row_lower_bound);
+        self.convergence = None;
+        true
+    }
+
+    /// Sorts buffered out-of-order cells into row-major order and rebuilds chunks and bounds.
+    /// For duplicated positions the last cell wins.
+    fn reorder(&mut self) {
+        let mut cells = std::mem::take(&mut self.cells);
+        cells.sort_by_key(|cell| (cell.row, cell.col)); // Stable, keeps duplicates in input order
+        let mut unique = Vec::<Cell>::with_capacity(cells.len());
+        for cell in cells {
+            match unique.last_mut() {
+                Some(last) if last.row == cell.row && last.col == cell.col => *last = cell,
+                _ => unique.push(cell),
+            }
+        }
+
+        self.is_unordered = false;
+        self.is_reordered = true;
+        self.chunks.clear();
+        self.chunk_index_lower = 0;
+        self.chunk_row_lower = self.range.row_lower_bound.filter(|_| !self.skip_empty_rows);
+        self.row_lower_bound = None;
+        self.row_upper_bound = None;
+        self.col_lower_bound = None;
+        self.col_upper_bound = None;
+        self.cells.reserve(unique.len());
+        for cell in unique {
+            if self.after_row_upper_bound(cell.row) {
+                break;
+            }
+            self.push(cell);
+        }
+    }
+
     /// Updates chunk boundaries when adding cells.
     /// Manages chunk creation for efficient data processing.
     fn update_chunk(&mut self, row: usize) {
@@ -161,6 +708,19 @@ impl Sheet {
     /// Finalizes chunk creation after all cells have been added.
     /// Creates remaining chunks to cover the entire data range.
     pub(super) fn finish(&mut self, end_at_empty_row: bool) {
+        metrics::record_cells_parsed(std::mem::take(&mut self.parsed_cells));
+        if let Some(reservoir) = self.reservoir.take() {
+            self.cells.extend(reservoir.finish());
+            return; // Sampled sheets are only analyzed, never read in chunks
+        }
+        if self.digest.is_some() {
+            return; // Hashed sheets keep no cells to read in chunks
+        }
+        if self.is_unordered {
+            self.reorder();
+        }
+        self.reached_limit = self.row_lower_bound.zip(self.row_upper_bound).zip(self.limit)
+            .is_some_and(|((row_lower_bound, row_upper_bound), limit)| row_lower_bound + limit <= row_upper_bound + 1);
         if let Some(row_upper_bound) = self.range.row_upper_bound
             .filter(|_| !self.skip_empty_rows && !end_at_empty_row)
             .or(self.row_upper_bound)
@@ -190,38 +750,131 @@ impl Sheet {
         }
     }
 
+    /// Returns the number of rows in a chunk without building its table.
+    /// Returns None if the chunk index is out of bounds.
+    pub(crate) fn chunk_len(&self, index: usize) -> Option<usize> {
+        self.chunks.get(index).map(|(row_lower, row_upper, _, _)| row_upper - row_lower + 1)
+    }
+
+    /// Returns the number of leading chunks that reading more rows would not change: every
+    /// chunk of a sheet that ended before its row limit, otherwise those ending before the last
+    /// row read. A sheet read up to a row limit may have stopped within its last chunk, and
+    /// out-of-order cells past the limit may belong to any chunk.
+    pub(crate) fn settled_chunks(&self) -> usize {
+        if self.is_reordered {
+            return 0;
+        }
+        if !self.reached_limit {
+            return self.chunks.len();
+        }
+        self.chunks.iter()
+            .take_while(|(_, row_upper, _, _)| self.row_upper_bound.is_some_and(|last_row| *row_upper < last_row))
+            .count()
+    }
+
+    /// Returns the number of rows read from the sheet, those of all its chunks.
+    pub(crate) fn row_count(&self) -> usize {
+        (0..self.chunks.len()).filter_map(|index| self.chunk_len(index)).sum()
+    }
+
+    /// Computes a hash of the cell positions, types and values.
+    ///
+    /// Shared strings are resolved through `shared_strings` and hashed as inline strings,
+    /// so identical sheets hash equally whatever the string tables of their workbooks.
+    pub(crate) fn content_hash(&self, shared_strings: &SharedStrings) -> Result<u64, RustySheetError> {
+        let mut hasher = ContentHasher::new();
+        for cell in &self.cells {
+            match cell.kind {
+                CellType::SharedString => {
+                    let shared_string = match cell.value.parse::<usize>() {
+                        Ok(index) => shared_strings.get(index)?,
+                        Err(_) => None,
+                    };
+                    match shared_string {
+                        Some(shared_string) => hasher.write_cell(cell.row, cell.col, CellType::InlineString, &shared_string),
+                        None => hasher.write_cell(cell.row, cell.col, CellType::Empty, ""), // null literal
+                    }
+                }
+                kind => hasher.write_cell(cell.row, cell.col, kind, &cell.value),
+            }
+        }
+        Ok(hasher.0)
+    }
+
+    /// Returns the checksum of a sheet read with `hash_only`, completing the hash of the
+    /// cells with the text of the shared strings they reference.
+    ///
+    /// Unlike `content_hash`, shared strings are hashed by index, so the checksum detects
+    /// changes of the same workbook over time without keeping its cells in memory.
+    pub(crate) fn checksum(&self, shared_strings: &[String], mappings: &HashMap<usize, usize>) -> Option<u64> {
+        let digest = self.digest.as_ref()?;
+        let mut hasher = digest.hasher;
+        for index in &digest.shared_indexes {
+            let position = mappings.get(index).copied().unwrap_or(*index);
+            hasher.write_u64(*index as u64);
+            hasher.write_str(shared_strings.get(position).map(String::as_str).unwrap_or_default());
+        }
+        Some(hasher.0)
+    }
+
     /// Retrieves a chunk of data as a 2D table of optional cell references.
     /// Returns None if the chunk index is out of bounds.
     pub(crate) fn chunk(&self, index: usize) -> Option<Vec<Vec<Option<&Cell>>>> {
-        let (row_lower, row_upper, index_lower, index_upper) = self.chunks.get(index)?;
+        let mut buffer = ChunkBuffer::default();
+        if !self.layout_chunk(index, &mut buffer) {
+            return None;
+        }
+        let table = (0..buffer.rows)
+            .map(|row| (0..buffer.width).map(|col| buffer.cell(self, row, col)).collect())
+            .collect();
+        Some(table)
+    }
+
+    /// Lays out the cells of a chunk into a reusable buffer, row after row.
+    /// Returns false if the chunk index is out of bounds.
+    pub(crate) fn layout_chunk(&self, index: usize, buffer: &mut ChunkBuffer) -> bool {
+        let Some((row_lower, row_upper, index_lower, index_upper)) = self.chunks.get(index) else {
+            return false;
+        };
         let col_lower = self.range.col_lower_bound.or(self.col_lower_bound).unwrap();
         let col_upper = self.range.col_upper_bound.or(self.col_upper_bound).unwrap();
+        buffer.cells.clear();
+        buffer.rows = row_upper - row_lower + 1;
+        buffer.width = (col_lower..=col_upper).filter(|col| !self.is_hidden_col(*col)).count();
         let mut index = *index_lower;
-        let mut table = Vec::<Vec<Option<&Cell>>>::new();
         for row in (*row_lower)..=(*row_upper) {
-            let mut record = Vec::<Option<&Cell>>::new();
-            for col in col_lower..=col_upper {
+            for col in (col_lower..=col_upper).filter(|col| !self.is_hidden_col(*col)) {
                 if index == *index_upper {
-                    record.push(None);
+                    buffer.cells.push(None);
                 } else {
                     let cell = &self.cells[index];
                     if row == cell.row && col == cell.col {
-                        record.push(Some(cell));
+                        buffer.cells.push(Some(index));
                         index += 1;
                     } else {
-                        record.push(None);
+                        buffer.cells.push(None);
                     }
                 }
             }
-            table.push(record);
         }
-        Some(table)
+        true
+    }
+
+    /// Releases the text of the cells of a chunk whose rows were consumed
+    pub(crate) fn release_chunk(&mut self, index: usize) {
+        if let Some(&(_, _, index_lower, index_upper)) = self.chunks.get(index) {
+            self.cells[index_lower..index_upper].iter_mut().for_each(|cell| cell.value = String::new());
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::spreadsheet::sheet::ChunkBuffer;
     use crate::database::range::Range;
+    use crate::spreadsheet::sheet::ColumnInfo;
+    use crate::spreadsheet::sheet::truncate_text;
+    use std::collections::HashMap;
     use crate::spreadsheet::*;
 
     fn push(sheet: &mut Sheet, row: usize, col: usize) {
@@ -235,7 +888,7 @@ mod tests {
 
     #[test]
     fn sheet_initial() {
-        let sheet = Sheet::new("", "", None, None, false);
+        let sheet = Sheet::new("", "", None, None, None, false, false);
 
         assert_eq!(sheet.row_lower_bound, None);
         assert_eq!(sheet.row_upper_bound, None);
@@ -245,7 +898,7 @@ mod tests {
 
     #[test]
     fn sheet_update() {
-        let mut sheet = Sheet::new("", "", None, None, false);
+        let mut sheet = Sheet::new("", "", None, None, None, false, false);
         push(&mut sheet, 1, 1);
         push(&mut sheet, 1, 3);
         push(&mut sheet, 3, 1);
@@ -265,11 +918,39 @@ mod tests {
         assert_eq!(*row_upper, 3);
         assert_eq!(*index_lower, 0);
         assert_eq!(*index_upper, 4);
+        assert_eq!(sheet.row_count(), 3);
+    }
+
+    #[test]
+    fn chunk_buffer_reuse() {
+        let mut wide = Sheet::new("", "", None, None, None, false, false);
+        push(&mut wide, 1, 1);
+        push(&mut wide, 1, 3);
+        push(&mut wide, 2, 2);
+        wide.finish(false);
+        let mut narrow = Sheet::new("", "", None, None, None, false, false);
+        push(&mut narrow, 0, 0);
+        narrow.finish(false);
+
+        let mut buffer = ChunkBuffer::default();
+        assert!(wide.layout_chunk(0, &mut buffer));
+        assert_eq!(buffer.rows(), 2);
+        let positions = |sheet: &Sheet, buffer: &ChunkBuffer, width: usize| (0..buffer.rows())
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| buffer.cell(sheet, row, col).map(|cell| (cell.row, cell.col)))
+            .collect::<Vec<_>>();
+        assert_eq!(positions(&wide, &buffer, 3), vec![Some((1, 1)), None, Some((1, 3)), None, Some((2, 2)), None]);
+
+        // The buffer keeps nothing of the chunk laid out before
+        assert!(narrow.layout_chunk(0, &mut buffer));
+        assert_eq!(buffer.rows(), 1);
+        assert_eq!(positions(&narrow, &buffer, 1), vec![Some((0, 0))]);
+        assert!(!narrow.layout_chunk(1, &mut buffer));
     }
 
     #[test]
     fn sheet_update_skip_empty_rows() {
-        let mut sheet = Sheet::new("", "", None, None, true);
+        let mut sheet = Sheet::new("", "", None, None, None, true, false);
         push(&mut sheet, 1, 1);
         push(&mut sheet, 1, 3);
         push(&mut sheet, 3, 1);
@@ -294,6 +975,7 @@ mod tests {
         assert_eq!(*row_upper, 3);
         assert_eq!(*index_lower, 2);
         assert_eq!(*index_upper, 4);
+        assert_eq!(sheet.row_count(), 2);
     }
 
     #[test]
@@ -303,7 +985,7 @@ mod tests {
             row_upper_bound: Some(5),
             col_lower_bound: Some(0),
             col_upper_bound: Some(5),
-        }), None, false);
+        }), None, None, false, false);
         push(&mut sheet, 1, 1);
         push(&mut sheet, 1, 3);
         push(&mut sheet, 3, 1);
@@ -332,7 +1014,7 @@ mod tests {
             row_upper_bound: Some(5),
             col_lower_bound: Some(0),
             col_upper_bound: Some(5),
-        }), None, true);
+        }), None, None, true, false);
         push(&mut sheet, 1, 1);
         push(&mut sheet, 1, 3);
         push(&mut sheet, 3, 1);
@@ -366,7 +1048,7 @@ mod tests {
             row_upper_bound: Some(5),
             col_lower_bound: None,
             col_upper_bound: None,
-        }), None, true);
+        }), None, None, true, false);
         push(&mut sheet, 1, 1);
         push(&mut sheet, 1, 3);
         push(&mut sheet, 2, 2);
@@ -388,4 +1070,182 @@ mod tests {
         assert_eq!(*index_lower, 0);
         assert_eq!(*index_upper, 5);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn sheet_hide_columns() {
+        let mut sheet = Sheet::new("", "", None, None, None, false, false);
+        sheet.hide_columns(&mut vec![
+            ColumnInfo { lower: 0, upper: 0, hidden: true, ..Default::default() },
+            ColumnInfo { lower: 2, upper: 2, width: Some(0f64), ..Default::default() },
+            ColumnInfo { lower: 4, upper: 5, outline_level: 1, ..Default::default() },
+            ColumnInfo { lower: 6, upper: 6, collapsed: true, ..Default::default() },
+            ColumnInfo { lower: 8, upper: 8, collapsed: true, ..Default::default() },
+            ColumnInfo { lower: 9, upper: 10, outline_level: 1, ..Default::default() },
+        ]);
+        push(&mut sheet, 1, 1);
+        push(&mut sheet, 1, 6);
+        sheet.finish(false);
+
+        assert_eq!(sheet.hidden_columns, vec![(0, 0), (2, 2), (4, 5), (9, 10)]);
+        assert!(!sheet.contains(1, 0));
+        assert!(sheet.contains(1, 1));
+        assert_eq!(sheet.chunk(0).unwrap()[0].len(), 3);
+    }
+
+    #[test]
+    fn sheet_update_out_of_order() {
+        let mut sheet = Sheet::new("", "", None, None, None, false, false);
+        push(&mut sheet, 1, 1);
+        push(&mut sheet, 3, 1);
+        push(&mut sheet, 2, 2);
+        push(&mut sheet, 1, 3);
+        push(&mut sheet, 3, 1);
+        sheet.finish(false);
+
+        let positions = sheet.cells.iter().map(|cell| (cell.row, cell.col)).collect::<Vec<_>>();
+        assert_eq!(positions, vec![(1, 1), (1, 3), (2, 2), (3, 1)]);
+
+        assert_eq!(sheet.row_lower_bound, Some(1));
+        assert_eq!(sheet.row_upper_bound, Some(3));
+        assert_eq!(sheet.col_lower_bound, Some(1));
+        assert_eq!(sheet.col_upper_bound, Some(3));
+
+        assert_eq!(sheet.chunks.len(), 1);
+        assert_eq!(sheet.chunks[0], (1, 3, 0, 4));
+    }
+
+    #[test]
+    fn sheet_reservoir_sample() {
+        let mut sheet = Sheet::new("", "", None, None, Some(10), false, false);
+        for row in 1..=1000 {
+            push(&mut sheet, row, 1);
+            push(&mut sheet, row, 2);
+        }
+        sheet.finish(false);
+
+        assert_eq!(sheet.cells.len(), 22);
+        assert_eq!(sheet.cells[0].row, 1); // First (header) row is always kept
+        assert!(sheet.cells.windows(2).all(|pair| (pair[0].row, pair[0].col) < (pair[1].row, pair[1].col)));
+        assert!(sheet.cells.iter().any(|cell| cell.row > 500)); // Not only the first rows
+        assert_eq!(sheet.row_upper_bound, Some(1000));
+        assert!(sheet.chunks.is_empty());
+    }
+
+    #[test]
+    fn sheet_count_only() {
+        let mut sheet = Sheet::new("", "", None, None, None, false, true);
+        for row in 0..3000 {
+            sheet.push(Cell {
+                row,
+                col: 0,
+                kind: CellType::Number,
+                value: row.to_string(),
+            });
+        }
+        sheet.finish(false);
+
+        assert!(sheet.cells.iter().all(|cell| cell.value.is_empty()));
+        assert_eq!(sheet.chunks.len(), 2);
+        assert_eq!(sheet.chunk_len(0), Some(2048));
+        assert_eq!(sheet.chunk_len(1), Some(952));
+        assert_eq!(sheet.chunk_len(2), None);
+    }
+
+    #[test]
+    fn sheet_content_hash() {
+        let sheet = |kind: CellType, value: &str| {
+            let mut sheet = Sheet::new("", "", None, None, None, false, false);
+            sheet.push(Cell { row: 0, col: 0, kind, value: value.to_owned() });
+            sheet.finish(false);
+            sheet
+        };
+        let shared_strings = SharedStrings::Memory(vec![None, Some("Total".to_owned())]);
+
+        let inline = sheet(CellType::InlineString, "Total").content_hash(&shared_strings).unwrap();
+        assert_eq!(sheet(CellType::SharedString, "1").content_hash(&shared_strings).unwrap(), inline);
+        assert_ne!(sheet(CellType::SharedString, "0").content_hash(&shared_strings).unwrap(), inline);
+        assert_ne!(sheet(CellType::Number, "Total").content_hash(&shared_strings).unwrap(), inline);
+    }
+
+    #[test]
+    fn sheet_checksum() {
+        let mut sheet = Sheet::new("", "", None, None, None, false, false);
+        sheet.hash_only();
+        sheet.push(Cell { row: 0, col: 0, kind: CellType::SharedString, value: "1".to_owned() });
+        sheet.push(Cell { row: 0, col: 1, kind: CellType::Number, value: "42".to_owned() });
+        sheet.finish(false);
+        assert!(sheet.cells.is_empty());
+
+        let mappings = HashMap::new();
+        let checksum = sheet.checksum(&["Unused".to_owned(), "Total".to_owned()], &mappings);
+        assert_eq!(sheet.checksum(&["Changed".to_owned(), "Total".to_owned()], &mappings), checksum);
+        assert_ne!(sheet.checksum(&["Unused".to_owned(), "Sum".to_owned()], &mappings), checksum);
+        assert_eq!(Sheet::new("", "", None, None, None, false, false).checksum(&[], &mappings), None);
+    }
+
+    #[test]
+    fn sheet_max_cell_length() {
+        let mut text = "Grüße aus Köln".to_owned();
+        assert!(truncate_text(&mut text, 4, "…"));
+        assert_eq!(text, "Grüß…");
+        let mut text = "Köln".to_owned();
+        assert!(!truncate_text(&mut text, 4, "…"));
+        assert_eq!(text, "Köln");
+
+        let mut sheet = Sheet::new("", "", None, None, None, false, false);
+        sheet.max_cell_length = Some((2, String::new()));
+        sheet.push(Cell { row: 0, col: 0, kind: CellType::InlineString, value: "日本語".to_owned() });
+        sheet.push(Cell { row: 0, col: 1, kind: CellType::Number, value: "12345".to_owned() });
+        assert_eq!(sheet.cells[0].value, "日本");
+        assert_eq!(sheet.cells[1].value, "12345");
+    }
+
+    #[test]
+    fn sheet_fix_encoding() {
+        let mut sheet = Sheet::new("", "", None, None, None, false, false);
+        sheet.fix_encoding = true;
+        sheet.max_cell_length = Some((4, String::new()));
+        sheet.push(Cell { row: 0, col: 0, kind: CellType::InlineString, value: "KÃ¶ln".to_owned() });
+        sheet.push(Cell { row: 0, col: 1, kind: CellType::InlineString, value: "MÃ¼nchen".to_owned() });
+        assert_eq!(sheet.cells[0].value, "Köln");
+        // Truncation counts the characters of the repaired text
+        assert_eq!(sheet.cells[1].value, "Münc");
+    }
+
+    #[test]
+    fn sheet_settled_chunks() {
+        let mut sheet = Sheet::new("", "", None, Some(2500), None, false, false);
+        for row in 0..2500 {
+            push(&mut sheet, row, 0);
+        }
+        sheet.finish(false);
+        assert_eq!(sheet.chunks.len(), 2);
+        assert_eq!(sheet.settled_chunks(), 1);
+
+        let mut sheet = Sheet::new("", "", None, None, None, false, false);
+        push(&mut sheet, 2500, 0);
+        push(&mut sheet, 0, 0);
+        sheet.finish(false);
+        assert_eq!(sheet.settled_chunks(), 0);
+    }
+
+    #[test]
+    fn sheet_shorter_than_limit_settled() {
+        // A sheet ending before its row limit was read whole, even within one chunk
+        let mut sheet = Sheet::new("", "", None, Some(Sheet::CHUNK_SIZE), None, false, false);
+        for row in 0..100 {
+            push(&mut sheet, row, 0);
+        }
+        sheet.finish(false);
+        assert_eq!(sheet.chunks.len(), 1);
+        assert_eq!(sheet.settled_chunks(), 1);
+
+        // One filling its row limit may have more rows
+        let mut sheet = Sheet::new("", "", None, Some(100), None, false, false);
+        for row in 0..100 {
+            push(&mut sheet, row, 0);
+        }
+        sheet.finish(false);
+        assert_eq!(sheet.settled_chunks(), 0);
+    }
+}
@@ -2,15 +2,30 @@ use crate::error::ResultOptionChain;
 use crate::error::RustySheetError;
 use crate::helpers::biff8::Biff8Reader;
 use crate::helpers::cfb::Cfb;
+use crate::helpers::crypto::CryptoError;
+use crate::helpers::crypto::RecordCipher;
+use crate::helpers::crypto::DEFAULT_PASSWORD;
 use crate::helpers::reader::UnifiedReader;
+use crate::helpers::string::to_u16;
 use crate::match_biff8_record;
 use crate::spreadsheet::cell::to_error_value;
 use crate::spreadsheet::cell::Cell;
 use crate::spreadsheet::cell::CellType;
 use crate::spreadsheet::criteria::Criteria;
 use crate::spreadsheet::excel::load_number_formats;
+use crate::spreadsheet::excel::to_hex_color;
+use crate::spreadsheet::excel::Color;
+use crate::spreadsheet::format::NumberFormat;
+use crate::spreadsheet::formula::decode_formula;
+use crate::spreadsheet::formula::with_formula;
+use crate::spreadsheet::metadata::parse_summary_information;
+use crate::spreadsheet::metadata::WorkbookMetadata;
+use crate::spreadsheet::reference::bounds_to_range;
 use crate::spreadsheet::reference::index_to_reference;
+use crate::spreadsheet::sheet::ColumnInfo;
 use crate::spreadsheet::sheet::Sheet;
+use crate::spreadsheet::sheet::SheetInfo;
+use crate::spreadsheet::sheet::Visibility;
 use crate::spreadsheet::Spreadsheet;
 use crate::spreadsheet::SpreadsheetError;
 use either::Either;
@@ -24,11 +39,13 @@ const EOF: u16 = 10;           // End of file record marking the end of a substr
 const DATE1904: u16 = 34;      // Date system flag (1904 vs 1900 base)
 const FILE_PASS: u16 = 47;     // File password protection record
 const CODE_PAGE: u16 = 66;     // Character encoding specification
+const COL_INFO: u16 = 125;     // Column width, visibility and outline properties
 const BOUND_SHEET8: u16 = 133; // Worksheet definition and position
 const MUL_RK: u16 = 189;       // Multiple RK number records for efficiency
 const XF: u16 = 224;           // Extended format record for cell styling
 const SST: u16 = 252;          // Shared string table containing repeated text
 const LABEL_SST: u16 = 253;    // Label referencing shared string table
+const DIMENSIONS: u16 = 512;   // Used range of the worksheet, follows the column properties
 const NUMBER: u16 = 515;       // Numeric cell value
 const LABEL: u16 = 516;        // Text label cell value
 const BOOL_ERR: u16 = 517;     // Boolean or error cell value
@@ -36,6 +53,14 @@ const STRING: u16 = 519;       // String value for formula results
 const RK: u16 = 638;           // RK number format for compressed numeric storage
 const FORMAT: u16 = 1054;      // Custom number format definition
 const BOF: u16 = 2057;         // Beginning of file record for substreams
+const SHEET_EXT: u16 = 2146;   // Sheet extension holding the tab color
+
+/// Name of the compound file stream holding the document properties
+const SUMMARY_INFORMATION: &str = "\u{5}SummaryInformation";
+
+/// Records stored unencrypted after the FILE_PASS record: BOF, FilePass, InterfaceHdr, RRDHead,
+/// UsrExcl, FileLock and RRDInfo (MS-XLS 2.2.10)
+const UNENCRYPTED_RECORDS: [u16; 7] = [BOF, FILE_PASS, 225, 312, 404, 405, 406];
 
 /// Error types specific to XLS file parsing
 #[derive(Error, Debug)]
@@ -58,9 +83,11 @@ pub(crate) struct XlsSpreadsheet {
     /// Shared string table containing repeated text values
     shared_strings: Vec<String>,
     /// Number format mappings for cell type detection
-    number_formats: Vec<CellType>,
-    /// List of worksheets with their names and stream positions
-    sheets: Vec<(String, usize)>,
+    number_formats: Vec<NumberFormat>,
+    /// List of worksheets with their names, stream positions and visibility
+    sheets: Vec<(String, usize, Visibility)>,
+    /// Document properties of the summary information stream, with the sheet count and date system
+    metadata: WorkbookMetadata,
 }
 
 impl XlsSpreadsheet {
@@ -68,25 +95,27 @@ impl XlsSpreadsheet {
     ///
     /// # Arguments
     /// * `file_name` - Path to the XLS file to open
+    /// * `snapshot` - Whether to read a consistent copy of a local file instead of the file itself
+    /// * `password` - Password of an encrypted workbook
     ///
     /// # Returns
     /// * `Result<XlsSpreadsheet, RustySheetError>` - Initialized spreadsheet or error
-    pub(crate) fn open(file_name: &str) -> Result<XlsSpreadsheet, RustySheetError> {
+    pub(crate) fn open(file_name: &str, snapshot: bool, password: Option<&str>) -> Result<XlsSpreadsheet, RustySheetError> {
         // Open file from local path or remote URL
-        let mut reader = UnifiedReader::new(file_name)?;
+        let mut reader = UnifiedReader::open(file_name, snapshot)?;
         let cfb = Cfb::new(&mut reader)?;
-        let mut reader = cfb.read("Workbook")
+        let mut stream = cfb.read("Workbook")
             .ok_none_else(|| cfb.read("Book"))?
-            .map(Biff8Reader::new)
             .ok_or_else(|| SpreadsheetError::SpreadsheetEmptyError(file_name.to_owned()))?;
+        decrypt_stream(&mut stream, file_name, password)?;
+        let mut reader = Biff8Reader::new(stream);
         let mut is_1904 = false;
         let mut shared_strings = Vec::new();
-        let mut custom_formats: HashMap<String, CellType> = HashMap::new();
+        let mut custom_formats: HashMap<String, String> = HashMap::new();
         let mut format_indexes: Vec<String> = Vec::new();
-        let mut sheets: Vec<(String, usize)> = Vec::new();
+        let mut sheets: Vec<(String, usize, Visibility)> = Vec::new();
         match_biff8_record!(reader => {
             EOF => break,
-            FILE_PASS if reader.read_u16()? != 0 => Err(SpreadsheetError::SpreadsheetPasswordProtectedError(file_name.to_owned()))?,
             DATE1904 if reader.read_u16()? == 1 => is_1904 = true,
             CODE_PAGE => {
                 let code_page = reader.read_u16()?;
@@ -95,10 +124,7 @@ impl XlsSpreadsheet {
             FORMAT => {
                 let id = reader.read_u16()?;
                 let format = reader.read_xl_unicode_string()?;
-                custom_formats.insert(
-                    id.to_string(),
-                    CellType::parse_custom_number_format(format.as_ref(), is_1904),
-                );
+                custom_formats.insert(id.to_string(), format.to_string());
             }
             XF => {
                 reader.skip(2)?;
@@ -108,9 +134,10 @@ impl XlsSpreadsheet {
             SST => shared_strings = load_shared_strings(&mut reader)?,
             BOUND_SHEET8 => {
                 let pointer = reader.read_usize()?;
-                reader.skip(2)?;
+                let visibility = Visibility::from_state(reader.read_u8()? as u32);
+                reader.skip(1)?;
                 let sheet_name = reader.read_short_xl_unicode_string()?;
-                sheets.push((sheet_name, pointer));
+                sheets.push((sheet_name, pointer, visibility));
             }
         });
         if sheets.is_empty() {
@@ -119,14 +146,133 @@ impl XlsSpreadsheet {
 
         let number_formats = load_number_formats(format_indexes, custom_formats, is_1904);
 
+        // The property set stream is optional and never encrypted; a damaged one is ignored
+        let summary = cfb.read(SUMMARY_INFORMATION).ok().flatten().unwrap_or_default();
+        let metadata = WorkbookMetadata {
+            sheet_count: sheets.len(),
+            is_1904,
+            ..parse_summary_information(&summary)
+        };
+
         Ok(XlsSpreadsheet {
             name: file_name.to_owned(),
             reader,
             shared_strings,
             number_formats,
             sheets,
+            metadata,
         })
     }
+
+    /// Parses the cell records of the worksheet substream at the reader position into the sheet
+    ///
+    /// # Arguments
+    /// * `sheet` - Sheet receiving the cells
+    /// * `criteria` - Selection criteria for which data to extract
+    fn read_cells(&mut self, sheet: &mut Sheet, criteria: &Criteria) -> Result<(), RustySheetError> {
+        let mut last_row = sheet.chunk_row_lower;
+        let mut columns = Vec::<ColumnInfo>::new();
+        while let Some(tag) = self.reader.next()? {
+            match tag {
+                BOF | EOF => break,
+                COL_INFO if !criteria.include_hidden_columns => columns.push(read_column_info(&mut self.reader)?),
+                DIMENSIONS => sheet.hide_columns(&mut columns),
+                SHEET_EXT => {
+                    self.reader.skip(16)?; // Skip FrtHeader and cb
+                    // Palette index of the tab color, 0x7F when the tab is not colored
+                    let index = self.reader.read_u32()? & 0x7F;
+                    if index != 0x7F {
+                        sheet.tab_color = to_hex_color(Color::Indexed(index as usize), 0.0, &[]);
+                    }
+                }
+                MUL_RK => {
+                    let row = self.reader.read_u16()? as usize;
+                    let col_lower_bound = self.reader.read_u16()? as usize;
+                    let col_upper_bound = self.reader.get_u16_back(2)? as usize;
+                    for col in col_lower_bound..=col_upper_bound {
+                        if sheet.contains(row, col) {
+                            if let Some(last_row) = last_row {
+                                if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row) || (!sheet.is_empty() && last_row + 1 < row)) {
+                                    break;
+                                }
+                            }
+                            last_row = Some(row);
+                            let index = self.reader.read_u16()? as usize;
+                            let value = self.reader.read_rk_number()?;
+                            let (kind, value) = if criteria.display_values {
+                                (CellType::InlineString, self.number_formats[index].render(&value))
+                            } else {
+                                (self.number_formats[index].kind, value)
+                            };
+                            sheet.push(Cell {
+                                row,
+                                col,
+                                kind,
+                                value,
+                            });
+                        } else {
+                            self.reader.skip(6)?; // Skip RkRec
+                        }
+                    }
+                }
+                BOOL_ERR | NUMBER | RK | LABEL_SST | LABEL | FORMULA => {
+                    let row = self.reader.read_u16()? as usize;
+                    let col = self.reader.read_u16()? as usize;
+                    if sheet.contains(row, col) {
+                        if let Some(last_row) = last_row {
+                            if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row) || (!sheet.is_empty() && last_row + 1 < row)) {
+                                break;
+                            }
+                        }
+                        last_row = Some(row);
+                        let mut is_formula = false;
+                        let (either, value) = match tag {
+                            BOOL_ERR => read_bool_or_error_cell(&mut self.reader)?,
+                            NUMBER => read_number_cell(&mut self.reader)?,
+                            RK => read_rk_cell(&mut self.reader)?,
+                            LABEL_SST => read_label_sst_cell(&mut self.reader)?,
+                            LABEL => read_label_cell(&mut self.reader)?,
+                            _ => {
+                                let (either, value, formula) = read_formula_cell(&mut self.reader, criteria.formulas)?;
+                                is_formula = formula;
+                                (either, value)
+                            }
+                        };
+                        let (kind, value) = match either {
+                            Either::Left(kind) => (kind, value),
+                            Either::Right(index) if criteria.display_values => (CellType::InlineString, self.number_formats[index].render(&value)),
+                            Either::Right(index) => (self.number_formats[index].kind, value),
+                        };
+                        if kind != CellType::Error {
+                            if !criteria.nulls.contains(&value) {
+                                let cell = Cell {
+                                    row,
+                                    col,
+                                    kind,
+                                    value,
+                                };
+                                if is_formula {
+                                    sheet.push_formula(cell);
+                                } else {
+                                    sheet.push(cell);
+                                }
+                            }
+                        } else if !criteria.error_as_null {
+                            let reference = index_to_reference(row, col);
+                            Err(SpreadsheetError::CellValueError(
+                                sheet.file_name.to_owned(),
+                                sheet.name.to_owned(),
+                                reference,
+                                value.to_owned(),
+                            ))?
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Spreadsheet for XlsSpreadsheet {
@@ -159,6 +305,46 @@ impl Spreadsheet for XlsSpreadsheet {
         Ok((shared_strings, mappings))
     }
 
+    /// Lists the worksheets with the state of their BOUNDSHEET8 record and the
+    /// DIMENSIONS record that precedes the cells of each worksheet substream
+    fn list_sheets(&mut self) -> Result<Vec<SheetInfo>, RustySheetError> {
+        let mut sheets = Vec::<SheetInfo>::new();
+        for (index, (sheet_name, pointer, visibility)) in self.sheets.to_owned().into_iter().enumerate() {
+            self.reader.goto(pointer);
+            self.reader.next()?;
+            let mut dimension = None::<String>;
+            while let Some(tag) = self.reader.next()? {
+                match tag {
+                    BOF | EOF => break,
+                    DIMENSIONS => {
+                        // rwMic, rwMac, colMic and colMac, the upper bounds exclusive
+                        let row_lower = self.reader.read_usize()?;
+                        let row_upper = self.reader.read_usize()?;
+                        let col_lower = self.reader.read_u16()? as usize;
+                        let col_upper = self.reader.read_u16()? as usize;
+                        if row_lower < row_upper && col_lower < col_upper {
+                            dimension = Some(bounds_to_range(row_lower, col_lower, row_upper - 1, col_upper - 1));
+                        }
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+            sheets.push(SheetInfo {
+                name: sheet_name,
+                index,
+                visibility,
+                dimension,
+            });
+        }
+        Ok(sheets)
+    }
+
+    /// Returns the document properties read when the workbook was opened
+    fn metadata(&mut self) -> Result<WorkbookMetadata, RustySheetError> {
+        Ok(self.metadata.clone())
+    }
+
     /// Reads worksheets from the XLS file according to specified criteria
     ///
     /// Parses BIFF8 records to extract cell data, handling various record types
@@ -172,7 +358,7 @@ impl Spreadsheet for XlsSpreadsheet {
     fn read_sheets(&mut self, criteria: &Criteria) -> Result<Vec<Sheet>, RustySheetError> {
         let mut sheets = Vec::<Sheet>::new();
         let mut sheet_count = 0usize;
-        for (sheet_name, pointer) in &self.sheets {
+        for (index, (sheet_name, pointer, _)) in self.sheets.to_owned().iter().enumerate() {
             if criteria.sheet_limit.map(|limit| sheet_count >= limit).unwrap_or(false) {
                 break;
             } else if criteria.accept(sheet_name) {
@@ -183,82 +369,17 @@ impl Spreadsheet for XlsSpreadsheet {
 
             self.reader.goto(*pointer);
             self.reader.next()?;
-            let mut sheet = Sheet::new(&self.name, sheet_name, criteria.range, criteria.rows_limit, criteria.skip_empty_rows);
-            let mut last_row = sheet.chunk_row_lower;
-            while let Some(tag) = self.reader.next()? {
-                match tag {
-                    BOF | EOF => break,
-                    MUL_RK => {
-                        let row = self.reader.read_u16()? as usize;
-                        let col_lower_bound = self.reader.read_u16()? as usize;
-                        let col_upper_bound = self.reader.get_u16_back(2)? as usize;
-                        for col in col_lower_bound..=col_upper_bound {
-                            if sheet.contains(row, col) {
-                                if let Some(last_row) = last_row {
-                                    if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row) || (!sheet.is_empty() && last_row + 1 < row)) {
-                                        break;
-                                    }
-                                }
-                                last_row = Some(row);
-                                let index = self.reader.read_u16()? as usize;
-                                let kind = self.number_formats[index];
-                                let value = self.reader.read_rk_number()?;
-                                sheet.push(Cell {
-                                    row,
-                                    col,
-                                    kind,
-                                    value,
-                                });
-                            } else {
-                                self.reader.skip(6)?; // Skip RkRec
-                            }
-                        }
-                    }
-                    BOOL_ERR | NUMBER | RK | LABEL_SST | LABEL | FORMULA => {
-                        let row = self.reader.read_u16()? as usize;
-                        let col = self.reader.read_u16()? as usize;
-                        if sheet.contains(row, col) {
-                            if let Some(last_row) = last_row {
-                                if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row) || (!sheet.is_empty() && last_row + 1 < row)) {
-                                    break;
-                                }
-                            }
-                            last_row = Some(row);
-                            let (either, value) = match tag {
-                                BOOL_ERR => read_bool_or_error_cell(&mut self.reader)?,
-                                NUMBER => read_number_cell(&mut self.reader)?,
-                                RK => read_rk_cell(&mut self.reader)?,
-                                LABEL_SST => read_label_sst_cell(&mut self.reader)?,
-                                LABEL => read_label_cell(&mut self.reader)?,
-                                _ => read_formula_cell(&mut self.reader)?,
-                            };
-                            let kind = match either {
-                                Either::Left(kind) => kind,
-                                Either::Right(index) => self.number_formats[index],
-                            };
-                            if kind != CellType::Error {
-                                if !criteria.nulls.contains(&value) {
-                                    sheet.push(Cell {
-                                        row,
-                                        col,
-                                        kind,
-                                        value,
-                                    });
-                                }
-                            } else if !criteria.error_as_null {
-                                let reference = index_to_reference(row, col);
-                                Err(SpreadsheetError::CellValueError(
-                                    sheet.file_name.to_owned(),
-                                    sheet.name.to_owned(),
-                                    reference,
-                                    value.to_owned(),
-                                ))?
-                            }
-                        }
-                    }
-                    _ => (),
-                }
+            let mut sheet = Sheet::new(&self.name, sheet_name, criteria.range, criteria.rows_limit, criteria.sample_rows, criteria.skip_empty_rows, criteria.count_only);
+            sheet.index = index;
+            sheet.max_cell_length = criteria.max_cell_length.to_owned();
+            sheet.fix_encoding = criteria.fix_encoding;
+            sheet.blocks = criteria.ranges.to_owned();
+            sheet.stop_when_stable(criteria);
+            if criteria.checksum {
+                sheet.hash_only();
             }
+            let parsed = self.read_cells(&mut sheet, criteria);
+            sheet.tolerate(parsed, criteria.lenient)?;
             sheet.finish(criteria.end_at_empty_row);
             sheets.push(sheet);
         }
@@ -267,6 +388,46 @@ impl Spreadsheet for XlsSpreadsheet {
     }
 }
 
+/// Decrypts the records following the FILE_PASS record of an encrypted workbook stream in place
+///
+/// Record headers and the stream positions of BOUND_SHEET8 records stay unencrypted.
+/// Without a password, Excel's default password of workbooks only protected against
+/// modification is tried.
+///
+/// # Arguments
+/// * `stream` - Workbook stream
+/// * `file_name` - File name for error reporting
+/// * `password` - Password of an encrypted workbook
+fn decrypt_stream(stream: &mut [u8], file_name: &str, password: Option<&str>) -> Result<(), RustySheetError> {
+    let mut cipher: Option<RecordCipher> = None;
+    let mut position = 0;
+    while position + 4 <= stream.len() {
+        let kind = to_u16(&stream[position..]);
+        let size = to_u16(&stream[position + 2..]) as usize;
+        let lower = position + 4;
+        let upper = stream.len().min(lower + size);
+        position = lower + size;
+        match cipher.as_mut() {
+            // FILE_PASS follows the BOF record of the workbook globals, if the workbook is encrypted
+            None if kind == EOF => break,
+            None if kind == FILE_PASS => {
+                cipher = match RecordCipher::new(&stream[lower..upper], password.unwrap_or(DEFAULT_PASSWORD)) {
+                    Err(CryptoError::PasswordError) if password.is_none() => {
+                        Err(SpreadsheetError::SpreadsheetPasswordProtectedError(file_name.to_owned()))?
+                    }
+                    result => Some(result?),
+                };
+            }
+            Some(cipher) if !UNENCRYPTED_RECORDS.contains(&kind) => {
+                let lower = if kind == BOUND_SHEET8 { upper.min(lower + 4) } else { lower };
+                cipher.decrypt(&mut stream[lower..upper], lower, size);
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
 /// Loads the shared string table from BIFF8 SST record
 ///
 /// Shared strings are stored once in the file and referenced by index
@@ -288,6 +449,30 @@ fn load_shared_strings(reader: &mut Biff8Reader) -> Result<Vec<String>, RustyShe
     Ok(shared_strings)
 }
 
+/// Reads a COLINFO record holding the width, visibility and outline properties of a
+/// range of columns
+///
+/// # Arguments
+/// * `reader` - BIFF8 reader positioned at COLINFO record
+///
+/// # Returns
+/// * `Result<ColumnInfo>` - Properties of the range of columns
+fn read_column_info(reader: &mut Biff8Reader) -> Result<ColumnInfo, RustySheetError> {
+    let lower = reader.read_u16()? as usize;
+    let upper = reader.read_u16()? as usize;
+    let width = reader.read_u16()?;
+    reader.skip(2)?;
+    let flags = reader.read_u16()?;
+    Ok(ColumnInfo {
+        lower,
+        upper,
+        width: Some(width as f64 / 256f64),
+        hidden: (flags & 0x0001) != 0,
+        outline_level: ((flags >> 8) & 0x07) as u8,
+        collapsed: (flags & 0x1000) != 0,
+    })
+}
+
 /// Reads a BOOL_ERR record containing boolean or error cell values
 ///
 /// BOOL_ERR records store either boolean values (TRUE/FALSE) or error codes
@@ -386,24 +571,40 @@ fn read_label_cell(reader: &mut Biff8Reader) -> Result<(Either<CellType, usize>,
 ///
 /// # Arguments
 /// * `reader` - BIFF8 reader positioned at FORMULA record
+/// * `formulas` - Whether to read the formula text, starting with `=`, instead of the result;
+///   formulas that cannot be decoded (e.g. shared formulas) still read as their result
 ///
 /// # Returns
-/// * `Result<(Either<CellType, usize>, String)>` - Cell type and formula result
+/// * `Result<(Either<CellType, usize>, String, bool)>` - Cell type, formula result or text, and whether it is the formula text
 fn read_formula_cell(
     reader: &mut Biff8Reader,
-) -> Result<(Either<CellType, usize>, String), RustySheetError> {
+    formulas: bool,
+) -> Result<(Either<CellType, usize>, String, bool), RustySheetError> {
     let index = reader.read_u16()? as usize;
     let formula = reader.read_u64()?;
     let is_number = (formula & 0xFFFF000000000000) != 0xFFFF000000000000;
     let flag = formula & 0xFF;
+    let text = if formulas || (!is_number && flag == 2) {
+        reader.skip(6)?; // Skip grbit and chn
+        let length = reader.read_u16()? as usize;
+        decode_formula(reader.skip(length)?, false)
+    } else {
+        None
+    };
+    if formulas {
+        if let Some(text) = &text {
+            // A string result is left in the STRING record that follows
+            return Ok((Either::Left(CellType::InlineString), format!("={}", text), true));
+        }
+    }
     if is_number {
-        Ok((Either::Right(index), f64::from_bits(formula).to_string(),))
+        Ok((Either::Right(index), f64::from_bits(formula).to_string(), false))
     } else if flag == 0 {
         if let Some(kind) = reader.next()? {
             if kind == STRING {
                 // Read Next String
                 let value = reader.read_xl_unicode_string()?;
-                Ok((Either::Left(CellType::InlineString), value))
+                Ok((Either::Left(CellType::InlineString), value, false))
             } else {
                 Err(XlsError::FormulaValueError(formula))?
             }
@@ -412,14 +613,54 @@ fn read_formula_cell(
         }
     } else if flag == 1 {
         let value = if (formula & 0xFF0000) > 0 { "1" } else { "0" };
-        Ok((Either::Left(CellType::Boolean), value.to_owned()))
+        Ok((Either::Left(CellType::Boolean), value.to_owned(), false))
     } else if flag == 2 {
         let code = ((formula >> 16) & 0xFF) as u8;
-        let value = to_error_value(code).to_owned();
-        Ok((Either::Left(CellType::Error), value))
+        let value = with_formula(to_error_value(code), text);
+        Ok((Either::Left(CellType::Error), value, false))
     } else if flag == 3 {
-        Ok((Either::Left(CellType::InlineString), "".to_owned()))
+        Ok((Either::Left(CellType::InlineString), "".to_owned(), false))
     } else {
         Err(XlsError::FormulaValueError(formula))?
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a COLINFO record of the given columns, width (1/256 of a character) and flags
+    fn column_info_record(lower: u16, upper: u16, width: u16, flags: u16) -> Vec<u8> {
+        let fields = [lower, upper, width, 15, flags, 0];
+        let mut record = [COL_INFO.to_le_bytes(), 12u16.to_le_bytes()].concat();
+        record.extend(fields.iter().flat_map(|field| field.to_le_bytes()));
+        record
+    }
+
+    #[test]
+    fn column_info() {
+        let mut reader = Biff8Reader::new([
+            column_info_record(1, 3, 0x0900, 0x0001),
+            column_info_record(4, 4, 0, 0x1200),
+        ].concat());
+
+        assert_eq!(reader.next().unwrap(), Some(COL_INFO));
+        assert_eq!(read_column_info(&mut reader).unwrap(), ColumnInfo {
+            lower: 1,
+            upper: 3,
+            width: Some(9.0),
+            hidden: true,
+            outline_level: 0,
+            collapsed: false,
+        });
+        assert_eq!(reader.next().unwrap(), Some(COL_INFO));
+        assert_eq!(read_column_info(&mut reader).unwrap(), ColumnInfo {
+            lower: 4,
+            upper: 4,
+            width: Some(0.0),
+            hidden: false,
+            outline_level: 2,
+            collapsed: true,
+        });
+    }
+}
@@ -1,24 +1,37 @@
 use crate::error::RustySheetError;
 use crate::helpers::biff12::Biff12Reader;
 use crate::helpers::reader::UnifiedReader;
-use crate::helpers::zip::ZipHelper;
+use crate::helpers::zip::ZipPackage;
+use crate::helpers::zip::ZipPart;
 use crate::match_biff12_record;
 use crate::spreadsheet::cell::to_error_value;
 use crate::spreadsheet::cell::Cell;
 use crate::spreadsheet::cell::CellType;
 use crate::spreadsheet::criteria::Criteria;
 use crate::spreadsheet::excel;
+use crate::spreadsheet::excel::load_document_properties;
 use crate::spreadsheet::excel::load_relationships;
+use crate::spreadsheet::excel::Color;
+use crate::spreadsheet::excel::Relationships;
+use crate::spreadsheet::excel::Workbook;
+use crate::spreadsheet::format::NumberFormat;
+use crate::spreadsheet::formula::decode_formula;
+use crate::spreadsheet::formula::with_formula;
+use crate::spreadsheet::metadata::WorkbookMetadata;
+use crate::spreadsheet::reference::bounds_to_range;
 use crate::spreadsheet::reference::index_to_reference;
+use crate::spreadsheet::shared_strings::SharedStringsBuilder;
+use crate::spreadsheet::sheet::ColumnInfo;
 use crate::spreadsheet::sheet::Sheet;
+use crate::spreadsheet::sheet::SheetInfo;
+use crate::spreadsheet::sheet::Visibility;
 use crate::spreadsheet::Spreadsheet;
 use crate::spreadsheet::SpreadsheetError;
 use either::Either;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::io::BufRead;
 use std::io::BufReader;
-use zip::read::ZipFile;
-use zip::ZipArchive;
 
 // BIFF12 record type constants for XLSB file format
 
@@ -54,6 +67,8 @@ const BRT_FRT_END: u16 = 36;
 const BRT_FMT: u16 = 44;
 /// Cell formatting (extended format)
 const BRT_XF: u16 = 47;
+/// Column properties of a column range
+const BRT_COL_INFO: u16 = 60;
 /// Cell containing rich text string
 const BRT_CELL_R_STRING: u16 = 62;
 /// End of worksheet bundle
@@ -62,6 +77,10 @@ const BRT_END_BUNDLE_SHS: u16 = 144;
 const BRT_BEGIN_SHEET_DATA: u16 = 145;
 /// End sheet data section
 const BRT_END_SHEET_DATA: u16 = 146;
+/// Worksheet properties, including the tab color
+const BRT_WS_PROP: u16 = 147;
+/// Used range of the worksheet
+const BRT_WS_DIM: u16 = 148;
 /// Workbook properties
 const BRT_WB_PROP: u16 = 153;
 /// Worksheet bundle
@@ -73,6 +92,11 @@ const BRT_BEGIN_FMTS: u16 = 615;
 /// Begin cell formatting section
 const BRT_BEGIN_CELL_XFS: u16 = 617;
 
+/// Usual part of the shared string table, for workbooks that do not declare it
+const SHARED_STRINGS_PART: &str = "xl/sharedStrings.bin";
+/// Usual part of the styles, for workbooks that do not declare it
+const STYLES_PART: &str = "xl/styles.bin";
+
 /// Represents an XLSB (Excel Binary Workbook) spreadsheet file
 ///
 /// This struct handles the parsing and reading of XLSB format files,
@@ -81,11 +105,17 @@ pub(crate) struct XlsbSpreadsheet {
     /// Original file name of the spreadsheet
     pub(crate) name: String,
     /// ZIP archive containing the XLSB file structure
-    zip: ZipArchive<UnifiedReader>,
+    zip: ZipPackage<UnifiedReader>,
     /// Pre-parsed number formats for cell type detection
-    number_formats: Vec<CellType>,
+    number_formats: Vec<NumberFormat>,
     /// List of worksheet names and their corresponding ZIP file paths
     sheets: Vec<(String, String)>,
+    /// ZIP file path of the shared string table
+    shared_strings_path: String,
+    /// Theme colors resolving theme color references
+    theme_colors: Vec<u32>,
+    /// Whether serial dates count from 1904-01-01
+    is_1904: bool,
 }
 
 impl XlsbSpreadsheet {
@@ -93,18 +123,136 @@ impl XlsbSpreadsheet {
     ///
     /// # Arguments
     /// * `file_name` - Path to the XLSB file to open
+    /// * `salvage` - Whether to recover the entries of an archive with a damaged central directory
+    /// * `snapshot` - Whether to read a consistent copy of a local file instead of the file itself
+    /// * `password` - Password of an encrypted workbook
     ///
     /// # Returns
     /// * `Result<XlsbSpreadsheet, RustySheetError>` - Initialized spreadsheet or error
-    pub(crate) fn open(file_name: &str) -> Result<XlsbSpreadsheet, RustySheetError> {
-        let (zip, number_formats, sheets) = excel::open(file_name, load_workbook, load_number_formats)?;
+    pub(crate) fn open(file_name: &str, salvage: bool, snapshot: bool, password: Option<&str>) -> Result<XlsbSpreadsheet, RustySheetError> {
+        let Workbook { zip, number_formats, sheets, shared_strings, theme_colors, is_1904 } = excel::open(file_name, salvage, snapshot, password, load_workbook, load_number_formats)?;
         Ok(XlsbSpreadsheet {
             name: file_name.to_owned(),
             zip,
             number_formats,
             sheets,
+            shared_strings_path: shared_strings.unwrap_or_else(|| SHARED_STRINGS_PART.to_owned()),
+            theme_colors,
+            is_1904,
         })
     }
+
+    /// Parses the cell records of a worksheet part into the sheet
+    ///
+    /// # Arguments
+    /// * `sheet` - Sheet receiving the cells
+    /// * `zip_path` - Path of the worksheet part in the archive
+    /// * `criteria` - Selection criteria for which data to extract
+    fn read_cells(&mut self, sheet: &mut Sheet, zip_path: &str, criteria: &Criteria) -> Result<(), RustySheetError> {
+        let mut last_row = sheet.chunk_row_lower;
+        let mut row = 0usize;
+        let mut reader = self.zip.biff_reader(&zip_path)?
+            .ok_or_else(|| SpreadsheetError::FileError(zip_path.to_owned()))?;
+        let mut columns = Vec::<ColumnInfo>::new();
+        match_biff12_record!(reader => {
+            BRT_BEGIN_SHEET_DATA => break,
+            BRT_WS_PROP => {
+                // BrtColor after 3 bytes of flags: color type, index, tint, then R, G, B and alpha
+                let color = &reader.buffer[3..11];
+                let tint = i16::from_le_bytes([color[2], color[3]]) as f64 / i16::MAX as f64;
+                let color = match color[0] >> 1 {
+                    1 => Some(Color::Indexed(color[1] as usize)),
+                    2 => Some(Color::Rgb(u32::from_be_bytes([0, color[4], color[5], color[6]]))),
+                    3 => Some(Color::Theme(color[1] as usize)),
+                    _ => None,
+                };
+                sheet.tab_color = color.and_then(|color| excel::to_hex_color(color, tint, &self.theme_colors));
+            }
+            BRT_COL_INFO if !criteria.include_hidden_columns => columns.push(read_column_info(&reader)),
+        });
+        sheet.hide_columns(&mut columns);
+        loop {
+            let tag = reader.next()?;
+            match tag {
+                BRT_END_SHEET_DATA => break,
+                BRT_ROW_HDR => {
+                    row = reader.get_usize(0);
+                    if sheet.after_row_upper_bound(row) {
+                        break;
+                    }
+                }
+                BRT_CELL_RK
+                | BRT_CELL_BOOL | BRT_FMLA_BOOL
+                | BRT_CELL_REAL | BRT_FMLA_NUM
+                | BRT_CELL_ST | BRT_FMLA_STRING
+                | BRT_CELL_R_STRING
+                | BRT_CELL_ISST
+                | BRT_CELL_ERROR | BRT_FMLA_ERROR
+                if !sheet.before_row_lower_bound(row) => {
+                    let col = reader.get_usize(0);
+                    if sheet.contains(row, col) {
+                        if let Some(last_row) = last_row {
+                            if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row) || (!sheet.is_empty() && last_row + 1 < row)) {
+                                break;
+                            }
+                        }
+                        last_row = Some(row);
+                        let formula = match tag {
+                            BRT_FMLA_BOOL | BRT_FMLA_ERROR if criteria.formulas => read_cell_formula(&reader, 11),
+                            BRT_FMLA_NUM if criteria.formulas => read_cell_formula(&reader, 18),
+                            BRT_FMLA_STRING if criteria.formulas => {
+                                let (_, bound) = reader.get_str_and_bound(8)?;
+                                read_cell_formula(&reader, bound + 2)
+                            }
+                            _ => None,
+                        };
+                        let is_formula = formula.is_some();
+                        let (either, value) = match (tag, formula) {
+                            (_, Some(formula)) => (Either::Left(CellType::InlineString), format!("={}", formula)),
+                            (BRT_CELL_BOOL | BRT_FMLA_BOOL, _) => read_bool_cell(&mut reader),
+                            (BRT_CELL_REAL | BRT_FMLA_NUM, _) => read_real_cell(&mut reader),
+                            (BRT_CELL_ST | BRT_FMLA_STRING, _) => read_st_cell(&mut reader)?,
+                            (BRT_CELL_R_STRING, _) => read_rich_string_cell(&mut reader)?,
+                            (BRT_CELL_ISST, _) => read_shared_string_cell(&mut reader),
+                            (BRT_FMLA_ERROR, _) => read_formula_error_cell(&mut reader),
+                            (BRT_CELL_ERROR, _) => read_error_cell(&mut reader),
+                            _ => read_rk_cell(&mut reader),
+                        };
+                        let (kind, value) = match either {
+                            Either::Left(kind) => (kind, value),
+                            Either::Right(index) if criteria.display_values => (CellType::InlineString, self.number_formats[index].render(&value)),
+                            Either::Right(index) => (self.number_formats[index].kind, value),
+                        };
+                        if kind != CellType::Error {
+                            if !criteria.nulls.contains(&value) {
+                                let cell = Cell {
+                                    row: row,
+                                    col: col,
+                                    kind,
+                                    value,
+                                };
+                                if is_formula {
+                                    sheet.push_formula(cell);
+                                } else {
+                                    sheet.push(cell);
+                                }
+                            }
+                        } else if !criteria.error_as_null {
+                            let reference = index_to_reference(row, col);
+                            Err(SpreadsheetError::CellValueError(
+                                sheet.file_name.to_owned(),
+                                sheet.name.to_owned(),
+                                reference,
+                                value.to_owned(),
+                            ))?
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Spreadsheet for XlsbSpreadsheet {
@@ -128,7 +276,7 @@ impl Spreadsheet for XlsbSpreadsheet {
     fn load_shared_strings(&mut self, mut indexes: Option<HashSet<usize>>) -> Result<(Vec<String>, HashMap<usize, usize>), RustySheetError> {
         let mut shared_strings = Vec::<String>::new();
         let mut mappings = HashMap::<usize, usize>::new();
-        let mut reader = match self.zip.biff_reader("xl/sharedStrings.bin")? {
+        let mut reader = match self.zip.biff_reader(&self.shared_strings_path)? {
             Some(reader) => reader,
             None => return Ok((shared_strings, mappings)),
         };
@@ -156,6 +304,56 @@ impl Spreadsheet for XlsbSpreadsheet {
         Ok((shared_strings, mappings))
     }
 
+    /// Streams the items of the shared string table
+    fn stream_shared_strings(&mut self, strings: &mut SharedStringsBuilder) -> Result<(), RustySheetError> {
+        let Some(mut reader) = self.zip.biff_reader(&self.shared_strings_path)? else {
+            return Ok(());
+        };
+
+        reader.find(BRT_BEGIN_SST)?;
+        for _ in 0..reader.get_usize(4) {
+            reader.find_with(BRT_SST_ITEM, &[(BRT_FRT_BEGIN, BRT_FRT_END)])?;
+            strings.push(reader.get_str(1)?.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Loads the phonetic strings stored in the rich strings (BrtSSTItem) of the shared string table
+    fn load_phonetic_strings(&mut self) -> Result<Vec<Option<String>>, RustySheetError> {
+        let mut phonetic_strings = Vec::<Option<String>>::new();
+        let Some(mut reader) = self.zip.biff_reader(&self.shared_strings_path)? else {
+            return Ok(phonetic_strings);
+        };
+
+        reader.find(BRT_BEGIN_SST)?;
+        for _ in 0..reader.get_usize(4) {
+            let size = reader.find_with(BRT_SST_ITEM, &[(BRT_FRT_BEGIN, BRT_FRT_END)])?;
+            phonetic_strings.push(read_phonetic_string(&reader, size)?);
+        }
+        Ok(phonetic_strings)
+    }
+
+    /// Lists the worksheets with the state of their BrtBundleSh record and the
+    /// BrtWsDim record that precedes the cells of each worksheet
+    fn list_sheets(&mut self) -> Result<Vec<SheetInfo>, RustySheetError> {
+        let states = load_sheet_states(&mut self.zip)?;
+        let mut sheets = Vec::<SheetInfo>::new();
+        for (index, (sheet_name, zip_path)) in self.sheets.iter().enumerate() {
+            sheets.push(SheetInfo {
+                name: sheet_name.to_owned(),
+                index,
+                visibility: states.get(sheet_name).copied().unwrap_or(Visibility::Visible),
+                dimension: load_dimension(&mut self.zip, zip_path)?,
+            });
+        }
+        Ok(sheets)
+    }
+
+    /// Reads the document properties from the package parts shared by the Office Open XML formats
+    fn metadata(&mut self) -> Result<WorkbookMetadata, RustySheetError> {
+        load_document_properties(&mut self.zip, self.sheets.len(), self.is_1904)
+    }
+
     /// Reads worksheet data from the XLSB file according to specified criteria
     ///
     /// Processes each worksheet, filtering by name and range constraints,
@@ -169,7 +367,7 @@ impl Spreadsheet for XlsbSpreadsheet {
     fn read_sheets(&mut self, criteria: &Criteria) -> Result<Vec<Sheet>, RustySheetError> {
         let mut sheets = Vec::<Sheet>::new();
         let mut sheet_count = 0usize;
-        for (sheet_name, zip_path) in &self.sheets {
+        for (index, (sheet_name, zip_path)) in self.sheets.to_owned().iter().enumerate() {
             if criteria.sheet_limit.map(|limit| sheet_count >= limit).unwrap_or(false) {
                 break;
             } else if criteria.accept(sheet_name) {
@@ -178,74 +376,17 @@ impl Spreadsheet for XlsbSpreadsheet {
                 continue;
             }
 
-            let mut sheet = Sheet::new(&self.name, sheet_name, criteria.range, criteria.rows_limit, criteria.skip_empty_rows);
-            let mut last_row = sheet.chunk_row_lower;
-            let mut row = 0usize;
-            let mut reader = self.zip.biff_reader(&zip_path)?
-                .ok_or_else(|| SpreadsheetError::FileError(zip_path.to_owned()))?;
-            reader.find(BRT_BEGIN_SHEET_DATA)?;
-            loop {
-                let tag = reader.next()?;
-                match tag {
-                    BRT_END_SHEET_DATA => break,
-                    BRT_ROW_HDR => {
-                        row = reader.get_usize(0);
-                        if sheet.after_row_upper_bound(row) {
-                            break;
-                        }
-                    }
-                    BRT_CELL_RK
-                    | BRT_CELL_BOOL | BRT_FMLA_BOOL
-                    | BRT_CELL_REAL | BRT_FMLA_NUM
-                    | BRT_CELL_ST | BRT_FMLA_STRING
-                    | BRT_CELL_R_STRING
-                    | BRT_CELL_ISST
-                    | BRT_CELL_ERROR | BRT_FMLA_ERROR
-                    if !sheet.before_row_lower_bound(row) => {
-                        let col = reader.get_usize(0);
-                        if sheet.contains(row, col) {
-                            if let Some(last_row) = last_row {
-                                if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row) || (!sheet.is_empty() && last_row + 1 < row)) {
-                                    break;
-                                }
-                            }
-                            last_row = Some(row);
-                            let (either, value) = match tag {
-                                BRT_CELL_BOOL | BRT_FMLA_BOOL => read_bool_cell(&mut reader),
-                                BRT_CELL_REAL | BRT_FMLA_NUM => read_real_cell(&mut reader),
-                                BRT_CELL_ST | BRT_FMLA_STRING => read_st_cell(&mut reader)?,
-                                BRT_CELL_R_STRING => read_rich_string_cell(&mut reader)?,
-                                BRT_CELL_ISST => read_shared_string_cell(&mut reader),
-                                BRT_CELL_ERROR | BRT_FMLA_ERROR => read_error_cell(&mut reader),
-                                _ => read_rk_cell(&mut reader),
-                            };
-                            let kind = match either {
-                                Either::Left(kind) => kind,
-                                Either::Right(index) => (self.number_formats)[index],
-                            };
-                            if kind != CellType::Error {
-                                if !criteria.nulls.contains(&value) {
-                                    sheet.push(Cell {
-                                        row: row,
-                                        col: col,
-                                        kind,
-                                        value,
-                                    });
-                                }
-                            } else if !criteria.error_as_null {
-                                let reference = index_to_reference(row, col);
-                                Err(SpreadsheetError::CellValueError(
-                                    sheet.file_name.to_owned(),
-                                    sheet.name.to_owned(),
-                                    reference,
-                                    value.to_owned(),
-                                ))?
-                            }
-                        }
-                    }
-                    _ => (),
-                }
+            let mut sheet = Sheet::new(&self.name, sheet_name, criteria.range, criteria.rows_limit, criteria.sample_rows, criteria.skip_empty_rows, criteria.count_only);
+            sheet.index = index;
+            sheet.max_cell_length = criteria.max_cell_length.to_owned();
+            sheet.fix_encoding = criteria.fix_encoding;
+            sheet.blocks = criteria.ranges.to_owned();
+            sheet.stop_when_stable(criteria);
+            if criteria.checksum {
+                sheet.hash_only();
             }
+            let parsed = self.read_cells(&mut sheet, zip_path, criteria);
+            sheet.tolerate(parsed, criteria.lenient)?;
             sheet.finish(criteria.end_at_empty_row);
             sheets.push(sheet);
         }
@@ -263,10 +404,11 @@ impl Spreadsheet for XlsbSpreadsheet {
 /// * `zip` - ZIP archive containing the XLSB file structure
 ///
 /// # Returns
-/// * `Result<(Vec<(String, String)>, bool)>` - Tuple containing:
+/// * `Result<(Vec<(String, String)>, bool, Relationships)>` - Tuple containing:
 ///   - Vector of worksheet names and their file paths
 ///   - Boolean indicating if 1904 date system is used
-fn load_workbook(zip: &mut ZipArchive<UnifiedReader>) -> Result<(Vec<(String, String)>, bool), RustySheetError> {
+///   - Workbook relationships locating the shared strings and styles
+fn load_workbook(zip: &mut ZipPackage<UnifiedReader>) -> Result<(Vec<(String, String)>, bool, Relationships), RustySheetError> {
     let relationships = load_relationships(zip, "xl/_rels/workbook.bin.rels")?;
     let mut reader = zip.biff_reader("xl/workbook.bin")?
         .ok_or_else(|| SpreadsheetError::FileError("xl/workbook.bin".to_string()))?;
@@ -276,7 +418,7 @@ fn load_workbook(zip: &mut ZipArchive<UnifiedReader>) -> Result<(Vec<(String, St
         BRT_END_BUNDLE_SHS => break,
         BRT_BUNDLE_SH => {
             let (id, index) = reader.get_str_and_bound(8)?;
-            if let Some(zip_path) = relationships.get(id.as_ref()) {
+            if let Some(zip_path) = relationships.worksheets.get(id.as_ref()) {
                 let sheet_name = reader.get_str(index)?;
                 sheets.push((sheet_name.to_string(), zip_path.to_owned()));
             }
@@ -285,7 +427,53 @@ fn load_workbook(zip: &mut ZipArchive<UnifiedReader>) -> Result<(Vec<(String, St
             is_1904 = (&reader.buffer[0] & 0x1) != 0;
         }
     });
-    Ok((sheets, is_1904))
+    Ok((sheets, is_1904, relationships))
+}
+
+/// Loads the visibility of the worksheets from the hsState field of their BrtBundleSh records
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSB file structure
+///
+/// # Returns
+/// * `Result<HashMap<String, Visibility>>` - Visibility by worksheet name
+fn load_sheet_states(zip: &mut ZipPackage<UnifiedReader>) -> Result<HashMap<String, Visibility>, RustySheetError> {
+    let mut reader = zip.biff_reader("xl/workbook.bin")?
+        .ok_or_else(|| SpreadsheetError::FileError("xl/workbook.bin".to_string()))?;
+    let mut states = HashMap::<String, Visibility>::new();
+    match_biff12_record!(reader => {
+        BRT_END_BUNDLE_SHS => break,
+        BRT_BUNDLE_SH => {
+            let visibility = Visibility::from_state(reader.get_u32(0));
+            let (_, index) = reader.get_str_and_bound(8)?;
+            states.insert(reader.get_str(index)?.to_string(), visibility);
+        }
+    });
+    Ok(states)
+}
+
+/// Loads the used range recorded by the BrtWsDim record of a worksheet
+///
+/// Only the records before the cells are parsed.
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSB file structure
+/// * `path` - Path of the worksheet part in the archive
+///
+/// # Returns
+/// * `Result<Option<String>>` - Used range (e.g. "A1:D10"), or None if the worksheet does not record it
+fn load_dimension(zip: &mut ZipPackage<UnifiedReader>, path: &str) -> Result<Option<String>, RustySheetError> {
+    let mut reader = zip.biff_reader(path)?
+        .ok_or_else(|| SpreadsheetError::FileError(path.to_owned()))?;
+    match_biff12_record!(reader => {
+        BRT_BEGIN_SHEET_DATA => break,
+        BRT_WS_DIM => {
+            // rwFirst, rwLast, colFirst and colLast, all inclusive
+            let dimension = bounds_to_range(reader.get_usize(0), reader.get_usize(8), reader.get_usize(4), reader.get_usize(12));
+            return Ok(Some(dimension));
+        }
+    });
+    Ok(None)
 }
 
 /// Loads number format definitions from the XLSB file
@@ -295,17 +483,18 @@ fn load_workbook(zip: &mut ZipArchive<UnifiedReader>) -> Result<(Vec<(String, St
 ///
 /// # Arguments
 /// * `zip` - ZIP archive containing the XLSB file structure
+/// * `path` - Styles part declared by the workbook relationships, if any
 /// * `is_1904` - Whether the workbook uses 1904 date system
 ///
 /// # Returns
-/// * `Result<Vec<CellType>>` - Vector of cell types for format indexes
-fn load_number_formats(zip: &mut ZipArchive<UnifiedReader>, is_1904: bool) -> Result<Vec<CellType>, RustySheetError> {
-    let mut reader = match zip.biff_reader("xl/styles.bin")? {
+/// * `Result<Vec<NumberFormat>>` - Vector of number formats for format indexes
+fn load_number_formats(zip: &mut ZipPackage<UnifiedReader>, path: Option<&str>, is_1904: bool) -> Result<Vec<NumberFormat>, RustySheetError> {
+    let mut reader = match zip.biff_reader(path.unwrap_or(STYLES_PART))? {
         Some(reader) => reader,
         None => return Ok(Vec::new()),
     };
 
-    let mut custom_formats: HashMap<String, CellType> = HashMap::new();
+    let mut custom_formats: HashMap<String, String> = HashMap::new();
     let mut format_indexes: Vec<String> = Vec::new();
     match_biff12_record!(reader => {
         BRT_BEGIN_FMTS => {
@@ -313,10 +502,7 @@ fn load_number_formats(zip: &mut ZipArchive<UnifiedReader>, is_1904: bool) -> Re
                 reader.find(BRT_FMT)?;
                 let id = reader.get_u16(0);
                 let format = reader.get_str(2)?;
-                custom_formats.insert(
-                    id.to_string(),
-                    CellType::parse_custom_number_format(format.as_ref(), is_1904),
-                );
+                custom_formats.insert(id.to_string(), format.to_string());
             }
         }
         BRT_BEGIN_CELL_XFS => {
@@ -341,7 +527,7 @@ fn load_number_formats(zip: &mut ZipArchive<UnifiedReader>, is_1904: bool) -> Re
 /// * `(Either<CellType, usize>, String)` - Tuple containing:
 ///   - Cell type (boolean) and format index
 ///   - String representation of boolean value ("1" or "0")
-fn read_bool_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
+fn read_bool_cell(reader: &mut Biff12Reader<BufReader<ZipPart<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
     let value = if reader.buffer[8] != 0 { "1" } else { "0" };
     (Either::Left(CellType::Boolean), value.to_owned())
 }
@@ -355,7 +541,7 @@ fn read_bool_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>)
 /// * `(Either<CellType, usize>, String)` - Tuple containing:
 ///   - Format index reference and cell type
 ///   - String representation of numeric value
-fn read_real_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
+fn read_real_cell(reader: &mut Biff12Reader<BufReader<ZipPart<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
     let index = reader.get_style(4);
     let value = reader.get_f64(8).to_string();
     (Either::Right(index), value)
@@ -370,7 +556,7 @@ fn read_real_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>)
 /// * `Result<(Either<CellType, usize>, String)>` - Tuple containing:
 ///   - Cell type (inline string) and format index
 ///   - String value extracted from cell
-fn read_st_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>) -> Result<(Either<CellType, usize>, String), RustySheetError> {
+fn read_st_cell(reader: &mut Biff12Reader<BufReader<ZipPart<UnifiedReader>>>) -> Result<(Either<CellType, usize>, String), RustySheetError> {
     let value = reader.get_str(8)?.to_string();
     Ok((Either::Left(CellType::InlineString), value))
 }
@@ -384,11 +570,58 @@ fn read_st_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>) ->
 /// * `Result<(Either<CellType, usize>, String)>` - Tuple containing:
 ///   - Cell type (inline string) and format index
 ///   - String value extracted from rich text cell
-fn read_rich_string_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>) -> Result<(Either<CellType, usize>, String), RustySheetError> {
+fn read_rich_string_cell(reader: &mut Biff12Reader<BufReader<ZipPart<UnifiedReader>>>) -> Result<(Either<CellType, usize>, String), RustySheetError> {
     let value = reader.get_str(8 + 1)?.to_string();
     Ok((Either::Left(CellType::InlineString), value))
 }
 
+/// Reads a BrtColInfo record holding the width, visibility and outline properties of a
+/// range of columns
+///
+/// # Arguments
+/// * `reader` - BIFF12 reader holding the BrtColInfo record
+///
+/// # Returns
+/// * `ColumnInfo` - Properties of the range of columns
+fn read_column_info<R: BufRead>(reader: &Biff12Reader<R>) -> ColumnInfo {
+    let flags = reader.get_u16(16);
+    ColumnInfo {
+        lower: reader.get_usize(0),
+        upper: reader.get_usize(4),
+        width: Some(reader.get_u32(8) as f64 / 256f64),
+        hidden: (flags & 0x0001) != 0,
+        outline_level: ((flags >> 8) & 0x07) as u8,
+        collapsed: (flags & 0x1000) != 0,
+    }
+}
+
+/// Reads the phonetic string of a rich string (RichStr) record
+///
+/// The string is followed by its formatting runs when fRichStr is set, then by
+/// the phonetic string and its runs when fExtStr is set.
+///
+/// # Arguments
+/// * `reader` - BIFF12 reader holding the rich string record
+/// * `size` - Size of the record data
+///
+/// # Returns
+/// * `Result<Option<String>>` - Phonetic string, or None if the record has none
+fn read_phonetic_string<R: BufRead>(reader: &Biff12Reader<R>, size: usize) -> Result<Option<String>, RustySheetError> {
+    let flags = reader.buffer[0];
+    if flags & 0x02 == 0 {
+        return Ok(None);
+    }
+    let (_, mut bound) = reader.get_str_and_bound(1)?;
+    if flags & 0x01 != 0 && bound + 4 <= size {
+        bound += 4 + reader.get_usize(bound) * 4;
+    }
+    if bound + 4 > size {
+        return Ok(None);
+    }
+    let phonetic = reader.get_str(bound)?;
+    Ok(Some(phonetic.to_string()).filter(|phonetic| !phonetic.is_empty()))
+}
+
 /// Reads a shared string reference cell value from BIFF12 data
 ///
 /// # Arguments
@@ -398,7 +631,7 @@ fn read_rich_string_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedRead
 /// * `(Either<CellType, usize>, String)` - Tuple containing:
 ///   - Cell type (shared string) and format index
 ///   - String representation of shared string index
-fn read_shared_string_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
+fn read_shared_string_cell(reader: &mut Biff12Reader<BufReader<ZipPart<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
     let value = reader.get_usize(8).to_string();
     (Either::Left(CellType::SharedString), value)
 }
@@ -412,11 +645,47 @@ fn read_shared_string_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedRe
 /// * `(Either<CellType, usize>, String)` - Tuple containing:
 ///   - Cell type (error) and format index
 ///   - String representation of error value
-fn read_error_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
+fn read_error_cell(reader: &mut Biff12Reader<BufReader<ZipPart<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
     let value = to_error_value(reader.buffer[8]).to_owned();
     (Either::Left(CellType::Error), value)
 }
 
+/// Reads a formula error cell value from BIFF12 data
+///
+/// The error value is followed by the parsed formula (`cce` and `rgce`),
+/// which is decoded into the error description when possible.
+///
+/// # Arguments
+/// * `reader` - BIFF12 reader positioned at formula error cell data
+///
+/// # Returns
+/// * `(Either<CellType, usize>, String)` - Tuple containing:
+///   - Cell type (error) and format index
+///   - String representation of error value with its formula
+fn read_formula_error_cell(reader: &mut Biff12Reader<BufReader<ZipPart<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
+    let value = to_error_value(reader.buffer[8]);
+    let formula = reader.buffer.get(11..15)
+        .map(|_| reader.get_usize(11))
+        .and_then(|length| reader.buffer.get(15..15 + length))
+        .and_then(|rgce| decode_formula(rgce, true));
+    (Either::Left(CellType::Error), with_formula(value, formula))
+}
+
+/// Decodes the parsed formula (`cce` and `rgce`) of a formula cell record
+///
+/// # Arguments
+/// * `reader` - BIFF12 reader holding the formula cell record
+/// * `at` - Position of the parsed formula, right after the value and its flags
+///
+/// # Returns
+/// * `Option<String>` - Formula text, or None if it cannot be decoded (e.g. shared formulas)
+fn read_cell_formula<R: BufRead>(reader: &Biff12Reader<R>, at: usize) -> Option<String> {
+    reader.buffer.get(at..at + 4)
+        .map(|_| reader.get_usize(at))
+        .and_then(|length| reader.buffer.get(at + 4..at + 4 + length))
+        .and_then(|rgce| decode_formula(rgce, true))
+}
+
 /// Reads an RK (compressed floating point) cell value from BIFF12 data
 ///
 /// RK format stores numbers in a compressed format that can represent
@@ -429,7 +698,7 @@ fn read_error_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>)
 /// * `(Either<CellType, usize>, String)` - Tuple containing:
 ///   - Format index reference and cell type
 ///   - String representation of decompressed numeric value
-fn read_rk_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
+fn read_rk_cell(reader: &mut Biff12Reader<BufReader<ZipPart<UnifiedReader>>>) -> (Either<CellType, usize>, String) {
     let index = reader.get_style(4);
     let is_percentage = (reader.buffer[8] & 0x01) != 0;
     let is_integer = (reader.buffer[8] & 0x02) != 0;
@@ -451,4 +720,60 @@ fn read_rk_cell(reader: &mut Biff12Reader<BufReader<ZipFile<UnifiedReader>>>) ->
     };
 
     (Either::Right(index), value)
- }
\ No newline at end of file
+ }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Appends an XLWideString (character count followed by UTF-16LE code units)
+    fn push_wide_string(data: &mut Vec<u8>, value: &str) {
+        let units = value.encode_utf16().collect::<Vec<_>>();
+        data.extend((units.len() as u32).to_le_bytes());
+        data.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+    }
+
+    #[test]
+    fn rich_string_phonetic() {
+        // BrtSSTItem with fRichStr and fExtStr: string, one formatting run, phonetic string, one phonetic run
+        let mut data = vec![0x03];
+        push_wide_string(&mut data, "東京");
+        data.extend(1u32.to_le_bytes());
+        data.extend([0u8; 4]);
+        push_wide_string(&mut data, "トウキョウ");
+        data.extend(1u32.to_le_bytes());
+        data.extend([0u8; 6]);
+        let mut record = vec![BRT_SST_ITEM as u8, data.len() as u8];
+        record.extend(&data);
+
+        let mut reader = Biff12Reader::new(Cursor::new(record));
+        let (_, size) = reader.read().unwrap();
+        assert_eq!(reader.get_str(1).unwrap(), "東京");
+        assert_eq!(read_phonetic_string(&reader, size).unwrap(), Some("トウキョウ".to_owned()));
+
+        // Without fExtStr the record ends after the string
+        reader.buffer[0] = 0x00;
+        assert_eq!(read_phonetic_string(&reader, size).unwrap(), None);
+    }
+
+    #[test]
+    fn column_info() {
+        // BrtColInfo: colFirst, colLast, coldx, ixfe, then flags with fHidden, iOutLevel 3 and fCollapsed
+        let mut data = [2u32, 16_383, 0x0A00, 0].iter().flat_map(|field| field.to_le_bytes()).collect::<Vec<_>>();
+        data.extend(0x1301u16.to_le_bytes());
+        let mut record = vec![BRT_COL_INFO as u8, data.len() as u8];
+        record.extend(&data);
+
+        let mut reader = Biff12Reader::new(Cursor::new(record));
+        assert_eq!(reader.read().unwrap().0, BRT_COL_INFO);
+        assert_eq!(read_column_info(&reader), ColumnInfo {
+            lower: 2,
+            upper: 16_383,
+            width: Some(10.0),
+            hidden: true,
+            outline_level: 3,
+            collapsed: true,
+        });
+    }
+}
@@ -1,19 +1,43 @@
 use crate::error::RustySheetError;
 use crate::helpers::reader::UnifiedReader;
+use crate::helpers::string::clear_buffer;
 use crate::helpers::xml::XmlAttributeHelper;
 use crate::helpers::xml::XmlNodeHelper;
 use crate::helpers::xml::XmlReader;
 use crate::helpers::xml::XmlTextContextHelper;
-use crate::helpers::zip::ZipHelper;
+use crate::helpers::zip::ZipPackage;
+use crate::helpers::zip::ZipPart;
 use crate::match_xml_events;
 use crate::spreadsheet::cell::Cell;
 use crate::spreadsheet::cell::CellType;
 use crate::spreadsheet::criteria::Criteria;
 use crate::spreadsheet::excel;
+use crate::spreadsheet::excel::load_document_properties;
+use crate::spreadsheet::excel::load_hyperlink_targets;
+use crate::spreadsheet::excel::load_part_relationships;
 use crate::spreadsheet::excel::load_relationships;
+use crate::spreadsheet::excel::Color;
+use crate::spreadsheet::excel::Relationships;
+use crate::spreadsheet::excel::Workbook;
+use crate::spreadsheet::format::is_formatted;
+use crate::spreadsheet::format::render_value;
+use crate::spreadsheet::format::NumberFormat;
+use crate::spreadsheet::formula::shift_formula;
+use crate::spreadsheet::metadata::WorkbookMetadata;
 use crate::spreadsheet::reference::index_to_reference;
 use crate::spreadsheet::reference::reference_to_index;
+use crate::spreadsheet::reference::split_sheet_range;
+use crate::spreadsheet::shared_strings::SharedStrings;
+use crate::spreadsheet::shared_strings::SharedStringsBuilder;
+use crate::spreadsheet::sheet::CellComment;
+use crate::spreadsheet::sheet::CellProtection;
+use crate::spreadsheet::sheet::ColumnInfo;
+use crate::spreadsheet::sheet::DefinedName;
+use crate::spreadsheet::sheet::Hyperlink;
+use crate::spreadsheet::sheet::ListObject;
 use crate::spreadsheet::sheet::Sheet;
+use crate::spreadsheet::sheet::SheetInfo;
+use crate::spreadsheet::sheet::Visibility;
 use crate::spreadsheet::Spreadsheet;
 use crate::spreadsheet::SpreadsheetError;
 use quick_xml::events::Event;
@@ -22,8 +46,8 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::io::BufReader;
-use zip::read::ZipFile;
-use zip::ZipArchive;
+use std::io::Cursor;
+use std::thread;
 
 // XML tag names for parsing Excel XLSX format
 const TAG_CUSTOM_FORMATS: QName = QName(b"numFmts"); // Custom number formats container
@@ -35,21 +59,51 @@ const TAG_PHONETIC_TEXT: QName = QName(b"rPh");       // Phonetic text for Asian
 const TAG_TEXT: QName = QName(b"t");                  // Text content within strings
 const TAG_WORKBOOK_PROPERTIES: &[u8] = b"workbookPr"; // Workbook properties
 const TAG_SHEET: &[u8] = b"sheet";             // Worksheet definition
+const TAG_DEFINED_NAME: &[u8] = b"definedName"; // Defined name and the formula it refers to
+const TAG_TAB_COLOR: QName = QName(b"tabColor");      // Color of the sheet tab
+const TAG_DIMENSION: QName = QName(b"dimension");     // Used range of the worksheet
+const TAG_COLUMNS: QName = QName(b"cols");            // Column properties container
+const TAG_COLUMN: QName = QName(b"col");              // Column properties of a column range
+const TAG_SHEET_DATA: QName = QName(b"sheetData");    // Cells of the worksheet
 const TAG_ROW: QName = QName(b"row");                 // Row in worksheet
 const TAG_CELL: QName = QName(b"c");                  // Cell in worksheet
 const TAG_INLINE_STRING: QName = QName(b"is");        // Inline string value
 const TAG_VALUE: QName = QName(b"v");                 // Cell value content
+const TAG_FORMULA: QName = QName(b"f");               // Formula of a cell
+const TAG_AUTHOR: QName = QName(b"author");           // Author of notes
+const TAG_COMMENT: QName = QName(b"comment");         // Note attached to a cell
+const TAG_THREADED_COMMENT: QName = QName(b"threadedComment"); // Threaded comment or reply
+const TAG_THREADED_TEXT: QName = QName(b"text");      // Text of a threaded comment
+const TAG_PERSON: QName = QName(b"person");           // Author of threaded comments
+const TAG_HYPERLINK: QName = QName(b"hyperlink");     // Hyperlink of a cell range
+const TAG_PROTECTION: QName = QName(b"protection");  // Protection attributes of a cell format
+const TAG_SHEET_PROTECTION: QName = QName(b"sheetProtection"); // Protection of the worksheet
+const TAG_TABLE: QName = QName(b"table");             // Excel Table (list object)
+const TAG_TABLE_COLUMN: QName = QName(b"tableColumn"); // Column of an Excel Table
+
+/// Usual part of the shared string table, for workbooks that do not declare it
+const SHARED_STRINGS_PART: &str = "xl/sharedStrings.xml";
+/// Usual part of the styles, for workbooks that do not declare it
+const STYLES_PART: &str = "xl/styles.xml";
 
 /// Represents an Excel XLSX spreadsheet file
 pub(crate) struct XlsxSpreadsheet {
     /// File name of the spreadsheet
     pub(crate) name: String,
     /// ZIP archive containing the XLSX file contents
-    zip: ZipArchive<UnifiedReader>,
+    zip: ZipPackage<UnifiedReader>,
     /// Parsed number formats for cell type detection
-    number_formats: Vec<CellType>,
+    number_formats: Vec<NumberFormat>,
     /// List of worksheets with (name, zip_path) pairs
     sheets: Vec<(String, String)>,
+    /// ZIP path of the shared string table
+    shared_strings_path: String,
+    /// Theme colors resolving theme color references
+    theme_colors: Vec<u32>,
+    /// Whether serial dates count from 1904-01-01
+    is_1904: bool,
+    /// Whether the package was read from a snapshot, so the file itself must not be reopened
+    snapshot: bool,
 }
 
 impl XlsxSpreadsheet {
@@ -57,18 +111,186 @@ impl XlsxSpreadsheet {
     ///
     /// # Arguments
     /// * `file_name` - Path to the XLSX file
+    /// * `salvage` - Whether to recover the entries of an archive with a damaged central directory
+    /// * `snapshot` - Whether to read a consistent copy of a local file instead of the file itself
+    /// * `password` - Password of an encrypted workbook
     ///
     /// # Returns
     /// Result containing the initialized XlsxSpreadsheet or an error
-    pub(crate) fn open(file_name: &str) -> Result<XlsxSpreadsheet, RustySheetError> {
-        let (zip, number_formats, sheets) = excel::open(file_name, load_workbook, load_number_formats)?;
+    pub(crate) fn open(file_name: &str, salvage: bool, snapshot: bool, password: Option<&str>) -> Result<XlsxSpreadsheet, RustySheetError> {
+        let Workbook { zip, number_formats, sheets, shared_strings, theme_colors, is_1904 } = excel::open(file_name, salvage, snapshot, password, load_workbook, load_number_formats)?;
         Ok(XlsxSpreadsheet {
             name: file_name.to_owned(),
             zip,
             number_formats,
             sheets,
+            shared_strings_path: shared_strings.unwrap_or_else(|| SHARED_STRINGS_PART.to_owned()),
+            theme_colors,
+            is_1904,
+            // A decrypted package only exists in memory
+            snapshot: snapshot || password.is_some(),
         })
     }
+
+    /// Parses the cells of a worksheet part into the sheet
+    ///
+    /// # Arguments
+    /// * `sheet` - Sheet receiving the cells
+    /// * `zip_path` - Path of the worksheet part in the archive
+    /// * `criteria` - Selection criteria for which data to extract
+    fn read_cells(&mut self, sheet: &mut Sheet, zip_path: &str, criteria: &Criteria) -> Result<(), RustySheetError> {
+        let mut last_row = sheet.chunk_row_lower;
+        let mut row_count = 0usize;
+        let mut col_count = 0usize;
+        let mut row = 0usize;
+        let mut col = 0usize;
+        let mut kind = CellType::default();
+        let mut format = None::<usize>;
+        let mut value = String::new();
+        let mut formula = None::<String>;
+        // Text and first cell of the shared formulas by index, filling the other cells of their ranges
+        let mut shared_formulas = HashMap::<String, (usize, usize, String)>::new();
+        let mut columns = Vec::<ColumnInfo>::new();
+        let mut reader = self.zip.xml_reader(zip_path)?
+            .ok_or_else(|| SpreadsheetError::FileError(zip_path.to_owned()))?;
+        match_xml_events!(reader => {
+            Event::Start(event) if event.name() == TAG_TAB_COLOR => {
+                let color = if let Some(argb) = event.get_attribute_value("rgb")? {
+                    u32::from_str_radix(&argb, 16).ok().map(Color::Rgb)
+                } else if let Some(index) = event.parse_attribute_value("indexed")? {
+                    Some(Color::Indexed(index))
+                } else {
+                    event.parse_attribute_value("theme")?.map(Color::Theme)
+                };
+                let tint = event.parse_attribute_value("tint")?.unwrap_or(0.0);
+                sheet.tab_color = color.and_then(|color| excel::to_hex_color(color, tint, &self.theme_colors));
+            }
+            Event::Start(event) if !criteria.include_hidden_columns && event.name() == TAG_COLUMN => {
+                let lower = event.parse_attribute_value::<usize>("min")?.unwrap_or(1);
+                let upper = event.parse_attribute_value::<usize>("max")?.unwrap_or(lower);
+                columns.push(ColumnInfo {
+                    lower: lower.saturating_sub(1),
+                    upper: upper.saturating_sub(1),
+                    width: event.parse_attribute_value("width")?,
+                    hidden: is_true(event.get_attribute_value("hidden")?),
+                    outline_level: event.parse_attribute_value("outlineLevel")?.unwrap_or(0),
+                    collapsed: is_true(event.get_attribute_value("collapsed")?),
+                });
+            }
+            Event::End(event) if event.name() == TAG_COLUMNS => sheet.hide_columns(&mut columns),
+            Event::Start(event) if event.name() == TAG_ROW => {
+                // Cells without a reference follow the row's own index, if any
+                if let Some(index) = event.parse_attribute_value::<usize>("r")? {
+                    row_count = index.saturating_sub(1);
+                }
+                col_count = 0;
+            }
+            Event::End(event) if event.name() == TAG_ROW => {
+                row_count += 1;
+                col_count = 0;
+            }
+            Event::Start(event) if event.name() == TAG_CELL => {
+                (row, col) = event.get_attribute_value("r")?
+                    .and_then(|reference| reference_to_index(&reference))
+                    .unwrap_or((row_count, col_count));
+                // Later cells without a reference continue right after this one
+                row_count = row;
+                col_count = col + 1;
+                formula = None;
+                if sheet.after_row_upper_bound(row) {
+                    break;
+                } else if sheet.contains(row, col) {
+                    kind = event.get_attribute_value("t")?.map(|t| {
+                        match t.as_ref() {
+                            "inlineStr" | "str" => CellType::InlineString,
+                            "s" => CellType::SharedString,
+                            "d" => CellType::IsoDateTime,
+                            "b" => CellType::Boolean,
+                            "e" => if criteria.error_as_null { CellType::Empty } else { CellType::Error },
+                            _ => CellType::Number,
+                        }
+                    }).unwrap_or(CellType::Number);
+                    format = None;
+                    if let Some(format_id) = event.get_attribute_value("s")? {
+                        if kind == CellType::Number && !format_id.is_empty() {
+                            let index = format_id.parse::<usize>()?;
+                            kind = self.number_formats[index].kind;
+                            format = Some(index);
+                        }
+                    }
+                } else {
+                    kind = CellType::default();
+                }
+            }
+            Event::Start(event) if criteria.formulas && event.name() == TAG_FORMULA => {
+                // Shared formulas are also recorded outside of the range, for the cells they fill
+                let is_shared = event.get_attribute_value("t")?.is_some_and(|t| t == "shared");
+                let index = event.get_attribute_value("si")?.filter(|_| is_shared).map(|index| index.to_string());
+                let text = read_string_value(&mut reader, TAG_FORMULA, true)?;
+                formula = match index {
+                    Some(index) if text.is_empty() => shared_formulas.get(&index).map(|(first_row, first_col, text)| {
+                        shift_formula(text, row as isize - *first_row as isize, col as isize - *first_col as isize)
+                    }),
+                    Some(index) => {
+                        shared_formulas.insert(index, (row, col, text.to_owned()));
+                        Some(text)
+                    }
+                    None => Some(text).filter(|text| !text.is_empty()),
+                };
+            }
+            Event::Start(event) if kind != CellType::Empty && event.name() == TAG_INLINE_STRING => {
+                value = read_string_value(&mut reader, TAG_INLINE_STRING, false)?;
+            }
+            Event::Start(event) if kind != CellType::Empty && event.name() == TAG_VALUE => {
+                value = read_string_value(&mut reader, TAG_VALUE, true)?;
+            }
+            Event::End(event) if formula.is_some() && sheet.contains(row, col) && event.name() == TAG_CELL => {
+                if let Some(last_row) = last_row {
+                    if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row) || (!sheet.is_empty() && last_row + 1 < row)) {
+                        break;
+                    }
+                }
+                last_row = Some(row);
+                sheet.push_formula(Cell {
+                    row,
+                    col,
+                    kind: CellType::InlineString,
+                    value: format!("={}", formula.take().unwrap_or_default()),
+                });
+                clear_buffer(&mut value);
+            }
+            Event::End(event) if kind != CellType::Empty && !criteria.nulls.contains(&value) && event.name() == TAG_CELL => {
+                if kind != CellType::Error {
+                    if let Some(last_row) = last_row {
+                        if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row) || (!sheet.is_empty() && last_row + 1 < row)) {
+                            break;
+                        }
+                    }
+                    last_row = Some(row);
+                    if criteria.display_values && !criteria.count_only && is_formatted(kind) {
+                        value = render_value(format.map(|index| &self.number_formats[index]), &value);
+                        kind = CellType::InlineString;
+                    }
+                    sheet.push(Cell {
+                        row,
+                        col,
+                        kind,
+                        value: value.to_owned(),
+                    });
+                    clear_buffer(&mut value);
+                } else {
+                    let reference = index_to_reference(row, col);
+                    Err(SpreadsheetError::CellValueError(
+                        sheet.file_name.to_owned(),
+                        sheet.name.to_owned(),
+                        reference,
+                        value.to_owned(),
+                    ))?
+                }
+            },
+        });
+        Ok(())
+    }
 }
 
 impl Spreadsheet for XlsxSpreadsheet {
@@ -87,36 +309,155 @@ impl Spreadsheet for XlsxSpreadsheet {
     ///
     /// # Returns
     /// Tuple of (shared_strings, mappings) where mappings maps original indexes to loaded positions
-    fn load_shared_strings(&mut self, mut indexes: Option<HashSet<usize>>) -> Result<(Vec<String>, HashMap<usize, usize>), RustySheetError> {
-        let mut shared_strings = Vec::<String>::new();
-        let mut mappings = HashMap::<usize, usize>::new();
-        let mut reader = match self.zip.xml_reader("xl/sharedStrings.xml")? {
-            Some(reader) => reader,
-            None => return Ok((shared_strings, mappings)),
+    fn load_shared_strings(&mut self, indexes: Option<HashSet<usize>>) -> Result<(Vec<String>, HashMap<usize, usize>), RustySheetError> {
+        load_shared_strings(&mut self.zip, &self.shared_strings_path, indexes)
+    }
+
+    /// Streams the items of the shared string table
+    fn stream_shared_strings(&mut self, strings: &mut SharedStringsBuilder) -> Result<(), RustySheetError> {
+        stream_shared_strings(self.zip.xml_reader(&self.shared_strings_path)?, strings)
+    }
+
+    /// Loads the phonetic runs (rPh) of every shared string item
+    fn load_phonetic_strings(&mut self) -> Result<Vec<Option<String>>, RustySheetError> {
+        let mut phonetic_strings = Vec::<Option<String>>::new();
+        let Some(mut reader) = self.zip.xml_reader(&self.shared_strings_path)? else {
+            return Ok(phonetic_strings);
         };
 
-        let mut id = 0usize;
         match_xml_events!(reader => {
             Event::Start(event) if event.name() == TAG_SHARED_STRING_ITEM => {
-                if let Some(keys) = &mut indexes {
-                    if keys.contains(&id) {
-                        keys.remove(&id);
-                        let string = read_string_value(&mut reader, TAG_SHARED_STRING_ITEM, false)?;
-                        let index = shared_strings.len();
-                        shared_strings.push(string);
-                        mappings.insert(id, index);
-                    }
-                    if keys.is_empty() {
-                        break;
+                let (_, phonetic) = read_string_and_phonetic_values(&mut reader, TAG_SHARED_STRING_ITEM, false)?;
+                phonetic_strings.push(Some(phonetic).filter(|phonetic| !phonetic.is_empty()));
+            }
+        });
+        Ok(phonetic_strings)
+    }
+
+    /// Reads the worksheets while the shared strings load on another thread
+    ///
+    /// The shared string table of a large workbook takes about as long to parse
+    /// as its worksheets, so the two overlap: the table is parsed from the copy a
+    /// previous pass left in memory, or else streamed from a second handle on the
+    /// file. Remote files, snapshots and salvaged archives are read sequentially.
+    fn read_sheets_and_shared_strings(&mut self, criteria: &Criteria, mut strings: SharedStringsBuilder) -> Result<(Vec<Sheet>, SharedStrings), RustySheetError> {
+        let cached = self.zip.take_cached(&self.shared_strings_path);
+        let package = if cached.is_some() || self.snapshot { None } else { excel::reopen(&self.name) };
+        if cached.is_none() && package.is_none() {
+            let sheets = self.read_sheets(criteria)?;
+            self.stream_shared_strings(&mut strings)?;
+            return Ok((sheets, strings.finish()?));
+        }
+
+        let path = self.shared_strings_path.to_owned();
+        thread::scope(|scope| {
+            let loader = scope.spawn(move || -> Result<SharedStrings, RustySheetError> {
+                match package {
+                    Some(mut package) => stream_shared_strings(package.xml_reader(&path)?, &mut strings)?,
+                    None => stream_shared_strings(
+                        cached.map(|data| XmlReader::new(BufReader::new(ZipPart::Cached(Cursor::new(data))))),
+                        &mut strings,
+                    )?,
+                }
+                strings.finish()
+            });
+            let sheets = self.read_sheets(criteria);
+            let shared_strings = loader.join().expect("Shared strings thread")?;
+            Ok((sheets?, shared_strings))
+        })
+    }
+
+    /// Lists the worksheets with the state declared in the workbook and the
+    /// dimension element that precedes the cells of each worksheet
+    fn list_sheets(&mut self) -> Result<Vec<SheetInfo>, RustySheetError> {
+        let states = load_sheet_states(&mut self.zip)?;
+        let mut sheets = Vec::<SheetInfo>::new();
+        for (index, (sheet_name, zip_path)) in self.sheets.iter().enumerate() {
+            sheets.push(SheetInfo {
+                name: sheet_name.to_owned(),
+                index,
+                visibility: states.get(sheet_name).copied().unwrap_or(Visibility::Visible),
+                dimension: load_dimension(&mut self.zip, zip_path)?,
+            });
+        }
+        Ok(sheets)
+    }
+
+    /// Lists the defined names of the workbook part
+    fn defined_names(&mut self) -> Result<Vec<DefinedName>, RustySheetError> {
+        load_defined_names(&mut self.zip)
+    }
+
+    /// Lists the notes and threaded comments of the worksheets, found through the
+    /// relationships of each worksheet part
+    fn comments(&mut self) -> Result<Vec<CellComment>, RustySheetError> {
+        let persons = load_persons(&mut self.zip)?;
+        let mut comments = Vec::<CellComment>::new();
+        for (sheet_name, zip_path) in self.sheets.to_owned() {
+            for (kind, path) in load_part_relationships(&mut self.zip, &zip_path)? {
+                match kind.as_str() {
+                    "comments" => load_notes(&mut self.zip, &path, &sheet_name, &mut comments)?,
+                    "threadedComment" => load_threaded_comments(&mut self.zip, &path, &sheet_name, &persons, &mut comments)?,
+                    _ => (),
+                }
+            }
+        }
+        Ok(comments)
+    }
+
+    /// Lists the hyperlinks of the worksheets; links without a display text of their
+    /// own show the text of their first cell, read in a second pass over the worksheet
+    fn hyperlinks(&mut self) -> Result<Vec<Hyperlink>, RustySheetError> {
+        let mut hyperlinks = Vec::<Hyperlink>::new();
+        for (sheet_name, zip_path) in self.sheets.to_owned() {
+            let lower = hyperlinks.len();
+            load_hyperlinks(&mut self.zip, &zip_path, &sheet_name, &mut hyperlinks)?;
+            let first_cell = |hyperlink: &Hyperlink| hyperlink.reference.split(':').next().and_then(reference_to_index);
+            let cells = hyperlinks[lower..].iter()
+                .filter(|hyperlink| hyperlink.display_text.is_none())
+                .filter_map(first_cell)
+                .collect::<HashSet<_>>();
+            if !cells.is_empty() {
+                let texts = load_cell_texts(&mut self.zip, &zip_path, &self.shared_strings_path, &cells)?;
+                for hyperlink in &mut hyperlinks[lower..] {
+                    if hyperlink.display_text.is_none() {
+                        hyperlink.display_text = first_cell(hyperlink).and_then(|cell| texts.get(&cell).cloned());
                     }
-                } else {
-                    let string = read_string_value(&mut reader, TAG_SHARED_STRING_ITEM, false)?;
-                    shared_strings.push(string);
                 }
-                id += 1;
             }
-        });
-        Ok((shared_strings, mappings))
+        }
+        Ok(hyperlinks)
+    }
+
+    /// Lists the protection attributes of the cells of the worksheets, resolving the cell
+    /// formats of the styles part; cells without a format use the first one
+    fn cell_protection(&mut self) -> Result<Vec<CellProtection>, RustySheetError> {
+        let styles = load_relationships(&mut self.zip, "xl/_rels/workbook.xml.rels")?.styles;
+        let formats = load_protection_formats(&mut self.zip, styles.as_deref())?;
+        let mut protections = Vec::<CellProtection>::new();
+        for (sheet_name, zip_path) in self.sheets.to_owned() {
+            load_cell_protection(&mut self.zip, &zip_path, &sheet_name, &formats, &mut protections)?;
+        }
+        Ok(protections)
+    }
+
+    /// Lists the Excel Tables of the worksheets, found through the relationships of
+    /// each worksheet part
+    fn list_objects(&mut self) -> Result<Vec<ListObject>, RustySheetError> {
+        let mut list_objects = Vec::<ListObject>::new();
+        for (sheet_name, zip_path) in self.sheets.to_owned() {
+            for (kind, path) in load_part_relationships(&mut self.zip, &zip_path)? {
+                if kind == "table" {
+                    list_objects.extend(load_list_object(&mut self.zip, &path, &sheet_name)?);
+                }
+            }
+        }
+        Ok(list_objects)
+    }
+
+    /// Reads the document properties from the package parts shared by the Office Open XML formats
+    fn metadata(&mut self) -> Result<WorkbookMetadata, RustySheetError> {
+        load_document_properties(&mut self.zip, self.sheets.len(), self.is_1904)
     }
 
     /// Reads worksheets from the XLSX file according to the specified criteria
@@ -132,7 +473,7 @@ impl Spreadsheet for XlsxSpreadsheet {
     fn read_sheets(&mut self, criteria: &Criteria) -> Result<Vec<Sheet>, RustySheetError> {
         let mut sheets = Vec::<Sheet>::new();
         let mut sheet_count = 0usize;
-        for (sheet_name, zip_path) in &self.sheets {
+        for (index, (sheet_name, zip_path)) in self.sheets.to_owned().iter().enumerate() {
             if criteria.sheet_limit.map(|limit| sheet_count >= limit).unwrap_or(false) {
                 break;
             } else if criteria.accept(sheet_name) {
@@ -141,80 +482,17 @@ impl Spreadsheet for XlsxSpreadsheet {
                 continue;
             }
 
-            let mut sheet = Sheet::new(&self.name, sheet_name, criteria.range, criteria.rows_limit, criteria.skip_empty_rows);
-            let mut last_row = sheet.chunk_row_lower;
-            let mut row_count = 0usize;
-            let mut col_count = 0usize;
-            let mut row = 0usize;
-            let mut col = 0usize;
-            let mut kind = CellType::default();
-            let mut value = String::new();
-            let mut reader = self.zip.xml_reader(zip_path)?.expect(sheet_name);
-            match_xml_events!(reader => {
-                Event::End(event) if event.name() == TAG_ROW => {
-                    row_count += 1;
-                    col_count = 0;
-                }
-                Event::Start(event) if event.name() == TAG_CELL => {
-                    (row, col) = event.get_attribute_value("r")?
-                        .and_then(|reference| reference_to_index(&reference))
-                        .unwrap_or((row_count, col_count));
-                    col_count += 1;
-                    if sheet.after_row_upper_bound(row) {
-                        break;
-                    } else if sheet.contains(row, col) {
-                        kind = event.get_attribute_value("t")?.map(|t| {
-                            match t.as_ref() {
-                                "inlineStr" | "str" => CellType::InlineString,
-                                "s" => CellType::SharedString,
-                                "d" => CellType::IsoDateTime,
-                                "b" => CellType::Boolean,
-                                "e" => if criteria.error_as_null { CellType::Empty } else { CellType::Error },
-                                _ => CellType::Number,
-                            }
-                        }).unwrap_or(CellType::Number);
-                        if let Some(format_id) = event.get_attribute_value("s")? {
-                            if kind == CellType::Number && !format_id.is_empty() {
-                                let index = format_id.parse::<usize>()?;
-                                kind = self.number_formats[index];
-                            }
-                        }
-                    } else {
-                        kind = CellType::default();
-                    }
-                }
-                Event::Start(event) if kind != CellType::Empty && event.name() == TAG_INLINE_STRING => {
-                    value = read_string_value(&mut reader, TAG_INLINE_STRING, false)?;
-                }
-                Event::Start(event) if kind != CellType::Empty && event.name() == TAG_VALUE => {
-                    value = read_string_value(&mut reader, TAG_VALUE, true)?;
-                }
-                Event::End(event) if kind != CellType::Empty && !criteria.nulls.contains(&value) && event.name() == TAG_CELL => {
-                    if kind != CellType::Error {
-                        if let Some(last_row) = last_row {
-                            if criteria.end_at_empty_row && ((sheet.is_empty() && last_row != row) || (!sheet.is_empty() && last_row + 1 < row)) {
-                                break;
-                            }
-                        }
-                        last_row = Some(row);
-                        sheet.push(Cell {
-                            row,
-                            col,
-                            kind,
-                            value: value.to_owned(),
-                        });
-                        value.clear();
-                    } else {
-                        let reference = index_to_reference(row, col);
-                        Err(SpreadsheetError::CellValueError(
-                            sheet.file_name.to_owned(),
-                            sheet.name.to_owned(),
-                            reference,
-                            value.to_owned(),
-                        ))?
-                    }
-                },
-            });
+            let mut sheet = Sheet::new(&self.name, sheet_name, criteria.range, criteria.rows_limit, criteria.sample_rows, criteria.skip_empty_rows, criteria.count_only);
+            sheet.index = index;
+            sheet.max_cell_length = criteria.max_cell_length.to_owned();
+            sheet.fix_encoding = criteria.fix_encoding;
+            sheet.blocks = criteria.ranges.to_owned();
+            sheet.stop_when_stable(criteria);
+            if criteria.checksum {
+                sheet.hash_only();
+            }
+            let parsed = self.read_cells(&mut sheet, zip_path, criteria);
+            sheet.tolerate(parsed, criteria.lenient)?;
             sheet.finish(criteria.end_at_empty_row);
             sheets.push(sheet);
         }
@@ -232,8 +510,8 @@ impl Spreadsheet for XlsxSpreadsheet {
 /// * `zip` - ZIP archive containing the XLSX file
 ///
 /// # Returns
-/// Tuple of (worksheets, is_1904_date_system) where worksheets are (name, zip_path) pairs
-fn load_workbook(zip: &mut ZipArchive<UnifiedReader>) -> Result<(Vec<(String, String)>, bool), RustySheetError> {
+/// Tuple of (worksheets, is_1904_date_system, relationships) where worksheets are (name, zip_path) pairs
+fn load_workbook(zip: &mut ZipPackage<UnifiedReader>) -> Result<(Vec<(String, String)>, bool, Relationships), RustySheetError> {
     let relationships = load_relationships(zip, "xl/_rels/workbook.xml.rels")?;
     let mut reader = zip.xml_reader("xl/workbook.xml")?
         .ok_or_else(|| SpreadsheetError::FileError("xl/workbook.xml".to_string()))?;
@@ -253,7 +531,7 @@ fn load_workbook(zip: &mut ZipArchive<UnifiedReader>) -> Result<(Vec<(String, St
                 }
             }
             if let Some((name, id)) = name.zip(id) {
-                if let Some(path) = relationships.get(&id.to_string()) {
+                if let Some(path) = relationships.worksheets.get(&id.to_string()) {
                     sheets.push((name.to_string(), path.to_owned()));
                 }
             }
@@ -264,7 +542,435 @@ fn load_workbook(zip: &mut ZipArchive<UnifiedReader>) -> Result<(Vec<(String, St
                 .unwrap_or(false);
         }
     });
-    Ok((sheets, is_1904))
+    Ok((sheets, is_1904, relationships))
+}
+
+/// Loads the visibility of the worksheets from the state attribute of their sheet elements
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSX file
+///
+/// # Returns
+/// Visibility by worksheet name; visible worksheets may be missing
+fn load_sheet_states(zip: &mut ZipPackage<UnifiedReader>) -> Result<HashMap<String, Visibility>, RustySheetError> {
+    let mut reader = zip.xml_reader("xl/workbook.xml")?
+        .ok_or_else(|| SpreadsheetError::FileError("xl/workbook.xml".to_string()))?;
+    let mut states = HashMap::<String, Visibility>::new();
+    match_xml_events!(reader => {
+        Event::Start(event) if event.local_name().as_ref() == TAG_SHEET => {
+            let visibility = match event.get_attribute_value("state")?.as_deref() {
+                Some("hidden") => Visibility::Hidden,
+                Some("veryHidden") => Visibility::VeryHidden,
+                _ => Visibility::Visible,
+            };
+            if let Some(name) = event.get_attribute_value("name")? {
+                states.insert(name.to_string(), visibility);
+            }
+        }
+    });
+    Ok(states)
+}
+
+/// Loads the defined names of the workbook, resolving the worksheet of local names
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSX file
+///
+/// # Returns
+/// Defined names in the order of the workbook part
+fn load_defined_names(zip: &mut ZipPackage<UnifiedReader>) -> Result<Vec<DefinedName>, RustySheetError> {
+    let mut reader = zip.xml_reader("xl/workbook.xml")?
+        .ok_or_else(|| SpreadsheetError::FileError("xl/workbook.xml".to_string()))?;
+    // localSheetId indexes the sheets in workbook order, chart sheets included
+    let mut sheet_names = Vec::<String>::new();
+    let mut names = Vec::<DefinedName>::new();
+    let mut name = None::<DefinedName>;
+    match_xml_events!(reader => {
+        Event::Start(event) if event.local_name().as_ref() == TAG_SHEET => {
+            sheet_names.push(event.get_attribute_value("name")?.unwrap_or_default().to_string());
+        }
+        Event::Start(event) if event.local_name().as_ref() == TAG_DEFINED_NAME => {
+            name = Some(DefinedName {
+                name: event.get_attribute_value("name")?.unwrap_or_default().to_string(),
+                scope: event.parse_attribute_value::<usize>("localSheetId")?
+                    .and_then(|index| sheet_names.get(index).cloned()),
+                refers_to: String::new(),
+                target: None,
+                hidden: is_true(event.get_attribute_value("hidden")?),
+            });
+        }
+        Event::Text(event) => if let Some(name) = &mut name {
+            name.refers_to.push_bytes_text(&event)?;
+        },
+        Event::GeneralRef(event) => if let Some(name) = &mut name {
+            name.refers_to.push_bytes_ref(&event)?;
+        },
+        Event::End(event) if event.local_name().as_ref() == TAG_DEFINED_NAME => {
+            if let Some(mut name) = name.take() {
+                name.target = split_sheet_range(&name.refers_to);
+                names.push(name);
+            }
+        }
+    });
+    Ok(names)
+}
+
+/// Loads the display names of the authors of threaded comments by person id
+fn load_persons(zip: &mut ZipPackage<UnifiedReader>) -> Result<HashMap<String, String>, RustySheetError> {
+    let mut persons = HashMap::<String, String>::new();
+    for (kind, path) in load_part_relationships(zip, "xl/workbook.xml")? {
+        if kind != "person" {
+            continue;
+        }
+        let Some(mut reader) = zip.xml_reader(&path)? else {
+            continue;
+        };
+        match_xml_events!(reader => {
+            Event::Start(event) if event.name() == TAG_PERSON => {
+                if let (Some(id), Some(name)) = (event.get_attribute_value("id")?, event.get_attribute_value("displayName")?) {
+                    persons.insert(id.to_string(), name.to_string());
+                }
+            }
+        });
+    }
+    Ok(persons)
+}
+
+/// Loads the notes of a worksheet from its comments part
+///
+/// Excel also writes each threaded comment as a note, for versions without threaded
+/// comments; their author is the id of the thread (`tc={...}`) and they are skipped,
+/// the thread being read from the threaded comments part.
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSX file
+/// * `path` - Path of the comments part in the archive
+/// * `sheet_name` - Name of the worksheet
+/// * `comments` - Comments receiving the notes
+fn load_notes(zip: &mut ZipPackage<UnifiedReader>, path: &str, sheet_name: &str, comments: &mut Vec<CellComment>) -> Result<(), RustySheetError> {
+    let Some(mut reader) = zip.xml_reader(path)? else {
+        return Ok(());
+    };
+    let mut authors = Vec::<String>::new();
+    match_xml_events!(reader => {
+        Event::Start(event) if event.name() == TAG_AUTHOR => {
+            authors.push(read_string_value(&mut reader, TAG_AUTHOR, true)?);
+        }
+        Event::Start(event) if event.name() == TAG_COMMENT => {
+            let reference = event.get_attribute_value("ref")?.unwrap_or_default().to_string();
+            let author = event.parse_attribute_value::<usize>("authorId")?
+                .and_then(|index| authors.get(index).cloned());
+            let text = read_string_value(&mut reader, TAG_COMMENT, false)?;
+            if !author.as_ref().is_some_and(|author| author.starts_with("tc=")) {
+                comments.push(CellComment {
+                    sheet_name: sheet_name.to_owned(),
+                    reference,
+                    author,
+                    text,
+                });
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Loads the threaded comments of a worksheet, replies following the comment they answer
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSX file
+/// * `path` - Path of the threaded comments part in the archive
+/// * `sheet_name` - Name of the worksheet
+/// * `persons` - Display names of the authors by person id
+/// * `comments` - Comments receiving the threaded comments
+fn load_threaded_comments(
+    zip: &mut ZipPackage<UnifiedReader>,
+    path: &str,
+    sheet_name: &str,
+    persons: &HashMap<String, String>,
+    comments: &mut Vec<CellComment>,
+) -> Result<(), RustySheetError> {
+    let Some(mut reader) = zip.xml_reader(path)? else {
+        return Ok(());
+    };
+    let mut comment = None::<CellComment>;
+    let mut is_text = false;
+    match_xml_events!(reader => {
+        Event::Start(event) if event.name() == TAG_THREADED_COMMENT => {
+            comment = Some(CellComment {
+                sheet_name: sheet_name.to_owned(),
+                reference: event.get_attribute_value("ref")?.unwrap_or_default().to_string(),
+                author: event.get_attribute_value("personId")?.and_then(|id| persons.get(id.as_ref()).cloned()),
+                text: String::new(),
+            });
+        }
+        Event::Start(event) if comment.is_some() && event.name() == TAG_THREADED_TEXT => is_text = true,
+        Event::End(event) if event.name() == TAG_THREADED_TEXT => is_text = false,
+        Event::Text(event) if is_text => if let Some(comment) = &mut comment {
+            comment.text.push_bytes_text(&event)?;
+        },
+        Event::GeneralRef(event) if is_text => if let Some(comment) = &mut comment {
+            comment.text.push_bytes_ref(&event)?;
+        },
+        Event::End(event) if event.name() == TAG_THREADED_COMMENT => comments.extend(comment.take()),
+    });
+    Ok(())
+}
+
+/// Loads the hyperlinks of a worksheet, resolving external targets through the
+/// relationships of the worksheet part
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSX file
+/// * `path` - Path of the worksheet part in the archive
+/// * `sheet_name` - Name of the worksheet
+/// * `hyperlinks` - Hyperlinks receiving the links of the worksheet
+fn load_hyperlinks(zip: &mut ZipPackage<UnifiedReader>, path: &str, sheet_name: &str, hyperlinks: &mut Vec<Hyperlink>) -> Result<(), RustySheetError> {
+    let targets = load_hyperlink_targets(zip, path)?;
+    let Some(mut reader) = zip.xml_reader(path)? else {
+        return Ok(());
+    };
+    match_xml_events!(reader => {
+        Event::Start(event) if event.name() == TAG_HYPERLINK => {
+            let target = event.get_attribute_value("r:id")?.and_then(|id| targets.get(id.as_ref()).cloned());
+            // Locations within the workbook, or fragments of external targets
+            let location = event.get_attribute_value("location")?;
+            let target_url = match (target, location) {
+                (Some(target), Some(location)) => format!("{target}#{location}"),
+                (Some(target), None) => target,
+                (None, Some(location)) => format!("#{location}"),
+                (None, None) => continue,
+            };
+            hyperlinks.push(Hyperlink {
+                sheet_name: sheet_name.to_owned(),
+                reference: event.get_attribute_value("ref")?.unwrap_or_default().to_string(),
+                display_text: event.get_attribute_value("display")?.map(|display| display.to_string()),
+                target_url,
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Loads the protection attributes of the cells of a worksheet
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSX file
+/// * `path` - Path of the worksheet part in the archive
+/// * `sheet_name` - Name of the worksheet
+/// * `formats` - Locked and hidden flags by cell format index
+/// * `protections` - Protection attributes receiving the cells of the worksheet
+fn load_cell_protection(
+    zip: &mut ZipPackage<UnifiedReader>,
+    path: &str,
+    sheet_name: &str,
+    formats: &[(bool, bool)],
+    protections: &mut Vec<CellProtection>,
+) -> Result<(), RustySheetError> {
+    let Some(mut reader) = zip.xml_reader(path)? else {
+        return Ok(());
+    };
+    let lower = protections.len();
+    let mut row_count = 0usize;
+    let mut col_count = 0usize;
+    match_xml_events!(reader => {
+        Event::Start(event) if event.name() == TAG_ROW => {
+            if let Some(index) = event.parse_attribute_value::<usize>("r")? {
+                row_count = index.saturating_sub(1);
+            }
+            col_count = 0;
+        }
+        Event::End(event) if event.name() == TAG_ROW => {
+            row_count += 1;
+            col_count = 0;
+        }
+        Event::Start(event) if event.name() == TAG_CELL => {
+            let (row, col) = event.get_attribute_value("r")?
+                .and_then(|reference| reference_to_index(&reference))
+                .unwrap_or((row_count, col_count));
+            row_count = row;
+            col_count = col + 1;
+            let index = event.parse_attribute_value::<usize>("s")?.unwrap_or(0);
+            // Without a format, cells are locked and show their formulas
+            let (locked, formula_hidden) = formats.get(index).copied().unwrap_or((true, false));
+            protections.push(CellProtection {
+                sheet_name: sheet_name.to_owned(),
+                reference: index_to_reference(row, col),
+                locked,
+                formula_hidden,
+                sheet_protected: false,
+            });
+        }
+        // The protection of the worksheet follows its cells
+        Event::Start(event) if event.name() == TAG_SHEET_PROTECTION => {
+            if is_true(event.get_attribute_value("sheet")?) {
+                for protection in &mut protections[lower..] {
+                    protection.sheet_protected = true;
+                }
+            }
+            break;
+        }
+    });
+    Ok(())
+}
+
+/// Loads the protection attributes of the cell formats of the styles part
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSX file
+/// * `path` - Styles part declared by the workbook relationships, if any
+///
+/// # Returns
+/// Locked and hidden flags indexed by cell format, as referenced by the cells
+fn load_protection_formats(zip: &mut ZipPackage<UnifiedReader>, path: Option<&str>) -> Result<Vec<(bool, bool)>, RustySheetError> {
+    let Some(mut reader) = zip.xml_reader(path.unwrap_or(STYLES_PART))? else {
+        return Ok(Vec::new());
+    };
+    let mut formats = Vec::<(bool, bool)>::new();
+    let mut format_indexes_context = false;
+    match_xml_events!(reader => {
+        Event::Start(event) if event.name() == TAG_FORMAT_INDEXES => format_indexes_context = true,
+        Event::End(event) if event.name() == TAG_FORMAT_INDEXES => break,
+        Event::Start(event) if format_indexes_context && event.name() == TAG_FORMAT_INDEX => formats.push((true, false)),
+        Event::Start(event) if format_indexes_context && event.name() == TAG_PROTECTION => if let Some(format) = formats.last_mut() {
+            *format = (
+                event.get_attribute_value("locked")?.map_or(true, |locked| is_true(Some(locked))),
+                is_true(event.get_attribute_value("hidden")?),
+            );
+        },
+    });
+    Ok(formats)
+}
+
+/// Loads the definition of an Excel Table from its table part
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSX file
+/// * `path` - Path of the table part in the archive
+/// * `sheet_name` - Name of the worksheet holding the table
+///
+/// # Returns
+/// The table, or None when the part is missing or has no table element
+fn load_list_object(zip: &mut ZipPackage<UnifiedReader>, path: &str, sheet_name: &str) -> Result<Option<ListObject>, RustySheetError> {
+    let Some(mut reader) = zip.xml_reader(path)? else {
+        return Ok(None);
+    };
+    let mut list_object = None::<ListObject>;
+    match_xml_events!(reader => {
+        Event::Start(event) if event.name() == TAG_TABLE => {
+            // Formulas refer to tables by their display name, which Excel keeps equal to the name
+            let name = match event.get_attribute_value("displayName")? {
+                Some(name) => Some(name),
+                None => event.get_attribute_value("name")?,
+            };
+            list_object = Some(ListObject {
+                name: name.unwrap_or_default().to_string(),
+                sheet_name: sheet_name.to_owned(),
+                reference: event.get_attribute_value("ref")?.unwrap_or_default().to_string(),
+                has_header: event.parse_attribute_value::<usize>("headerRowCount")?.unwrap_or(1) > 0,
+                totals_rows: event.parse_attribute_value::<usize>("totalsRowCount")?.unwrap_or(0),
+                column_names: Vec::new(),
+            });
+        }
+        Event::Start(event) if event.name() == TAG_TABLE_COLUMN => if let Some(list_object) = &mut list_object {
+            list_object.column_names.push(event.get_attribute_value("name")?.unwrap_or_default().to_string());
+        },
+    });
+    Ok(list_object)
+}
+
+/// Loads the text of the given cells of a worksheet, resolving shared strings
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSX file
+/// * `path` - Path of the worksheet part in the archive
+/// * `shared_strings_path` - Path of the shared string table in the archive
+/// * `cells` - Positions (row, column) of the cells to read
+///
+/// # Returns
+/// Values of the cells as stored, by position; empty cells are left out
+fn load_cell_texts(
+    zip: &mut ZipPackage<UnifiedReader>,
+    path: &str,
+    shared_strings_path: &str,
+    cells: &HashSet<(usize, usize)>,
+) -> Result<HashMap<(usize, usize), String>, RustySheetError> {
+    let mut texts = HashMap::<(usize, usize), String>::new();
+    let Some(mut reader) = zip.xml_reader(path)? else {
+        return Ok(texts);
+    };
+    let mut indexes = HashMap::<(usize, usize), usize>::new();
+    let mut row_count = 0usize;
+    let mut col_count = 0usize;
+    let mut cell = None::<(usize, usize)>;
+    let mut is_shared = false;
+    match_xml_events!(reader => {
+        Event::End(event) if event.name() == TAG_SHEET_DATA => break,
+        Event::Start(event) if event.name() == TAG_ROW => {
+            if let Some(index) = event.parse_attribute_value::<usize>("r")? {
+                row_count = index.saturating_sub(1);
+            }
+            col_count = 0;
+        }
+        Event::End(event) if event.name() == TAG_ROW => {
+            row_count += 1;
+            col_count = 0;
+        }
+        Event::Start(event) if event.name() == TAG_CELL => {
+            let (row, col) = event.get_attribute_value("r")?
+                .and_then(|reference| reference_to_index(&reference))
+                .unwrap_or((row_count, col_count));
+            row_count = row;
+            col_count = col + 1;
+            cell = Some((row, col)).filter(|cell| cells.contains(cell));
+            is_shared = event.get_attribute_value("t")?.is_some_and(|t| t == "s");
+        }
+        Event::Start(event) if cell.is_some() && event.name() == TAG_INLINE_STRING => {
+            let value = read_string_value(&mut reader, TAG_INLINE_STRING, false)?;
+            texts.extend(cell.take().map(|cell| (cell, value)));
+        }
+        Event::Start(event) if cell.is_some() && event.name() == TAG_VALUE => {
+            let value = read_string_value(&mut reader, TAG_VALUE, true)?;
+            if let Some(cell) = cell.take() {
+                if is_shared {
+                    indexes.insert(cell, value.parse::<usize>()?);
+                } else {
+                    texts.insert(cell, value);
+                }
+            }
+        }
+    });
+    drop(reader);
+    if !indexes.is_empty() {
+        let (strings, mappings) = load_shared_strings(zip, shared_strings_path, Some(indexes.values().copied().collect()))?;
+        for (cell, index) in indexes {
+            if let Some(&position) = mappings.get(&index) {
+                texts.insert(cell, strings[position].to_owned());
+            }
+        }
+    }
+    Ok(texts)
+}
+
+/// Loads the used range recorded by the dimension element of a worksheet
+///
+/// Only the elements before the cells are parsed.
+///
+/// # Arguments
+/// * `zip` - ZIP archive containing the XLSX file
+/// * `path` - Path of the worksheet part in the archive
+///
+/// # Returns
+/// Reference of the used range (e.g. "A1:D10"), or None if the worksheet does not record it
+fn load_dimension(zip: &mut ZipPackage<UnifiedReader>, path: &str) -> Result<Option<String>, RustySheetError> {
+    let mut reader = zip.xml_reader(path)?
+        .ok_or_else(|| SpreadsheetError::FileError(path.to_owned()))?;
+    match_xml_events!(reader => {
+        Event::Start(event) if event.name() == TAG_DIMENSION => {
+            return Ok(event.get_attribute_value("ref")?.map(|reference| reference.to_string()));
+        }
+        Event::Start(event) if event.name() == TAG_SHEET_DATA => break,
+    });
+    Ok(None)
 }
 
 /// Loads number formats and cell styles from XLSX styles.xml file
@@ -274,19 +980,20 @@ fn load_workbook(zip: &mut ZipArchive<UnifiedReader>) -> Result<(Vec<(String, St
 ///
 /// # Arguments
 /// * `zip` - ZIP archive containing the XLSX file
+/// * `path` - Styles part declared by the workbook relationships, if any
 /// * `is_1904` - Whether the file uses the 1904 date system
 ///
 /// # Returns
 /// Vector of CellType values indexed by style ID
-fn load_number_formats(zip: &mut ZipArchive<UnifiedReader>, is_1904: bool) -> Result<Vec<CellType>, RustySheetError> {
-    let mut reader = match zip.xml_reader("xl/styles.xml")? {
+fn load_number_formats(zip: &mut ZipPackage<UnifiedReader>, path: Option<&str>, is_1904: bool) -> Result<Vec<NumberFormat>, RustySheetError> {
+    let mut reader = match zip.xml_reader(path.unwrap_or(STYLES_PART))? {
         Some(reader) => reader,
         None => return Ok(Vec::new()),
     };
 
     let mut has_custom_formats = false;
     let mut custom_formats_context = false;
-    let mut custom_formats = HashMap::<String, CellType>::new();
+    let mut custom_formats = HashMap::<String, String>::new();
 
     let mut has_format_indexes = false;
     let mut format_indexes_context = false;
@@ -307,8 +1014,7 @@ fn load_number_formats(zip: &mut ZipArchive<UnifiedReader>, is_1904: bool) -> Re
             let id = event.get_attribute_value("numFmtId")?;
             let format = event.get_attribute_value("formatCode")?;
             if let Some((id, format)) = id.zip(format) {
-                let style = CellType::parse_custom_number_format(&format, is_1904);
-                custom_formats.insert(id.to_string(), style);
+                custom_formats.insert(id.to_string(), format.to_string());
             }
         }
 
@@ -334,6 +1040,72 @@ fn load_number_formats(zip: &mut ZipArchive<UnifiedReader>, is_1904: bool) -> Re
 
 /// Reads string value from XML content, handling text and CDATA sections
 ///
+/// Loads shared strings from the shared string table of an XLSX package
+///
+/// # Arguments
+/// * `zip` - Package holding the shared string table
+/// * `path` - ZIP path of the shared string table
+/// * `indexes` - Optional set of specific string indexes to load, or None to load all
+///
+/// # Returns
+/// Tuple of (shared_strings, mappings) where mappings maps original indexes to loaded positions
+fn load_shared_strings(zip: &mut ZipPackage<UnifiedReader>, path: &str, indexes: Option<HashSet<usize>>) -> Result<(Vec<String>, HashMap<usize, usize>), RustySheetError> {
+    read_shared_strings(zip.xml_reader(path)?, indexes)
+}
+
+/// Parses the items of a shared string table, or returns empty results when the package has none
+fn read_shared_strings(
+    reader: Option<XmlReader<BufReader<ZipPart<'_, UnifiedReader>>>>,
+    mut indexes: Option<HashSet<usize>>,
+) -> Result<(Vec<String>, HashMap<usize, usize>), RustySheetError> {
+    let mut shared_strings = Vec::<String>::new();
+    let mut mappings = HashMap::<usize, usize>::new();
+    let Some(mut reader) = reader else {
+        return Ok((shared_strings, mappings));
+    };
+
+    let mut id = 0usize;
+    match_xml_events!(reader => {
+        Event::Start(event) if event.name() == TAG_SHARED_STRING_ITEM => {
+            if let Some(keys) = &mut indexes {
+                if keys.contains(&id) {
+                    keys.remove(&id);
+                    let string = read_string_value(&mut reader, TAG_SHARED_STRING_ITEM, false)?;
+                    let index = shared_strings.len();
+                    shared_strings.push(string);
+                    mappings.insert(id, index);
+                }
+                if keys.is_empty() {
+                    break;
+                }
+            } else {
+                let string = read_string_value(&mut reader, TAG_SHARED_STRING_ITEM, false)?;
+                shared_strings.push(string);
+            }
+            id += 1;
+        }
+    });
+    Ok((shared_strings, mappings))
+}
+
+/// Streams every item of a shared string table into `strings`; a package without
+/// a shared string table streams nothing
+fn stream_shared_strings(
+    reader: Option<XmlReader<BufReader<ZipPart<'_, UnifiedReader>>>>,
+    strings: &mut SharedStringsBuilder,
+) -> Result<(), RustySheetError> {
+    let Some(mut reader) = reader else {
+        return Ok(());
+    };
+
+    match_xml_events!(reader => {
+        Event::Start(event) if event.name() == TAG_SHARED_STRING_ITEM => {
+            strings.push(read_string_value(&mut reader, TAG_SHARED_STRING_ITEM, false)?)?;
+        }
+    });
+    Ok(())
+}
+
 /// Extracts string content from XML elements, skipping phonetic text annotations
 /// and properly handling both text nodes and CDATA sections.
 ///
@@ -345,22 +1117,846 @@ fn load_number_formats(zip: &mut ZipArchive<UnifiedReader>, is_1904: bool) -> Re
 /// # Returns
 /// Extracted string value
 fn read_string_value(
-    reader: &mut XmlReader<BufReader<ZipFile<'_, UnifiedReader>>>,
+    reader: &mut XmlReader<BufReader<ZipPart<'_, UnifiedReader>>>,
     end_tag: QName,
     is_text_content: bool,
 ) -> Result<String, RustySheetError> {
+    read_string_and_phonetic_values(reader, end_tag, is_text_content).map(|(text, _)| text)
+}
+
+/// Extracts the string value and, separately, the text of its phonetic runs (rPh)
+fn read_string_and_phonetic_values(
+    reader: &mut XmlReader<BufReader<ZipPart<'_, UnifiedReader>>>,
+    end_tag: QName,
+    is_text_content: bool,
+) -> Result<(String, String), RustySheetError> {
     let mut is_phonetic_text = false;
+    let mut is_phonetic = false;
     let mut is_text = is_text_content;
     let mut text = String::new();
+    let mut phonetic = String::new();
     match_xml_events!(reader => {
         Event::End(event) if event.name() == end_tag => break,
         Event::Start(event) if event.name() == TAG_PHONETIC_TEXT => is_phonetic_text = true,
         Event::End(event) if event.name() == TAG_PHONETIC_TEXT => is_phonetic_text = false,
+        Event::Start(event) if is_phonetic_text && event.name() == TAG_TEXT => is_phonetic = true,
+        Event::End(event) if is_phonetic && event.name() == TAG_TEXT => is_phonetic = false,
         Event::Start(event) if !is_phonetic_text && event.name() == TAG_TEXT => is_text = true,
         Event::End(event) if is_text && event.name() == TAG_TEXT => is_text = false,
+        Event::Text(event) if is_phonetic => phonetic.push_str(&event.xml_content()?),
+        Event::CData(event) if is_phonetic => phonetic.push_str(&event.xml_content()?),
+        Event::GeneralRef(event) if is_phonetic => phonetic.push_bytes_ref(&event)?,
         Event::Text(event) if is_text => text.push_str(&event.xml_content()?),
         Event::CData(event) if is_text => text.push_str(&event.xml_content()?),
         Event::GeneralRef(event) if is_text => text.push_bytes_ref(&event)?,
     });
-    Ok(text)
+    Ok((text, phonetic))
+}
+
+/// Checks if an XML boolean attribute value is true ("1" or "true")
+fn is_true(value: Option<Cow<'_, str>>) -> bool {
+    value.map(|value| value == "1" || value == "true").unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::column::ColumnType;
+    use crate::database::range::Range;
+    use crate::database::range::RangeSpec;
+    use crate::database::table::Conversion;
+    use crate::database::table::Table;
+    use crate::spreadsheet::excel::to_zip_path;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    /// Writes a shared workbook with tracked changes: the revision log still holds
+    /// the previous value of A2, the worksheet holds the latest one.
+    fn write_shared_workbook(path: &std::path::Path) {
+        let parts = [
+            ("xl/workbook.xml", r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><fileSharing/><sheets><sheet name="Data" sheetId="1" r:id="rId1"/></sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/../worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/revisionHeaders" Target="revisions/revisionHeaders.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/usernames" Target="revisions/userNames.xml"/></Relationships>"#),
+            ("xl/revisions/revisionHeaders.xml", r#"<headers xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" guid="{00000000-0000-0000-0000-000000000000}"><header guid="{00000000-0000-0000-0000-000000000001}" dateTime="2024-01-01T00:00:00" maxSheetId="2" userName="a" r:id="rId1"><sheetIdMap count="1"><sheetId val="1"/></sheetIdMap></header></headers>"#),
+            ("xl/revisions/_rels/revisionHeaders.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/revisionLog" Target="revisionLog1.xml"/></Relationships>"#),
+            ("xl/revisions/revisionLog1.xml", r#"<revisions xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><rcc rId="1" sId="1"><oc r="A2"><v>1</v></oc><nc r="A2"><v>42</v></nc></rcc></revisions>"#),
+            ("xl/revisions/userNames.xml", r#"<users xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="1"><userInfo guid="{00000000-0000-0000-0000-000000000002}" name="a" id="1" dateTime="2024-01-01T00:00:00"/></users>"#),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1" t="inlineStr"><is><t>value</t></is></c></row><row r="2"><c r="A2"><v>42</v></c></row></sheetData></worksheet>"#),
+        ];
+        write_package(path, &parts);
+    }
+
+    /// Writes a single-sheet workbook named Data around the given worksheet xml and extra parts.
+    fn write_single_sheet(path: &std::path::Path, worksheet: &str, parts: &[(&str, &str)]) {
+        let mut package = vec![
+            ("xl/workbook.xml", r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/></sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#),
+            ("xl/worksheets/sheet1.xml", worksheet),
+        ];
+        package.extend_from_slice(parts);
+        write_package(path, &package);
+    }
+
+    fn write_package(path: &std::path::Path, parts: &[(&str, &str)]) {
+        let mut zip = ZipWriter::new(std::fs::File::create(path).unwrap());
+        for (name, content) in parts {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn zip_path_normalization() {
+        assert_eq!(to_zip_path("worksheets/sheet1.xml".into()), "xl/worksheets/sheet1.xml");
+        assert_eq!(to_zip_path("/xl/worksheets/sheet1.xml".into()), "xl/worksheets/sheet1.xml");
+        assert_eq!(to_zip_path("xl/worksheets/sheet1.xml".into()), "xl/worksheets/sheet1.xml");
+        assert_eq!(to_zip_path("../xl/worksheets/./sheet1.xml".into()), "xl/worksheets/sheet1.xml");
+    }
+
+    #[test]
+    fn shared_workbook_ignores_revisions() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_shared_{}.xlsx", std::process::id()));
+        write_shared_workbook(&path);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        assert_eq!(spreadsheet.sheets, vec![("Data".to_owned(), "xl/worksheets/sheet1.xml".to_owned())]);
+
+        let sheets = spreadsheet.read_sheets(&Criteria::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(sheets.len(), 1);
+        let values = sheets[0].cells.iter().map(|cell| cell.value.as_str()).collect::<Vec<_>>();
+        assert_eq!(values, vec!["value", "42"]);
+    }
+
+    #[test]
+    fn implied_cell_positions() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_implied_{}.xlsx", std::process::id()));
+        // Generator output mixing rows and cells with and without references
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row><c t="inlineStr"><is><t>a</t></is></c><c r="C1"><v>1</v></c><c><v>2</v></c></row>"#,
+            r#"<row r="4"><c><v>3</v></c></row>"#,
+            r#"<row><c r="B5"><v>4</v></c><c><v>5</v></c></row>"#,
+            r#"<row/>"#,
+            r#"<row><c/><c><v>6</v></c></row>"#,
+            r#"</sheetData></worksheet>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let sheets = spreadsheet.read_sheets(&Criteria::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let cells = sheets[0].cells.iter()
+            .map(|cell| (cell.row, cell.col, cell.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(cells, vec![
+            (0, 0, "a"),
+            (0, 2, "1"),
+            (0, 3, "2"),
+            (3, 0, "3"),
+            (4, 1, "4"),
+            (4, 2, "5"),
+            (6, 0, ""),
+            (6, 1, "6"),
+        ]);
+    }
+
+    #[test]
+    fn formula_texts() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_formulas_{}.xlsx", std::process::id()));
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="A1"><v>1</v></c><c r="B1"><f t="shared" ref="B1:B3" si="0">A1*2</f><v>2</v></c><c r="C1" t="e"><f>1/0</f><v>#DIV/0!</v></c></row>"#,
+            r#"<row r="2"><c r="A2"><v>2</v></c><c r="B2"><f t="shared" si="0"/><v>4</v></c><c r="C2" t="str"><f>"a"&amp;"b"</f><v>ab</v></c><c r="D2" t="inlineStr"><is><t>=text</t></is></c></row>"#,
+            r#"<row r="3"><c r="B3"><f t="shared" si="0"/><v>0</v></c><c r="C3"><f>SUM($A$1:A2)</f></c></row>"#,
+            r#"</sheetData></worksheet>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let range = Range::try_from("B2:D3").unwrap();
+        let sheets = spreadsheet.read_sheets(&Criteria { formulas: true, range: Some(range), ..Default::default() }).unwrap();
+        let cached = spreadsheet.read_sheets(&Criteria { error_as_null: true, ..Default::default() }).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let cells = sheets[0].cells.iter()
+            .map(|cell| (cell.reference(), cell.kind, cell.value.as_str()))
+            .collect::<Vec<_>>();
+        // Shared formulas are filled from their first cell, even outside of the range
+        assert_eq!(cells, vec![
+            ("B2".to_owned(), CellType::InlineString, "=A2*2"),
+            ("C2".to_owned(), CellType::InlineString, r#"="a"&"b""#),
+            ("D2".to_owned(), CellType::InlineString, "=text"),
+            ("B3".to_owned(), CellType::InlineString, "=A3*2"),
+            ("C3".to_owned(), CellType::InlineString, "=SUM($A$1:A2)"),
+        ]);
+        // Text starting with `=` is not a formula
+        assert_eq!(sheets[0].formula_cells, HashSet::from([(1, 1), (1, 2), (2, 1), (2, 2)]));
+        let values = cached[0].cells.iter().map(|cell| cell.value.as_str()).collect::<Vec<_>>();
+        assert_eq!(values, vec!["1", "2", "2", "4", "ab", "=text", "0", ""]);
+    }
+
+    #[test]
+    fn shared_string_phonetic_runs() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_phonetic_{}.xlsx", std::process::id()));
+        write_single_sheet(
+            &path,
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row></sheetData></worksheet>"#,
+            &[("xl/sharedStrings.xml", concat!(
+                r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="2" uniqueCount="2">"#,
+                r#"<si><t>東京</t><rPh sb="0" eb="2"><t>トウキョウ</t></rPh><phoneticPr fontId="1"/></si>"#,
+                r#"<si><r><t>大</t></r><r><t>阪</t></r><rPh sb="0" eb="1"><t>オオ</t></rPh><rPh sb="1" eb="2"><t>サカ</t></rPh></si>"#,
+                r#"</sst>"#,
+            ))],
+        );
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let (shared_strings, _) = spreadsheet.load_shared_strings(None).unwrap();
+        let phonetic_strings = spreadsheet.load_phonetic_strings().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(shared_strings, vec!["東京", "大阪"]);
+        assert_eq!(phonetic_strings, vec![Some("トウキョウ".to_owned()), Some("オオサカ".to_owned())]);
+    }
+
+    /// Returns a shared string builder without null literals, spilling above `spill_threshold` strings
+    fn strings(spill_threshold: Option<usize>) -> SharedStringsBuilder {
+        SharedStringsBuilder::new(&HashSet::new(), &None, false, spill_threshold)
+    }
+
+    /// Resolves the first `len` strings of a shared string table
+    fn texts(shared_strings: &SharedStrings, len: usize) -> Vec<Option<String>> {
+        (0..len).map(|index| shared_strings.get(index).unwrap().map(Cow::into_owned)).collect()
+    }
+
+    #[test]
+    fn shared_strings_alongside_sheets() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_pipelined_{}.xlsx", std::process::id()));
+        write_single_sheet(
+            &path,
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1" t="s"><v>1</v></c><c r="B1" t="s"><v>0</v></c></row></sheetData></worksheet>"#,
+            &[("xl/sharedStrings.xml", r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="2" uniqueCount="2"><si><t>first</t></si><si><t>second</t></si></sst>"#)],
+        );
+        // Streamed from a second handle on the file
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let (sheets, streamed) = spreadsheet.read_sheets_and_shared_strings(&Criteria::default(), strings(None)).unwrap();
+        // Parsed from the copy cached by a previous pass
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        spreadsheet.load_shared_strings(Some(HashSet::from([1]))).unwrap();
+        let (_, cached) = spreadsheet.read_sheets_and_shared_strings(&Criteria::default(), strings(None)).unwrap();
+        // Spilled to disk past the first string
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let (_, spilled) = spreadsheet.read_sheets_and_shared_strings(&Criteria::default(), strings(Some(1))).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let values = sheets[0].cells.iter().map(|cell| cell.value.as_str()).collect::<Vec<_>>();
+        assert_eq!(values, vec!["1", "0"]);
+        let expected = vec![Some("first".to_owned()), Some("second".to_owned())];
+        assert_eq!(texts(&streamed, 2), expected);
+        assert_eq!(texts(&cached, 2), expected);
+        assert!(matches!(spilled, SharedStrings::Spilled(_)));
+        assert_eq!(texts(&spilled, 2), expected);
+    }
+
+    #[test]
+    fn parts_declared_in_relationships() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_declared_parts_{}.xlsx", std::process::id()));
+        // Producer storing the shared strings and styles away from their usual paths
+        write_package(&path, &[
+            ("xl/workbook.xml", r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/></sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", concat!(
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+                r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>"#,
+                r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings" Target="/shared/strings.xml"/>"#,
+                r#"<Relationship Id="rId3" Type="http://purl.oclc.org/ooxml/officeDocument/relationships/styles" Target="format/Styles.xml"/>"#,
+                r#"</Relationships>"#,
+            )),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" s="1"><v>45000</v></c></row></sheetData></worksheet>"#),
+            ("shared/strings.xml", r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="1" uniqueCount="1"><si><t>moved</t></si></sst>"#),
+            ("xl/format/Styles.xml", r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><cellXfs count="2"><xf numFmtId="0"/><xf numFmtId="14"/></cellXfs></styleSheet>"#),
+        ]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let (_, shared_strings) = spreadsheet.read_sheets_and_shared_strings(&Criteria::default(), strings(None)).unwrap();
+        let phonetic_strings = spreadsheet.load_phonetic_strings().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(texts(&shared_strings, 1), vec![Some("moved".to_owned())]);
+        assert_eq!(phonetic_strings, vec![None]);
+        let kinds = spreadsheet.number_formats.iter().map(|format| format.kind).collect::<Vec<_>>();
+        assert_eq!(kinds, vec![CellType::Number, CellType::NumberDate1900]);
+    }
+
+    #[test]
+    fn sheet_tab_colors() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_tab_colors_{}.xlsx", std::process::id()));
+        let worksheet = |properties: &str| format!(r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">{properties}<sheetData/></worksheet>"#);
+        let (rgb, theme, indexed, plain) = (
+            worksheet(r#"<sheetPr><tabColor rgb="FF00B050"/></sheetPr>"#),
+            worksheet(r#"<sheetPr><tabColor theme="4" tint="0.39997558519241921"/></sheetPr>"#),
+            worksheet(r#"<sheetPr><tabColor indexed="10"/></sheetPr>"#),
+            worksheet(""),
+        );
+        // Listed out of tab order in the relationships and the archive
+        write_package(&path, &[
+            ("xl/worksheets/sheet4.xml", plain.as_str()),
+            ("xl/workbook.xml", r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Rgb" sheetId="1" r:id="rId1"/><sheet name="Theme" sheetId="2" r:id="rId2"/><sheet name="Indexed" sheetId="3" r:id="rId3"/><sheet name="Plain" sheetId="4" r:id="rId4"/></sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", concat!(
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+                r#"<Relationship Id="rId5" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="theme/theme1.xml"/>"#,
+                r#"<Relationship Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet4.xml"/>"#,
+                r#"<Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet3.xml"/>"#,
+                r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/>"#,
+                r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>"#,
+                r#"</Relationships>"#,
+            )),
+            ("xl/worksheets/sheet3.xml", indexed.as_str()),
+            ("xl/worksheets/sheet2.xml", theme.as_str()),
+            ("xl/worksheets/sheet1.xml", rgb.as_str()),
+            ("xl/theme/theme1.xml", concat!(
+                r#"<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" name="Office Theme"><a:themeElements><a:clrScheme name="Office">"#,
+                r#"<a:dk1><a:sysClr val="windowText" lastClr="000000"/></a:dk1><a:lt1><a:sysClr val="window" lastClr="FFFFFF"/></a:lt1>"#,
+                r#"<a:dk2><a:srgbClr val="1F497D"/></a:dk2><a:lt2><a:srgbClr val="EEECE1"/></a:lt2>"#,
+                r#"<a:accent1><a:srgbClr val="4F81BD"/></a:accent1><a:accent2><a:srgbClr val="C0504D"/></a:accent2>"#,
+                r#"<a:accent3><a:srgbClr val="9BBB59"/></a:accent3><a:accent4><a:srgbClr val="8064A2"/></a:accent4>"#,
+                r#"<a:accent5><a:srgbClr val="4BACC6"/></a:accent5><a:accent6><a:srgbClr val="F79646"/></a:accent6>"#,
+                r#"<a:hlink><a:srgbClr val="0000FF"/></a:hlink><a:folHlink><a:srgbClr val="800080"/></a:folHlink>"#,
+                r#"</a:clrScheme></a:themeElements></a:theme>"#,
+            )),
+        ]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let sheets = spreadsheet.read_sheets(&Criteria::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let tabs = sheets.iter()
+            .map(|sheet| (sheet.index, sheet.name.as_str(), sheet.tab_color.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(tabs, vec![
+            (0, "Rgb", Some("#00B050")),
+            (1, "Theme", Some("#95B3D7")),
+            (2, "Indexed", Some("#FF0000")),
+            (3, "Plain", None),
+        ]);
+    }
+
+    #[test]
+    fn sheet_list_without_cells() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_list_sheets_{}.xlsx", std::process::id()));
+        write_package(&path, &[
+            ("xl/workbook.xml", r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/><sheet name="Lookup" sheetId="2" state="hidden" r:id="rId2"/><sheet name="Macro" sheetId="3" state="veryHidden" r:id="rId3"/></sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet3.xml"/></Relationships>"#),
+            // The cells of the first worksheet are malformed, so reading them would fail
+            ("xl/worksheets/sheet1.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><dimension ref="A1:D10"/><sheetData><row r="1"><c r="A1" s="?"><v>1</v></c></row></sheetData></worksheet>"#),
+            ("xl/worksheets/sheet2.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><dimension ref="A1"/><sheetData/></worksheet>"#),
+            ("xl/worksheets/sheet3.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#),
+        ]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let sheets = spreadsheet.list_sheets().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let sheets = sheets.iter()
+            .map(|sheet| (sheet.name.as_str(), sheet.index, sheet.visibility, sheet.dimension.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(sheets, vec![
+            ("Data", 0, Visibility::Visible, Some("A1:D10")),
+            ("Lookup", 1, Visibility::Hidden, Some("A1")),
+            ("Macro", 2, Visibility::VeryHidden, None),
+        ]);
+    }
+
+    #[test]
+    fn document_properties() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_metadata_{}.xlsx", std::process::id()));
+        write_package(&path, &[
+            ("xl/workbook.xml", r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><workbookPr date1904="1"/><sheets><sheet name="Data" sheetId="1" r:id="rId1"/><sheet name="Lookup" sheetId="2" r:id="rId2"/></sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/></Relationships>"#),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#),
+            ("xl/worksheets/sheet2.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#),
+            ("docProps/core.xml", r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><dc:title>Budget</dc:title><dc:creator>Ann</dc:creator><cp:lastModifiedBy>Bob</cp:lastModifiedBy><dcterms:created xsi:type="dcterms:W3CDTF">2024-02-29T13:45:30Z</dcterms:created></cp:coreProperties>"#),
+            ("docProps/app.xml", r#"<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties"><Application>Microsoft Excel</Application></Properties>"#),
+        ]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let metadata = spreadsheet.metadata().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(metadata.title.as_deref(), Some("Budget"));
+        assert_eq!(metadata.author.as_deref(), Some("Ann"));
+        assert_eq!(metadata.created, Some(1_709_214_330_000_000));
+        assert_eq!(metadata.modified, None);
+        assert_eq!(metadata.application.as_deref(), Some("Microsoft Excel"));
+        assert_eq!(metadata.sheet_count, 2);
+        assert!(metadata.is_1904);
+    }
+
+    #[test]
+    fn defined_names_with_scopes() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_defined_names_{}.xlsx", std::process::id()));
+        write_package(&path, &[
+            ("xl/workbook.xml", r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/><sheet name="Q1 Sales" sheetId="2" r:id="rId2"/></sheets><definedNames><definedName name="_xlnm._FilterDatabase" localSheetId="0" hidden="1">Data!$A$1:$D$10</definedName><definedName name="Regions">'Q1 Sales'!$B$2:$B$20</definedName><definedName name="Rate">0.25</definedName><definedName name="Total" localSheetId="1">'Q1 Sales'!$E$21</definedName></definedNames></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/></Relationships>"#),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#),
+            ("xl/worksheets/sheet2.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#),
+        ]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let names = spreadsheet.defined_names().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let names = names.iter()
+            .map(|name| (name.name.as_str(), name.scope.as_deref(), name.target.as_ref().map(|(sheet, range)| (sheet.as_str(), range.as_str())), name.hidden))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![
+            ("_xlnm._FilterDatabase", Some("Data"), Some(("Data", "A1:D10")), true),
+            ("Regions", None, Some(("Q1 Sales", "B2:B20")), false),
+            ("Rate", None, None, false),
+            ("Total", Some("Q1 Sales"), Some(("Q1 Sales", "E21:E21")), false),
+        ]);
+    }
+
+    #[test]
+    fn range_from_defined_name() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_named_range_{}.xlsx", std::process::id()));
+        write_package(&path, &[
+            ("xl/workbook.xml", r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/><sheet name="Q1 Sales" sheetId="2" r:id="rId2"/></sheets><definedNames><definedName name="Regions">'Q1 Sales'!$B$2:$B$20</definedName><definedName name="Rate">0.25</definedName><definedName name="Regions" localSheetId="0">Data!$A$1:$A$5</definedName></definedNames></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/></Relationships>"#),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#),
+            ("xl/worksheets/sheet2.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#),
+        ]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let mut resolve = |range: &str, sheet: Option<&str>| {
+            let patterns = sheet.map(|sheet| vec![glob::Pattern::new(sheet).unwrap()]);
+            spreadsheet.resolve_range(Some(&RangeSpec::try_from(range).unwrap()), patterns)
+                .map(|(patterns, range)| {
+                    let range = range.unwrap();
+                    (patterns.unwrap()[0].to_string(), range.col_lower_bound, range.row_lower_bound, range.row_upper_bound)
+                })
+        };
+        let global = resolve("regions", None).unwrap();
+        let local = resolve("Regions", Some("Data")).unwrap();
+        let cells = resolve("B2:C3", Some("Data")).unwrap();
+        let formula = resolve("Rate", None).is_err();
+        let missing = resolve("Unknown", None).is_err();
+        std::fs::remove_file(&path).unwrap();
+
+        // The name local to the matched worksheet comes before the name of the workbook
+        assert_eq!(global, ("Q1 Sales".to_owned(), Some(1), Some(1), Some(19)));
+        assert_eq!(local, ("Data".to_owned(), Some(0), Some(0), Some(4)));
+        assert_eq!(cells, ("Data".to_owned(), Some(1), Some(1), Some(2)));
+        assert!(formula);
+        assert!(missing);
+    }
+
+    #[test]
+    fn notes_and_threaded_comments() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_comments_{}.xlsx", std::process::id()));
+        write_package(&path, &[
+            ("xl/workbook.xml", r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/><sheet name="Plain" sheetId="2" r:id="rId2"/></sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/><Relationship Id="rId3" Type="http://schemas.microsoft.com/office/2017/10/relationships/person" Target="persons/person.xml"/></Relationships>"#),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#),
+            ("xl/worksheets/sheet2.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#),
+            ("xl/worksheets/_rels/sheet1.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments1.xml"/><Relationship Id="rId2" Type="http://schemas.microsoft.com/office/2017/10/relationships/threadedComment" Target="../threadedComments/threadedComment1.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/" TargetMode="External"/></Relationships>"#),
+            // The note of C3 stands in for its threaded comment
+            ("xl/comments1.xml", r#"<comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><authors><author>Ann</author><author>tc={00000000-0000-0000-0000-000000000001}</author></authors><commentList><comment ref="B2" authorId="0"><text><r><rPr><b/></rPr><t>Ann:</t></r><r><t xml:space="preserve">
+Check &amp; fix</t></r></text></comment><comment ref="C3" authorId="1"><text><t>[Threaded comment]</t></text></comment></commentList></comments>"#),
+            ("xl/threadedComments/threadedComment1.xml", r#"<ThreadedComments xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments"><threadedComment ref="C3" personId="{00000000-0000-0000-0000-00000000000A}" id="{00000000-0000-0000-0000-000000000001}"><text>Why negative?</text></threadedComment><threadedComment ref="C3" personId="{00000000-0000-0000-0000-00000000000B}" id="{00000000-0000-0000-0000-000000000002}" parentId="{00000000-0000-0000-0000-000000000001}"><text>Refund</text></threadedComment></ThreadedComments>"#),
+            ("xl/persons/person.xml", r#"<personList xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments"><person displayName="Bob" id="{00000000-0000-0000-0000-00000000000A}" userId="bob" providerId="None"/></personList>"#),
+        ]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let comments = spreadsheet.comments().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let comments = comments.iter()
+            .map(|comment| (comment.sheet_name.as_str(), comment.reference.as_str(), comment.author.as_deref(), comment.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(comments, vec![
+            ("Data", "B2", Some("Ann"), "Ann:\nCheck & fix"),
+            ("Data", "C3", Some("Bob"), "Why negative?"),
+            ("Data", "C3", None, "Refund"),
+        ]);
+    }
+
+    #[test]
+    fn hyperlinks_with_targets() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_hyperlinks_{}.xlsx", std::process::id()));
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheetData>"#,
+            r#"<row r="1"><c r="A1" t="s"><v>1</v></c><c r="B1" t="inlineStr"><is><t>B-7</t></is></c></row><row r="2"><c r="A2"><v>42</v></c></row>"#,
+            r#"</sheetData><hyperlinks><hyperlink ref="A1" r:id="rId1"/><hyperlink ref="B1:C1" r:id="rId2" location="top" display="Ticket"/>"#,
+            r#"<hyperlink ref="A2" location="'Other sheet'!A1"/><hyperlink ref="A3" r:id="rId9"/></hyperlinks></worksheet>"#,
+        ), &[
+            ("xl/worksheets/_rels/sheet1.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/items/1" TargetMode="External"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/tickets?id=7&amp;view=full" TargetMode="External"/></Relationships>"#),
+            ("xl/sharedStrings.xml", r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><si><t>unused</t></si><si><t>Item 1</t></si></sst>"#),
+        ]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let hyperlinks = spreadsheet.hyperlinks().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let hyperlinks = hyperlinks.iter()
+            .map(|hyperlink| (hyperlink.sheet_name.as_str(), hyperlink.reference.as_str(), hyperlink.display_text.as_deref(), hyperlink.target_url.as_str()))
+            .collect::<Vec<_>>();
+        // The link without a known target is left out
+        assert_eq!(hyperlinks, vec![
+            ("Data", "A1", Some("Item 1"), "https://example.com/items/1"),
+            ("Data", "B1:C1", Some("Ticket"), "https://example.com/tickets?id=7&view=full#top"),
+            ("Data", "A2", Some("42"), "#'Other sheet'!A1"),
+        ]);
+    }
+
+    #[test]
+    fn cell_protection_flags() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_protection_{}.xlsx", std::process::id()));
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" s="1"/></row><row r="2"><c r="A2" s="2"><f>B1*2</f><v>0</v></c></row>"#,
+            r#"</sheetData><sheetProtection sheet="1" objects="1" scenarios="1"/></worksheet>"#,
+        ), &[
+            ("xl/styles.xml", r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><cellXfs count="3"><xf numFmtId="0"/><xf numFmtId="0" applyProtection="1"><protection locked="0"/></xf><xf numFmtId="0" applyProtection="1"><protection hidden="1"/></xf></cellXfs></styleSheet>"#),
+        ]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let protections = spreadsheet.cell_protection().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let protections = protections.iter()
+            .map(|protection| (protection.reference.as_str(), protection.locked, protection.formula_hidden, protection.sheet_protected))
+            .collect::<Vec<_>>();
+        assert_eq!(protections, vec![
+            ("A1", true, false, true),
+            ("B1", false, false, true),
+            ("A2", true, true, true),
+        ]);
+    }
+
+    #[test]
+    fn list_objects_with_columns() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_list_objects_{}.xlsx", std::process::id()));
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheetData/>"#,
+            r#"<tableParts count="2"><tablePart r:id="rId1"/><tablePart r:id="rId2"/></tableParts></worksheet>"#,
+        ), &[
+            ("xl/worksheets/_rels/sheet1.xml.rels", concat!(
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+                r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/table" Target="../tables/table1.xml"/>"#,
+                r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/table" Target="../tables/table2.xml"/></Relationships>"#,
+            )),
+            ("xl/tables/table1.xml", concat!(
+                r#"<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="1" name="Table1" displayName="SalesTable" ref="B2:C10" totalsRowCount="1">"#,
+                r#"<autoFilter ref="B2:C9"/><tableColumns count="2"><tableColumn id="1" name="Region"/><tableColumn id="2" name="Amount"/></tableColumns></table>"#,
+            )),
+            ("xl/tables/table2.xml", concat!(
+                r#"<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="2" name="Rates" displayName="Rates" ref="E1:E4" headerRowCount="0">"#,
+                r#"<tableColumns count="1"><tableColumn id="1" name="Column1"/></tableColumns></table>"#,
+            )),
+        ]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let list_objects = spreadsheet.list_objects().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(list_objects, vec![
+            ListObject {
+                name: "SalesTable".to_owned(),
+                sheet_name: "Data".to_owned(),
+                reference: "B2:C10".to_owned(),
+                has_header: true,
+                totals_rows: 1,
+                column_names: vec!["Region".to_owned(), "Amount".to_owned()],
+            },
+            ListObject {
+                name: "Rates".to_owned(),
+                sheet_name: "Data".to_owned(),
+                reference: "E1:E4".to_owned(),
+                has_header: false,
+                totals_rows: 0,
+                column_names: vec!["Column1".to_owned()],
+            },
+        ]);
+    }
+
+    #[test]
+    fn missing_worksheet_parts() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_missing_parts_{}.xlsx", std::process::id()));
+        write_package(&path, &[
+            ("xl/workbook.xml", r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/><sheet name="Lost" sheetId="2" r:id="rId2"/><sheet name="Gone" sheetId="3" r:id="rId3"/></sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet3.xml"/></Relationships>"#),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#),
+        ]);
+        let error = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).err().map(|error| error.to_string());
+        std::fs::remove_file(&path).unwrap();
+
+        let error = error.unwrap();
+        assert!(error.contains("'Lost' (xl/worksheets/sheet2.xml), 'Gone' (xl/worksheets/sheet3.xml)"), "{error}");
+    }
+
+    #[test]
+    fn lenient_partial_rows() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_lenient_{}.xlsx", std::process::id()));
+        // Damaged worksheet: a bad style index, then the part ends mid-row
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="A1"><v>1</v></c><c r="B1"><v>2</v></c></row>"#,
+            r#"<row r="2"><c r="A2" s="?"><v>3</v></c>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let strict = spreadsheet.read_sheets(&Criteria::default()).err().map(|error| error.to_string());
+        let sheets = spreadsheet.read_sheets(&Criteria { lenient: true, ..Default::default() }).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(strict.is_some());
+        let values = sheets[0].cells.iter().map(|cell| cell.value.as_str()).collect::<Vec<_>>();
+        assert_eq!(values, vec!["1", "2"]);
+        assert_eq!(sheets[0].anomalies, vec![strict.unwrap()]);
+    }
+
+    #[test]
+    fn rest_after_settled_chunks() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_rest_{}.xlsx", std::process::id()));
+        let rows = (1..=5000)
+            .filter(|row| row % 700 != 0)
+            .map(|row| format!(r#"<row r="{row}"><c r="A{row}"><v>{row}</v></c></row>"#))
+            .collect::<String>();
+        write_single_sheet(&path, &format!(r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>{rows}</sheetData></worksheet>"#), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        for skip_empty_rows in [false, true] {
+            let range = Range { row_lower_bound: Some(1), row_upper_bound: None, col_lower_bound: Some(0), col_upper_bound: Some(0) };
+            let criteria = Criteria { range: Some(range), skip_empty_rows, ..Default::default() };
+            let full = spreadsheet.read_sheets(&criteria).unwrap().remove(0);
+            let preview = spreadsheet.read_sheets(&Criteria { rows_limit: Some(2 * Sheet::CHUNK_SIZE), ..criteria.clone() }).unwrap().remove(0);
+            let settled = preview.settled_chunks();
+            let settled_row = preview.chunks[settled - 1].1;
+            let rest = spreadsheet.read_sheets(&Criteria {
+                range: Some(Range { row_lower_bound: Some(settled_row + 1), ..range }),
+                ..criteria.clone()
+            }).unwrap().remove(0);
+
+            let values = |sheet: &Sheet, chunks: usize| sheet.cells[..sheet.chunks.get(chunks.wrapping_sub(1)).map(|chunk| chunk.3).unwrap_or(0)]
+                .iter()
+                .map(|cell| (cell.row, cell.value.to_owned()))
+                .collect::<Vec<_>>();
+            let mut combined = values(&preview, settled);
+            combined.extend(values(&rest, rest.chunks.len()));
+            assert_eq!(combined, values(&full, full.chunks.len()));
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn header_aliases_before_presets() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_aliases_{}.xlsx", std::process::id()));
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="A1" t="inlineStr"><is><t>Montant</t></is></c><c r="B1" t="inlineStr"><is><t>Date</t></is></c></row>"#,
+            r#"<row r="2"><c r="A2"><v>12.5</v></c><c r="B2"><v>1</v></c></row>"#,
+            r#"</sheetData></worksheet>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let criteria = Criteria {
+            header_aliases: HashMap::from([
+                ("Montant".to_owned(), "Amount".to_owned()),
+                ("Betrag".to_owned(), "Amount".to_owned()),
+            ]),
+            ..Default::default()
+        };
+        let presets = vec![(glob::Pattern::new("Amount").unwrap(), ColumnType::Varchar)];
+        let tables = spreadsheet.analyze_sheets(true, &criteria, &presets).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let columns = tables[0].columns.iter()
+            .map(|column| (column.name.as_str(), column.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec![("Amount", ColumnType::Varchar), ("Date", ColumnType::BigInt)]);
+        assert_eq!(tables[0].headers[0].1.as_deref(), Some("Montant"));
+    }
+
+    #[test]
+    fn missing_header_names_from_template() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_missing_headers_{}.xlsx", std::process::id()));
+        // Headers missing for the first and third columns, one header looking like a generated name
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="B1" t="inlineStr"><is><t>A</t></is></c><c r="D1" t="inlineStr"><is><t>column_3</t></is></c></row>"#,
+            r#"<row r="2"><c r="A2"><v>1</v></c><c r="B2"><v>2</v></c><c r="C2"><v>3</v></c><c r="D2"><v>4</v></c></row>"#,
+            r#"</sheetData></worksheet>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let letters = spreadsheet.analyze_sheets(true, &Criteria::default(), &Vec::new()).unwrap();
+        let templated = spreadsheet.analyze_sheets(true, &Criteria {
+            missing_header_name: Some("column_{n}".to_owned()),
+            ..Default::default()
+        }, &Vec::new()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let names = |tables: &[Table]| tables[0].columns.iter().map(|column| column.name.to_owned()).collect::<Vec<_>>();
+        assert_eq!(names(&letters), vec!["A", "A_2", "C", "column_3"]);
+        assert_eq!(names(&templated), vec!["column_1", "A", "column_3_2", "column_3"]);
+    }
+
+    #[test]
+    fn sanitized_header_names() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_sanitized_headers_{}.xlsx", std::process::id()));
+        // Line breaks inside headers, a header of control characters only and headers equal after cutting
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="A1" t="inlineStr"><is><t xml:space="preserve">Net&#10;&#13;&#10;sum </t></is></c>"#,
+            r#"<c r="B1" t="inlineStr"><is><t xml:space="preserve">&#9;&#10;</t></is></c>"#,
+            r#"<c r="C1" t="inlineStr"><is><t>Quantity ordered</t></is></c><c r="D1" t="inlineStr"><is><t>quantity shipped</t></is></c></row>"#,
+            r#"<row r="2"><c r="A2"><v>1</v></c><c r="B2"><v>2</v></c><c r="C2"><v>3</v></c><c r="D2"><v>4</v></c></row>"#,
+            r#"</sheetData></worksheet>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let tables = spreadsheet.analyze_sheets(true, &Criteria {
+            max_header_length: 9,
+            ..Default::default()
+        }, &Vec::new()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let names = tables[0].columns.iter().map(|column| column.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["Net sum", "B", "Quantity", "quantity_2"]);
+    }
+
+    #[test]
+    fn normalized_header_names() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_normalized_headers_{}.xlsx", std::process::id()));
+        // Punctuation and accents, a leading digit, a reserved keyword, a symbol only and two headers equal once normalized
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="A1" t="inlineStr"><is><t>Montant (€) 2024</t></is></c><c r="B1" t="inlineStr"><is><t>Échéance</t></is></c>"#,
+            r#"<c r="C1" t="inlineStr"><is><t>2024 Total</t></is></c><c r="D1" t="inlineStr"><is><t>Order</t></is></c><c r="E1" t="inlineStr"><is><t>€</t></is></c>"#,
+            r#"<c r="F1" t="inlineStr"><is><t>Net-Sum</t></is></c><c r="G1" t="inlineStr"><is><t>net sum</t></is></c></row>"#,
+            r#"<row r="2"><c r="A2"><v>1</v></c><c r="B2"><v>2</v></c><c r="C2"><v>3</v></c><c r="D2"><v>4</v></c><c r="E2"><v>5</v></c><c r="F2"><v>6</v></c><c r="G2"><v>7</v></c></row>"#,
+            r#"</sheetData></worksheet>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let tables = spreadsheet.analyze_sheets(true, &Criteria {
+            normalize_names: true,
+            ..Default::default()
+        }, &Vec::new()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let names = tables[0].columns.iter().map(|column| column.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["montant_2024", "echeance", "_2024_total", "_order", "E", "net_sum", "net_sum_2"]);
+    }
+
+    #[test]
+    fn disjoint_ranges() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_disjoint_ranges_{}.xlsx", std::process::id()));
+        // Two blocks separated by the spacer column C, the second one a row shorter
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="A1" t="inlineStr"><is><t>Id</t></is></c><c r="B1" t="inlineStr"><is><t>Q1</t></is></c><c r="C1" t="inlineStr"><is><t>note</t></is></c>"#,
+            r#"<c r="D1" t="inlineStr"><is><t>Q2</t></is></c><c r="E1" t="inlineStr"><is><t>Q3</t></is></c></row>"#,
+            r#"<row r="2"><c r="A2"><v>1</v></c><c r="B2"><v>10</v></c><c r="C2"><v>0</v></c><c r="D2"><v>20</v></c><c r="E2"><v>30</v></c></row>"#,
+            r#"<row r="3"><c r="A3"><v>2</v></c><c r="B3"><v>11</v></c><c r="D3"><v>99</v></c></row>"#,
+            r#"</sheetData></worksheet>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let ranges = vec![Range::try_from("A1:B3").unwrap(), Range::try_from("D1:E2").unwrap()];
+        let criteria = Criteria {
+            range: Range::cover(&ranges),
+            ranges,
+            ..Default::default()
+        };
+        let tables = spreadsheet.analyze_sheets(true, &criteria, &Vec::new()).unwrap();
+        let sheets = spreadsheet.read_sheets(&criteria).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let names = tables[0].columns.iter().map(|column| column.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["Id", "Q1", "Q2", "Q3"]);
+        let references = sheets[0].cells.iter()
+            .filter(|cell| cell.row > 0)
+            .map(|cell| cell.reference())
+            .collect::<Vec<_>>();
+        assert_eq!(references, vec!["A2", "B2", "D2", "E2", "A3", "B3"]);
+    }
+
+    #[test]
+    fn positional_presets_before_names() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_positional_{}.xlsx", std::process::id()));
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="A1" t="inlineStr"><is><t>Id</t></is></c><c r="B1" t="inlineStr"><is><t>Betrag</t></is></c></row>"#,
+            r#"<row r="2"><c r="A2"><v>1</v></c><c r="B2"><v>12</v></c></row>"#,
+            r#"</sheetData></worksheet>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let presets = vec![
+            (glob::Pattern::new("*").unwrap(), ColumnType::Varchar),
+            (glob::Pattern::new("#2").unwrap(), ColumnType::Double),
+        ];
+        let tables = spreadsheet.analyze_sheets(true, &Criteria::default(), &presets).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let columns = tables[0].columns.iter()
+            .map(|column| (column.name.as_str(), column.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec![("Id", ColumnType::Varchar), ("Betrag", ColumnType::Double)]);
+    }
+
+    #[test]
+    fn all_varchar_keeps_presets() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_all_varchar_{}.xlsx", std::process::id()));
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="A1" t="inlineStr"><is><t>Id</t></is></c><c r="B1" t="inlineStr"><is><t>Amount</t></is></c></row>"#,
+            r#"<row r="2"><c r="A2"><v>1</v></c><c r="B2"><v>12.5</v></c></row>"#,
+            r#"</sheetData></worksheet>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let presets = vec![(glob::Pattern::new("Amount").unwrap(), ColumnType::Double)];
+        let tables = spreadsheet.analyze_sheets(true, &Criteria { all_varchar: true, ..Default::default() }, &presets).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let kinds = tables[0].columns.iter().map(|column| column.kind).collect::<Vec<_>>();
+        assert_eq!(kinds, vec![ColumnType::Varchar, ColumnType::Double]);
+    }
+
+    #[test]
+    fn declared_columns_by_position() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_declared_{}.xlsx", std::process::id()));
+        write_single_sheet(&path, concat!(
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            r#"<row r="1"><c r="A1" t="inlineStr"><is><t>Id</t></is></c><c r="B1" t="inlineStr"><is><t>Betrag</t></is></c></row>"#,
+            r#"<row r="2"><c r="A2"><v>1</v></c><c r="B2"><v>12</v></c></row>"#,
+            r#"</sheetData></worksheet>"#,
+        ), &[]);
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let presets = vec![
+            (glob::Pattern::new("id").unwrap(), ColumnType::Varchar),
+            (glob::Pattern::new("amount").unwrap(), ColumnType::Double),
+        ];
+        let mut tables = spreadsheet.analyze_sheets(true, &Criteria::default(), &presets).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut table = tables.remove(0);
+        assert!(crate::spreadsheet::apply_declared_columns(&mut table.clone(), "book.xlsx", &presets[..1]).is_err());
+        crate::spreadsheet::apply_declared_columns(&mut table, "book.xlsx", &presets).unwrap();
+        let columns = table.columns.iter()
+            .map(|column| (column.name.as_str(), column.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec![("id", ColumnType::Varchar), ("amount", ColumnType::Double)]);
+    }
+
+    #[test]
+    fn validate_conversions() {
+        let path = std::env::temp_dir().join(format!("rusty_sheet_validate_{}.xlsx", std::process::id()));
+        write_single_sheet(
+            &path,
+            concat!(
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+                r#"<row r="1"><c r="A1" t="inlineStr"><is><t>Amount</t></is></c><c r="B1" t="inlineStr"><is><t>Note</t></is></c></row>"#,
+                r#"<row r="2"><c r="A2"><v>12</v></c><c r="B2" t="s"><v>0</v></c></row>"#,
+                r#"<row r="3"><c r="A3" t="inlineStr"><is><t>n/a</t></is></c><c r="B3" t="inlineStr"><is><t>ok</t></is></c></row>"#,
+                r#"<row r="4"><c r="B4" t="inlineStr"><is><t>late</t></is></c></row>"#,
+                r#"</sheetData></worksheet>"#,
+            ),
+            &[("xl/sharedStrings.xml", r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="1" uniqueCount="1"><si><t>-</t></si></sst>"#)],
+        );
+        let mut spreadsheet = XlsxSpreadsheet::open(path.to_str().unwrap(), false, false, None).unwrap();
+        let criteria = Criteria {
+            nulls: HashSet::from(["".to_owned(), "-".to_owned()]),
+            validate: true,
+            ..Default::default()
+        };
+        let presets = vec![(glob::Pattern::new("Amount").unwrap(), ColumnType::BigInt)];
+        let tables = spreadsheet.analyze_sheets(true, &criteria, &presets).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(tables[0].conversions, vec![
+            Conversion { nulls: 1, failures: 1, first_failure: Some("A3: parse 'n/a' to bigint failed".to_owned()) },
+            Conversion { nulls: 1, failures: 0, first_failure: None },
+        ]);
+    }
 }